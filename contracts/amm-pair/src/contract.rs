@@ -0,0 +1,785 @@
+use cosmwasm_std::{
+    attr, coins, entry_point, from_binary, to_binary, Addr, Attribute, BankMsg, Binary, Deps,
+    DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128,
+};
+use cw_erc20::ReceiveMsg;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, HookMsg, InstantiateMsg, PoolResponse, QueryMsg, SharesResponse,
+    SimulateSwapResponse,
+};
+use crate::state::{
+    config, config_read, pool, pool_read, shares, shares_read, Config, Offer, Pool, MAX_BPS,
+};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.fee_bps >= MAX_BPS {
+        return Err(ContractError::InvalidFee {
+            fee_bps: msg.fee_bps,
+        });
+    }
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        denom: msg.denom,
+        fee_bps: msg.fee_bps,
+    };
+    config(deps.storage).save(&state)?;
+    pool(deps.storage).save(&Pool::default())?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::AddLiquidity {
+            max_token,
+            min_shares,
+        } => try_add_liquidity(deps, env, info, max_token, min_shares),
+        ExecuteMsg::RemoveLiquidity {
+            shares,
+            min_native,
+            min_token,
+        } => try_remove_liquidity(deps, info, shares, min_native, min_token),
+        ExecuteMsg::Swap { min_output } => try_swap(deps, info, min_output),
+        ExecuteMsg::Receive(receive) => try_receive(deps, info, receive),
+    }
+}
+
+/// Add liquidity
+///
+/// Deposits the sent native coins together with tokens at the current reserve ratio. The token
+/// amount rounds up and the issued shares round down, so a deposit never dilutes the existing
+/// providers.
+///
+/// @param max_token the most tokens to pull from the sender, exactly the deposit on the first one
+/// @param min_shares fail if fewer shares would be issued
+fn try_add_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_token: Uint128,
+    min_shares: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let native_in = native_amount(&info, &state.denom)?;
+    if max_token.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let mut totals = pool_read(deps.storage).load()?;
+    let (token_in, issued) = if totals.total_shares.is_zero() {
+        (max_token, native_in)
+    } else {
+        let token_in = native_in
+            .multiply_ratio(totals.token_reserve, totals.native_reserve)
+            .checked_add(Uint128::from(1u128))?;
+        let issued = native_in.multiply_ratio(totals.total_shares, totals.native_reserve);
+        (token_in, issued)
+    };
+    if token_in > max_token {
+        return Err(ContractError::TooMuchToken {
+            required: token_in,
+            max_token,
+        });
+    }
+    if issued.is_zero() {
+        return Err(ContractError::ZeroOutput {});
+    }
+    if let Some(min_shares) = min_shares {
+        if issued < min_shares {
+            return Err(ContractError::TooFewShares {
+                shares: issued,
+                min_shares,
+            });
+        }
+    }
+
+    totals.native_reserve = totals.native_reserve.checked_add(native_in)?;
+    totals.token_reserve = totals.token_reserve.checked_add(token_in)?;
+    totals.total_shares = totals.total_shares.checked_add(issued)?;
+    pool(deps.storage).save(&totals)?;
+    let balance = load_shares(deps.storage, &info.sender)?;
+    shares(deps.storage).save(
+        info.sender.as_str().as_bytes(),
+        &balance.checked_add(issued)?,
+    )?;
+
+    let transfer =
+        cw_erc20::ExecuteMsg::transfer_from(info.sender.as_str(), env.contract.address, token_in);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.token)?],
+        attributes: vec![
+            attr("action", "add_liquidity"),
+            attr("provider", info.sender),
+            attr("native_amount", native_in),
+            attr("token_amount", token_in),
+            attr("shares", issued),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Remove liquidity
+///
+/// Burns shares of the signer and pays out their part of both reserves, rounded down.
+///
+/// @param shares the number of shares to burn
+/// @param min_native fail if fewer native coins would be paid out
+/// @param min_token fail if fewer tokens would be paid out
+fn try_remove_liquidity(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+    min_native: Option<Uint128>,
+    min_token: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let balance = load_shares(deps.storage, &info.sender)?;
+    if balance < amount {
+        return Err(ContractError::InsufficientShares {
+            balance,
+            required: amount,
+        });
+    }
+
+    let mut totals = pool_read(deps.storage).load()?;
+    let native_out = totals
+        .native_reserve
+        .multiply_ratio(amount, totals.total_shares);
+    let token_out = totals
+        .token_reserve
+        .multiply_ratio(amount, totals.total_shares);
+    check_minimum(native_out, min_native)?;
+    check_minimum(token_out, min_token)?;
+
+    totals.native_reserve = totals.native_reserve.checked_sub(native_out)?;
+    totals.token_reserve = totals.token_reserve.checked_sub(token_out)?;
+    totals.total_shares = totals.total_shares.checked_sub(amount)?;
+    pool(deps.storage).save(&totals)?;
+    let key = info.sender.as_str().as_bytes();
+    let remaining = balance.checked_sub(amount)?;
+    if remaining.is_zero() {
+        shares(deps.storage).remove(key);
+    } else {
+        shares(deps.storage).save(key, &remaining)?;
+    }
+
+    // empty payouts are skipped, the bank module rejects them
+    let mut messages = vec![];
+    if !native_out.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(native_out.u128(), &state.denom),
+            }
+            .into(),
+        );
+    }
+    if !token_out.is_zero() {
+        let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), token_out);
+        messages.push(transfer.into_cosmos_msg(&state.token)?);
+    }
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", "remove_liquidity"),
+            attr("provider", info.sender),
+            attr("native_amount", native_out),
+            attr("token_amount", token_out),
+            attr("shares", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Swap
+///
+/// Swaps the sent native coins for tokens.
+///
+/// @param min_output fail if fewer tokens would be received
+fn try_swap(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_output: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let amount = native_amount(&info, &state.denom)?;
+    let output = swap(deps.storage, &state, Offer::Native, amount, min_output)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), output);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.token)?],
+        attributes: swap_attributes(&info.sender, Offer::Native, amount, output),
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Receive
+///
+/// Swaps the tokens sent with the erc20 `Send` message for native coins, which go to the
+/// sender of the tokens.
+///
+/// @param receive the `ReceiveMsg` forwarded by the token
+fn try_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    receive: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if info.sender != state.token {
+        return Err(ContractError::WrongToken {
+            token: state.token.to_string(),
+        });
+    }
+    let min_output = match receive.msg {
+        Some(msg) => match from_binary(&msg)? {
+            HookMsg::Swap { min_output } => min_output,
+        },
+        None => return Err(ContractError::MissingPayload {}),
+    };
+    let trader = deps.api.addr_validate(&receive.sender)?;
+    let output = swap(
+        deps.storage,
+        &state,
+        Offer::Token,
+        receive.amount,
+        min_output,
+    )?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![BankMsg::Send {
+            to_address: trader.to_string(),
+            amount: coins(output.u128(), &state.denom),
+        }
+        .into()],
+        attributes: swap_attributes(&trader, Offer::Token, receive.amount, output),
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Moves `amount` of the `offer` side into the pool and the output out of it. The whole input,
+/// fee included, stays in the reserves.
+fn swap(
+    storage: &mut dyn Storage,
+    state: &Config,
+    offer: Offer,
+    amount: Uint128,
+    min_output: Option<Uint128>,
+) -> Result<Uint128, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let mut totals = pool_read(storage).load()?;
+    if totals.total_shares.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+    let output = totals.swap_output(offer, amount, state.fee_bps);
+    if output.is_zero() {
+        return Err(ContractError::ZeroOutput {});
+    }
+    check_minimum(output, min_output)?;
+
+    match offer {
+        Offer::Native => {
+            totals.native_reserve = totals.native_reserve.checked_add(amount)?;
+            totals.token_reserve = totals.token_reserve.checked_sub(output)?;
+        }
+        Offer::Token => {
+            totals.token_reserve = totals.token_reserve.checked_add(amount)?;
+            totals.native_reserve = totals.native_reserve.checked_sub(output)?;
+        }
+    }
+    pool(storage).save(&totals)?;
+    Ok(output)
+}
+
+fn swap_attributes(
+    trader: &Addr,
+    offer: Offer,
+    amount: Uint128,
+    output: Uint128,
+) -> Vec<Attribute> {
+    let offer = match offer {
+        Offer::Native => "native",
+        Offer::Token => "token",
+    };
+    vec![
+        attr("action", "swap"),
+        attr("trader", trader),
+        attr("offer", offer),
+        attr("offer_amount", amount),
+        attr("return_amount", output),
+    ]
+}
+
+/// The amount of the single `denom` coin sent along
+fn native_amount(info: &MessageInfo, denom: &str) -> Result<Uint128, ContractError> {
+    match info.funds.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => Ok(coin.amount),
+        _ => Err(ContractError::InvalidFunds {
+            denom: denom.to_string(),
+        }),
+    }
+}
+
+fn check_minimum(amount: Uint128, minimum: Option<Uint128>) -> Result<(), ContractError> {
+    match minimum {
+        Some(minimum) if amount < minimum => {
+            Err(ContractError::TooLittleReceived { amount, minimum })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn load_shares(storage: &dyn Storage, address: &Addr) -> StdResult<Uint128> {
+    Ok(shares_read(storage)
+        .may_load(address.as_str().as_bytes())?
+        .unwrap_or_default())
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Pool {} => {
+            let state = config_read(deps.storage).load()?;
+            let totals = pool_read(deps.storage).load()?;
+            to_binary(&PoolResponse {
+                token: state.token.to_string(),
+                denom: state.denom,
+                fee_bps: state.fee_bps,
+                native_reserve: totals.native_reserve,
+                token_reserve: totals.token_reserve,
+                total_shares: totals.total_shares,
+            })
+        }
+        QueryMsg::Shares { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let shares = load_shares(deps.storage, &address)?;
+            to_binary(&SharesResponse { shares })
+        }
+        QueryMsg::SimulateSwap { offer, amount } => {
+            let state = config_read(deps.storage).load()?;
+            let totals = pool_read(deps.storage).load()?;
+            let output = totals.swap_output(offer, amount, state.fee_bps);
+            to_binary(&SimulateSwapResponse { output })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{Coin, OwnedDeps};
+
+    const DENOM: &str = "ucosm";
+
+    fn setup(fee_bps: u64) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            denom: DENOM.to_string(),
+            fee_bps,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(
+        deps: DepsMut,
+        sender: &str,
+        funds: &[Coin],
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, funds), msg)
+    }
+
+    fn add(deps: DepsMut, provider: &str, native: u128, max_token: u128) -> Response {
+        let msg = ExecuteMsg::AddLiquidity {
+            max_token: Uint128::from(max_token),
+            min_shares: None,
+        };
+        run(deps, provider, &coins(native, DENOM), msg).unwrap()
+    }
+
+    fn sell_token(amount: u128, min_output: Option<u128>) -> ExecuteMsg {
+        let hook = HookMsg::Swap {
+            min_output: min_output.map(Uint128::from),
+        };
+        ExecuteMsg::Receive(ReceiveMsg {
+            sender: "trader".to_string(),
+            amount: Uint128::from(amount),
+            msg: Some(to_binary(&hook).unwrap()),
+        })
+    }
+
+    fn pool_of(deps: Deps) -> Pool {
+        pool_read(deps.storage).load().unwrap()
+    }
+
+    #[test]
+    fn validates_fee() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            denom: DENOM.to_string(),
+            fee_bps: 10_000,
+        };
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::InvalidFee { fee_bps: 10_000 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn adds_liquidity_at_reserve_ratio() {
+        let mut deps = setup(30);
+        let res = add(deps.as_mut(), "alice", 1000, 4000);
+        let transfer = cw_erc20::ExecuteMsg::transfer_from("alice", "cosmos2contract", 4000u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("token").unwrap()]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "add_liquidity"),
+                attr("provider", "alice"),
+                attr("native_amount", "1000"),
+                attr("token_amount", "4000"),
+                attr("shares", "1000"),
+            ]
+        );
+
+        // 250 * 4000 / 1000 rounds up to 1001
+        let msg = ExecuteMsg::AddLiquidity {
+            max_token: Uint128::from(1000u128),
+            min_shares: None,
+        };
+        match run(deps.as_mut(), "bob", &coins(250, DENOM), msg).unwrap_err() {
+            ContractError::TooMuchToken {
+                required,
+                max_token,
+            } => {
+                assert_eq!(required.u128(), 1001);
+                assert_eq!(max_token.u128(), 1000);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::AddLiquidity {
+            max_token: Uint128::from(2000u128),
+            min_shares: Some(Uint128::from(251u128)),
+        };
+        match run(deps.as_mut(), "bob", &coins(250, DENOM), msg).unwrap_err() {
+            ContractError::TooFewShares { shares, .. } => assert_eq!(shares.u128(), 250),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = add(deps.as_mut(), "bob", 250, 2000);
+        assert_eq!(res.attributes[3], attr("token_amount", "1001"));
+        assert_eq!(
+            pool_of(deps.as_ref()),
+            Pool {
+                native_reserve: Uint128::from(1250u128),
+                token_reserve: Uint128::from(5001u128),
+                total_shares: Uint128::from(1250u128),
+            }
+        );
+        assert_eq!(
+            load_shares(&deps.storage, &Addr::unchecked("bob"))
+                .unwrap()
+                .u128(),
+            250
+        );
+
+        for funds in &[vec![], coins(10, "uatom"), coins(0, DENOM)] {
+            let msg = ExecuteMsg::AddLiquidity {
+                max_token: Uint128::from(100u128),
+                min_shares: None,
+            };
+            match run(deps.as_mut(), "carol", funds, msg).unwrap_err() {
+                ContractError::InvalidFunds { denom } => assert_eq!(denom, DENOM),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn swaps_both_ways() {
+        let mut deps = setup(30);
+        let msg = ExecuteMsg::Swap { min_output: None };
+        match run(deps.as_mut(), "trader", &coins(100, DENOM), msg).unwrap_err() {
+            ContractError::EmptyPool {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        add(deps.as_mut(), "alice", 1_000_000, 2_000_000);
+
+        // 10000 in, 9970 after the fee: 2000000 * 9970 / 1009970 = 19743.16
+        let query_msg = QueryMsg::SimulateSwap {
+            offer: Offer::Native,
+            amount: Uint128::from(10_000u128),
+        };
+        let simulated: SimulateSwapResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(simulated.output.u128(), 19743);
+        let msg = ExecuteMsg::Swap {
+            min_output: Some(Uint128::from(19744u128)),
+        };
+        match run(deps.as_mut(), "trader", &coins(10_000, DENOM), msg).unwrap_err() {
+            ContractError::TooLittleReceived { amount, minimum } => {
+                assert_eq!(amount.u128(), 19743);
+                assert_eq!(minimum.u128(), 19744);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::Swap {
+            min_output: Some(Uint128::from(19743u128)),
+        };
+        let res = run(deps.as_mut(), "trader", &coins(10_000, DENOM), msg).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("trader", 19743u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("token").unwrap()]
+        );
+        let totals = pool_of(deps.as_ref());
+        assert_eq!(totals.native_reserve.u128(), 1_010_000);
+        assert_eq!(totals.token_reserve.u128(), 1_980_257);
+
+        // 19743 back, 19683 after the fee: 1010000 * 19683 / 1999940 = 9940.18
+        let res = run(deps.as_mut(), "token", &[], sell_token(19743, None)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "trader".to_string(),
+                amount: coins(9940, DENOM),
+            }
+            .into()]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "swap"),
+                attr("trader", "trader"),
+                attr("offer", "token"),
+                attr("offer_amount", "19743"),
+                attr("return_amount", "9940"),
+            ]
+        );
+        let totals = pool_of(deps.as_ref());
+        assert_eq!(totals.native_reserve.u128(), 1_000_060);
+        assert_eq!(totals.token_reserve.u128(), 2_000_000);
+
+        match run(deps.as_mut(), "token", &[], sell_token(1, None)).unwrap_err() {
+            ContractError::ZeroOutput {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_token_and_missing_payload() {
+        let mut deps = setup(30);
+        add(deps.as_mut(), "alice", 1000, 1000);
+        match run(deps.as_mut(), "other_token", &[], sell_token(10, None)).unwrap_err() {
+            ContractError::WrongToken { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "trader".to_string(),
+            amount: Uint128::from(10u128),
+            msg: None,
+        });
+        match run(deps.as_mut(), "token", &[], msg).unwrap_err() {
+            ContractError::MissingPayload {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn removes_liquidity_pro_rata() {
+        let mut deps = setup(0);
+        add(deps.as_mut(), "alice", 1000, 3001);
+        add(deps.as_mut(), "bob", 500, 2000);
+
+        let msg = ExecuteMsg::RemoveLiquidity {
+            shares: Uint128::from(501u128),
+            min_native: None,
+            min_token: None,
+        };
+        match run(deps.as_mut(), "bob", &[], msg).unwrap_err() {
+            ContractError::InsufficientShares { balance, required } => {
+                assert_eq!(balance.u128(), 500);
+                assert_eq!(required.u128(), 501);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // bob deposited 500 and 1501, 1/3 of 4502 rounds down to 1500
+        let msg = ExecuteMsg::RemoveLiquidity {
+            shares: Uint128::from(500u128),
+            min_native: Some(Uint128::from(500u128)),
+            min_token: Some(Uint128::from(1501u128)),
+        };
+        match run(deps.as_mut(), "bob", &[], msg).unwrap_err() {
+            ContractError::TooLittleReceived { amount, .. } => assert_eq!(amount.u128(), 1500),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::RemoveLiquidity {
+            shares: Uint128::from(500u128),
+            min_native: Some(Uint128::from(500u128)),
+            min_token: Some(Uint128::from(1500u128)),
+        };
+        let res = run(deps.as_mut(), "bob", &[], msg).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("bob", 1500u128);
+        assert_eq!(
+            res.messages,
+            vec![
+                BankMsg::Send {
+                    to_address: "bob".to_string(),
+                    amount: coins(500, DENOM),
+                }
+                .into(),
+                transfer.into_cosmos_msg("token").unwrap(),
+            ]
+        );
+        assert_eq!(
+            load_shares(&deps.storage, &Addr::unchecked("bob"))
+                .unwrap()
+                .u128(),
+            0
+        );
+
+        // the last provider takes everything
+        let msg = ExecuteMsg::RemoveLiquidity {
+            shares: Uint128::from(1000u128),
+            min_native: None,
+            min_token: None,
+        };
+        let res = run(deps.as_mut(), "alice", &[], msg).unwrap();
+        assert_eq!(res.attributes[3], attr("token_amount", "3002"));
+        assert_eq!(pool_of(deps.as_ref()), Pool::default());
+    }
+
+    /// Minimal linear congruential generator, so the property tests are reproducible
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, bound: u128) -> u128 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 33) as u128 % bound
+        }
+    }
+
+    fn product(totals: &Pool) -> u128 {
+        totals.native_reserve.u128() * totals.token_reserve.u128()
+    }
+
+    /// `a / b <= c / d` without rounding
+    fn ratio_le(a: Uint128, b: Uint128, c: Uint128, d: Uint128) -> bool {
+        a.u128() * d.u128() <= c.u128() * b.u128()
+    }
+
+    #[test]
+    fn k_never_decreases() {
+        let providers = ["alice", "bob", "carol"];
+        for &fee_bps in &[0u64, 30, 500] {
+            let mut deps = setup(fee_bps);
+            let mut rng = Lcg(fee_bps + 1);
+            add(deps.as_mut(), "alice", 1_000_000, 3_000_000);
+
+            for _ in 0..500 {
+                let before = pool_of(deps.as_ref());
+                let amount = rng.next(50_000) + 1;
+                let provider = providers[rng.next(3) as usize];
+                let is_swap = match rng.next(4) {
+                    0 => {
+                        let msg = ExecuteMsg::Swap { min_output: None };
+                        let _ = run(deps.as_mut(), "trader", &coins(amount, DENOM), msg);
+                        true
+                    }
+                    1 => {
+                        let _ = run(deps.as_mut(), "token", &[], sell_token(amount, None));
+                        true
+                    }
+                    2 => {
+                        let msg = ExecuteMsg::AddLiquidity {
+                            max_token: Uint128::from(u64::MAX),
+                            min_shares: None,
+                        };
+                        let _ = run(deps.as_mut(), provider, &coins(amount, DENOM), msg);
+                        false
+                    }
+                    _ => {
+                        let balance = load_shares(&deps.storage, &Addr::unchecked(provider))
+                            .unwrap()
+                            .u128();
+                        let msg = ExecuteMsg::RemoveLiquidity {
+                            shares: Uint128::from(amount.min(balance)),
+                            min_native: None,
+                            min_token: None,
+                        };
+                        let _ = run(deps.as_mut(), provider, &[], msg);
+                        false
+                    }
+                };
+
+                let after = pool_of(deps.as_ref());
+                if is_swap {
+                    assert!(product(&after) >= product(&before));
+                    assert_eq!(after.total_shares, before.total_shares);
+                } else if !after.total_shares.is_zero() {
+                    // deposits and withdrawals never lower the reserves behind each share
+                    assert!(ratio_le(
+                        before.native_reserve,
+                        before.total_shares,
+                        after.native_reserve,
+                        after.total_shares
+                    ));
+                    assert!(ratio_le(
+                        before.token_reserve,
+                        before.total_shares,
+                        after.token_reserve,
+                        after.total_shares
+                    ));
+                } else {
+                    add(deps.as_mut(), "alice", 1_000_000, 3_000_000);
+                }
+
+                let issued: u128 = providers
+                    .iter()
+                    .map(|p| {
+                        load_shares(&deps.storage, &Addr::unchecked(*p))
+                            .unwrap()
+                            .u128()
+                    })
+                    .sum();
+                assert_eq!(issued, pool_of(deps.as_ref()).total_shares.u128());
+            }
+        }
+    }
+}
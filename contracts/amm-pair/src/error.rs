@@ -0,0 +1,50 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Fee must be below 10000 basis points (got {fee_bps})")]
+    InvalidFee { fee_bps: u64 },
+
+    #[error("Only {token} tokens are accepted")]
+    WrongToken { token: String },
+
+    #[error("Expected a single non-zero {denom} coin")]
+    InvalidFunds { denom: String },
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Missing hook message")]
+    MissingPayload {},
+
+    #[error("Pool has no liquidity")]
+    EmptyPool {},
+
+    #[error("Deposit requires {required} tokens, more than the maximum of {max_token}")]
+    TooMuchToken {
+        required: Uint128,
+        max_token: Uint128,
+    },
+
+    #[error("Deposit issues {shares} shares, less than the minimum of {min_shares}")]
+    TooFewShares {
+        shares: Uint128,
+        min_shares: Uint128,
+    },
+
+    #[error("Received {amount}, less than the minimum of {minimum}")]
+    TooLittleReceived { amount: Uint128, minimum: Uint128 },
+
+    #[error("Insufficient shares (balance {balance}, required {required})")]
+    InsufficientShares { balance: Uint128, required: Uint128 },
+
+    #[error("Amount too small to receive anything")]
+    ZeroOutput {},
+}
@@ -0,0 +1,81 @@
+use cosmwasm_std::Uint128;
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::Offer;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 side of the pair
+    pub token: String,
+    /// The native side of the pair
+    pub denom: String,
+    /// Share of every swap input kept by the pool, in basis points
+    pub fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Deposits the sent native coins and the matching amount of tokens at the current
+    /// price. The tokens are pulled with `TransferFrom`, so the pair needs an allowance first.
+    /// The first deposit sets the price and deposits exactly `max_token`.
+    AddLiquidity {
+        max_token: Uint128,
+        min_shares: Option<Uint128>,
+    },
+    /// Burns `shares` and pays out their part of both reserves
+    RemoveLiquidity {
+        shares: Uint128,
+        min_native: Option<Uint128>,
+        min_token: Option<Uint128>,
+    },
+    /// Swaps the sent native coins for tokens
+    Swap { min_output: Option<Uint128> },
+    /// Swaps the tokens sent with the erc20 `Send` message for native coins. The message
+    /// must carry a `HookMsg`.
+    Receive(ReceiveMsg),
+}
+
+/// The payload of the erc20 `Send` message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookMsg {
+    Swap { min_output: Option<Uint128> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Pool {},
+    /// Returns the liquidity shares of `address`
+    Shares {
+        address: String,
+    },
+    /// Returns what a swap of `amount` of the `offer` side would receive right now
+    SimulateSwap {
+        offer: Offer,
+        amount: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolResponse {
+    pub token: String,
+    pub denom: String,
+    pub fee_bps: u64,
+    pub native_reserve: Uint128,
+    pub token_reserve: Uint128,
+    pub total_shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharesResponse {
+    pub shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateSwapResponse {
+    pub output: Uint128,
+}
@@ -0,0 +1,85 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static POOL_KEY: &[u8] = b"pool";
+static SHARES_KEY: &[u8] = b"shares";
+
+pub const MAX_BPS: u64 = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The erc20 side of the pair
+    pub token: Addr,
+    /// The native side of the pair
+    pub denom: String,
+    /// Share of every swap input kept by the pool, in basis points
+    pub fee_bps: u64,
+}
+
+/// The side of the pair offered in a swap
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Offer {
+    Native,
+    Token,
+}
+
+/// The reserves are tracked here instead of read from the balances, so coins or tokens
+/// transferred to the contract directly don't move the price.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Pool {
+    pub native_reserve: Uint128,
+    pub token_reserve: Uint128,
+    /// Liquidity shares issued to all providers
+    pub total_shares: Uint128,
+}
+
+impl Pool {
+    /// The amount received for `amount` of the `offer` side. The fee is taken from the input
+    /// first, and both the fee and the output round in favour of the pool, so the product of
+    /// the reserves never decreases.
+    pub fn swap_output(&self, offer: Offer, amount: Uint128, fee_bps: u64) -> Uint128 {
+        let (offer_reserve, ask_reserve) = match offer {
+            Offer::Native => (self.native_reserve, self.token_reserve),
+            Offer::Token => (self.token_reserve, self.native_reserve),
+        };
+        let amount_after_fee = amount.multiply_ratio(MAX_BPS - fee_bps, MAX_BPS);
+        if amount_after_fee.is_zero() {
+            return Uint128::zero();
+        }
+        // cannot overflow, the sum is at most the offer side's total supply
+        let denominator = offer_reserve.u128() + amount_after_fee.u128();
+        ask_reserve.multiply_ratio(amount_after_fee, denominator)
+    }
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn pool(storage: &mut dyn Storage) -> Singleton<'_, Pool> {
+    singleton(storage, POOL_KEY)
+}
+
+pub fn pool_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Pool> {
+    singleton_read(storage, POOL_KEY)
+}
+
+/// Liquidity shares by provider address
+pub fn shares(storage: &mut dyn Storage) -> Bucket<'_, Uint128> {
+    bucket(storage, SHARES_KEY)
+}
+
+pub fn shares_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Uint128> {
+    bucket_read(storage, SHARES_KEY)
+}
@@ -0,0 +1,156 @@
+//! Runs the pair against a real erc20 contract on a test chain, which keeps the native coins
+//! in its bank and reverts failing messages like a real chain.
+
+use cosmwasm_std::{coins, to_binary, Uint128};
+use cw_amm_pair::contract::{execute, instantiate, query};
+use cw_amm_pair::msg::{
+    ExecuteMsg, HookMsg, InstantiateMsg, PoolResponse, QueryMsg, SharesResponse,
+};
+use cw_erc20::BalanceResponse;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const PAIR: &str = "pair";
+const DENOM: &str = "ucosm";
+
+/// A pair with a 0.3% fee. alice and bob hold tokens and coins, trader only coins.
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    let msg = erc20_msg("PAIR", &[("alice", 1_000_000), ("bob", 1_000_000)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let pair = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        denom: DENOM.to_string(),
+        fee_bps: 30,
+    };
+    chain.instantiate(pair, PAIR, "creator", &msg, &[]).unwrap();
+
+    chain.set_balance("alice", &coins(500_000, DENOM));
+    chain.set_balance("bob", &coins(500_000, DENOM));
+    chain.set_balance("trader", &coins(100_000, DENOM));
+    chain
+}
+
+fn add_liquidity(chain: &mut Chain, provider: &str, native: u128, max_token: u128) {
+    let msg = cw_erc20::ExecuteMsg::approve(PAIR, max_token);
+    chain.execute(TOKEN, provider, &msg, &[]).unwrap();
+    let msg = ExecuteMsg::AddLiquidity {
+        max_token: Uint128::from(max_token),
+        min_shares: None,
+    };
+    chain
+        .execute(PAIR, provider, &msg, &coins(native, DENOM))
+        .unwrap();
+}
+
+fn tokens(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn shares(chain: &Chain, address: &str) -> u128 {
+    let msg = QueryMsg::Shares {
+        address: address.to_string(),
+    };
+    let res: SharesResponse = chain.query(PAIR, &msg).unwrap();
+    res.shares.u128()
+}
+
+fn pool(chain: &Chain) -> PoolResponse {
+    chain.query(PAIR, &QueryMsg::Pool {}).unwrap()
+}
+
+#[test]
+fn add_swap_remove_cycle() {
+    let mut chain = setup();
+    add_liquidity(&mut chain, "alice", 100_000, 400_000);
+    add_liquidity(&mut chain, "bob", 50_000, 250_000);
+    // 50000 * 400000 / 100000 + 1
+    assert_eq!(tokens(&chain, "bob"), 799_999);
+    assert_eq!(shares(&chain, "bob"), 50_000);
+    let res = pool(&chain);
+    assert_eq!(res.native_reserve.u128(), 150_000);
+    assert_eq!(res.token_reserve.u128(), 600_001);
+    assert_eq!(tokens(&chain, PAIR), 600_001);
+    assert_eq!(chain.balance(PAIR, DENOM), 150_000);
+
+    // trader buys tokens with coins, 9970 after the fee: 600001 * 9970 / 159970 = 37394.4
+    let msg = ExecuteMsg::Swap {
+        min_output: Some(Uint128::from(37_000u128)),
+    };
+    chain
+        .execute(PAIR, "trader", &msg, &coins(10_000, DENOM))
+        .unwrap();
+    assert_eq!(tokens(&chain, "trader"), 37_394);
+    assert_eq!(chain.balance("trader", DENOM), 90_000);
+
+    // and sells them back, 37282 after the fee: 160000 * 37282 / 599889 = 9943.7
+    let hook = HookMsg::Swap {
+        min_output: Some(Uint128::from(9_900u128)),
+    };
+    let msg = cw_erc20::ExecuteMsg::send(PAIR, 37_394u128, Some(to_binary(&hook).unwrap()));
+    chain.execute(TOKEN, "trader", &msg, &[]).unwrap();
+    assert_eq!(tokens(&chain, "trader"), 0);
+    assert_eq!(chain.balance("trader", DENOM), 99_943);
+
+    // the fees stay with the providers
+    let res = pool(&chain);
+    assert_eq!(res.native_reserve.u128(), 150_057);
+    assert_eq!(res.token_reserve.u128(), 600_001);
+    let msg = ExecuteMsg::RemoveLiquidity {
+        shares: Uint128::from(50_000u128),
+        min_native: Some(Uint128::from(50_000u128)),
+        min_token: Some(Uint128::from(200_000u128)),
+    };
+    chain.execute(PAIR, "bob", &msg, &[]).unwrap();
+    assert_eq!(chain.balance("bob", DENOM), 500_019);
+    assert_eq!(tokens(&chain, "bob"), 999_999);
+    let msg = ExecuteMsg::RemoveLiquidity {
+        shares: Uint128::from(100_000u128),
+        min_native: None,
+        min_token: None,
+    };
+    chain.execute(PAIR, "alice", &msg, &[]).unwrap();
+    assert_eq!(chain.balance("alice", DENOM), 500_038);
+    assert_eq!(tokens(&chain, "alice"), 1_000_001);
+
+    assert_eq!(chain.balance(PAIR, DENOM), 0);
+    assert_eq!(tokens(&chain, PAIR), 0);
+    assert_eq!(pool(&chain).total_shares.u128(), 0);
+}
+
+#[test]
+fn deposit_needs_allowance() {
+    let mut chain = setup();
+    let msg = ExecuteMsg::AddLiquidity {
+        max_token: Uint128::from(1_000u128),
+        min_shares: None,
+    };
+    let err = chain
+        .execute(PAIR, "alice", &msg, &coins(1_000, DENOM))
+        .unwrap_err();
+    assert!(err.contains("allowance"), "unexpected error: {}", err);
+    // the pair's shares and the coins sent along are reverted with the token transfer
+    assert_eq!(shares(&chain, "alice"), 0);
+    assert_eq!(pool(&chain).native_reserve.u128(), 0);
+    assert_eq!(chain.balance("alice", DENOM), 500_000);
+    assert_eq!(chain.balance(PAIR, DENOM), 0);
+}
+
+#[test]
+fn slippage_guard_rejects_swap() {
+    let mut chain = setup();
+    add_liquidity(&mut chain, "alice", 100_000, 400_000);
+    let msg = ExecuteMsg::Swap {
+        min_output: Some(Uint128::from(40_000u128)),
+    };
+    let err = chain
+        .execute(PAIR, "trader", &msg, &coins(10_000, DENOM))
+        .unwrap_err();
+    assert_eq!(err, "Received 36264, less than the minimum of 40000");
+    assert_eq!(chain.balance("trader", DENOM), 100_000);
+    assert_eq!(pool(&chain).native_reserve.u128(), 100_000);
+}
@@ -0,0 +1,22 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use cw_amm::msg::{
+    ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg, SharesResponse, SimulateSwapResponse,
+};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(PoolInfoResponse), &out_dir);
+    export_schema(&schema_for!(SharesResponse), &out_dir);
+    export_schema(&schema_for!(SimulateSwapResponse), &out_dir);
+}
@@ -0,0 +1,707 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Storage, Uint128,
+};
+
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg, SharesResponse, SimulateSwapResponse,
+};
+use crate::state::{
+    config, config_read, pool, pool_read, shares, shares_read, Config, Pool, Side, MAX_BPS,
+};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.fee_bps >= MAX_BPS {
+        return Err(ContractError::InvalidFee {
+            fee_bps: msg.fee_bps,
+        });
+    }
+    let state = Config {
+        token_a: deps.api.addr_validate(&msg.token_a)?,
+        token_b: deps.api.addr_validate(&msg.token_b)?,
+        fee_bps: msg.fee_bps,
+    };
+    if state.token_a == state.token_b {
+        return Err(ContractError::SameToken {
+            token: state.token_a.to_string(),
+        });
+    }
+    config(deps.storage).save(&state)?;
+    pool(deps.storage).save(&Pool::default())?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::AddLiquidity {
+            amount_a,
+            amount_b,
+            min_shares,
+        } => try_add_liquidity(deps, env, info, amount_a, amount_b, min_shares),
+        ExecuteMsg::RemoveLiquidity {
+            shares,
+            min_a,
+            min_b,
+        } => try_remove_liquidity(deps, info, shares, min_a, min_b),
+        ExecuteMsg::Swap {
+            input_token,
+            input_amount,
+            min_output,
+        } => try_swap(deps, env, info, input_token, input_amount, min_output),
+    }
+}
+
+/// Add liquidity
+///
+/// Deposits both tokens at the current reserve ratio. The shares are issued for the side that
+/// runs out first and round down, the deposited amounts round up, so a deposit never dilutes
+/// the existing providers.
+///
+/// @param amount_a the most A tokens to pull from the sender, exactly the deposit on the first one
+/// @param amount_b the most B tokens to pull from the sender, exactly the deposit on the first one
+/// @param min_shares fail if fewer shares would be issued
+fn try_add_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount_a: Uint128,
+    amount_b: Uint128,
+    min_shares: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    if amount_a.is_zero() || amount_b.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+
+    let mut totals = pool_read(deps.storage).load()?;
+    let (in_a, in_b, issued) = if totals.total_shares.is_zero() {
+        (amount_a, amount_b, amount_a)
+    } else {
+        let issued = std::cmp::min(
+            amount_a.multiply_ratio(totals.total_shares, totals.reserve_a),
+            amount_b.multiply_ratio(totals.total_shares, totals.reserve_b),
+        );
+        let in_a = multiply_ratio_up(issued, totals.reserve_a, totals.total_shares);
+        let in_b = multiply_ratio_up(issued, totals.reserve_b, totals.total_shares);
+        (in_a, in_b, issued)
+    };
+    if issued.is_zero() {
+        return Err(ContractError::ZeroOutput {});
+    }
+    if let Some(min_shares) = min_shares {
+        if issued < min_shares {
+            return Err(ContractError::TooFewShares {
+                shares: issued,
+                min_shares,
+            });
+        }
+    }
+
+    totals.reserve_a = totals.reserve_a.checked_add(in_a)?;
+    totals.reserve_b = totals.reserve_b.checked_add(in_b)?;
+    totals.total_shares = totals.total_shares.checked_add(issued)?;
+    pool(deps.storage).save(&totals)?;
+    let balance = load_shares(deps.storage, &info.sender)?;
+    shares(deps.storage).save(
+        info.sender.as_str().as_bytes(),
+        &balance.checked_add(issued)?,
+    )?;
+
+    let transfer_a = cw_erc20::ExecuteMsg::transfer_from(
+        info.sender.as_str(),
+        env.contract.address.as_str(),
+        in_a,
+    );
+    let transfer_b =
+        cw_erc20::ExecuteMsg::transfer_from(info.sender.as_str(), env.contract.address, in_b);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![
+            transfer_a.into_cosmos_msg(&state.token_a)?,
+            transfer_b.into_cosmos_msg(&state.token_b)?,
+        ],
+        attributes: vec![
+            attr("action", "add_liquidity"),
+            attr("provider", info.sender),
+            attr("amount_a", in_a),
+            attr("amount_b", in_b),
+            attr("shares", issued),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Remove liquidity
+///
+/// Burns shares of the signer and pays out their part of both reserves, rounded down.
+///
+/// @param shares the number of shares to burn
+/// @param min_a fail if fewer A tokens would be paid out
+/// @param min_b fail if fewer B tokens would be paid out
+fn try_remove_liquidity(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+    min_a: Option<Uint128>,
+    min_b: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let balance = load_shares(deps.storage, &info.sender)?;
+    if balance < amount {
+        return Err(ContractError::InsufficientShares {
+            balance,
+            required: amount,
+        });
+    }
+
+    let mut totals = pool_read(deps.storage).load()?;
+    let out_a = totals.reserve_a.multiply_ratio(amount, totals.total_shares);
+    let out_b = totals.reserve_b.multiply_ratio(amount, totals.total_shares);
+    check_minimum(out_a, min_a)?;
+    check_minimum(out_b, min_b)?;
+
+    totals.reserve_a = totals.reserve_a.checked_sub(out_a)?;
+    totals.reserve_b = totals.reserve_b.checked_sub(out_b)?;
+    totals.total_shares = totals.total_shares.checked_sub(amount)?;
+    pool(deps.storage).save(&totals)?;
+    let key = info.sender.as_str().as_bytes();
+    let remaining = balance.checked_sub(amount)?;
+    if remaining.is_zero() {
+        shares(deps.storage).remove(key);
+    } else {
+        shares(deps.storage).save(key, &remaining)?;
+    }
+
+    // empty payouts are skipped
+    let mut messages = vec![];
+    if !out_a.is_zero() {
+        let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), out_a);
+        messages.push(transfer.into_cosmos_msg(&state.token_a)?);
+    }
+    if !out_b.is_zero() {
+        let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), out_b);
+        messages.push(transfer.into_cosmos_msg(&state.token_b)?);
+    }
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", "remove_liquidity"),
+            attr("provider", info.sender),
+            attr("amount_a", out_a),
+            attr("amount_b", out_b),
+            attr("shares", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Swap
+///
+/// Pulls `input_amount` of `input_token` from the signer and sends them the other token. The
+/// whole input, fee included, stays in the reserves.
+///
+/// @param input_token the token offered, either side of the pair
+/// @param input_amount the amount offered
+/// @param min_output fail if fewer tokens would be received
+fn try_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    input_token: String,
+    input_amount: Uint128,
+    min_output: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    if input_amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let offer = side_of(&state, &deps.api.addr_validate(&input_token)?)?;
+    let output = swap(deps.storage, &state, offer, input_amount, min_output)?;
+
+    let transfer_in = cw_erc20::ExecuteMsg::transfer_from(
+        info.sender.as_str(),
+        env.contract.address,
+        input_amount,
+    );
+    let transfer_out = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), output);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![
+            transfer_in.into_cosmos_msg(state.token(offer))?,
+            transfer_out.into_cosmos_msg(state.token(offer.other()))?,
+        ],
+        attributes: vec![
+            attr("action", "swap"),
+            attr("trader", info.sender),
+            attr("offer_token", state.token(offer)),
+            attr("offer_amount", input_amount),
+            attr("return_token", state.token(offer.other())),
+            attr("return_amount", output),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Moves `amount` of the `offer` side into the pool and the output out of it
+fn swap(
+    storage: &mut dyn Storage,
+    state: &Config,
+    offer: Side,
+    amount: Uint128,
+    min_output: Option<Uint128>,
+) -> Result<Uint128, ContractError> {
+    let mut totals = pool_read(storage).load()?;
+    if totals.total_shares.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+    let output = totals.swap_output(offer, amount, state.fee_bps);
+    if output.is_zero() {
+        return Err(ContractError::ZeroOutput {});
+    }
+    check_minimum(output, min_output)?;
+
+    let offer_reserve = totals.reserve_mut(offer);
+    *offer_reserve = offer_reserve.checked_add(amount)?;
+    let ask_reserve = totals.reserve_mut(offer.other());
+    *ask_reserve = ask_reserve.checked_sub(output)?;
+    pool(storage).save(&totals)?;
+    Ok(output)
+}
+
+fn side_of(state: &Config, token: &Addr) -> Result<Side, ContractError> {
+    state.side(token).ok_or_else(|| ContractError::WrongToken {
+        token_a: state.token_a.to_string(),
+        token_b: state.token_b.to_string(),
+    })
+}
+
+/// `amount * numerator / denominator`, rounded up
+fn multiply_ratio_up(amount: Uint128, numerator: Uint128, denominator: Uint128) -> Uint128 {
+    let product = amount.u128() * numerator.u128();
+    let quotient = product / denominator.u128();
+    if quotient * denominator.u128() == product {
+        Uint128::from(quotient)
+    } else {
+        Uint128::from(quotient + 1)
+    }
+}
+
+fn check_minimum(amount: Uint128, minimum: Option<Uint128>) -> Result<(), ContractError> {
+    match minimum {
+        Some(minimum) if amount < minimum => {
+            Err(ContractError::TooLittleReceived { amount, minimum })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn load_shares(storage: &dyn Storage, address: &Addr) -> StdResult<Uint128> {
+    Ok(shares_read(storage)
+        .may_load(address.as_str().as_bytes())?
+        .unwrap_or_default())
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::PoolInfo {} => {
+            let state = config_read(deps.storage).load()?;
+            let totals = pool_read(deps.storage).load()?;
+            to_binary(&PoolInfoResponse {
+                token_a: state.token_a.to_string(),
+                token_b: state.token_b.to_string(),
+                fee_bps: state.fee_bps,
+                reserve_a: totals.reserve_a,
+                reserve_b: totals.reserve_b,
+                total_shares: totals.total_shares,
+            })
+        }
+        QueryMsg::Shares { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let shares = load_shares(deps.storage, &address)?;
+            to_binary(&SharesResponse { shares })
+        }
+        QueryMsg::SimulateSwap {
+            input_token,
+            input_amount,
+        } => {
+            let state = config_read(deps.storage).load()?;
+            let offer = side_of(&state, &deps.api.addr_validate(&input_token)?)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            let totals = pool_read(deps.storage).load()?;
+            let output = totals.swap_output(offer, input_amount, state.fee_bps);
+            to_binary(&SimulateSwapResponse { output })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, OwnedDeps};
+
+    fn setup(fee_bps: u64) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            token_a: "token_a".to_string(),
+            token_b: "token_b".to_string(),
+            fee_bps,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn add_msg(amount_a: u128, amount_b: u128, min_shares: Option<u128>) -> ExecuteMsg {
+        ExecuteMsg::AddLiquidity {
+            amount_a: Uint128::from(amount_a),
+            amount_b: Uint128::from(amount_b),
+            min_shares: min_shares.map(Uint128::from),
+        }
+    }
+
+    fn swap_msg(input_token: &str, input_amount: u128, min_output: Option<u128>) -> ExecuteMsg {
+        ExecuteMsg::Swap {
+            input_token: input_token.to_string(),
+            input_amount: Uint128::from(input_amount),
+            min_output: min_output.map(Uint128::from),
+        }
+    }
+
+    fn remove_msg(shares: u128) -> ExecuteMsg {
+        ExecuteMsg::RemoveLiquidity {
+            shares: Uint128::from(shares),
+            min_a: None,
+            min_b: None,
+        }
+    }
+
+    fn pool_of(deps: Deps) -> Pool {
+        pool_read(deps.storage).load().unwrap()
+    }
+
+    fn product(totals: &Pool) -> u128 {
+        totals.reserve_a.u128() * totals.reserve_b.u128()
+    }
+
+    #[test]
+    fn validates_config() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            token_a: "token_a".to_string(),
+            token_b: "token_b".to_string(),
+            fee_bps: 10_000,
+        };
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::InvalidFee { fee_bps: 10_000 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = InstantiateMsg {
+            token_a: "token_a".to_string(),
+            token_b: "token_a".to_string(),
+            fee_bps: 30,
+        };
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::SameToken { token } => assert_eq!(token, "token_a"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn adds_liquidity_at_reserve_ratio() {
+        let mut deps = setup(30);
+        let res = run(deps.as_mut(), "alice", add_msg(1000, 3001, None)).unwrap();
+        let transfer_a = cw_erc20::ExecuteMsg::transfer_from("alice", "cosmos2contract", 1000u128);
+        let transfer_b = cw_erc20::ExecuteMsg::transfer_from("alice", "cosmos2contract", 3001u128);
+        assert_eq!(
+            res.messages,
+            vec![
+                transfer_a.into_cosmos_msg("token_a").unwrap(),
+                transfer_b.into_cosmos_msg("token_b").unwrap(),
+            ]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "add_liquidity"),
+                attr("provider", "alice"),
+                attr("amount_a", "1000"),
+                attr("amount_b", "3001"),
+                attr("shares", "1000"),
+            ]
+        );
+
+        // A runs out first: 300 shares, which take 300 * 3001 / 1000 = 900.3 B rounded up
+        match run(deps.as_mut(), "bob", add_msg(300, 1000, Some(301))).unwrap_err() {
+            ContractError::TooFewShares { shares, .. } => assert_eq!(shares.u128(), 300),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = run(deps.as_mut(), "bob", add_msg(300, 1000, Some(300))).unwrap();
+        assert_eq!(res.attributes[2], attr("amount_a", "300"));
+        assert_eq!(res.attributes[3], attr("amount_b", "901"));
+        let totals = pool_of(deps.as_ref());
+        assert_eq!(
+            totals,
+            Pool {
+                reserve_a: Uint128::from(1300u128),
+                reserve_b: Uint128::from(3902u128),
+                total_shares: Uint128::from(1300u128),
+            }
+        );
+        assert!(product(&totals) >= 1000 * 3001);
+
+        // B runs out first: 30 * 1300 / 3902 = 9.99 shares, which take 9 A and 27.01 B
+        let res = run(deps.as_mut(), "carol", add_msg(1000, 30, None)).unwrap();
+        assert_eq!(res.attributes[2], attr("amount_a", "9"));
+        assert_eq!(res.attributes[3], attr("amount_b", "28"));
+        assert_eq!(res.attributes[4], attr("shares", "9"));
+
+        match run(deps.as_mut(), "carol", add_msg(0, 100, None)).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), "carol", add_msg(100, 1, None)).unwrap_err() {
+            ContractError::ZeroOutput {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn swaps_both_ways() {
+        let mut deps = setup(30);
+        match run(deps.as_mut(), "trader", swap_msg("token_a", 100, None)).unwrap_err() {
+            ContractError::EmptyPool {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        run(deps.as_mut(), "alice", add_msg(1_000_000, 2_000_000, None)).unwrap();
+        let k = product(&pool_of(deps.as_ref()));
+
+        // 10000 in, 9970 after the fee: 2000000 * 9970 / 1009970 = 19743.16
+        let query_msg = QueryMsg::SimulateSwap {
+            input_token: "token_a".to_string(),
+            input_amount: Uint128::from(10_000u128),
+        };
+        let simulated: SimulateSwapResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(simulated.output.u128(), 19743);
+        let msg = swap_msg("token_a", 10_000, Some(19744));
+        match run(deps.as_mut(), "trader", msg).unwrap_err() {
+            ContractError::TooLittleReceived { amount, minimum } => {
+                assert_eq!(amount.u128(), 19743);
+                assert_eq!(minimum.u128(), 19744);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = run(
+            deps.as_mut(),
+            "trader",
+            swap_msg("token_a", 10_000, Some(19743)),
+        )
+        .unwrap();
+        let transfer_in =
+            cw_erc20::ExecuteMsg::transfer_from("trader", "cosmos2contract", 10_000u128);
+        let transfer_out = cw_erc20::ExecuteMsg::transfer("trader", 19743u128);
+        assert_eq!(
+            res.messages,
+            vec![
+                transfer_in.into_cosmos_msg("token_a").unwrap(),
+                transfer_out.into_cosmos_msg("token_b").unwrap(),
+            ]
+        );
+        let totals = pool_of(deps.as_ref());
+        assert_eq!(totals.reserve_a.u128(), 1_010_000);
+        assert_eq!(totals.reserve_b.u128(), 1_980_257);
+        assert!(product(&totals) >= k);
+        let k = product(&totals);
+
+        // 19743 back, 19683 after the fee: 1010000 * 19683 / 1999940 = 9940.18
+        let res = run(deps.as_mut(), "trader", swap_msg("token_b", 19743, None)).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "swap"),
+                attr("trader", "trader"),
+                attr("offer_token", "token_b"),
+                attr("offer_amount", "19743"),
+                attr("return_token", "token_a"),
+                attr("return_amount", "9940"),
+            ]
+        );
+        let totals = pool_of(deps.as_ref());
+        assert_eq!(totals.reserve_a.u128(), 1_000_060);
+        assert_eq!(totals.reserve_b.u128(), 2_000_000);
+        assert!(product(&totals) >= k);
+
+        match run(deps.as_mut(), "trader", swap_msg("token_b", 1, None)).unwrap_err() {
+            ContractError::ZeroOutput {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), "trader", swap_msg("token_c", 100, None)).unwrap_err() {
+            ContractError::WrongToken { token_a, token_b } => {
+                assert_eq!(token_a, "token_a");
+                assert_eq!(token_b, "token_b");
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn removes_liquidity_pro_rata() {
+        let mut deps = setup(0);
+        run(deps.as_mut(), "alice", add_msg(1000, 3001, None)).unwrap();
+        run(deps.as_mut(), "bob", add_msg(500, 2000, None)).unwrap();
+
+        match run(deps.as_mut(), "bob", remove_msg(501)).unwrap_err() {
+            ContractError::InsufficientShares { balance, required } => {
+                assert_eq!(balance.u128(), 500);
+                assert_eq!(required.u128(), 501);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // bob deposited 500 and 1501, 1/3 of 4502 rounds down to 1500
+        let msg = ExecuteMsg::RemoveLiquidity {
+            shares: Uint128::from(500u128),
+            min_a: Some(Uint128::from(500u128)),
+            min_b: Some(Uint128::from(1501u128)),
+        };
+        match run(deps.as_mut(), "bob", msg).unwrap_err() {
+            ContractError::TooLittleReceived { amount, .. } => assert_eq!(amount.u128(), 1500),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = run(deps.as_mut(), "bob", remove_msg(500)).unwrap();
+        let transfer_a = cw_erc20::ExecuteMsg::transfer("bob", 500u128);
+        let transfer_b = cw_erc20::ExecuteMsg::transfer("bob", 1500u128);
+        assert_eq!(
+            res.messages,
+            vec![
+                transfer_a.into_cosmos_msg("token_a").unwrap(),
+                transfer_b.into_cosmos_msg("token_b").unwrap(),
+            ]
+        );
+        assert_eq!(
+            load_shares(&deps.storage, &Addr::unchecked("bob"))
+                .unwrap()
+                .u128(),
+            0
+        );
+
+        // the last provider takes everything
+        let res = run(deps.as_mut(), "alice", remove_msg(1000)).unwrap();
+        assert_eq!(res.attributes[3], attr("amount_b", "3002"));
+        assert_eq!(pool_of(deps.as_ref()), Pool::default());
+    }
+
+    /// Minimal linear congruential generator, so the property tests are reproducible
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, bound: u128) -> u128 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 33) as u128 % bound
+        }
+    }
+
+    /// `a / b <= c / d` without rounding
+    fn ratio_le(a: Uint128, b: Uint128, c: Uint128, d: Uint128) -> bool {
+        a.u128() * d.u128() <= c.u128() * b.u128()
+    }
+
+    #[test]
+    fn k_never_decreases() {
+        let providers = ["alice", "bob", "carol"];
+        for &fee_bps in &[0u64, 30, 500] {
+            let mut deps = setup(fee_bps);
+            let mut rng = Lcg(fee_bps + 1);
+            run(deps.as_mut(), "alice", add_msg(1_000_000, 3_000_000, None)).unwrap();
+
+            for _ in 0..500 {
+                let before = pool_of(deps.as_ref());
+                let amount = rng.next(50_000) + 1;
+                let provider = providers[rng.next(3) as usize];
+                let is_swap = match rng.next(4) {
+                    0 => {
+                        let _ = run(deps.as_mut(), "trader", swap_msg("token_a", amount, None));
+                        true
+                    }
+                    1 => {
+                        let _ = run(deps.as_mut(), "trader", swap_msg("token_b", amount, None));
+                        true
+                    }
+                    2 => {
+                        let other = rng.next(150_000) + 1;
+                        let _ = run(deps.as_mut(), provider, add_msg(amount, other, None));
+                        false
+                    }
+                    _ => {
+                        let balance = load_shares(&deps.storage, &Addr::unchecked(provider))
+                            .unwrap()
+                            .u128();
+                        let _ = run(deps.as_mut(), provider, remove_msg(amount.min(balance)));
+                        false
+                    }
+                };
+
+                let after = pool_of(deps.as_ref());
+                if is_swap {
+                    assert!(product(&after) >= product(&before));
+                    assert_eq!(after.total_shares, before.total_shares);
+                } else if !after.total_shares.is_zero() {
+                    // deposits and withdrawals never lower the reserves behind each share
+                    assert!(ratio_le(
+                        before.reserve_a,
+                        before.total_shares,
+                        after.reserve_a,
+                        after.total_shares
+                    ));
+                    assert!(ratio_le(
+                        before.reserve_b,
+                        before.total_shares,
+                        after.reserve_b,
+                        after.total_shares
+                    ));
+                } else {
+                    run(deps.as_mut(), "alice", add_msg(1_000_000, 3_000_000, None)).unwrap();
+                }
+
+                let issued: u128 = providers
+                    .iter()
+                    .map(|p| {
+                        load_shares(&deps.storage, &Addr::unchecked(*p))
+                            .unwrap()
+                            .u128()
+                    })
+                    .sum();
+                assert_eq!(issued, pool_of(deps.as_ref()).total_shares.u128());
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub token_a: String,
+    pub token_b: String,
+    /// Share of every swap input kept by the pool, in basis points, e.g. 30 for 0.3%
+    pub fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Deposits up to `amount_a` and `amount_b` at the current reserve ratio. The tokens are
+    /// pulled with `TransferFrom`, so the pair needs allowances on both tokens first.
+    /// The first deposit sets the price and deposits both amounts exactly.
+    AddLiquidity {
+        amount_a: Uint128,
+        amount_b: Uint128,
+        min_shares: Option<Uint128>,
+    },
+    /// Burns `shares` and pays out their part of both reserves
+    RemoveLiquidity {
+        shares: Uint128,
+        min_a: Option<Uint128>,
+        min_b: Option<Uint128>,
+    },
+    /// Swaps `input_amount` of `input_token` for the other token. The input is pulled with
+    /// `TransferFrom`, so the pair needs an allowance first.
+    Swap {
+        input_token: String,
+        input_amount: Uint128,
+        min_output: Option<Uint128>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    PoolInfo {},
+    /// Returns the liquidity shares of `address`
+    Shares {
+        address: String,
+    },
+    /// Returns what a swap of `input_amount` of `input_token` would receive right now
+    SimulateSwap {
+        input_token: String,
+        input_amount: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolInfoResponse {
+    pub token_a: String,
+    pub token_b: String,
+    pub fee_bps: u64,
+    pub reserve_a: Uint128,
+    pub reserve_b: Uint128,
+    pub total_shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharesResponse {
+    pub shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateSwapResponse {
+    pub output: Uint128,
+}
@@ -0,0 +1,123 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static POOL_KEY: &[u8] = b"pool";
+static SHARES_KEY: &[u8] = b"shares";
+
+pub const MAX_BPS: u64 = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub token_a: Addr,
+    pub token_b: Addr,
+    /// Share of every swap input kept by the pool, in basis points
+    pub fee_bps: u64,
+}
+
+impl Config {
+    /// The side of the pair `token` is on, if any
+    pub fn side(&self, token: &Addr) -> Option<Side> {
+        if *token == self.token_a {
+            Some(Side::A)
+        } else if *token == self.token_b {
+            Some(Side::B)
+        } else {
+            None
+        }
+    }
+
+    pub fn token(&self, side: Side) -> &Addr {
+        match side {
+            Side::A => &self.token_a,
+            Side::B => &self.token_b,
+        }
+    }
+}
+
+/// One of the two tokens of the pair
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    A,
+    B,
+}
+
+impl Side {
+    pub fn other(self) -> Side {
+        match self {
+            Side::A => Side::B,
+            Side::B => Side::A,
+        }
+    }
+}
+
+/// The reserves are tracked here instead of read from the balances, so tokens transferred to
+/// the contract directly don't move the price.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Pool {
+    pub reserve_a: Uint128,
+    pub reserve_b: Uint128,
+    /// Liquidity shares issued to all providers
+    pub total_shares: Uint128,
+}
+
+impl Pool {
+    pub fn reserve(&self, side: Side) -> Uint128 {
+        match side {
+            Side::A => self.reserve_a,
+            Side::B => self.reserve_b,
+        }
+    }
+
+    pub fn reserve_mut(&mut self, side: Side) -> &mut Uint128 {
+        match side {
+            Side::A => &mut self.reserve_a,
+            Side::B => &mut self.reserve_b,
+        }
+    }
+
+    /// The amount received for `amount` of the `offer` side. The fee is taken from the input
+    /// first, and both the fee and the output round in favour of the pool, so the product of
+    /// the reserves never decreases.
+    pub fn swap_output(&self, offer: Side, amount: Uint128, fee_bps: u64) -> Uint128 {
+        let offer_reserve = self.reserve(offer);
+        let ask_reserve = self.reserve(offer.other());
+        let amount_after_fee = amount.multiply_ratio(MAX_BPS - fee_bps, MAX_BPS);
+        if amount_after_fee.is_zero() {
+            return Uint128::zero();
+        }
+        // cannot overflow, the sum is at most the offer token's total supply
+        let denominator = offer_reserve.u128() + amount_after_fee.u128();
+        ask_reserve.multiply_ratio(amount_after_fee, denominator)
+    }
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn pool(storage: &mut dyn Storage) -> Singleton<'_, Pool> {
+    singleton(storage, POOL_KEY)
+}
+
+pub fn pool_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Pool> {
+    singleton_read(storage, POOL_KEY)
+}
+
+/// Liquidity shares by provider address
+pub fn shares(storage: &mut dyn Storage) -> Bucket<'_, Uint128> {
+    bucket(storage, SHARES_KEY)
+}
+
+pub fn shares_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Uint128> {
+    bucket_read(storage, SHARES_KEY)
+}
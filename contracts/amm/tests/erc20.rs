@@ -0,0 +1,181 @@
+//! Runs the pair against two real erc20 contracts on a test chain, which reverts failing
+//! messages like a real chain.
+
+use cosmwasm_std::Uint128;
+use cw_amm::contract::{execute, instantiate, query};
+use cw_amm::msg::{ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg, SharesResponse};
+use cw_erc20::BalanceResponse;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN_A: &str = "token_a";
+const TOKEN_B: &str = "token_b";
+const PAIR: &str = "pair";
+
+/// A pair with a 0.3% fee. alice and bob hold both tokens, trader only A.
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    let msg = erc20_msg(
+        "TOKA",
+        &[
+            ("alice", 1_000_000),
+            ("bob", 1_000_000),
+            ("trader", 100_000),
+        ],
+    );
+    chain.instantiate_erc20(TOKEN_A, &msg).unwrap();
+    let msg = erc20_msg("TOKB", &[("alice", 1_000_000), ("bob", 1_000_000)]);
+    chain.instantiate_erc20(TOKEN_B, &msg).unwrap();
+
+    let pair = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token_a: TOKEN_A.to_string(),
+        token_b: TOKEN_B.to_string(),
+        fee_bps: 30,
+    };
+    chain.instantiate(pair, PAIR, "creator", &msg, &[]).unwrap();
+    chain
+}
+
+fn approve(chain: &mut Chain, token: &str, owner: &str, amount: u128) {
+    let msg = cw_erc20::ExecuteMsg::approve(PAIR, amount);
+    chain.execute(token, owner, &msg, &[]).unwrap();
+}
+
+fn add_liquidity(chain: &mut Chain, provider: &str, amount_a: u128, amount_b: u128) {
+    approve(chain, TOKEN_A, provider, amount_a);
+    approve(chain, TOKEN_B, provider, amount_b);
+    let msg = ExecuteMsg::AddLiquidity {
+        amount_a: Uint128::from(amount_a),
+        amount_b: Uint128::from(amount_b),
+        min_shares: None,
+    };
+    chain.execute(PAIR, provider, &msg, &[]).unwrap();
+}
+
+fn tokens(chain: &Chain, token: &str, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(token, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn shares(chain: &Chain, address: &str) -> u128 {
+    let msg = QueryMsg::Shares {
+        address: address.to_string(),
+    };
+    let res: SharesResponse = chain.query(PAIR, &msg).unwrap();
+    res.shares.u128()
+}
+
+fn pool(chain: &Chain) -> PoolInfoResponse {
+    chain.query(PAIR, &QueryMsg::PoolInfo {}).unwrap()
+}
+
+/// The pool's reserves must match what it actually holds
+fn assert_backed(chain: &Chain) {
+    let pool = pool(chain);
+    assert_eq!(pool.reserve_a.u128(), tokens(chain, TOKEN_A, PAIR));
+    assert_eq!(pool.reserve_b.u128(), tokens(chain, TOKEN_B, PAIR));
+}
+
+fn product(pool: &PoolInfoResponse) -> u128 {
+    pool.reserve_a.u128() * pool.reserve_b.u128()
+}
+
+#[test]
+fn add_swap_remove_cycle() {
+    let mut chain = setup();
+    add_liquidity(&mut chain, "alice", 100_000, 400_000);
+    assert_backed(&chain);
+    add_liquidity(&mut chain, "bob", 50_000, 250_000);
+    assert_backed(&chain);
+    // B is capped by the reserve ratio
+    assert_eq!(tokens(&chain, TOKEN_B, "bob"), 800_000);
+    assert_eq!(shares(&chain, "bob"), 50_000);
+    let res = pool(&chain);
+    assert_eq!(res.reserve_a.u128(), 150_000);
+    assert_eq!(res.reserve_b.u128(), 600_000);
+    let k = product(&res);
+
+    // trader buys B with A, 9970 after the fee: 600000 * 9970 / 159970 = 37394.5
+    approve(&mut chain, TOKEN_A, "trader", 10_000);
+    let msg = ExecuteMsg::Swap {
+        input_token: TOKEN_A.to_string(),
+        input_amount: Uint128::from(10_000u128),
+        min_output: Some(Uint128::from(37_000u128)),
+    };
+    chain.execute(PAIR, "trader", &msg, &[]).unwrap();
+    assert_eq!(tokens(&chain, TOKEN_A, "trader"), 90_000);
+    assert_eq!(tokens(&chain, TOKEN_B, "trader"), 37_394);
+    assert_backed(&chain);
+    assert!(product(&pool(&chain)) >= k);
+    let k = product(&pool(&chain));
+
+    // and sells them back, 37281 after the fee: 160000 * 37281 / 599887 = 9943.6
+    approve(&mut chain, TOKEN_B, "trader", 37_394);
+    let msg = ExecuteMsg::Swap {
+        input_token: TOKEN_B.to_string(),
+        input_amount: Uint128::from(37_394u128),
+        min_output: Some(Uint128::from(9_900u128)),
+    };
+    chain.execute(PAIR, "trader", &msg, &[]).unwrap();
+    assert_eq!(tokens(&chain, TOKEN_A, "trader"), 99_943);
+    assert_eq!(tokens(&chain, TOKEN_B, "trader"), 0);
+    assert_backed(&chain);
+    assert!(product(&pool(&chain)) >= k);
+
+    // the fees stay with the providers
+    let msg = ExecuteMsg::RemoveLiquidity {
+        shares: Uint128::from(50_000u128),
+        min_a: Some(Uint128::from(50_000u128)),
+        min_b: Some(Uint128::from(200_000u128)),
+    };
+    chain.execute(PAIR, "bob", &msg, &[]).unwrap();
+    assert_eq!(tokens(&chain, TOKEN_A, "bob"), 1_000_019);
+    assert_eq!(tokens(&chain, TOKEN_B, "bob"), 1_000_000);
+    assert_backed(&chain);
+    let msg = ExecuteMsg::RemoveLiquidity {
+        shares: Uint128::from(100_000u128),
+        min_a: None,
+        min_b: None,
+    };
+    chain.execute(PAIR, "alice", &msg, &[]).unwrap();
+    assert_eq!(tokens(&chain, TOKEN_A, "alice"), 1_000_038);
+    assert_eq!(tokens(&chain, TOKEN_B, "alice"), 1_000_000);
+
+    assert_eq!(tokens(&chain, TOKEN_A, PAIR), 0);
+    assert_eq!(tokens(&chain, TOKEN_B, PAIR), 0);
+    assert_eq!(pool(&chain).total_shares.u128(), 0);
+}
+
+#[test]
+fn swap_needs_allowance() {
+    let mut chain = setup();
+    add_liquidity(&mut chain, "alice", 100_000, 400_000);
+    let msg = ExecuteMsg::Swap {
+        input_token: TOKEN_A.to_string(),
+        input_amount: Uint128::from(1_000u128),
+        min_output: None,
+    };
+    let err = chain.execute(PAIR, "trader", &msg, &[]).unwrap_err();
+    assert!(err.contains("allowance"), "unexpected error: {}", err);
+    // the pair updated its reserves before pulling the input, which is reverted too
+    assert_eq!(tokens(&chain, TOKEN_B, "trader"), 0);
+    assert_eq!(pool(&chain).reserve_a.u128(), 100_000);
+    assert_backed(&chain);
+}
+
+#[test]
+fn slippage_guard_rejects_swap() {
+    let mut chain = setup();
+    add_liquidity(&mut chain, "alice", 100_000, 400_000);
+    approve(&mut chain, TOKEN_A, "trader", 10_000);
+    let msg = ExecuteMsg::Swap {
+        input_token: TOKEN_A.to_string(),
+        input_amount: Uint128::from(10_000u128),
+        min_output: Some(Uint128::from(40_000u128)),
+    };
+    let err = chain.execute(PAIR, "trader", &msg, &[]).unwrap_err();
+    assert_eq!(err, "Received 36264, less than the minimum of 40000");
+    assert_eq!(tokens(&chain, TOKEN_A, "trader"), 100_000);
+    assert_backed(&chain);
+}
@@ -0,0 +1,403 @@
+use cosmwasm_std::{
+    attr, entry_point, from_binary, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, WasmMsg,
+};
+use cw_erc20::ReceiveMsg;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, HookMsg, InstantiateMsg, QueryMsg};
+use crate::state::{swaps, swaps_read, Swap, SwapStatus};
+
+/// Shortest accepted preimage, so it cannot be guessed from the hash
+pub const MIN_PREIMAGE_LENGTH: usize = 32;
+/// Longest accepted preimage. Swaps with counterparts on other chains need a preimage that
+/// both chains accept.
+pub const MAX_PREIMAGE_LENGTH: usize = 64;
+
+#[entry_point]
+pub fn instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(receive) => try_receive(deps, env, info, receive),
+        ExecuteMsg::Claim { id, preimage } => try_claim(deps, env, id, preimage),
+        ExecuteMsg::Refund { id } => try_refund(deps, env, id),
+    }
+}
+
+/// Receive
+///
+/// Creates a swap from tokens sent with the erc20 `Send` message. The signer is the token
+/// contract and `receive.sender` the account that sent the tokens.
+///
+/// @param receive the `ReceiveMsg` with a `HookMsg::CreateSwap` payload
+fn try_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let payload = receive.msg.ok_or(ContractError::MissingPayload {})?;
+    let HookMsg::CreateSwap {
+        id,
+        hash,
+        recipient,
+        expires_at_height,
+    } = from_binary(&payload)?;
+
+    if receive.amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    if id.is_empty() {
+        return Err(ContractError::EmptyId {});
+    }
+    if swaps_read(deps.storage).may_load(id.as_bytes())?.is_some() {
+        return Err(ContractError::AlreadyExists { id });
+    }
+    if !matches!(hex::decode(&hash), Ok(bytes) if bytes.len() == 32) {
+        return Err(ContractError::InvalidHash {});
+    }
+    if expires_at_height <= env.block.height {
+        return Err(ContractError::InvalidExpiration { expires_at_height });
+    }
+
+    let swap = Swap {
+        token: info.sender,
+        amount: receive.amount,
+        creator: deps.api.addr_validate(&receive.sender)?,
+        recipient: deps.api.addr_validate(&recipient)?,
+        hash: hash.to_lowercase(),
+        expires_at_height,
+        status: SwapStatus::Open,
+        preimage: None,
+    };
+    swaps(deps.storage).save(id.as_bytes(), &swap)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "create_swap"),
+            attr("id", id),
+            attr("token", swap.token),
+            attr("amount", swap.amount),
+            attr("creator", swap.creator),
+            attr("recipient", swap.recipient),
+            attr("expires_at_height", expires_at_height),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Claim
+///
+/// Sends the escrowed tokens to the recipient if the preimage hashes to the swap's hash.
+/// The preimage is stored, so the swap's creator can use it to claim the counterpart swap.
+///
+/// @param id the swap
+/// @param preimage the hex encoded preimage
+fn try_claim(
+    deps: DepsMut,
+    env: Env,
+    id: String,
+    preimage: String,
+) -> Result<Response, ContractError> {
+    let mut swap = load_open_swap(deps.as_ref(), &id)?;
+    if env.block.height >= swap.expires_at_height {
+        return Err(ContractError::Expired {
+            expires_at_height: swap.expires_at_height,
+        });
+    }
+    let preimage_bytes = match hex::decode(&preimage) {
+        Ok(bytes) if (MIN_PREIMAGE_LENGTH..=MAX_PREIMAGE_LENGTH).contains(&bytes.len()) => bytes,
+        _ => {
+            return Err(ContractError::InvalidPreimage {
+                min: MIN_PREIMAGE_LENGTH,
+                max: MAX_PREIMAGE_LENGTH,
+            })
+        }
+    };
+    if hex::encode(Sha256::digest(&preimage_bytes)) != swap.hash {
+        return Err(ContractError::WrongPreimage {});
+    }
+
+    swap.status = SwapStatus::Claimed;
+    swap.preimage = Some(preimage.to_lowercase());
+    swaps(deps.storage).save(id.as_bytes(), &swap)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer_msg(&swap, swap.recipient.as_str())?],
+        attributes: vec![
+            attr("action", "claim"),
+            attr("id", id),
+            attr("recipient", &swap.recipient),
+            attr("preimage", preimage),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Refund
+///
+/// Returns the escrowed tokens of an expired swap to its creator.
+///
+/// @param id the swap
+fn try_refund(deps: DepsMut, env: Env, id: String) -> Result<Response, ContractError> {
+    let mut swap = load_open_swap(deps.as_ref(), &id)?;
+    if env.block.height < swap.expires_at_height {
+        return Err(ContractError::NotExpired {
+            expires_at_height: swap.expires_at_height,
+        });
+    }
+
+    swap.status = SwapStatus::Refunded;
+    swaps(deps.storage).save(id.as_bytes(), &swap)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer_msg(&swap, swap.creator.as_str())?],
+        attributes: vec![
+            attr("action", "refund"),
+            attr("id", id),
+            attr("creator", &swap.creator),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn load_open_swap(deps: Deps, id: &str) -> Result<Swap, ContractError> {
+    let swap = swaps_read(deps.storage)
+        .may_load(id.as_bytes())?
+        .ok_or_else(|| ContractError::SwapNotFound { id: id.to_string() })?;
+    if swap.status != SwapStatus::Open {
+        return Err(ContractError::SwapClosed {});
+    }
+    Ok(swap)
+}
+
+/// Transfers the escrowed tokens to `recipient`
+fn transfer_msg(swap: &Swap, recipient: &str) -> StdResult<CosmosMsg> {
+    let transfer = cw_erc20::ExecuteMsg::transfer(recipient, swap.amount);
+    Ok(WasmMsg::Execute {
+        contract_addr: swap.token.to_string(),
+        msg: to_binary(&transfer)?,
+        send: vec![],
+    }
+    .into())
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Swap { id } => {
+            let swap = swaps_read(deps.storage)
+                .may_load(id.as_bytes())?
+                .ok_or_else(|| StdError::not_found("Swap"))?;
+            to_binary(&swap)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{OwnedDeps, Uint128};
+
+    const PREIMAGE: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+    fn hash_of(preimage: &str) -> String {
+        hex::encode(Sha256::digest(&hex::decode(preimage).unwrap()))
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn receive(id: &str, amount: u128, hash: String, expires_at_height: u64) -> ExecuteMsg {
+        let hook = HookMsg::CreateSwap {
+            id: id.to_string(),
+            hash,
+            recipient: "recipient".to_string(),
+            expires_at_height,
+        };
+        ExecuteMsg::Receive(ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::from(amount),
+            msg: Some(to_binary(&hook).unwrap()),
+        })
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("creator", &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let msg = receive("swap", 10, hash_of(PREIMAGE), 110);
+        execute(deps.as_mut(), env_at(100), mock_info("token", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(deps: DepsMut, height: u64, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info("anyone", &[]), msg)
+    }
+
+    fn claim(preimage: &str) -> ExecuteMsg {
+        ExecuteMsg::Claim {
+            id: "swap".to_string(),
+            preimage: preimage.to_string(),
+        }
+    }
+
+    fn status(deps: Deps) -> SwapStatus {
+        let msg = QueryMsg::Swap {
+            id: "swap".to_string(),
+        };
+        let swap: Swap = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        swap.status
+    }
+
+    #[test]
+    fn create_validates_swap() {
+        let mut deps = setup();
+        let cases = vec![
+            (receive("swap", 10, hash_of(PREIMAGE), 110), "AlreadyExists"),
+            (receive("", 10, hash_of(PREIMAGE), 110), "EmptyId"),
+            (receive("other", 0, hash_of(PREIMAGE), 110), "ZeroAmount"),
+            (receive("other", 10, "abcd".to_string(), 110), "InvalidHash"),
+            (receive("other", 10, "xyz".to_string(), 110), "InvalidHash"),
+            (
+                receive("other", 10, hash_of(PREIMAGE), 100),
+                "InvalidExpiration",
+            ),
+        ];
+        for (msg, expected) in cases {
+            let err =
+                execute(deps.as_mut(), env_at(100), mock_info("token", &[]), msg).unwrap_err();
+            assert!(
+                format!("{:?}", err).starts_with(expected),
+                "unexpected error: {:?}",
+                err
+            );
+        }
+
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::from(10u128),
+            msg: None,
+        });
+        match execute(deps.as_mut(), env_at(100), mock_info("token", &[]), msg).unwrap_err() {
+            ContractError::MissingPayload {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn claim_checks_preimage() {
+        let mut deps = setup();
+        let short = &PREIMAGE[..62];
+        let long = PREIMAGE.repeat(3);
+        for preimage in &[short, long.as_str(), "not hex"] {
+            match run(deps.as_mut(), 105, claim(preimage)).unwrap_err() {
+                ContractError::InvalidPreimage { min: 32, max: 64 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        let wrong = PREIMAGE.replace('1', "2");
+        match run(deps.as_mut(), 105, claim(&wrong)).unwrap_err() {
+            ContractError::WrongPreimage {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 110, claim(PREIMAGE)).unwrap_err() {
+            ContractError::Expired {
+                expires_at_height: 110,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(status(deps.as_ref()), SwapStatus::Open);
+    }
+
+    #[test]
+    fn claim_works_once() {
+        let mut deps = setup();
+        let res = run(deps.as_mut(), 109, claim(PREIMAGE)).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("recipient", 10u128);
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&transfer).unwrap(),
+                send: vec![],
+            })]
+        );
+        assert_eq!(status(deps.as_ref()), SwapStatus::Claimed);
+
+        match run(deps.as_mut(), 109, claim(PREIMAGE)).unwrap_err() {
+            ContractError::SwapClosed {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let refund = ExecuteMsg::Refund {
+            id: "swap".to_string(),
+        };
+        match run(deps.as_mut(), 110, refund).unwrap_err() {
+            ContractError::SwapClosed {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn refund_works_after_expiry() {
+        let mut deps = setup();
+        let refund = ExecuteMsg::Refund {
+            id: "swap".to_string(),
+        };
+        match run(deps.as_mut(), 109, refund.clone()).unwrap_err() {
+            ContractError::NotExpired {
+                expires_at_height: 110,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = run(deps.as_mut(), 110, refund.clone()).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("creator", 10u128);
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&transfer).unwrap(),
+                send: vec![],
+            })]
+        );
+        assert_eq!(status(deps.as_ref()), SwapStatus::Refunded);
+        match run(deps.as_mut(), 111, refund).unwrap_err() {
+            ContractError::SwapClosed {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Received tokens without a swap to create")]
+    MissingPayload {},
+
+    #[error("Swap id must not be empty")]
+    EmptyId {},
+
+    #[error("Swap {id} already exists")]
+    AlreadyExists { id: String },
+
+    #[error("Swap {id} not found")]
+    SwapNotFound { id: String },
+
+    #[error("Invalid hash: expected 32 bytes as hex")]
+    InvalidHash {},
+
+    #[error("Invalid preimage: expected {min} to {max} bytes as hex")]
+    InvalidPreimage { min: usize, max: usize },
+
+    #[error("Preimage does not match the hash")]
+    WrongPreimage {},
+
+    #[error("Expiration height {expires_at_height} is not in the future")]
+    InvalidExpiration { expires_at_height: u64 },
+
+    #[error("Swap expired at height {expires_at_height}")]
+    Expired { expires_at_height: u64 },
+
+    #[error("Swap does not expire before height {expires_at_height}")]
+    NotExpired { expires_at_height: u64 },
+
+    #[error("Swap was already claimed or refunded")]
+    SwapClosed {},
+}
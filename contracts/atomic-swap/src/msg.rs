@@ -0,0 +1,39 @@
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Tokens sent with the erc20 `Send` message. The payload must be a `HookMsg`.
+    Receive(ReceiveMsg),
+    /// Sends the tokens to the recipient. Anyone knowing the preimage can do this before the
+    /// swap expires.
+    Claim { id: String, preimage: String },
+    /// Returns the tokens to the creator. Anyone can do this once the swap expired.
+    Refund { id: String },
+}
+
+/// Payload of the `ReceiveMsg` telling the contract what to do with the tokens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookMsg {
+    /// Escrows the received tokens for `recipient` until `expires_at_height`
+    CreateSwap {
+        id: String,
+        /// Hex encoded sha256 hash of the preimage
+        hash: String,
+        recipient: String,
+        expires_at_height: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the `Swap` with the given id
+    Swap { id: String },
+}
@@ -0,0 +1,39 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{bucket, bucket_read, Bucket, ReadonlyBucket};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static SWAPS_KEY: &[u8] = b"swaps";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapStatus {
+    Open,
+    Claimed,
+    Refunded,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Swap {
+    /// The erc20 contract holding the escrowed tokens
+    pub token: Addr,
+    pub amount: Uint128,
+    /// Sent the tokens and receives them back on refund
+    pub creator: Addr,
+    pub recipient: Addr,
+    /// Hex encoded sha256 hash of the preimage
+    pub hash: String,
+    pub expires_at_height: u64,
+    pub status: SwapStatus,
+    /// Hex encoded preimage, revealed by the claim
+    pub preimage: Option<String>,
+}
+
+/// Swaps by id. Closed swaps are kept, so their ids cannot be reused.
+pub fn swaps(storage: &mut dyn Storage) -> Bucket<'_, Swap> {
+    bucket(storage, SWAPS_KEY)
+}
+
+pub fn swaps_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Swap> {
+    bucket_read(storage, SWAPS_KEY)
+}
@@ -0,0 +1,91 @@
+//! Runs swaps against a real erc20 contract on a test chain.
+
+use cosmwasm_std::to_binary;
+use cw_atomic_swap::contract::{execute, instantiate, query};
+use cw_atomic_swap::msg::{ExecuteMsg, HookMsg, InstantiateMsg, QueryMsg};
+use cw_atomic_swap::state::{Swap, SwapStatus};
+use cw_erc20::BalanceResponse;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+use sha2::{Digest, Sha256};
+
+const TOKEN: &str = "token";
+const SWAP: &str = "swap";
+const PREIMAGE: &str = "6b1415c567b4d4e2b5a2a0d1c7e3f4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1";
+
+/// alice holds 100 tokens
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    let msg = erc20_msg("CASH", &[("alice", 100)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+    let swap = chain.store_code(Contract::new(instantiate, execute, query));
+    chain
+        .instantiate(swap, SWAP, "creator", &InstantiateMsg {}, &[])
+        .unwrap();
+    chain.set_height(100);
+    chain
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn swap(chain: &Chain, id: &str) -> Swap {
+    let msg = QueryMsg::Swap { id: id.to_string() };
+    chain.query(SWAP, &msg).unwrap()
+}
+
+/// alice sends 40 tokens to the swap contract, claimable by bob until block 110
+fn create_swap(chain: &mut Chain) {
+    let hash = hex::encode(Sha256::digest(&hex::decode(PREIMAGE).unwrap()));
+    let hook = HookMsg::CreateSwap {
+        id: "trade".to_string(),
+        hash,
+        recipient: "bob".to_string(),
+        expires_at_height: 110,
+    };
+    let send = cw_erc20::ExecuteMsg::send(SWAP, 40u128, Some(to_binary(&hook).unwrap()));
+    chain.execute(TOKEN, "alice", &send, &[]).unwrap();
+}
+
+#[test]
+fn claim_flow() {
+    let mut chain = setup();
+    create_swap(&mut chain);
+    assert_eq!(balance(&chain, "alice"), 60);
+    assert_eq!(balance(&chain, SWAP), 40);
+    let res = swap(&chain, "trade");
+    assert_eq!(res.token.as_str(), TOKEN);
+    assert_eq!(res.creator.as_str(), "alice");
+    assert_eq!(res.amount.u128(), 40);
+
+    chain.set_height(109);
+    let claim = ExecuteMsg::Claim {
+        id: "trade".to_string(),
+        preimage: PREIMAGE.to_string(),
+    };
+    chain.execute(SWAP, "relayer", &claim, &[]).unwrap();
+    assert_eq!(balance(&chain, SWAP), 0);
+    assert_eq!(balance(&chain, "bob"), 40);
+
+    let res = swap(&chain, "trade");
+    assert_eq!(res.status, SwapStatus::Claimed);
+    assert_eq!(res.preimage, Some(PREIMAGE.to_string()));
+}
+
+#[test]
+fn refund_flow() {
+    let mut chain = setup();
+    create_swap(&mut chain);
+
+    chain.set_height(110);
+    let refund = ExecuteMsg::Refund {
+        id: "trade".to_string(),
+    };
+    chain.execute(SWAP, "anyone", &refund, &[]).unwrap();
+    assert_eq!(balance(&chain, SWAP), 0);
+    assert_eq!(balance(&chain, "alice"), 100);
+    assert_eq!(balance(&chain, "bob"), 0);
+    assert_eq!(swap(&chain, "trade").status, SwapStatus::Refunded);
+}
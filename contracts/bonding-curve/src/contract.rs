@@ -0,0 +1,499 @@
+use cosmwasm_std::{
+    attr, coins, entry_point, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo,
+    OverflowError, Response, StdResult, Uint128,
+};
+
+use crate::error::ContractError;
+use crate::msg::{CurveResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SimulateResponse};
+use crate::state::{config, config_read, curve, curve_read, Config, Curve};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.slope_numerator == 0 || msg.slope_denominator == 0 {
+        return Err(ContractError::InvalidSlope {});
+    }
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        denom: msg.denom,
+        slope_numerator: msg.slope_numerator,
+        slope_denominator: msg.slope_denominator,
+    };
+    config(deps.storage).save(&state)?;
+    curve(deps.storage).save(&Curve::default())?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Buy { min_out } => try_buy(deps, info, min_out),
+        ExecuteMsg::Sell { amount, min_out } => try_sell(deps, env, info, amount, min_out),
+    }
+}
+
+/// Buy
+///
+/// Mints as many tokens as the sent native coins pay for along the curve. The whole payment
+/// goes into the reserve, including the remainder too small to buy another base unit.
+///
+/// @param min_out fail if fewer tokens would be minted
+fn try_buy(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_out: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let paid = native_amount(&info, &state.denom)?;
+    let mut totals = curve_read(deps.storage).load()?;
+    let minted = buy_output(&state, &totals, paid)?;
+    if minted.is_zero() {
+        return Err(ContractError::ZeroOutput {});
+    }
+    check_minimum(minted, min_out)?;
+
+    totals.supply = totals.supply.checked_add(minted)?;
+    totals.reserve = totals.reserve.checked_add(paid)?;
+    curve(deps.storage).save(&totals)?;
+
+    let mint = cw_erc20::ExecuteMsg::mint(info.sender.as_str(), minted);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![mint.into_cosmos_msg(&state.token)?],
+        attributes: vec![
+            attr("action", "buy"),
+            attr("buyer", info.sender),
+            attr("paid", paid),
+            attr("minted", minted),
+            attr("supply", totals.supply),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Sell
+///
+/// Pulls tokens from the signer, burns them and pays out the area under the curve between
+/// the old and the new supply, rounded down.
+///
+/// @param amount the number of tokens to sell
+/// @param min_out fail if fewer native coins would be paid out
+fn try_sell(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    min_out: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let mut totals = curve_read(deps.storage).load()?;
+    if amount > totals.supply {
+        return Err(ContractError::InsufficientSupply {
+            supply: totals.supply,
+            required: amount,
+        });
+    }
+    let payout = sell_output(&state, &totals, amount)?;
+    if payout.is_zero() {
+        return Err(ContractError::ZeroOutput {});
+    }
+    check_minimum(payout, min_out)?;
+
+    totals.supply = totals.supply.checked_sub(amount)?;
+    totals.reserve = totals.reserve.checked_sub(payout)?;
+    curve(deps.storage).save(&totals)?;
+
+    let pull =
+        cw_erc20::ExecuteMsg::transfer_from(info.sender.as_str(), env.contract.address, amount);
+    let burn = cw_erc20::ExecuteMsg::burn(amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![
+            pull.into_cosmos_msg(&state.token)?,
+            burn.into_cosmos_msg(&state.token)?,
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(payout.u128(), &state.denom),
+            }
+            .into(),
+        ],
+        attributes: vec![
+            attr("action", "sell"),
+            attr("seller", info.sender),
+            attr("burned", amount),
+            attr("payout", payout),
+            attr("supply", totals.supply),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// The tokens minted for `paid` coins: the largest supply whose reserve is covered by the
+/// reserve of the current supply plus the payment
+fn buy_output(state: &Config, totals: &Curve, paid: Uint128) -> Result<Uint128, OverflowError> {
+    let target = state.reserve_for(totals.supply)?.checked_add(paid)?;
+    state.supply_for(target)?.checked_sub(totals.supply)
+}
+
+/// The coins paid out for burning `amount` tokens. Fails if more than the supply is sold.
+fn sell_output(state: &Config, totals: &Curve, amount: Uint128) -> Result<Uint128, OverflowError> {
+    let remaining = totals.supply.checked_sub(amount)?;
+    state
+        .reserve_for(totals.supply)?
+        .checked_sub(state.reserve_for(remaining)?)
+}
+
+/// The amount of the single `denom` coin sent along
+fn native_amount(info: &MessageInfo, denom: &str) -> Result<Uint128, ContractError> {
+    match info.funds.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => Ok(coin.amount),
+        _ => Err(ContractError::InvalidFunds {
+            denom: denom.to_string(),
+        }),
+    }
+}
+
+fn check_minimum(amount: Uint128, minimum: Option<Uint128>) -> Result<(), ContractError> {
+    match minimum {
+        Some(minimum) if amount < minimum => {
+            Err(ContractError::TooLittleReceived { amount, minimum })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let state = config_read(deps.storage).load()?;
+    let totals = curve_read(deps.storage).load()?;
+    match msg {
+        QueryMsg::Curve {} => to_binary(&CurveResponse {
+            token: state.token.to_string(),
+            denom: state.denom,
+            slope_numerator: state.slope_numerator,
+            slope_denominator: state.slope_denominator,
+            supply: totals.supply,
+            reserve: totals.reserve,
+        }),
+        QueryMsg::SimulateBuy { amount } => {
+            let output = buy_output(&state, &totals, amount)?;
+            to_binary(&SimulateResponse { output })
+        }
+        QueryMsg::SimulateSell { amount } => {
+            let output = sell_output(&state, &totals, amount)?;
+            to_binary(&SimulateResponse { output })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::isqrt;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, Coin, OwnedDeps};
+
+    const DENOM: &str = "ucosm";
+
+    /// `price = supply * numerator / denominator`
+    fn setup(numerator: u64, denominator: u64) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            denom: DENOM.to_string(),
+            slope_numerator: numerator,
+            slope_denominator: denominator,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(
+        deps: DepsMut,
+        sender: &str,
+        funds: &[Coin],
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, funds), msg)
+    }
+
+    /// Returns the minted tokens
+    fn buy(deps: DepsMut, buyer: &str, paid: u128) -> Result<u128, ContractError> {
+        let msg = ExecuteMsg::Buy { min_out: None };
+        let res = run(deps, buyer, &coins(paid, DENOM), msg)?;
+        Ok(res.attributes[3].value.parse().unwrap())
+    }
+
+    /// Returns the paid out coins
+    fn sell(deps: DepsMut, seller: &str, amount: u128) -> Result<u128, ContractError> {
+        let msg = ExecuteMsg::Sell {
+            amount: Uint128::from(amount),
+            min_out: None,
+        };
+        let res = run(deps, seller, &[], msg)?;
+        Ok(res.attributes[3].value.parse().unwrap())
+    }
+
+    fn curve_of(deps: Deps) -> Curve {
+        curve_read(deps.storage).load().unwrap()
+    }
+
+    fn assert_solvent(deps: Deps) {
+        let state = config_read(deps.storage).load().unwrap();
+        let totals = curve_of(deps);
+        assert!(totals.reserve >= state.reserve_for(totals.supply).unwrap());
+    }
+
+    #[test]
+    fn isqrt_works() {
+        for n in 0..10_000u128 {
+            let root = isqrt(n);
+            assert!(root * root <= n && (root + 1) * (root + 1) > n);
+        }
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(
+            isqrt((u64::MAX as u128) * (u64::MAX as u128)),
+            u64::MAX as u128
+        );
+    }
+
+    #[test]
+    fn supply_for_inverts_reserve_for() {
+        for &(numerator, denominator) in &[(1u64, 1u64), (3, 7), (1, 1_000_000), (500, 3)] {
+            let state = Config {
+                token: cosmwasm_std::Addr::unchecked("token"),
+                denom: DENOM.to_string(),
+                slope_numerator: numerator,
+                slope_denominator: denominator,
+            };
+            for reserve in (0..5_000u128).chain(vec![10u128.pow(12), 10u128.pow(18)]) {
+                let reserve = Uint128::from(reserve);
+                let supply = state.supply_for(reserve).unwrap();
+                assert!(state.reserve_for(supply).unwrap() <= reserve);
+                let next = supply.checked_add(Uint128::from(1u128)).unwrap();
+                assert!(state.reserve_for(next).unwrap() > reserve);
+            }
+        }
+    }
+
+    #[test]
+    fn validates_slope() {
+        let mut deps = mock_dependencies(&[]);
+        for &(numerator, denominator) in &[(0u64, 1u64), (1, 0)] {
+            let msg = InstantiateMsg {
+                token: "token".to_string(),
+                denom: DENOM.to_string(),
+                slope_numerator: numerator,
+                slope_denominator: denominator,
+            };
+            let info = mock_info("creator", &[]);
+            match instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err() {
+                ContractError::InvalidSlope {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn buys_and_sells_along_the_curve() {
+        // the reserve for a supply s is s^2 / 2
+        let mut deps = setup(1, 1);
+
+        // 5000 pays for 100 tokens exactly
+        let query_msg = QueryMsg::SimulateBuy {
+            amount: Uint128::from(5000u128),
+        };
+        let simulated: SimulateResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(simulated.output.u128(), 100);
+        let msg = ExecuteMsg::Buy {
+            min_out: Some(Uint128::from(101u128)),
+        };
+        match run(deps.as_mut(), "alice", &coins(5000, DENOM), msg).unwrap_err() {
+            ContractError::TooLittleReceived { amount, minimum } => {
+                assert_eq!(amount.u128(), 100);
+                assert_eq!(minimum.u128(), 101);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::Buy {
+            min_out: Some(Uint128::from(100u128)),
+        };
+        let res = run(deps.as_mut(), "alice", &coins(5000, DENOM), msg).unwrap();
+        let mint = cw_erc20::ExecuteMsg::mint("alice", 100u128);
+        assert_eq!(res.messages, vec![mint.into_cosmos_msg("token").unwrap()]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "buy"),
+                attr("buyer", "alice"),
+                attr("paid", "5000"),
+                attr("minted", "100"),
+                attr("supply", "100"),
+            ]
+        );
+
+        // the price went up: 15000 buys the next 100, 14999 only 99 and the rest stays in the reserve
+        assert_eq!(buy(deps.as_mut(), "bob", 14_999).unwrap(), 99);
+        assert_eq!(
+            curve_of(deps.as_ref()),
+            Curve {
+                supply: Uint128::from(199u128),
+                reserve: Uint128::from(19_999u128),
+            }
+        );
+
+        // selling pays out the area under the curve: (199^2 - 99^2) / 2 = 14900
+        let msg = ExecuteMsg::Sell {
+            amount: Uint128::from(100u128),
+            min_out: Some(Uint128::from(14_901u128)),
+        };
+        match run(deps.as_mut(), "alice", &[], msg).unwrap_err() {
+            ContractError::TooLittleReceived { amount, .. } => assert_eq!(amount.u128(), 14_900),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::Sell {
+            amount: Uint128::from(100u128),
+            min_out: Some(Uint128::from(14_900u128)),
+        };
+        let res = run(deps.as_mut(), "alice", &[], msg).unwrap();
+        let pull = cw_erc20::ExecuteMsg::transfer_from("alice", "cosmos2contract", 100u128);
+        let burn = cw_erc20::ExecuteMsg::burn(100u128);
+        assert_eq!(
+            res.messages,
+            vec![
+                pull.into_cosmos_msg("token").unwrap(),
+                burn.into_cosmos_msg("token").unwrap(),
+                BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: coins(14_900, DENOM),
+                }
+                .into(),
+            ]
+        );
+        assert_eq!(
+            curve_of(deps.as_ref()),
+            Curve {
+                supply: Uint128::from(99u128),
+                reserve: Uint128::from(5099u128),
+            }
+        );
+        assert_solvent(deps.as_ref());
+
+        match sell(deps.as_mut(), "bob", 100).unwrap_err() {
+            ContractError::InsufficientSupply { supply, required } => {
+                assert_eq!(supply.u128(), 99);
+                assert_eq!(required.u128(), 100);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match sell(deps.as_mut(), "bob", 0).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_bad_funds_and_dust() {
+        let mut deps = setup(1, 1);
+        for funds in &[vec![], coins(10, "uatom"), coins(0, DENOM)] {
+            let msg = ExecuteMsg::Buy { min_out: None };
+            match run(deps.as_mut(), "alice", funds, msg).unwrap_err() {
+                ContractError::InvalidFunds { denom } => assert_eq!(denom, DENOM),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        assert_eq!(buy(deps.as_mut(), "alice", 5000).unwrap(), 100);
+        // the reserve for 101 tokens is 5100
+        match buy(deps.as_mut(), "alice", 99).unwrap_err() {
+            ContractError::ZeroOutput {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // on a flat curve the first tokens are worth nothing
+        let mut deps = setup(1, 1000);
+        assert_eq!(buy(deps.as_mut(), "alice", 1).unwrap(), 63);
+        match sell(deps.as_mut(), "alice", 1).unwrap_err() {
+            ContractError::ZeroOutput {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn round_trips_never_extract_more_than_paid() {
+        let sizes = [
+            1u128,
+            2,
+            3,
+            7,
+            99,
+            100,
+            101,
+            999,
+            12_345,
+            1_000_000,
+            987_654_321,
+        ];
+        for &(numerator, denominator) in &[(1u64, 1u64), (3, 7), (1, 1_000), (1_000, 1)] {
+            for &preload in &[0u128, 1, 77_777] {
+                for &paid in &sizes {
+                    let mut deps = setup(numerator, denominator);
+                    if preload > 0 {
+                        let _ = buy(deps.as_mut(), "whale", preload);
+                    }
+                    let reserve_before = curve_of(deps.as_ref()).reserve;
+
+                    let minted = match buy(deps.as_mut(), "trader", paid) {
+                        Ok(minted) => minted,
+                        Err(ContractError::ZeroOutput {}) => continue,
+                        Err(e) => panic!("unexpected error: {:?}", e),
+                    };
+                    assert_solvent(deps.as_ref());
+
+                    // sold back at once or in pieces
+                    let mut received = 0;
+                    let mut left = minted;
+                    let piece = (minted / 3).max(1);
+                    while left > 0 {
+                        let amount = piece.min(left);
+                        match sell(deps.as_mut(), "trader", amount) {
+                            Ok(payout) => received += payout,
+                            Err(ContractError::ZeroOutput {}) => {}
+                            Err(e) => panic!("unexpected error: {:?}", e),
+                        }
+                        left -= amount;
+                        assert_solvent(deps.as_ref());
+                    }
+                    assert!(
+                        received <= paid,
+                        "paid {} and received {} (slope {}/{}, preload {})",
+                        paid,
+                        received,
+                        numerator,
+                        denominator,
+                        preload
+                    );
+                    // whatever the trader left behind still backs the earlier buyers
+                    assert!(curve_of(deps.as_ref()).reserve >= reserve_before);
+                }
+            }
+        }
+    }
+}
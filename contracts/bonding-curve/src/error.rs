@@ -0,0 +1,29 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Slope numerator and denominator must not be zero")]
+    InvalidSlope {},
+
+    #[error("Expected a single non-zero {denom} coin")]
+    InvalidFunds { denom: String },
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Amount too small to receive anything")]
+    ZeroOutput {},
+
+    #[error("Received {amount}, less than the minimum of {minimum}")]
+    TooLittleReceived { amount: Uint128, minimum: Uint128 },
+
+    #[error("Cannot sell {required} tokens, only {supply} were bought from the curve")]
+    InsufficientSupply { supply: Uint128, required: Uint128 },
+}
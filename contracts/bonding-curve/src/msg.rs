@@ -0,0 +1,58 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token to sell. This contract must be its minter, and the curve only backs
+    /// the tokens it minted itself.
+    pub token: String,
+    /// The native coin tokens are paid with
+    pub denom: String,
+    /// The price of one token base unit is `supply * slope_numerator / slope_denominator`
+    pub slope_numerator: u64,
+    pub slope_denominator: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Mints tokens for the sent native coins at the current price
+    Buy { min_out: Option<Uint128> },
+    /// Burns `amount` tokens of the sender and pays out native coins from the reserve. The
+    /// tokens are pulled with `TransferFrom`, so the curve needs an allowance first.
+    Sell {
+        amount: Uint128,
+        min_out: Option<Uint128>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Curve {},
+    /// Returns the tokens minted for `amount` native coins right now
+    SimulateBuy {
+        amount: Uint128,
+    },
+    /// Returns the native coins paid out for `amount` tokens right now
+    SimulateSell {
+        amount: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurveResponse {
+    pub token: String,
+    pub denom: String,
+    pub slope_numerator: u64,
+    pub slope_denominator: u64,
+    /// Tokens minted by the curve and not sold back yet
+    pub supply: Uint128,
+    pub reserve: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateResponse {
+    pub output: Uint128,
+}
@@ -0,0 +1,85 @@
+use cosmwasm_std::{Addr, OverflowError, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static CURVE_KEY: &[u8] = b"curve";
+
+/// The price of one token base unit rises linearly with the supply,
+/// `price = supply * slope_numerator / slope_denominator` native coins.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The erc20 token sold, which has this contract as its minter
+    pub token: Addr,
+    /// The native coin tokens are paid with
+    pub denom: String,
+    pub slope_numerator: u64,
+    pub slope_denominator: u64,
+}
+
+impl Config {
+    /// The native coins needed to back `supply` tokens: the integral of the price from zero,
+    /// `supply^2 * slope_numerator / (2 * slope_denominator)`, rounded down.
+    pub fn reserve_for(&self, supply: Uint128) -> Result<Uint128, OverflowError> {
+        let numerator = supply
+            .checked_mul(supply)?
+            .checked_mul(Uint128::from(self.slope_numerator))?;
+        Ok(Uint128::from(numerator.u128() / self.twice_denominator()))
+    }
+
+    /// The largest supply backed by `reserve`, the inverse of `reserve_for`. Rounds down, so
+    /// `reserve_for(supply_for(reserve)) <= reserve`.
+    pub fn supply_for(&self, reserve: Uint128) -> Result<Uint128, OverflowError> {
+        // reserve_for(s) <= reserve  <=>  s^2 * num < (reserve + 1) * 2 * den
+        let bound = reserve
+            .checked_add(Uint128::from(1u128))?
+            .checked_mul(Uint128::from(self.twice_denominator()))?;
+        let max_square = (bound.u128() - 1) / u128::from(self.slope_numerator);
+        Ok(Uint128::from(isqrt(max_square)))
+    }
+
+    fn twice_denominator(&self) -> u128 {
+        2 * u128::from(self.slope_denominator)
+    }
+}
+
+/// Tokens minted by the curve and the coins paid for them. The reserve never drops below
+/// `reserve_for(supply)`, so every outstanding token can be sold back. Buys round in favour
+/// of the reserve, which may therefore hold slightly more.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Curve {
+    pub supply: Uint128,
+    pub reserve: Uint128,
+}
+
+/// The largest `x` with `x * x <= n`
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above, `(n + 1) / 2` without overflowing
+    let mut x = n;
+    let mut y = n / 2 + n % 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn curve(storage: &mut dyn Storage) -> Singleton<'_, Curve> {
+    singleton(storage, CURVE_KEY)
+}
+
+pub fn curve_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Curve> {
+    singleton_read(storage, CURVE_KEY)
+}
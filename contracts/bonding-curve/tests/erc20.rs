@@ -0,0 +1,121 @@
+//! Runs the curve against a real erc20 contract which has the curve as its minter, on a test
+//! chain that keeps the native coins in its bank and reverts failing messages like a real
+//! chain.
+
+use cosmwasm_std::{coins, Uint128};
+use cw_bonding_curve::contract::{execute, instantiate, query};
+use cw_bonding_curve::msg::{CurveResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw_erc20::BalanceResponse;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const CURVE: &str = "curve";
+const DENOM: &str = "ucosm";
+
+/// A curve with `price = supply / 1000`. alice and bob hold coins, nobody holds tokens.
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    let msg = cw_erc20::InstantiateMsg {
+        minter: Some(CURVE.to_string()),
+        ..erc20_msg("CURVE", &[])
+    };
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let curve = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        denom: DENOM.to_string(),
+        slope_numerator: 1,
+        slope_denominator: 1000,
+    };
+    chain
+        .instantiate(curve, CURVE, "creator", &msg, &[])
+        .unwrap();
+
+    chain.set_balance("alice", &coins(1_000_000, DENOM));
+    chain.set_balance("bob", &coins(1_000_000, DENOM));
+    chain
+}
+
+fn buy(chain: &mut Chain, buyer: &str, native: u128) {
+    let msg = ExecuteMsg::Buy { min_out: None };
+    chain
+        .execute(CURVE, buyer, &msg, &coins(native, DENOM))
+        .unwrap();
+}
+
+fn sell(chain: &mut Chain, seller: &str, amount: u128) {
+    let msg = cw_erc20::ExecuteMsg::approve(CURVE, amount);
+    chain.execute(TOKEN, seller, &msg, &[]).unwrap();
+    let msg = ExecuteMsg::Sell {
+        amount: Uint128::from(amount),
+        min_out: None,
+    };
+    chain.execute(CURVE, seller, &msg, &[]).unwrap();
+}
+
+fn tokens(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn curve(chain: &Chain) -> CurveResponse {
+    chain.query(CURVE, &QueryMsg::Curve {}).unwrap()
+}
+
+#[test]
+fn buy_and_sell_cycle() {
+    let mut chain = setup();
+    // the reserve for a supply s is s^2 / 2000
+    buy(&mut chain, "alice", 500_000);
+    assert_eq!(tokens(&chain, "alice"), 31_622);
+    buy(&mut chain, "bob", 500_000);
+    // the remainder of alice's payment stays in the reserve without buying bob anything, so
+    // the supply ends below sqrt(2000 * 1000000) = 44721.4
+    assert_eq!(tokens(&chain, "bob"), 13_098);
+    let res = curve(&chain);
+    assert_eq!(res.supply.u128(), 44_720);
+    assert_eq!(res.reserve.u128(), 1_000_000);
+    assert_eq!(chain.balance(CURVE, DENOM), 1_000_000);
+
+    // selling back never returns more than was paid
+    sell(&mut chain, "bob", 13_098);
+    assert_eq!(tokens(&chain, "bob"), 0);
+    assert_eq!(tokens(&chain, CURVE), 0);
+    assert_eq!(chain.balance("bob", DENOM), 999_964);
+    sell(&mut chain, "alice", 31_622);
+    assert_eq!(chain.balance("alice", DENOM), 999_975);
+
+    // what is left over is the rounding in favour of the reserve
+    let res = curve(&chain);
+    assert_eq!(res.supply.u128(), 0);
+    assert_eq!(res.reserve.u128(), 61);
+    assert_eq!(chain.balance(CURVE, DENOM), 61);
+}
+
+#[test]
+fn sell_needs_allowance() {
+    let mut chain = setup();
+    buy(&mut chain, "alice", 10_000);
+    let before = curve(&chain);
+    let msg = ExecuteMsg::Sell {
+        amount: Uint128::from(100u128),
+        min_out: None,
+    };
+    let err = chain.execute(CURVE, "alice", &msg, &[]).unwrap_err();
+    assert!(err.contains("allowance"), "unexpected error: {}", err);
+    // neither the curve nor the payout moved
+    assert_eq!(curve(&chain), before);
+    assert_eq!(chain.balance("alice", DENOM), 990_000);
+    assert_eq!(chain.balance(CURVE, DENOM), 10_000);
+}
+
+#[test]
+fn only_the_curve_mints() {
+    let mut chain = setup();
+    let msg = cw_erc20::ExecuteMsg::mint("alice", 100u128);
+    let err = chain.execute(TOKEN, "alice", &msg, &[]).unwrap_err();
+    assert_eq!(err, "Unauthorized");
+    assert_eq!(tokens(&chain, "alice"), 0);
+}
@@ -0,0 +1,451 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw_erc20::BalanceResponse;
+
+use crate::error::ContractError;
+use crate::msg::{AccountResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{accounts, accounts_read, config, config_read, Account, Config, Position};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let lp_contracts = msg
+        .lp_contracts
+        .iter()
+        .map(|lp_contract| deps.api.addr_validate(lp_contract))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    let state = Config {
+        reward_token: deps.api.addr_validate(&msg.reward_token)?,
+        schedule_id: msg.schedule_id,
+        reward_per_block: msg.reward_per_block,
+        lp_contracts,
+    };
+    config(deps.storage).save(&state)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::RegisterLp {
+            lp_contract,
+            lp_amount,
+        } => try_register_lp(deps, env, info, lp_contract, lp_amount),
+        ExecuteMsg::RemoveLp { lp_contract } => try_remove_lp(deps, env, info, lp_contract),
+        ExecuteMsg::Claim {} => try_claim(deps, env, info),
+    }
+}
+
+/// Register LP
+///
+/// Checks that the signer holds `lp_amount` LP tokens and starts rewarding them with their
+/// share of the LP token's current total supply.
+///
+/// @param lp_contract the LP token, which must be one of the configured pairs
+/// @param lp_amount the registered LP tokens
+fn try_register_lp(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_contract: String,
+    lp_amount: Uint128,
+) -> Result<Response, ContractError> {
+    if lp_amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let lp_addr = deps.api.addr_validate(&lp_contract)?;
+    if !state.lp_contracts.contains(&lp_addr) {
+        return Err(ContractError::UnknownLpContract { lp_contract });
+    }
+
+    let balance: BalanceResponse = deps
+        .querier
+        .query_wasm_smart(&lp_addr, &cw_erc20::QueryMsg::balance(info.sender.as_str()))?;
+    if balance.balance < lp_amount {
+        return Err(ContractError::InsufficientLpBalance {
+            balance: balance.balance,
+            required: lp_amount,
+        });
+    }
+    let lp_supply = query_lp_supply(deps.as_ref(), &lp_addr)?;
+
+    let mut account = load_account(deps.as_ref(), &state, &info.sender, env.block.height)?;
+    account
+        .positions
+        .retain(|position| position.lp_contract != lp_addr);
+    account.positions.push(Position {
+        lp_contract: lp_addr,
+        lp_amount,
+        lp_supply,
+    });
+    accounts(deps.storage).save(info.sender.as_str().as_bytes(), &account)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "register_lp"),
+            attr("provider", info.sender),
+            attr("lp_contract", lp_contract),
+            attr("lp_amount", lp_amount),
+            attr("lp_supply", lp_supply),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Remove LP
+///
+/// Stops rewarding a position. Rewards accrued until now stay claimable.
+///
+/// @param lp_contract the LP token of the position
+fn try_remove_lp(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_contract: String,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let mut account = load_account(deps.as_ref(), &state, &info.sender, env.block.height)?;
+    let count = account.positions.len();
+    account
+        .positions
+        .retain(|position| position.lp_contract.as_str() != lp_contract);
+    if account.positions.len() == count {
+        return Err(ContractError::PositionNotFound { lp_contract });
+    }
+    accounts(deps.storage).save(info.sender.as_str().as_bytes(), &account)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "remove_lp"),
+            attr("provider", info.sender),
+            attr("lp_contract", lp_contract),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Claim
+///
+/// Executes the reward token's mint schedule, which mints the rewards accrued since its
+/// last execution to this contract, and sends the signer's pending rewards to them.
+fn try_claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let mut account = load_account(deps.as_ref(), &state, &info.sender, env.block.height)?;
+    let amount = account.pending;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    account.pending = Uint128::zero();
+    accounts(deps.storage).save(info.sender.as_str().as_bytes(), &account)?;
+
+    let mint = cw_erc20::ExecuteMsg::execute_mint_schedule(state.schedule_id);
+    let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![
+            execute_msg(&state.reward_token, to_binary(&mint)?),
+            execute_msg(&state.reward_token, to_binary(&transfer)?),
+        ],
+        attributes: vec![
+            attr("action", "claim"),
+            attr("provider", info.sender),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn execute_msg(contract: &Addr, msg: Binary) -> CosmosMsg {
+    WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg,
+        send: vec![],
+    }
+    .into()
+}
+
+/// Reads the total supply of an erc20 LP token from its storage
+fn query_lp_supply(deps: Deps, lp_contract: &Addr) -> Result<Uint128, ContractError> {
+    let supply = match deps
+        .querier
+        .query_wasm_raw(lp_contract, cw_erc20::total_supply_key())?
+    {
+        Some(data) => cw_erc20::decode_amount(&data).map_err(|_| ContractError::NoLpSupply {
+            lp_contract: lp_contract.to_string(),
+        })?,
+        None => 0,
+    };
+    if supply == 0 {
+        return Err(ContractError::NoLpSupply {
+            lp_contract: lp_contract.to_string(),
+        });
+    }
+    Ok(Uint128::from(supply))
+}
+
+/// Loads the account of `provider` with all rewards up to `height` added to `pending`
+fn load_account(
+    deps: Deps,
+    state: &Config,
+    provider: &Addr,
+    height: u64,
+) -> Result<Account, ContractError> {
+    let mut account = accounts_read(deps.storage)
+        .may_load(provider.as_str().as_bytes())?
+        .unwrap_or_default();
+    if height > account.last_height {
+        let blocks = Uint128::from(height - account.last_height);
+        for position in &account.positions {
+            let rewards = state
+                .reward_per_block
+                .checked_mul(blocks)?
+                .multiply_ratio(position.lp_amount, position.lp_supply);
+            account.pending = account.pending.checked_add(rewards)?;
+        }
+        account.last_height = height;
+    }
+    Ok(account)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Account { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let state = config_read(deps.storage).load()?;
+            let account = load_account(deps, &state, &address, env.block.height)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_binary(&AccountResponse {
+                positions: account.positions,
+                pending: account.pending,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+    use cosmwasm_std::{
+        from_binary, from_slice, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+        QueryRequest, SystemError, SystemResult, WasmQuery,
+    };
+    use std::collections::HashMap;
+
+    /// Answers queries for the LP tokens "lp1" and "lp2"
+    #[derive(Default)]
+    struct LpQuerier {
+        balances: HashMap<(String, String), u128>,
+        supplies: HashMap<String, u128>,
+    }
+
+    impl Querier for LpQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            let res = match request {
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                    let address = match from_binary(&msg).unwrap() {
+                        cw_erc20::QueryMsg::Balance { address } => address,
+                        query => panic!("unexpected query: {:?}", query),
+                    };
+                    let balance = self.balances.get(&(contract_addr, address));
+                    to_binary(&BalanceResponse {
+                        balance: Uint128::from(balance.copied().unwrap_or_default()),
+                    })
+                    .unwrap()
+                }
+                QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
+                    assert_eq!(key.as_slice(), cw_erc20::total_supply_key().as_slice());
+                    let supply = self.supplies.get(&contract_addr);
+                    // raw queries return the stored bytes, empty for missing keys
+                    supply
+                        .map(|supply| Binary::from(supply.to_be_bytes()))
+                        .unwrap_or_default()
+                }
+                _ => {
+                    return SystemResult::Err(SystemError::UnsupportedRequest {
+                        kind: "non-wasm".to_string(),
+                    })
+                }
+            };
+            SystemResult::Ok(ContractResult::Ok(res))
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, LpQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: LpQuerier::default(),
+        };
+        let balances = &mut deps.querier.balances;
+        balances.insert(("lp1".to_string(), "alice".to_string()), 500);
+        balances.insert(("lp1".to_string(), "bob".to_string()), 250);
+        balances.insert(("lp2".to_string(), "alice".to_string()), 10);
+        deps.querier.supplies.insert("lp1".to_string(), 1000);
+        deps.querier.supplies.insert("lp2".to_string(), 40);
+
+        let msg = InstantiateMsg {
+            reward_token: "token".to_string(),
+            schedule_id: 3,
+            reward_per_block: Uint128::from(100u128),
+            lp_contracts: vec!["lp1".to_string(), "lp2".to_string()],
+        };
+        instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn register(lp_contract: &str, lp_amount: u128) -> ExecuteMsg {
+        ExecuteMsg::RegisterLp {
+            lp_contract: lp_contract.to_string(),
+            lp_amount: Uint128::from(lp_amount),
+        }
+    }
+
+    fn pending(deps: Deps, height: u64, address: &str) -> u128 {
+        let msg = QueryMsg::Account {
+            address: address.to_string(),
+        };
+        let res: AccountResponse = from_binary(&query(deps, env_at(height), msg).unwrap()).unwrap();
+        res.pending.u128()
+    }
+
+    #[test]
+    fn register_checks_lp_tokens() {
+        let mut deps = setup();
+        match run(deps.as_mut(), 100, "alice", register("lp3", 1)).unwrap_err() {
+            ContractError::UnknownLpContract { lp_contract } => assert_eq!(lp_contract, "lp3"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 100, "alice", register("lp1", 0)).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 100, "alice", register("lp1", 501)).unwrap_err() {
+            ContractError::InsufficientLpBalance { balance, required } => {
+                assert_eq!(balance.u128(), 500);
+                assert_eq!(required.u128(), 501);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = run(deps.as_mut(), 100, "alice", register("lp1", 500)).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "register_lp"),
+                attr("provider", "alice"),
+                attr("lp_contract", "lp1"),
+                attr("lp_amount", "500"),
+                attr("lp_supply", "1000"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rewards_are_proportional_to_lp_share() {
+        let mut deps = setup();
+        // alice holds 50% and bob 25% of lp1, alice also 25% of lp2
+        run(deps.as_mut(), 100, "alice", register("lp1", 500)).unwrap();
+        run(deps.as_mut(), 100, "bob", register("lp1", 250)).unwrap();
+        assert_eq!(pending(deps.as_ref(), 110, "alice"), 500);
+        assert_eq!(pending(deps.as_ref(), 110, "bob"), 250);
+
+        run(deps.as_mut(), 110, "alice", register("lp2", 10)).unwrap();
+        assert_eq!(pending(deps.as_ref(), 120, "alice"), 500 + 500 + 250);
+
+        // registering again replaces the position
+        run(deps.as_mut(), 120, "bob", register("lp1", 100)).unwrap();
+        assert_eq!(pending(deps.as_ref(), 130, "bob"), 500 + 100);
+        let msg = QueryMsg::Account {
+            address: "bob".to_string(),
+        };
+        let res: AccountResponse =
+            from_binary(&query(deps.as_ref(), env_at(130), msg).unwrap()).unwrap();
+        assert_eq!(
+            res.positions,
+            vec![Position {
+                lp_contract: Addr::unchecked("lp1"),
+                lp_amount: Uint128::from(100u128),
+                lp_supply: Uint128::from(1000u128),
+            }]
+        );
+    }
+
+    #[test]
+    fn removing_position_stops_accrual() {
+        let mut deps = setup();
+        run(deps.as_mut(), 100, "alice", register("lp1", 500)).unwrap();
+
+        let remove = ExecuteMsg::RemoveLp {
+            lp_contract: "lp1".to_string(),
+        };
+        run(deps.as_mut(), 104, "alice", remove.clone()).unwrap();
+        assert_eq!(pending(deps.as_ref(), 104, "alice"), 200);
+        assert_eq!(pending(deps.as_ref(), 200, "alice"), 200);
+
+        match run(deps.as_mut(), 105, "alice", remove).unwrap_err() {
+            ContractError::PositionNotFound { lp_contract } => assert_eq!(lp_contract, "lp1"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn claim_mints_and_sends_rewards() {
+        let mut deps = setup();
+        match run(deps.as_mut(), 100, "alice", ExecuteMsg::Claim {}).unwrap_err() {
+            ContractError::NothingToClaim {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        run(deps.as_mut(), 100, "alice", register("lp1", 500)).unwrap();
+        let res = run(deps.as_mut(), 103, "alice", ExecuteMsg::Claim {}).unwrap();
+        let mint = cw_erc20::ExecuteMsg::execute_mint_schedule(3);
+        let transfer = cw_erc20::ExecuteMsg::transfer("alice", 150u128);
+        assert_eq!(
+            res.messages,
+            vec![
+                execute_msg(&Addr::unchecked("token"), to_binary(&mint).unwrap()),
+                execute_msg(&Addr::unchecked("token"), to_binary(&transfer).unwrap()),
+            ]
+        );
+        assert_eq!(pending(deps.as_ref(), 103, "alice"), 0);
+        assert_eq!(pending(deps.as_ref(), 105, "alice"), 100);
+    }
+}
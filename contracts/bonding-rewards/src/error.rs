@@ -0,0 +1,29 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("{lp_contract} is not a rewarded LP token")]
+    UnknownLpContract { lp_contract: String },
+
+    #[error("Insufficient LP tokens (balance {balance}, required={required})")]
+    InsufficientLpBalance { balance: Uint128, required: Uint128 },
+
+    #[error("LP token {lp_contract} has no supply")]
+    NoLpSupply { lp_contract: String },
+
+    #[error("No position registered for {lp_contract}")]
+    PositionNotFound { lp_contract: String },
+
+    #[error("No rewards to claim")]
+    NothingToClaim {},
+}
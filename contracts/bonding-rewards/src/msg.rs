@@ -0,0 +1,48 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::Position;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub reward_token: String,
+    /// Mint schedule of the reward token with this contract as recipient. Its rate should
+    /// be `reward_per_block` times the number of LP tokens.
+    pub schedule_id: u64,
+    pub reward_per_block: Uint128,
+    /// LP tokens of the pairs involving the reward token
+    pub lp_contracts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Registers `lp_amount` LP tokens of the signer, replacing an earlier registration for
+    /// the same LP token
+    RegisterLp {
+        lp_contract: String,
+        lp_amount: Uint128,
+    },
+    /// Stops the rewards of a registered position
+    RemoveLp { lp_contract: String },
+    /// Mints and sends all accrued rewards to the signer
+    Claim {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Positions and claimable rewards of `address`
+    Account {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AccountResponse {
+    pub positions: Vec<Position>,
+    /// Rewards accrued up to the current block
+    pub pending: Uint128,
+}
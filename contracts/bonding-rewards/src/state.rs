@@ -0,0 +1,55 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static ACCOUNTS_KEY: &[u8] = b"accounts";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub reward_token: Addr,
+    /// Mint schedule of the reward token paying this contract
+    pub schedule_id: u64,
+    /// Rewards per block shared by all providers of one LP token
+    pub reward_per_block: Uint128,
+    pub lp_contracts: Vec<Addr>,
+}
+
+/// LP tokens registered by a provider
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Position {
+    pub lp_contract: Addr,
+    pub lp_amount: Uint128,
+    /// Total supply of the LP token when the position was registered
+    pub lp_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
+pub struct Account {
+    pub positions: Vec<Position>,
+    /// Rewards accrued but not claimed yet
+    pub pending: Uint128,
+    /// Height up to which rewards were added to `pending`
+    pub last_height: u64,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Accounts by provider address
+pub fn accounts(storage: &mut dyn Storage) -> Bucket<'_, Account> {
+    bucket(storage, ACCOUNTS_KEY)
+}
+
+pub fn accounts_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Account> {
+    bucket_read(storage, ACCOUNTS_KEY)
+}
@@ -0,0 +1,21 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use cw_buyback::msg::{DexExecuteMsg, ExecuteMsg, InstantiateMsg, QueryMsg, TotalsResponse};
+use cw_buyback::state::Config;
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(DexExecuteMsg), &out_dir);
+    export_schema(&schema_for!(TotalsResponse), &out_dir);
+    export_schema(&schema_for!(Config), &out_dir);
+}
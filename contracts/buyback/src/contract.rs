@@ -0,0 +1,307 @@
+use cosmwasm_std::{
+    attr, coins, entry_point, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, Uint128, WasmMsg,
+};
+use cw_erc20::ReceiveMsg;
+
+use crate::error::ContractError;
+use crate::msg::{DexExecuteMsg, ExecuteMsg, InstantiateMsg, QueryMsg, TotalsResponse};
+use crate::state::{
+    config, config_read, total_burned, total_burned_read, total_spent, total_spent_read, Config,
+};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        dex: deps.api.addr_validate(&msg.dex)?,
+        spend_denom: msg.spend_denom,
+        max_spend_per_execution: msg.max_spend_per_execution,
+        interval: msg.interval,
+        last_execution: None,
+    };
+    config(deps.storage).save(&state)?;
+    total_spent(deps.storage).save(&Uint128::zero())?;
+    total_burned(deps.storage).save(&Uint128::zero())?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Execute {} => try_execute(deps, env),
+        ExecuteMsg::Receive(msg) => try_receive(deps, info, msg),
+    }
+}
+
+fn try_execute(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if let Some(last_execution) = state.last_execution {
+        let next_execution = last_execution + state.interval;
+        if env.block.height < next_execution {
+            return Err(ContractError::TooEarly { next_execution });
+        }
+    }
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &state.spend_denom)?;
+    let spend = balance.amount.min(state.max_spend_per_execution);
+    if spend.is_zero() {
+        return Err(ContractError::NoFunds {
+            denom: state.spend_denom,
+        });
+    }
+
+    state.last_execution = Some(env.block.height);
+    config(deps.storage).save(&state)?;
+    total_spent(deps.storage)
+        .update(|spent| -> Result<_, ContractError> { Ok(spent.checked_add(spend)?) })?;
+
+    let swap = DexExecuteMsg::Swap {
+        ask_token: state.token.to_string(),
+    };
+    let res = Response {
+        submessages: vec![],
+        messages: vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: state.dex.to_string(),
+            msg: to_binary(&swap)?,
+            send: coins(spend.u128(), &state.spend_denom),
+        })],
+        attributes: vec![attr("action", "buyback"), attr("spent", spend)],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    // only tokens of the bought contract can be burned
+    if info.sender != state.token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    total_burned(deps.storage)
+        .update(|burned| -> Result<_, ContractError> { Ok(burned.checked_add(msg.amount)?) })?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: state.token.to_string(),
+            msg: cw_erc20::ExecuteMsg::burn(msg.amount).to_binary()?,
+            send: vec![],
+        })],
+        attributes: vec![attr("action", "burn"), attr("amount", msg.amount)],
+        data: None,
+    };
+    Ok(res)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Totals {} => to_binary(&TotalsResponse {
+            total_spent: total_spent_read(deps.storage).load()?,
+            total_burned: total_burned_read(deps.storage).load()?,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, from_binary, from_slice, Coin};
+
+    fn init_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            token: "token".to_string(),
+            dex: "dex".to_string(),
+            spend_denom: "ucosm".to_string(),
+            max_spend_per_execution: Uint128::from(100u128),
+            interval: 10,
+        }
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn buyback(deps: DepsMut, height: u64) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            env_at(height),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Execute {},
+        )
+    }
+
+    fn swap_funds(res: &Response) -> Vec<Coin> {
+        match &res.messages[0] {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                send,
+            }) => {
+                assert_eq!(contract_addr, "dex");
+                let swap: DexExecuteMsg = from_slice(msg).unwrap();
+                assert_eq!(
+                    swap,
+                    DexExecuteMsg::Swap {
+                        ask_token: "token".to_string()
+                    }
+                );
+                send.clone()
+            }
+            _ => panic!("expected wasm execute message"),
+        }
+    }
+
+    fn totals(deps: Deps) -> TotalsResponse {
+        from_binary(&query(deps, mock_env(), QueryMsg::Totals {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn execute_swaps_on_dex() {
+        let mut deps = mock_dependencies(&[coin(250, "ucosm")]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+
+        let res = buyback(deps.as_mut(), 100).unwrap();
+        assert_eq!(swap_funds(&res), coins(100, "ucosm"));
+        assert_eq!(totals(deps.as_ref()).total_spent, Uint128::from(100u128));
+    }
+
+    #[test]
+    fn execute_spends_remaining_balance() {
+        let mut deps = mock_dependencies(&[coin(30, "ucosm"), coin(1000, "uatom")]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+
+        let res = buyback(deps.as_mut(), 100).unwrap();
+        assert_eq!(swap_funds(&res), coins(30, "ucosm"));
+    }
+
+    #[test]
+    fn execute_requires_funds() {
+        let mut deps = mock_dependencies(&[coin(1000, "uatom")]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+
+        match buyback(deps.as_mut(), 100).unwrap_err() {
+            ContractError::NoFunds { denom } => assert_eq!(denom, "ucosm"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn execute_respects_interval() {
+        let mut deps = mock_dependencies(&[coin(1000, "ucosm")]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+
+        buyback(deps.as_mut(), 100).unwrap();
+        match buyback(deps.as_mut(), 109).unwrap_err() {
+            ContractError::TooEarly {
+                next_execution: 110,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        buyback(deps.as_mut(), 110).unwrap();
+        assert_eq!(totals(deps.as_ref()).total_spent, Uint128::from(200u128));
+    }
+
+    #[test]
+    fn receive_burns_tokens() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "dex".to_string(),
+            amount: Uint128::from(42u128),
+            msg: None,
+        });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("token", &[]), msg).unwrap();
+        match &res.messages[0] {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                send,
+            }) => {
+                assert_eq!(contract_addr, "token");
+                let burn: cw_erc20::ExecuteMsg = from_slice(msg).unwrap();
+                assert_eq!(burn, cw_erc20::ExecuteMsg::burn(42u128));
+                assert_eq!(send, &vec![]);
+            }
+            _ => panic!("expected wasm execute message"),
+        }
+        assert_eq!(totals(deps.as_ref()).total_burned, Uint128::from(42u128));
+    }
+
+    #[test]
+    fn receive_only_from_token() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "dex".to_string(),
+            amount: Uint128::from(42u128),
+            msg: None,
+        });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("other", &[]), msg);
+        match res.unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(totals(deps.as_ref()).total_burned, Uint128::zero());
+    }
+}
@@ -0,0 +1,49 @@
+use cosmwasm_std::Uint128;
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token contract bought and burned
+    pub token: String,
+    /// The DEX contract swapping native coins for the token
+    pub dex: String,
+    /// Native denom spent on buybacks. The contract's balance in it is the buyback budget.
+    pub spend_denom: String,
+    pub max_spend_per_execution: Uint128,
+    /// Minimum number of blocks between two buybacks
+    pub interval: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Spends up to `max_spend_per_execution` on buying the token. Anyone can call this
+    /// once `interval` blocks passed since the last buyback.
+    Execute {},
+    /// Burns the bought tokens. Called by the token contract when the DEX sends them here.
+    Receive(ReceiveMsg),
+}
+
+/// The swap message of the DEX. It sells the attached native coins for `ask_token`
+/// and sends the proceeds back to the caller via the token's `Receive` hook.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DexExecuteMsg {
+    Swap { ask_token: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Returns the amounts spent and burned over all buybacks
+    Totals {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalsResponse {
+    pub total_spent: Uint128,
+    pub total_burned: Uint128,
+}
@@ -0,0 +1,43 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static TOTAL_SPENT_KEY: &[u8] = b"total_spent";
+static TOTAL_BURNED_KEY: &[u8] = b"total_burned";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub token: Addr,
+    pub dex: Addr,
+    pub spend_denom: String,
+    pub max_spend_per_execution: Uint128,
+    pub interval: u64,
+    /// Block of the last buyback, `None` before the first one
+    pub last_execution: Option<u64>,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn total_spent(storage: &mut dyn Storage) -> Singleton<'_, Uint128> {
+    singleton(storage, TOTAL_SPENT_KEY)
+}
+
+pub fn total_spent_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Uint128> {
+    singleton_read(storage, TOTAL_SPENT_KEY)
+}
+
+pub fn total_burned(storage: &mut dyn Storage) -> Singleton<'_, Uint128> {
+    singleton(storage, TOTAL_BURNED_KEY)
+}
+
+pub fn total_burned_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Uint128> {
+    singleton_read(storage, TOTAL_BURNED_KEY)
+}
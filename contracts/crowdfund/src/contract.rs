@@ -0,0 +1,392 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128,
+};
+use cw_erc20::ReceiveMsg;
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ContributionResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{config, config_read, contributions, contributions_read, Config};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.goal.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    if msg.deadline <= env.block.height {
+        return Err(ContractError::InvalidDeadline {
+            deadline: msg.deadline,
+        });
+    }
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        beneficiary: deps.api.addr_validate(&msg.beneficiary)?,
+        goal: msg.goal,
+        deadline: msg.deadline,
+        raised: Uint128::zero(),
+        withdrawn: false,
+    };
+    config(deps.storage).save(&state)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(receive) => try_receive(deps, env, info, receive),
+        ExecuteMsg::Withdraw {} => try_withdraw(deps, env, info),
+        ExecuteMsg::Refund {} => try_refund(deps, env, info),
+    }
+}
+
+/// Receive
+///
+/// Records the tokens sent with the erc20 `Send` message as contribution of their sender.
+///
+/// @param receive the `ReceiveMsg` forwarded by the token
+fn try_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.token {
+        return Err(ContractError::WrongToken {
+            token: state.token.to_string(),
+        });
+    }
+    if env.block.height >= state.deadline {
+        return Err(ContractError::Closed {
+            deadline: state.deadline,
+        });
+    }
+    if receive.amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let contributor = deps.api.addr_validate(&receive.sender)?;
+    let key = contributor.as_str().as_bytes();
+    let contribution = contributions_read(deps.storage)
+        .may_load(key)?
+        .unwrap_or_default()
+        .checked_add(receive.amount)?;
+    contributions(deps.storage).save(key, &contribution)?;
+    state.raised = state.raised.checked_add(receive.amount)?;
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "contribute"),
+            attr("contributor", contributor),
+            attr("amount", receive.amount),
+            attr("raised", state.raised),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Withdraw
+///
+/// Sends everything raised to the beneficiary once the deadline passed with the goal met.
+fn try_withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.beneficiary {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.height < state.deadline {
+        return Err(ContractError::NotClosed {
+            deadline: state.deadline,
+        });
+    }
+    if state.raised < state.goal {
+        return Err(ContractError::GoalNotReached {
+            raised: state.raised,
+            goal: state.goal,
+        });
+    }
+    if state.withdrawn {
+        return Err(ContractError::AlreadyWithdrawn {});
+    }
+    state.withdrawn = true;
+    config(deps.storage).save(&state)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(state.beneficiary.as_str(), state.raised);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.token)?],
+        attributes: vec![
+            attr("action", "withdraw"),
+            attr("beneficiary", state.beneficiary),
+            attr("amount", state.raised),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Refund
+///
+/// Returns the signer's exact contribution once the deadline passed with the goal missed.
+fn try_refund(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if env.block.height < state.deadline {
+        return Err(ContractError::NotClosed {
+            deadline: state.deadline,
+        });
+    }
+    if state.raised >= state.goal {
+        return Err(ContractError::GoalReached {});
+    }
+    let key = info.sender.as_str().as_bytes();
+    let amount = match contributions_read(deps.storage).may_load(key)? {
+        Some(amount) => amount,
+        None => return Err(ContractError::NothingToRefund {}),
+    };
+    contributions(deps.storage).remove(key);
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.token)?],
+        attributes: vec![
+            attr("action", "refund"),
+            attr("contributor", info.sender),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => {
+            let state = config_read(deps.storage).load()?;
+            to_binary(&ConfigResponse {
+                token: state.token.to_string(),
+                beneficiary: state.beneficiary.to_string(),
+                goal: state.goal,
+                deadline: state.deadline,
+                raised: state.raised,
+                withdrawn: state.withdrawn,
+            })
+        }
+        QueryMsg::Contribution { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let amount = contributions_read(deps.storage)
+                .may_load(address.as_str().as_bytes())?
+                .unwrap_or_default();
+            to_binary(&ContributionResponse { amount })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, OwnedDeps};
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            beneficiary: "beneficiary".to_string(),
+            goal: Uint128::from(100u128),
+            deadline: 200,
+        };
+        instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn contribute(contributor: &str, amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Receive(ReceiveMsg {
+            sender: contributor.to_string(),
+            amount: Uint128::from(amount),
+            msg: None,
+        })
+    }
+
+    fn contribution(deps: Deps, address: &str) -> u128 {
+        let msg = QueryMsg::Contribution {
+            address: address.to_string(),
+        };
+        let res: ContributionResponse =
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.amount.u128()
+    }
+
+    #[test]
+    fn validates_config() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            beneficiary: "beneficiary".to_string(),
+            goal: Uint128::from(100u128),
+            deadline: 100,
+        };
+        match instantiate(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap_err()
+        {
+            ContractError::InvalidDeadline { deadline: 100 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = InstantiateMsg {
+            goal: Uint128::zero(),
+            deadline: 200,
+            ..msg
+        };
+        match instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn tracks_contributions() {
+        let mut deps = setup();
+        let res = run(deps.as_mut(), 150, "token", contribute("alice", 30)).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "contribute"),
+                attr("contributor", "alice"),
+                attr("amount", "30"),
+                attr("raised", "30"),
+            ]
+        );
+        run(deps.as_mut(), 151, "token", contribute("bob", 50)).unwrap();
+        run(deps.as_mut(), 199, "token", contribute("alice", 15)).unwrap();
+        assert_eq!(contribution(deps.as_ref(), "alice"), 45);
+        assert_eq!(contribution(deps.as_ref(), "bob"), 50);
+        assert_eq!(contribution(deps.as_ref(), "carol"), 0);
+        let res: ConfigResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(res.raised.u128(), 95);
+
+        match run(deps.as_mut(), 200, "token", contribute("carol", 10)).unwrap_err() {
+            ContractError::Closed { deadline: 200 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 150, "token", contribute("carol", 0)).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_other_tokens() {
+        let mut deps = setup();
+        match run(deps.as_mut(), 150, "other_token", contribute("alice", 30)).unwrap_err() {
+            ContractError::WrongToken { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // calling the hook directly does not count either
+        match run(deps.as_mut(), 150, "alice", contribute("alice", 30)).unwrap_err() {
+            ContractError::WrongToken { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(contribution(deps.as_ref(), "alice"), 0);
+    }
+
+    #[test]
+    fn withdraws_when_goal_met() {
+        let mut deps = setup();
+        run(deps.as_mut(), 150, "token", contribute("alice", 60)).unwrap();
+        run(deps.as_mut(), 150, "token", contribute("bob", 40)).unwrap();
+
+        match run(deps.as_mut(), 199, "beneficiary", ExecuteMsg::Withdraw {}).unwrap_err() {
+            ContractError::NotClosed { deadline: 200 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 200, "alice", ExecuteMsg::Withdraw {}).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 200, "alice", ExecuteMsg::Refund {}).unwrap_err() {
+            ContractError::GoalReached {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = run(deps.as_mut(), 200, "beneficiary", ExecuteMsg::Withdraw {}).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("beneficiary", 100u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("token").unwrap()]
+        );
+        match run(deps.as_mut(), 201, "beneficiary", ExecuteMsg::Withdraw {}).unwrap_err() {
+            ContractError::AlreadyWithdrawn {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn refunds_when_goal_missed() {
+        let mut deps = setup();
+        run(deps.as_mut(), 150, "token", contribute("alice", 60)).unwrap();
+
+        match run(deps.as_mut(), 199, "alice", ExecuteMsg::Refund {}).unwrap_err() {
+            ContractError::NotClosed { deadline: 200 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 200, "beneficiary", ExecuteMsg::Withdraw {}).unwrap_err() {
+            ContractError::GoalNotReached { raised, goal } => {
+                assert_eq!(raised.u128(), 60);
+                assert_eq!(goal.u128(), 100);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = run(deps.as_mut(), 200, "alice", ExecuteMsg::Refund {}).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("alice", 60u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("token").unwrap()]
+        );
+        match run(deps.as_mut(), 201, "alice", ExecuteMsg::Refund {}).unwrap_err() {
+            ContractError::NothingToRefund {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 201, "bob", ExecuteMsg::Refund {}).unwrap_err() {
+            ContractError::NothingToRefund {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
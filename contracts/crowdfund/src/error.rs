@@ -0,0 +1,41 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Deadline must be in the future (deadline {deadline})")]
+    InvalidDeadline { deadline: u64 },
+
+    #[error("Only {token} tokens are accepted")]
+    WrongToken { token: String },
+
+    #[error("Crowdfund closed at height {deadline}")]
+    Closed { deadline: u64 },
+
+    #[error("Crowdfund open until height {deadline}")]
+    NotClosed { deadline: u64 },
+
+    #[error("Goal not reached ({raised} of {goal} raised)")]
+    GoalNotReached { raised: Uint128, goal: Uint128 },
+
+    #[error("Goal reached, contributions are not refunded")]
+    GoalReached {},
+
+    #[error("Funds already withdrawn")]
+    AlreadyWithdrawn {},
+
+    #[error("Nothing to refund")]
+    NothingToRefund {},
+}
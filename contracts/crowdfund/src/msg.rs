@@ -0,0 +1,53 @@
+use cosmwasm_std::Uint128;
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token contributions are made in
+    pub token: String,
+    /// Receives the funds if the goal is met
+    pub beneficiary: String,
+    pub goal: Uint128,
+    /// Contributions are accepted up to, but not including, this block height
+    pub deadline: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Contributes the tokens sent with the erc20 `Send` message. Only accepted from the
+    /// configured token and before the deadline.
+    Receive(ReceiveMsg),
+    /// Sends all contributions to the beneficiary once the deadline passed and the goal is met.
+    /// Only the beneficiary can do this.
+    Withdraw {},
+    /// Returns the signer's contribution once the deadline passed without meeting the goal
+    Refund {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Returns the amount contributed by `address`
+    Contribution {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub token: String,
+    pub beneficiary: String,
+    pub goal: Uint128,
+    pub deadline: u64,
+    pub raised: Uint128,
+    pub withdrawn: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContributionResponse {
+    pub amount: Uint128,
+}
@@ -0,0 +1,42 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static CONTRIBUTIONS_KEY: &[u8] = b"contributions";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The only erc20 token accepted as contribution
+    pub token: Addr,
+    /// Receives the funds if the goal is met
+    pub beneficiary: Addr,
+    pub goal: Uint128,
+    /// Contributions are accepted up to, but not including, this block height
+    pub deadline: u64,
+    /// Sum of all contributions
+    pub raised: Uint128,
+    /// Set once the beneficiary withdrew the funds
+    pub withdrawn: bool,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Contributed amounts by contributor address
+pub fn contributions(storage: &mut dyn Storage) -> Bucket<'_, Uint128> {
+    bucket(storage, CONTRIBUTIONS_KEY)
+}
+
+pub fn contributions_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Uint128> {
+    bucket_read(storage, CONTRIBUTIONS_KEY)
+}
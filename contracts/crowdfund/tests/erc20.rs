@@ -0,0 +1,113 @@
+//! Runs the crowdfund against real erc20 contracts on a test chain, which reverts failing
+//! messages like a real chain.
+
+use cosmwasm_std::Uint128;
+use cw_crowdfund::contract::{execute, instantiate, query};
+use cw_crowdfund::msg::{ExecuteMsg, InstantiateMsg};
+use cw_erc20::BalanceResponse;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const OTHER_TOKEN: &str = "other_token";
+const CROWDFUND: &str = "crowdfund";
+
+/// A crowdfund for `goal` tokens, open until height 200. alice, bob and carol hold both
+/// tokens.
+fn setup(goal: u128) -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(100);
+    for (address, symbol) in &[(TOKEN, "FUND"), (OTHER_TOKEN, "OTHER")] {
+        let msg = erc20_msg(symbol, &[("alice", 60), ("bob", 30), ("carol", 20)]);
+        chain.instantiate_erc20(address, &msg).unwrap();
+    }
+
+    let crowdfund = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        beneficiary: "beneficiary".to_string(),
+        goal: Uint128::from(goal),
+        deadline: 200,
+    };
+    chain
+        .instantiate(crowdfund, CROWDFUND, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn contribute(chain: &mut Chain, contributor: &str, amount: u128) {
+    let msg = cw_erc20::ExecuteMsg::send(CROWDFUND, amount, None);
+    chain.execute(TOKEN, contributor, &msg, &[]).unwrap();
+}
+
+fn balance(chain: &Chain, token: &str, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(token, &msg).unwrap();
+    res.balance.u128()
+}
+
+#[test]
+fn beneficiary_withdraws_when_goal_met() {
+    let mut chain = setup(100);
+    contribute(&mut chain, "alice", 50);
+    contribute(&mut chain, "bob", 30);
+    chain.set_height(150);
+    contribute(&mut chain, "carol", 20);
+    assert_eq!(balance(&chain, TOKEN, CROWDFUND), 100);
+
+    chain.set_height(200);
+    let err = chain
+        .execute(CROWDFUND, "bob", &ExecuteMsg::Refund {}, &[])
+        .unwrap_err();
+    assert_eq!(err, "Goal reached, contributions are not refunded");
+
+    chain
+        .execute(CROWDFUND, "beneficiary", &ExecuteMsg::Withdraw {}, &[])
+        .unwrap();
+    assert_eq!(balance(&chain, TOKEN, "beneficiary"), 100);
+    assert_eq!(balance(&chain, TOKEN, CROWDFUND), 0);
+}
+
+#[test]
+fn contributors_refund_when_goal_missed() {
+    let mut chain = setup(100);
+    contribute(&mut chain, "alice", 30);
+    contribute(&mut chain, "bob", 25);
+    contribute(&mut chain, "alice", 20);
+
+    chain.set_height(199);
+    let err = chain
+        .execute(CROWDFUND, "alice", &ExecuteMsg::Refund {}, &[])
+        .unwrap_err();
+    assert_eq!(err, "Crowdfund open until height 200");
+
+    chain.set_height(200);
+    let err = chain
+        .execute(CROWDFUND, "beneficiary", &ExecuteMsg::Withdraw {}, &[])
+        .unwrap_err();
+    assert_eq!(err, "Goal not reached (75 of 100 raised)");
+
+    chain
+        .execute(CROWDFUND, "alice", &ExecuteMsg::Refund {}, &[])
+        .unwrap();
+    chain
+        .execute(CROWDFUND, "bob", &ExecuteMsg::Refund {}, &[])
+        .unwrap();
+    assert_eq!(balance(&chain, TOKEN, "alice"), 60);
+    assert_eq!(balance(&chain, TOKEN, "bob"), 30);
+    assert_eq!(balance(&chain, TOKEN, CROWDFUND), 0);
+    let err = chain
+        .execute(CROWDFUND, "carol", &ExecuteMsg::Refund {}, &[])
+        .unwrap_err();
+    assert_eq!(err, "Nothing to refund");
+}
+
+#[test]
+fn rejects_other_tokens() {
+    let mut chain = setup(100);
+    let msg = cw_erc20::ExecuteMsg::send(CROWDFUND, 10u128, None);
+    let err = chain.execute(OTHER_TOKEN, "alice", &msg, &[]).unwrap_err();
+    assert_eq!(err, "Only token tokens are accepted");
+    // the send is reverted with the rejected hook
+    assert_eq!(balance(&chain, OTHER_TOKEN, "alice"), 60);
+    assert_eq!(balance(&chain, OTHER_TOKEN, CROWDFUND), 0);
+}
@@ -0,0 +1,438 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Storage, Uint128,
+};
+
+use crate::error::ContractError;
+use crate::msg::{BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg, TokenInfoResponse};
+use crate::state::{
+    config, config_read, interest_index, interest_index_read, scaled_balances,
+    scaled_balances_read, total_scaled, total_scaled_read, Config, InterestIndex, INDEX_UNIT,
+};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = Config {
+        name: msg.name,
+        symbol: msg.symbol,
+        decimals: msg.decimals,
+        pool: deps.api.addr_validate(&msg.pool)?,
+        interest_rate: msg.interest_rate,
+    };
+    config(deps.storage).save(&state)?;
+    interest_index(deps.storage).save(&InterestIndex {
+        index: Uint128::from(INDEX_UNIT),
+        height: env.block.height,
+    })?;
+    total_scaled(deps.storage).save(&Uint128::zero())?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // every balance change happens at the current index
+    let index = current_index(deps.storage, &env)?;
+    interest_index(deps.storage).save(&index)?;
+
+    match msg {
+        ExecuteMsg::Mint { borrower, amount } => try_mint(deps, info, &index, borrower, amount),
+        ExecuteMsg::Burn { borrower, amount } => try_burn(deps, info, &index, borrower, amount),
+        ExecuteMsg::Transfer { recipient, amount } => {
+            try_transfer(deps, info, &index, recipient, amount)
+        }
+    }
+}
+
+/// Mint
+///
+/// Records a new loan. The borrower owes at least `amount` right away, rounding is in
+/// favour of the pool.
+///
+/// @param borrower the address taking the loan
+/// @param amount the loaned tokens
+fn try_mint(
+    deps: DepsMut,
+    info: MessageInfo,
+    index: &InterestIndex,
+    borrower: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_pool(deps.storage, &info.sender)?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let borrower = deps.api.addr_validate(&borrower)?;
+
+    let mut scaled = amount.multiply_ratio(INDEX_UNIT, index.index);
+    if to_tokens(scaled, index) < amount {
+        scaled = scaled.checked_add(Uint128::from(1u128))?;
+    }
+    scaled_balances(deps.storage).update(
+        borrower.as_str().as_bytes(),
+        |owned| -> Result<_, ContractError> { Ok(owned.unwrap_or_default().checked_add(scaled)?) },
+    )?;
+    total_scaled(deps.storage)
+        .update(|total| -> Result<_, ContractError> { Ok(total.checked_add(scaled)?) })?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "mint"),
+            attr("borrower", borrower),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Burn
+///
+/// Records a repayment, which must not exceed the current debt of the borrower
+///
+/// @param borrower the address whose debt is repaid
+/// @param amount the repaid tokens
+fn try_burn(
+    deps: DepsMut,
+    info: MessageInfo,
+    index: &InterestIndex,
+    borrower: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_pool(deps.storage, &info.sender)?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let borrower = deps.api.addr_validate(&borrower)?;
+
+    let key = borrower.as_str().as_bytes();
+    let owned = scaled_balances_read(deps.storage)
+        .may_load(key)?
+        .unwrap_or_default();
+    let scaled = scaled_part(owned, index, amount)?;
+    scaled_balances(deps.storage).save(key, &owned.checked_sub(scaled)?)?;
+    total_scaled(deps.storage)
+        .update(|total| -> Result<_, ContractError> { Ok(total.checked_sub(scaled)?) })?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "burn"),
+            attr("borrower", borrower),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Transfer
+///
+/// Moves debt of the signer to the recipient, who owes it and its future interest from now on
+///
+/// @param recipient the address taking over the debt
+/// @param amount the transferred tokens
+fn try_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    index: &InterestIndex,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let sender_key = info.sender.as_str().as_bytes();
+    let owned = scaled_balances_read(deps.storage)
+        .may_load(sender_key)?
+        .unwrap_or_default();
+    let scaled = scaled_part(owned, index, amount)?;
+    scaled_balances(deps.storage).save(sender_key, &owned.checked_sub(scaled)?)?;
+    scaled_balances(deps.storage).update(
+        recipient.as_str().as_bytes(),
+        |owned| -> Result<_, ContractError> { Ok(owned.unwrap_or_default().checked_add(scaled)?) },
+    )?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "transfer"),
+            attr("sender", info.sender),
+            attr("recipient", recipient),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn assert_pool(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    if config_read(storage).load()?.pool != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/// The index as of the current block. Interest compounds whenever the index is stored.
+fn current_index(storage: &dyn Storage, env: &Env) -> StdResult<InterestIndex> {
+    let stored = interest_index_read(storage).load()?;
+    if env.block.height <= stored.height {
+        return Ok(stored);
+    }
+    let rate = config_read(storage).load()?.interest_rate;
+    let blocks = Uint128::from(env.block.height - stored.height);
+    let interest = (stored.index * rate).checked_mul(blocks)?;
+    Ok(InterestIndex {
+        index: stored.index.checked_add(interest)?,
+        height: env.block.height,
+    })
+}
+
+fn to_tokens(scaled: Uint128, index: &InterestIndex) -> Uint128 {
+    scaled.multiply_ratio(index.index, INDEX_UNIT)
+}
+
+/// The scaled units worth `amount` tokens of a `scaled` balance. Moving the whole debt
+/// moves all units, so no rounding dust is left behind.
+fn scaled_part(
+    scaled: Uint128,
+    index: &InterestIndex,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let debt = to_tokens(scaled, index);
+    if amount > debt {
+        return Err(ContractError::InsufficientDebt {
+            debt,
+            required: amount,
+        });
+    }
+    if amount == debt {
+        Ok(scaled)
+    } else {
+        Ok(amount.multiply_ratio(INDEX_UNIT, index.index))
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let index = current_index(deps.storage, &env)?;
+    match msg {
+        QueryMsg::Balance { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let scaled = scaled_balances_read(deps.storage)
+                .may_load(address.as_str().as_bytes())?
+                .unwrap_or_default();
+            to_binary(&BalanceResponse {
+                balance: to_tokens(scaled, &index),
+            })
+        }
+        QueryMsg::TokenInfo {} => {
+            let state = config_read(deps.storage).load()?;
+            let total = total_scaled_read(deps.storage).load()?;
+            to_binary(&TokenInfoResponse {
+                name: state.name,
+                symbol: state.symbol,
+                decimals: state.decimals,
+                total_supply: to_tokens(total, &index),
+            })
+        }
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::InterestIndex {} => to_binary(&index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, Decimal, OwnedDeps};
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    /// Instantiated at height 100 with 0.1% interest per block
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            name: "Debt Token".to_string(),
+            symbol: "DEBT".to_string(),
+            decimals: 6,
+            pool: "pool".to_string(),
+            interest_rate: Decimal::permille(1),
+        };
+        instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn mint(borrower: &str, amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Mint {
+            borrower: borrower.to_string(),
+            amount: Uint128::from(amount),
+        }
+    }
+
+    fn burn(borrower: &str, amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Burn {
+            borrower: borrower.to_string(),
+            amount: Uint128::from(amount),
+        }
+    }
+
+    fn transfer(recipient: &str, amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        }
+    }
+
+    fn balance(deps: Deps, height: u64, address: &str) -> u128 {
+        let msg = QueryMsg::Balance {
+            address: address.to_string(),
+        };
+        let res: BalanceResponse = from_binary(&query(deps, env_at(height), msg).unwrap()).unwrap();
+        res.balance.u128()
+    }
+
+    fn total_supply(deps: Deps, height: u64) -> u128 {
+        let res: TokenInfoResponse =
+            from_binary(&query(deps, env_at(height), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+        res.total_supply.u128()
+    }
+
+    #[test]
+    fn only_pool_mints() {
+        let mut deps = setup();
+        match run(deps.as_mut(), 100, "borrower", mint("borrower", 1000)).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 100, "pool", mint("borrower", 0)).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = run(deps.as_mut(), 100, "pool", mint("borrower", 1000)).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "mint"),
+                attr("borrower", "borrower"),
+                attr("amount", "1000"),
+            ]
+        );
+        assert_eq!(balance(deps.as_ref(), 100, "borrower"), 1000);
+        assert_eq!(total_supply(deps.as_ref(), 100), 1000);
+    }
+
+    #[test]
+    fn interest_increases_balances() {
+        let mut deps = setup();
+        run(deps.as_mut(), 100, "pool", mint("alice", 1000)).unwrap();
+
+        // 10 blocks at 0.1%
+        assert_eq!(balance(deps.as_ref(), 110, "alice"), 1010);
+        assert_eq!(total_supply(deps.as_ref(), 110), 1010);
+
+        // new loans start without interest
+        run(deps.as_mut(), 110, "pool", mint("bob", 1000)).unwrap();
+        assert_eq!(balance(deps.as_ref(), 110, "bob"), 1000);
+
+        // interest compounds on the stored index
+        assert_eq!(balance(deps.as_ref(), 120, "alice"), 1020);
+        assert_eq!(balance(deps.as_ref(), 120, "bob"), 1010);
+        let index: InterestIndex =
+            from_binary(&query(deps.as_ref(), env_at(120), QueryMsg::InterestIndex {}).unwrap())
+                .unwrap();
+        assert_eq!(index.index.u128(), 1_020_100_000_000_000_000);
+        assert_eq!(index.height, 120);
+    }
+
+    #[test]
+    fn transfer_moves_debt() {
+        let mut deps = setup();
+        run(deps.as_mut(), 100, "pool", mint("alice", 1000)).unwrap();
+
+        let res = run(deps.as_mut(), 100, "alice", transfer("bob", 400)).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "transfer"),
+                attr("sender", "alice"),
+                attr("recipient", "bob"),
+                attr("amount", "400"),
+            ]
+        );
+        assert_eq!(balance(deps.as_ref(), 100, "alice"), 600);
+        assert_eq!(balance(deps.as_ref(), 100, "bob"), 400);
+
+        // the new holder pays the interest from now on
+        assert_eq!(balance(deps.as_ref(), 110, "alice"), 606);
+        assert_eq!(balance(deps.as_ref(), 110, "bob"), 404);
+        assert_eq!(total_supply(deps.as_ref(), 110), 1010);
+
+        match run(deps.as_mut(), 110, "bob", transfer("carl", 405)).unwrap_err() {
+            ContractError::InsufficientDebt { debt, required } => {
+                assert_eq!(debt.u128(), 404);
+                assert_eq!(required.u128(), 405);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        run(deps.as_mut(), 110, "bob", transfer("carl", 404)).unwrap();
+        assert_eq!(balance(deps.as_ref(), 110, "bob"), 0);
+        assert_eq!(balance(deps.as_ref(), 110, "carl"), 404);
+    }
+
+    #[test]
+    fn repayment_burns_debt() {
+        let mut deps = setup();
+        run(deps.as_mut(), 100, "pool", mint("alice", 1000)).unwrap();
+
+        match run(deps.as_mut(), 110, "alice", burn("alice", 10)).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        run(deps.as_mut(), 110, "pool", burn("alice", 10)).unwrap();
+        assert_eq!(balance(deps.as_ref(), 110, "alice"), 1000);
+
+        match run(deps.as_mut(), 110, "pool", burn("alice", 1001)).unwrap_err() {
+            ContractError::InsufficientDebt { debt, .. } => assert_eq!(debt.u128(), 1000),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // repaying everything leaves no dust accruing interest
+        run(deps.as_mut(), 110, "pool", burn("alice", 1000)).unwrap();
+        let scaled = scaled_balances_read(&deps.storage).load(b"alice").unwrap();
+        assert!(scaled.is_zero());
+        assert_eq!(balance(deps.as_ref(), 1000, "alice"), 0);
+        assert_eq!(total_supply(deps.as_ref(), 1000), 0);
+    }
+}
@@ -0,0 +1,20 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Insufficient debt (debt {debt}, required={required})")]
+    InsufficientDebt { debt: Uint128, required: Uint128 },
+}
@@ -0,0 +1,52 @@
+use cosmwasm_std::{Decimal, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// The lending pool contract minting and burning the debt tokens
+    pub pool: String,
+    /// Interest added to all debt per block, e.g. `"0.0001"`
+    pub interest_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Records a new loan of `amount` to `borrower`. Only the pool can do this.
+    Mint { borrower: String, amount: Uint128 },
+    /// Records a repayment of `amount` of the debt of `borrower`. Only the pool can do this.
+    Burn { borrower: String, amount: Uint128 },
+    /// Moves `amount` of the signer's debt to `recipient`, who owes it from now on
+    Transfer { recipient: String, amount: Uint128 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the current debt of `address`, including interest accrued until this block
+    Balance {
+        address: String,
+    },
+    TokenInfo {},
+    Config {},
+    /// Returns the interest index as of this block
+    InterestIndex {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BalanceResponse {
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenInfoResponse {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// The total debt of all borrowers
+    pub total_supply: Uint128,
+}
@@ -0,0 +1,68 @@
+use cosmwasm_std::{Addr, Decimal, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static INDEX_KEY: &[u8] = b"index";
+static TOTAL_SCALED_KEY: &[u8] = b"total_scaled";
+static SCALED_BALANCES_KEY: &[u8] = b"scaled_balances";
+
+/// The value of `InterestIndex::index` when the contract is instantiated
+pub const INDEX_UNIT: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// The lending pool, the only address allowed to mint and burn
+    pub pool: Addr,
+    /// Interest added to all debt per block
+    pub interest_rate: Decimal,
+}
+
+/// Debt is stored in scaled units, which are converted to tokens by multiplying with
+/// `index / INDEX_UNIT`. The index grows every block, rebasing all balances at once.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InterestIndex {
+    pub index: Uint128,
+    /// The block height the index was last updated at
+    pub height: u64,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn interest_index(storage: &mut dyn Storage) -> Singleton<'_, InterestIndex> {
+    singleton(storage, INDEX_KEY)
+}
+
+pub fn interest_index_read(storage: &dyn Storage) -> ReadonlySingleton<'_, InterestIndex> {
+    singleton_read(storage, INDEX_KEY)
+}
+
+pub fn total_scaled(storage: &mut dyn Storage) -> Singleton<'_, Uint128> {
+    singleton(storage, TOTAL_SCALED_KEY)
+}
+
+pub fn total_scaled_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Uint128> {
+    singleton_read(storage, TOTAL_SCALED_KEY)
+}
+
+/// Scaled debt by borrower address
+pub fn scaled_balances(storage: &mut dyn Storage) -> Bucket<'_, Uint128> {
+    bucket(storage, SCALED_BALANCES_KEY)
+}
+
+pub fn scaled_balances_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Uint128> {
+    bucket_read(storage, SCALED_BALANCES_KEY)
+}
@@ -0,0 +1,417 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Uint128, WasmMsg,
+};
+
+use crate::error::ContractError;
+use crate::msg::{
+    ClaimableResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg, TokenExecuteMsg,
+};
+use crate::state::{config, config_read, recipients, recipients_read, RecipientInfo, State};
+
+const TOTAL_BPS: u64 = 10_000;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.duration_blocks == 0 {
+        return Err(ContractError::ZeroDuration {});
+    }
+    let total_bps: u64 = msg.recipients.iter().map(|r| r.share_bps as u64).sum();
+    if total_bps != TOTAL_BPS {
+        return Err(ContractError::InvalidShares { total_bps });
+    }
+
+    let state = State {
+        token: deps.api.addr_validate(&msg.token)?,
+        total: msg.total,
+        start_block: msg.start_block,
+        duration_blocks: msg.duration_blocks,
+        funded: Uint128::zero(),
+    };
+    config(deps.storage).save(&state)?;
+
+    for recipient in msg.recipients {
+        let address = deps.api.addr_validate(&recipient.address)?;
+        let key = address.as_str().as_bytes();
+        if recipients_read(deps.storage).may_load(key)?.is_some() {
+            return Err(ContractError::DuplicateRecipient {
+                address: recipient.address,
+            });
+        }
+        recipients(deps.storage).save(
+            key,
+            &RecipientInfo {
+                share_bps: recipient.share_bps,
+                claimed: Uint128::zero(),
+            },
+        )?;
+    }
+
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => try_receive(deps, env, info, msg),
+        ExecuteMsg::Claim {} => try_claim(deps, env, info),
+    }
+}
+
+fn try_receive(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    // only the distributed token can fund the contract
+    if info.sender != state.token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let funded = state.funded.checked_add(msg.amount)?;
+    if funded > state.total {
+        return Err(ContractError::Overfunded {
+            funded: funded.u128(),
+            total: state.total.u128(),
+        });
+    }
+    state.funded = funded;
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "fund"),
+            attr("sender", msg.sender),
+            attr("amount", msg.amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if state.funded < state.total {
+        return Err(ContractError::NotFunded {
+            funded: state.funded.u128(),
+            total: state.total.u128(),
+        });
+    }
+
+    let key = info.sender.as_str().as_bytes();
+    let mut recipient = recipients_read(deps.storage)
+        .may_load(key)?
+        .ok_or(ContractError::Unauthorized {})?;
+
+    let vested = state.vested(recipient.share_bps, env.block.height);
+    let amount = vested.checked_sub(recipient.claimed)?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    recipient.claimed = vested;
+    recipients(deps.storage).save(key, &recipient)?;
+
+    let transfer = TokenExecuteMsg::Transfer {
+        recipient: info.sender.to_string(),
+        amount,
+    };
+    let res = Response {
+        submessages: vec![],
+        messages: vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: state.token.to_string(),
+            msg: to_binary(&transfer)?,
+            send: vec![],
+        })],
+        attributes: vec![
+            attr("action", "claim"),
+            attr("recipient", info.sender),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Claimable { address } => to_binary(&query_claimable(deps, env, address)?),
+    }
+}
+
+fn query_claimable(deps: Deps, env: Env, address: String) -> StdResult<ClaimableResponse> {
+    let state = config_read(deps.storage).load()?;
+    let address = deps.api.addr_validate(&address)?;
+    let recipient = recipients_read(deps.storage).load(address.as_str().as_bytes())?;
+    let vested = state.vested(recipient.share_bps, env.block.height);
+    Ok(ClaimableResponse {
+        share_bps: recipient.share_bps,
+        vested,
+        claimed: recipient.claimed,
+        claimable: vested.checked_sub(recipient.claimed)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::Recipient;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_binary, from_slice};
+
+    const TOKEN: &str = "token";
+
+    fn init_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            token: TOKEN.to_string(),
+            total: Uint128::from(10_000u128),
+            start_block: 1000,
+            duration_blocks: 100,
+            recipients: vec![
+                Recipient {
+                    address: "alice".to_string(),
+                    share_bps: 6000,
+                },
+                Recipient {
+                    address: "bob".to_string(),
+                    share_bps: 4000,
+                },
+            ],
+        }
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn fund(deps: DepsMut, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "funder".to_string(),
+            amount: Uint128::from(amount),
+            msg: None,
+        });
+        execute(deps, env_at(900), mock_info(TOKEN, &[]), msg)
+    }
+
+    fn claim(deps: DepsMut, sender: &str, height: u64) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            env_at(height),
+            mock_info(sender, &[]),
+            ExecuteMsg::Claim {},
+        )
+    }
+
+    fn claimed_amount(res: &Response) -> u128 {
+        match &res.messages[0] {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) => {
+                assert_eq!(contract_addr, TOKEN);
+                match from_slice(msg).unwrap() {
+                    TokenExecuteMsg::Transfer { amount, .. } => amount.u128(),
+                }
+            }
+            _ => panic!("expected wasm execute message"),
+        }
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), env_at(900), QueryMsg::Config {}).unwrap();
+        let state: State = from_binary(&res).unwrap();
+        assert_eq!(state.total, Uint128::from(10_000u128));
+        assert_eq!(state.funded, Uint128::zero());
+    }
+
+    #[test]
+    fn init_fails_for_invalid_shares() {
+        let mut deps = mock_dependencies(&[]);
+        let mut msg = init_msg();
+        msg.recipients[1].share_bps = 3999;
+        let err = instantiate(deps.as_mut(), env_at(900), mock_info("creator", &[]), msg);
+        match err.unwrap_err() {
+            ContractError::InvalidShares { total_bps: 9999 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let mut msg = init_msg();
+        msg.recipients[1].address = "alice".to_string();
+        let err = instantiate(deps.as_mut(), env_at(900), mock_info("creator", &[]), msg);
+        match err.unwrap_err() {
+            ContractError::DuplicateRecipient { address } => assert_eq!(address, "alice"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn only_token_can_fund() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "funder".to_string(),
+            amount: Uint128::from(10_000u128),
+            msg: None,
+        });
+        let err = execute(deps.as_mut(), env_at(900), mock_info("other", &[]), msg);
+        match err.unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        match fund(deps.as_mut(), 10_001).unwrap_err() {
+            ContractError::Overfunded { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn cannot_claim_before_funded() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+        fund(deps.as_mut(), 4000).unwrap();
+
+        match claim(deps.as_mut(), "alice", 1050).unwrap_err() {
+            ContractError::NotFunded {
+                funded: 4000,
+                total: 10_000,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn claims_proportionally() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+        fund(deps.as_mut(), 6000).unwrap();
+        fund(deps.as_mut(), 4000).unwrap();
+
+        // nothing vested before start
+        match claim(deps.as_mut(), "alice", 1000).unwrap_err() {
+            ContractError::NothingToClaim {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // at 25% alice gets 25% of her 60%, bob 25% of his 40%
+        let res = claim(deps.as_mut(), "alice", 1025).unwrap();
+        assert_eq!(claimed_amount(&res), 1500);
+        let res = claim(deps.as_mut(), "bob", 1025).unwrap();
+        assert_eq!(claimed_amount(&res), 1000);
+
+        // progress is tracked per recipient
+        let res = claim(deps.as_mut(), "alice", 1050).unwrap();
+        assert_eq!(claimed_amount(&res), 1500);
+        match claim(deps.as_mut(), "alice", 1050).unwrap_err() {
+            ContractError::NothingToClaim {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = query(
+            deps.as_ref(),
+            env_at(1075),
+            QueryMsg::Claimable {
+                address: "bob".to_string(),
+            },
+        )
+        .unwrap();
+        let claimable: ClaimableResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            claimable,
+            ClaimableResponse {
+                share_bps: 4000,
+                vested: Uint128::from(3000u128),
+                claimed: Uint128::from(1000u128),
+                claimable: Uint128::from(2000u128),
+            }
+        );
+    }
+
+    #[test]
+    fn claims_everything_after_duration() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+        fund(deps.as_mut(), 10_000).unwrap();
+
+        let res = claim(deps.as_mut(), "alice", 1010).unwrap();
+        assert_eq!(claimed_amount(&res), 600);
+        let res = claim(deps.as_mut(), "alice", 5000).unwrap();
+        assert_eq!(claimed_amount(&res), 5400);
+        let res = claim(deps.as_mut(), "bob", 1100).unwrap();
+        assert_eq!(claimed_amount(&res), 4000);
+
+        match claim(deps.as_mut(), "bob", 6000).unwrap_err() {
+            ContractError::NothingToClaim {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn non_recipient_cannot_claim() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("creator", &[]),
+            init_msg(),
+        )
+        .unwrap();
+        fund(deps.as_mut(), 10_000).unwrap();
+
+        match claim(deps.as_mut(), "mallory", 1050).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Recipient shares must add up to 10000 bps (got {total_bps})")]
+    InvalidShares { total_bps: u64 },
+
+    #[error("Recipient {address} listed more than once")]
+    DuplicateRecipient { address: String },
+
+    #[error("Duration must not be zero")]
+    ZeroDuration {},
+
+    #[error("Distribution not fully funded (funded {funded}, total {total})")]
+    NotFunded { funded: u128, total: u128 },
+
+    #[error("Funding exceeds the distribution total (funded {funded}, total {total})")]
+    Overfunded { funded: u128, total: u128 },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
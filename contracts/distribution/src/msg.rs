@@ -0,0 +1,65 @@
+use cosmwasm_std::{Binary, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Recipient {
+    pub address: String,
+    /// Share of the total allocation in basis points (1/10000)
+    pub share_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token contract distributed by this contract
+    pub token: String,
+    /// Total amount distributed over all recipients
+    pub total: Uint128,
+    pub start_block: u64,
+    pub duration_blocks: u64,
+    /// Shares must add up to exactly 10000 bps
+    pub recipients: Vec<Recipient>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Funds the distribution. Called by the token contract when tokens are sent here.
+    Receive(ReceiveMsg),
+    /// Transfers the portion vested so far and not yet claimed to the signer
+    Claim {},
+}
+
+/// The hook message sent by the erc20 contract on receiving tokens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiveMsg {
+    pub sender: String,
+    pub amount: Uint128,
+    pub msg: Option<Binary>,
+}
+
+/// The subset of the erc20 execute messages this contract sends to the token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenExecuteMsg {
+    Transfer { recipient: String, amount: Uint128 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Returns the claiming progress of a recipient
+    Claimable {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableResponse {
+    pub share_bps: u16,
+    /// Vested at the current block, including claimed tokens
+    pub vested: Uint128,
+    pub claimed: Uint128,
+    pub claimable: Uint128,
+}
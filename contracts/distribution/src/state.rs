@@ -0,0 +1,54 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static RECIPIENTS_KEY: &[u8] = b"recipients";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct State {
+    pub token: Addr,
+    pub total: Uint128,
+    pub start_block: u64,
+    pub duration_blocks: u64,
+    /// Amount received from the token contract so far
+    pub funded: Uint128,
+}
+
+impl State {
+    /// Amount vested for a recipient with the given share at `height`
+    pub fn vested(&self, share_bps: u16, height: u64) -> Uint128 {
+        let elapsed = height
+            .saturating_sub(self.start_block)
+            .min(self.duration_blocks);
+        self.total
+            .multiply_ratio(elapsed, self.duration_blocks)
+            .multiply_ratio(share_bps, 10_000u128)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecipientInfo {
+    pub share_bps: u16,
+    pub claimed: Uint128,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, State> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, State> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn recipients(storage: &mut dyn Storage) -> Bucket<'_, RecipientInfo> {
+    bucket(storage, RECIPIENTS_KEY)
+}
+
+pub fn recipients_read(storage: &dyn Storage) -> ReadonlyBucket<'_, RecipientInfo> {
+    bucket_read(storage, RECIPIENTS_KEY)
+}
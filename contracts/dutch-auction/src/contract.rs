@@ -0,0 +1,522 @@
+use cosmwasm_std::{
+    attr, coins, entry_point, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, Uint128,
+};
+use cw_erc20::ReceiveMsg;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, PriceResponse, QueryMsg};
+use crate::state::{config, config_read, AuctionStatus, Config};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.start_height >= msg.end_height {
+        return Err(ContractError::InvalidSchedule {});
+    }
+    if msg.end_price > msg.start_price {
+        return Err(ContractError::InvalidPrices {});
+    }
+    if msg.amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        seller: info.sender,
+        denom: msg.denom,
+        amount: msg.amount,
+        start_price: msg.start_price,
+        end_price: msg.end_price,
+        start_height: msg.start_height,
+        end_height: msg.end_height,
+        status: AuctionStatus::Unfunded,
+    };
+    config(deps.storage).save(&state)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(receive) => try_receive(deps, info, receive),
+        ExecuteMsg::Bid {} => try_bid(deps, env, info),
+        ExecuteMsg::Withdraw {} => try_withdraw(deps, env, info),
+    }
+}
+
+/// Receive
+///
+/// Opens the auction with the tokens the seller sent with the erc20 `Send` message.
+///
+/// @param receive the `ReceiveMsg` forwarded by the token
+fn try_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    receive: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.token {
+        return Err(ContractError::WrongToken {
+            token: state.token.to_string(),
+        });
+    }
+    if receive.sender != state.seller.as_str() {
+        return Err(ContractError::Unauthorized {});
+    }
+    if state.status != AuctionStatus::Unfunded {
+        return Err(ContractError::NotOpen {});
+    }
+    if receive.amount != state.amount {
+        return Err(ContractError::WrongAmount {
+            expected: state.amount,
+            received: receive.amount,
+        });
+    }
+    state.status = AuctionStatus::Open;
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "fund"), attr("amount", receive.amount)],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Bid
+///
+/// Buys all tokens for `price * amount` of the sent coins, which go to the seller. Coins
+/// sent beyond that are refunded, so bidders can send a little more to cover the blocks
+/// until their bid is included.
+fn try_bid(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if state.status != AuctionStatus::Open {
+        return Err(ContractError::NotOpen {});
+    }
+    if env.block.height < state.start_height {
+        return Err(ContractError::NotStarted {
+            start_height: state.start_height,
+        });
+    }
+    if env.block.height >= state.end_height {
+        return Err(ContractError::Ended {
+            end_height: state.end_height,
+        });
+    }
+    let sent = native_amount(&info, &state.denom)?;
+    let price = state.price(env.block.height);
+    let total = price.checked_mul(state.amount)?;
+    if sent < total {
+        return Err(ContractError::BidTooLow {
+            sent,
+            required: total,
+        });
+    }
+    let refund = sent.checked_sub(total)?;
+    state.status = AuctionStatus::Sold {
+        buyer: info.sender.clone(),
+        price,
+    };
+    config(deps.storage).save(&state)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), state.amount);
+    let mut messages = vec![transfer.into_cosmos_msg(&state.token)?];
+    // a zero price leaves nothing to pay
+    if !total.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: state.seller.to_string(),
+                amount: coins(total.u128(), &state.denom),
+            }
+            .into(),
+        );
+    }
+    if !refund.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(refund.u128(), &state.denom),
+            }
+            .into(),
+        );
+    }
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", "bid"),
+            attr("buyer", info.sender),
+            attr("price", price),
+            attr("total", total),
+            attr("refund", refund),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Withdraw
+///
+/// Returns the unsold tokens to the seller once the auction ended without a bid.
+fn try_withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+    if state.status != AuctionStatus::Open {
+        return Err(ContractError::NotOpen {});
+    }
+    if env.block.height < state.end_height {
+        return Err(ContractError::NotEnded {
+            end_height: state.end_height,
+        });
+    }
+    state.status = AuctionStatus::Withdrawn;
+    config(deps.storage).save(&state)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(state.seller.as_str(), state.amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.token)?],
+        attributes: vec![
+            attr("action", "withdraw"),
+            attr("seller", state.seller),
+            attr("amount", state.amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// The amount of the single `denom` coin sent along
+fn native_amount(info: &MessageInfo, denom: &str) -> Result<Uint128, ContractError> {
+    match info.funds.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => Ok(coin.amount),
+        _ => Err(ContractError::InvalidFunds {
+            denom: denom.to_string(),
+        }),
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Price {} => {
+            let state = config_read(deps.storage).load()?;
+            let price = state.price(env.block.height);
+            to_binary(&PriceResponse {
+                price,
+                total: price.checked_mul(state.amount)?,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coin, from_binary, Addr, CosmosMsg, OwnedDeps};
+
+    fn init_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            token: "token".to_string(),
+            amount: Uint128::from(1000u128),
+            denom: "ucosm".to_string(),
+            start_price: Uint128::from(100u128),
+            end_price: Uint128::from(20u128),
+            start_height: 100,
+            end_height: 200,
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("seller", &[]);
+        instantiate(deps.as_mut(), env_at(50), info, init_msg()).unwrap();
+        deps
+    }
+
+    /// An auction funded with all 1000 tokens
+    fn setup_funded() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = setup();
+        fund(deps.as_mut(), "seller", 1000).unwrap();
+        deps
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn fund(deps: DepsMut, sender: &str, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(amount),
+            msg: None,
+        });
+        execute(deps, env_at(60), mock_info("token", &[]), msg)
+    }
+
+    fn bid(deps: DepsMut, height: u64, sent: u128) -> Result<Response, ContractError> {
+        let info = mock_info("bidder", &[coin(sent, "ucosm")]);
+        execute(deps, env_at(height), info, ExecuteMsg::Bid {})
+    }
+
+    fn price(deps: Deps, height: u64) -> PriceResponse {
+        from_binary(&query(deps, env_at(height), QueryMsg::Price {}).unwrap()).unwrap()
+    }
+
+    fn status(deps: Deps) -> AuctionStatus {
+        let state: Config =
+            from_binary(&query(deps, mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        state.status
+    }
+
+    fn bank_send(to: &str, amount: u128) -> CosmosMsg {
+        BankMsg::Send {
+            to_address: to.to_string(),
+            amount: coins(amount, "ucosm"),
+        }
+        .into()
+    }
+
+    fn token_transfer(to: &str, amount: u128) -> CosmosMsg {
+        cw_erc20::ExecuteMsg::transfer(to, amount)
+            .into_cosmos_msg("token")
+            .unwrap()
+    }
+
+    #[test]
+    fn validates_auction() {
+        let mut deps = mock_dependencies(&[]);
+        for &(start, end) in &[(100, 100), (200, 100)] {
+            let msg = InstantiateMsg {
+                start_height: start,
+                end_height: end,
+                ..init_msg()
+            };
+            match instantiate(deps.as_mut(), env_at(50), mock_info("seller", &[]), msg).unwrap_err()
+            {
+                ContractError::InvalidSchedule {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        let msg = InstantiateMsg {
+            end_price: Uint128::from(101u128),
+            ..init_msg()
+        };
+        match instantiate(deps.as_mut(), env_at(50), mock_info("seller", &[]), msg).unwrap_err() {
+            ContractError::InvalidPrices {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = InstantiateMsg {
+            amount: Uint128::zero(),
+            ..init_msg()
+        };
+        match instantiate(deps.as_mut(), env_at(50), mock_info("seller", &[]), msg).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // a fixed price is allowed
+        let msg = InstantiateMsg {
+            end_price: Uint128::from(100u128),
+            ..init_msg()
+        };
+        instantiate(deps.as_mut(), env_at(50), mock_info("seller", &[]), msg).unwrap();
+        let state: Config =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(state.seller, "seller");
+        assert_eq!(state.status, AuctionStatus::Unfunded);
+    }
+
+    #[test]
+    fn price_falls_linearly() {
+        let deps = setup();
+        let expected = [
+            (0, 100),
+            (100, 100),
+            // 0.8 off, rounded up in favour of the seller
+            (101, 100),
+            (102, 99),
+            (125, 80),
+            (150, 60),
+            (199, 21),
+            (200, 20),
+            (u64::MAX, 20),
+        ];
+        for &(height, expected) in &expected {
+            let res = price(deps.as_ref(), height);
+            assert_eq!(res.price.u128(), expected, "height {}", height);
+            assert_eq!(res.total.u128(), expected * 1000, "height {}", height);
+        }
+    }
+
+    #[test]
+    fn funds_once_with_exact_amount() {
+        let mut deps = setup();
+        // only the seller's tokens are accepted
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "seller".to_string(),
+            amount: Uint128::from(1000u128),
+            msg: None,
+        });
+        match execute(
+            deps.as_mut(),
+            env_at(60),
+            mock_info("other_token", &[]),
+            msg,
+        )
+        .unwrap_err()
+        {
+            ContractError::WrongToken { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match fund(deps.as_mut(), "stranger", 1000).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match fund(deps.as_mut(), "seller", 999).unwrap_err() {
+            ContractError::WrongAmount { expected, received } => {
+                assert_eq!(expected.u128(), 1000);
+                assert_eq!(received.u128(), 999);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // nothing can be bought before funding
+        match bid(deps.as_mut(), 150, 60_000).unwrap_err() {
+            ContractError::NotOpen {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        fund(deps.as_mut(), "seller", 1000).unwrap();
+        assert_eq!(status(deps.as_ref()), AuctionStatus::Open);
+        match fund(deps.as_mut(), "seller", 1000).unwrap_err() {
+            ContractError::NotOpen {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn bid_below_price_fails() {
+        let mut deps = setup_funded();
+        // 60 per token at height 150
+        match bid(deps.as_mut(), 150, 59_999).unwrap_err() {
+            ContractError::BidTooLow { sent, required } => {
+                assert_eq!(sent.u128(), 59_999);
+                assert_eq!(required.u128(), 60_000);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // the same coins are enough once the price dropped to 59
+        bid(deps.as_mut(), 152, 59_999).unwrap();
+    }
+
+    #[test]
+    fn bid_pays_seller_and_refunds_excess() {
+        let mut deps = setup_funded();
+        let res = bid(deps.as_mut(), 150, 65_000).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                token_transfer("bidder", 1000),
+                bank_send("seller", 60_000),
+                bank_send("bidder", 5_000),
+            ]
+        );
+        assert_eq!(
+            status(deps.as_ref()),
+            AuctionStatus::Sold {
+                buyer: Addr::unchecked("bidder"),
+                price: Uint128::from(60u128),
+            }
+        );
+
+        // the exact price leaves nothing to refund
+        let mut deps = setup_funded();
+        let res = bid(deps.as_mut(), 100, 100_000).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![token_transfer("bidder", 1000), bank_send("seller", 100_000)]
+        );
+        // and the tokens are sold only once
+        match bid(deps.as_mut(), 101, 100_000).unwrap_err() {
+            ContractError::NotOpen {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn bids_only_while_running() {
+        let mut deps = setup_funded();
+        match bid(deps.as_mut(), 99, 100_000).unwrap_err() {
+            ContractError::NotStarted { start_height } => assert_eq!(start_height, 100),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match bid(deps.as_mut(), 200, 100_000).unwrap_err() {
+            ContractError::Ended { end_height } => assert_eq!(end_height, 200),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // bids are paid in the auction's denom only
+        let info = mock_info("bidder", &[coin(100_000, "uatom")]);
+        match execute(deps.as_mut(), env_at(150), info, ExecuteMsg::Bid {}).unwrap_err() {
+            ContractError::InvalidFunds { denom } => assert_eq!(denom, "ucosm"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let info = mock_info("bidder", &[]);
+        match execute(deps.as_mut(), env_at(150), info, ExecuteMsg::Bid {}).unwrap_err() {
+            ContractError::InvalidFunds { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn seller_withdraws_unsold_tokens() {
+        let mut deps = setup_funded();
+        let withdraw = |deps: DepsMut, sender: &str, height: u64| {
+            execute(
+                deps,
+                env_at(height),
+                mock_info(sender, &[]),
+                ExecuteMsg::Withdraw {},
+            )
+        };
+        match withdraw(deps.as_mut(), "seller", 199).unwrap_err() {
+            ContractError::NotEnded { end_height } => assert_eq!(end_height, 200),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match withdraw(deps.as_mut(), "bidder", 200).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = withdraw(deps.as_mut(), "seller", 200).unwrap();
+        assert_eq!(res.messages, vec![token_transfer("seller", 1000)]);
+        assert_eq!(status(deps.as_ref()), AuctionStatus::Withdrawn);
+        match withdraw(deps.as_mut(), "seller", 201).unwrap_err() {
+            ContractError::NotOpen {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // sold tokens cannot be withdrawn
+        let mut deps = setup_funded();
+        bid(deps.as_mut(), 150, 60_000).unwrap();
+        match withdraw(deps.as_mut(), "seller", 200).unwrap_err() {
+            ContractError::NotOpen {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
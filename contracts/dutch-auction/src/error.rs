@@ -0,0 +1,50 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Auction must end after it starts")]
+    InvalidSchedule {},
+
+    #[error("End price must not exceed the start price")]
+    InvalidPrices {},
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Only {token} tokens are accepted")]
+    WrongToken { token: String },
+
+    #[error("Expected {expected} tokens, received {received}")]
+    WrongAmount {
+        expected: Uint128,
+        received: Uint128,
+    },
+
+    #[error("Expected a single non-zero {denom} coin")]
+    InvalidFunds { denom: String },
+
+    #[error("Auction is not open")]
+    NotOpen {},
+
+    #[error("Auction starts at height {start_height}")]
+    NotStarted { start_height: u64 },
+
+    #[error("Auction ended at height {end_height}")]
+    Ended { end_height: u64 },
+
+    #[error("Auction ends at height {end_height}")]
+    NotEnded { end_height: u64 },
+
+    #[error("Sent {sent}, less than the current price of {required}")]
+    BidTooLow { sent: Uint128, required: Uint128 },
+}
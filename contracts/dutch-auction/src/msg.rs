@@ -0,0 +1,49 @@
+use cosmwasm_std::Uint128;
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token being sold. The instantiating address becomes the seller.
+    pub token: String,
+    /// Tokens sold, all to a single bidder
+    pub amount: Uint128,
+    /// The native coin bids are paid with
+    pub denom: String,
+    /// Price of one token base unit in `denom` at `start_height`
+    pub start_price: Uint128,
+    /// Price of one token base unit in `denom` at `end_height`
+    pub end_price: Uint128,
+    pub start_height: u64,
+    pub end_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Opens the auction once the seller sent exactly `amount` tokens with the erc20 `Send`
+    /// message
+    Receive(ReceiveMsg),
+    /// Buys all tokens at the current price with the sent coins and refunds the rest
+    Bid {},
+    /// Returns the tokens to the seller if nobody bought them before the end. Only the
+    /// seller can do this.
+    Withdraw {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Returns the price at the current height
+    Price {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceResponse {
+    /// Price of one token base unit
+    pub price: Uint128,
+    /// Price of all tokens, which a bid has to pay
+    pub total: Uint128,
+}
@@ -0,0 +1,69 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The erc20 token being sold
+    pub token: Addr,
+    /// Funds the auction and receives the proceeds
+    pub seller: Addr,
+    /// The native coin bids are paid with
+    pub denom: String,
+    /// Tokens sold, all to a single bidder
+    pub amount: Uint128,
+    /// Price of one token base unit at `start_height`
+    pub start_price: Uint128,
+    /// Price of one token base unit at `end_height`
+    pub end_price: Uint128,
+    /// Bids are accepted from this height on
+    pub start_height: u64,
+    /// Bids are accepted up to, but not including, this height
+    pub end_height: u64,
+    pub status: AuctionStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionStatus {
+    /// Waiting for the seller to send the tokens
+    Unfunded,
+    Open,
+    Sold {
+        buyer: Addr,
+        price: Uint128,
+    },
+    /// The seller took the unsold tokens back
+    Withdrawn,
+}
+
+impl Config {
+    /// Price of one token base unit at `height`. It falls linearly from `start_price` to
+    /// `end_price`, rounded up in favour of the seller.
+    pub fn price(&self, height: u64) -> Uint128 {
+        if height <= self.start_height {
+            self.start_price
+        } else if height >= self.end_height {
+            self.end_price
+        } else {
+            // instantiate ensures end_price <= start_price
+            let range = self.start_price.u128() - self.end_price.u128();
+            let decay = Uint128::from(range).multiply_ratio(
+                height - self.start_height,
+                self.end_height - self.start_height,
+            );
+            Uint128::from(self.start_price.u128() - decay.u128())
+        }
+    }
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
@@ -0,0 +1,88 @@
+//! Runs the auction against a real erc20 contract on a test chain, which keeps the native coins
+//! in its bank and reverts failing messages like a real chain.
+
+use cosmwasm_std::{coins, Uint128};
+use cw_dutch_auction::contract::{execute, instantiate, query};
+use cw_dutch_auction::msg::{ExecuteMsg, InstantiateMsg};
+use cw_erc20::BalanceResponse;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const AUCTION: &str = "auction";
+const DENOM: &str = "ucosm";
+
+/// The seller auctions 1000 of their 5000 tokens from 100 coins each at height 100 down to 20
+/// at height 200. alice holds coins.
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(50);
+    let msg = erc20_msg("AUCT", &[("seller", 5000)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let auction = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        amount: Uint128::from(1000u128),
+        denom: DENOM.to_string(),
+        start_price: Uint128::from(100u128),
+        end_price: Uint128::from(20u128),
+        start_height: 100,
+        end_height: 200,
+    };
+    chain
+        .instantiate(auction, AUCTION, "seller", &msg, &[])
+        .unwrap();
+    chain.set_balance("alice", &coins(1_000_000, DENOM));
+    chain
+}
+
+fn fund(chain: &mut Chain) {
+    let msg = cw_erc20::ExecuteMsg::send(AUCTION, 1000u128, None);
+    chain.execute(TOKEN, "seller", &msg, &[]).unwrap();
+}
+
+fn tokens(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+#[test]
+fn sells_to_first_bidder() {
+    let mut chain = setup();
+    fund(&mut chain);
+    assert_eq!(tokens(&chain, "seller"), 4000);
+    assert_eq!(tokens(&chain, AUCTION), 1000);
+
+    chain.set_height(150);
+    let err = chain
+        .execute(AUCTION, "alice", &ExecuteMsg::Bid {}, &coins(59_999, DENOM))
+        .unwrap_err();
+    assert_eq!(err, "Sent 59999, less than the current price of 60000");
+    assert_eq!(chain.balance("alice", DENOM), 1_000_000);
+    assert_eq!(chain.balance(AUCTION, DENOM), 0);
+    chain
+        .execute(AUCTION, "alice", &ExecuteMsg::Bid {}, &coins(65_000, DENOM))
+        .unwrap();
+    assert_eq!(tokens(&chain, "alice"), 1000);
+    assert_eq!(tokens(&chain, AUCTION), 0);
+    assert_eq!(chain.balance("alice", DENOM), 940_000);
+    assert_eq!(chain.balance("seller", DENOM), 60_000);
+    assert_eq!(chain.balance(AUCTION, DENOM), 0);
+}
+
+#[test]
+fn seller_withdraws_without_bid() {
+    let mut chain = setup();
+    fund(&mut chain);
+    chain.set_height(200);
+    let err = chain
+        .execute(AUCTION, "alice", &ExecuteMsg::Bid {}, &coins(20_000, DENOM))
+        .unwrap_err();
+    assert_eq!(err, "Auction ended at height 200");
+    chain
+        .execute(AUCTION, "seller", &ExecuteMsg::Withdraw {}, &[])
+        .unwrap();
+    assert_eq!(tokens(&chain, "seller"), 5000);
+    assert_eq!(tokens(&chain, AUCTION), 0);
+}
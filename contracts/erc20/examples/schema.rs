@@ -4,7 +4,12 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use cw_erc20::{
-    AllowanceResponse, BalanceResponse, Constants, ExecuteMsg, InstantiateMsg, QueryMsg,
+    AllAccountsResponse, AllowanceResponse, BalanceResponse, BlacklistedResponse,
+    ConditionResponse, Constants, ContractVersion, DelegationResponse, ExecuteMsg, FrozenResponse,
+    HumanizeResponse, InstantiateMsg, Marketing, MaxSupplyResponse, MigrateMsg,
+    MintProposalResponse, MinterResponse, PausedResponse, PendingUpgradeResponse, PermitMsg,
+    PermitNonceResponse, QueryMsg, ReceiveMsg, SupplyHistoryResponse, TokenInfoResponse,
+    TopHoldersResponse, VersionedExecuteMsg, VotesResponse, VotingPowerResponse, WhitelistResponse,
 };
 
 fn main() {
@@ -15,8 +20,32 @@ fn main() {
 
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(VersionedExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
     export_schema(&schema_for!(BalanceResponse), &out_dir);
     export_schema(&schema_for!(AllowanceResponse), &out_dir);
+    export_schema(&schema_for!(AllAccountsResponse), &out_dir);
+    export_schema(&schema_for!(WhitelistResponse), &out_dir);
+    export_schema(&schema_for!(TopHoldersResponse), &out_dir);
+    export_schema(&schema_for!(VotingPowerResponse), &out_dir);
+    export_schema(&schema_for!(VotesResponse), &out_dir);
+    export_schema(&schema_for!(DelegationResponse), &out_dir);
+    export_schema(&schema_for!(SupplyHistoryResponse), &out_dir);
+    export_schema(&schema_for!(MaxSupplyResponse), &out_dir);
+    export_schema(&schema_for!(TokenInfoResponse), &out_dir);
+    export_schema(&schema_for!(PausedResponse), &out_dir);
+    export_schema(&schema_for!(FrozenResponse), &out_dir);
+    export_schema(&schema_for!(BlacklistedResponse), &out_dir);
+    export_schema(&schema_for!(PermitNonceResponse), &out_dir);
+    export_schema(&schema_for!(PermitMsg), &out_dir);
+    export_schema(&schema_for!(MinterResponse), &out_dir);
+    export_schema(&schema_for!(HumanizeResponse), &out_dir);
+    export_schema(&schema_for!(PendingUpgradeResponse), &out_dir);
+    export_schema(&schema_for!(MintProposalResponse), &out_dir);
+    export_schema(&schema_for!(ConditionResponse), &out_dir);
     export_schema(&schema_for!(Constants), &out_dir);
+    export_schema(&schema_for!(Marketing), &out_dir);
+    export_schema(&schema_for!(ContractVersion), &out_dir);
 }
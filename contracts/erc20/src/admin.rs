@@ -0,0 +1,271 @@
+//! Address based permissions for admin-gated handlers.
+//!
+//! The stores work on any `Storage` and fail with `AdminError`, which converts into the
+//! contract's error type, so other contracts can use them as well.
+
+use cosmwasm_std::{from_slice, to_vec, Addr, StdError, StdResult, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AdminError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("No admin set")]
+    NoAdmin {},
+}
+
+/// A role held by at most one address, e.g. the minter.
+///
+/// The holder hands the role over in two steps: `propose` a successor, who then has to
+/// `accept` it. A mistyped address therefore cannot lock the role away.
+pub struct AdminStore<'a> {
+    namespace: &'a [u8],
+    key: &'a [u8],
+    pending_key: &'a [u8],
+}
+
+impl<'a> AdminStore<'a> {
+    pub const fn new(namespace: &'a [u8], key: &'a [u8], pending_key: &'a [u8]) -> Self {
+        AdminStore {
+            namespace,
+            key,
+            pending_key,
+        }
+    }
+
+    pub fn load(&self, store: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.read(store, self.key)
+    }
+
+    /// Sets the role holder, or leaves the role unassigned for `None`.
+    /// Drops any pending transfer.
+    pub fn save(&self, store: &mut dyn Storage, admin: Option<&Addr>) -> StdResult<()> {
+        self.write(store, self.pending_key, None)?;
+        self.write(store, self.key, admin)
+    }
+
+    /// Fails unless `sender` holds the role
+    pub fn assert_admin(&self, store: &dyn Storage, sender: &Addr) -> Result<(), AdminError> {
+        match self.load(store)? {
+            Some(admin) if admin == *sender => Ok(()),
+            Some(_) => Err(AdminError::Unauthorized {}),
+            None => Err(AdminError::NoAdmin {}),
+        }
+    }
+
+    /// The address the role is being handed over to, if any
+    pub fn load_pending(&self, store: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.read(store, self.pending_key)
+    }
+
+    /// Offers the role to `candidate`. Only the current holder can do this, and a new
+    /// proposal replaces the previous one.
+    pub fn propose(
+        &self,
+        store: &mut dyn Storage,
+        sender: &Addr,
+        candidate: &Addr,
+    ) -> Result<(), AdminError> {
+        self.assert_admin(store, sender)?;
+        self.write(store, self.pending_key, Some(candidate))?;
+        Ok(())
+    }
+
+    /// Completes the transfer. Only the proposed candidate can do this.
+    pub fn accept(&self, store: &mut dyn Storage, sender: &Addr) -> Result<(), AdminError> {
+        if self.load_pending(store)?.as_ref() != Some(sender) {
+            return Err(AdminError::Unauthorized {});
+        }
+        self.save(store, Some(sender))?;
+        Ok(())
+    }
+
+    fn read(&self, store: &dyn Storage, key: &[u8]) -> StdResult<Option<Addr>> {
+        let namespace_store = ReadonlyPrefixedStorage::new(store, self.namespace);
+        match namespace_store.get(key) {
+            Some(data) => Ok(Some(from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn write(&self, store: &mut dyn Storage, key: &[u8], value: Option<&Addr>) -> StdResult<()> {
+        let mut namespace_store = PrefixedStorage::new(store, self.namespace);
+        match value {
+            Some(address) => namespace_store.set(key, &to_vec(address)?),
+            None => namespace_store.remove(key),
+        }
+        Ok(())
+    }
+}
+
+/// Marks a renounced `AdminSet`. Addresses are never empty, so it cannot clash with a member.
+const RENOUNCED_KEY: &[u8] = b"";
+
+/// A role shared by any number of addresses, e.g. the token admins
+pub struct AdminSet<'a> {
+    prefix: &'a [u8],
+}
+
+impl<'a> AdminSet<'a> {
+    pub const fn new(prefix: &'a [u8]) -> Self {
+        AdminSet { prefix }
+    }
+
+    pub fn contains(&self, store: &dyn Storage, address: &Addr) -> bool {
+        let admins_store = ReadonlyPrefixedStorage::new(store, self.prefix);
+        !self.is_renounced(store) && admins_store.get(address.as_str().as_bytes()).is_some()
+    }
+
+    /// Whether the role was given up with `renounce`
+    pub fn is_renounced(&self, store: &dyn Storage) -> bool {
+        let admins_store = ReadonlyPrefixedStorage::new(store, self.prefix);
+        admins_store.get(RENOUNCED_KEY).is_some()
+    }
+
+    /// Revokes the role of all members for good. Addresses added afterwards are ignored.
+    pub fn renounce(&self, store: &mut dyn Storage) {
+        let mut admins_store = PrefixedStorage::new(store, self.prefix);
+        admins_store.set(RENOUNCED_KEY, &[1]);
+    }
+
+    pub fn add(&self, store: &mut dyn Storage, address: &Addr) {
+        let mut admins_store = PrefixedStorage::new(store, self.prefix);
+        admins_store.set(address.as_str().as_bytes(), &[1]);
+    }
+
+    pub fn remove(&self, store: &mut dyn Storage, address: &Addr) {
+        let mut admins_store = PrefixedStorage::new(store, self.prefix);
+        admins_store.remove(address.as_str().as_bytes());
+    }
+
+    /// Fails unless `sender` is in the set
+    pub fn assert_admin(&self, store: &dyn Storage, sender: &Addr) -> Result<(), AdminError> {
+        if self.is_renounced(store) {
+            Err(AdminError::NoAdmin {})
+        } else if self.contains(store, sender) {
+            Ok(())
+        } else {
+            Err(AdminError::Unauthorized {})
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    const MINTER: AdminStore = AdminStore::new(b"config", b"minter", b"pending_minter");
+    const ADMINS: AdminSet = AdminSet::new(b"admins");
+
+    fn addr(address: &str) -> Addr {
+        Addr::unchecked(address)
+    }
+
+    #[test]
+    fn assert_admin_works() {
+        let mut store = MockStorage::new();
+        MINTER.save(&mut store, Some(&addr("minter"))).unwrap();
+        assert_eq!(MINTER.load(&store).unwrap(), Some(addr("minter")));
+        MINTER.assert_admin(&store, &addr("minter")).unwrap();
+
+        match MINTER.assert_admin(&store, &addr("addr0000")).unwrap_err() {
+            AdminError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn assert_admin_fails_without_admin() {
+        let mut store = MockStorage::new();
+        match MINTER.assert_admin(&store, &addr("minter")).unwrap_err() {
+            AdminError::NoAdmin {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        MINTER.save(&mut store, Some(&addr("minter"))).unwrap();
+        MINTER.save(&mut store, None).unwrap();
+        assert_eq!(MINTER.load(&store).unwrap(), None);
+        match MINTER.assert_admin(&store, &addr("minter")).unwrap_err() {
+            AdminError::NoAdmin {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn transfers_in_two_steps() {
+        let mut store = MockStorage::new();
+        MINTER.save(&mut store, Some(&addr("minter"))).unwrap();
+
+        match MINTER
+            .propose(&mut store, &addr("addr0000"), &addr("addr0000"))
+            .unwrap_err()
+        {
+            AdminError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        MINTER
+            .propose(&mut store, &addr("minter"), &addr("successor"))
+            .unwrap();
+        assert_eq!(
+            MINTER.load_pending(&store).unwrap(),
+            Some(addr("successor"))
+        );
+        // the role only moves once accepted
+        MINTER.assert_admin(&store, &addr("minter")).unwrap();
+
+        match MINTER.accept(&mut store, &addr("minter")).unwrap_err() {
+            AdminError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        MINTER.accept(&mut store, &addr("successor")).unwrap();
+        assert_eq!(MINTER.load(&store).unwrap(), Some(addr("successor")));
+        assert_eq!(MINTER.load_pending(&store).unwrap(), None);
+
+        // cannot be accepted twice
+        match MINTER.accept(&mut store, &addr("successor")).unwrap_err() {
+            AdminError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn admin_set_works() {
+        let mut store = MockStorage::new();
+        ADMINS.add(&mut store, &addr("admin1"));
+        ADMINS.add(&mut store, &addr("admin2"));
+        ADMINS.assert_admin(&store, &addr("admin1")).unwrap();
+        ADMINS.assert_admin(&store, &addr("admin2")).unwrap();
+
+        ADMINS.remove(&mut store, &addr("admin1"));
+        assert!(!ADMINS.contains(&store, &addr("admin1")));
+        match ADMINS.assert_admin(&store, &addr("admin1")).unwrap_err() {
+            AdminError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn admin_set_renounces() {
+        let mut store = MockStorage::new();
+        ADMINS.add(&mut store, &addr("admin1"));
+        ADMINS.add(&mut store, &addr("admin2"));
+        assert!(!ADMINS.is_renounced(&store));
+
+        ADMINS.renounce(&mut store);
+        assert!(ADMINS.is_renounced(&store));
+        ADMINS.add(&mut store, &addr("admin3"));
+        for admin in &["admin1", "admin2", "admin3"] {
+            assert!(!ADMINS.contains(&store, &addr(admin)));
+            match ADMINS.assert_admin(&store, &addr(admin)).unwrap_err() {
+                AdminError::NoAdmin {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+}
@@ -1,37 +1,132 @@
+#[cfg(feature = "iterator")]
+use cosmwasm_std::Order;
 use cosmwasm_std::{
-    attr, entry_point, to_binary, to_vec, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Storage, Uint128,
+    attr, from_slice, to_binary, to_vec, Addr, Api, Binary, BlockInfo, CosmosMsg, Deps, DepsMut,
+    Env, MessageInfo, QueryRequest, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+    WasmQuery,
 };
 use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use sha2::{Digest, Sha256};
 use std::convert::TryInto;
 
-use crate::error::ContractError;
-use crate::msg::{AllowanceResponse, BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::Constants;
+use crate::admin::{AdminSet, AdminStore};
+use crate::error::{ContractError, ResultExt};
+use crate::msg::{
+    AdminAction, AirdropEntry, AllowanceResponse, BalanceResponse, BlacklistedResponse,
+    ConditionResponse, DelegationResponse, ExecuteMsg, Expiration, FrozenResponse, GenesisConfig,
+    HumanizeResponse, InitialBalance, InstantiateMsg, MaxSupplyResponse, MintProposalResponse,
+    MinterResponse, PausedResponse, PendingUpgradeResponse, PermitMsg, PermitNonceResponse,
+    QueryMsg, ReceiveMsg, SupplyHistoryEntry, SupplyHistoryResponse, TokenInfoResponse,
+    VotesResponse, VotingPowerResponse,
+};
+#[cfg(feature = "iterator")]
+use crate::msg::{AllAccountsResponse, HolderBalance, TopHoldersResponse, WhitelistResponse};
+#[cfg(feature = "iterator")]
+use crate::ranking::{clear_ranking, rank_balance, top_balances};
+#[cfg(feature = "iterator")]
+use crate::snapshots::balance_at;
+use crate::snapshots::record_balance;
+use crate::state::{
+    query_constants, query_legacy_minter, query_total_supply, ConditionalTransfer, Constants,
+    Marketing, MintApproval, MintProposal, MintRateLimit, MintSchedule, PendingUpgrade,
+    QueuedAdminAction, VotingLock,
+};
+use crate::transaction::StorageTransaction;
+use crate::units::format_units;
+use crate::version::{
+    read_contract_version, write_contract_version, MigrateMsg, CONTRACT_NAME, CONTRACT_VERSION,
+};
 
 pub const PREFIX_CONFIG: &[u8] = b"config";
 pub const PREFIX_BALANCES: &[u8] = b"balances";
 pub const PREFIX_ALLOWANCES: &[u8] = b"allowances";
+pub const PREFIX_ALLOWANCE_EXPIRIES: &[u8] = b"allowance_expiries";
+pub const PREFIX_MINT_SCHEDULES: &[u8] = b"mint_schedules";
+pub const PREFIX_VOTING_LOCKS: &[u8] = b"voting_locks";
+pub const PREFIX_DELEGATES: &[u8] = b"delegates";
+pub const PREFIX_DELEGATED_BALANCE: &[u8] = b"delegated_balance";
+pub const PREFIX_CONDITIONAL_TRANSFERS: &[u8] = b"conditional_transfers";
+pub const PREFIX_GENESIS_CHUNKS: &[u8] = b"genesis_chunks";
+pub const PREFIX_BLACKLIST: &[u8] = b"blacklist";
+pub const PREFIX_FROZEN: &[u8] = b"frozen";
+pub const PREFIX_WHITELIST: &[u8] = b"whitelist";
+pub const PREFIX_PERMIT_KEYS: &[u8] = b"permit_keys";
+pub const PREFIX_NONCES: &[u8] = b"nonces";
+pub const PREFIX_ADMINS: &[u8] = b"admins";
+pub const PREFIX_ADMIN_ACTIONS: &[u8] = b"admin_actions";
+pub const PREFIX_SUPPLY_HISTORY: &[u8] = b"supply_history";
+pub const PREFIX_MINT_PROPOSALS: &[u8] = b"mint_proposals";
 
 pub const KEY_CONSTANTS: &[u8] = b"constants";
 pub const KEY_TOTAL_SUPPLY: &[u8] = b"total_supply";
+pub const KEY_MINT_SCHEDULE_COUNT: &[u8] = b"mint_schedule_count";
+pub const KEY_CONDITIONAL_TRANSFER_COUNT: &[u8] = b"conditional_transfer_count";
+pub const KEY_GENESIS_CONFIG: &[u8] = b"genesis_config";
+pub const KEY_MINT_RATE_LIMIT: &[u8] = b"mint_rate_limit";
+pub const KEY_ADMIN_DELAY: &[u8] = b"admin_delay";
+pub const KEY_ADMIN_ACTION_COUNT: &[u8] = b"admin_action_count";
+pub const KEY_SUPPLY_HISTORY_COUNT: &[u8] = b"supply_history_count";
+pub const KEY_MAX_SUPPLY: &[u8] = b"max_supply";
+pub const KEY_PENDING_UPGRADE: &[u8] = b"pending_upgrade";
+pub const KEY_MINTER: &[u8] = b"minter";
+pub const KEY_PENDING_MINTER: &[u8] = b"pending_minter";
+pub const KEY_MINT_APPROVAL: &[u8] = b"mint_approval";
+pub const KEY_FLAGS: &[u8] = b"flags";
+pub const KEY_MARKETING: &[u8] = b"marketing";
+pub const KEY_MINT_PROPOSAL_COUNT: &[u8] = b"mint_proposal_count";
+
+/// The only address allowed to create mint schedules. No minting is possible when unset.
+pub const MINTER: AdminStore = AdminStore::new(PREFIX_CONFIG, KEY_MINTER, KEY_PENDING_MINTER);
+pub const ADMINS: AdminSet = AdminSet::new(PREFIX_ADMINS);
+
+/// Blocks between initiating and executing an upgrade
+pub const UPGRADE_DELAY: u64 = 48;
+
+/// Supply history entries returned by default and at most
+pub const DEFAULT_SUPPLY_HISTORY_LIMIT: u32 = 10;
+pub const MAX_SUPPLY_HISTORY_LIMIT: u32 = 30;
+
+/// Accounts returned by default and at most
+pub const DEFAULT_ACCOUNTS_LIMIT: u32 = 10;
+pub const MAX_ACCOUNTS_LIMIT: u32 = 30;
+
+/// Holders returned at most by `TopHolders`
+pub const MAX_TOP_HOLDERS_LIMIT: u32 = 30;
+
+/// Most entries a single genesis airdrop chunk may contain
+pub const MAX_AIRDROP_CHUNK_SIZE: usize = 100;
+
+/// Most recipients a single batch transfer may credit
+pub const MAX_BATCH_TRANSFERS: usize = 50;
+
+/// Most spenders a single `SweepExpiredAllowances` may check
+pub const MAX_SWEEP_SPENDERS: usize = 50;
+
+/// Longest marketing fields in bytes
+pub const MAX_PROJECT_LENGTH: usize = 128;
+pub const MAX_DESCRIPTION_LENGTH: usize = 512;
+pub const MAX_LOGO_URL_LENGTH: usize = 256;
+
+/// Longest possible voting lock (about 4 years of 6 second blocks).
+/// Locking for this long grants one unit of voting power per locked token.
+pub const MAX_LOCK_DURATION: u64 = 21_024_000;
 
-#[entry_point]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    let mut total_supply: u128 = 0;
-    {
-        // Initial balances
-        let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-        for row in msg.initial_balances {
-            let amount_raw = row.amount.u128();
-            balances_store.set(row.address.as_str().as_bytes(), &amount_raw.to_be_bytes());
-            total_supply += amount_raw;
-        }
+    let mut total_supply = Uint128::zero();
+    // Initial balances
+    for row in msg.initial_balances {
+        write_balance(
+            deps.storage,
+            env.block.height,
+            &Addr::unchecked(row.address),
+            row.amount.u128(),
+        )?;
+        total_supply = total_supply.checked_add(row.amount)?;
     }
 
     // Check name, symbol, decimals
@@ -45,6 +140,67 @@ pub fn instantiate(
         return Err(ContractError::DecimalsExceeded {});
     }
 
+    let minter = match msg.minter {
+        Some(minter) => Some(deps.api.addr_validate(&minter)?),
+        None => None,
+    };
+    let admins = validate_all_unique(deps.api, &msg.admins).context("invalid admins")?;
+    let mint_rate_limit = match msg.mint_limit_per_window {
+        Some(limit) => {
+            if msg.mint_window_blocks == 0 {
+                return Err(ContractError::InvalidMintWindow {});
+            }
+            Some(MintRateLimit {
+                limit,
+                window_blocks: msg.mint_window_blocks,
+                window_start_block: 0,
+                minted_in_window: Uint128::zero(),
+            })
+        }
+        None => None,
+    };
+    let mint_approval = match msg.mint_approval {
+        Some(config) => {
+            let approvers = validate_all_unique(deps.api, &config.approvers)
+                .context("invalid mint approvers")?;
+            if config.threshold == 0 || config.threshold > approvers.len() as u64 {
+                return Err(ContractError::InvalidMintThreshold {
+                    approvers: approvers.len(),
+                });
+            }
+            if config.proposal_expiry == 0 {
+                return Err(ContractError::InvalidProposalExpiry {});
+            }
+            Some(MintApproval {
+                approvers,
+                threshold: config.threshold,
+                proposal_expiry: config.proposal_expiry,
+            })
+        }
+        None => None,
+    };
+    let marketing = match msg.marketing {
+        Some(config) => {
+            let marketing_admin = match config.marketing_admin {
+                Some(admin) => Some(
+                    deps.api
+                        .addr_validate(&admin)
+                        .context("invalid marketing admin")?,
+                ),
+                None => None,
+            };
+            let marketing = Marketing {
+                project: non_empty(config.project),
+                description: non_empty(config.description),
+                logo_url: non_empty(config.logo_url),
+                marketing_admin,
+            };
+            validate_marketing(&marketing)?;
+            Some(marketing)
+        }
+        None => None,
+    };
+
     let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
     let constants = to_vec(&Constants {
         name: msg.name,
@@ -52,34 +208,263 @@ pub fn instantiate(
         decimals: msg.decimals,
     })?;
     config_store.set(KEY_CONSTANTS, &constants);
-    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.u128().to_be_bytes());
+    if let Some(genesis) = msg.genesis {
+        config_store.set(KEY_GENESIS_CONFIG, &to_vec(&genesis)?);
+    }
+    if msg.admin_delay > 0 {
+        config_store.set(KEY_ADMIN_DELAY, &msg.admin_delay.to_be_bytes());
+    }
+    if msg.restricted {
+        config_store.set(KEY_FLAGS, &[FLAG_RESTRICTED]);
+    }
+    if let Some(mint_rate_limit) = mint_rate_limit {
+        config_store.set(KEY_MINT_RATE_LIMIT, &to_vec(&mint_rate_limit)?);
+    }
+    if let Some(mint_approval) = mint_approval {
+        config_store.set(KEY_MINT_APPROVAL, &to_vec(&mint_approval)?);
+    }
+    if let Some(marketing) = marketing {
+        config_store.set(KEY_MARKETING, &to_vec(&marketing)?);
+    }
+    MINTER.save(deps.storage, minter.as_ref())?;
+    for admin in admins {
+        ADMINS.add(deps.storage, &admin);
+    }
+    record_supply_change(deps.storage, env.block.height, total_supply.u128())?;
+    write_contract_version(deps.storage)?;
 
     Ok(Response::default())
 }
 
-#[entry_point]
 pub fn execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Checked here so no message, including those in a batch, can bypass the pause
+    let flags = read_flags(deps.storage);
+    if !matches!(msg, ExecuteMsg::Unpause {}) && flags.paused {
+        return Err(ContractError::Paused {});
+    }
+    let restricted = flags.restricted;
     match msg {
-        ExecuteMsg::Approve { spender, amount } => try_approve(deps, env, info, spender, &amount),
+        ExecuteMsg::Approve {
+            spender,
+            amount,
+            expires,
+        } => try_approve(deps, env, info, spender, &amount, expires),
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => try_increase_allowance(deps, env, info, spender, &amount, expires),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            exact,
+            expires,
+        } => try_decrease_allowance(deps, env, info, spender, &amount, exact, expires),
+        ExecuteMsg::SweepExpiredAllowances { owner, spenders } => {
+            try_sweep_expired_allowances(deps, env, info, owner, spenders)
+        }
+        ExecuteMsg::Permit {
+            owner,
+            spender,
+            amount,
+            nonce,
+            expiry,
+            signature,
+        } => try_permit(
+            deps, env, info, owner, spender, amount, nonce, expiry, signature,
+        ),
+        ExecuteMsg::SetPermitKey { pubkey } => try_set_permit_key(deps, env, info, pubkey),
         ExecuteMsg::Transfer { recipient, amount } => {
-            try_transfer(deps, env, info, recipient, &amount)
+            try_transfer(deps, env, info, recipient, &amount, restricted)
+        }
+        ExecuteMsg::TransferAll { recipient } => {
+            try_transfer_all(deps, env, info, recipient, restricted)
         }
+        ExecuteMsg::BatchTransfer { recipients } => {
+            try_batch_transfer(deps, env, info, recipients, restricted)
+        }
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => try_send(deps, env, info, contract, &amount, msg, restricted),
         ExecuteMsg::TransferFrom {
             owner,
             recipient,
             amount,
-        } => try_transfer_from(deps, env, info, owner, recipient, &amount),
+        } => try_transfer_from(deps, env, info, owner, recipient, &amount, restricted),
         ExecuteMsg::Burn { amount } => try_burn(deps, env, info, &amount),
+        ExecuteMsg::BurnFrom { owner, amount } => try_burn_from(deps, env, info, owner, &amount),
+        ExecuteMsg::SetMintSchedule {
+            recipient,
+            rate,
+            start,
+            end,
+        } => try_set_mint_schedule(deps, env, info, recipient, rate, start, end),
+        ExecuteMsg::ExecuteMintSchedule { schedule_id } => {
+            try_execute_mint_schedule(deps, env, info, schedule_id)
+        }
+        ExecuteMsg::Mint { recipient, amount } => try_mint(deps, env, info, recipient, amount),
+        ExecuteMsg::TransferMinter { address } => try_transfer_minter(deps, env, info, address),
+        ExecuteMsg::AcceptMinter {} => try_accept_minter(deps, env, info),
+        ExecuteMsg::UpdateMinter { new_minter } => try_update_minter(deps, env, info, new_minter),
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            logo_url,
+        } => try_update_marketing(deps, env, info, project, description, logo_url),
+        ExecuteMsg::ProposeMint { recipient, amount } => {
+            try_propose_mint(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::ApproveMint { mint_id } => try_approve_mint(deps, env, info, mint_id),
+        ExecuteMsg::ExecuteMint { mint_id } => try_execute_mint(deps, env, info, mint_id),
+        ExecuteMsg::LockForVoting {
+            amount,
+            lock_duration,
+        } => try_lock_for_voting(deps, env, info, &amount, lock_duration),
+        ExecuteMsg::Withdraw {} => try_withdraw(deps, env, info),
+        ExecuteMsg::Delegate { delegatee } => try_delegate(deps, env, info, delegatee),
+        ExecuteMsg::ScheduleConditional {
+            recipient,
+            amount,
+            oracle,
+            condition_query,
+            expiry,
+        } => try_schedule_conditional(
+            deps,
+            env,
+            info,
+            recipient,
+            &amount,
+            oracle,
+            condition_query,
+            expiry,
+        ),
+        ExecuteMsg::ExecuteConditional { transfer_id } => {
+            try_execute_conditional(deps, env, info, transfer_id)
+        }
+        ExecuteMsg::RefundConditional { transfer_id } => {
+            try_refund_conditional(deps, env, info, transfer_id)
+        }
+        ExecuteMsg::GenesisAirdrop {
+            entries,
+            attestation,
+        } => try_genesis_airdrop(deps, env, info, entries, attestation),
+        ExecuteMsg::AddBlacklist { address } => {
+            try_admin_action(deps, env, info, AdminAction::AddBlacklist { address })
+        }
+        ExecuteMsg::RemoveBlacklist { address } => {
+            try_admin_action(deps, env, info, AdminAction::RemoveBlacklist { address })
+        }
+        ExecuteMsg::AddToWhitelist { addresses } => {
+            try_admin_action(deps, env, info, AdminAction::AddToWhitelist { addresses })
+        }
+        ExecuteMsg::RemoveFromWhitelist { addresses } => try_admin_action(
+            deps,
+            env,
+            info,
+            AdminAction::RemoveFromWhitelist { addresses },
+        ),
+        ExecuteMsg::Clawback {
+            owner,
+            recipient,
+            amount,
+        } => try_admin_action(
+            deps,
+            env,
+            info,
+            AdminAction::Clawback {
+                owner,
+                recipient,
+                amount,
+            },
+        ),
+        ExecuteMsg::RenounceRoles {} => {
+            try_admin_action(deps, env, info, AdminAction::RenounceRoles {})
+        }
+        ExecuteMsg::AddAdmin { address } => {
+            try_admin_action(deps, env, info, AdminAction::AddAdmin { address })
+        }
+        ExecuteMsg::RemoveAdmin { address } => {
+            try_admin_action(deps, env, info, AdminAction::RemoveAdmin { address })
+        }
+        ExecuteMsg::ProposeAdminAction { action, eta } => {
+            try_propose_admin_action(deps, env, info, action, eta)
+        }
+        ExecuteMsg::ExecuteAdminAction { action_id } => {
+            try_execute_admin_action(deps, env, info, action_id)
+        }
+        ExecuteMsg::CancelAdminAction { action_id } => {
+            try_cancel_admin_action(deps, env, info, action_id)
+        }
+        ExecuteMsg::InitiateUpgrade {
+            new_code_id,
+            migrate_msg,
+        } => try_initiate_upgrade(deps, env, info, new_code_id, migrate_msg),
+        ExecuteMsg::ExecuteUpgrade {} => try_execute_upgrade(deps, env, info),
+        ExecuteMsg::CancelUpgrade {} => try_cancel_upgrade(deps, env, info),
+        ExecuteMsg::Pause {} => try_set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => try_set_paused(deps, info, false),
+        ExecuteMsg::Freeze { address } => {
+            try_admin_action(deps, env, info, AdminAction::Freeze { address })
+        }
+        ExecuteMsg::Unfreeze { address } => {
+            try_admin_action(deps, env, info, AdminAction::Unfreeze { address })
+        }
+        ExecuteMsg::RecoverOwn { recipient, amount } => try_admin_action(
+            deps,
+            env,
+            info,
+            AdminAction::RecoverOwn { recipient, amount },
+        ),
+        ExecuteMsg::Batch { msgs } => try_batch(deps, env, info, msgs),
+    }
+}
+
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // Instances created before versions were stored have no version entry
+    let previous = read_contract_version(deps.storage)?;
+    let from_version = match previous {
+        Some(version) if version.contract != CONTRACT_NAME => {
+            return Err(ContractError::CannotMigrate {
+                contract: version.contract,
+            })
+        }
+        Some(version) => version.version,
+        None => "unknown".to_string(),
+    };
+    write_contract_version(deps.storage)?;
+
+    if let Some(minter) = query_legacy_minter(deps.storage)? {
+        MINTER.save(deps.storage, Some(&minter))?;
+        // rewrite the constants without the minter
+        let constants = to_vec(&query_constants(deps.storage)?)?;
+        PrefixedStorage::new(deps.storage, PREFIX_CONFIG).set(KEY_CONSTANTS, &constants);
     }
+    migrate_legacy_flags(deps.storage);
+    #[cfg(feature = "iterator")]
+    rank_existing_balances(deps.storage)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "migrate"),
+            attr("from_version", from_version),
+            attr("to_version", CONTRACT_VERSION),
+        ],
+        data: None,
+    };
+    Ok(res)
 }
 
-#[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Balance { address } => {
             let address_key = deps.api.addr_validate(&address)?;
@@ -92,27 +477,181 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
         QueryMsg::Allowance { owner, spender } => {
             let owner_key = deps.api.addr_validate(&owner)?;
             let spender_key = deps.api.addr_validate(&spender)?;
-            let allowance = read_allowance(deps.storage, &owner_key, &spender_key)?;
+            let (allowance, expires) =
+                read_allowance(deps.storage, &env.block, &owner_key, &spender_key)?;
             let out = to_binary(&AllowanceResponse {
                 allowance: Uint128::from(allowance),
+                expires,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::VotingPower { address, at_block } => {
+            // Only the current lock is stored, which says nothing about earlier blocks
+            if at_block < env.block.height {
+                return Err(ContractError::PastVotingPower {
+                    height: env.block.height,
+                });
+            }
+            let address_key = deps.api.addr_validate(&address)?;
+            let voting_power = match read_voting_lock(deps.storage, &address_key)? {
+                Some(lock) => voting_power_at(&lock, at_block),
+                None => Uint128::zero(),
+            };
+            let out = to_binary(&VotingPowerResponse { voting_power })?;
+            Ok(out)
+        }
+        QueryMsg::SupplyHistory {
+            from_block,
+            to_block,
+            limit,
+        } => {
+            let entries = query_supply_history(deps.storage, from_block, to_block, limit)?;
+            let out = to_binary(&SupplyHistoryResponse { entries })?;
+            Ok(out)
+        }
+        QueryMsg::MaxSupply {} => {
+            // instances created before the supply history was added have no stored maximum
+            let max_supply = read_max_supply(deps.storage)?.max(query_total_supply(deps.storage)?);
+            let out = to_binary(&MaxSupplyResponse {
+                max_supply: Uint128::from(max_supply),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Humanize { amount } => {
+            let constants = query_constants(deps.storage)?;
+            let out = to_binary(&HumanizeResponse {
+                amount: format_units(amount.u128(), constants.decimals),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::PendingUpgrade {} => {
+            let out = to_binary(&PendingUpgradeResponse {
+                upgrade: read_pending_upgrade(deps.storage)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::MintProposal { mint_id } => {
+            let out = to_binary(&MintProposalResponse {
+                proposal: read_mint_proposal(deps.storage, mint_id)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::TokenInfo {} => {
+            let constants = query_constants(deps.storage)?;
+            let out = to_binary(&TokenInfoResponse {
+                name: constants.name,
+                symbol: constants.symbol,
+                decimals: constants.decimals,
+                total_supply: Uint128::from(query_total_supply(deps.storage)?),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Paused {} => {
+            let out = to_binary(&PausedResponse {
+                paused: read_flags(deps.storage).paused,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Minter {} => {
+            let out = to_binary(&MinterResponse {
+                minter: MINTER.load(deps.storage)?,
+                pending_minter: MINTER.load_pending(deps.storage)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::PermitNonce { owner } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let out = to_binary(&PermitNonceResponse {
+                nonce: read_permit_nonce(deps.storage, &owner)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::IsFrozen { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let out = to_binary(&FrozenResponse {
+                frozen: is_frozen(deps.storage, &address),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Votes { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let out = to_binary(&VotesResponse {
+                votes: Uint128::from(read_delegated_balance(deps.storage, &address)?),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Delegation { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let out = to_binary(&DelegationResponse {
+                delegatee: read_delegate(deps.storage, &address)?,
             })?;
             Ok(out)
         }
+        QueryMsg::Blacklisted { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let out = to_binary(&BlacklistedResponse {
+                blacklisted: is_blacklisted(deps.storage, &address),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::MarketingInfo {} => {
+            let out = to_binary(&read_marketing(deps.storage)?)?;
+            Ok(out)
+        }
+        #[cfg(feature = "iterator")]
+        QueryMsg::BalanceAt { address, height } => {
+            let address = deps.api.addr_validate(&address)?;
+            let balance = balance_at(deps.storage, &address, height)?;
+            let out = to_binary(&BalanceResponse {
+                balance: Uint128::from(balance),
+            })?;
+            Ok(out)
+        }
+        #[cfg(feature = "iterator")]
+        QueryMsg::AllAccounts { start_after, limit } => {
+            let accounts = query_all_accounts(deps.storage, start_after, limit)?;
+            let out = to_binary(&AllAccountsResponse { accounts })?;
+            Ok(out)
+        }
+        #[cfg(feature = "iterator")]
+        QueryMsg::Whitelist { start_after, limit } => {
+            let addresses = query_whitelist(deps.storage, start_after, limit)?;
+            let out = to_binary(&WhitelistResponse { addresses })?;
+            Ok(out)
+        }
+        #[cfg(feature = "iterator")]
+        QueryMsg::TopHolders { limit } => {
+            let holders = query_top_holders(deps.storage, limit)?;
+            let out = to_binary(&TopHoldersResponse { holders })?;
+            Ok(out)
+        }
+        QueryMsg::ContractVersion {} => {
+            let version = read_contract_version(deps.storage)?
+                .ok_or_else(|| StdError::not_found("ContractVersion"))?;
+            let out = to_binary(&version)?;
+            Ok(out)
+        }
     }
 }
 
 fn try_transfer(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     recipient: String,
     amount: &Uint128,
+    restricted: bool,
 ) -> Result<Response, ContractError> {
     perform_transfer(
         deps.storage,
+        env.block.height,
         &info.sender,
-        &deps.api.addr_validate(recipient.as_str())?,
+        &deps
+            .api
+            .addr_validate(recipient.as_str())
+            .context("invalid recipient")?,
         amount.u128(),
+        restricted,
     )?;
 
     let res = Response {
@@ -122,6 +661,166 @@ fn try_transfer(
             attr("action", "transfer"),
             attr("sender", info.sender),
             attr("recipient", recipient),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Transfer all
+///
+/// Transfers the signer's whole balance, as read when the message executes, and removes the
+/// signer's balance entry. An empty balance transfers 0 tokens.
+///
+/// @param recipient the address receiving the tokens
+fn try_transfer_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    restricted: bool,
+) -> Result<Response, ContractError> {
+    let recipient_address = deps
+        .api
+        .addr_validate(recipient.as_str())
+        .context("invalid recipient")?;
+    let amount = read_balance(deps.storage, &info.sender)?;
+    perform_transfer(
+        deps.storage,
+        env.block.height,
+        &info.sender,
+        &recipient_address,
+        amount,
+        restricted,
+    )?;
+    if recipient_address != info.sender {
+        let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
+        balances_store.remove(info.sender.as_str().as_bytes());
+    }
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "transfer_all"),
+            attr("sender", info.sender),
+            attr("recipient", recipient),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Batch transfer
+///
+/// Transfers tokens from the signer to every recipient. The total is checked against the
+/// signer's balance up front and the balances are only stored once all transfers succeeded,
+/// so a failing transfer leaves no recipient credited.
+///
+/// @param recipients the addresses and amounts, an address can appear more than once
+fn try_batch_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<InitialBalance>,
+    restricted: bool,
+) -> Result<Response, ContractError> {
+    if recipients.len() > MAX_BATCH_TRANSFERS {
+        return Err(ContractError::TooManyTransfers {
+            max: MAX_BATCH_TRANSFERS,
+        });
+    }
+
+    let mut transfers = Vec::with_capacity(recipients.len());
+    let mut total = Uint128::zero();
+    for recipient in recipients {
+        let address = deps
+            .api
+            .addr_validate(&recipient.address)
+            .context("invalid recipient")?;
+        total = total.checked_add(recipient.amount)?;
+        transfers.push((address, recipient.amount.u128()));
+    }
+    let balance = read_balance(deps.storage, &info.sender)?;
+    if balance < total.u128() {
+        return Err(ContractError::InsufficientFunds {
+            balance,
+            required: total.u128(),
+        });
+    }
+
+    let mut transaction = StorageTransaction::new(deps.storage);
+    for (address, amount) in &transfers {
+        perform_transfer(
+            &mut transaction,
+            env.block.height,
+            &info.sender,
+            address,
+            *amount,
+            restricted,
+        )?;
+    }
+    transaction.commit();
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "batch_transfer"),
+            attr("sender", info.sender),
+            attr("recipients", transfers.len()),
+            attr("total", total),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Send
+///
+/// Transfers tokens to a contract and notifies it with a `Receive` message, so the contract
+/// can act on the tokens in the same transaction.
+///
+/// @param contract the recipient contract
+/// @param amount the transferred tokens
+/// @param msg optional payload for the recipient contract
+fn try_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    amount: &Uint128,
+    msg: Option<Binary>,
+    restricted: bool,
+) -> Result<Response, ContractError> {
+    let contract_address = deps
+        .api
+        .addr_validate(contract.as_str())
+        .context("invalid contract")?;
+    perform_transfer(
+        deps.storage,
+        env.block.height,
+        &info.sender,
+        &contract_address,
+        amount.u128(),
+        restricted,
+    )?;
+
+    let receive = ReceiveMsg {
+        sender: info.sender.to_string(),
+        amount: *amount,
+        msg,
+    };
+    let res = Response {
+        submessages: vec![],
+        messages: vec![receive.into_cosmos_msg(contract_address)?],
+        attributes: vec![
+            attr("action", "send"),
+            attr("sender", info.sender),
+            attr("recipient", contract),
+            attr("amount", amount),
         ],
         data: None,
     };
@@ -130,17 +829,25 @@ fn try_transfer(
 
 fn try_transfer_from(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     owner: String,
     recipient: String,
     amount: &Uint128,
+    restricted: bool,
 ) -> Result<Response, ContractError> {
-    let owner_address = deps.api.addr_validate(owner.as_str())?;
-    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
+    let owner_address = deps
+        .api
+        .addr_validate(owner.as_str())
+        .context("invalid owner")?;
+    let recipient_address = deps
+        .api
+        .addr_validate(recipient.as_str())
+        .context("invalid recipient")?;
     let amount_raw = amount.u128();
 
-    let mut allowance = read_allowance(deps.storage, &owner_address, &info.sender)?;
+    let (mut allowance, expires) =
+        read_allowance(deps.storage, &env.block, &owner_address, &info.sender)?;
     if allowance < amount_raw {
         return Err(ContractError::InsufficientAllowance {
             allowance,
@@ -148,8 +855,21 @@ fn try_transfer_from(
         });
     }
     allowance -= amount_raw;
-    write_allowance(deps.storage, &owner_address, &info.sender, allowance)?;
-    perform_transfer(deps.storage, &owner_address, &recipient_address, amount_raw)?;
+    write_allowance(
+        deps.storage,
+        &owner_address,
+        &info.sender,
+        allowance,
+        expires,
+    )?;
+    perform_transfer(
+        deps.storage,
+        env.block.height,
+        &owner_address,
+        &recipient_address,
+        amount_raw,
+        restricted,
+    )?;
 
     let res = Response {
         submessages: vec![],
@@ -159,6 +879,7 @@ fn try_transfer_from(
             attr("spender", &info.sender),
             attr("sender", owner),
             attr("recipient", recipient),
+            attr("amount", amount),
         ],
         data: None,
     };
@@ -167,13 +888,24 @@ fn try_transfer_from(
 
 fn try_approve(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     spender: String,
     amount: &Uint128,
+    expires: Option<Expiration>,
 ) -> Result<Response, ContractError> {
-    let spender_address = deps.api.addr_validate(spender.as_str())?;
-    write_allowance(deps.storage, &info.sender, &spender_address, amount.u128())?;
+    let spender_address = deps
+        .api
+        .addr_validate(spender.as_str())
+        .context("invalid spender")?;
+    validate_allowance_expiration(&env, expires)?;
+    write_allowance(
+        deps.storage,
+        &info.sender,
+        &spender_address,
+        amount.u128(),
+        expires.unwrap_or_default(),
+    )?;
     let res = Response {
         submessages: vec![],
         messages: vec![],
@@ -181,50 +913,304 @@ fn try_approve(
             attr("action", "approve"),
             attr("owner", info.sender),
             attr("spender", spender),
+            attr("allowance", amount),
         ],
         data: None,
     };
     Ok(res)
 }
 
-/// Burn tokens
+/// Permit
 ///
-/// Remove `amount` tokens from the system irreversibly, from signer account
+/// Sets an allowance signed off-chain by `owner`, so a relayer can submit it for the owner.
+/// The signature covers the chain ID and this contract's address as well, so it cannot be
+/// replayed on other tokens, and the nonce increments with every permit.
 ///
-/// @param amount the amount of money to burn
-fn try_burn(
+/// @param owner the account granting the allowance
+/// @param spender the address allowed to spend the owner's tokens
+/// @param amount the new allowance
+/// @param nonce the owner's current permit nonce
+/// @param expiry the block height from which the permit is no longer valid
+/// @param signature the owner's signature of the `PermitMsg`
+#[allow(clippy::too_many_arguments)]
+fn try_permit(
     deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    amount: &Uint128,
+    env: Env,
+    _info: MessageInfo,
+    owner: String,
+    spender: String,
+    amount: Uint128,
+    nonce: u64,
+    expiry: u64,
+    signature: Binary,
 ) -> Result<Response, ContractError> {
-    let amount_raw = amount.u128();
-
-    let mut account_balance = read_balance(deps.storage, &info.sender)?;
+    let owner_address = deps.api.addr_validate(&owner).context("invalid owner")?;
+    let spender_address = deps
+        .api
+        .addr_validate(&spender)
+        .context("invalid spender")?;
+    if env.block.height >= expiry {
+        return Err(ContractError::PermitExpired { expiry });
+    }
+    let expected = read_permit_nonce(deps.storage, &owner_address)?;
+    if nonce != expected {
+        return Err(ContractError::InvalidPermitNonce { expected });
+    }
+    let pubkey = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_PERMIT_KEYS)
+        .get(owner_address.as_str().as_bytes())
+        .ok_or(ContractError::PermitKeyNotSet {})?;
 
-    if account_balance < amount_raw {
-        return Err(ContractError::InsufficientFunds {
-            balance: account_balance,
-            required: amount_raw,
-        });
+    let permit = PermitMsg {
+        chain_id: env.block.chain_id,
+        contract: env.contract.address.to_string(),
+        owner: owner.clone(),
+        spender: spender.clone(),
+        amount,
+        nonce,
+        expiry,
+    };
+    let hash = Sha256::digest(&to_vec(&permit)?);
+    let verified = deps
+        .api
+        .secp256k1_verify(&hash, &signature, &pubkey)
+        .unwrap_or(false);
+    if !verified {
+        return Err(ContractError::InvalidPermitSignature {});
     }
-    account_balance -= amount_raw;
 
-    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-    balances_store.set(
-        info.sender.as_str().as_bytes(),
-        &account_balance.to_be_bytes(),
+    let mut nonces_store = PrefixedStorage::new(deps.storage, PREFIX_NONCES);
+    nonces_store.set(
+        owner_address.as_str().as_bytes(),
+        &(nonce + 1).to_be_bytes(),
     );
+    write_allowance(
+        deps.storage,
+        &owner_address,
+        &spender_address,
+        amount.u128(),
+        Expiration::Never {},
+    )?;
 
-    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
-    let data = config_store
-        .get(KEY_TOTAL_SUPPLY)
-        .expect("no total supply data stored");
-    let mut total_supply = bytes_to_u128(&data).unwrap();
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "permit"),
+            attr("owner", owner),
+            attr("spender", spender),
+            attr("allowance", amount),
+            attr("nonce", nonce),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Set permit key
+///
+/// Stores the public key whose signatures `Permit` accepts for the signer, or removes it so
+/// no permits of the signer are accepted anymore.
+///
+/// @param pubkey a compressed or uncompressed secp256k1 public key
+fn try_set_permit_key(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pubkey: Option<Binary>,
+) -> Result<Response, ContractError> {
+    if let Some(pubkey) = &pubkey {
+        let valid = match pubkey.as_slice() {
+            [0x02, ..] | [0x03, ..] => pubkey.len() == 33,
+            [0x04, ..] => pubkey.len() == 65,
+            _ => false,
+        };
+        if !valid {
+            return Err(ContractError::InvalidPermitKey {});
+        }
+    }
+    let mut keys_store = PrefixedStorage::new(deps.storage, PREFIX_PERMIT_KEYS);
+    let key = info.sender.as_str().as_bytes();
+    match pubkey {
+        Some(pubkey) => keys_store.set(key, &pubkey),
+        None => keys_store.remove(key),
+    }
 
-    total_supply -= amount_raw;
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "set_permit_key"), attr("owner", info.sender)],
+        data: None,
+    };
+    Ok(res)
+}
 
-    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+/// Increase allowance
+///
+/// Adds `amount` to the allowance the signer granted `spender`. Fails if the allowance would
+/// exceed the maximum amount.
+///
+/// @param spender the address allowed to spend the signer's tokens
+/// @param amount the amount to add
+/// @param expires the new expiration, or `None` to keep the current one
+fn try_increase_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: &Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let spender_address = deps
+        .api
+        .addr_validate(spender.as_str())
+        .context("invalid spender")?;
+    validate_allowance_expiration(&env, expires)?;
+    let (allowance, current_expires) =
+        read_allowance(deps.storage, &env.block, &info.sender, &spender_address)?;
+    let allowance = Uint128::from(allowance).checked_add(*amount)?;
+    write_allowance(
+        deps.storage,
+        &info.sender,
+        &spender_address,
+        allowance.u128(),
+        expires.unwrap_or(current_expires),
+    )?;
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "increase_allowance"),
+            attr("owner", info.sender),
+            attr("spender", spender),
+            attr("allowance", allowance),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Decrease allowance
+///
+/// Subtracts `amount` from the allowance the signer granted `spender`. Decreasing by more than
+/// the remaining allowance sets it to zero, or fails if `exact` is set.
+///
+/// @param spender the address allowed to spend the signer's tokens
+/// @param amount the amount to subtract
+/// @param exact fail instead of stopping at zero
+/// @param expires the new expiration, or `None` to keep the current one
+fn try_decrease_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: &Uint128,
+    exact: bool,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let spender_address = deps
+        .api
+        .addr_validate(spender.as_str())
+        .context("invalid spender")?;
+    validate_allowance_expiration(&env, expires)?;
+    let (allowance, current_expires) =
+        read_allowance(deps.storage, &env.block, &info.sender, &spender_address)?;
+    if exact && allowance < amount.u128() {
+        return Err(ContractError::InsufficientAllowance {
+            allowance,
+            required: amount.u128(),
+        });
+    }
+    let allowance = allowance.saturating_sub(amount.u128());
+    write_allowance(
+        deps.storage,
+        &info.sender,
+        &spender_address,
+        allowance,
+        expires.unwrap_or(current_expires),
+    )?;
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "decrease_allowance"),
+            attr("owner", info.sender),
+            attr("spender", spender),
+            attr("allowance", allowance),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Sweep expired allowances
+///
+/// Removes the allowances `owner` granted `spenders` that have expired. They already read as
+/// zero, so this only frees their storage. Anyone can do this.
+///
+/// @param owner the account that granted the allowances
+/// @param spenders the spenders whose allowances to check
+fn try_sweep_expired_allowances(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    owner: String,
+    spenders: Vec<String>,
+) -> Result<Response, ContractError> {
+    if spenders.len() > MAX_SWEEP_SPENDERS {
+        return Err(ContractError::TooManySpenders {
+            max: MAX_SWEEP_SPENDERS,
+        });
+    }
+    let owner_address = deps.api.addr_validate(&owner).context("invalid owner")?;
+    let mut swept = 0u32;
+    for spender in &spenders {
+        let spender_address = deps.api.addr_validate(spender).context("invalid spender")?;
+        let key = spender_address.as_str().as_bytes();
+        let mut expiry_store = PrefixedStorage::multilevel(
+            deps.storage,
+            &[PREFIX_ALLOWANCE_EXPIRIES, owner_address.as_str().as_bytes()],
+        );
+        let expires: Expiration = match expiry_store.get(key) {
+            Some(data) => from_slice(&data)?,
+            None => continue,
+        };
+        if !expires.is_expired(&env.block) {
+            continue;
+        }
+        expiry_store.remove(key);
+        PrefixedStorage::multilevel(
+            deps.storage,
+            &[PREFIX_ALLOWANCES, owner_address.as_str().as_bytes()],
+        )
+        .remove(key);
+        swept += 1;
+    }
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "sweep_expired_allowances"),
+            attr("owner", owner),
+            attr("swept", swept),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Burn tokens
+///
+/// Remove `amount` tokens from the system irreversibly, from signer account
+///
+/// @param amount the amount of money to burn
+fn try_burn(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: &Uint128,
+) -> Result<Response, ContractError> {
+    perform_burn(deps.storage, env.block.height, &info.sender, amount.u128())?;
 
     let res = Response {
         submessages: vec![],
@@ -240,209 +1226,6665 @@ fn try_burn(
     Ok(res)
 }
 
-fn perform_transfer(
-    store: &mut dyn Storage,
-    from: &Addr,
-    to: &Addr,
-    amount: u128,
-) -> Result<(), ContractError> {
-    let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
-
-    let mut from_balance = match balances_store.get(from.as_str().as_bytes()) {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
-    }?;
+/// Burn tokens of another account
+///
+/// Remove `amount` tokens from the system irreversibly, from the owner's account, spending
+/// the signer's allowance
+///
+/// @param owner the account to burn from
+/// @param amount the amount of money to burn
+fn try_burn_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    amount: &Uint128,
+) -> Result<Response, ContractError> {
+    let owner_address = deps
+        .api
+        .addr_validate(owner.as_str())
+        .context("invalid owner")?;
+    let amount_raw = amount.u128();
 
-    if from_balance < amount {
-        return Err(ContractError::InsufficientFunds {
-            balance: from_balance,
-            required: amount,
+    let (mut allowance, expires) =
+        read_allowance(deps.storage, &env.block, &owner_address, &info.sender)?;
+    if allowance < amount_raw {
+        return Err(ContractError::InsufficientAllowance {
+            allowance,
+            required: amount_raw,
         });
     }
-    from_balance -= amount;
-    balances_store.set(from.as_str().as_bytes(), &from_balance.to_be_bytes());
-
-    let mut to_balance = match balances_store.get(to.as_str().as_bytes()) {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
-    }?;
-    to_balance += amount;
-    balances_store.set(to.as_str().as_bytes(), &to_balance.to_be_bytes());
+    allowance -= amount_raw;
+    write_allowance(
+        deps.storage,
+        &owner_address,
+        &info.sender,
+        allowance,
+        expires,
+    )?;
+    perform_burn(deps.storage, env.block.height, &owner_address, amount_raw)?;
 
-    Ok(())
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "burn_from"),
+            attr("spender", &info.sender),
+            attr("account", owner),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
 }
 
-// Converts 16 bytes value into u128
-// Errors if data found that is not 16 bytes
-pub fn bytes_to_u128(data: &[u8]) -> Result<u128, ContractError> {
-    match data[0..16].try_into() {
-        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
-        Err(_) => Err(ContractError::CorruptedDataFound {}),
+fn try_set_mint_schedule(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    recipient: String,
+    rate: Uint128,
+    start: u64,
+    end: u64,
+) -> Result<Response, ContractError> {
+    MINTER.assert_admin(deps.storage, &info.sender)?;
+    if read_mint_approval(deps.storage)?.is_some() {
+        return Err(ContractError::MintApprovalRequired {});
+    }
+    if end <= start {
+        return Err(ContractError::InvalidMintSchedule { start, end });
     }
+
+    let schedule = MintSchedule {
+        recipient: deps
+            .api
+            .addr_validate(&recipient)
+            .context("invalid recipient")?,
+        rate,
+        start,
+        end,
+        last_executed_block: start,
+    };
+
+    let schedule_id = next_id(deps.storage, KEY_MINT_SCHEDULE_COUNT)?;
+    write_mint_schedule(deps.storage, schedule_id, &schedule)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "set_mint_schedule"),
+            attr("schedule_id", schedule_id),
+            attr("recipient", recipient),
+            attr("rate", rate),
+        ],
+        data: None,
+    };
+    Ok(res)
 }
 
-// Reads 16 byte storage value into u128
-// Returns zero if key does not exist. Errors if data found that is not 16 bytes
-pub fn read_u128(store: &ReadonlyPrefixedStorage, key: &Addr) -> Result<u128, ContractError> {
-    let result = store.get(key.as_str().as_bytes());
-    match result {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
+/// Execute mint schedule
+///
+/// Mints `rate` tokens for every block between the last execution and the current block,
+/// but never for blocks after the schedule's `end`. Calling late mints the full backlog.
+/// Fails once the token has no minter anymore.
+///
+/// @param schedule_id the schedule to execute
+fn try_execute_mint_schedule(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    schedule_id: u64,
+) -> Result<Response, ContractError> {
+    assert_minter_set(deps.storage)?;
+    let mut schedule = read_mint_schedule(deps.storage, schedule_id)?
+        .ok_or(ContractError::MintScheduleNotFound { schedule_id })?;
+
+    let until = env.block.height.min(schedule.end);
+    let mut amount = Uint128::zero();
+    if until > schedule.last_executed_block {
+        let blocks = until - schedule.last_executed_block;
+        amount = schedule.rate.checked_mul(Uint128::from(blocks))?;
+        consume_mint_allowance(deps.storage, env.block.height, amount)?;
+        perform_mint(
+            deps.storage,
+            env.block.height,
+            &schedule.recipient,
+            amount.u128(),
+        )?;
+        schedule.last_executed_block = until;
+        write_mint_schedule(deps.storage, schedule_id, &schedule)?;
     }
-}
 
-fn read_balance(store: &dyn Storage, owner: &Addr) -> Result<u128, ContractError> {
-    let balance_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
-    read_u128(&balance_store, owner)
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "execute_mint_schedule"),
+            attr("schedule_id", schedule_id),
+            attr("recipient", schedule.recipient),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
 }
 
-fn read_allowance(
-    store: &dyn Storage,
-    owner: &Addr,
-    spender: &Addr,
-) -> Result<u128, ContractError> {
-    let owner_store =
-        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
-    read_u128(&owner_store, spender)
-}
+/// Mint
+///
+/// Mints `amount` tokens to `recipient`. The mint counts against the mint rate limit like
+/// schedules do.
+///
+/// @param recipient the account receiving the minted tokens
+/// @param amount the amount of tokens to mint
+fn try_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    MINTER.assert_admin(deps.storage, &info.sender)?;
+    if read_mint_approval(deps.storage)?.is_some() {
+        return Err(ContractError::MintApprovalRequired {});
+    }
+    let recipient = deps
+        .api
+        .addr_validate(&recipient)
+        .context("invalid recipient")?;
+    consume_mint_allowance(deps.storage, env.block.height, amount)?;
+    perform_mint(deps.storage, env.block.height, &recipient, amount.u128())?;
 
-#[allow(clippy::unnecessary_wraps)]
-fn write_allowance(
-    store: &mut dyn Storage,
-    owner: &Addr,
-    spender: &Addr,
-    amount: u128,
-) -> StdResult<()> {
-    let mut owner_store =
-        PrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
-    owner_store.set(spender.as_str().as_bytes(), &amount.to_be_bytes());
-    Ok(())
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "mint"),
+            attr("minter", info.sender),
+            attr("recipient", recipient),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
 }
 
-fn is_valid_name(name: &str) -> bool {
-    let bytes = name.as_bytes();
-    if bytes.len() < 3 || bytes.len() > 30 {
-        return false;
-    }
-    true
-}
+/// Transfer minter
+///
+/// Offers the minter role to `address`. The role only moves once `address` accepts it,
+/// so a mistyped address cannot disable minting for good.
+///
+/// @param address the proposed new minter
+fn try_transfer_minter(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let candidate = deps
+        .api
+        .addr_validate(&address)
+        .context("invalid address")?;
+    MINTER.propose(deps.storage, &info.sender, &candidate)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "transfer_minter"),
+            attr("minter", info.sender),
+            attr("candidate", candidate),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Completes a minter transfer. Only the proposed minter can do this.
+fn try_accept_minter(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    MINTER.accept(deps.storage, &info.sender)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "accept_minter"), attr("minter", info.sender)],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Update minter
+///
+/// Hands the minter role to `new_minter` without waiting for it to accept, unlike
+/// `TransferMinter`. For `None` the token is left without a minter: nobody can mint, and
+/// mint schedules and mint proposals created before can no longer be executed. The role
+/// cannot be assigned again. Either way a pending minter transfer is dropped.
+///
+/// @param new_minter the new minter, or `None` to fix the supply
+fn try_update_minter(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_minter: Option<String>,
+) -> Result<Response, ContractError> {
+    MINTER.assert_admin(deps.storage, &info.sender)?;
+    let new_minter = match new_minter {
+        Some(address) => Some(
+            deps.api
+                .addr_validate(&address)
+                .context("invalid new_minter")?,
+        ),
+        None => None,
+    };
+    MINTER.save(deps.storage, new_minter.as_ref())?;
+
+    let mut attributes = vec![attr("action", "update_minter"), attr("minter", info.sender)];
+    if let Some(new_minter) = new_minter {
+        attributes.push(attr("new_minter", new_minter));
+    }
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes,
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Update marketing
+///
+/// Replaces the given marketing fields, clearing those set to an empty string. The other
+/// fields keep their values.
+///
+/// @param project the project name or URL
+/// @param description a description of the token
+/// @param logo_url a URL of the token's logo
+fn try_update_marketing(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    project: Option<String>,
+    description: Option<String>,
+    logo_url: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut marketing = read_marketing(deps.storage)?;
+    match &marketing.marketing_admin {
+        Some(admin) if *admin == info.sender => {}
+        Some(_) => return Err(ContractError::Unauthorized {}),
+        None => return Err(ContractError::NoAdmin {}),
+    }
+    if project.is_some() {
+        marketing.project = non_empty(project);
+    }
+    if description.is_some() {
+        marketing.description = non_empty(description);
+    }
+    if logo_url.is_some() {
+        marketing.logo_url = non_empty(logo_url);
+    }
+    validate_marketing(&marketing)?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_MARKETING, &to_vec(&marketing)?);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "update_marketing"),
+            attr("marketing_admin", info.sender),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Propose mint
+///
+/// Stores a mint of `amount` tokens to `recipient`, which can be executed once enough mint
+/// approvers approved it. The proposal expires after the configured number of blocks.
+///
+/// @param recipient the account receiving the minted tokens
+/// @param amount the amount of tokens to mint
+fn try_propose_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    MINTER.assert_admin(deps.storage, &info.sender)?;
+    let mint_approval =
+        read_mint_approval(deps.storage)?.ok_or(ContractError::MintApprovalDisabled {})?;
+
+    let proposal = MintProposal {
+        recipient: deps
+            .api
+            .addr_validate(&recipient)
+            .context("invalid recipient")?,
+        amount,
+        approvals: vec![],
+        expiry: env.block.height + mint_approval.proposal_expiry,
+    };
+    let mint_id = next_id(deps.storage, KEY_MINT_PROPOSAL_COUNT)?;
+    write_mint_proposal(deps.storage, mint_id, &proposal)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "propose_mint"),
+            attr("mint_id", mint_id),
+            attr("recipient", recipient),
+            attr("amount", amount),
+            attr("expiry", proposal.expiry),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Approve mint
+///
+/// Adds the signer's approval to a mint proposal. Every approver can approve once.
+///
+/// @param mint_id the proposal to approve
+fn try_approve_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mint_id: u64,
+) -> Result<Response, ContractError> {
+    let mint_approval =
+        read_mint_approval(deps.storage)?.ok_or(ContractError::MintApprovalDisabled {})?;
+    if !mint_approval.approvers.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut proposal = read_mint_proposal(deps.storage, mint_id)?
+        .ok_or(ContractError::MintProposalNotFound { mint_id })?;
+    if env.block.height >= proposal.expiry {
+        return Err(ContractError::MintProposalExpired {
+            expiry: proposal.expiry,
+        });
+    }
+    if proposal.approvals.contains(&info.sender) {
+        return Err(ContractError::AlreadyApproved {});
+    }
+    proposal.approvals.push(info.sender.clone());
+    write_mint_proposal(deps.storage, mint_id, &proposal)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "approve_mint"),
+            attr("mint_id", mint_id),
+            attr("approver", info.sender),
+            attr("approvals", proposal.approvals.len()),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Execute mint
+///
+/// Mints the tokens of a proposal once it has at least `threshold` approvals. The proposal
+/// is removed, so it can only be executed once. Fails once the token has no minter anymore.
+///
+/// @param mint_id the proposal to execute
+fn try_execute_mint(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    mint_id: u64,
+) -> Result<Response, ContractError> {
+    assert_minter_set(deps.storage)?;
+    let mint_approval =
+        read_mint_approval(deps.storage)?.ok_or(ContractError::MintApprovalDisabled {})?;
+    let proposal = read_mint_proposal(deps.storage, mint_id)?
+        .ok_or(ContractError::MintProposalNotFound { mint_id })?;
+    if env.block.height >= proposal.expiry {
+        return Err(ContractError::MintProposalExpired {
+            expiry: proposal.expiry,
+        });
+    }
+    let approvals = proposal.approvals.len() as u64;
+    if approvals < mint_approval.threshold {
+        return Err(ContractError::NotEnoughApprovals {
+            approvals,
+            threshold: mint_approval.threshold,
+        });
+    }
+
+    consume_mint_allowance(deps.storage, env.block.height, proposal.amount)?;
+    perform_mint(
+        deps.storage,
+        env.block.height,
+        &proposal.recipient,
+        proposal.amount.u128(),
+    )?;
+    remove_mint_proposal(deps.storage, mint_id);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "execute_mint"),
+            attr("mint_id", mint_id),
+            attr("recipient", proposal.recipient),
+            attr("amount", proposal.amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Lock for voting
+///
+/// Moves `amount` tokens from the signer's balance into its voting lock. The lock ends
+/// `lock_duration` blocks from now, unless an existing lock ends even later.
+///
+/// @param amount the amount of tokens to add to the lock
+/// @param lock_duration the number of blocks the lock lasts at least
+fn try_lock_for_voting(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: &Uint128,
+    lock_duration: u64,
+) -> Result<Response, ContractError> {
+    if lock_duration == 0 || lock_duration > MAX_LOCK_DURATION {
+        return Err(ContractError::InvalidLockDuration {
+            max: MAX_LOCK_DURATION,
+        });
+    }
+    let amount_raw = amount.u128();
+
+    let account_balance = read_balance(deps.storage, &info.sender)?;
+    if account_balance < amount_raw {
+        return Err(ContractError::InsufficientFunds {
+            balance: account_balance,
+            required: amount_raw,
+        });
+    }
+    write_balance(
+        deps.storage,
+        env.block.height,
+        &info.sender,
+        account_balance - amount_raw,
+    )?;
+
+    let end = env.block.height + lock_duration;
+    let lock = match read_voting_lock(deps.storage, &info.sender)? {
+        Some(lock) => VotingLock {
+            amount: lock.amount.checked_add(*amount)?,
+            start: lock.start,
+            end: lock.end.max(end),
+        },
+        None => VotingLock {
+            amount: *amount,
+            start: env.block.height,
+            end,
+        },
+    };
+    write_voting_lock(deps.storage, &info.sender, &lock)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "lock_for_voting"),
+            attr("owner", info.sender),
+            attr("amount", amount),
+            attr("end", lock.end),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let lock =
+        read_voting_lock(deps.storage, &info.sender)?.ok_or(ContractError::NoVotingLock {})?;
+    if env.block.height < lock.end {
+        return Err(ContractError::VotingLockNotExpired { end: lock.end });
+    }
+
+    let account_balance = read_balance(deps.storage, &info.sender)?;
+    write_balance(
+        deps.storage,
+        env.block.height,
+        &info.sender,
+        account_balance + lock.amount.u128(),
+    )?;
+    let mut locks_store = PrefixedStorage::new(deps.storage, PREFIX_VOTING_LOCKS);
+    locks_store.remove(info.sender.as_str().as_bytes());
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "withdraw"),
+            attr("owner", info.sender),
+            attr("amount", lock.amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Delegate
+///
+/// Moves the votes of the signer's balance to `delegatee` without moving any tokens. The
+/// delegatee keeps them, including later balance changes, until the signer delegates again.
+///
+/// @param delegatee the address receiving the votes, or an empty string for the signer itself
+fn try_delegate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    delegatee: String,
+) -> Result<Response, ContractError> {
+    let delegatee = if delegatee.is_empty() {
+        info.sender.clone()
+    } else {
+        deps.api
+            .addr_validate(&delegatee)
+            .context("invalid delegatee")?
+    };
+
+    let balance = read_balance(deps.storage, &info.sender)?;
+    if let Some(previous) = read_delegate(deps.storage, &info.sender)? {
+        let votes = Uint128::from(read_delegated_balance(deps.storage, &previous)?)
+            .checked_sub(Uint128::from(balance))?;
+        write_delegated_balance(deps.storage, &previous, votes.u128());
+    }
+    let votes = Uint128::from(read_delegated_balance(deps.storage, &delegatee)?)
+        .checked_add(Uint128::from(balance))?;
+    write_delegated_balance(deps.storage, &delegatee, votes.u128());
+    let mut delegates_store = PrefixedStorage::new(deps.storage, PREFIX_DELEGATES);
+    delegates_store.set(
+        info.sender.as_str().as_bytes(),
+        delegatee.as_str().as_bytes(),
+    );
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "delegate"),
+            attr("delegator", info.sender),
+            attr("delegatee", delegatee),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn try_schedule_conditional(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: &Uint128,
+    oracle: String,
+    condition_query: Binary,
+    expiry: u64,
+) -> Result<Response, ContractError> {
+    if expiry <= env.block.height {
+        return Err(ContractError::InvalidExpiry { expiry });
+    }
+    let transfer = ConditionalTransfer {
+        sender: info.sender.clone(),
+        recipient: deps
+            .api
+            .addr_validate(&recipient)
+            .context("invalid recipient")?,
+        amount: *amount,
+        oracle: deps.api.addr_validate(&oracle).context("invalid oracle")?,
+        condition_query,
+        expiry,
+    };
+    let restricted = read_flags(deps.storage).restricted;
+    assert_can_transfer(
+        deps.storage,
+        &transfer.sender,
+        &transfer.recipient,
+        restricted,
+    )?;
+
+    let amount_raw = amount.u128();
+    let account_balance = read_balance(deps.storage, &info.sender)?;
+    if account_balance < amount_raw {
+        return Err(ContractError::InsufficientFunds {
+            balance: account_balance,
+            required: amount_raw,
+        });
+    }
+    write_balance(
+        deps.storage,
+        env.block.height,
+        &info.sender,
+        account_balance - amount_raw,
+    )?;
+
+    let transfer_id = next_id(deps.storage, KEY_CONDITIONAL_TRANSFER_COUNT)?;
+    write_conditional_transfer(deps.storage, transfer_id, &transfer)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "schedule_conditional"),
+            attr("transfer_id", transfer_id),
+            attr("sender", info.sender),
+            attr("recipient", recipient),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_execute_conditional(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    transfer_id: u64,
+) -> Result<Response, ContractError> {
+    let transfer = read_conditional_transfer(deps.storage, transfer_id)?
+        .ok_or(ContractError::ConditionalTransferNotFound { transfer_id })?;
+    if env.block.height >= transfer.expiry {
+        return Err(ContractError::ConditionalTransferExpired {
+            expiry: transfer.expiry,
+        });
+    }
+
+    let request = QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: transfer.oracle.to_string(),
+        msg: transfer.condition_query.clone(),
+    });
+    let condition: ConditionResponse = deps
+        .querier
+        .query(&request)
+        .context("oracle query failed")?;
+    if !condition.satisfied {
+        return Err(ContractError::ConditionNotMet {});
+    }
+    // the sides may have been restricted since the transfer was scheduled
+    let restricted = read_flags(deps.storage).restricted;
+    assert_can_transfer(
+        deps.storage,
+        &transfer.sender,
+        &transfer.recipient,
+        restricted,
+    )?;
+
+    let recipient_balance = read_balance(deps.storage, &transfer.recipient)?;
+    write_balance(
+        deps.storage,
+        env.block.height,
+        &transfer.recipient,
+        recipient_balance + transfer.amount.u128(),
+    )?;
+    remove_conditional_transfer(deps.storage, transfer_id);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "execute_conditional"),
+            attr("transfer_id", transfer_id),
+            attr("recipient", transfer.recipient),
+            attr("amount", transfer.amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_refund_conditional(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    transfer_id: u64,
+) -> Result<Response, ContractError> {
+    let transfer = read_conditional_transfer(deps.storage, transfer_id)?
+        .ok_or(ContractError::ConditionalTransferNotFound { transfer_id })?;
+    if env.block.height < transfer.expiry {
+        return Err(ContractError::ConditionalTransferNotExpired {
+            expiry: transfer.expiry,
+        });
+    }
+
+    let sender_balance = read_balance(deps.storage, &transfer.sender)?;
+    write_balance(
+        deps.storage,
+        env.block.height,
+        &transfer.sender,
+        sender_balance + transfer.amount.u128(),
+    )?;
+    remove_conditional_transfer(deps.storage, transfer_id);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "refund_conditional"),
+            attr("transfer_id", transfer_id),
+            attr("sender", transfer.sender),
+            attr("amount", transfer.amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_genesis_airdrop(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    entries: Vec<AirdropEntry>,
+    attestation: Binary,
+) -> Result<Response, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    let genesis: GenesisConfig = match config_store.get(KEY_GENESIS_CONFIG) {
+        Some(data) => from_slice(&data)?,
+        None => return Err(ContractError::GenesisAirdropDisabled {}),
+    };
+    if env.block.height >= genesis.window_end {
+        return Err(ContractError::GenesisWindowClosed {
+            window_end: genesis.window_end,
+        });
+    }
+    if entries.is_empty() || entries.len() > MAX_AIRDROP_CHUNK_SIZE {
+        return Err(ContractError::InvalidAirdropChunk {
+            max: MAX_AIRDROP_CHUNK_SIZE,
+        });
+    }
+
+    // The hash identifies the chunk, so the same entries cannot be minted twice
+    let chunk_hash = Sha256::digest(&to_vec(&entries)?);
+    // The admin signs the chain and this contract along with the entries, so an attestation
+    // cannot be replayed on another chain or token
+    let digest = Sha256::digest(&to_vec(&(
+        &env.block.chain_id,
+        &env.contract.address,
+        &entries,
+    ))?);
+    let verified = deps
+        .api
+        .secp256k1_verify(&digest, &attestation, &genesis.admin_pubkey)
+        .unwrap_or(false);
+    if !verified {
+        return Err(ContractError::InvalidAttestation {});
+    }
+    let mut chunks_store = PrefixedStorage::new(deps.storage, PREFIX_GENESIS_CHUNKS);
+    if chunks_store.get(&chunk_hash).is_some() {
+        return Err(ContractError::ChunkAlreadyProcessed {});
+    }
+    chunks_store.set(&chunk_hash, &env.block.height.to_be_bytes());
+
+    let addresses: Vec<&str> = entries.iter().map(|entry| entry.address.as_str()).collect();
+    let addresses = validate_all(deps.api, &addresses).context("invalid airdrop entries")?;
+    let mut total = Uint128::zero();
+    for (entry, address) in entries.iter().zip(addresses) {
+        perform_mint(
+            deps.storage,
+            env.block.height,
+            &address,
+            entry.amount.u128(),
+        )?;
+        total = total.checked_add(entry.amount)?;
+    }
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "genesis_airdrop"),
+            attr("chunk", hex::encode(chunk_hash)),
+            attr("entries", entries.len()),
+            attr("amount", total),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Performs an admin action right away, which is only possible without an admin delay
+fn try_admin_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: AdminAction,
+) -> Result<Response, ContractError> {
+    ADMINS.assert_admin(deps.storage, &info.sender)?;
+    let min_delay = read_admin_delay(deps.storage)?;
+    if min_delay > 0 {
+        return Err(ContractError::TimelockRequired { min_delay });
+    }
+    apply_admin_action(deps, &env, action)
+}
+
+/// Propose admin action
+///
+/// Queues `action` to be executed at block `eta` or later. The admin delay protects holders
+/// from a compromised admin key, so `eta` must be at least that far in the future.
+///
+/// @param action the action to perform
+/// @param eta the earliest block height the action can be executed at
+fn try_propose_admin_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: AdminAction,
+    eta: u64,
+) -> Result<Response, ContractError> {
+    ADMINS.assert_admin(deps.storage, &info.sender)?;
+    let min_eta = env.block.height + read_admin_delay(deps.storage)?;
+    if eta < min_eta {
+        return Err(ContractError::EtaTooEarly { min_eta });
+    }
+
+    let action_id = next_id(deps.storage, KEY_ADMIN_ACTION_COUNT)?;
+    let queued = QueuedAdminAction {
+        action,
+        eta,
+        proposer: info.sender.clone(),
+    };
+    let mut actions_store = PrefixedStorage::new(deps.storage, PREFIX_ADMIN_ACTIONS);
+    actions_store.set(&action_id.to_be_bytes(), &to_vec(&queued)?);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "propose_admin_action"),
+            attr("action_id", action_id),
+            attr("proposer", info.sender),
+            attr("eta", eta),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Performs a queued admin action once its eta is reached. Any admin can do this.
+fn try_execute_admin_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action_id: u64,
+) -> Result<Response, ContractError> {
+    ADMINS.assert_admin(deps.storage, &info.sender)?;
+    let queued = read_admin_action(deps.storage, action_id)?
+        .ok_or(ContractError::AdminActionNotFound { action_id })?;
+    if env.block.height < queued.eta {
+        return Err(ContractError::AdminActionNotReady { eta: queued.eta });
+    }
+
+    let mut actions_store = PrefixedStorage::new(deps.storage, PREFIX_ADMIN_ACTIONS);
+    actions_store.remove(&action_id.to_be_bytes());
+    let mut res = apply_admin_action(deps, &env, queued.action)?;
+    res.attributes.splice(
+        0..0,
+        vec![
+            attr("action", "execute_admin_action"),
+            attr("action_id", action_id),
+        ],
+    );
+    Ok(res)
+}
+
+/// Drops a queued admin action. Any admin can do this.
+fn try_cancel_admin_action(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    action_id: u64,
+) -> Result<Response, ContractError> {
+    ADMINS.assert_admin(deps.storage, &info.sender)?;
+    if read_admin_action(deps.storage, action_id)?.is_none() {
+        return Err(ContractError::AdminActionNotFound { action_id });
+    }
+    let mut actions_store = PrefixedStorage::new(deps.storage, PREFIX_ADMIN_ACTIONS);
+    actions_store.remove(&action_id.to_be_bytes());
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "cancel_admin_action"),
+            attr("action_id", action_id),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Performs `action` without checking permissions
+fn apply_admin_action(
+    deps: DepsMut,
+    env: &Env,
+    action: AdminAction,
+) -> Result<Response, ContractError> {
+    let (name, address) = match &action {
+        AdminAction::AddToWhitelist { addresses } => {
+            return set_whitelisted(deps, addresses, true);
+        }
+        AdminAction::RemoveFromWhitelist { addresses } => {
+            return set_whitelisted(deps, addresses, false);
+        }
+        AdminAction::Clawback {
+            owner,
+            recipient,
+            amount,
+        } => {
+            return clawback(deps, env.block.height, owner, recipient, amount.u128());
+        }
+        AdminAction::RecoverOwn { recipient, amount } => {
+            return recover_own(deps, env, recipient, amount.u128());
+        }
+        AdminAction::RenounceRoles {} => {
+            return renounce_roles(deps);
+        }
+        AdminAction::AddBlacklist { address } => ("add_blacklist", address),
+        AdminAction::RemoveBlacklist { address } => ("remove_blacklist", address),
+        // Timelocked unlike pausing, so a compromised admin key cannot lock holders out at once
+        AdminAction::Freeze { address } => ("freeze", address),
+        AdminAction::Unfreeze { address } => ("unfreeze", address),
+        AdminAction::AddAdmin { address } => ("add_admin", address),
+        AdminAction::RemoveAdmin { address } => ("remove_admin", address),
+    };
+    let address = deps.api.addr_validate(address).context("invalid address")?;
+    let key = address.as_str().as_bytes();
+
+    match action {
+        AdminAction::AddBlacklist { .. } => {
+            PrefixedStorage::new(deps.storage, PREFIX_BLACKLIST).set(key, &[1])
+        }
+        AdminAction::RemoveBlacklist { .. } => {
+            PrefixedStorage::new(deps.storage, PREFIX_BLACKLIST).remove(key)
+        }
+        AdminAction::Freeze { .. } => {
+            PrefixedStorage::new(deps.storage, PREFIX_FROZEN).set(key, &[1])
+        }
+        AdminAction::Unfreeze { .. } => {
+            PrefixedStorage::new(deps.storage, PREFIX_FROZEN).remove(key)
+        }
+        AdminAction::AddAdmin { .. } => ADMINS.add(deps.storage, &address),
+        // Admins can remove themselves. Once the last admin is removed,
+        // no admin actions are possible anymore.
+        AdminAction::RemoveAdmin { .. } => ADMINS.remove(deps.storage, &address),
+        // handled above
+        AdminAction::AddToWhitelist { .. }
+        | AdminAction::RemoveFromWhitelist { .. }
+        | AdminAction::Clawback { .. }
+        | AdminAction::RecoverOwn { .. }
+        | AdminAction::RenounceRoles {} => {}
+    }
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", name), attr("address", address)],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Adds `addresses` to the whitelist or removes them. Fails without changes if any of
+/// them is invalid.
+fn set_whitelisted(
+    deps: DepsMut,
+    addresses: &[String],
+    whitelisted: bool,
+) -> Result<Response, ContractError> {
+    let addresses = addresses
+        .iter()
+        .map(|address| deps.api.addr_validate(address).context("invalid address"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut whitelist_store = PrefixedStorage::new(deps.storage, PREFIX_WHITELIST);
+    for address in &addresses {
+        if whitelisted {
+            whitelist_store.set(address.as_str().as_bytes(), &[1]);
+        } else {
+            whitelist_store.remove(address.as_str().as_bytes());
+        }
+    }
+
+    let action = if whitelisted {
+        "add_to_whitelist"
+    } else {
+        "remove_from_whitelist"
+    };
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", action), attr("count", addresses.len())],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Moves `amount` tokens of `owner` to `recipient`. The recipient is checked like for any
+/// other transfer, but the owner is not: frozen holders are what clawbacks are for, and
+/// requiring an unfreeze first would let them move the tokens away before the clawback.
+fn clawback(
+    deps: DepsMut,
+    height: u64,
+    owner: &str,
+    recipient: &str,
+    amount: u128,
+) -> Result<Response, ContractError> {
+    let owner_address = deps.api.addr_validate(owner).context("invalid owner")?;
+    let recipient_address = deps
+        .api
+        .addr_validate(recipient)
+        .context("invalid recipient")?;
+    let restricted = read_flags(deps.storage).restricted;
+    assert_can_receive(deps.storage, &recipient_address, restricted)?;
+    move_balance(
+        deps.storage,
+        height,
+        &owner_address,
+        &recipient_address,
+        amount,
+    )?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "clawback"),
+            attr("sender", owner_address),
+            attr("recipient", recipient_address),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Moves `amount` tokens that were sent to the token contract's own address by mistake to
+/// `recipient`. Nobody else can move them.
+fn recover_own(
+    deps: DepsMut,
+    env: &Env,
+    recipient: &str,
+    amount: u128,
+) -> Result<Response, ContractError> {
+    let recipient_address = deps
+        .api
+        .addr_validate(recipient)
+        .context("invalid recipient")?;
+    let restricted = read_flags(deps.storage).restricted;
+    perform_transfer(
+        deps.storage,
+        env.block.height,
+        &env.contract.address,
+        &recipient_address,
+        amount,
+        restricted,
+    )?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "recover_own"),
+            attr("sender", env.contract.address.clone()),
+            attr("recipient", recipient_address),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Clears the admins, the minter with any pending minter and the marketing admin. None of the
+/// roles can be assigned again, so everything gated by them fails with `NoAdmin` afterwards,
+/// including mint schedules and mint proposals created before.
+fn renounce_roles(deps: DepsMut) -> Result<Response, ContractError> {
+    ADMINS.renounce(deps.storage);
+    MINTER.save(deps.storage, None)?;
+    let mut marketing = read_marketing(deps.storage)?;
+    marketing.marketing_admin = None;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_MARKETING, &to_vec(&marketing)?);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "renounce_roles")],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Pause or unpause
+///
+/// Stops or resumes all other messages. This is meant for emergencies, so it does not wait
+/// for the admin delay.
+///
+/// @param paused whether to pause the contract
+fn try_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ADMINS.assert_admin(deps.storage, &info.sender)?;
+    let flags = Flags {
+        paused,
+        ..read_flags(deps.storage)
+    };
+    write_flags(deps.storage, flags);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", if paused { "pause" } else { "unpause" }),
+            attr("admin", info.sender),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Initiate upgrade
+///
+/// Stores a migration of this contract, so holders can review `migrate_msg` during the
+/// `UPGRADE_DELAY` blocks before it can be executed. Only one upgrade can be pending.
+///
+/// @param new_code_id the code to migrate to
+/// @param migrate_msg the message passed to the `migrate` entry point of the new code
+fn try_initiate_upgrade(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_code_id: u64,
+    migrate_msg: Binary,
+) -> Result<Response, ContractError> {
+    ADMINS.assert_admin(deps.storage, &info.sender)?;
+    if read_pending_upgrade(deps.storage)?.is_some() {
+        return Err(ContractError::UpgradePending {});
+    }
+
+    let eta = env.block.height + UPGRADE_DELAY;
+    let upgrade = PendingUpgrade {
+        new_code_id,
+        migrate_msg,
+        eta,
+        proposer: info.sender.clone(),
+    };
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_PENDING_UPGRADE, &to_vec(&upgrade)?);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "initiate_upgrade"),
+            attr("new_code_id", new_code_id),
+            attr("proposer", info.sender),
+            attr("eta", eta),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Migrates the contract to the pending upgrade once its eta is reached. This requires the
+/// contract to be its own wasm admin. Any admin can do this.
+fn try_execute_upgrade(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ADMINS.assert_admin(deps.storage, &info.sender)?;
+    let upgrade = read_pending_upgrade(deps.storage)?.ok_or(ContractError::NoPendingUpgrade {})?;
+    if env.block.height < upgrade.eta {
+        return Err(ContractError::UpgradeNotReady { eta: upgrade.eta });
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.remove(KEY_PENDING_UPGRADE);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: env.contract.address.into(),
+            new_code_id: upgrade.new_code_id,
+            msg: upgrade.migrate_msg,
+        })],
+        attributes: vec![
+            attr("action", "execute_upgrade"),
+            attr("new_code_id", upgrade.new_code_id),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Drops the pending upgrade. Any admin can do this.
+fn try_cancel_upgrade(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ADMINS.assert_admin(deps.storage, &info.sender)?;
+    if read_pending_upgrade(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingUpgrade {});
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.remove(KEY_PENDING_UPGRADE);
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "cancel_upgrade")],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Execute batch
+///
+/// Runs every message with the same env and info, so each one checks its own permissions.
+/// Storage writes are only applied once all messages succeeded. Attributes of the messages
+/// are prefixed with their index in the batch, e.g. `0.action`.
+///
+/// @param msgs the messages to execute, which must not contain batches
+fn try_batch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<ExecuteMsg>,
+) -> Result<Response, ContractError> {
+    if msgs
+        .iter()
+        .any(|msg| matches!(msg, ExecuteMsg::Batch { .. }))
+    {
+        return Err(ContractError::NestedBatch {});
+    }
+
+    let mut res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "batch"), attr("count", msgs.len())],
+        data: None,
+    };
+    let DepsMut {
+        storage,
+        api,
+        querier,
+    } = deps;
+    let mut transaction = StorageTransaction::new(storage);
+    for (index, msg) in msgs.into_iter().enumerate() {
+        let deps = DepsMut {
+            storage: &mut transaction,
+            api,
+            querier,
+        };
+        let item = execute(deps, env.clone(), info.clone(), msg)
+            .context(format!("batch message {}", index))?;
+        res.submessages.extend(item.submessages);
+        res.messages.extend(item.messages);
+        res.attributes.extend(
+            item.attributes
+                .into_iter()
+                .map(|item_attr| attr(format!("{}.{}", index, item_attr.key), item_attr.value)),
+        );
+    }
+    transaction.commit();
+
+    Ok(res)
+}
+
+/// Voting power decays linearly from `amount * remaining / MAX_LOCK_DURATION` to zero at the end of the lock
+fn voting_power_at(lock: &VotingLock, height: u64) -> Uint128 {
+    if height < lock.start || height >= lock.end {
+        return Uint128::zero();
+    }
+    lock.amount
+        .multiply_ratio(lock.end - height, MAX_LOCK_DURATION)
+}
+
+fn perform_mint(
+    store: &mut dyn Storage,
+    height: u64,
+    to: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let total_supply =
+        Uint128::from(query_total_supply(store)?).checked_add(Uint128::from(amount))?;
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.u128().to_be_bytes());
+    record_supply_change(store, height, total_supply.u128())?;
+
+    let to_balance = read_balance(store, to)?;
+    write_balance(store, height, to, to_balance + amount)?;
+
+    Ok(())
+}
+
+/// Fails once the minter role was removed, so mints queued before can no longer run
+fn assert_minter_set(store: &dyn Storage) -> Result<(), ContractError> {
+    match MINTER.load(store)? {
+        Some(_) => Ok(()),
+        None => Err(ContractError::NoAdmin {}),
+    }
+}
+
+/// Counts `amount` against the mint rate limit, if any, starting a new window when the
+/// current one is over
+fn consume_mint_allowance(
+    store: &mut dyn Storage,
+    height: u64,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    let mut rate_limit: MintRateLimit = match config_store.get(KEY_MINT_RATE_LIMIT) {
+        Some(data) => from_slice(&data)?,
+        None => return Ok(()),
+    };
+
+    if height >= rate_limit.window_start_block + rate_limit.window_blocks {
+        rate_limit.window_start_block = height;
+        rate_limit.minted_in_window = Uint128::zero();
+    }
+    let minted_in_window = rate_limit.minted_in_window.checked_add(amount)?;
+    if minted_in_window > rate_limit.limit {
+        return Err(ContractError::MintRateLimitExceeded {});
+    }
+    rate_limit.minted_in_window = minted_in_window;
+    config_store.set(KEY_MINT_RATE_LIMIT, &to_vec(&rate_limit)?);
+
+    Ok(())
+}
+
+fn perform_burn(
+    store: &mut dyn Storage,
+    height: u64,
+    from: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let mut account_balance = read_balance(store, from)?;
+    if account_balance < amount {
+        return Err(ContractError::InsufficientFunds {
+            balance: account_balance,
+            required: amount,
+        });
+    }
+    account_balance -= amount;
+    write_balance(store, height, from, account_balance)?;
+
+    let total_supply = query_total_supply(store)? - amount;
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    record_supply_change(store, height, total_supply)?;
+
+    Ok(())
+}
+
+/// Checks the restrictions on both sides of a transfer from `from` to `to`. Conditional
+/// transfers move balances without `perform_transfer`, so they call this themselves.
+/// `restricted` is the flag of the same name, which callers usually have read already.
+fn assert_can_transfer(
+    store: &dyn Storage,
+    from: &Addr,
+    to: &Addr,
+    restricted: bool,
+) -> Result<(), ContractError> {
+    if is_frozen(store, from) {
+        return Err(ContractError::SenderFrozen {});
+    }
+    if restricted && !is_whitelisted(store, from) {
+        return Err(ContractError::SenderNotWhitelisted {});
+    }
+    assert_can_receive(store, to, restricted)
+}
+
+/// The recipient side of `assert_can_transfer`
+fn assert_can_receive(
+    store: &dyn Storage,
+    to: &Addr,
+    restricted: bool,
+) -> Result<(), ContractError> {
+    if is_blacklisted(store, to) {
+        return Err(ContractError::RecipientBlacklisted {});
+    }
+    if is_frozen(store, to) {
+        return Err(ContractError::RecipientFrozen {});
+    }
+    if restricted && !is_whitelisted(store, to) {
+        return Err(ContractError::RecipientNotWhitelisted {});
+    }
+    Ok(())
+}
+
+fn perform_transfer(
+    store: &mut dyn Storage,
+    height: u64,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+    restricted: bool,
+) -> Result<(), ContractError> {
+    assert_can_transfer(store, from, to, restricted)?;
+    move_balance(store, height, from, to, amount)
+}
+
+/// Moves `amount` tokens from `from` to `to` without checking any restriction
+fn move_balance(
+    store: &mut dyn Storage,
+    height: u64,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let from_balance = read_balance(store, from)?;
+    let from_balance = match from_balance.checked_sub(amount) {
+        Some(balance) => balance,
+        None => {
+            return Err(ContractError::InsufficientFunds {
+                balance: from_balance,
+                required: amount,
+            })
+        }
+    };
+    write_balance(store, height, from, from_balance)?;
+
+    let to_balance = read_balance(store, to)?;
+    let to_balance = Uint128::from(to_balance).checked_add(Uint128::from(amount))?;
+    write_balance(store, height, to, to_balance.u128())?;
+
+    Ok(())
+}
+
+// Converts 16 bytes value into u128
+// Errors if data found that is not 16 bytes
+pub fn bytes_to_u128(data: &[u8]) -> Result<u128, ContractError> {
+    match data.try_into() {
+        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
+        Err(_) => Err(ContractError::CorruptedDataFound {}),
+    }
+}
+
+// Reads 16 byte storage value into u128
+// Returns zero if key does not exist. Errors if data found that is not 16 bytes
+pub fn read_u128(store: &ReadonlyPrefixedStorage, key: &Addr) -> Result<u128, ContractError> {
+    let result = store.get(key.as_str().as_bytes());
+    match result {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0u128),
+    }
+}
+
+fn read_balance(store: &dyn Storage, owner: &Addr) -> Result<u128, ContractError> {
+    let balance_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
+    read_u128(&balance_store, owner)
+}
+
+/// Stores the balance of `owner` and updates the snapshots, the ranking and the votes of its
+/// delegatee
+fn write_balance(
+    store: &mut dyn Storage,
+    height: u64,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let previous = read_balance(store, owner)?;
+    let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
+    balances_store.set(owner.as_str().as_bytes(), &amount.to_be_bytes());
+    record_balance(store, owner, height, amount);
+    #[cfg(feature = "iterator")]
+    rank_balance(store, owner, previous, amount);
+
+    if let Some(delegatee) = read_delegate(store, owner)? {
+        // the votes include `previous` and are bounded by the total supply
+        let votes = Uint128::from(read_delegated_balance(store, &delegatee)?)
+            .checked_sub(Uint128::from(previous))?
+            .checked_add(Uint128::from(amount))?;
+        write_delegated_balance(store, &delegatee, votes.u128());
+    }
+    Ok(())
+}
+
+/// Returns the allowance with its expiration. Expired allowances are zero and never expire.
+fn read_allowance(
+    store: &dyn Storage,
+    block: &BlockInfo,
+    owner: &Addr,
+    spender: &Addr,
+) -> Result<(u128, Expiration), ContractError> {
+    let expiry_store = ReadonlyPrefixedStorage::multilevel(
+        store,
+        &[PREFIX_ALLOWANCE_EXPIRIES, owner.as_str().as_bytes()],
+    );
+    let expires = match expiry_store.get(spender.as_str().as_bytes()) {
+        Some(data) => from_slice(&data)?,
+        None => Expiration::Never {},
+    };
+    if expires.is_expired(block) {
+        return Ok((0, Expiration::Never {}));
+    }
+    let owner_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
+    Ok((read_u128(&owner_store, spender)?, expires))
+}
+
+/// Stores the expiration apart from the amount, so the raw allowance stays a plain 16 byte value
+fn write_allowance(
+    store: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    amount: u128,
+    expires: Expiration,
+) -> StdResult<()> {
+    let mut owner_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
+    owner_store.set(spender.as_str().as_bytes(), &amount.to_be_bytes());
+    let mut expiry_store = PrefixedStorage::multilevel(
+        store,
+        &[PREFIX_ALLOWANCE_EXPIRIES, owner.as_str().as_bytes()],
+    );
+    match expires {
+        Expiration::Never {} => expiry_store.remove(spender.as_str().as_bytes()),
+        expires => expiry_store.set(spender.as_str().as_bytes(), &to_vec(&expires)?),
+    }
+    Ok(())
+}
+
+fn validate_allowance_expiration(
+    env: &Env,
+    expires: Option<Expiration>,
+) -> Result<(), ContractError> {
+    match expires {
+        Some(expires) if expires.is_expired(&env.block) => {
+            Err(ContractError::AllowanceExpirationPassed { expires })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn read_mint_schedule(
+    store: &dyn Storage,
+    schedule_id: u64,
+) -> Result<Option<MintSchedule>, ContractError> {
+    let schedules_store = ReadonlyPrefixedStorage::new(store, PREFIX_MINT_SCHEDULES);
+    match schedules_store.get(&schedule_id.to_be_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn write_mint_schedule(
+    store: &mut dyn Storage,
+    schedule_id: u64,
+    schedule: &MintSchedule,
+) -> StdResult<()> {
+    let mut schedules_store = PrefixedStorage::new(store, PREFIX_MINT_SCHEDULES);
+    schedules_store.set(&schedule_id.to_be_bytes(), &to_vec(schedule)?);
+    Ok(())
+}
+
+/// Validates all `addresses`. Errors name the index and value of the first invalid one.
+fn read_mint_approval(store: &dyn Storage) -> Result<Option<MintApproval>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_MINT_APPROVAL) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn read_mint_proposal(
+    store: &dyn Storage,
+    mint_id: u64,
+) -> Result<Option<MintProposal>, ContractError> {
+    let proposals_store = ReadonlyPrefixedStorage::new(store, PREFIX_MINT_PROPOSALS);
+    match proposals_store.get(&mint_id.to_be_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn write_mint_proposal(
+    store: &mut dyn Storage,
+    mint_id: u64,
+    proposal: &MintProposal,
+) -> StdResult<()> {
+    let mut proposals_store = PrefixedStorage::new(store, PREFIX_MINT_PROPOSALS);
+    proposals_store.set(&mint_id.to_be_bytes(), &to_vec(proposal)?);
+    Ok(())
+}
+
+fn remove_mint_proposal(store: &mut dyn Storage, mint_id: u64) {
+    let mut proposals_store = PrefixedStorage::new(store, PREFIX_MINT_PROPOSALS);
+    proposals_store.remove(&mint_id.to_be_bytes());
+}
+
+fn validate_all<T: AsRef<str>>(api: &dyn Api, addresses: &[T]) -> Result<Vec<Addr>, ContractError> {
+    addresses
+        .iter()
+        .enumerate()
+        .map(|(index, address)| {
+            let address = address.as_ref();
+            api.addr_validate(address)
+                .context(format!("invalid address at index {} ({})", index, address))
+        })
+        .collect()
+}
+
+/// Like `validate_all`, but also rejects addresses that occur more than once
+fn validate_all_unique<T: AsRef<str>>(
+    api: &dyn Api,
+    addresses: &[T],
+) -> Result<Vec<Addr>, ContractError> {
+    let validated = validate_all(api, addresses)?;
+    for (index, address) in validated.iter().enumerate() {
+        if validated[..index].contains(address) {
+            return Err(ContractError::DuplicateAddress {
+                index,
+                address: address.to_string(),
+            });
+        }
+    }
+    Ok(validated)
+}
+
+/// Blocks between proposing and executing admin actions. Zero when actions are immediate.
+fn read_admin_delay(store: &dyn Storage) -> Result<u64, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_ADMIN_DELAY) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => Ok(u64::from_be_bytes(bytes)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        },
+        None => Ok(0),
+    }
+}
+
+fn read_admin_action(
+    store: &dyn Storage,
+    action_id: u64,
+) -> Result<Option<QueuedAdminAction>, ContractError> {
+    let actions_store = ReadonlyPrefixedStorage::new(store, PREFIX_ADMIN_ACTIONS);
+    match actions_store.get(&action_id.to_be_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn read_pending_upgrade(store: &dyn Storage) -> Result<Option<PendingUpgrade>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_PENDING_UPGRADE) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn read_marketing(store: &dyn Storage) -> Result<Marketing, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_MARKETING) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(Marketing::default()),
+    }
+}
+
+/// Treats empty marketing fields as unset
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.is_empty())
+}
+
+fn validate_marketing(marketing: &Marketing) -> Result<(), ContractError> {
+    let fields = [
+        ("project", &marketing.project, MAX_PROJECT_LENGTH),
+        (
+            "description",
+            &marketing.description,
+            MAX_DESCRIPTION_LENGTH,
+        ),
+        ("logo_url", &marketing.logo_url, MAX_LOGO_URL_LENGTH),
+    ];
+    for (field, value, max) in fields.iter() {
+        if value.as_ref().map_or(0, String::len) > *max {
+            return Err(ContractError::MarketingFieldTooLong {
+                field: field.to_string(),
+                max: *max,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Contract wide switches. They share one entry, which `execute` reads for the pause check
+/// of every message, so transfers of unrestricted tokens need no further read for them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Flags {
+    paused: bool,
+    /// Restricted tokens can only be transferred between whitelisted addresses
+    restricted: bool,
+}
+
+const FLAG_PAUSED: u8 = 1;
+const FLAG_RESTRICTED: u8 = 2;
+
+fn read_flags(store: &dyn Storage) -> Flags {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    let bits = config_store
+        .get(KEY_FLAGS)
+        .and_then(|data| data.first().copied())
+        .unwrap_or(0);
+    Flags {
+        paused: bits & FLAG_PAUSED != 0,
+        restricted: bits & FLAG_RESTRICTED != 0,
+    }
+}
+
+fn write_flags(store: &mut dyn Storage, flags: Flags) {
+    let mut bits = 0;
+    if flags.paused {
+        bits |= FLAG_PAUSED;
+    }
+    if flags.restricted {
+        bits |= FLAG_RESTRICTED;
+    }
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    if bits == 0 {
+        config_store.remove(KEY_FLAGS);
+    } else {
+        config_store.set(KEY_FLAGS, &[bits]);
+    }
+}
+
+/// Older versions kept each flag in its own entry. Moves them into the shared one.
+fn migrate_legacy_flags(store: &mut dyn Storage) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    let paused = config_store.get(b"paused").is_some();
+    let restricted = config_store.get(b"restricted").is_some();
+    if paused || restricted {
+        config_store.remove(b"paused");
+        config_store.remove(b"restricted");
+        write_flags(store, Flags { paused, restricted });
+    }
+}
+
+fn is_whitelisted(store: &dyn Storage, address: &Addr) -> bool {
+    let whitelist_store = ReadonlyPrefixedStorage::new(store, PREFIX_WHITELIST);
+    whitelist_store.get(address.as_str().as_bytes()).is_some()
+}
+
+fn read_permit_nonce(store: &dyn Storage, owner: &Addr) -> Result<u64, ContractError> {
+    let nonces_store = ReadonlyPrefixedStorage::new(store, PREFIX_NONCES);
+    match nonces_store.get(owner.as_str().as_bytes()) {
+        Some(data) => Ok(u64::from_be_bytes(
+            data[..]
+                .try_into()
+                .map_err(|_| ContractError::CorruptedDataFound {})?,
+        )),
+        None => Ok(0),
+    }
+}
+
+/// Frozen addresses can neither send nor receive transfers
+fn is_frozen(store: &dyn Storage, address: &Addr) -> bool {
+    let frozen_store = ReadonlyPrefixedStorage::new(store, PREFIX_FROZEN);
+    frozen_store.get(address.as_str().as_bytes()).is_some()
+}
+
+/// Blacklisted addresses cannot receive transfers
+fn is_blacklisted(store: &dyn Storage, address: &Addr) -> bool {
+    let blacklist_store = ReadonlyPrefixedStorage::new(store, PREFIX_BLACKLIST);
+    blacklist_store.get(address.as_str().as_bytes()).is_some()
+}
+
+/// Appends the new total supply to the supply history. Several changes in one block
+/// result in a single entry with the supply at the end of the block.
+fn record_supply_change(
+    store: &mut dyn Storage,
+    height: u64,
+    total_supply: u128,
+) -> Result<(), ContractError> {
+    let count = read_supply_history_count(store)?;
+    let index = match count.checked_sub(1) {
+        Some(last) if read_supply_history_entry(store, last)?.height == height => last,
+        _ => count,
+    };
+    let entry = SupplyHistoryEntry {
+        height,
+        total_supply: Uint128::from(total_supply),
+    };
+    let mut history_store = PrefixedStorage::new(store, PREFIX_SUPPLY_HISTORY);
+    history_store.set(&index.to_be_bytes(), &to_vec(&entry)?);
+
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_SUPPLY_HISTORY_COUNT, &(index + 1).to_be_bytes());
+    if total_supply > read_max_supply(store)? {
+        let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+        config_store.set(KEY_MAX_SUPPLY, &total_supply.to_be_bytes());
+    }
+    Ok(())
+}
+
+fn read_supply_history_count(store: &dyn Storage) -> Result<u64, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_SUPPLY_HISTORY_COUNT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => Ok(u64::from_be_bytes(bytes)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        },
+        None => Ok(0),
+    }
+}
+
+fn read_supply_history_entry(
+    store: &dyn Storage,
+    index: u64,
+) -> Result<SupplyHistoryEntry, ContractError> {
+    let history_store = ReadonlyPrefixedStorage::new(store, PREFIX_SUPPLY_HISTORY);
+    match history_store.get(&index.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(StdError::not_found("SupplyHistoryEntry").into()),
+    }
+}
+
+fn read_max_supply(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_MAX_SUPPLY) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+/// Supply history entries between `from_block` and `to_block` (both inclusive), oldest first.
+/// To get the next page, query again starting after the height of the last entry.
+fn query_supply_history(
+    store: &dyn Storage,
+    from_block: u64,
+    to_block: u64,
+    limit: Option<u32>,
+) -> Result<Vec<SupplyHistoryEntry>, ContractError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_SUPPLY_HISTORY_LIMIT)
+        .min(MAX_SUPPLY_HISTORY_LIMIT) as usize;
+    let count = read_supply_history_count(store)?;
+
+    // entries are sorted by height, so binary search for the first one in range
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_supply_history_entry(store, mid)?.height < from_block {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    let mut entries = vec![];
+    for index in low..count {
+        let entry = read_supply_history_entry(store, index)?;
+        if entry.height > to_block || entries.len() == limit {
+            break;
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Balances are stored by address, so they are iterated in address order. Accounts which
+/// held tokens before but have none left are skipped.
+#[cfg(feature = "iterator")]
+fn query_all_accounts(
+    store: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<String>, ContractError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_ACCOUNTS_LIMIT)
+        .min(MAX_ACCOUNTS_LIMIT) as usize;
+    // the smallest key after `start_after`
+    let start = start_after.map(|address| {
+        let mut key = address.into_bytes();
+        key.push(0);
+        key
+    });
+
+    let balances_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
+    let mut accounts = vec![];
+    for (key, value) in balances_store.range(start.as_deref(), None, Order::Ascending) {
+        if accounts.len() == limit {
+            break;
+        }
+        if bytes_to_u128(&value)? > 0 {
+            accounts.push(String::from_utf8(key).map_err(StdError::from)?);
+        }
+    }
+    Ok(accounts)
+}
+
+/// Whitelisted addresses in address order, like `query_all_accounts`
+#[cfg(feature = "iterator")]
+fn query_whitelist(
+    store: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<String>, ContractError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_ACCOUNTS_LIMIT)
+        .min(MAX_ACCOUNTS_LIMIT) as usize;
+    // the smallest key after `start_after`
+    let start = start_after.map(|address| {
+        let mut key = address.into_bytes();
+        key.push(0);
+        key
+    });
+
+    let whitelist_store = ReadonlyPrefixedStorage::new(store, PREFIX_WHITELIST);
+    whitelist_store
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|(key, _)| Ok(String::from_utf8(key).map_err(StdError::from)?))
+        .collect()
+}
+
+/// The `limit` largest non-zero balances, largest first and ties ordered by address
+#[cfg(feature = "iterator")]
+fn query_top_holders(store: &dyn Storage, limit: u32) -> Result<Vec<HolderBalance>, ContractError> {
+    let limit = limit.min(MAX_TOP_HOLDERS_LIMIT) as usize;
+    let holders = top_balances(store, limit)?
+        .into_iter()
+        .map(|(address, balance)| HolderBalance {
+            address,
+            balance: Uint128::from(balance),
+        })
+        .collect();
+    Ok(holders)
+}
+
+/// Ranks all balances again, including those stored before the ranking existed or by builds
+/// without the `iterator` feature, which leave the ranking as it was. This is safe to run on
+/// every migration.
+#[cfg(feature = "iterator")]
+fn rank_existing_balances(store: &mut dyn Storage) -> Result<(), ContractError> {
+    clear_ranking(store);
+    let balances: Vec<_> = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES)
+        .range(None, None, Order::Ascending)
+        .collect();
+    for (key, value) in balances {
+        let owner = Addr::unchecked(String::from_utf8(key).map_err(StdError::from)?);
+        rank_balance(store, &owner, 0, bytes_to_u128(&value)?);
+    }
+    Ok(())
+}
+
+/// Returns the value of the counter at `key` and increments it
+fn next_id(store: &mut dyn Storage, key: &[u8]) -> Result<u64, ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    let id = match config_store.get(key) {
+        Some(data) => u64::from_be_bytes(
+            data[..]
+                .try_into()
+                .map_err(|_| ContractError::CorruptedDataFound {})?,
+        ),
+        None => 0,
+    };
+    config_store.set(key, &(id + 1).to_be_bytes());
+    Ok(id)
+}
+
+fn read_conditional_transfer(
+    store: &dyn Storage,
+    transfer_id: u64,
+) -> Result<Option<ConditionalTransfer>, ContractError> {
+    let transfers_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONDITIONAL_TRANSFERS);
+    match transfers_store.get(&transfer_id.to_be_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn write_conditional_transfer(
+    store: &mut dyn Storage,
+    transfer_id: u64,
+    transfer: &ConditionalTransfer,
+) -> StdResult<()> {
+    let mut transfers_store = PrefixedStorage::new(store, PREFIX_CONDITIONAL_TRANSFERS);
+    transfers_store.set(&transfer_id.to_be_bytes(), &to_vec(transfer)?);
+    Ok(())
+}
+
+fn remove_conditional_transfer(store: &mut dyn Storage, transfer_id: u64) {
+    let mut transfers_store = PrefixedStorage::new(store, PREFIX_CONDITIONAL_TRANSFERS);
+    transfers_store.remove(&transfer_id.to_be_bytes());
+}
+
+fn read_voting_lock(
+    store: &dyn Storage,
+    owner: &Addr,
+) -> Result<Option<VotingLock>, ContractError> {
+    let locks_store = ReadonlyPrefixedStorage::new(store, PREFIX_VOTING_LOCKS);
+    match locks_store.get(owner.as_str().as_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn write_voting_lock(store: &mut dyn Storage, owner: &Addr, lock: &VotingLock) -> StdResult<()> {
+    let mut locks_store = PrefixedStorage::new(store, PREFIX_VOTING_LOCKS);
+    locks_store.set(owner.as_str().as_bytes(), &to_vec(lock)?);
+    Ok(())
+}
+
+/// The address `delegator` delegated its votes to, if any
+fn read_delegate(store: &dyn Storage, delegator: &Addr) -> Result<Option<Addr>, ContractError> {
+    let delegates_store = ReadonlyPrefixedStorage::new(store, PREFIX_DELEGATES);
+    match delegates_store.get(delegator.as_str().as_bytes()) {
+        Some(data) => Ok(Some(Addr::unchecked(
+            String::from_utf8(data).map_err(StdError::from)?,
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Sum of the balances delegated to `delegatee`
+fn read_delegated_balance(store: &dyn Storage, delegatee: &Addr) -> Result<u128, ContractError> {
+    let votes_store = ReadonlyPrefixedStorage::new(store, PREFIX_DELEGATED_BALANCE);
+    read_u128(&votes_store, delegatee)
+}
+
+fn write_delegated_balance(store: &mut dyn Storage, delegatee: &Addr, votes: u128) {
+    let mut votes_store = PrefixedStorage::new(store, PREFIX_DELEGATED_BALANCE);
+    votes_store.set(delegatee.as_str().as_bytes(), &votes.to_be_bytes());
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    if bytes.len() < 3 || bytes.len() > 30 {
+        return false;
+    }
+    true
+}
+
+fn is_valid_symbol(symbol: &str) -> bool {
+    let bytes = symbol.as_bytes();
+    if bytes.len() < 3 || bytes.len() > 6 {
+        return false;
+    }
+    for byte in bytes.iter() {
+        if *byte < 65 || *byte > 90 {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{parse_attributes, Erc20Event};
+    use crate::msg::InitialBalance;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        from_slice, Addr, Deps, DepsMut, Env, MessageInfo, Response, Storage, Timestamp, Uint128,
+    };
+    use cosmwasm_storage::ReadonlyPrefixedStorage;
+
+    fn mock_env_height(signer: &str, height: u64, time: u64) -> (Env, MessageInfo) {
+        let mut env = mock_env();
+        let info = mock_info(signer, &[]);
+        env.block.height = height;
+        env.block.time = Timestamp::from_seconds(time);
+        (env, info)
+    }
+
+    fn get_constants(storage: &dyn Storage) -> Constants {
+        query_constants(storage).unwrap()
+    }
+
+    fn get_total_supply(storage: &dyn Storage) -> u128 {
+        query_total_supply(storage).unwrap()
+    }
+
+    fn get_balance(storage: &dyn Storage, address: &Addr) -> u128 {
+        let balances_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_BALANCES);
+        read_u128(&balances_storage, address).unwrap()
+    }
+
+    fn get_allowance(storage: &dyn Storage, owner: &Addr, spender: &Addr) -> u128 {
+        let owner_storage = ReadonlyPrefixedStorage::multilevel(
+            storage,
+            &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()],
+        );
+        read_u128(&owner_storage, spender).unwrap()
+    }
+
+    mod instantiate {
+        use super::*;
+        use crate::error::ContractError;
+
+        #[test]
+        fn works() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: [InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11223344u128),
+                }]
+                .to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_constants(&deps.storage),
+                Constants {
+                    name: "Cash Token".to_string(),
+                    symbol: "CASH".to_string(),
+                    decimals: 9,
+                }
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11223344
+            );
+            assert_eq!(get_total_supply(&deps.storage), 11223344);
+        }
+
+        #[test]
+        fn works_with_empty_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: [].to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+        #[test]
+        fn works_with_multiple_balances() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: [
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                    InitialBalance {
+                        address: "addrbbbb".to_string(),
+                        amount: Uint128::from(33u128),
+                    },
+                ]
+                .to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+        }
+
+        #[test]
+        fn works_with_balance_larger_than_53_bit() {
+            let mut deps = mock_dependencies(&[]);
+            // This value cannot be represented precisely in JavaScript and jq. Both
+            //   node -e "console.attr(9007199254740993)"
+            //   echo '{ "value": 9007199254740993 }' | jq
+            // return 9007199254740992
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: [InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(9007199254740993u128),
+                }]
+                .to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                9007199254740993
+            );
+            assert_eq!(get_total_supply(&deps.storage), 9007199254740993);
+        }
+
+        #[test]
+        // Typical supply like 100 million tokens with 18 decimals exceeds the 64 bit range
+        fn works_with_balance_larger_than_64_bit() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: [InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100000000000000000000000000u128),
+                }]
+                .to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                100000000000000000000000000
+            );
+            assert_eq!(get_total_supply(&deps.storage), 100000000000000000000000000);
+        }
+
+        #[test]
+        fn fails_for_large_decimals() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 42,
+                initial_balances: [].to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::DecimalsExceeded {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_name_too_short() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "CC".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: [].to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NameWrongFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_name_too_long() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash coin. Cash coin. Cash coin. Cash coin.".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: [].to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NameWrongFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_symbol_too_short() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "De De".to_string(),
+                symbol: "DD".to_string(),
+                decimals: 9,
+                initial_balances: [].to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_symbol_too_long() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Super Coin".to_string(),
+                symbol: "SUPERCOIN".to_string(),
+                decimals: 9,
+                initial_balances: [].to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_symbol_lowercase() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CaSH".to_string(),
+                decimals: 9,
+                initial_balances: [].to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_total_supply_overflow() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: [
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(u128::MAX),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(1u128),
+                    },
+                ]
+                .to_vec(),
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Overflow(_)) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod transfer {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                    InitialBalance {
+                        address: "addrbbbb".to_string(),
+                        amount: Uint128::from(33u128),
+                    },
+                ],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        #[test]
+        fn can_send_to_existing_recipient() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(transfer_result.messages.len(), 0);
+            assert_eq!(
+                transfer_result.attributes,
+                vec![
+                    attr("action", "transfer"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "addr1111"),
+                    attr("amount", "1"),
+                ]
+            );
+            assert_eq!(
+                parse_attributes(&transfer_result.attributes).unwrap(),
+                vec![Erc20Event::Transfer {
+                    from: "addr0000".to_string(),
+                    to: "addr1111".to_string(),
+                    amount: Uint128::from(1u128),
+                }]
+            );
+            // New state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                10
+            ); // -1
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                23
+            ); // +1
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+        }
+
+        #[test]
+        fn can_send_to_non_existent_recipient() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr2323".to_string(),
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(transfer_result.messages.len(), 0);
+            assert_eq!(
+                transfer_result.attributes,
+                vec![
+                    attr("action", "transfer"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "addr2323"),
+                    attr("amount", "1"),
+                ]
+            );
+            // New state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                10
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr2323".to_string())),
+                1
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+        }
+
+        #[test]
+        fn can_send_zero_amount() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(0u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(transfer_result.messages.len(), 0);
+            assert_eq!(
+                transfer_result.attributes,
+                vec![
+                    attr("action", "transfer"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "addr1111"),
+                    attr("amount", "0"),
+                ]
+            );
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+        }
+
+        #[test]
+        fn can_send_to_sender() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let sender = "addr0000";
+            // Initial state
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(sender)), 11);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: sender.to_string(),
+                amount: Uint128::from(3u128),
+            };
+            let (env, info) = mock_env_height(sender, 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(transfer_result.messages.len(), 0);
+            assert_eq!(
+                transfer_result.attributes,
+                vec![
+                    attr("action", "transfer"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "addr0000"),
+                    attr("amount", "3"),
+                ]
+            );
+            // New state
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(sender)), 11);
+        }
+
+        #[test]
+        fn fails_on_insufficient_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(12u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 11,
+                    required: 12,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+        }
+
+        #[test]
+        fn fails_on_recipient_overflow() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            // Balances cannot get this large through the contract's own messages
+            PrefixedStorage::new(&mut deps.storage, PREFIX_BALANCES)
+                .set(b"addr1111", &(u128::MAX - 5).to_be_bytes());
+
+            let transfer_msg = ExecuteMsg::transfer("addr1111", 5u128);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                u128::MAX
+            );
+
+            let transfer_msg = ExecuteMsg::transfer("addr1111", 1u128);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Overflow(_)) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_on_invalid_recipient() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let transfer_msg = ExecuteMsg::transfer("x", 1u128);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Context { context, source }) => {
+                    assert_eq!(context, "invalid recipient");
+                    match *source {
+                        ContractError::Std(StdError::GenericErr { .. }) => {}
+                        e => panic!("unexpected source: {:?}", e),
+                    }
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+        }
+    }
+
+    mod send {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{attr, to_binary};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        #[test]
+        fn transfers_and_calls_receive_hook() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let payload = to_binary(&"swap").unwrap();
+            let msg = ExecuteMsg::send("contract", 4u128, Some(payload.clone()));
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(
+                res.messages,
+                vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "contract".to_string(),
+                    msg: Binary::from(
+                        br#"{"receive":{"sender":"addr0000","amount":"4","msg":"InN3YXAi"}}"#
+                            .to_vec()
+                    ),
+                    send: vec![],
+                })]
+            );
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "send"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "contract"),
+                    attr("amount", "4"),
+                ]
+            );
+            assert_eq!(
+                parse_attributes(&res.attributes).unwrap(),
+                vec![Erc20Event::Transfer {
+                    from: "addr0000".to_string(),
+                    to: "contract".to_string(),
+                    amount: Uint128::from(4u128),
+                }]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 7);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("contract")), 4);
+        }
+
+        #[test]
+        fn works_without_payload() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::send("contract", 11u128, None);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            let receive = ReceiveMsg {
+                sender: "addr0000".to_string(),
+                amount: Uint128::from(11u128),
+                msg: None,
+            };
+            assert_eq!(
+                res.messages,
+                vec![receive.into_cosmos_msg("contract").unwrap()]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 0);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("contract")), 11);
+        }
+
+        #[test]
+        fn fails_on_insufficient_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::send("contract", 12u128, None);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::InsufficientFunds { balance, required } => {
+                    assert_eq!(balance, 11);
+                    assert_eq!(required, 12);
+                }
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+        }
+    }
+
+    mod batch_transfer {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, Addr, OwnedDeps};
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec!["admin".to_string()],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn batch_transfer(
+            deps: DepsMut,
+            recipients: &[(&str, u128)],
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps, env, info, ExecuteMsg::batch_transfer(recipients))
+        }
+
+        fn balances(storage: &dyn Storage) -> Vec<u128> {
+            ["addr0000", "addr1111", "addr2222"]
+                .iter()
+                .map(|address| get_balance(storage, &Addr::unchecked(*address)))
+                .collect()
+        }
+
+        #[test]
+        fn credits_every_recipient() {
+            let mut deps = setup();
+            let recipients = [("addr1111", 10), ("addr2222", 20), ("addr1111", 5)];
+            let res = batch_transfer(deps.as_mut(), &recipients).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "batch_transfer"),
+                    attr("sender", "addr0000"),
+                    attr("recipients", "3"),
+                    attr("total", "35"),
+                ]
+            );
+            assert_eq!(balances(&deps.storage), vec![65, 15, 20]);
+            assert_eq!(get_total_supply(&deps.storage), 100);
+        }
+
+        #[test]
+        fn fails_on_insufficient_balance() {
+            let mut deps = setup();
+            let recipients = [("addr1111", 60), ("addr2222", 50)];
+            match batch_transfer(deps.as_mut(), &recipients).unwrap_err() {
+                ContractError::InsufficientFunds {
+                    balance: 100,
+                    required: 110,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let recipients = [("addr1111", u128::MAX), ("addr2222", 1)];
+            match batch_transfer(deps.as_mut(), &recipients).unwrap_err() {
+                ContractError::Overflow(_) => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(balances(&deps.storage), vec![100, 0, 0]);
+        }
+
+        #[test]
+        fn limits_batch_size() {
+            let mut deps = setup();
+            let recipients = vec![("addr1111", 1); MAX_BATCH_TRANSFERS];
+            batch_transfer(deps.as_mut(), &recipients).unwrap();
+            let recipients = vec![("addr2222", 1); MAX_BATCH_TRANSFERS + 1];
+            match batch_transfer(deps.as_mut(), &recipients).unwrap_err() {
+                ContractError::TooManyTransfers { max: 50 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(balances(&deps.storage), vec![50, 50, 0]);
+        }
+
+        #[test]
+        fn credits_nobody_on_failure() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("admin", 450, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::add_blacklist("addr2222"),
+            )
+            .unwrap();
+            let recipients = [("addr1111", 10), ("addr2222", 20)];
+            match batch_transfer(deps.as_mut(), &recipients).unwrap_err() {
+                ContractError::RecipientBlacklisted {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(balances(&deps.storage), vec![100, 0, 0]);
+        }
+    }
+
+    mod transfer_all {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, Addr, OwnedDeps};
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(100u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(10u128),
+                    },
+                ],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn transfer_all(
+            deps: DepsMut,
+            sender: &str,
+            recipient: &str,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, ExecuteMsg::transfer_all(recipient))
+        }
+
+        fn has_balance_entry(storage: &dyn Storage, address: &str) -> bool {
+            ReadonlyPrefixedStorage::new(storage, PREFIX_BALANCES)
+                .get(address.as_bytes())
+                .is_some()
+        }
+
+        #[test]
+        fn sweeps_funded_account() {
+            let mut deps = setup();
+            let res = transfer_all(deps.as_mut(), "addr0000", "addr1111").unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "transfer_all"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "addr1111"),
+                    attr("amount", "100"),
+                ]
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111")),
+                110
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 0);
+            assert!(!has_balance_entry(&deps.storage, "addr0000"));
+            assert_eq!(get_total_supply(&deps.storage), 110);
+        }
+
+        #[test]
+        fn sweeps_empty_account() {
+            let mut deps = setup();
+            let res = transfer_all(deps.as_mut(), "addr2222", "addr1111").unwrap();
+            assert_eq!(res.attributes[3], attr("amount", "0"));
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 10);
+            assert!(!has_balance_entry(&deps.storage, "addr2222"));
+        }
+
+        #[test]
+        fn sweeping_to_self_keeps_balance() {
+            let mut deps = setup();
+            transfer_all(deps.as_mut(), "addr0000", "addr0000").unwrap();
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                100
+            );
+        }
+    }
+
+    mod approve {
+        use super::*;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                    InitialBalance {
+                        address: "addrbbbb".to_string(),
+                        amount: Uint128::from(33u128),
+                    },
+                ],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        fn make_spender() -> Addr {
+            Addr::unchecked("dadadadadadadada".to_string())
+        }
+
+        #[test]
+        fn has_zero_allowance_by_default() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Existing owner
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked("addr0000"), &make_spender()),
+                0
+            );
+            // Non-existing owner
+            assert_eq!(
+                get_allowance(
+                    &deps.storage,
+                    &Addr::unchecked("addr4567".to_string()),
+                    &make_spender()
+                ),
+                0
+            );
+        }
+
+        #[test]
+        fn can_set_allowance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_allowance(
+                    &deps.storage,
+                    &Addr::unchecked("addr7654".to_string()),
+                    &make_spender()
+                ),
+                0
+            );
+            // First approval
+            let owner = Addr::unchecked("addr7654".to_string());
+            let spender = make_spender();
+            let approve_msg1 = ExecuteMsg::Approve {
+                spender: spender.clone().to_string().to_string(),
+                amount: Uint128::from(334422u128),
+                expires: None,
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let approve_result1 = execute(deps.as_mut(), env, info, approve_msg1).unwrap();
+            assert_eq!(approve_result1.messages.len(), 0);
+            assert_eq!(
+                approve_result1.attributes,
+                vec![
+                    attr("action", "approve"),
+                    attr("owner", owner.clone().to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("allowance", "334422"),
+                ]
+            );
+            assert_eq!(
+                get_allowance(&deps.storage, &owner, &make_spender()),
+                334422
+            );
+            // Updated approval
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string().to_string(),
+                amount: Uint128::from(777888u128),
+                expires: None,
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let approve_result2 = execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            assert_eq!(approve_result2.messages.len(), 0);
+            assert_eq!(
+                approve_result2.attributes,
+                vec![
+                    attr("action", "approve"),
+                    attr("owner", owner.as_str()),
+                    attr("spender", spender.as_str()),
+                    attr("allowance", "777888"),
+                ]
+            );
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 777888);
+        }
+    }
+
+    mod change_allowance {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, from_binary, Addr, OwnedDeps};
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn run(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, msg)
+        }
+
+        #[test]
+        fn interleaves_with_transfer_from() {
+            let mut deps = setup();
+            let owner = Addr::unchecked("addr0000");
+            let spender = Addr::unchecked("spender");
+
+            let msg = ExecuteMsg::increase_allowance("spender", 5u128);
+            let res = run(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "increase_allowance"),
+                    attr("owner", "addr0000"),
+                    attr("spender", "spender"),
+                    attr("allowance", "5"),
+                ]
+            );
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 2u128);
+            run(deps.as_mut(), "spender", msg).unwrap();
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 3);
+
+            let msg = ExecuteMsg::increase_allowance("spender", 4u128);
+            run(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 7);
+            let msg = ExecuteMsg::decrease_allowance("spender", 3u128);
+            let res = run(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "decrease_allowance"),
+                    attr("owner", "addr0000"),
+                    attr("spender", "spender"),
+                    attr("allowance", "4"),
+                ]
+            );
+
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 4u128);
+            run(deps.as_mut(), "spender", msg).unwrap();
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 1u128);
+            match run(deps.as_mut(), "spender", msg).unwrap_err() {
+                ContractError::InsufficientAllowance {
+                    allowance: 0,
+                    required: 1,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &owner), 94);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 6);
+        }
+
+        #[test]
+        fn decrease_saturates_at_zero() {
+            let mut deps = setup();
+            let owner = Addr::unchecked("addr0000");
+            let spender = Addr::unchecked("spender");
+            run(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::approve("spender", 3u128),
+            )
+            .unwrap();
+            let msg = ExecuteMsg::decrease_allowance("spender", 10u128);
+            let res = run(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(res.attributes[3], attr("allowance", "0"));
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 0);
+
+            // also without any allowance
+            let msg = ExecuteMsg::decrease_allowance("addr1111", 1u128);
+            run(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(
+                get_allowance(&deps.storage, &owner, &Addr::unchecked("addr1111")),
+                0
+            );
+        }
+
+        #[test]
+        fn exact_decrease_fails_below_zero() {
+            let mut deps = setup();
+            let owner = Addr::unchecked("addr0000");
+            let spender = Addr::unchecked("spender");
+            run(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::approve("spender", 3u128),
+            )
+            .unwrap();
+            let msg = ExecuteMsg::DecreaseAllowance {
+                spender: "spender".to_string(),
+                amount: Uint128::from(4u128),
+                exact: true,
+                expires: None,
+            };
+            match run(deps.as_mut(), "addr0000", msg).unwrap_err() {
+                ContractError::InsufficientAllowance {
+                    allowance: 3,
+                    required: 4,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 3);
+
+            // decreasing to exactly zero is fine
+            let msg = ExecuteMsg::DecreaseAllowance {
+                spender: "spender".to_string(),
+                amount: Uint128::from(3u128),
+                exact: true,
+                expires: None,
+            };
+            run(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 0);
+        }
+
+        #[test]
+        fn increase_fails_on_overflow() {
+            let mut deps = setup();
+            let msg = ExecuteMsg::approve("spender", u128::MAX - 1);
+            run(deps.as_mut(), "addr0000", msg).unwrap();
+            let msg = ExecuteMsg::increase_allowance("spender", 2u128);
+            match run(deps.as_mut(), "addr0000", msg).unwrap_err() {
+                ContractError::Overflow(_) => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(
+                get_allowance(
+                    &deps.storage,
+                    &Addr::unchecked("addr0000"),
+                    &Addr::unchecked("spender")
+                ),
+                u128::MAX - 1
+            );
+        }
+
+        #[test]
+        fn approve_still_overwrites() {
+            let mut deps = setup();
+            let msg = ExecuteMsg::increase_allowance("spender", 8u128);
+            run(deps.as_mut(), "addr0000", msg).unwrap();
+            run(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::approve("spender", 2u128),
+            )
+            .unwrap();
+            assert_eq!(
+                get_allowance(
+                    &deps.storage,
+                    &Addr::unchecked("addr0000"),
+                    &Addr::unchecked("spender")
+                ),
+                2
+            );
+        }
+
+        fn query_allowance_at(deps: Deps, height: u64, time: u64) -> AllowanceResponse {
+            let (env, _) = mock_env_height("anyone", height, time);
+            let msg = QueryMsg::allowance("addr0000", "spender");
+            from_binary(&query(deps, env, msg).unwrap()).unwrap()
+        }
+
+        fn approve_until(deps: DepsMut, amount: u128, expires: Expiration) {
+            let msg = ExecuteMsg::Approve {
+                spender: "spender".to_string(),
+                amount: Uint128::from(amount),
+                expires: Some(expires),
+            };
+            run(deps, "addr0000", msg).unwrap();
+        }
+
+        #[test]
+        fn expires_at_height() {
+            let mut deps = setup();
+            approve_until(deps.as_mut(), 10, Expiration::AtHeight(500));
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 499, 550),
+                AllowanceResponse {
+                    allowance: Uint128::from(10u128),
+                    expires: Expiration::AtHeight(500),
+                }
+            );
+
+            // spending keeps the expiration
+            let (env, info) = mock_env_height("spender", 499, 550);
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 4u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 499, 550),
+                AllowanceResponse {
+                    allowance: Uint128::from(6u128),
+                    expires: Expiration::AtHeight(500),
+                }
+            );
+
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 500, 550),
+                AllowanceResponse {
+                    allowance: Uint128::zero(),
+                    expires: Expiration::Never {},
+                }
+            );
+            let (env, info) = mock_env_height("spender", 500, 550);
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 1u128);
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::InsufficientAllowance {
+                    allowance: 0,
+                    required: 1,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let (env, info) = mock_env_height("spender", 500, 550);
+            let msg = ExecuteMsg::burn_from("addr0000", 1u128);
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::InsufficientAllowance { allowance: 0, .. } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn expires_at_time() {
+            let mut deps = setup();
+            approve_until(deps.as_mut(), 10, Expiration::AtTime(600));
+            // the height does not matter
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 1_000_000, 599).allowance,
+                Uint128::from(10u128)
+            );
+            let (env, info) = mock_env_height("spender", 1_000_000, 599);
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 4u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 450, 600),
+                AllowanceResponse {
+                    allowance: Uint128::zero(),
+                    expires: Expiration::Never {},
+                }
+            );
+            let (env, info) = mock_env_height("spender", 450, 600);
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 1u128);
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::InsufficientAllowance {
+                    allowance: 0,
+                    required: 1,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn never_expires() {
+            let mut deps = setup();
+            approve_until(deps.as_mut(), 10, Expiration::Never {});
+            let (env, info) = mock_env_height("spender", u64::MAX, u64::MAX / 1_000_000_000);
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 10u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+
+            // like an approval without expiration
+            run(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::approve("spender", 3u128),
+            )
+            .unwrap();
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), u64::MAX, u64::MAX / 1_000_000_000),
+                AllowanceResponse {
+                    allowance: Uint128::from(3u128),
+                    expires: Expiration::Never {},
+                }
+            );
+        }
+
+        #[test]
+        fn rejects_passed_expiration() {
+            let mut deps = setup();
+            let msg = ExecuteMsg::Approve {
+                spender: "spender".to_string(),
+                amount: Uint128::from(10u128),
+                expires: Some(Expiration::AtHeight(450)),
+            };
+            match run(deps.as_mut(), "addr0000", msg).unwrap_err() {
+                ContractError::AllowanceExpirationPassed {
+                    expires: Expiration::AtHeight(450),
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender".to_string(),
+                amount: Uint128::from(10u128),
+                expires: Some(Expiration::AtTime(549)),
+            };
+            match run(deps.as_mut(), "addr0000", msg).unwrap_err() {
+                ContractError::AllowanceExpirationPassed {
+                    expires: Expiration::AtTime(549),
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn sweeps_only_expired_allowances() {
+            let mut deps = setup();
+            approve_until(deps.as_mut(), 10, Expiration::AtHeight(500));
+            let msg = ExecuteMsg::sweep_expired_allowances("addr0000", vec!["spender", "nobody"]);
+            let res = run(deps.as_mut(), "anyone", msg.clone()).unwrap();
+            assert_eq!(res.attributes[2], attr("swept", "0"));
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 450, 550).allowance,
+                Uint128::from(10u128)
+            );
+
+            let (env, info) = mock_env_height("anyone", 500, 550);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(res.attributes[2], attr("swept", "1"));
+            for prefix in &[PREFIX_ALLOWANCES, PREFIX_ALLOWANCE_EXPIRIES] {
+                let store =
+                    ReadonlyPrefixedStorage::multilevel(&deps.storage, &[prefix, b"addr0000"]);
+                assert_eq!(store.get(b"spender"), None);
+            }
+        }
+
+        #[test]
+        fn limits_spenders_swept() {
+            let mut deps = setup();
+            let spenders = vec!["spender"; MAX_SWEEP_SPENDERS];
+            let msg = ExecuteMsg::sweep_expired_allowances("addr0000", spenders);
+            run(deps.as_mut(), "anyone", msg).unwrap();
+            let spenders = vec!["spender"; MAX_SWEEP_SPENDERS + 1];
+            let msg = ExecuteMsg::sweep_expired_allowances("addr0000", spenders);
+            match run(deps.as_mut(), "anyone", msg).unwrap_err() {
+                ContractError::TooManySpenders { max: 50 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn changes_keep_or_replace_expiration() {
+            let mut deps = setup();
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender".to_string(),
+                amount: Uint128::from(10u128),
+                expires: Some(Expiration::AtHeight(500)),
+            };
+            run(deps.as_mut(), "addr0000", msg).unwrap();
+            let msg = ExecuteMsg::decrease_allowance("spender", 3u128);
+            run(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 450, 550).expires,
+                Expiration::AtHeight(500)
+            );
+            let msg = ExecuteMsg::DecreaseAllowance {
+                spender: "spender".to_string(),
+                amount: Uint128::from(3u128),
+                exact: false,
+                expires: Some(Expiration::AtTime(700)),
+            };
+            run(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 600, 699),
+                AllowanceResponse {
+                    allowance: Uint128::from(4u128),
+                    expires: Expiration::AtTime(700),
+                }
+            );
+
+            // an expired allowance starts over from zero without expiration
+            let (env, info) = mock_env_height("addr0000", 450, 700);
+            let msg = ExecuteMsg::increase_allowance("spender", 2u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 1000, 1000),
+                AllowanceResponse {
+                    allowance: Uint128::from(2u128),
+                    expires: Expiration::Never {},
+                }
+            );
+
+            // approving without expiration removes it
+            approve_until(deps.as_mut(), 10, Expiration::AtHeight(500));
+            run(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::approve("spender", 1u128),
+            )
+            .unwrap();
+            assert_eq!(
+                query_allowance_at(deps.as_ref(), 1000, 1000).expires,
+                Expiration::Never {}
+            );
+        }
+    }
+
+    mod permit {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, from_binary, Addr, OwnedDeps};
+        use k256::ecdsa::signature::DigestSigner;
+        use k256::ecdsa::{Signature, SigningKey};
+
+        fn owner_key() -> SigningKey {
+            SigningKey::from_bytes(&[3u8; 32]).unwrap()
+        }
+
+        fn other_key() -> SigningKey {
+            SigningKey::from_bytes(&[4u8; 32]).unwrap()
+        }
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let pubkey = Binary::from(&owner_key().verify_key().to_bytes()[..]);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::set_permit_key(Some(pubkey)),
+            )
+            .unwrap();
+            deps
+        }
+
+        /// Signs a permit of `amount` for addr1111 and returns the message submitting it
+        fn permit(key: &SigningKey, amount: u128, nonce: u64, expiry: u64) -> ExecuteMsg {
+            let env = mock_env();
+            let permit = PermitMsg {
+                chain_id: env.block.chain_id,
+                contract: env.contract.address.to_string(),
+                owner: "addr0000".to_string(),
+                spender: "addr1111".to_string(),
+                amount: Uint128::from(amount),
+                nonce,
+                expiry,
+            };
+            let digest = Sha256::new().chain(to_vec(&permit).unwrap());
+            let signature: Signature = key.sign_digest(digest);
+            ExecuteMsg::Permit {
+                owner: permit.owner,
+                spender: permit.spender,
+                amount: permit.amount,
+                nonce,
+                expiry,
+                signature: Binary::from(signature.as_ref()),
+            }
+        }
+
+        fn submit(deps: DepsMut, msg: ExecuteMsg) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height("relayer", 450, 550);
+            execute(deps, env, info, msg)
+        }
+
+        fn nonce(deps: Deps) -> u64 {
+            let msg = QueryMsg::permit_nonce("addr0000");
+            let res: PermitNonceResponse =
+                from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.nonce
+        }
+
+        fn allowance(storage: &dyn Storage) -> u128 {
+            get_allowance(
+                storage,
+                &Addr::unchecked("addr0000"),
+                &Addr::unchecked("addr1111"),
+            )
+        }
+
+        #[test]
+        fn sets_allowance() {
+            let mut deps = setup();
+            let res = submit(deps.as_mut(), permit(&owner_key(), 40, 0, 500)).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "permit"),
+                    attr("owner", "addr0000"),
+                    attr("spender", "addr1111"),
+                    attr("allowance", "40"),
+                    attr("nonce", "0"),
+                ]
+            );
+            assert_eq!(allowance(&deps.storage), 40);
+            assert_eq!(nonce(deps.as_ref()), 1);
+
+            let (env, info) = mock_env_height("addr1111", 450, 550);
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 30u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(allowance(&deps.storage), 10);
+
+            submit(deps.as_mut(), permit(&owner_key(), 0, 1, 500)).unwrap();
+            assert_eq!(allowance(&deps.storage), 0);
+        }
+
+        #[test]
+        fn rejects_replay() {
+            let mut deps = setup();
+            let msg = permit(&owner_key(), 40, 0, 500);
+            submit(deps.as_mut(), msg.clone()).unwrap();
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let approve = ExecuteMsg::approve("addr1111", 5u128);
+            execute(deps.as_mut(), env, info, approve).unwrap();
+            match submit(deps.as_mut(), msg).unwrap_err() {
+                ContractError::InvalidPermitNonce { expected: 1 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(allowance(&deps.storage), 5);
+        }
+
+        #[test]
+        fn rejects_wrong_signer() {
+            let mut deps = setup();
+            match submit(deps.as_mut(), permit(&other_key(), 40, 0, 500)).unwrap_err() {
+                ContractError::InvalidPermitSignature {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            // the signature covers all fields
+            let mut msg = permit(&owner_key(), 40, 0, 500);
+            if let ExecuteMsg::Permit { amount, .. } = &mut msg {
+                *amount = Uint128::from(50u128);
+            }
+            match submit(deps.as_mut(), msg).unwrap_err() {
+                ContractError::InvalidPermitSignature {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+
+            // removing the key disables permits
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, ExecuteMsg::set_permit_key(None)).unwrap();
+            match submit(deps.as_mut(), permit(&owner_key(), 40, 0, 500)).unwrap_err() {
+                ContractError::PermitKeyNotSet {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(allowance(&deps.storage), 0);
+            assert_eq!(nonce(deps.as_ref()), 0);
+        }
+
+        #[test]
+        fn rejects_invalid_permit_key() {
+            let mut deps = setup();
+            let mut truncated = owner_key().verify_key().to_bytes().to_vec();
+            truncated.pop();
+            for pubkey in &[truncated, vec![0x05; 33], vec![]] {
+                let msg = ExecuteMsg::set_permit_key(Some(Binary::from(pubkey.as_slice())));
+                let (env, info) = mock_env_height("addr0000", 450, 550);
+                match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                    ContractError::InvalidPermitKey {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
+            // the previous key stays in place
+            submit(deps.as_mut(), permit(&owner_key(), 40, 0, 500)).unwrap();
+            assert_eq!(allowance(&deps.storage), 40);
+        }
+
+        #[test]
+        fn rejects_expired_permit() {
+            let mut deps = setup();
+            match submit(deps.as_mut(), permit(&owner_key(), 40, 0, 450)).unwrap_err() {
+                ContractError::PermitExpired { expiry: 450 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            submit(deps.as_mut(), permit(&owner_key(), 40, 0, 451)).unwrap();
+        }
+    }
+
+    mod transfer_from {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{attr, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                    InitialBalance {
+                        address: "addrbbbb".to_string(),
+                        amount: Uint128::from(33u128),
+                    },
+                ],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        fn make_spender() -> Addr {
+            Addr::unchecked("dadadadadadadada".to_string())
+        }
+
+        #[test]
+        fn works() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let owner = "addr0000";
+            let spender = make_spender();
+            let recipient = Addr::unchecked("addr1212".to_string());
+            // Set approval
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string().to_string(),
+                amount: Uint128::from(4u128),
+                expires: None,
+            };
+            let (env, info) = mock_env_height(owner, 450, 550);
+            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            assert_eq!(approve_result.messages.len(), 0);
+            assert_eq!(
+                approve_result.attributes,
+                vec![
+                    attr("action", "approve"),
+                    attr("owner", owner.to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("allowance", "4"),
+                ]
+            );
+            assert_eq!(
+                parse_attributes(&approve_result.attributes).unwrap(),
+                vec![Erc20Event::Approval {
+                    owner: owner.to_string(),
+                    spender: spender.to_string(),
+                    allowance: Uint128::from(4u128),
+                }]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 11);
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
+                4
+            );
+            // Transfer less than allowance but more than balance
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: owner.to_string().to_string(),
+                recipient: recipient.clone().to_string(),
+                amount: Uint128::from(3u128),
+            };
+            let (env, info) = mock_env_height(spender.as_str(), 450, 550);
+            let transfer_from_result =
+                execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
+            assert_eq!(transfer_from_result.messages.len(), 0);
+            assert_eq!(
+                transfer_from_result.attributes,
+                vec![
+                    attr("action", "transfer_from"),
+                    attr("spender", spender.clone()),
+                    attr("sender", owner),
+                    attr("recipient", recipient),
+                    attr("amount", "3"),
+                ]
+            );
+            assert_eq!(
+                parse_attributes(&transfer_from_result.attributes).unwrap(),
+                vec![Erc20Event::Transfer {
+                    from: owner.to_string(),
+                    to: "addr1212".to_string(),
+                    amount: Uint128::from(3u128),
+                }]
+            );
+            // State changed
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 8);
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
+                1
+            );
+        }
+
+        #[test]
+        fn fails_when_allowance_too_low() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let owner = "addr0000";
+            let spender = make_spender();
+            let recipient = Addr::unchecked("addr1212".to_string());
+            // Set approval
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string(),
+                amount: Uint128::from(2u128),
+                expires: None,
+            };
+            let (env, info) = mock_env_height(owner, 450, 550);
+            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            assert_eq!(approve_result.messages.len(), 0);
+            assert_eq!(
+                approve_result.attributes,
+                vec![
+                    attr("action", "approve"),
+                    attr("owner", owner.to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("allowance", "2"),
+                ]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 11);
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
+                2
+            );
+            // Transfer less than allowance but more than balance
+            let fransfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: owner.to_string(),
+                recipient: recipient.clone().to_string(),
+                amount: Uint128::from(3u128),
+            };
+            let (env, info) = mock_env_height(spender.as_str(), 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientAllowance {
+                    allowance: 2,
+                    required: 3,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_when_allowance_is_set_but_balance_too_low() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let owner = "addr0000";
+            let spender = make_spender();
+            let recipient = Addr::unchecked("addr1212".to_string());
+            // Set approval
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string(),
+                amount: Uint128::from(20u128),
+                expires: None,
+            };
+            let (env, info) = mock_env_height(owner, 450, 550);
+            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            assert_eq!(approve_result.messages.len(), 0);
+            assert_eq!(
+                approve_result.attributes,
+                vec![
+                    attr("action", "approve"),
+                    attr("owner", owner.to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("allowance", "20"),
+                ]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 11);
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
+                20
+            );
+            // Transfer less than allowance but more than balance
+            let fransfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: owner.to_string(),
+                recipient: recipient.clone().to_string(),
+                amount: Uint128::from(15u128),
+            };
+            let (env, info) = mock_env_height(spender.as_str(), 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 11,
+                    required: 15,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod burn {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{attr, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                ],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        #[test]
+        fn can_burn_from_existing_account() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(get_total_supply(&deps.storage), 33);
+            // Burn
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let burn_result = execute(deps.as_mut(), env, info, burn_msg).unwrap();
+            assert_eq!(burn_result.messages.len(), 0);
+            assert_eq!(
+                burn_result.attributes,
+                vec![
+                    attr("action", "burn"),
+                    attr("account", "addr0000"),
+                    attr("amount", "1")
+                ]
+            );
+            assert_eq!(
+                parse_attributes(&burn_result.attributes).unwrap(),
+                vec![Erc20Event::Burn {
+                    from: "addr0000".to_string(),
+                    amount: Uint128::from(1u128),
+                }]
+            );
+            // New state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                10
+            ); // -1
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(get_total_supply(&deps.storage), 32);
+        }
+
+        #[test]
+        fn can_burn_zero_amount() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(get_total_supply(&deps.storage), 33);
+            // Burn
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(0u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let burn_result = execute(deps.as_mut(), env, info, burn_msg).unwrap();
+            assert_eq!(burn_result.messages.len(), 0);
+            assert_eq!(
+                burn_result.attributes,
+                vec![
+                    attr("action", "burn"),
+                    attr("account", "addr0000"),
+                    attr("amount", "0"),
+                ]
+            );
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(get_total_supply(&deps.storage), 33);
+        }
+
+        #[test]
+        fn fails_on_insufficient_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(get_total_supply(&deps.storage), 33);
+            // Burn
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(12u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let burn_result = execute(deps.as_mut(), env, info, burn_msg);
+            match burn_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 11,
+                    required: 12,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(get_total_supply(&deps.storage), 33);
+        }
+    }
+
+    mod burn_from {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, Addr, OwnedDeps};
+
+        fn setup(allowance: u128) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let msg = ExecuteMsg::approve("spender", allowance);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            deps
+        }
+
+        #[test]
+        fn burns_exactly_the_allowance() {
+            let mut deps = setup(4);
+            let (env, info) = mock_env_height("spender", 450, 550);
+            let res = execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::burn_from("addr0000", 4u128),
+            )
+            .unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "burn_from"),
+                    attr("spender", "spender"),
+                    attr("account", "addr0000"),
+                    attr("amount", "4"),
+                ]
+            );
+            assert_eq!(
+                parse_attributes(&res.attributes).unwrap(),
+                vec![Erc20Event::Burn {
+                    from: "addr0000".to_string(),
+                    amount: Uint128::from(4u128),
+                }]
+            );
+            let owner = Addr::unchecked("addr0000");
+            let spender = Addr::unchecked("spender");
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 0);
+            assert_eq!(get_balance(&deps.storage, &owner), 7);
+            assert_eq!(get_total_supply(&deps.storage), 7);
+        }
+
+        #[test]
+        fn reduces_allowance_by_burned_amount() {
+            let mut deps = setup(10);
+            let owner = Addr::unchecked("addr0000");
+            let spender = Addr::unchecked("spender");
+            for (amount, allowance) in &[(3u128, 7u128), (0, 7), (5, 2)] {
+                let (env, info) = mock_env_height("spender", 450, 550);
+                let msg = ExecuteMsg::burn_from("addr0000", *amount);
+                execute(deps.as_mut(), env, info, msg).unwrap();
+                assert_eq!(get_allowance(&deps.storage, &owner, &spender), *allowance);
+            }
+            assert_eq!(get_balance(&deps.storage, &owner), 3);
+            assert_eq!(get_total_supply(&deps.storage), 3);
+        }
+
+        #[test]
+        fn reports_allowance_and_balance_differently() {
+            let mut deps = setup(4);
+            let (env, info) = mock_env_height("spender", 450, 550);
+            let msg = ExecuteMsg::burn_from("addr0000", 5u128);
+            let allowance_err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(
+                allowance_err.to_string(),
+                "Insufficient allowance (allowance 4, required=5)"
+            );
+
+            let mut deps = setup(100);
+            let (env, info) = mock_env_height("spender", 450, 550);
+            let msg = ExecuteMsg::burn_from("addr0000", 12u128);
+            let balance_err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(
+                balance_err.to_string(),
+                "Insufficient funds (balance 11, required=12)"
+            );
+        }
+
+        #[test]
+        fn fails_on_insufficient_allowance() {
+            let mut deps = setup(4);
+            let (env, info) = mock_env_height("spender", 450, 550);
+            match execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::burn_from("addr0000", 5u128),
+            ) {
+                Err(ContractError::InsufficientAllowance {
+                    allowance: 4,
+                    required: 5,
+                }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            // without any allowance
+            let (env, info) = mock_env_height("other", 450, 550);
+            match execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::burn_from("addr0000", 1u128),
+            ) {
+                Err(ContractError::InsufficientAllowance {
+                    allowance: 0,
+                    required: 1,
+                }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 11);
+        }
+
+        #[test]
+        fn fails_on_insufficient_balance() {
+            let mut deps = setup(100);
+            let (env, info) = mock_env_height("spender", 450, 550);
+            match execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::burn_from("addr0000", 12u128),
+            ) {
+                Err(ContractError::InsufficientFunds {
+                    balance: 11,
+                    required: 12,
+                }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+            assert_eq!(get_total_supply(&deps.storage), 11);
+        }
+    }
+
+    mod mint {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{attr, from_binary, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: Some("minter".to_string()),
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        #[test]
+        fn minter_mints_directly() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let recipient = Addr::unchecked("addr1111");
+
+            let (env, info) = mock_env_height("minter", 100, 550);
+            let msg = ExecuteMsg::mint(recipient.as_str(), 40u128);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "mint"),
+                    attr("minter", "minter"),
+                    attr("recipient", "addr1111"),
+                    attr("amount", "40"),
+                ]
+            );
+            assert_eq!(
+                parse_attributes(&res.attributes).unwrap(),
+                vec![Erc20Event::Mint {
+                    to: "addr1111".to_string(),
+                    amount: Uint128::from(40u128),
+                }]
+            );
+            assert_eq!(get_balance(&deps.storage, &recipient), 40);
+            assert_eq!(get_total_supply(&deps.storage), 51);
+
+            let (env, info) = mock_env_height("addr1111", 100, 550);
+            let msg = ExecuteMsg::mint(recipient.as_str(), 40u128);
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 51);
+        }
+
+        #[test]
+        fn fails_without_minter() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                minter: None,
+                ..make_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            for sender in &["creator", "minter", "addr0000"] {
+                let (env, info) = mock_env_height(sender, 100, 550);
+                let msg = ExecuteMsg::mint("addr1111", 1u128);
+                match execute(deps.as_mut(), env, info, msg) {
+                    Err(ContractError::NoAdmin {}) => {}
+                    res => panic!("unexpected result: {:?}", res),
+                }
+            }
+            assert_eq!(get_total_supply(&deps.storage), 11);
+        }
+
+        #[test]
+        fn rejects_total_supply_overflow() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            // up to the maximum is fine
+            let (env, info) = mock_env_height("minter", 100, 550);
+            let msg = ExecuteMsg::mint("addr1111", u128::MAX - 11);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(get_total_supply(&deps.storage), u128::MAX);
+
+            let (env, info) = mock_env_height("minter", 101, 550);
+            match execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::mint("addr0000", 1u128),
+            ) {
+                Err(ContractError::Overflow(_)) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_total_supply(&deps.storage), u128::MAX);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+        }
+
+        #[test]
+        fn respects_rate_limit() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                mint_limit_per_window: Some(Uint128::from(100u128)),
+                mint_window_blocks: 10,
+                ..make_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let (env, info) = mock_env_height("minter", 100, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::mint("addr1111", 70u128),
+            )
+            .unwrap();
+            let (env, info) = mock_env_height("minter", 109, 550);
+            match execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::mint("addr1111", 31u128),
+            ) {
+                Err(ContractError::MintRateLimitExceeded {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let (env, info) = mock_env_height("minter", 110, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::mint("addr1111", 31u128),
+            )
+            .unwrap();
+            assert_eq!(get_total_supply(&deps.storage), 112);
+        }
+
+        fn query_minter(deps: Deps) -> MinterResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::minter()).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn updates_minter_right_away() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let (env, info) = mock_env_height("minter2", 90, 550);
+            let msg = ExecuteMsg::update_minter(Some("minter2"));
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let (env, info) = mock_env_height("minter", 90, 550);
+            let msg = ExecuteMsg::update_minter(Some("minter2"));
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "update_minter"),
+                    attr("minter", "minter"),
+                    attr("new_minter", "minter2"),
+                ]
+            );
+            assert_eq!(
+                query_minter(deps.as_ref()),
+                MinterResponse {
+                    minter: Some(Addr::unchecked("minter2")),
+                    pending_minter: None,
+                }
+            );
+
+            // the old minter cannot mint anymore
+            let (env, info) = mock_env_height("minter", 100, 550);
+            match execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::mint("addr1111", 1u128),
+            ) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let (env, info) = mock_env_height("minter2", 100, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::mint("addr1111", 1u128),
+            )
+            .unwrap();
+            assert_eq!(get_total_supply(&deps.storage), 12);
+        }
+
+        #[test]
+        fn revokes_minter_for_good() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            // a schedule created before the revocation
+            let (env, info) = mock_env_height("minter", 90, 550);
+            let msg = ExecuteMsg::SetMintSchedule {
+                recipient: "addr1111".to_string(),
+                rate: Uint128::from(1u128),
+                start: 100,
+                end: 200,
+            };
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            let (env, info) = mock_env_height("minter", 90, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::transfer_minter("minter2"),
+            )
+            .unwrap();
+            assert_eq!(
+                query_minter(deps.as_ref()),
+                MinterResponse {
+                    minter: Some(Addr::unchecked("minter")),
+                    pending_minter: Some(Addr::unchecked("minter2")),
+                }
+            );
+
+            let (env, info) = mock_env_height("minter2", 90, 550);
+            let msg = ExecuteMsg::update_minter(None::<String>);
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let (env, info) = mock_env_height("minter", 90, 550);
+            let msg = ExecuteMsg::update_minter(None::<String>);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![attr("action", "update_minter"), attr("minter", "minter")]
+            );
+            assert_eq!(
+                query_minter(deps.as_ref()),
+                MinterResponse {
+                    minter: None,
+                    pending_minter: None,
+                }
+            );
+
+            // the pending transfer is gone and nobody can take over or mint
+            let (env, info) = mock_env_height("minter2", 90, 550);
+            match execute(deps.as_mut(), env, info, ExecuteMsg::accept_minter()) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            for sender in &["minter", "minter2"] {
+                let (env, info) = mock_env_height(sender, 100, 550);
+                match execute(
+                    deps.as_mut(),
+                    env,
+                    info,
+                    ExecuteMsg::mint("addr1111", 1u128),
+                ) {
+                    Err(ContractError::NoAdmin {}) => {}
+                    res => panic!("unexpected result: {:?}", res),
+                }
+                let (env, info) = mock_env_height(sender, 100, 550);
+                match execute(
+                    deps.as_mut(),
+                    env,
+                    info,
+                    ExecuteMsg::transfer_minter(*sender),
+                ) {
+                    Err(ContractError::NoAdmin {}) => {}
+                    res => panic!("unexpected result: {:?}", res),
+                }
+            }
+            // nor can the schedule created before
+            let (env, info) = mock_env_height("anyone", 150, 550);
+            let msg = ExecuteMsg::ExecuteMintSchedule { schedule_id: 0 };
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::NoAdmin {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 11);
+        }
+
+        #[test]
+        fn revoking_minter_stops_approved_proposals() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                mint_approval: Some(crate::msg::MintApprovalConfig {
+                    approvers: vec!["approver1".to_string(), "approver2".to_string()],
+                    threshold: 2,
+                    proposal_expiry: 10,
+                }),
+                ..make_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let (env, info) = mock_env_height("minter", 90, 550);
+            let msg = ExecuteMsg::ProposeMint {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(50u128),
+            };
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            for approver in &["approver1", "approver2"] {
+                let (env, info) = mock_env_height(approver, 91, 550);
+                let msg = ExecuteMsg::ApproveMint { mint_id: 0 };
+                execute(deps.as_mut(), env, info, msg).unwrap();
+            }
+
+            let (env, info) = mock_env_height("minter", 92, 550);
+            let msg = ExecuteMsg::update_minter(None::<String>);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            let (env, info) = mock_env_height("anyone", 93, 550);
+            let msg = ExecuteMsg::ExecuteMint { mint_id: 0 };
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::NoAdmin {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 11);
+        }
+    }
+
+    mod mint_schedule {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{attr, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: Some("minter".to_string()),
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        fn set_schedule(deps: DepsMut, recipient: &str, rate: u128, start: u64, end: u64) -> u64 {
+            let msg = ExecuteMsg::SetMintSchedule {
+                recipient: recipient.to_string(),
+                rate: Uint128::from(rate),
+                start,
+                end,
+            };
+            let (env, info) = mock_env_height("minter", 90, 550);
+            let res = execute(deps, env, info, msg).unwrap();
+            assert_eq!(res.attributes[0], attr("action", "set_mint_schedule"));
+            res.attributes[1].value.parse().unwrap()
+        }
+
+        fn execute_schedule(deps: DepsMut, schedule_id: u64, height: u64) -> Response {
+            let msg = ExecuteMsg::ExecuteMintSchedule { schedule_id };
+            let (env, info) = mock_env_height("anyone", height, 550);
+            execute(deps, env, info, msg).unwrap()
+        }
+
+        #[test]
+        fn accumulates_per_block() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let recipient = Addr::unchecked("addr1111");
+
+            let schedule_id = set_schedule(deps.as_mut(), recipient.as_str(), 10, 100, 200);
+            assert_eq!(schedule_id, 0);
+
+            // Nothing accrued before start
+            let res = execute_schedule(deps.as_mut(), schedule_id, 95);
+            assert_eq!(res.attributes[3], attr("amount", "0"));
+            assert_eq!(get_balance(&deps.storage, &recipient), 0);
+
+            let res = execute_schedule(deps.as_mut(), schedule_id, 150);
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "execute_mint_schedule"),
+                    attr("schedule_id", "0"),
+                    attr("recipient", "addr1111"),
+                    attr("amount", "500"),
+                ]
+            );
+            assert_eq!(
+                parse_attributes(&res.attributes).unwrap(),
+                vec![Erc20Event::Mint {
+                    to: "addr1111".to_string(),
+                    amount: Uint128::from(500u128),
+                }]
+            );
+            assert_eq!(get_balance(&deps.storage, &recipient), 500);
+            assert_eq!(get_total_supply(&deps.storage), 511);
+
+            // Executing twice in the same block mints nothing
+            execute_schedule(deps.as_mut(), schedule_id, 150);
+            assert_eq!(get_balance(&deps.storage, &recipient), 500);
+
+            execute_schedule(deps.as_mut(), schedule_id, 160);
+            assert_eq!(get_balance(&deps.storage, &recipient), 600);
+            assert_eq!(get_total_supply(&deps.storage), 611);
+        }
+
+        #[test]
+        fn caps_at_end_block() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let recipient = Addr::unchecked("addr1111");
+
+            let schedule_id = set_schedule(deps.as_mut(), recipient.as_str(), 10, 100, 200);
+            execute_schedule(deps.as_mut(), schedule_id, 180);
+            assert_eq!(get_balance(&deps.storage, &recipient), 800);
+
+            let res = execute_schedule(deps.as_mut(), schedule_id, 300);
+            assert_eq!(res.attributes[3], attr("amount", "200"));
+            assert_eq!(get_balance(&deps.storage, &recipient), 1000);
+
+            let res = execute_schedule(deps.as_mut(), schedule_id, 400);
+            assert_eq!(res.attributes[3], attr("amount", "0"));
+            assert_eq!(get_balance(&deps.storage, &recipient), 1000);
+            assert_eq!(get_total_supply(&deps.storage), 1011);
+        }
+
+        #[test]
+        fn stale_call_mints_full_backlog() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let recipient = Addr::unchecked("addr1111");
+
+            let schedule_id = set_schedule(deps.as_mut(), recipient.as_str(), 7, 100, 200);
+            let res = execute_schedule(deps.as_mut(), schedule_id, 190);
+            assert_eq!(res.attributes[3], attr("amount", "630"));
+            assert_eq!(get_balance(&deps.storage, &recipient), 630);
+        }
+
+        #[test]
+        fn runs_multiple_schedules_concurrently() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let recipient1 = Addr::unchecked("addr1111");
+            let recipient2 = Addr::unchecked("addr2222");
+
+            let first = set_schedule(deps.as_mut(), recipient1.as_str(), 10, 100, 200);
+            let second = set_schedule(deps.as_mut(), recipient2.as_str(), 3, 120, 130);
+            assert_eq!((first, second), (0, 1));
+
+            execute_schedule(deps.as_mut(), second, 150);
+            execute_schedule(deps.as_mut(), first, 150);
+            assert_eq!(get_balance(&deps.storage, &recipient1), 500);
+            assert_eq!(get_balance(&deps.storage, &recipient2), 30);
+            assert_eq!(get_total_supply(&deps.storage), 541);
+        }
+
+        #[test]
+        fn respects_rate_limit() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                mint_limit_per_window: Some(Uint128::from(100u128)),
+                mint_window_blocks: 10,
+                mint_approval: None,
+                ..make_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let recipient = Addr::unchecked("addr1111");
+
+            let schedule_id = set_schedule(deps.as_mut(), recipient.as_str(), 10, 100, 200);
+            // The first mint starts a window of blocks 105 to 114
+            execute_schedule(deps.as_mut(), schedule_id, 105);
+            execute_schedule(deps.as_mut(), schedule_id, 110);
+            assert_eq!(get_balance(&deps.storage, &recipient), 100);
+
+            let msg = ExecuteMsg::ExecuteMintSchedule { schedule_id };
+            let (env, info) = mock_env_height("anyone", 114, 550);
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::MintRateLimitExceeded {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_balance(&deps.storage, &recipient), 100);
+            assert_eq!(get_total_supply(&deps.storage), 111);
+
+            // A new window starts at block 115
+            let res = execute_schedule(deps.as_mut(), schedule_id, 115);
+            assert_eq!(res.attributes[3], attr("amount", "50"));
+            assert_eq!(get_balance(&deps.storage, &recipient), 150);
+        }
+
+        #[test]
+        fn rate_limit_requires_window() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                mint_limit_per_window: Some(Uint128::from(100u128)),
+                mint_window_blocks: 0,
+                mint_approval: None,
+                ..make_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 90, 550);
+            match instantiate(deps.as_mut(), env, info, instantiate_msg) {
+                Err(ContractError::InvalidMintWindow {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+
+        #[test]
+        fn fails_for_non_minter() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::SetMintSchedule {
+                recipient: "addr1111".to_string(),
+                rate: Uint128::from(10u128),
+                start: 100,
+                end: 200,
+            };
+            let (env, info) = mock_env_height("addr0000", 90, 550);
+            let result = execute(deps.as_mut(), env, info, msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_without_minter() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                minter: None,
+                ..make_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let msg = ExecuteMsg::SetMintSchedule {
+                recipient: "addr1111".to_string(),
+                rate: Uint128::from(10u128),
+                start: 100,
+                end: 200,
+            };
+            let (env, info) = mock_env_height("minter", 90, 550);
+            let result = execute(deps.as_mut(), env, info, msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NoAdmin {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn transfers_minter_role() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let (env, info) = mock_env_height("addr0000", 90, 550);
+            match execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::transfer_minter("addr0000"),
+            ) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            let (env, info) = mock_env_height("minter", 90, 550);
+            let res = execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::transfer_minter("minter2"),
+            )
+            .unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "transfer_minter"),
+                    attr("minter", "minter"),
+                    attr("candidate", "minter2"),
+                ]
+            );
+            // still the minter until the transfer is accepted
+            set_schedule(deps.as_mut(), "addr1111", 10, 100, 200);
+
+            let (env, info) = mock_env_height("addr0000", 90, 550);
+            match execute(deps.as_mut(), env, info, ExecuteMsg::accept_minter()) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let (env, info) = mock_env_height("minter2", 90, 550);
+            let res = execute(deps.as_mut(), env, info, ExecuteMsg::accept_minter()).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![attr("action", "accept_minter"), attr("minter", "minter2")]
+            );
+            assert_eq!(
+                MINTER.load(&deps.storage).unwrap(),
+                Some(Addr::unchecked("minter2"))
+            );
+
+            let msg = ExecuteMsg::SetMintSchedule {
+                recipient: "addr1111".to_string(),
+                rate: Uint128::from(10u128),
+                start: 100,
+                end: 200,
+            };
+            let (env, info) = mock_env_height("minter", 90, 550);
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+
+        #[test]
+        fn fails_for_empty_range() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::SetMintSchedule {
+                recipient: "addr1111".to_string(),
+                rate: Uint128::from(10u128),
+                start: 200,
+                end: 200,
+            };
+            let (env, info) = mock_env_height("minter", 90, 550);
+            let result = execute(deps.as_mut(), env, info, msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidMintSchedule {
+                    start: 200,
+                    end: 200,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_unknown_schedule() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 90, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::ExecuteMintSchedule { schedule_id: 3 };
+            let (env, info) = mock_env_height("anyone", 150, 550);
+            let result = execute(deps.as_mut(), env, info, msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintScheduleNotFound { schedule_id: 3 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod mint_approval {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::events::{parse_attributes, Erc20Event};
+        use crate::msg::MintApprovalConfig;
+        use cosmwasm_std::{attr, from_binary};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: Some("minter".to_string()),
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: Some(MintApprovalConfig {
+                    approvers: vec![
+                        "approver1".to_string(),
+                        "approver2".to_string(),
+                        "approver3".to_string(),
+                    ],
+                    threshold: 2,
+                    proposal_expiry: 10,
+                }),
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        fn execute_at(
+            deps: DepsMut,
+            sender: &str,
+            height: u64,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, height, 550);
+            execute(deps, env, info, msg)
+        }
+
+        fn mint_proposal(deps: Deps, mint_id: u64) -> Option<MintProposal> {
+            let res = query(deps, mock_env(), QueryMsg::mint_proposal(mint_id)).unwrap();
+            let res: MintProposalResponse = from_binary(&res).unwrap();
+            res.proposal
+        }
+
+        #[test]
+        fn validates_config() {
+            let configs = vec![
+                (0, vec!["approver1", "approver2"], 10),
+                (3, vec!["approver1", "approver2"], 10),
+                (1, vec![], 10),
+            ];
+            for (threshold, approvers, proposal_expiry) in configs {
+                let mut msg = make_instantiate_msg();
+                msg.mint_approval = Some(MintApprovalConfig {
+                    approvers: approvers.iter().map(|a| a.to_string()).collect(),
+                    threshold,
+                    proposal_expiry,
+                });
+                let mut deps = mock_dependencies(&[]);
+                let (env, info) = mock_env_height("creator", 100, 550);
+                match instantiate(deps.as_mut(), env, info, msg).unwrap_err() {
+                    ContractError::InvalidMintThreshold { approvers: n } => {
+                        assert_eq!(n, approvers.len())
+                    }
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
+
+            let mut msg = make_instantiate_msg();
+            msg.mint_approval.as_mut().unwrap().proposal_expiry = 0;
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            match instantiate(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::InvalidProposalExpiry {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+
+            let mut msg = make_instantiate_msg();
+            msg.mint_approval.as_mut().unwrap().approvers[1] = "approver1".to_string();
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            let err = instantiate(deps.as_mut(), env, info, msg).unwrap_err();
+            match err.root_cause() {
+                ContractError::DuplicateAddress { index: 1, .. } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn mints_after_threshold_approvals() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::propose_mint("addr1111", 50u128);
+            match execute_at(deps.as_mut(), "approver1", 100, msg.clone()) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let res = execute_at(deps.as_mut(), "minter", 100, msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "propose_mint"),
+                    attr("mint_id", "0"),
+                    attr("recipient", "addr1111"),
+                    attr("amount", "50"),
+                    attr("expiry", "110"),
+                ]
+            );
+
+            match execute_at(deps.as_mut(), "minter", 101, ExecuteMsg::approve_mint(0)) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let res =
+                execute_at(deps.as_mut(), "approver1", 101, ExecuteMsg::approve_mint(0)).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "approve_mint"),
+                    attr("mint_id", "0"),
+                    attr("approver", "approver1"),
+                    attr("approvals", "1"),
+                ]
+            );
+            match execute_at(deps.as_mut(), "approver1", 102, ExecuteMsg::approve_mint(0)) {
+                Err(ContractError::AlreadyApproved {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            match execute_at(deps.as_mut(), "anyone", 102, ExecuteMsg::execute_mint(0)) {
+                Err(ContractError::NotEnoughApprovals {
+                    approvals: 1,
+                    threshold: 2,
+                }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 0);
+
+            execute_at(deps.as_mut(), "approver3", 103, ExecuteMsg::approve_mint(0)).unwrap();
+            assert_eq!(
+                mint_proposal(deps.as_ref(), 0),
+                Some(MintProposal {
+                    recipient: Addr::unchecked("addr1111"),
+                    amount: Uint128::from(50u128),
+                    approvals: vec![Addr::unchecked("approver1"), Addr::unchecked("approver3")],
+                    expiry: 110,
+                })
+            );
+
+            let res =
+                execute_at(deps.as_mut(), "anyone", 104, ExecuteMsg::execute_mint(0)).unwrap();
+            assert_eq!(
+                parse_attributes(&res.attributes).unwrap(),
+                vec![Erc20Event::Mint {
+                    to: "addr1111".to_string(),
+                    amount: Uint128::from(50u128),
+                }]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 50);
+            assert_eq!(get_total_supply(&deps.storage), 61);
+            assert_eq!(mint_proposal(deps.as_ref(), 0), None);
+
+            // cannot be executed twice
+            match execute_at(deps.as_mut(), "anyone", 105, ExecuteMsg::execute_mint(0)) {
+                Err(ContractError::MintProposalNotFound { mint_id: 0 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+
+        #[test]
+        fn rejects_expired_proposals() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::propose_mint("addr1111", 50u128);
+            execute_at(deps.as_mut(), "minter", 100, msg).unwrap();
+            execute_at(deps.as_mut(), "approver1", 105, ExecuteMsg::approve_mint(0)).unwrap();
+            match execute_at(deps.as_mut(), "approver2", 110, ExecuteMsg::approve_mint(0)) {
+                Err(ContractError::MintProposalExpired { expiry: 110 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            // approved in time, but executed too late
+            let msg = ExecuteMsg::propose_mint("addr1111", 50u128);
+            execute_at(deps.as_mut(), "minter", 100, msg).unwrap();
+            execute_at(deps.as_mut(), "approver1", 105, ExecuteMsg::approve_mint(1)).unwrap();
+            execute_at(deps.as_mut(), "approver2", 109, ExecuteMsg::approve_mint(1)).unwrap();
+            match execute_at(deps.as_mut(), "anyone", 110, ExecuteMsg::execute_mint(1)) {
+                Err(ContractError::MintProposalExpired { expiry: 110 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 11);
+        }
+
+        #[test]
+        fn replaces_direct_minting() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::set_mint_schedule("addr1111", 5u128, 110, 120);
+            match execute_at(deps.as_mut(), "minter", 100, msg) {
+                Err(ContractError::MintApprovalRequired {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let msg = ExecuteMsg::mint("addr1111", 5u128);
+            match execute_at(deps.as_mut(), "minter", 100, msg) {
+                Err(ContractError::MintApprovalRequired {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            // without approvers, mints cannot be proposed
+            let mut msg = make_instantiate_msg();
+            msg.mint_approval = None;
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, msg).unwrap();
+            let msg = ExecuteMsg::propose_mint("addr1111", 50u128);
+            match execute_at(deps.as_mut(), "minter", 100, msg) {
+                Err(ContractError::MintApprovalDisabled {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+    }
+
+    mod voting_lock {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{from_binary, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(5000u128),
+                }],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        fn lock(deps: DepsMut, height: u64, amount: u128, lock_duration: u64) {
+            let msg = ExecuteMsg::LockForVoting {
+                amount: Uint128::from(amount),
+                lock_duration,
+            };
+            let (env, info) = mock_env_height("addr0000", height, 550);
+            execute(deps, env, info, msg).unwrap();
+        }
+
+        fn voting_power(deps: Deps, at_block: u64) -> u128 {
+            let msg = QueryMsg::VotingPower {
+                address: "addr0000".to_string(),
+                at_block,
+            };
+            let (env, _) = mock_env_height("anyone", 1, 550);
+            let res: VotingPowerResponse = from_binary(&query(deps, env, msg).unwrap()).unwrap();
+            res.voting_power.u128()
+        }
+
+        #[test]
+        fn max_duration_lock_grants_full_power() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            lock(deps.as_mut(), 100, 1000, MAX_LOCK_DURATION);
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                4000
+            );
+            assert_eq!(get_total_supply(&deps.storage), 5000);
+            assert_eq!(voting_power(deps.as_ref(), 100), 1000);
+            // nothing before the lock started
+            assert_eq!(voting_power(deps.as_ref(), 99), 0);
+        }
+
+        #[test]
+        fn power_is_proportional_to_duration() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            lock(deps.as_mut(), 100, 1000, MAX_LOCK_DURATION / 4);
+            assert_eq!(voting_power(deps.as_ref(), 100), 250);
+        }
+
+        #[test]
+        fn power_decays_linearly_to_zero() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            lock(deps.as_mut(), 100, 1000, MAX_LOCK_DURATION);
+            let midpoint = 100 + MAX_LOCK_DURATION / 2;
+            assert_eq!(voting_power(deps.as_ref(), midpoint), 500);
+            let expiry = 100 + MAX_LOCK_DURATION;
+            assert_eq!(voting_power(deps.as_ref(), expiry - 1), 0);
+            assert_eq!(voting_power(deps.as_ref(), expiry), 0);
+            assert_eq!(voting_power(deps.as_ref(), expiry + 1000), 0);
+        }
+
+        #[test]
+        fn relocking_extends_duration() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            lock(deps.as_mut(), 100, 1000, MAX_LOCK_DURATION / 2);
+            assert_eq!(voting_power(deps.as_ref(), 100), 500);
+
+            // Extend only, no new tokens
+            lock(deps.as_mut(), 100, 0, MAX_LOCK_DURATION);
+            assert_eq!(voting_power(deps.as_ref(), 100), 1000);
+
+            // A shorter relock adds the amount but keeps the later end
+            lock(deps.as_mut(), 100, 1000, 10);
+            assert_eq!(voting_power(deps.as_ref(), 100), 2000);
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                3000
+            );
+        }
+
+        #[test]
+        fn rejects_past_blocks() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            lock(deps.as_mut(), 100, 1000, MAX_LOCK_DURATION / 2);
+            lock(deps.as_mut(), 200, 1000, MAX_LOCK_DURATION);
+
+            // the relock must not rewrite the power held at block 150
+            let msg = QueryMsg::VotingPower {
+                address: "addr0000".to_string(),
+                at_block: 150,
+            };
+            let (env, _) = mock_env_height("anyone", 200, 550);
+            match query(deps.as_ref(), env.clone(), msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::PastVotingPower { height: 200 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let msg = QueryMsg::VotingPower {
+                address: "addr0000".to_string(),
+                at_block: 200,
+            };
+            let res: VotingPowerResponse =
+                from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+            assert_eq!(res.voting_power.u128(), 2000);
+        }
+
+        #[test]
+        fn withdraw_after_expiry() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            lock(deps.as_mut(), 100, 1000, 50);
+
+            let (env, info) = mock_env_height("addr0000", 149, 550);
+            let result = execute(deps.as_mut(), env, info, ExecuteMsg::Withdraw {});
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::VotingLockNotExpired { end: 150 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let (env, info) = mock_env_height("addr0000", 150, 550);
+            execute(deps.as_mut(), env, info, ExecuteMsg::Withdraw {}).unwrap();
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                5000
+            );
+            assert_eq!(voting_power(deps.as_ref(), 120), 0);
+
+            let (env, info) = mock_env_height("addr0000", 150, 550);
+            let result = execute(deps.as_mut(), env, info, ExecuteMsg::Withdraw {});
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NoVotingLock {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_on_insufficient_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::LockForVoting {
+                amount: Uint128::from(5001u128),
+                lock_duration: 10,
+            };
+            let (env, info) = mock_env_height("addr0000", 100, 550);
+            let result = execute(deps.as_mut(), env, info, msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 5000,
+                    required: 5001,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_invalid_duration() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 100, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            for lock_duration in [0, MAX_LOCK_DURATION + 1].iter() {
+                let msg = ExecuteMsg::LockForVoting {
+                    amount: Uint128::from(10u128),
+                    lock_duration: *lock_duration,
+                };
+                let (env, info) = mock_env_height("addr0000", 100, 550);
+                let result = execute(deps.as_mut(), env, info, msg);
+                match result {
+                    Ok(_) => panic!("expected error"),
+                    Err(ContractError::InvalidLockDuration { .. }) => {}
+                    Err(e) => panic!("unexpected error: {:?}", e),
+                }
+            }
+        }
+    }
+
+    mod delegation {
+        use super::*;
+        use cosmwasm_std::{attr, from_binary, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                ],
+                minter: Some("minter".to_string()),
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        fn execute_as(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Response {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, msg).unwrap()
+        }
+
+        fn votes(deps: Deps, address: &str) -> u128 {
+            let res: VotesResponse =
+                from_binary(&query(deps, mock_env(), QueryMsg::votes(address)).unwrap()).unwrap();
+            res.votes.u128()
+        }
+
+        #[test]
+        fn moves_votes_but_not_tokens() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            // balances count for nobody until delegated
+            assert_eq!(votes(deps.as_ref(), "addr0000"), 0);
+
+            let res = execute_as(deps.as_mut(), "addr0000", ExecuteMsg::delegate("delegate"));
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "delegate"),
+                    attr("delegator", "addr0000"),
+                    attr("delegatee", "delegate"),
+                ]
+            );
+            assert_eq!(votes(deps.as_ref(), "delegate"), 11);
+            assert_eq!(votes(deps.as_ref(), "addr0000"), 0);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("delegate")), 0);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+
+            let msg = QueryMsg::delegation("addr0000");
+            let res: DelegationResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.delegatee, Some(Addr::unchecked("delegate")));
+        }
+
+        #[test]
+        fn redelegating_moves_votes() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            execute_as(deps.as_mut(), "addr0000", ExecuteMsg::delegate("first"));
+            execute_as(deps.as_mut(), "addr1111", ExecuteMsg::delegate("first"));
+            assert_eq!(votes(deps.as_ref(), "first"), 33);
+
+            execute_as(deps.as_mut(), "addr0000", ExecuteMsg::delegate("second"));
+            assert_eq!(votes(deps.as_ref(), "first"), 22);
+            assert_eq!(votes(deps.as_ref(), "second"), 11);
+
+            // an empty delegatee delegates to the signer
+            execute_as(deps.as_mut(), "addr0000", ExecuteMsg::delegate(""));
+            assert_eq!(votes(deps.as_ref(), "second"), 0);
+            assert_eq!(votes(deps.as_ref(), "addr0000"), 11);
+        }
+
+        #[test]
+        fn follows_balance_changes() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            execute_as(deps.as_mut(), "addr0000", ExecuteMsg::delegate("first"));
+            execute_as(deps.as_mut(), "addr1111", ExecuteMsg::delegate("second"));
+
+            execute_as(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::transfer("addr1111", 5u128),
+            );
+            assert_eq!(votes(deps.as_ref(), "first"), 6);
+            assert_eq!(votes(deps.as_ref(), "second"), 27);
+
+            execute_as(deps.as_mut(), "minter", ExecuteMsg::mint("addr0000", 4u128));
+            execute_as(deps.as_mut(), "addr1111", ExecuteMsg::burn(7u128));
+            assert_eq!(votes(deps.as_ref(), "first"), 10);
+            assert_eq!(votes(deps.as_ref(), "second"), 20);
+
+            // tokens sent to an account without a delegate count for nobody
+            execute_as(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::transfer("addr2222", 10u128),
+            );
+            assert_eq!(votes(deps.as_ref(), "first"), 0);
+            assert_eq!(votes(deps.as_ref(), "addr2222"), 0);
+        }
+
+        #[test]
+        fn inconsistent_votes_fail_instead_of_panicking() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            execute_as(deps.as_mut(), "addr0000", ExecuteMsg::delegate("first"));
+            // votes below the delegated balance cannot happen, but must not underflow
+            write_delegated_balance(&mut deps.storage, &Addr::unchecked("first"), 5);
+
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let msg = ExecuteMsg::transfer("addr1111", 1u128);
+            match execute(deps.as_mut(), env.clone(), info.clone(), msg) {
+                Err(ContractError::Overflow(_)) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            match execute(deps.as_mut(), env, info, ExecuteMsg::delegate("second")) {
+                Err(ContractError::Overflow(_)) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+    }
+
+    mod conditional_transfer {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::{MockApi, MockStorage};
+        use cosmwasm_std::{
+            Addr, ContractResult, Empty, OwnedDeps, Querier, QuerierResult, SystemError,
+            SystemResult,
+        };
+
+        const CONDITION_QUERY: &[u8] = br#"{"price_above":{"price":"100"}}"#;
+
+        /// An oracle at address "oracle" answering the condition query
+        struct OracleQuerier {
+            satisfied: bool,
+        }
+
+        impl Querier for OracleQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+                match request {
+                    QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                        if contract_addr == "oracle" =>
+                    {
+                        assert_eq!(msg.as_slice(), CONDITION_QUERY);
+                        let response = ConditionResponse {
+                            satisfied: self.satisfied,
+                        };
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+                    }
+                    QueryRequest::Wasm(WasmQuery::Smart { contract_addr, .. }) => {
+                        SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr,
+                        })
+                    }
+                    _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                        kind: "non-wasm".to_string(),
+                    }),
+                }
+            }
+        }
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, OracleQuerier> {
+            let mut deps = OwnedDeps {
+                storage: MockStorage::default(),
+                api: MockApi::default(),
+                querier: OracleQuerier { satisfied: false },
+            };
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec!["admin".to_string()],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let msg = ExecuteMsg::schedule_conditional(
+                "addr1111",
+                40u128,
+                "oracle",
+                Binary::from(CONDITION_QUERY),
+                500,
+            );
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(res.attributes[1], attr("transfer_id", 0));
+            deps
+        }
+
+        fn run(deps: DepsMut, msg: ExecuteMsg, height: u64) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height("anyone", height, 550);
+            execute(deps, env, info, msg)
+        }
+
+        #[test]
+        fn schedule_locks_tokens() {
+            let deps = setup();
+            let sender = Addr::unchecked("addr0000");
+            let recipient = Addr::unchecked("addr1111");
+            assert_eq!(get_balance(&deps.storage, &sender), 60);
+            assert_eq!(get_balance(&deps.storage, &recipient), 0);
+            assert_eq!(get_total_supply(&deps.storage), 100);
+        }
+
+        #[test]
+        fn schedule_fails_for_past_expiry_or_insufficient_balance() {
+            let mut deps = setup();
+            let msg = ExecuteMsg::schedule_conditional(
+                "addr1111",
+                1u128,
+                "oracle",
+                Binary::default(),
+                450,
+            );
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidExpiry { expiry: 450 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let msg = ExecuteMsg::schedule_conditional(
+                "addr1111",
+                61u128,
+                "oracle",
+                Binary::default(),
+                500,
+            );
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 60,
+                    required: 61,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn executes_when_condition_satisfied() {
+            let mut deps = setup();
+            match run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 460) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ConditionNotMet {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            deps.querier.satisfied = true;
+            run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 470).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 60);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 40);
+
+            // a transfer can only be executed once
+            match run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 480) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ConditionalTransferNotFound { transfer_id: 0 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn checks_frozen_accounts() {
+            let mut deps = setup();
+            let admin = |deps: DepsMut, msg: ExecuteMsg| {
+                let (env, info) = mock_env_height("admin", 455, 550);
+                execute(deps, env, info, msg).unwrap();
+            };
+            admin(deps.as_mut(), ExecuteMsg::freeze("addr0000"));
+            let msg = ExecuteMsg::schedule_conditional(
+                "addr1111",
+                10u128,
+                "oracle",
+                Binary::from(CONDITION_QUERY),
+                500,
+            );
+            let (env, info) = mock_env_height("addr0000", 460, 550);
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::SenderFrozen {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            // a frozen sender cannot move the tokens scheduled before either
+            deps.querier.satisfied = true;
+            match run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 460) {
+                Err(ContractError::SenderFrozen {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            admin(deps.as_mut(), ExecuteMsg::unfreeze("addr0000"));
+            admin(deps.as_mut(), ExecuteMsg::freeze("addr1111"));
+            match run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 460) {
+                Err(ContractError::RecipientFrozen {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 60);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 0);
+
+            admin(deps.as_mut(), ExecuteMsg::unfreeze("addr1111"));
+            run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 460).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 40);
+        }
+
+        #[test]
+        fn checks_blacklisted_recipient() {
+            let mut deps = setup();
+            let admin = |deps: DepsMut, msg: ExecuteMsg| {
+                let (env, info) = mock_env_height("admin", 455, 550);
+                execute(deps, env, info, msg).unwrap();
+            };
+            admin(deps.as_mut(), ExecuteMsg::add_blacklist("addr1111"));
+            let msg = ExecuteMsg::schedule_conditional(
+                "addr1111",
+                10u128,
+                "oracle",
+                Binary::from(CONDITION_QUERY),
+                500,
+            );
+            let (env, info) = mock_env_height("addr0000", 460, 550);
+            match execute(deps.as_mut(), env, info, msg) {
+                Err(ContractError::RecipientBlacklisted {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            // nor is a transfer scheduled before the recipient was blacklisted credited
+            deps.querier.satisfied = true;
+            match run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 460) {
+                Err(ContractError::RecipientBlacklisted {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 0);
+
+            // the sender gets the tokens back after expiry
+            run(deps.as_mut(), ExecuteMsg::refund_conditional(0), 500).unwrap();
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                100
+            );
+        }
+
+        #[test]
+        fn checks_whitelist_of_restricted_token() {
+            let mut deps = OwnedDeps {
+                storage: MockStorage::default(),
+                api: MockApi::default(),
+                querier: OracleQuerier { satisfied: true },
+            };
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec!["admin".to_string()],
+                admin_delay: 0,
+                marketing: None,
+                restricted: true,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let admin = |deps: DepsMut, msg: ExecuteMsg| {
+                let (env, info) = mock_env_height("admin", 450, 550);
+                execute(deps, env, info, msg).unwrap();
+            };
+            admin(
+                deps.as_mut(),
+                ExecuteMsg::add_to_whitelist(vec!["addr0000"]),
+            );
+            let schedule = |deps: DepsMut| {
+                let msg = ExecuteMsg::schedule_conditional(
+                    "addr1111",
+                    40u128,
+                    "oracle",
+                    Binary::from(CONDITION_QUERY),
+                    500,
+                );
+                let (env, info) = mock_env_height("addr0000", 450, 550);
+                execute(deps, env, info, msg)
+            };
+            match schedule(deps.as_mut()) {
+                Err(ContractError::RecipientNotWhitelisted {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            // removed from the whitelist after scheduling
+            admin(
+                deps.as_mut(),
+                ExecuteMsg::add_to_whitelist(vec!["addr1111"]),
+            );
+            schedule(deps.as_mut()).unwrap();
+            admin(
+                deps.as_mut(),
+                ExecuteMsg::remove_from_whitelist(vec!["addr1111"]),
+            );
+            match run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 460) {
+                Err(ContractError::RecipientNotWhitelisted {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 0);
+        }
+
+        #[test]
+        fn refunds_after_expiry() {
+            let mut deps = setup();
+            match run(deps.as_mut(), ExecuteMsg::refund_conditional(0), 499) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ConditionalTransferNotExpired { expiry: 500 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            // the condition no longer matters once expired
+            deps.querier.satisfied = true;
+            match run(deps.as_mut(), ExecuteMsg::execute_conditional(0), 500) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ConditionalTransferExpired { expiry: 500 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            run(deps.as_mut(), ExecuteMsg::refund_conditional(0), 500).unwrap();
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                100
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 0);
+
+            match run(deps.as_mut(), ExecuteMsg::refund_conditional(0), 500) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ConditionalTransferNotFound { transfer_id: 0 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod genesis_airdrop {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+        use cosmwasm_std::{Addr, OwnedDeps};
+        use k256::ecdsa::signature::DigestSigner;
+        use k256::ecdsa::{Signature, SigningKey};
+
+        const ADMIN_KEY: [u8; 32] = [7u8; 32];
+
+        fn admin() -> SigningKey {
+            SigningKey::from_bytes(&ADMIN_KEY).unwrap()
+        }
+
+        fn attest(key: &SigningKey, entries: &[AirdropEntry]) -> Binary {
+            attest_for(key, MOCK_CONTRACT_ADDR, entries)
+        }
+
+        fn attest_for(key: &SigningKey, contract: &str, entries: &[AirdropEntry]) -> Binary {
+            let payload = to_vec(&(mock_env().block.chain_id, contract, entries)).unwrap();
+            let signature: Signature = key.sign_digest(Sha256::new().chain(payload));
+            Binary::from(signature.as_ref())
+        }
+
+        fn entries(start: usize, count: usize) -> Vec<AirdropEntry> {
+            (start..start + count)
+                .map(|i| AirdropEntry {
+                    address: format!("addr{:04}", i),
+                    amount: Uint128::from(i as u128 + 1),
+                })
+                .collect()
+        }
+
+        fn setup(genesis: Option<GenesisConfig>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![],
+                minter: None,
+                genesis,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            };
+            let (env, info) = mock_env_height("creator", 10, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn genesis_config() -> Option<GenesisConfig> {
+            Some(GenesisConfig {
+                admin_pubkey: Binary::from(&admin().verify_key().to_bytes()[..]),
+                window_end: 100,
+            })
+        }
+
+        fn airdrop(
+            deps: DepsMut,
+            height: u64,
+            entries: Vec<AirdropEntry>,
+            attestation: Binary,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height("anyone", height, 550);
+            execute(
+                deps,
+                env,
+                info,
+                ExecuteMsg::genesis_airdrop(entries, attestation),
+            )
+        }
+
+        #[test]
+        fn mints_attested_entries() {
+            let mut deps = setup(genesis_config());
+            let chunk = entries(0, 3);
+            let attestation = attest(&admin(), &chunk);
+            airdrop(deps.as_mut(), 50, chunk, attestation).unwrap();
+
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 1);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0002")), 3);
+            assert_eq!(get_total_supply(&deps.storage), 6);
+        }
+
+        #[test]
+        fn rejects_invalid_attestation() {
+            let mut deps = setup(genesis_config());
+            let chunk = entries(0, 3);
+
+            // signed by someone else
+            let other = SigningKey::from_bytes(&[8u8; 32]).unwrap();
+            let attestation = attest(&other, &chunk);
+            match airdrop(deps.as_mut(), 50, chunk.clone(), attestation) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidAttestation {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            // signature of different entries
+            let attestation = attest(&admin(), &entries(0, 2));
+            match airdrop(deps.as_mut(), 50, chunk.clone(), attestation) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidAttestation {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            // signature of the same entries for another token
+            let attestation = attest_for(&admin(), "other_token", &chunk);
+            match airdrop(deps.as_mut(), 50, chunk, attestation) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidAttestation {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+        #[test]
+        fn tracks_processed_chunks() {
+            let mut deps = setup(genesis_config());
+            let first = entries(0, MAX_AIRDROP_CHUNK_SIZE);
+            let second = entries(MAX_AIRDROP_CHUNK_SIZE, 2);
+            airdrop(deps.as_mut(), 50, first.clone(), attest(&admin(), &first)).unwrap();
+            airdrop(deps.as_mut(), 51, second.clone(), attest(&admin(), &second)).unwrap();
+            let supply = get_total_supply(&deps.storage);
+            assert_eq!(supply, (1..=102).sum::<u128>());
+
+            match airdrop(deps.as_mut(), 52, first.clone(), attest(&admin(), &first)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ChunkAlreadyProcessed {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_total_supply(&deps.storage), supply);
+
+            let too_big = entries(200, MAX_AIRDROP_CHUNK_SIZE + 1);
+            let attestation = attest(&admin(), &too_big);
+            match airdrop(deps.as_mut(), 52, too_big, attestation) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidAirdropChunk { max: 100 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn window_closes_at_configured_block() {
+            let mut deps = setup(genesis_config());
+            let chunk = entries(0, 1);
+            airdrop(deps.as_mut(), 99, chunk.clone(), attest(&admin(), &chunk)).unwrap();
+
+            let chunk = entries(1, 1);
+            match airdrop(deps.as_mut(), 100, chunk.clone(), attest(&admin(), &chunk)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::GenesisWindowClosed { window_end: 100 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn disabled_without_genesis_config() {
+            let mut deps = setup(None);
+            let chunk = entries(0, 1);
+            match airdrop(deps.as_mut(), 50, chunk.clone(), attest(&admin(), &chunk)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::GenesisAirdropDisabled {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod marketing {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::MarketingConfig;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, from_binary, Addr, OwnedDeps};
+
+        fn make_instantiate_msg(marketing: Option<MarketingConfig>) -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![],
+                minter: None,
+                admins: vec![],
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay: 0,
+                marketing,
+                restricted: false,
+            }
+        }
+
+        fn marketing_config() -> MarketingConfig {
+            MarketingConfig {
+                project: Some("Cash Project".to_string()),
+                description: Some("A token for cash".to_string()),
+                logo_url: Some("https://example.com/logo.png".to_string()),
+                marketing_admin: Some("marketer".to_string()),
+            }
+        }
+
+        fn setup(
+            marketing: Option<MarketingConfig>,
+        ) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(marketing)).unwrap();
+            deps
+        }
+
+        fn update(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, msg)
+        }
+
+        fn query_marketing(deps: Deps) -> Marketing {
+            from_binary(&query(deps, mock_env(), QueryMsg::marketing_info()).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn stores_marketing_info() {
+            let deps = setup(Some(marketing_config()));
+            assert_eq!(
+                query_marketing(deps.as_ref()),
+                Marketing {
+                    project: Some("Cash Project".to_string()),
+                    description: Some("A token for cash".to_string()),
+                    logo_url: Some("https://example.com/logo.png".to_string()),
+                    marketing_admin: Some(Addr::unchecked("marketer")),
+                }
+            );
+
+            let deps = setup(None);
+            assert_eq!(query_marketing(deps.as_ref()), Marketing::default());
+        }
+
+        #[test]
+        fn updates_given_fields() {
+            let mut deps = setup(Some(marketing_config()));
+            let msg = ExecuteMsg::update_marketing(Some("New Project"), None, Some(""));
+            let res = update(deps.as_mut(), "marketer", msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "update_marketing"),
+                    attr("marketing_admin", "marketer"),
+                ]
+            );
+            assert_eq!(
+                query_marketing(deps.as_ref()),
+                Marketing {
+                    project: Some("New Project".to_string()),
+                    description: Some("A token for cash".to_string()),
+                    logo_url: None,
+                    marketing_admin: Some(Addr::unchecked("marketer")),
+                }
+            );
+        }
+
+        #[test]
+        fn only_marketing_admin_updates() {
+            let mut deps = setup(Some(marketing_config()));
+            let msg = ExecuteMsg::update_marketing(Some("Stolen"), None, None);
+            match update(deps.as_mut(), "creator", msg.clone()).unwrap_err() {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(
+                query_marketing(deps.as_ref()).project,
+                Some("Cash Project".to_string())
+            );
+
+            let mut deps = setup(None);
+            match update(deps.as_mut(), "creator", msg).unwrap_err() {
+                ContractError::NoAdmin {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn rejects_long_fields() {
+            let mut deps = mock_dependencies(&[]);
+            let marketing = MarketingConfig {
+                description: Some("a".repeat(MAX_DESCRIPTION_LENGTH + 1)),
+                ..marketing_config()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            match instantiate(
+                deps.as_mut(),
+                env,
+                info,
+                make_instantiate_msg(Some(marketing)),
+            ) {
+                Err(ContractError::MarketingFieldTooLong { field, max: 512 }) => {
+                    assert_eq!(field, "description")
+                }
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            let mut deps = setup(Some(marketing_config()));
+            let description = "a".repeat(MAX_DESCRIPTION_LENGTH);
+            let msg = ExecuteMsg::update_marketing(None, Some(&description), None);
+            update(deps.as_mut(), "marketer", msg).unwrap();
+            let logo_url = "a".repeat(MAX_LOGO_URL_LENGTH + 1);
+            let msg = ExecuteMsg::update_marketing(None, None, Some(&logo_url));
+            match update(deps.as_mut(), "marketer", msg).unwrap_err() {
+                ContractError::MarketingFieldTooLong { field, max: 256 } => {
+                    assert_eq!(field, "logo_url")
+                }
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod blacklist {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{attr, from_binary};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                ],
+                minter: None,
+                admins: vec!["admin".to_string()],
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        fn transfer(
+            deps: DepsMut,
+            sender: &str,
+            recipient: &str,
+            amount: u128,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, ExecuteMsg::transfer(recipient, amount))
+        }
+
+        #[test]
+        fn blocks_receiving() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let (env, info) = mock_env_height("admin", 450, 550);
+            let res = execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::add_blacklist("addr1111"),
+            )
+            .unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![attr("action", "add_blacklist"), attr("address", "addr1111")]
+            );
+
+            match transfer(deps.as_mut(), "addr0000", "addr1111", 1).unwrap_err() {
+                ContractError::RecipientBlacklisted {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::approve("spender", 5u128),
+            )
+            .unwrap();
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 1u128);
+            let (env, info) = mock_env_height("spender", 450, 550);
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::RecipientBlacklisted {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
 
-fn is_valid_symbol(symbol: &str) -> bool {
-    let bytes = symbol.as_bytes();
-    if bytes.len() < 3 || bytes.len() > 6 {
-        return false;
-    }
-    for byte in bytes.iter() {
-        if *byte < 65 || *byte > 90 {
-            return false;
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 22);
         }
-    }
-    true
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::msg::InitialBalance;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{from_slice, Addr, Env, MessageInfo, Storage, Timestamp, Uint128};
-    use cosmwasm_storage::ReadonlyPrefixedStorage;
 
-    fn mock_env_height(signer: &str, height: u64, time: u64) -> (Env, MessageInfo) {
-        let mut env = mock_env();
-        let info = mock_info(signer, &[]);
-        env.block.height = height;
-        env.block.time = Timestamp::from_seconds(time);
-        (env, info)
-    }
+        #[test]
+        fn blacklisted_can_send() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
 
-    fn get_constants(storage: &dyn Storage) -> Constants {
-        let config_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
-        let data = config_storage
-            .get(KEY_CONSTANTS)
-            .expect("no config data stored");
-        from_slice(&data).expect("invalid data")
-    }
+            let (env, info) = mock_env_height("admin", 450, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::add_blacklist("addr1111"),
+            )
+            .unwrap();
 
-    fn get_total_supply(storage: &dyn Storage) -> u128 {
-        let config_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
-        let data = config_storage
-            .get(KEY_TOTAL_SUPPLY)
-            .expect("no decimals data stored");
-        return bytes_to_u128(&data).unwrap();
-    }
+            transfer(deps.as_mut(), "addr1111", "addr0000", 2).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 13);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 20);
+        }
 
-    fn get_balance(storage: &dyn Storage, address: &Addr) -> u128 {
-        let balances_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_BALANCES);
-        return read_u128(&balances_storage, address).unwrap();
-    }
+        #[test]
+        fn blocks_existing_allowances() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::approve("spender", 5u128),
+            )
+            .unwrap();
+            let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 2u128);
+            let (env, info) = mock_env_height("spender", 450, 550);
+            execute(deps.as_mut(), env, info, msg.clone()).unwrap();
 
-    fn get_allowance(storage: &dyn Storage, owner: &Addr, spender: &Addr) -> u128 {
-        let owner_storage = ReadonlyPrefixedStorage::multilevel(
-            storage,
-            &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()],
-        );
-        return read_u128(&owner_storage, spender).unwrap();
-    }
+            let (env, info) = mock_env_height("admin", 450, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::add_blacklist("addr1111"),
+            )
+            .unwrap();
+            let (env, info) = mock_env_height("spender", 450, 550);
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::RecipientBlacklisted {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
 
-    mod instantiate {
-        use super::*;
-        use crate::error::ContractError;
+            // the allowance can still be spent on other recipients
+            let msg = ExecuteMsg::transfer_from("addr0000", "spender", 1u128);
+            let (env, info) = mock_env_height("spender", 450, 550);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("spender")), 1);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 24);
+        }
 
         #[test]
-        fn works() {
+        fn queries_blacklisted() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                initial_balances: [InitialBalance {
-                    address: "addr0000".to_string(),
-                    amount: Uint128::from(11223344u128),
-                }]
-                .to_vec(),
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let blacklisted = |deps: Deps| -> bool {
+                let msg = QueryMsg::blacklisted("addr1111");
+                let res: BlacklistedResponse =
+                    from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+                res.blacklisted
             };
+            assert!(!blacklisted(deps.as_ref()));
+
+            let (env, info) = mock_env_height("admin", 450, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::add_blacklist("addr1111"),
+            )
+            .unwrap();
+            assert!(blacklisted(deps.as_ref()));
+        }
+
+        #[test]
+        fn removal_restores_receiving() {
+            let mut deps = mock_dependencies(&[]);
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            assert_eq!(
-                get_constants(&deps.storage),
-                Constants {
-                    name: "Cash Token".to_string(),
-                    symbol: "CASH".to_string(),
-                    decimals: 9
-                }
-            );
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let (env, info) = mock_env_height("admin", 450, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::add_blacklist("addr1111"),
+            )
+            .unwrap();
+            transfer(deps.as_mut(), "addr0000", "addr1111", 1).unwrap_err();
+
+            let (env, info) = mock_env_height("admin", 450, 550);
+            let res = execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::remove_blacklist("addr1111"),
+            )
+            .unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11223344
+                res.attributes,
+                vec![
+                    attr("action", "remove_blacklist"),
+                    attr("address", "addr1111")
+                ]
             );
-            assert_eq!(get_total_supply(&deps.storage), 11223344);
+            transfer(deps.as_mut(), "addr0000", "addr1111", 1).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 23);
         }
 
         #[test]
-        fn works_with_empty_balance() {
+        fn only_admin_manages_blacklist() {
             let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            for msg in &[
+                ExecuteMsg::add_blacklist("addr1111"),
+                ExecuteMsg::remove_blacklist("addr1111"),
+            ] {
+                let (env, info) = mock_env_height("addr0000", 450, 550);
+                match execute(deps.as_mut(), env, info, msg.clone()).unwrap_err() {
+                    ContractError::Unauthorized {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
+
+            // no one can manage the blacklist without an admin
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
+                admins: vec![],
+                ..make_instantiate_msg()
             };
+            let mut deps = mock_dependencies(&[]);
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            assert_eq!(get_total_supply(&deps.storage), 0);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let (env, info) = mock_env_height("admin", 450, 550);
+            match execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::add_blacklist("addr1111"),
+            )
+            .unwrap_err()
+            {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
         }
+    }
 
-        #[test]
-        fn works_with_multiple_balances() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
+    mod whitelist {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                initial_balances: [
+                initial_balances: vec![
                     InitialBalance {
                         address: "addr0000".to_string(),
                         amount: Uint128::from(11u128),
@@ -451,192 +7893,524 @@ mod tests {
                         address: "addr1111".to_string(),
                         amount: Uint128::from(22u128),
                     },
-                    InitialBalance {
-                        address: "addrbbbb".to_string(),
-                        amount: Uint128::from(33u128),
-                    },
-                ]
-                .to_vec(),
-            };
+                ],
+                minter: None,
+                admins: vec!["admin".to_string()],
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay: 0,
+                marketing: None,
+                restricted: true,
+            }
+        }
+
+        fn execute_as(
+            deps: DepsMut,
+            sender: &str,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, msg)
+        }
+
+        #[test]
+        fn requires_both_sides_whitelisted() {
+            let mut deps = mock_dependencies(&[]);
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
-            );
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let res = execute_as(
+                deps.as_mut(),
+                "admin",
+                ExecuteMsg::add_to_whitelist(vec!["addr0000"]),
+            )
+            .unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
+                res.attributes,
+                vec![attr("action", "add_to_whitelist"), attr("count", 1)]
             );
+
+            let transfer = ExecuteMsg::transfer("addr1111", 1u128);
+            match execute_as(deps.as_mut(), "addr0000", transfer.clone()).unwrap_err() {
+                ContractError::RecipientNotWhitelisted {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let msg = ExecuteMsg::transfer("addr0000", 1u128);
+            match execute_as(deps.as_mut(), "addr1111", msg).unwrap_err() {
+                ContractError::SenderNotWhitelisted {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+
+            execute_as(
+                deps.as_mut(),
+                "admin",
+                ExecuteMsg::add_to_whitelist(vec!["addr1111"]),
+            )
+            .unwrap();
+            execute_as(deps.as_mut(), "addr0000", transfer.clone()).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 23);
+
+            let res = execute_as(
+                deps.as_mut(),
+                "admin",
+                ExecuteMsg::remove_from_whitelist(vec!["addr1111"]),
+            )
+            .unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
+                res.attributes,
+                vec![attr("action", "remove_from_whitelist"), attr("count", 1)]
             );
-            assert_eq!(get_total_supply(&deps.storage), 66);
+            match execute_as(deps.as_mut(), "addr0000", transfer).unwrap_err() {
+                ContractError::RecipientNotWhitelisted {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
         }
 
         #[test]
-        fn works_with_balance_larger_than_53_bit() {
+        fn unrestricted_ignores_whitelist() {
             let mut deps = mock_dependencies(&[]);
-            // This value cannot be represented precisely in JavaScript and jq. Both
-            //   node -e "console.attr(9007199254740993)"
-            //   echo '{ "value": 9007199254740993 }' | jq
-            // return 9007199254740992
+            let (env, info) = mock_env_height("creator", 450, 550);
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                initial_balances: [InitialBalance {
-                    address: "addr0000".to_string(),
-                    amount: Uint128::from(9007199254740993u128),
-                }]
-                .to_vec(),
+                restricted: false,
+                ..make_instantiate_msg()
             };
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let msg = ExecuteMsg::transfer("addr1111", 1u128);
+            execute_as(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 23);
+        }
+
+        #[test]
+        fn only_admin_manages_whitelist() {
+            let mut deps = mock_dependencies(&[]);
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                9007199254740993
-            );
-            assert_eq!(get_total_supply(&deps.storage), 9007199254740993);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            for msg in &[
+                ExecuteMsg::add_to_whitelist(vec!["addr0000"]),
+                ExecuteMsg::remove_from_whitelist(vec!["addr0000"]),
+            ] {
+                match execute_as(deps.as_mut(), "addr0000", msg.clone()).unwrap_err() {
+                    ContractError::Unauthorized {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
         }
 
         #[test]
-        // Typical supply like 100 million tokens with 18 decimals exceeds the 64 bit range
-        fn works_with_balance_larger_than_64_bit() {
+        #[cfg(feature = "iterator")]
+        fn lists_whitelist_page_by_page() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let msg = ExecuteMsg::add_to_whitelist(vec!["addr2222", "addr0000", "addr1111"]);
+            execute_as(deps.as_mut(), "admin", msg).unwrap();
+
+            let list = |start_after: Option<&str>, limit: Option<u32>| -> Vec<String> {
+                let msg = QueryMsg::whitelist(start_after, limit);
+                let res: WhitelistResponse =
+                    cosmwasm_std::from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap())
+                        .unwrap();
+                res.addresses
+            };
+            assert_eq!(list(None, None), vec!["addr0000", "addr1111", "addr2222"]);
+            assert_eq!(list(None, Some(2)), vec!["addr0000", "addr1111"]);
+            assert_eq!(list(Some("addr1111"), None), vec!["addr2222"]);
+        }
+    }
+
+    mod clawback {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::AdminAction;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg(admins: &[&str], admin_delay: u64) -> InstantiateMsg {
+            InstantiateMsg {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                initial_balances: [InitialBalance {
+                initial_balances: vec![InitialBalance {
                     address: "addr0000".to_string(),
-                    amount: Uint128::from(100000000000000000000000000u128),
-                }]
-                .to_vec(),
-            };
+                    amount: Uint128::from(11u128),
+                }],
+                minter: None,
+                admins: admins.iter().map(|admin| admin.to_string()).collect(),
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        fn execute_at(
+            deps: DepsMut,
+            sender: &str,
+            height: u64,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, height, 550);
+            execute(deps, env, info, msg)
+        }
+
+        fn balance(deps: Deps, address: &str) -> u128 {
+            get_balance(deps.storage, &Addr::unchecked(address))
+        }
+
+        #[test]
+        fn moves_tokens_without_allowance() {
+            let mut deps = mock_dependencies(&[]);
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            instantiate(
+                deps.as_mut(),
+                env,
+                info,
+                make_instantiate_msg(&["admin"], 0),
+            )
+            .unwrap();
+
+            let msg = ExecuteMsg::clawback("addr0000", "treasury", 4u128);
+            let res = execute_at(deps.as_mut(), "admin", 450, msg).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                100000000000000000000000000
+                res.attributes,
+                vec![
+                    attr("action", "clawback"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "treasury"),
+                    attr("amount", "4"),
+                ]
             );
-            assert_eq!(get_total_supply(&deps.storage), 100000000000000000000000000);
+            assert_eq!(balance(deps.as_ref(), "addr0000"), 7);
+            assert_eq!(balance(deps.as_ref(), "treasury"), 4);
+
+            let msg = ExecuteMsg::clawback("addr0000", "treasury", 8u128);
+            match execute_at(deps.as_mut(), "admin", 450, msg).unwrap_err() {
+                ContractError::InsufficientFunds {
+                    balance: 7,
+                    required: 8,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let msg = ExecuteMsg::clawback("addr0000", "addr1111", 1u128);
+            match execute_at(deps.as_mut(), "addr1111", 450, msg).unwrap_err() {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(balance(deps.as_ref(), "addr0000"), 7);
         }
 
         #[test]
-        fn fails_for_large_decimals() {
+        fn never_possible_without_admin() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 42,
-                initial_balances: [].to_vec(),
-            };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::DecimalsExceeded {}) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(&[], 0)).unwrap();
+
+            for sender in &["creator", "addr0000", "treasury"] {
+                let msg = ExecuteMsg::clawback("addr0000", "treasury", 1u128);
+                match execute_at(deps.as_mut(), sender, 450, msg).unwrap_err() {
+                    ContractError::Unauthorized {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+                let action = AdminAction::Clawback {
+                    owner: "addr0000".to_string(),
+                    recipient: "treasury".to_string(),
+                    amount: Uint128::from(1u128),
+                };
+                let msg = ExecuteMsg::propose_admin_action(action, 450);
+                match execute_at(deps.as_mut(), sender, 450, msg).unwrap_err() {
+                    ContractError::Unauthorized {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
+            // nobody can become admin either
+            let msg = ExecuteMsg::AddAdmin {
+                address: "creator".to_string(),
+            };
+            match execute_at(deps.as_mut(), "creator", 450, msg).unwrap_err() {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
             }
+            assert_eq!(balance(deps.as_ref(), "addr0000"), 11);
         }
 
         #[test]
-        fn fails_for_name_too_short() {
+        fn waits_for_admin_delay() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "CC".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
-            };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::NameWrongFormat {}) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+            instantiate(
+                deps.as_mut(),
+                env,
+                info,
+                make_instantiate_msg(&["admin"], 100),
+            )
+            .unwrap();
+
+            let msg = ExecuteMsg::clawback("addr0000", "treasury", 4u128);
+            match execute_at(deps.as_mut(), "admin", 450, msg).unwrap_err() {
+                ContractError::TimelockRequired { min_delay: 100 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let action = AdminAction::Clawback {
+                owner: "addr0000".to_string(),
+                recipient: "treasury".to_string(),
+                amount: Uint128::from(4u128),
+            };
+            let msg = ExecuteMsg::propose_admin_action(action, 550);
+            execute_at(deps.as_mut(), "admin", 450, msg).unwrap();
+
+            // freezing waits as well, the owner is frozen first and the clawback still works
+            let action = AdminAction::Freeze {
+                address: "addr0000".to_string(),
+            };
+            let msg = ExecuteMsg::propose_admin_action(action, 550);
+            execute_at(deps.as_mut(), "admin", 450, msg).unwrap();
+            match execute_at(deps.as_mut(), "admin", 450, ExecuteMsg::freeze("addr0000"))
+                .unwrap_err()
+            {
+                ContractError::TimelockRequired { min_delay: 100 } => {}
+                e => panic!("unexpected error: {:?}", e),
             }
+
+            let msg = ExecuteMsg::batch(vec![
+                ExecuteMsg::execute_admin_action(1),
+                ExecuteMsg::execute_admin_action(0),
+            ]);
+            execute_at(deps.as_mut(), "admin", 550, msg).unwrap();
+            assert_eq!(balance(deps.as_ref(), "addr0000"), 7);
+            assert_eq!(balance(deps.as_ref(), "treasury"), 4);
         }
 
         #[test]
-        fn fails_for_name_too_long() {
+        fn takes_tokens_from_frozen_account() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash coin. Cash coin. Cash coin. Cash coin.".to_string(),
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(
+                deps.as_mut(),
+                env,
+                info,
+                make_instantiate_msg(&["admin"], 0),
+            )
+            .unwrap();
+
+            execute_at(deps.as_mut(), "admin", 450, ExecuteMsg::freeze("addr0000")).unwrap();
+            let msg = ExecuteMsg::clawback("addr0000", "treasury", 4u128);
+            execute_at(deps.as_mut(), "admin", 450, msg).unwrap();
+            assert_eq!(balance(deps.as_ref(), "addr0000"), 7);
+            assert_eq!(balance(deps.as_ref(), "treasury"), 4);
+
+            // the owner stays frozen, and the recipient is still checked
+            let msg = ExecuteMsg::transfer("addr1111", 1u128);
+            match execute_at(deps.as_mut(), "addr0000", 450, msg).unwrap_err() {
+                ContractError::SenderFrozen {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            execute_at(deps.as_mut(), "admin", 450, ExecuteMsg::freeze("treasury")).unwrap();
+            let msg = ExecuteMsg::clawback("addr0000", "treasury", 1u128);
+            match execute_at(deps.as_mut(), "admin", 450, msg).unwrap_err() {
+                ContractError::RecipientFrozen {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(balance(deps.as_ref(), "addr0000"), 7);
+        }
+    }
+
+    mod renounce_roles {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{MarketingConfig, MinterResponse};
+        use crate::state::Marketing;
+        use cosmwasm_std::{attr, from_binary, Binary};
+
+        fn make_instantiate_msg(admin_delay: u64) -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                initial_balances: [].to_vec(),
-            };
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::NameWrongFormat {}) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: Some("minter".to_string()),
+                admins: vec!["admin1".to_string(), "admin2".to_string()],
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay,
+                marketing: Some(MarketingConfig {
+                    project: Some("Cash Project".to_string()),
+                    description: None,
+                    logo_url: None,
+                    marketing_admin: Some("marketer".to_string()),
+                }),
+                restricted: false,
             }
         }
 
+        fn execute_at(
+            deps: DepsMut,
+            sender: &str,
+            height: u64,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, height, 550);
+            execute(deps, env, info, msg)
+        }
+
         #[test]
-        fn fails_for_symbol_too_short() {
+        fn rejects_every_privileged_handler() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "De De".to_string(),
-                symbol: "DD".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
-            };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::TickerWrongSymbolFormat {}) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(0)).unwrap();
+            // offered, but not accepted yet
+            let msg = ExecuteMsg::transfer_minter("successor");
+            execute_at(deps.as_mut(), "minter", 450, msg).unwrap();
+
+            let msg = ExecuteMsg::renounce_roles();
+            match execute_at(deps.as_mut(), "minter", 450, msg.clone()).unwrap_err() {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let res = execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
+            assert_eq!(res.attributes, vec![attr("action", "renounce_roles")]);
+
+            let minter: MinterResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::minter()).unwrap())
+                    .unwrap();
+            assert_eq!(
+                minter,
+                MinterResponse {
+                    minter: None,
+                    pending_minter: None,
+                }
+            );
+            let marketing: Marketing =
+                from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::marketing_info()).unwrap())
+                    .unwrap();
+            assert_eq!(marketing.marketing_admin, None);
+            assert_eq!(marketing.project, Some("Cash Project".to_string()));
+
+            let privileged = vec![
+                ("admin2", ExecuteMsg::freeze("addr0000")),
+                ("admin2", ExecuteMsg::unfreeze("addr0000")),
+                ("admin2", ExecuteMsg::pause()),
+                ("admin2", ExecuteMsg::clawback("addr0000", "admin2", 1u128)),
+                ("admin2", ExecuteMsg::add_admin("admin3")),
+                ("admin2", ExecuteMsg::renounce_roles()),
+                (
+                    "admin2",
+                    ExecuteMsg::initiate_upgrade(2, Binary::from(b"{}".to_vec())),
+                ),
+                ("minter", ExecuteMsg::mint("minter", 1u128)),
+                ("successor", ExecuteMsg::AcceptMinter {}),
+                (
+                    "marketer",
+                    ExecuteMsg::update_marketing(Some("Other Project"), None, None),
+                ),
+            ];
+            for (sender, msg) in privileged {
+                match execute_at(deps.as_mut(), sender, 451, msg.clone()).unwrap_err() {
+                    ContractError::NoAdmin {} => {}
+                    // the offer was dropped, so the candidate is rejected like anyone else
+                    ContractError::Unauthorized {} if msg == ExecuteMsg::AcceptMinter {} => {}
+                    e => panic!("unexpected error for {:?}: {:?}", msg, e),
+                }
             }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
         }
 
         #[test]
-        fn fails_for_symbol_too_long() {
+        fn stops_queued_mints() {
+            // a mint schedule created before renouncing
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(0)).unwrap();
+            let msg = ExecuteMsg::SetMintSchedule {
+                recipient: "addr1111".to_string(),
+                rate: Uint128::from(1u128),
+                start: 450,
+                end: 500,
+            };
+            execute_at(deps.as_mut(), "minter", 450, msg).unwrap();
+            execute_at(deps.as_mut(), "admin1", 460, ExecuteMsg::renounce_roles()).unwrap();
+            let msg = ExecuteMsg::ExecuteMintSchedule { schedule_id: 0 };
+            match execute_at(deps.as_mut(), "anyone", 470, msg).unwrap_err() {
+                ContractError::NoAdmin {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 11);
+
+            // and an approved mint proposal
             let mut deps = mock_dependencies(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "Super Coin".to_string(),
-                symbol: "SUPERCOIN".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
+                mint_approval: Some(crate::msg::MintApprovalConfig {
+                    approvers: vec!["approver".to_string()],
+                    threshold: 1,
+                    proposal_expiry: 100,
+                }),
+                ..make_instantiate_msg(0)
             };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::TickerWrongSymbolFormat {}) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let msg = ExecuteMsg::ProposeMint {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(50u128),
+            };
+            execute_at(deps.as_mut(), "minter", 450, msg).unwrap();
+            let msg = ExecuteMsg::ApproveMint { mint_id: 0 };
+            execute_at(deps.as_mut(), "approver", 450, msg).unwrap();
+            execute_at(deps.as_mut(), "admin1", 460, ExecuteMsg::renounce_roles()).unwrap();
+            let msg = ExecuteMsg::ExecuteMint { mint_id: 0 };
+            match execute_at(deps.as_mut(), "anyone", 470, msg).unwrap_err() {
+                ContractError::NoAdmin {} => {}
+                e => panic!("unexpected error: {:?}", e),
             }
+            assert_eq!(get_total_supply(&deps.storage), 11);
         }
 
         #[test]
-        fn fails_for_symbol_lowercase() {
+        fn waits_for_admin_delay() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CaSH".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
-            };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::TickerWrongSymbolFormat {}) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(100)).unwrap();
+
+            match execute_at(deps.as_mut(), "admin1", 450, ExecuteMsg::renounce_roles())
+                .unwrap_err()
+            {
+                ContractError::TimelockRequired { min_delay: 100 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let msg = ExecuteMsg::propose_admin_action(AdminAction::RenounceRoles {}, 550);
+            execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
+            // still admins until the action is executed
+            let action = AdminAction::Freeze {
+                address: "addr0000".to_string(),
+            };
+            let msg = ExecuteMsg::propose_admin_action(action, 600);
+            execute_at(deps.as_mut(), "admin2", 500, msg).unwrap();
+
+            let msg = ExecuteMsg::execute_admin_action(0);
+            execute_at(deps.as_mut(), "admin2", 550, msg).unwrap();
+            match execute_at(
+                deps.as_mut(),
+                "admin2",
+                551,
+                ExecuteMsg::unfreeze("addr0000"),
+            )
+            .unwrap_err()
+            {
+                ContractError::NoAdmin {} => {}
+                e => panic!("unexpected error: {:?}", e),
             }
         }
     }
 
-    mod transfer {
+    mod admins {
         use super::*;
         use crate::error::ContractError;
         use cosmwasm_std::attr;
@@ -646,728 +8420,1457 @@ mod tests {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                initial_balances: vec![
-                    InitialBalance {
-                        address: "addr0000".to_string(),
-                        amount: Uint128::from(11u128),
-                    },
-                    InitialBalance {
-                        address: "addr1111".to_string(),
-                        amount: Uint128::from(22u128),
-                    },
-                    InitialBalance {
-                        address: "addrbbbb".to_string(),
-                        amount: Uint128::from(33u128),
-                    },
+                initial_balances: vec![],
+                minter: None,
+                admins: vec![
+                    "admin1".to_string(),
+                    "admin2".to_string(),
+                    "admin3".to_string(),
                 ],
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
             }
         }
 
-        #[test]
-        fn can_send_to_existing_recipient() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
-            );
-            assert_eq!(get_total_supply(&deps.storage), 66);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: "addr1111".to_string(),
-                amount: Uint128::from(1u128),
-            };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
-            assert_eq!(transfer_result.messages.len(), 0);
-            assert_eq!(
-                transfer_result.attributes,
-                vec![
-                    attr("action", "transfer"),
-                    attr("sender", "addr0000"),
-                    attr("recipient", "addr1111"),
-                ]
-            );
-            // New state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                10
-            ); // -1
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                23
-            ); // +1
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
-            );
-            assert_eq!(get_total_supply(&deps.storage), 66);
+        fn execute_as(
+            deps: DepsMut,
+            sender: &str,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, msg)
         }
 
         #[test]
-        fn can_send_to_non_existent_recipient() {
+        fn any_admin_is_privileged() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
-            );
-            assert_eq!(get_total_supply(&deps.storage), 66);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: "addr2323".to_string(),
-                amount: Uint128::from(1u128),
-            };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
-            assert_eq!(transfer_result.messages.len(), 0);
-            assert_eq!(
-                transfer_result.attributes,
-                vec![
-                    attr("action", "transfer"),
-                    attr("sender", "addr0000"),
-                    attr("recipient", "addr2323"),
-                ]
-            );
-            // New state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                10
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr2323".to_string())),
-                1
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
-            );
-            assert_eq!(get_total_supply(&deps.storage), 66);
-        }
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
 
-        #[test]
-        fn can_send_zero_amount() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
-            );
-            assert_eq!(get_total_supply(&deps.storage), 66);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: "addr1111".to_string(),
-                amount: Uint128::from(0u128),
-            };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
-            assert_eq!(transfer_result.messages.len(), 0);
-            assert_eq!(
-                transfer_result.attributes,
-                vec![
-                    attr("action", "transfer"),
-                    attr("sender", "addr0000"),
-                    attr("recipient", "addr1111"),
-                ]
-            );
-            // New state (unchanged)
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
-            );
-            assert_eq!(get_total_supply(&deps.storage), 66);
+            for admin in &["admin1", "admin2", "admin3"] {
+                execute_as(deps.as_mut(), admin, ExecuteMsg::add_blacklist("addr1111")).unwrap();
+                execute_as(
+                    deps.as_mut(),
+                    admin,
+                    ExecuteMsg::remove_blacklist("addr1111"),
+                )
+                .unwrap();
+            }
+
+            match execute_as(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::add_blacklist("addr1111"),
+            ) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
         }
 
         #[test]
-        fn can_send_to_sender() {
+        fn admins_manage_admins() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let sender = "addr0000";
-            // Initial state
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(sender)), 11);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: sender.to_string(),
-                amount: Uint128::from(3u128),
-            };
-            let (env, info) = mock_env_height(&sender, 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
-            assert_eq!(transfer_result.messages.len(), 0);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            match execute_as(deps.as_mut(), "addr0000", ExecuteMsg::add_admin("addr0000")) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            let res =
+                execute_as(deps.as_mut(), "admin1", ExecuteMsg::add_admin("addr0000")).unwrap();
             assert_eq!(
-                transfer_result.attributes,
-                vec![
-                    attr("action", "transfer"),
-                    attr("sender", "addr0000"),
-                    attr("recipient", "addr0000"),
-                ]
+                res.attributes,
+                vec![attr("action", "add_admin"), attr("address", "addr0000")]
             );
-            // New state
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(sender)), 11);
+            execute_as(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::add_blacklist("addr1111"),
+            )
+            .unwrap();
+
+            let res = execute_as(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::remove_admin("admin2"),
+            )
+            .unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![attr("action", "remove_admin"), attr("address", "admin2")]
+            );
+            match execute_as(
+                deps.as_mut(),
+                "admin2",
+                ExecuteMsg::remove_blacklist("addr1111"),
+            ) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            match execute_as(deps.as_mut(), "admin2", ExecuteMsg::add_admin("admin2")) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
         }
+    }
 
-        #[test]
-        fn fails_on_insufficient_balance() {
+    mod pause {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, from_binary, OwnedDeps};
+
+        fn setup(admin_delay: u64) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                minter: None,
+                admins: vec!["admin".to_string()],
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay,
+                marketing: None,
+                restricted: false,
+            };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn execute_as(
+            deps: DepsMut,
+            sender: &str,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, msg)
+        }
+
+        fn is_paused(deps: Deps) -> bool {
+            let res: PausedResponse =
+                from_binary(&query(deps, mock_env(), QueryMsg::paused()).unwrap()).unwrap();
+            res.paused
+        }
+
+        #[test]
+        fn only_admins_pause() {
+            let mut deps = setup(0);
+            for msg in &[ExecuteMsg::pause(), ExecuteMsg::unpause()] {
+                match execute_as(deps.as_mut(), "addr0000", msg.clone()).unwrap_err() {
+                    ContractError::Unauthorized {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
+            assert!(!is_paused(deps.as_ref()));
+
+            // pausing is immediate even with an admin delay
+            let mut deps = setup(100);
+            let res = execute_as(deps.as_mut(), "admin", ExecuteMsg::pause()).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
+                res.attributes,
+                vec![attr("action", "pause"), attr("admin", "admin")]
             );
+            assert!(is_paused(deps.as_ref()));
+        }
+
+        #[test]
+        fn rejects_messages_while_paused() {
+            let mut deps = setup(0);
+            execute_as(deps.as_mut(), "admin", ExecuteMsg::pause()).unwrap();
+            let msgs = vec![
+                ("addr0000", ExecuteMsg::transfer("addr1111", 1u128)),
+                ("addr0000", ExecuteMsg::approve("addr1111", 1u128)),
+                (
+                    "addr1111",
+                    ExecuteMsg::transfer_from("addr0000", "addr1111", 1u128),
+                ),
+                ("addr0000", ExecuteMsg::burn(1u128)),
+                (
+                    "addr0000",
+                    ExecuteMsg::batch(vec![ExecuteMsg::transfer("addr1111", 1u128)]),
+                ),
+                ("admin", ExecuteMsg::add_blacklist("addr1111")),
+            ];
+            for (sender, msg) in msgs {
+                match execute_as(deps.as_mut(), sender, msg).unwrap_err() {
+                    ContractError::Paused {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                100
             );
+            // only unpausing is possible, so a paused token cannot be paused again
+            match execute_as(deps.as_mut(), "admin", ExecuteMsg::pause()).unwrap_err() {
+                ContractError::Paused {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn resumes_after_unpause() {
+            let mut deps = setup(0);
+            execute_as(deps.as_mut(), "admin", ExecuteMsg::pause()).unwrap();
+            let res = execute_as(deps.as_mut(), "admin", ExecuteMsg::unpause()).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
+                res.attributes,
+                vec![attr("action", "unpause"), attr("admin", "admin")]
             );
-            assert_eq!(get_total_supply(&deps.storage), 66);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: "addr1111".to_string(),
-                amount: Uint128::from(12u128),
+            assert!(!is_paused(deps.as_ref()));
+
+            let msg = ExecuteMsg::transfer("addr1111", 40u128);
+            execute_as(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 40);
+        }
+    }
+
+    mod freeze {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::AdminAction;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, from_binary, OwnedDeps};
+
+        fn setup(admin_delay: u64) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(100u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(100u128),
+                    },
+                ],
+                minter: None,
+                admins: vec!["admin".to_string()],
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay,
+                marketing: None,
+                restricted: false,
             };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
-            match transfer_result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientFunds {
-                    balance: 11,
-                    required: 12,
-                }) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn execute_as(
+            deps: DepsMut,
+            sender: &str,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, msg)
+        }
+
+        fn is_frozen(deps: Deps, address: &str) -> bool {
+            let res: FrozenResponse =
+                from_binary(&query(deps, mock_env(), QueryMsg::is_frozen(address)).unwrap())
+                    .unwrap();
+            res.frozen
+        }
+
+        #[test]
+        fn only_admins_freeze() {
+            let mut deps = setup(0);
+            for msg in &[
+                ExecuteMsg::freeze("addr1111"),
+                ExecuteMsg::unfreeze("addr1111"),
+            ] {
+                match execute_as(deps.as_mut(), "addr0000", msg.clone()).unwrap_err() {
+                    ContractError::Unauthorized {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
             }
-            // New state (unchanged)
+            assert!(!is_frozen(deps.as_ref(), "addr1111"));
+
+            let res = execute_as(deps.as_mut(), "admin", ExecuteMsg::freeze("addr1111")).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
+                res.attributes,
+                vec![attr("action", "freeze"), attr("address", "addr1111")]
             );
+            assert!(is_frozen(deps.as_ref(), "addr1111"));
+            assert!(!is_frozen(deps.as_ref(), "addr0000"));
+        }
+
+        #[test]
+        fn waits_for_admin_delay() {
+            let mut deps = setup(100);
+            for msg in &[
+                ExecuteMsg::freeze("addr1111"),
+                ExecuteMsg::unfreeze("addr1111"),
+            ] {
+                match execute_as(deps.as_mut(), "admin", msg.clone()).unwrap_err() {
+                    ContractError::TimelockRequired { min_delay: 100 } => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
+            assert!(!is_frozen(deps.as_ref(), "addr1111"));
+
+            let action = AdminAction::Freeze {
+                address: "addr1111".to_string(),
+            };
+            let msg = ExecuteMsg::propose_admin_action(action, 550);
+            execute_as(deps.as_mut(), "admin", msg).unwrap();
+            let (env, info) = mock_env_height("admin", 550, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::execute_admin_action(0),
+            )
+            .unwrap();
+            assert!(is_frozen(deps.as_ref(), "addr1111"));
+        }
+
+        #[test]
+        fn blocks_frozen_sender() {
+            let mut deps = setup(0);
+            execute_as(deps.as_mut(), "admin", ExecuteMsg::freeze("addr0000")).unwrap();
+            execute_as(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::approve("addr2222", 10u128),
+            )
+            .unwrap();
+            let msgs = vec![
+                ("addr0000", ExecuteMsg::transfer("addr1111", 1u128)),
+                ("addr0000", ExecuteMsg::send("addr1111", 1u128, None)),
+                (
+                    "addr2222",
+                    ExecuteMsg::transfer_from("addr0000", "addr2222", 1u128),
+                ),
+            ];
+            for (sender, msg) in msgs {
+                match execute_as(deps.as_mut(), sender, msg).unwrap_err() {
+                    ContractError::SenderFrozen {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                100
             );
+        }
+
+        #[test]
+        fn blocks_frozen_recipient() {
+            let mut deps = setup(0);
+            execute_as(deps.as_mut(), "admin", ExecuteMsg::freeze("addr1111")).unwrap();
+            execute_as(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::approve("addr2222", 10u128),
+            )
+            .unwrap();
+            let msgs = vec![
+                ("addr0000", ExecuteMsg::transfer("addr1111", 1u128)),
+                ("addr0000", ExecuteMsg::send("addr1111", 1u128, None)),
+                (
+                    "addr2222",
+                    ExecuteMsg::transfer_from("addr0000", "addr1111", 1u128),
+                ),
+            ];
+            for (sender, msg) in msgs {
+                match execute_as(deps.as_mut(), sender, msg).unwrap_err() {
+                    ContractError::RecipientFrozen {} => {}
+                    e => panic!("unexpected error: {:?}", e),
+                }
+            }
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
+                get_balance(&deps.storage, &Addr::unchecked("addr1111")),
+                100
             );
-            assert_eq!(get_total_supply(&deps.storage), 66);
+        }
+
+        #[test]
+        fn transfers_after_unfreeze() {
+            let mut deps = setup(0);
+            execute_as(deps.as_mut(), "admin", ExecuteMsg::freeze("addr1111")).unwrap();
+            let res = execute_as(deps.as_mut(), "admin", ExecuteMsg::unfreeze("addr1111")).unwrap();
+            assert_eq!(res.attributes[0], attr("action", "unfreeze"));
+            assert!(!is_frozen(deps.as_ref(), "addr1111"));
+
+            execute_as(
+                deps.as_mut(),
+                "addr1111",
+                ExecuteMsg::transfer("addr0000", 5u128),
+            )
+            .unwrap();
+            execute_as(
+                deps.as_mut(),
+                "addr0000",
+                ExecuteMsg::transfer("addr1111", 1u128),
+            )
+            .unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 96);
         }
     }
 
-    mod approve {
+    mod recover_own {
         use super::*;
+        use crate::error::ContractError;
+        use crate::msg::AdminAction;
         use cosmwasm_std::attr;
 
-        fn make_instantiate_msg() -> InstantiateMsg {
+        fn make_instantiate_msg(admin_delay: u64) -> InstantiateMsg {
             InstantiateMsg {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                initial_balances: vec![
-                    InitialBalance {
-                        address: "addr0000".to_string(),
-                        amount: Uint128::from(11u128),
-                    },
-                    InitialBalance {
-                        address: "addr1111".to_string(),
-                        amount: Uint128::from(22u128),
-                    },
-                    InitialBalance {
-                        address: "addrbbbb".to_string(),
-                        amount: Uint128::from(33u128),
-                    },
-                ],
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                minter: None,
+                admins: vec!["admin".to_string()],
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay,
+                marketing: None,
+                restricted: false,
             }
         }
 
-        fn make_spender() -> Addr {
-            Addr::unchecked("dadadadadadadada".to_string())
+        fn execute_as(
+            deps: DepsMut,
+            sender: &str,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, 450, 550);
+            execute(deps, env, info, msg)
         }
 
         #[test]
-        fn has_zero_allowance_by_default() {
+        fn recovers_tokens_sent_to_contract() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Existing owner
+            let contract = env.contract.address.clone();
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(0)).unwrap();
+            let msg = ExecuteMsg::transfer(contract.as_str(), 30u128);
+            execute_as(deps.as_mut(), "addr0000", msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &contract), 30);
+
+            let msg = ExecuteMsg::recover_own("addr0000", 30u128);
+            let res = execute_as(deps.as_mut(), "admin", msg).unwrap();
             assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked("addr0000"), &make_spender()),
-                0
+                res.attributes,
+                vec![
+                    attr("action", "recover_own"),
+                    attr("sender", contract.as_str()),
+                    attr("recipient", "addr0000"),
+                    attr("amount", "30"),
+                ]
             );
-            // Non-existing owner
+            assert_eq!(get_balance(&deps.storage, &contract), 0);
             assert_eq!(
-                get_allowance(
-                    &deps.storage,
-                    &Addr::unchecked("addr4567".to_string()),
-                    &make_spender()
-                ),
-                0
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                100
             );
         }
 
         #[test]
-        fn can_set_allowance() {
+        fn fails_for_insufficient_balance() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            let contract = env.contract.address.clone();
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(0)).unwrap();
+            let msg = ExecuteMsg::transfer(contract.as_str(), 30u128);
+            execute_as(deps.as_mut(), "addr0000", msg).unwrap();
+
+            let msg = ExecuteMsg::recover_own("addr0000", 31u128);
+            match execute_as(deps.as_mut(), "admin", msg).unwrap_err() {
+                ContractError::InsufficientFunds {
+                    balance: 30,
+                    required: 31,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn only_admin_recovers() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let contract = env.contract.address.clone();
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(0)).unwrap();
+            let msg = ExecuteMsg::transfer(contract.as_str(), 30u128);
+            execute_as(deps.as_mut(), "addr0000", msg).unwrap();
+
+            let msg = ExecuteMsg::recover_own("addr0000", 30u128);
+            match execute_as(deps.as_mut(), "addr0000", msg).unwrap_err() {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &contract), 30);
+        }
+
+        #[test]
+        fn waits_for_admin_delay() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let contract = env.contract.address.clone();
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg(100)).unwrap();
+            let msg = ExecuteMsg::transfer(contract.as_str(), 30u128);
+            execute_as(deps.as_mut(), "addr0000", msg).unwrap();
+
+            let msg = ExecuteMsg::recover_own("addr0000", 30u128);
+            match execute_as(deps.as_mut(), "admin", msg).unwrap_err() {
+                ContractError::TimelockRequired { min_delay: 100 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &contract), 30);
+
+            let action = AdminAction::RecoverOwn {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(30u128),
+            };
+            let msg = ExecuteMsg::propose_admin_action(action, 550);
+            execute_as(deps.as_mut(), "admin", msg).unwrap();
+            let (env, info) = mock_env_height("admin", 550, 550);
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::execute_admin_action(0),
+            )
+            .unwrap();
+            assert_eq!(get_balance(&deps.storage, &contract), 0);
             assert_eq!(
-                get_allowance(
-                    &deps.storage,
-                    &Addr::unchecked("addr7654".to_string()),
-                    &make_spender()
-                ),
-                0
+                get_balance(&deps.storage, &Addr::unchecked("addr0000")),
+                100
             );
-            // First approval
-            let owner = Addr::unchecked("addr7654".to_string());
-            let spender = make_spender();
-            let approve_msg1 = ExecuteMsg::Approve {
-                spender: spender.clone().to_string().to_string(),
-                amount: Uint128::from(334422u128),
-            };
-            let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
-            let approve_result1 = execute(deps.as_mut(), env, info, approve_msg1).unwrap();
-            assert_eq!(approve_result1.messages.len(), 0);
+        }
+    }
+
+    mod admin_timelock {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::AdminAction;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: None,
+                admins: vec!["admin1".to_string(), "admin2".to_string()],
+                admin_delay: 100,
+                marketing: None,
+                restricted: false,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+            }
+        }
+
+        fn execute_at(
+            deps: DepsMut,
+            sender: &str,
+            height: u64,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, height, 550);
+            execute(deps, env, info, msg)
+        }
+
+        fn blacklist_action() -> AdminAction {
+            AdminAction::AddBlacklist {
+                address: "addr1111".to_string(),
+            }
+        }
+
+        fn target_blacklisted(deps: Deps) -> bool {
+            is_blacklisted(deps.storage, &Addr::unchecked("addr1111"))
+        }
+
+        #[test]
+        fn requires_proposal() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            match execute_at(
+                deps.as_mut(),
+                "admin1",
+                450,
+                ExecuteMsg::add_blacklist("addr1111"),
+            ) {
+                Err(ContractError::TimelockRequired { min_delay: 100 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert!(!target_blacklisted(deps.as_ref()));
+        }
+
+        #[test]
+        fn enforces_delay() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::propose_admin_action(blacklist_action(), 549);
+            match execute_at(deps.as_mut(), "admin1", 450, msg) {
+                Err(ContractError::EtaTooEarly { min_eta: 550 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            let msg = ExecuteMsg::propose_admin_action(blacklist_action(), 550);
+            let res = execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
             assert_eq!(
-                approve_result1.attributes,
+                res.attributes,
                 vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
+                    attr("action", "propose_admin_action"),
+                    attr("action_id", "0"),
+                    attr("proposer", "admin1"),
+                    attr("eta", "550"),
                 ]
             );
+
+            match execute_at(
+                deps.as_mut(),
+                "admin2",
+                549,
+                ExecuteMsg::execute_admin_action(0),
+            ) {
+                Err(ContractError::AdminActionNotReady { eta: 550 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert!(!target_blacklisted(deps.as_ref()));
+
+            let res = execute_at(
+                deps.as_mut(),
+                "admin2",
+                550,
+                ExecuteMsg::execute_admin_action(0),
+            )
+            .unwrap();
             assert_eq!(
-                get_allowance(&deps.storage, &owner, &make_spender()),
-                334422
+                res.attributes,
+                vec![
+                    attr("action", "execute_admin_action"),
+                    attr("action_id", "0"),
+                    attr("action", "add_blacklist"),
+                    attr("address", "addr1111"),
+                ]
             );
-            // Updated approval
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string().to_string(),
-                amount: Uint128::from(777888u128),
-            };
-            let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
-            let approve_result2 = execute(deps.as_mut(), env, info, approve_msg).unwrap();
-            assert_eq!(approve_result2.messages.len(), 0);
+            assert!(target_blacklisted(deps.as_ref()));
+
+            // actions can only be executed once
+            match execute_at(
+                deps.as_mut(),
+                "admin2",
+                551,
+                ExecuteMsg::execute_admin_action(0),
+            ) {
+                Err(ContractError::AdminActionNotFound { action_id: 0 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+
+        #[test]
+        fn cancel_prevents_execution() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::propose_admin_action(blacklist_action(), 600);
+            execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
+
+            match execute_at(
+                deps.as_mut(),
+                "addr0000",
+                500,
+                ExecuteMsg::cancel_admin_action(0),
+            ) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let res = execute_at(
+                deps.as_mut(),
+                "admin2",
+                500,
+                ExecuteMsg::cancel_admin_action(0),
+            )
+            .unwrap();
             assert_eq!(
-                approve_result2.attributes,
+                res.attributes,
                 vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.as_str()),
-                    attr("spender", spender.as_str()),
+                    attr("action", "cancel_admin_action"),
+                    attr("action_id", "0"),
                 ]
             );
-            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 777888);
+
+            match execute_at(
+                deps.as_mut(),
+                "admin1",
+                600,
+                ExecuteMsg::execute_admin_action(0),
+            ) {
+                Err(ContractError::AdminActionNotFound { action_id: 0 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert!(!target_blacklisted(deps.as_ref()));
+        }
+
+        #[test]
+        fn only_admins_use_timelock() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::propose_admin_action(blacklist_action(), 600);
+            match execute_at(deps.as_mut(), "addr0000", 450, msg.clone()) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
+            match execute_at(
+                deps.as_mut(),
+                "addr0000",
+                600,
+                ExecuteMsg::execute_admin_action(0),
+            ) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert!(!target_blacklisted(deps.as_ref()));
         }
     }
 
-    mod transfer_from {
+    mod upgrade {
         use super::*;
         use crate::error::ContractError;
-        use cosmwasm_std::{attr, Addr};
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+        use cosmwasm_std::{attr, from_binary, CosmosMsg, WasmMsg};
 
         fn make_instantiate_msg() -> InstantiateMsg {
             InstantiateMsg {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                initial_balances: vec![
-                    InitialBalance {
-                        address: "addr0000".to_string(),
-                        amount: Uint128::from(11u128),
-                    },
-                    InitialBalance {
-                        address: "addr1111".to_string(),
-                        amount: Uint128::from(22u128),
-                    },
-                    InitialBalance {
-                        address: "addrbbbb".to_string(),
-                        amount: Uint128::from(33u128),
-                    },
-                ],
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: None,
+                admins: vec!["admin1".to_string(), "admin2".to_string()],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
             }
         }
 
-        fn make_spender() -> Addr {
-            Addr::unchecked("dadadadadadadada".to_string())
+        fn execute_at(
+            deps: DepsMut,
+            sender: &str,
+            height: u64,
+            msg: ExecuteMsg,
+        ) -> Result<Response, ContractError> {
+            let (env, info) = mock_env_height(sender, height, 550);
+            execute(deps, env, info, msg)
+        }
+
+        fn pending_upgrade(deps: Deps) -> Option<PendingUpgrade> {
+            let res = query(deps, mock_env(), QueryMsg::PendingUpgrade {}).unwrap();
+            let res: PendingUpgradeResponse = from_binary(&res).unwrap();
+            res.upgrade
         }
 
         #[test]
-        fn works() {
+        fn enforces_delay() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let owner = "addr0000";
-            let spender = make_spender();
-            let recipient = Addr::unchecked("addr1212".to_string());
-            // Set approval
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string().to_string(),
-                amount: Uint128::from(4u128),
-            };
-            let (env, info) = mock_env_height(&owner.clone(), 450, 550);
-            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
-            assert_eq!(approve_result.messages.len(), 0);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let migrate_msg = Binary::from(br#"{"fix":{}}"#);
+            let msg = ExecuteMsg::initiate_upgrade(7, migrate_msg.clone());
+            let res = execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
             assert_eq!(
-                approve_result.attributes,
+                res.attributes,
                 vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
+                    attr("action", "initiate_upgrade"),
+                    attr("new_code_id", "7"),
+                    attr("proposer", "admin1"),
+                    attr("eta", "498"),
                 ]
             );
+            assert_eq!(res.messages.len(), 0);
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked(owner.clone())),
-                11
-            );
-            assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked(owner.clone()), &spender),
-                4
+                pending_upgrade(deps.as_ref()),
+                Some(PendingUpgrade {
+                    new_code_id: 7,
+                    migrate_msg: migrate_msg.clone(),
+                    eta: 450 + UPGRADE_DELAY,
+                    proposer: Addr::unchecked("admin1"),
+                })
             );
-            // Transfer less than allowance but more than balance
-            let transfer_from_msg = ExecuteMsg::TransferFrom {
-                owner: owner.clone().to_string().to_string(),
-                recipient: recipient.clone().to_string(),
-                amount: Uint128::from(3u128),
-            };
-            let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
-            let transfer_from_result =
-                execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
-            assert_eq!(transfer_from_result.messages.len(), 0);
+
+            match execute_at(deps.as_mut(), "admin2", 497, ExecuteMsg::execute_upgrade()) {
+                Err(ContractError::UpgradeNotReady { eta: 498 }) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            let res =
+                execute_at(deps.as_mut(), "admin2", 498, ExecuteMsg::execute_upgrade()).unwrap();
             assert_eq!(
-                transfer_from_result.attributes,
-                vec![
-                    attr("action", "transfer_from"),
-                    attr("spender", spender.clone()),
-                    attr("sender", owner),
-                    attr("recipient", recipient),
-                ]
+                res.messages,
+                vec![CosmosMsg::Wasm(WasmMsg::Migrate {
+                    contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                    new_code_id: 7,
+                    msg: migrate_msg,
+                })]
             );
-            // State changed
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 8);
             assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
-                1
+                res.attributes,
+                vec![attr("action", "execute_upgrade"), attr("new_code_id", "7")]
             );
+            assert_eq!(pending_upgrade(deps.as_ref()), None);
+
+            // cannot be executed twice
+            match execute_at(deps.as_mut(), "admin2", 499, ExecuteMsg::execute_upgrade()) {
+                Err(ContractError::NoPendingUpgrade {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
         }
 
         #[test]
-        fn fails_when_allowance_too_low() {
+        fn allows_one_pending_upgrade() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let owner = "addr0000";
-            let spender = make_spender();
-            let recipient = Addr::unchecked("addr1212".to_string());
-            // Set approval
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string(),
-                amount: Uint128::from(2u128),
-            };
-            let (env, info) = mock_env_height(&owner.clone(), 450, 550);
-            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
-            assert_eq!(approve_result.messages.len(), 0);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::initiate_upgrade(7, Binary::from(b"{}"));
+            execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
+            let msg = ExecuteMsg::initiate_upgrade(8, Binary::from(b"{}"));
+            match execute_at(deps.as_mut(), "admin2", 460, msg) {
+                Err(ContractError::UpgradePending {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(pending_upgrade(deps.as_ref()).unwrap().new_code_id, 7);
+        }
+
+        #[test]
+        fn cancel_prevents_execution() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            match execute_at(deps.as_mut(), "admin1", 450, ExecuteMsg::cancel_upgrade()) {
+                Err(ContractError::NoPendingUpgrade {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            let msg = ExecuteMsg::initiate_upgrade(7, Binary::from(b"{}"));
+            execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
+            match execute_at(deps.as_mut(), "addr0000", 460, ExecuteMsg::cancel_upgrade()) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            let res =
+                execute_at(deps.as_mut(), "admin2", 460, ExecuteMsg::cancel_upgrade()).unwrap();
+            assert_eq!(res.attributes, vec![attr("action", "cancel_upgrade")]);
+            assert_eq!(pending_upgrade(deps.as_ref()), None);
+
+            match execute_at(deps.as_mut(), "admin1", 600, ExecuteMsg::execute_upgrade()) {
+                Err(ContractError::NoPendingUpgrade {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            // a new upgrade can be initiated after cancelling
+            let msg = ExecuteMsg::initiate_upgrade(8, Binary::from(b"{}"));
+            execute_at(deps.as_mut(), "admin1", 600, msg).unwrap();
+        }
+
+        #[test]
+        fn only_admins_upgrade() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::initiate_upgrade(7, Binary::from(b"{}"));
+            match execute_at(deps.as_mut(), "addr0000", 450, msg.clone()) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert_eq!(pending_upgrade(deps.as_ref()), None);
+
+            execute_at(deps.as_mut(), "admin1", 450, msg).unwrap();
+            match execute_at(
+                deps.as_mut(),
+                "addr0000",
+                600,
+                ExecuteMsg::execute_upgrade(),
+            ) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert!(pending_upgrade(deps.as_ref()).is_some());
+        }
+    }
+
+    mod batch {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: Some("minter".to_string()),
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+            }
+        }
+
+        #[test]
+        fn executes_all_messages() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::batch(vec![
+                ExecuteMsg::approve("addr1111", 5u128),
+                ExecuteMsg::transfer("addr2222", 4u128),
+            ]);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
             assert_eq!(
-                approve_result.attributes,
+                res.attributes,
                 vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
+                    attr("action", "batch"),
+                    attr("count", "2"),
+                    attr("0.action", "approve"),
+                    attr("0.owner", "addr0000"),
+                    attr("0.spender", "addr1111"),
+                    attr("0.allowance", "5"),
+                    attr("1.action", "transfer"),
+                    attr("1.sender", "addr0000"),
+                    attr("1.recipient", "addr2222"),
+                    attr("1.amount", "4"),
                 ]
             );
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 11);
             assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
-                2
+                parse_attributes(&res.attributes).unwrap()[1..],
+                [
+                    Erc20Event::Approval {
+                        owner: "addr0000".to_string(),
+                        spender: "addr1111".to_string(),
+                        allowance: Uint128::from(5u128),
+                    },
+                    Erc20Event::Transfer {
+                        from: "addr0000".to_string(),
+                        to: "addr2222".to_string(),
+                        amount: Uint128::from(4u128),
+                    },
+                ]
             );
-            // Transfer less than allowance but more than balance
-            let fransfer_from_msg = ExecuteMsg::TransferFrom {
-                owner: owner.clone().to_string(),
-                recipient: recipient.clone().to_string(),
-                amount: Uint128::from(3u128),
-            };
-            let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
-            match transfer_result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientAllowance {
-                    allowance: 2,
-                    required: 3,
-                }) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+
+            let owner = Addr::unchecked("addr0000");
+            let spender = Addr::unchecked("addr1111");
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 5);
+            assert_eq!(get_balance(&deps.storage, &owner), 7);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr2222")), 4);
+        }
+
+        #[test]
+        fn failure_leaves_no_state() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::batch(vec![
+                ExecuteMsg::transfer("addr2222", 4u128),
+                ExecuteMsg::transfer("addr2222", 8u128),
+                ExecuteMsg::burn(1u128),
+            ]);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert!(err.to_string().starts_with("batch message 1: "));
+            match err.root_cause() {
+                ContractError::InsufficientFunds {
+                    balance: 7,
+                    required: 8,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
             }
+
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr2222")), 0);
+            assert_eq!(get_total_supply(&deps.storage), 11);
         }
 
         #[test]
-        fn fails_when_allowance_is_set_but_balance_too_low() {
+        fn messages_check_their_permissions() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let owner = "addr0000";
-            let spender = make_spender();
-            let recipient = Addr::unchecked("addr1212".to_string());
-            // Set approval
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string(),
-                amount: Uint128::from(20u128),
-            };
-            let (env, info) = mock_env_height(&owner.clone(), 450, 550);
-            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
-            assert_eq!(approve_result.messages.len(), 0);
-            assert_eq!(
-                approve_result.attributes,
-                vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
-                ]
-            );
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 11);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::batch(vec![
+                ExecuteMsg::transfer("addr2222", 4u128),
+                ExecuteMsg::set_mint_schedule("addr0000", 10u128, 500, 600),
+            ]);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, msg)
+                .unwrap_err()
+                .root_cause()
+            {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr2222")), 0);
+        }
+
+        #[test]
+        fn rejects_nested_batches() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let msg = ExecuteMsg::batch(vec![
+                ExecuteMsg::transfer("addr2222", 4u128),
+                ExecuteMsg::batch(vec![ExecuteMsg::transfer("addr2222", 4u128)]),
+            ]);
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::NestedBatch {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr2222")), 0);
+        }
+    }
+
+    mod validate_all {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::testing::MockApi;
+
+        #[test]
+        fn validates_all_addresses() {
+            let api = MockApi::default();
+            let addresses = validate_all(&api, &["addr0000", "addr1111"]).unwrap();
             assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
-                20
+                addresses,
+                vec![Addr::unchecked("addr0000"), Addr::unchecked("addr1111")]
             );
-            // Transfer less than allowance but more than balance
-            let fransfer_from_msg = ExecuteMsg::TransferFrom {
-                owner: owner.clone().to_string(),
-                recipient: recipient.clone().to_string(),
-                amount: Uint128::from(15u128),
+            assert!(validate_all::<&str>(&api, &[]).unwrap().is_empty());
+        }
+
+        #[test]
+        fn names_invalid_entry() {
+            let api = MockApi::default();
+            let err = validate_all(&api, &["addr0000", "x", "addr1111"]).unwrap_err();
+            assert!(
+                err.to_string()
+                    .starts_with("invalid address at index 1 (x): "),
+                "unexpected error: {}",
+                err
+            );
+            match err.root_cause() {
+                ContractError::Std(StdError::GenericErr { .. }) => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn unique_rejects_duplicates() {
+            let api = MockApi::default();
+            validate_all(&api, &["addr0000", "addr1111", "addr0000"]).unwrap();
+            match validate_all_unique(&api, &["addr0000", "addr1111", "addr0000"]).unwrap_err() {
+                ContractError::DuplicateAddress { index, address } => {
+                    assert_eq!(index, 2);
+                    assert_eq!(address, "addr0000");
+                }
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn instantiate_checks_admins() {
+            let msg = |admins: &[&str]| InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![],
+                minter: None,
+                admins: admins.iter().map(|admin| admin.to_string()).collect(),
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
             };
-            let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
-            match transfer_result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientFunds {
-                    balance: 11,
-                    required: 15,
-                }) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
+
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let err = instantiate(deps.as_mut(), env, info, msg(&["admin1", "x"])).unwrap_err();
+            assert!(
+                err.to_string()
+                    .starts_with("invalid admins: invalid address at index 1 (x): "),
+                "unexpected error: {}",
+                err
+            );
+
+            let (env, info) = mock_env_height("creator", 450, 550);
+            match instantiate(deps.as_mut(), env, info, msg(&["admin1", "admin1"]))
+                .unwrap_err()
+                .root_cause()
+            {
+                ContractError::DuplicateAddress { index: 1, .. } => {}
+                e => panic!("unexpected error: {:?}", e),
             }
         }
     }
 
-    mod burn {
+    mod migrate {
         use super::*;
         use crate::error::ContractError;
-        use cosmwasm_std::{attr, Addr};
+        use crate::version::{ContractVersion, KEY_CONTRACT_VERSION};
+        use cosmwasm_std::{attr, from_binary};
 
         fn make_instantiate_msg() -> InstantiateMsg {
             InstantiateMsg {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                initial_balances: vec![
-                    InitialBalance {
-                        address: "addr0000".to_string(),
-                        amount: Uint128::from(11u128),
-                    },
-                    InitialBalance {
-                        address: "addr1111".to_string(),
-                        amount: Uint128::from(22u128),
-                    },
-                ],
+                initial_balances: vec![],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
             }
         }
 
         #[test]
-        fn can_burn_from_existing_account() {
+        fn instantiate_stores_version() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
-            );
+            instantiate(deps.as_mut(), env.clone(), info, make_instantiate_msg()).unwrap();
+
+            let query_result = query(deps.as_ref(), env, QueryMsg::ContractVersion {}).unwrap();
+            let version: ContractVersion = from_binary(&query_result).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
+                version,
+                ContractVersion {
+                    contract: CONTRACT_NAME.to_string(),
+                    version: CONTRACT_VERSION.to_string(),
+                }
             );
-            assert_eq!(get_total_supply(&deps.storage), 33);
-            // Burn
-            let burn_msg = ExecuteMsg::Burn {
-                amount: Uint128::from(1u128),
-            };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let burn_result = execute(deps.as_mut(), env, info, burn_msg).unwrap();
-            assert_eq!(burn_result.messages.len(), 0);
+        }
+
+        #[test]
+        fn works_for_legacy_instance() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, make_instantiate_msg()).unwrap();
+            // emulate an instance created before versions were stored
+            deps.storage.remove(KEY_CONTRACT_VERSION);
+            assert!(query(deps.as_ref(), env.clone(), QueryMsg::ContractVersion {}).is_err());
+
+            let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
             assert_eq!(
-                burn_result.attributes,
+                res.attributes,
                 vec![
-                    attr("action", "burn"),
-                    attr("account", "addr0000"),
-                    attr("amount", "1")
+                    attr("action", "migrate"),
+                    attr("from_version", "unknown"),
+                    attr("to_version", CONTRACT_VERSION),
                 ]
             );
-            // New state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                10
-            ); // -1
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
-            );
-            assert_eq!(get_total_supply(&deps.storage), 32);
+            let query_result = query(deps.as_ref(), env, QueryMsg::ContractVersion {}).unwrap();
+            let version: ContractVersion = from_binary(&query_result).unwrap();
+            assert_eq!(version.version, CONTRACT_VERSION);
         }
 
         #[test]
-        fn can_burn_zero_amount() {
+        fn moves_legacy_minter() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
+            instantiate(deps.as_mut(), env.clone(), info, make_instantiate_msg()).unwrap();
+            // emulate an instance storing the minter in the constants
+            PrefixedStorage::new(&mut deps.storage, PREFIX_CONFIG).set(
+                KEY_CONSTANTS,
+                br#"{"name":"Cash Token","symbol":"CASH","decimals":9,"minter":"minter"}"#,
+            );
+
+            migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
+                MINTER.load(&deps.storage).unwrap(),
+                Some(Addr::unchecked("minter"))
             );
+            assert_eq!(query_legacy_minter(&deps.storage).unwrap(), None);
+            assert_eq!(get_constants(&deps.storage).name, "Cash Token");
+        }
+
+        #[test]
+        fn moves_legacy_flags() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, make_instantiate_msg()).unwrap();
+            // emulate an instance storing each flag in its own entry
+            let mut config_store = PrefixedStorage::new(&mut deps.storage, PREFIX_CONFIG);
+            config_store.set(b"paused", &[1]);
+            config_store.set(b"restricted", &[1]);
+
+            migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
+                read_flags(&deps.storage),
+                Flags {
+                    paused: true,
+                    restricted: true,
+                }
             );
-            assert_eq!(get_total_supply(&deps.storage), 33);
-            // Burn
-            let burn_msg = ExecuteMsg::Burn {
-                amount: Uint128::from(0u128),
+            let config_store = ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_CONFIG);
+            assert_eq!(config_store.get(b"paused"), None);
+            assert_eq!(config_store.get(b"restricted"), None);
+        }
+
+        #[test]
+        #[cfg(feature = "iterator")]
+        fn ranks_existing_balances() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, make_instantiate_msg()).unwrap();
+            // emulate balances stored before the ranking existed
+            let mut balances_store = PrefixedStorage::new(&mut deps.storage, PREFIX_BALANCES);
+            balances_store.set(b"addr0000", &10u128.to_be_bytes());
+            balances_store.set(b"addr1111", &20u128.to_be_bytes());
+            // and an entry left behind by a build without the ranking
+            rank_balance(&mut deps.storage, &Addr::unchecked("addr2222"), 0, 5);
+            let top = |deps: Deps| -> Vec<HolderBalance> {
+                let msg = QueryMsg::top_holders(10);
+                let res: TopHoldersResponse =
+                    from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+                res.holders
             };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let burn_result = execute(deps.as_mut(), env, info, burn_msg).unwrap();
-            assert_eq!(burn_result.messages.len(), 0);
-            assert_eq!(
-                burn_result.attributes,
-                vec![
-                    attr("action", "burn"),
-                    attr("account", "addr0000"),
-                    attr("amount", "0"),
-                ]
+            migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+            let expected = vec![
+                HolderBalance {
+                    address: "addr1111".to_string(),
+                    balance: Uint128::from(20u128),
+                },
+                HolderBalance {
+                    address: "addr0000".to_string(),
+                    balance: Uint128::from(10u128),
+                },
+            ];
+            assert_eq!(top(deps.as_ref()), expected);
+            // migrating again leaves the ranking as it is
+            migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+            assert_eq!(top(deps.as_ref()), expected);
+        }
+
+        #[test]
+        fn fails_for_other_contract() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, make_instantiate_msg()).unwrap();
+            deps.storage.set(
+                KEY_CONTRACT_VERSION,
+                br#"{"contract":"cw-escrow","version":"0.10.0"}"#,
             );
-            // New state (unchanged)
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
+
+            let result = migrate(deps.as_mut(), env, MigrateMsg {});
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::CannotMigrate { contract }) => assert_eq!(contract, "cw-escrow"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod supply_history {
+        use super::*;
+        use crate::msg::{MaxSupplyResponse, SupplyHistoryEntry, SupplyHistoryResponse};
+        use cosmwasm_std::from_binary;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                minter: Some("minter".to_string()),
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+            }
+        }
+
+        fn entry(height: u64, total_supply: u128) -> SupplyHistoryEntry {
+            SupplyHistoryEntry {
+                height,
+                total_supply: Uint128::from(total_supply),
+            }
+        }
+
+        fn history(
+            deps: Deps,
+            from_block: u64,
+            to_block: u64,
+            limit: Option<u32>,
+        ) -> Vec<SupplyHistoryEntry> {
+            let msg = QueryMsg::supply_history(from_block, to_block, limit);
+            let res: SupplyHistoryResponse =
+                from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.entries
+        }
+
+        fn max_supply(deps: Deps) -> u128 {
+            let res: MaxSupplyResponse =
+                from_binary(&query(deps, mock_env(), QueryMsg::max_supply()).unwrap()).unwrap();
+            res.max_supply.u128()
+        }
+
+        fn execute_at(deps: DepsMut, sender: &str, height: u64, msg: ExecuteMsg) {
+            let (env, info) = mock_env_height(sender, height, 550);
+            execute(deps, env, info, msg).unwrap();
+        }
+
+        /// Instantiates with a total supply of 11 at block 10
+        fn setup(deps: DepsMut) {
+            let (env, info) = mock_env_height("creator", 10, 550);
+            instantiate(deps, env, info, make_instantiate_msg()).unwrap();
+        }
+
+        #[test]
+        fn records_mints_and_burns() {
+            let mut deps = mock_dependencies(&[]);
+            setup(deps.as_mut());
+            assert_eq!(history(deps.as_ref(), 0, 1000, None), vec![entry(10, 11)]);
+
+            let msg = ExecuteMsg::set_mint_schedule("addr0000", 10u128, 100, 200);
+            execute_at(deps.as_mut(), "minter", 90, msg);
+            execute_at(
+                deps.as_mut(),
+                "anyone",
+                150,
+                ExecuteMsg::execute_mint_schedule(0),
             );
+            execute_at(deps.as_mut(), "addr0000", 160, ExecuteMsg::burn(100u128));
+            // two burns in one block give one entry
+            execute_at(deps.as_mut(), "addr0000", 170, ExecuteMsg::burn(4u128));
+            execute_at(deps.as_mut(), "addr0000", 170, ExecuteMsg::burn(6u128));
+
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
+                history(deps.as_ref(), 0, 1000, None),
+                vec![
+                    entry(10, 11),
+                    entry(150, 511),
+                    entry(160, 411),
+                    entry(170, 401)
+                ]
             );
-            assert_eq!(get_total_supply(&deps.storage), 33);
+            assert_eq!(max_supply(deps.as_ref()), 511);
         }
 
         #[test]
-        fn fails_on_insufficient_balance() {
+        fn filters_by_height() {
             let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
+            setup(deps.as_mut());
+            let msg = ExecuteMsg::set_mint_schedule("addr0000", 10u128, 100, 200);
+            execute_at(deps.as_mut(), "minter", 90, msg);
+            execute_at(
+                deps.as_mut(),
+                "anyone",
+                150,
+                ExecuteMsg::execute_mint_schedule(0),
+            );
+            execute_at(deps.as_mut(), "addr0000", 160, ExecuteMsg::burn(100u128));
+            execute_at(deps.as_mut(), "addr0000", 170, ExecuteMsg::burn(10u128));
+
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
+                history(deps.as_ref(), 150, 160, None),
+                vec![entry(150, 511), entry(160, 411)]
             );
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
+                history(deps.as_ref(), 11, 169, None),
+                vec![entry(150, 511), entry(160, 411)]
             );
-            assert_eq!(get_total_supply(&deps.storage), 33);
-            // Burn
-            let burn_msg = ExecuteMsg::Burn {
-                amount: Uint128::from(12u128),
-            };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let burn_result = execute(deps.as_mut(), env, info, burn_msg);
-            match burn_result {
-                Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientFunds {
-                    balance: 11,
-                    required: 12,
-                }) => {}
-                Err(e) => panic!("unexpected error: {:?}", e),
-            }
-            // New state (unchanged)
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                11
+                history(deps.as_ref(), 170, 170, None),
+                vec![entry(170, 401)]
             );
+            assert_eq!(history(deps.as_ref(), 171, 1000, None), vec![]);
+            assert_eq!(history(deps.as_ref(), 0, 9, None), vec![]);
+
+            // paginate by starting after the last entry
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
+                history(deps.as_ref(), 0, 1000, Some(2)),
+                vec![entry(10, 11), entry(150, 511)]
+            );
+            assert_eq!(
+                history(deps.as_ref(), 151, 1000, Some(2)),
+                vec![entry(160, 411), entry(170, 401)]
             );
-            assert_eq!(get_total_supply(&deps.storage), 33);
         }
     }
 
     mod query {
         use super::*;
-        use cosmwasm_std::{attr, Addr};
+        use cosmwasm_std::{attr, from_binary, Addr};
 
         fn address(index: u8) -> Addr {
             match index {
@@ -1399,6 +9902,15 @@ mod tests {
                         amount: Uint128::from(33u128),
                     },
                 ],
+                minter: None,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
             }
         }
 
@@ -1406,7 +9918,7 @@ mod tests {
         fn can_query_balance_of_existing_address() {
             let mut deps = mock_dependencies(&[]);
             let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
             let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
             assert_eq!(0, res.messages.len());
             let query_msg = QueryMsg::Balance {
@@ -1416,11 +9928,59 @@ mod tests {
             assert_eq!(query_result.as_slice(), b"{\"balance\":\"11\"}");
         }
 
+        #[test]
+        fn can_query_balance_with_constructed_message() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            let payload = QueryMsg::balance(address(2)).to_binary().unwrap();
+            let query_msg: QueryMsg = from_binary(&payload).unwrap();
+            let query_result = query(deps.as_ref(), env, query_msg).unwrap();
+            assert_eq!(query_result.as_slice(), b"{\"balance\":\"22\"}");
+        }
+
+        #[test]
+        fn can_humanize_amounts() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            let query_result = query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::humanize(1_500_000_000u128),
+            )
+            .unwrap();
+            assert_eq!(query_result.as_slice(), b"{\"amount\":\"1.5\"}");
+            let query_result = query(deps.as_ref(), env, QueryMsg::humanize(11u128)).unwrap();
+            assert_eq!(query_result.as_slice(), b"{\"amount\":\"0.000000011\"}");
+        }
+
+        #[test]
+        fn can_query_token_info() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            let query_result = query(deps.as_ref(), env, QueryMsg::token_info()).unwrap();
+            let token_info: TokenInfoResponse = from_binary(&query_result).unwrap();
+            assert_eq!(
+                token_info,
+                TokenInfoResponse {
+                    name: "Cash Token".to_string(),
+                    symbol: "CASH".to_string(),
+                    decimals: 9,
+                    total_supply: Uint128::from(66u128),
+                }
+            );
+        }
+
         #[test]
         fn can_query_balance_of_nonexisting_address() {
             let mut deps = mock_dependencies(&[]);
             let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
             let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
             assert_eq!(0, res.messages.len());
             let query_msg = QueryMsg::Balance {
@@ -1434,7 +9994,7 @@ mod tests {
         fn can_query_allowance_of_existing_addresses() {
             let mut deps = mock_dependencies(&[]);
             let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
             assert_eq!(0, res.messages.len());
             let owner = address(2);
@@ -1442,8 +10002,9 @@ mod tests {
             let approve_msg = ExecuteMsg::Approve {
                 spender: spender.clone().to_string(),
                 amount: Uint128::from(42u128),
+                expires: None,
             };
-            let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
             let action_result = execute(deps.as_mut(), env.clone(), info, approve_msg).unwrap();
             assert_eq!(action_result.messages.len(), 0);
             assert_eq!(
@@ -1452,6 +10013,7 @@ mod tests {
                     attr("action", "approve"),
                     attr("owner", owner.clone().to_string()),
                     attr("spender", spender.clone().to_string()),
+                    attr("allowance", "42"),
                 ]
             );
             let query_msg = QueryMsg::Allowance {
@@ -1459,14 +10021,17 @@ mod tests {
                 spender: spender.clone().to_string(),
             };
             let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"42\"}");
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"allowance\":\"42\",\"expires\":{\"never\":{}}}"
+            );
         }
 
         #[test]
         fn can_query_allowance_of_nonexisting_owner() {
             let mut deps = mock_dependencies(&[]);
             let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
             assert_eq!(0, res.messages.len());
             let owner = address(2);
@@ -1475,8 +10040,9 @@ mod tests {
             let approve_msg = ExecuteMsg::Approve {
                 spender: spender.clone().to_string(),
                 amount: Uint128::from(42u128),
+                expires: None,
             };
-            let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
             let approve_result = execute(deps.as_mut(), env.clone(), info, approve_msg).unwrap();
             assert_eq!(approve_result.messages.len(), 0);
             assert_eq!(
@@ -1485,6 +10051,7 @@ mod tests {
                     attr("action", "approve"),
                     attr("owner", owner.clone().to_string()),
                     attr("spender", spender.clone().to_string()),
+                    attr("allowance", "42"),
                 ]
             );
             // different spender
@@ -1493,14 +10060,150 @@ mod tests {
                 spender: bob.clone().to_string(),
             };
             let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"allowance\":\"0\",\"expires\":{\"never\":{}}}"
+            );
             // differnet owner
             let query_msg = QueryMsg::Allowance {
                 owner: bob.clone().to_string(),
                 spender: spender.clone().to_string(),
             };
             let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"allowance\":\"0\",\"expires\":{\"never\":{}}}"
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "iterator")]
+        fn lists_accounts_page_by_page() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            // address(1) has no tokens left afterwards
+            let (env, info) = mock_env_height(address(1).as_str(), 450, 550);
+            let msg = ExecuteMsg::transfer(address(4).as_str(), 11u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+
+            let list = |start_after: Option<Addr>, limit: Option<u32>| -> Vec<String> {
+                let msg = QueryMsg::all_accounts(start_after, limit);
+                let res: AllAccountsResponse =
+                    from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+                res.accounts
+            };
+            assert_eq!(list(None, None), vec!["addr4321", "addr5432", "addr6543"]);
+            assert_eq!(list(None, Some(2)), vec!["addr4321", "addr5432"]);
+            assert_eq!(list(Some(address(3)), Some(2)), vec!["addr6543"]);
+            assert_eq!(list(Some(address(4)), Some(2)), Vec::<String>::new());
+        }
+
+        #[test]
+        #[cfg(feature = "iterator")]
+        fn ranks_top_holders() {
+            let mut deps = mock_dependencies(&[]);
+            // a dozen holders with balances 10, 20, 30, 40, 50, 60, 10, ... and one empty
+            let mut initial_balances: Vec<InitialBalance> = (0..12u128)
+                .map(|i| InitialBalance {
+                    address: format!("holder{:02}", i),
+                    amount: Uint128::from((i % 6 + 1) * 10),
+                })
+                .collect();
+            initial_balances.push(InitialBalance {
+                address: "empty".to_string(),
+                amount: Uint128::zero(),
+            });
+            let msg = InstantiateMsg {
+                initial_balances,
+                ..make_instantiate_msg()
+            };
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+            let top = |limit: u32| -> Vec<(String, u128)> {
+                let msg = QueryMsg::top_holders(limit);
+                let res: TopHoldersResponse =
+                    from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+                res.holders
+                    .into_iter()
+                    .map(|holder| (holder.address, holder.balance.u128()))
+                    .collect()
+            };
+            let expected: Vec<(String, u128)> = [
+                ("holder05", 60),
+                ("holder11", 60),
+                ("holder04", 50),
+                ("holder10", 50),
+                ("holder03", 40),
+                ("holder09", 40),
+                ("holder02", 30),
+                ("holder08", 30),
+                ("holder01", 20),
+                ("holder07", 20),
+                ("holder00", 10),
+                ("holder06", 10),
+            ]
+            .iter()
+            .map(|(address, balance)| (address.to_string(), *balance))
+            .collect();
+            // accounts without tokens are left out
+            assert_eq!(top(30), expected);
+            assert_eq!(top(5), &expected[..5]);
+            assert_eq!(top(0), vec![]);
+            // the limit is capped
+            assert_eq!(top(u32::MAX), expected);
+
+            // transfers move both sides
+            let (env, info) = mock_env_height("holder05", 450, 550);
+            let msg = ExecuteMsg::transfer("holder00", 60u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            let msg = QueryMsg::top_holders(3);
+            let res: TopHoldersResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            let top: Vec<(String, u128)> = res
+                .holders
+                .into_iter()
+                .map(|holder| (holder.address, holder.balance.u128()))
+                .collect();
+            assert_eq!(
+                top,
+                vec![
+                    ("holder00".to_string(), 70),
+                    ("holder11".to_string(), 60),
+                    ("holder04".to_string(), 50),
+                ]
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "iterator")]
+        fn returns_balance_at_past_heights() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let (env, info) = mock_env_height(address(1).as_str(), 500, 600);
+            let msg = ExecuteMsg::transfer(address(4).as_str(), 5u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            let (env, info) = mock_env_height(address(1).as_str(), 600, 700);
+            let msg = ExecuteMsg::transfer(address(4).as_str(), 4u128);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+
+            let balance_at = |address: &Addr, height: u64| -> u128 {
+                let msg = QueryMsg::balance_at(address.as_str(), height);
+                let res: BalanceResponse =
+                    from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+                res.balance.u128()
+            };
+            assert_eq!(balance_at(&address(1), 449), 0);
+            assert_eq!(balance_at(&address(1), 450), 11);
+            assert_eq!(balance_at(&address(1), 550), 6);
+            assert_eq!(balance_at(&address(1), 600), 2);
+            assert_eq!(balance_at(&address(4), 499), 0);
+            assert_eq!(balance_at(&address(4), 550), 5);
+            assert_eq!(balance_at(&address(4), 1000), 9);
+            // untouched accounts keep their initial balance
+            assert_eq!(balance_at(&address(2), 1000), 22);
         }
     }
 }
@@ -0,0 +1,127 @@
+//! The functions exported to the wasm VM. They accept the wire format of the contract,
+//! which is a bit more lenient than the typed messages of `contract`.
+
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+use crate::contract;
+use crate::error::ContractError;
+use crate::msg::VersionedExecuteMsg;
+
+pub use crate::contract::{instantiate, migrate, query};
+
+/// Like `contract::execute`, but also accepts messages in a versioned envelope
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: VersionedExecuteMsg,
+) -> Result<Response, ContractError> {
+    contract::execute(deps, env, info, msg.into_msg())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, to_vec, StdError, StdResult};
+
+    fn parse(data: &[u8]) -> StdResult<ExecuteMsg> {
+        from_slice::<VersionedExecuteMsg>(data).map(VersionedExecuteMsg::into_msg)
+    }
+
+    #[test]
+    fn accepts_bare_messages() {
+        let msg = parse(br#"{"transfer":{"recipient":"addr1111","amount":"3"}}"#).unwrap();
+        assert_eq!(msg, ExecuteMsg::transfer("addr1111", 3u128));
+
+        let msg = parse(br#"{"execute_mint_schedule":{"schedule_id":1}}"#).unwrap();
+        assert_eq!(msg, ExecuteMsg::execute_mint_schedule(1));
+
+        parse(br#"{"frobnicate":{}}"#).unwrap_err();
+        parse(br#"{"transfer":{"recipient":"addr1111","amount":"3"},"burn":{"amount":"1"}}"#)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn accepts_supported_envelopes() {
+        let msg =
+            parse(br#"{"version":1,"msg":{"transfer":{"recipient":"addr1111","amount":"3"}}}"#)
+                .unwrap();
+        assert_eq!(msg, ExecuteMsg::transfer("addr1111", 3u128));
+
+        parse(br#"{"version":1,"msg":{"frobnicate":{}}}"#).unwrap_err();
+        parse(br#"{"version":1}"#).unwrap_err();
+    }
+
+    #[test]
+    fn rejects_future_envelopes() {
+        // newer messages are rejected whether or not this version could parse them
+        for data in &[
+            &br#"{"version":2,"msg":{"transfer":{"recipient":"addr1111","amount":"3"}}}"#[..],
+            &br#"{"version":2,"msg":{"frobnicate":{}}}"#[..],
+        ] {
+            match parse(data).unwrap_err() {
+                StdError::ParseErr { msg, .. } => {
+                    assert!(msg.contains("message version 2 not supported, max 1"))
+                }
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn serializes_like_wrapped_message() {
+        let msg = VersionedExecuteMsg::Envelope {
+            version: 1,
+            msg: ExecuteMsg::burn(5u128),
+        };
+        assert_eq!(
+            to_vec(&msg).unwrap(),
+            br#"{"version":1,"msg":{"burn":{"amount":"5"}}}"#.to_vec()
+        );
+        assert_eq!(
+            from_slice::<VersionedExecuteMsg>(&to_vec(&msg).unwrap()).unwrap(),
+            msg
+        );
+
+        let msg = VersionedExecuteMsg::Bare(ExecuteMsg::burn(5u128));
+        assert_eq!(
+            to_vec(&msg).unwrap(),
+            br#"{"burn":{"amount":"5"}}"#.to_vec()
+        );
+    }
+
+    #[test]
+    fn execute_unwraps_envelope() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            name: "Cash Token".to_string(),
+            symbol: "CASH".to_string(),
+            decimals: 9,
+            initial_balances: vec![],
+            minter: None,
+            genesis: None,
+            mint_limit_per_window: None,
+            mint_window_blocks: 0,
+            mint_approval: None,
+            admins: vec![],
+            admin_delay: 0,
+            marketing: None,
+            restricted: false,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = from_slice(
+            br#"{"version":1,"msg":{"transfer":{"recipient":"addr1111","amount":"3"}}}"#,
+        )
+        .unwrap();
+        match execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err() {
+            ContractError::InsufficientFunds {
+                balance: 0,
+                required: 3,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
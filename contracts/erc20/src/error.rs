@@ -1,11 +1,23 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError};
 use thiserror::Error;
 
+use crate::admin::AdminError;
+use crate::msg::Expiration;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("No admin set")]
+    NoAdmin {},
+
     #[error("Name is not in the expected format (3-30 UTF-8 bytes)")]
     NameWrongFormat {},
 
@@ -21,6 +33,249 @@ pub enum ContractError {
     #[error("Insufficient funds (balance {balance}, required={required})")]
     InsufficientFunds { balance: u128, required: u128 },
 
+    #[error("Invalid amount {value}")]
+    InvalidAmount { value: String },
+
+    #[error("Amount {value} has more than {decimals} decimals")]
+    TooManyDecimals { value: String, decimals: u8 },
+
     #[error("Corrupted data found (16 byte expected)")]
     CorruptedDataFound {},
+
+    #[error("Mint schedule must end after it starts (start {start}, end {end})")]
+    InvalidMintSchedule { start: u64, end: u64 },
+
+    #[error("Mint schedule {schedule_id} not found")]
+    MintScheduleNotFound { schedule_id: u64 },
+
+    #[error("Mint window must be at least 1 block")]
+    InvalidMintWindow {},
+
+    #[error("Mint rate limit exceeded")]
+    MintRateLimitExceeded {},
+
+    #[error("Mint threshold must be between 1 and the number of approvers ({approvers})")]
+    InvalidMintThreshold { approvers: usize },
+
+    #[error("Mint proposal expiry must be at least 1 block")]
+    InvalidProposalExpiry {},
+
+    #[error("Mint approval not enabled")]
+    MintApprovalDisabled {},
+
+    #[error("Mints must be proposed and approved")]
+    MintApprovalRequired {},
+
+    #[error("Mint proposal {mint_id} not found")]
+    MintProposalNotFound { mint_id: u64 },
+
+    #[error("Mint proposal expired (expiry {expiry})")]
+    MintProposalExpired { expiry: u64 },
+
+    #[error("Mint proposal already approved by this approver")]
+    AlreadyApproved {},
+
+    #[error("Not enough approvals (approvals {approvals}, threshold {threshold})")]
+    NotEnoughApprovals { approvals: u64, threshold: u64 },
+
+    #[error("Lock duration must be between 1 and {max} blocks")]
+    InvalidLockDuration { max: u64 },
+
+    #[error("No tokens locked for voting")]
+    NoVotingLock {},
+
+    #[error("Cannot migrate from contract {contract}")]
+    CannotMigrate { contract: String },
+
+    #[error("Voting lock not expired (end {end})")]
+    VotingLockNotExpired { end: u64 },
+
+    #[error("Voting power is only known from the current block on (height {height})")]
+    PastVotingPower { height: u64 },
+
+    #[error("Expiry must be in the future (expiry {expiry})")]
+    InvalidExpiry { expiry: u64 },
+
+    #[error("Allowance expiration {expires:?} already passed")]
+    AllowanceExpirationPassed { expires: Expiration },
+
+    #[error("Conditional transfer {transfer_id} not found")]
+    ConditionalTransferNotFound { transfer_id: u64 },
+
+    #[error("Conditional transfer expired (expiry {expiry})")]
+    ConditionalTransferExpired { expiry: u64 },
+
+    #[error("Conditional transfer not expired (expiry {expiry})")]
+    ConditionalTransferNotExpired { expiry: u64 },
+
+    #[error("Condition not satisfied")]
+    ConditionNotMet {},
+
+    #[error("Genesis airdrop not enabled")]
+    GenesisAirdropDisabled {},
+
+    #[error("Genesis window closed (end {window_end})")]
+    GenesisWindowClosed { window_end: u64 },
+
+    #[error("Airdrop chunk must contain between 1 and {max} entries")]
+    InvalidAirdropChunk { max: usize },
+
+    #[error("Marketing {field} must not be longer than {max} bytes")]
+    MarketingFieldTooLong { field: String, max: usize },
+
+    #[error("Invalid airdrop attestation")]
+    InvalidAttestation {},
+
+    #[error("Airdrop chunk already processed")]
+    ChunkAlreadyProcessed {},
+
+    #[error("No permit key set for the owner")]
+    PermitKeyNotSet {},
+
+    #[error("Permit key must be a compressed or uncompressed secp256k1 public key")]
+    InvalidPermitKey {},
+
+    #[error("Invalid permit signature")]
+    InvalidPermitSignature {},
+
+    #[error("Permit expired (expiry {expiry})")]
+    PermitExpired { expiry: u64 },
+
+    #[error("Invalid permit nonce (expected {expected})")]
+    InvalidPermitNonce { expected: u64 },
+
+    #[error("Duplicate address {address} at index {index}")]
+    DuplicateAddress { index: usize, address: String },
+
+    #[error("Recipient is blacklisted")]
+    RecipientBlacklisted {},
+
+    #[error("Sender is frozen")]
+    SenderFrozen {},
+
+    #[error("Recipient is frozen")]
+    RecipientFrozen {},
+
+    #[error("Sender is not whitelisted")]
+    SenderNotWhitelisted {},
+
+    #[error("Recipient is not whitelisted")]
+    RecipientNotWhitelisted {},
+
+    #[error("Contract is paused")]
+    Paused {},
+
+    #[error("Admin actions must be proposed at least {min_delay} blocks ahead")]
+    TimelockRequired { min_delay: u64 },
+
+    #[error("Admin action cannot be executed before block {min_eta}")]
+    EtaTooEarly { min_eta: u64 },
+
+    #[error("Admin action {action_id} not found")]
+    AdminActionNotFound { action_id: u64 },
+
+    #[error("Admin action not ready until block {eta}")]
+    AdminActionNotReady { eta: u64 },
+
+    #[error("An upgrade is already pending")]
+    UpgradePending {},
+
+    #[error("No upgrade pending")]
+    NoPendingUpgrade {},
+
+    #[error("Upgrade not ready until block {eta}")]
+    UpgradeNotReady { eta: u64 },
+
+    #[error("Batches cannot be nested")]
+    NestedBatch {},
+
+    #[error("Batch transfer must not contain more than {max} recipients")]
+    TooManyTransfers { max: usize },
+
+    #[error("Sweep must not check more than {max} spenders")]
+    TooManySpenders { max: usize },
+
+    /// Wraps another error with a description of what the contract was doing
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        source: Box<ContractError>,
+    },
+}
+
+impl From<AdminError> for ContractError {
+    fn from(err: AdminError) -> Self {
+        match err {
+            AdminError::Std(err) => ContractError::Std(err),
+            AdminError::Unauthorized {} => ContractError::Unauthorized {},
+            AdminError::NoAdmin {} => ContractError::NoAdmin {},
+        }
+    }
+}
+
+impl ContractError {
+    /// The innermost error, skipping all context added along the way
+    pub fn root_cause(&self) -> &ContractError {
+        match self {
+            ContractError::Context { source, .. } => source.root_cause(),
+            err => err,
+        }
+    }
+}
+
+/// Adds context to the error of a result, e.g.
+/// `deps.api.addr_validate(&recipient).context("invalid recipient")?`
+pub trait ResultExt<T> {
+    fn context<C: Into<String>>(self, context: C) -> Result<T, ContractError>;
+}
+
+impl<T, E: Into<ContractError>> ResultExt<T> for Result<T, E> {
+    fn context<C: Into<String>>(self, context: C) -> Result<T, ContractError> {
+        self.map_err(|err| ContractError::Context {
+            context: context.into(),
+            source: Box::new(err.into()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn context_wraps_errors() {
+        let result: Result<(), StdError> = Err(StdError::generic_err("invalid address"));
+        let err = result
+            .context("invalid recipient")
+            .context("while processing transfer_from")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "while processing transfer_from: invalid recipient: Generic error: invalid address"
+        );
+
+        // the source chain walks through every layer of context
+        let inner = err.source().unwrap();
+        assert_eq!(
+            inner.to_string(),
+            "invalid recipient: Generic error: invalid address"
+        );
+        let root = inner.source().unwrap();
+        assert_eq!(root.to_string(), "Generic error: invalid address");
+
+        match err.root_cause() {
+            ContractError::Std(StdError::GenericErr { msg, .. }) => {
+                assert_eq!(msg, "invalid address")
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn root_cause_of_plain_error_is_itself() {
+        let err = ContractError::Unauthorized {};
+        assert!(matches!(err.root_cause(), ContractError::Unauthorized {}));
+        assert!(err.source().is_none());
+    }
 }
@@ -0,0 +1,212 @@
+//! Typed events parsed from the attributes the contract emits, so off-chain consumers
+//! don't need to know the attribute layout of each action.
+
+use cosmwasm_std::{from_slice, Attribute, StdError, StdResult, Uint128};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Erc20Event {
+    /// Emitted by `Transfer`, `Send` and `TransferFrom`
+    Transfer {
+        from: String,
+        to: String,
+        amount: Uint128,
+    },
+    /// Emitted by `Approve`, `IncreaseAllowance` and `DecreaseAllowance` with the new allowance
+    Approval {
+        owner: String,
+        spender: String,
+        allowance: Uint128,
+    },
+    /// Emitted by `Mint` and when a mint schedule or an approved mint proposal is executed
+    Mint { to: String, amount: Uint128 },
+    /// Emitted by `Burn` and `BurnFrom`
+    Burn { from: String, amount: Uint128 },
+    /// Any other action. Newer versions of the contract may emit actions this parser
+    /// does not know yet, so these are kept instead of failing.
+    Unknown {
+        action: String,
+        attributes: Vec<Attribute>,
+    },
+}
+
+/// Parses the JSON encoded attributes of a transaction log, e.g.
+/// `[{"key":"action","value":"burn"},{"key":"account","value":"addr0000"},...]`.
+///
+/// Every `action` attribute starts a new event. Attributes before the first action, like the
+/// `contract_address` added by the chain, are skipped. Batch indices like `0.action` are
+/// removed from the keys.
+pub fn parse_events(log: &str) -> StdResult<Vec<Erc20Event>> {
+    let attributes: Vec<Attribute> = from_slice(log.as_bytes())?;
+    parse_attributes(&attributes)
+}
+
+/// Like `parse_events`, but for attributes which were already decoded, e.g. `Response::attributes`
+pub fn parse_attributes(attributes: &[Attribute]) -> StdResult<Vec<Erc20Event>> {
+    let mut groups: Vec<(String, Vec<Attribute>)> = vec![];
+    for attribute in attributes {
+        let key = strip_batch_index(&attribute.key);
+        if key == "action" {
+            groups.push((attribute.value.clone(), vec![]));
+        } else if let Some((_, group)) = groups.last_mut() {
+            group.push(Attribute {
+                key: key.to_string(),
+                value: attribute.value.clone(),
+            });
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(action, attributes)| to_event(action, attributes))
+        .collect()
+}
+
+fn to_event(action: String, attributes: Vec<Attribute>) -> StdResult<Erc20Event> {
+    let get = |key: &str| {
+        attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.clone())
+            .ok_or_else(|| {
+                StdError::generic_err(format!("missing attribute {} in {} event", key, action))
+            })
+    };
+    let get_amount = |key: &str| {
+        get(key)?
+            .parse::<u128>()
+            .map(Uint128::from)
+            .map_err(|err| StdError::parse_err("Uint128", err))
+    };
+
+    let event = match action.as_str() {
+        "transfer" | "transfer_all" | "send" | "transfer_from" | "recover_own" | "clawback" => {
+            Erc20Event::Transfer {
+                from: get("sender")?,
+                to: get("recipient")?,
+                amount: get_amount("amount")?,
+            }
+        }
+        "approve" | "permit" | "increase_allowance" | "decrease_allowance" => {
+            Erc20Event::Approval {
+                owner: get("owner")?,
+                spender: get("spender")?,
+                allowance: get_amount("allowance")?,
+            }
+        }
+        "mint" | "execute_mint_schedule" | "execute_mint" => Erc20Event::Mint {
+            to: get("recipient")?,
+            amount: get_amount("amount")?,
+        },
+        "burn" | "burn_from" => Erc20Event::Burn {
+            from: get("account")?,
+            amount: get_amount("amount")?,
+        },
+        _ => Erc20Event::Unknown { action, attributes },
+    };
+    Ok(event)
+}
+
+/// Turns `0.action` into `action`
+fn strip_batch_index(key: &str) -> &str {
+    match key.find('.') {
+        Some(index) if index > 0 && key[..index].bytes().all(|byte| byte.is_ascii_digit()) => {
+            &key[index + 1..]
+        }
+        _ => key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::attr;
+
+    #[test]
+    fn parses_json_log() {
+        let log = r#"[
+            {"key":"contract_address","value":"cosmos2contract"},
+            {"key":"action","value":"burn"},
+            {"key":"account","value":"addr0000"},
+            {"key":"amount","value":"12"}
+        ]"#;
+        assert_eq!(
+            parse_events(log).unwrap(),
+            vec![Erc20Event::Burn {
+                from: "addr0000".to_string(),
+                amount: Uint128::from(12u128),
+            }]
+        );
+
+        match parse_events("{\"key\":\"action\"}").unwrap_err() {
+            StdError::ParseErr { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn splits_batches() {
+        let attributes = vec![
+            attr("action", "batch"),
+            attr("count", "2"),
+            attr("0.action", "approve"),
+            attr("0.owner", "addr0000"),
+            attr("0.spender", "addr1111"),
+            attr("0.allowance", "5"),
+            attr("1.action", "transfer"),
+            attr("1.sender", "addr0000"),
+            attr("1.recipient", "addr2222"),
+            attr("1.amount", "3"),
+        ];
+        assert_eq!(
+            parse_attributes(&attributes).unwrap(),
+            vec![
+                Erc20Event::Unknown {
+                    action: "batch".to_string(),
+                    attributes: vec![attr("count", "2")],
+                },
+                Erc20Event::Approval {
+                    owner: "addr0000".to_string(),
+                    spender: "addr1111".to_string(),
+                    allowance: Uint128::from(5u128),
+                },
+                Erc20Event::Transfer {
+                    from: "addr0000".to_string(),
+                    to: "addr2222".to_string(),
+                    amount: Uint128::from(3u128),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_actions() {
+        let attributes = vec![attr("action", "stake"), attr("staker", "addr0000")];
+        assert_eq!(
+            parse_attributes(&attributes).unwrap(),
+            vec![Erc20Event::Unknown {
+                action: "stake".to_string(),
+                attributes: vec![attr("staker", "addr0000")],
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_known_actions() {
+        let attributes = vec![attr("action", "transfer"), attr("sender", "addr0000")];
+        match parse_attributes(&attributes).unwrap_err() {
+            StdError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "missing attribute recipient in transfer event")
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let attributes = vec![
+            attr("action", "burn"),
+            attr("account", "addr0000"),
+            attr("amount", "-1"),
+        ];
+        match parse_attributes(&attributes).unwrap_err() {
+            StdError::ParseErr { target_type, .. } => assert_eq!(target_type, "Uint128"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -1,9 +1,38 @@
+pub mod admin;
 pub mod contract;
+pub mod entry;
 mod error;
+pub mod events;
 mod msg;
+#[cfg(feature = "iterator")]
+pub mod ranking;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod sim;
+pub mod snapshots;
 mod state;
+mod transaction;
+pub mod units;
+pub mod version;
 
+pub use error::{ContractError, ResultExt};
 pub use msg::{
-    AllowanceResponse, BalanceResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg,
+    AdminAction, AirdropEntry, AllAccountsResponse, AllowanceResponse, BalanceResponse,
+    BlacklistedResponse, ConditionResponse, DelegationResponse, ExecuteMsg, Expiration,
+    FrozenResponse, GenesisConfig, HolderBalance, HumanizeResponse, InitialBalance, InstantiateMsg,
+    MarketingConfig, MaxSupplyResponse, MintApprovalConfig, MintProposalResponse, MinterResponse,
+    PausedResponse, PendingUpgradeResponse, PermitMsg, PermitNonceResponse, QueryMsg, ReceiveMsg,
+    SupplyHistoryEntry, SupplyHistoryResponse, TokenInfoResponse, TopHoldersResponse,
+    VersionedExecuteMsg, VotesResponse, VotingPowerResponse, WhitelistResponse,
+    EXECUTE_MSG_VERSION,
 };
-pub use state::Constants;
+pub use state::{
+    allowance_key, balance_key, decode_amount, query_constants, query_total_supply,
+    total_supply_key, ConditionalTransfer, Constants, Marketing, MintProposal, MintSchedule,
+    PendingUpgrade, QueuedAdminAction, VotingLock,
+};
+pub use version::{ContractVersion, MigrateMsg, CONTRACT_NAME, CONTRACT_VERSION};
+
+// The wasm exports only exist in wasm builds, so the contract logic can be used natively
+// by tests and other contracts. Use the library feature to omit them in wasm builds too.
+#[cfg(all(target_arch = "wasm32", not(feature = "library")))]
+cosmwasm_std::create_entry_points_with_migration!(entry);
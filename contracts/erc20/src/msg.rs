@@ -1,33 +1,251 @@
 use schemars::JsonSchema;
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, MapAccess, VariantAccess,
+    Visitor,
+};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{to_binary, Addr, Binary, BlockInfo, CosmosMsg, StdResult, Uint128, WasmMsg};
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+use crate::state::{MintProposal, PendingUpgrade};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct InitialBalance {
     pub address: String,
     pub amount: Uint128,
 }
 
-#[derive(Serialize, Deserialize, JsonSchema)]
+impl InitialBalance {
+    pub fn new<T: Into<String>, A: Into<Uint128>>(address: T, amount: A) -> Self {
+        InitialBalance {
+            address: address.into(),
+            amount: amount.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct InstantiateMsg {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
     pub initial_balances: Vec<InitialBalance>,
+    pub minter: Option<String>,
+    /// Can manage the blacklist and the list of admins. No admin actions are possible when empty.
+    #[serde(default)]
+    pub admins: Vec<String>,
+    /// When set, admin actions must be proposed this many blocks before they can be executed
+    #[serde(default)]
+    pub admin_delay: u64,
+    /// Allows a one-time airdrop honoring balances from a previous chain. Disabled when unset.
+    pub genesis: Option<GenesisConfig>,
+    /// Most tokens the minter can mint within `mint_window_blocks` blocks. Unlimited when unset.
+    pub mint_limit_per_window: Option<Uint128>,
+    #[serde(default)]
+    pub mint_window_blocks: u64,
+    /// When set, the minter can only mint through proposals approved by several approvers
+    pub mint_approval: Option<MintApprovalConfig>,
+    /// Project information for wallets and explorers. Empty when unset.
+    pub marketing: Option<MarketingConfig>,
+    /// When set, only whitelisted addresses can send and receive transfers
+    #[serde(default)]
+    pub restricted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct GenesisConfig {
+    /// secp256k1 public key (compressed or uncompressed) of the admin attesting airdrops
+    pub admin_pubkey: Binary,
+    /// Genesis airdrops are possible before this block height only
+    pub window_end: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct MarketingConfig {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub logo_url: Option<String>,
+    /// Can update the marketing information. Nobody can when unset.
+    pub marketing_admin: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct MintApprovalConfig {
+    pub approvers: Vec<String>,
+    /// Approvals a mint proposal needs before it can be executed
+    pub threshold: u64,
+    /// Mint proposals expire this many blocks after they were proposed
+    pub proposal_expiry: u64,
+}
+
+/// What an owner signs to grant an allowance with `Permit`. The signature is the owner's
+/// secp256k1 signature of the sha256 hash of the JSON serialized `PermitMsg`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct PermitMsg {
+    /// Chain ID and token address, so a permit is only valid for a single token
+    pub chain_id: String,
+    pub contract: String,
+    pub owner: String,
+    pub spender: String,
+    pub amount: Uint128,
+    pub nonce: u64,
+    pub expiry: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct AirdropEntry {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+/// When an allowance stops being usable
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Expiration {
+    /// Expires once the block height reaches this value
+    AtHeight(u64),
+    /// Expires once the block time reaches this value, in seconds since epoch
+    AtTime(u64),
+    Never {},
+}
+
+impl Expiration {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        match self {
+            Expiration::AtHeight(height) => block.height >= *height,
+            Expiration::AtTime(time) => block.time.nanos() / 1_000_000_000 >= *time,
+            Expiration::Never {} => false,
+        }
+    }
+}
+
+impl Default for Expiration {
+    fn default() -> Self {
+        Expiration::Never {}
+    }
+}
+
+/// The operations only admins can perform, either directly or through the timelock
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub enum AdminAction {
+    AddBlacklist {
+        address: String,
+    },
+    RemoveBlacklist {
+        address: String,
+    },
+    Freeze {
+        address: String,
+    },
+    Unfreeze {
+        address: String,
+    },
+    AddAdmin {
+        address: String,
+    },
+    RemoveAdmin {
+        address: String,
+    },
+    AddToWhitelist {
+        addresses: Vec<String>,
+    },
+    RemoveFromWhitelist {
+        addresses: Vec<String>,
+    },
+    Clawback {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    RecoverOwn {
+        recipient: String,
+        amount: Uint128,
+    },
+    RenounceRoles {},
 }
 
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub enum ExecuteMsg {
+    /// Sets the allowance of `spender` to `amount`, which reads as zero once `expires` passed.
+    /// Without `expires`, the allowance never expires.
     Approve {
         spender: String,
         amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Adds `amount` to the allowance of `spender`. Unlike `Approve`, this cannot be
+    /// front-run by the spender using the old allowance first.
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Subtracts `amount` from the allowance of `spender`, stopping at zero. With `exact` set,
+    /// decreasing by more than the allowance fails instead.
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        #[serde(default)]
+        exact: bool,
+        expires: Option<Expiration>,
+    },
+    /// Removes the expired allowances `owner` granted `spenders` from storage. Allowances that
+    /// have not expired are left as they are. Anyone can do this. At most 50 spenders can be
+    /// checked at once.
+    SweepExpiredAllowances {
+        owner: String,
+        spenders: Vec<String>,
+    },
+    /// Sets the allowance of `spender` to `amount` on behalf of `owner`, who signed the matching
+    /// `PermitMsg` off-chain with its permit key. Anyone can submit the permit before block
+    /// `expiry`. `nonce` must be the owner's current permit nonce, so a permit works only once.
+    Permit {
+        owner: String,
+        spender: String,
+        amount: Uint128,
+        nonce: u64,
+        expiry: u64,
+        signature: Binary,
+    },
+    /// Sets the secp256k1 public key signing the signer's permits, or removes it.
+    SetPermitKey {
+        pubkey: Option<Binary>,
     },
     Transfer {
         recipient: String,
         amount: Uint128,
     },
+    /// Transfers the sender's whole balance to `recipient`. Succeeds with an amount of 0 if
+    /// the balance is empty.
+    TransferAll {
+        recipient: String,
+    },
+    /// Transfers tokens to every recipient. Either all transfers succeed or none.
+    BatchTransfer {
+        recipients: Vec<InitialBalance>,
+    },
+    /// Transfers `amount` tokens to `contract` and executes its `Receive` hook with a
+    /// `ReceiveMsg` forwarding `msg`
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Option<Binary>,
+    },
+    // Older releases used lowercase variant names. Keep accepting the spelling from
+    // those clients while schemas advertise the snake_case name.
+    #[serde(alias = "transferfrom")]
     TransferFrom {
         owner: String,
         recipient: String,
@@ -36,13 +254,927 @@ pub enum ExecuteMsg {
     Burn {
         amount: Uint128,
     },
+    /// Burns `amount` tokens of `owner`, spending the signer's allowance like `TransferFrom`
+    BurnFrom {
+        owner: String,
+        amount: Uint128,
+    },
+    /// Creates a schedule minting `rate` tokens per block to `recipient` from block `start`
+    /// until block `end`. Only the minter can do this.
+    SetMintSchedule {
+        recipient: String,
+        rate: Uint128,
+        start: u64,
+        end: u64,
+    },
+    /// Mints all tokens accrued by the schedule since its last execution. Anyone can call this.
+    ExecuteMintSchedule {
+        schedule_id: u64,
+    },
+    /// Mints `amount` tokens to `recipient` right away. Only the minter can do this, and only
+    /// while mint approval is disabled.
+    Mint {
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Offers the minter role to `address`, who has to accept it with `AcceptMinter`.
+    /// Only the minter can do this.
+    TransferMinter {
+        address: String,
+    },
+    /// Makes the signer the minter if it was offered the role with `TransferMinter`
+    AcceptMinter {},
+    /// Hands the minter role to `new_minter` right away, or removes it for good for `None`,
+    /// so no mints can be executed anymore. Only the minter can do this.
+    UpdateMinter {
+        new_minter: Option<String>,
+    },
+    /// Updates the marketing information. Fields left out stay unchanged and empty strings
+    /// remove them. Only the marketing admin can do this.
+    UpdateMarketing {
+        project: Option<String>,
+        description: Option<String>,
+        logo_url: Option<String>,
+    },
+    /// Proposes minting `amount` tokens to `recipient`, which needs the approval of the
+    /// mint approvers. Only the minter can do this.
+    ProposeMint {
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Approves a mint proposal. Only mint approvers can do this.
+    ApproveMint {
+        mint_id: u64,
+    },
+    /// Mints the tokens of a proposal approved by enough approvers. Anyone can call this
+    /// before the proposal expires.
+    ExecuteMint {
+        mint_id: u64,
+    },
+    /// Locks `amount` tokens of the signer for `lock_duration` blocks in exchange for voting power.
+    /// Locking again adds to the locked amount and extends the lock if the new one ends later.
+    LockForVoting {
+        amount: Uint128,
+        lock_duration: u64,
+    },
+    /// Returns all tokens locked for voting to the signer once the lock expired
+    Withdraw {},
+    /// Gives the votes of the signer's balance to `delegatee`, or to the signer itself if
+    /// empty. Balances count for nobody until they are delegated.
+    Delegate {
+        delegatee: String,
+    },
+    /// Takes `amount` tokens from the signer and schedules a transfer to `recipient`, which
+    /// happens once `condition_query` sent to `oracle` returns a satisfied `ConditionResponse`
+    ScheduleConditional {
+        recipient: String,
+        amount: Uint128,
+        oracle: String,
+        condition_query: Binary,
+        expiry: u64,
+    },
+    /// Performs a conditional transfer if the oracle reports the condition as satisfied.
+    /// Anyone can call this before the transfer expires.
+    ExecuteConditional {
+        transfer_id: u64,
+    },
+    /// Returns the tokens of an expired conditional transfer to its sender. Anyone can call this.
+    RefundConditional {
+        transfer_id: u64,
+    },
+    /// Mints the pre-existing balances of up to 100 `entries`. `attestation` is the admin's
+    /// secp256k1 signature of the sha256 hash of the JSON serialized
+    /// `[chain_id, contract_address, entries]`. Every chunk of entries can only be airdropped once.
+    GenesisAirdrop {
+        entries: Vec<AirdropEntry>,
+        attestation: Binary,
+    },
+    /// Blocks `address` from receiving transfers. It can still send its tokens.
+    /// Only the admin can do this.
+    AddBlacklist {
+        address: String,
+    },
+    /// Allows `address` to receive transfers again. Only the admin can do this.
+    RemoveBlacklist {
+        address: String,
+    },
+    /// Allows `addresses` to send and receive transfers of a restricted token.
+    /// Only the admin can do this.
+    AddToWhitelist {
+        addresses: Vec<String>,
+    },
+    /// Stops `addresses` from sending and receiving transfers of a restricted token.
+    /// Only the admin can do this.
+    RemoveFromWhitelist {
+        addresses: Vec<String>,
+    },
+    /// Moves `amount` tokens of `owner` to `recipient` without an allowance, e.g. to enforce a
+    /// court order on a regulated token. Only the admin can do this.
+    Clawback {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Removes all admins, the minter and the marketing admin for good, so nobody can change
+    /// the token anymore. Only admins can do this.
+    RenounceRoles {},
+    /// Makes `address` an admin. Only admins can do this.
+    AddAdmin {
+        address: String,
+    },
+    /// Revokes the admin role of `address`. Only admins can do this.
+    RemoveAdmin {
+        address: String,
+    },
+    /// Queues `action` to be executed at block `eta` or later, which must be at least the
+    /// admin delay ahead. Only admins can do this.
+    ProposeAdminAction {
+        action: AdminAction,
+        eta: u64,
+    },
+    /// Performs a queued admin action once its eta is reached. Only admins can do this.
+    ExecuteAdminAction {
+        action_id: u64,
+    },
+    /// Drops a queued admin action. Only admins can do this.
+    CancelAdminAction {
+        action_id: u64,
+    },
+    /// Rejects all other messages until `Unpause` is sent. Only admins can do this, without
+    /// waiting for the admin delay.
+    Pause {},
+    /// Accepts messages again after `Pause`. Only admins can do this.
+    Unpause {},
+    /// Blocks `address` from sending and receiving transfers. Only admins can do this, and
+    /// like other admin actions it waits for the admin delay.
+    Freeze {
+        address: String,
+    },
+    /// Allows `address` to send and receive transfers again. Only admins can do this.
+    Unfreeze {
+        address: String,
+    },
+    /// Moves `amount` tokens sent to the token contract's own address to `recipient`.
+    /// Only admins can do this, through the timelock when there is an admin delay.
+    RecoverOwn {
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Stores a migration of this contract to `new_code_id` with `migrate_msg`, which can be
+    /// executed after `UPGRADE_DELAY` blocks. Only admins can do this.
+    InitiateUpgrade {
+        new_code_id: u64,
+        migrate_msg: Binary,
+    },
+    /// Migrates the contract as stored by `InitiateUpgrade` once its eta is reached.
+    /// Only admins can do this.
+    ExecuteUpgrade {},
+    /// Drops the pending upgrade. Only admins can do this.
+    CancelUpgrade {},
+    /// Executes `msgs` in order as if they were sent one by one, but fails as a whole when any
+    /// of them fails. Batches cannot contain other batches.
+    Batch {
+        msgs: Vec<ExecuteMsg>,
+    },
+}
+
+impl ExecuteMsg {
+    pub fn approve<T: Into<String>, A: Into<Uint128>>(spender: T, amount: A) -> Self {
+        ExecuteMsg::Approve {
+            spender: spender.into(),
+            amount: amount.into(),
+            expires: None,
+        }
+    }
+
+    pub fn increase_allowance<T: Into<String>, A: Into<Uint128>>(spender: T, amount: A) -> Self {
+        ExecuteMsg::IncreaseAllowance {
+            spender: spender.into(),
+            amount: amount.into(),
+            expires: None,
+        }
+    }
+
+    pub fn decrease_allowance<T: Into<String>, A: Into<Uint128>>(spender: T, amount: A) -> Self {
+        ExecuteMsg::DecreaseAllowance {
+            spender: spender.into(),
+            amount: amount.into(),
+            exact: false,
+            expires: None,
+        }
+    }
+
+    pub fn sweep_expired_allowances<T: Into<String>>(owner: T, spenders: Vec<T>) -> Self {
+        ExecuteMsg::SweepExpiredAllowances {
+            owner: owner.into(),
+            spenders: spenders.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn set_permit_key(pubkey: Option<Binary>) -> Self {
+        ExecuteMsg::SetPermitKey { pubkey }
+    }
+
+    pub fn transfer<T: Into<String>, A: Into<Uint128>>(recipient: T, amount: A) -> Self {
+        ExecuteMsg::Transfer {
+            recipient: recipient.into(),
+            amount: amount.into(),
+        }
+    }
+
+    pub fn transfer_all<T: Into<String>>(recipient: T) -> Self {
+        ExecuteMsg::TransferAll {
+            recipient: recipient.into(),
+        }
+    }
+
+    pub fn batch_transfer<T: Into<String> + Clone, A: Into<Uint128> + Copy>(
+        recipients: &[(T, A)],
+    ) -> Self {
+        ExecuteMsg::BatchTransfer {
+            recipients: recipients
+                .iter()
+                .map(|(address, amount)| InitialBalance {
+                    address: address.clone().into(),
+                    amount: (*amount).into(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn send<T: Into<String>, A: Into<Uint128>>(
+        contract: T,
+        amount: A,
+        msg: Option<Binary>,
+    ) -> Self {
+        ExecuteMsg::Send {
+            contract: contract.into(),
+            amount: amount.into(),
+            msg,
+        }
+    }
+
+    pub fn transfer_from<T: Into<String>, U: Into<String>, A: Into<Uint128>>(
+        owner: T,
+        recipient: U,
+        amount: A,
+    ) -> Self {
+        ExecuteMsg::TransferFrom {
+            owner: owner.into(),
+            recipient: recipient.into(),
+            amount: amount.into(),
+        }
+    }
+
+    pub fn burn<A: Into<Uint128>>(amount: A) -> Self {
+        ExecuteMsg::Burn {
+            amount: amount.into(),
+        }
+    }
+
+    pub fn burn_from<T: Into<String>, A: Into<Uint128>>(owner: T, amount: A) -> Self {
+        ExecuteMsg::BurnFrom {
+            owner: owner.into(),
+            amount: amount.into(),
+        }
+    }
+
+    pub fn set_mint_schedule<T: Into<String>, A: Into<Uint128>>(
+        recipient: T,
+        rate: A,
+        start: u64,
+        end: u64,
+    ) -> Self {
+        ExecuteMsg::SetMintSchedule {
+            recipient: recipient.into(),
+            rate: rate.into(),
+            start,
+            end,
+        }
+    }
+
+    pub fn execute_mint_schedule(schedule_id: u64) -> Self {
+        ExecuteMsg::ExecuteMintSchedule { schedule_id }
+    }
+
+    pub fn mint<T: Into<String>, A: Into<Uint128>>(recipient: T, amount: A) -> Self {
+        ExecuteMsg::Mint {
+            recipient: recipient.into(),
+            amount: amount.into(),
+        }
+    }
+
+    pub fn transfer_minter<T: Into<String>>(address: T) -> Self {
+        ExecuteMsg::TransferMinter {
+            address: address.into(),
+        }
+    }
+
+    pub fn accept_minter() -> Self {
+        ExecuteMsg::AcceptMinter {}
+    }
+
+    pub fn update_minter<T: Into<String>>(new_minter: Option<T>) -> Self {
+        ExecuteMsg::UpdateMinter {
+            new_minter: new_minter.map(Into::into),
+        }
+    }
+
+    pub fn update_marketing(
+        project: Option<&str>,
+        description: Option<&str>,
+        logo_url: Option<&str>,
+    ) -> Self {
+        ExecuteMsg::UpdateMarketing {
+            project: project.map(String::from),
+            description: description.map(String::from),
+            logo_url: logo_url.map(String::from),
+        }
+    }
+
+    pub fn propose_mint<T: Into<String>, A: Into<Uint128>>(recipient: T, amount: A) -> Self {
+        ExecuteMsg::ProposeMint {
+            recipient: recipient.into(),
+            amount: amount.into(),
+        }
+    }
+
+    pub fn approve_mint(mint_id: u64) -> Self {
+        ExecuteMsg::ApproveMint { mint_id }
+    }
+
+    pub fn execute_mint(mint_id: u64) -> Self {
+        ExecuteMsg::ExecuteMint { mint_id }
+    }
+
+    pub fn lock_for_voting<A: Into<Uint128>>(amount: A, lock_duration: u64) -> Self {
+        ExecuteMsg::LockForVoting {
+            amount: amount.into(),
+            lock_duration,
+        }
+    }
+
+    pub fn withdraw() -> Self {
+        ExecuteMsg::Withdraw {}
+    }
+
+    pub fn delegate<T: Into<String>>(delegatee: T) -> Self {
+        ExecuteMsg::Delegate {
+            delegatee: delegatee.into(),
+        }
+    }
+
+    pub fn schedule_conditional<T: Into<String>, A: Into<Uint128>, U: Into<String>>(
+        recipient: T,
+        amount: A,
+        oracle: U,
+        condition_query: Binary,
+        expiry: u64,
+    ) -> Self {
+        ExecuteMsg::ScheduleConditional {
+            recipient: recipient.into(),
+            amount: amount.into(),
+            oracle: oracle.into(),
+            condition_query,
+            expiry,
+        }
+    }
+
+    pub fn execute_conditional(transfer_id: u64) -> Self {
+        ExecuteMsg::ExecuteConditional { transfer_id }
+    }
+
+    pub fn refund_conditional(transfer_id: u64) -> Self {
+        ExecuteMsg::RefundConditional { transfer_id }
+    }
+
+    pub fn genesis_airdrop(entries: Vec<AirdropEntry>, attestation: Binary) -> Self {
+        ExecuteMsg::GenesisAirdrop {
+            entries,
+            attestation,
+        }
+    }
+
+    pub fn add_blacklist<T: Into<String>>(address: T) -> Self {
+        ExecuteMsg::AddBlacklist {
+            address: address.into(),
+        }
+    }
+
+    pub fn remove_blacklist<T: Into<String>>(address: T) -> Self {
+        ExecuteMsg::RemoveBlacklist {
+            address: address.into(),
+        }
+    }
+
+    pub fn add_to_whitelist<T: Into<String>>(addresses: Vec<T>) -> Self {
+        ExecuteMsg::AddToWhitelist {
+            addresses: addresses.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn remove_from_whitelist<T: Into<String>>(addresses: Vec<T>) -> Self {
+        ExecuteMsg::RemoveFromWhitelist {
+            addresses: addresses.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn clawback<T: Into<String>, U: Into<String>, A: Into<Uint128>>(
+        owner: T,
+        recipient: U,
+        amount: A,
+    ) -> Self {
+        ExecuteMsg::Clawback {
+            owner: owner.into(),
+            recipient: recipient.into(),
+            amount: amount.into(),
+        }
+    }
+
+    pub fn renounce_roles() -> Self {
+        ExecuteMsg::RenounceRoles {}
+    }
+
+    pub fn add_admin<T: Into<String>>(address: T) -> Self {
+        ExecuteMsg::AddAdmin {
+            address: address.into(),
+        }
+    }
+
+    pub fn remove_admin<T: Into<String>>(address: T) -> Self {
+        ExecuteMsg::RemoveAdmin {
+            address: address.into(),
+        }
+    }
+
+    pub fn propose_admin_action(action: AdminAction, eta: u64) -> Self {
+        ExecuteMsg::ProposeAdminAction { action, eta }
+    }
+
+    pub fn execute_admin_action(action_id: u64) -> Self {
+        ExecuteMsg::ExecuteAdminAction { action_id }
+    }
+
+    pub fn cancel_admin_action(action_id: u64) -> Self {
+        ExecuteMsg::CancelAdminAction { action_id }
+    }
+
+    pub fn initiate_upgrade(new_code_id: u64, migrate_msg: Binary) -> Self {
+        ExecuteMsg::InitiateUpgrade {
+            new_code_id,
+            migrate_msg,
+        }
+    }
+
+    pub fn execute_upgrade() -> Self {
+        ExecuteMsg::ExecuteUpgrade {}
+    }
+
+    pub fn cancel_upgrade() -> Self {
+        ExecuteMsg::CancelUpgrade {}
+    }
+
+    pub fn pause() -> Self {
+        ExecuteMsg::Pause {}
+    }
+
+    pub fn unpause() -> Self {
+        ExecuteMsg::Unpause {}
+    }
+
+    pub fn freeze<T: Into<String>>(address: T) -> Self {
+        ExecuteMsg::Freeze {
+            address: address.into(),
+        }
+    }
+
+    pub fn unfreeze<T: Into<String>>(address: T) -> Self {
+        ExecuteMsg::Unfreeze {
+            address: address.into(),
+        }
+    }
+
+    pub fn recover_own<T: Into<String>, A: Into<Uint128>>(recipient: T, amount: A) -> Self {
+        ExecuteMsg::RecoverOwn {
+            recipient: recipient.into(),
+            amount: amount.into(),
+        }
+    }
+
+    pub fn batch(msgs: Vec<ExecuteMsg>) -> Self {
+        ExecuteMsg::Batch { msgs }
+    }
+
+    /// Serializes the message into the JSON payload the `execute` entry point expects
+    pub fn to_binary(&self) -> StdResult<Binary> {
+        to_binary(self)
+    }
+
+    /// Creates a message executing this message on the token at `contract_addr`, for
+    /// contracts holding tokens
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.to_binary()?,
+            send: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// Highest `version` of `VersionedExecuteMsg` envelopes this contract understands
+pub const EXECUTE_MSG_VERSION: u32 = 1;
+
+/// What the contract accepts as execute message: either a bare `ExecuteMsg` or one wrapped
+/// in a `{"version":1,"msg":{...}}` envelope. Envelopes of a newer version are rejected with
+/// an explicit error before their message is parsed, so clients know they need to downgrade.
+#[derive(Serialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum VersionedExecuteMsg {
+    Envelope { version: u32, msg: ExecuteMsg },
+    Bare(ExecuteMsg),
+}
+
+impl VersionedExecuteMsg {
+    pub fn into_msg(self) -> ExecuteMsg {
+        match self {
+            VersionedExecuteMsg::Envelope { msg, .. } => msg,
+            VersionedExecuteMsg::Bare(msg) => msg,
+        }
+    }
+}
+
+// serde-json-wasm cannot deserialize untagged enums, so the envelope is detected by
+// looking at the first key: `version` for envelopes, the variant name for bare messages.
+impl<'de> Deserialize<'de> for VersionedExecuteMsg {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct(
+            "VersionedExecuteMsg",
+            &["version", "msg"],
+            VersionedVisitor,
+        )
+    }
+}
+
+struct VersionedVisitor;
+
+impl<'de> Visitor<'de> for VersionedVisitor {
+    type Value = VersionedExecuteMsg;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an execute message, optionally in a versioned envelope")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let key = map
+            .next_key_seed(KeySeed)?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        if key != "version" {
+            let msg = ExecuteMsg::deserialize(BareMsgDeserializer {
+                variant: key,
+                map: &mut map,
+            })?;
+            return Ok(VersionedExecuteMsg::Bare(msg));
+        }
+
+        let version: u32 = map.next_value()?;
+        if version > EXECUTE_MSG_VERSION {
+            return Err(de::Error::custom(format!(
+                "message version {} not supported, max {}",
+                version, EXECUTE_MSG_VERSION
+            )));
+        }
+        match map.next_key_seed(KeySeed)? {
+            Some(key) if key == "msg" => {}
+            _ => return Err(de::Error::missing_field("msg")),
+        }
+        let msg = map.next_value()?;
+        Ok(VersionedExecuteMsg::Envelope { version, msg })
+    }
+}
+
+struct KeySeed;
+
+impl<'de> DeserializeSeed<'de> for KeySeed {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<String, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a message key")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<String, E> {
+        Ok(value.to_string())
+    }
+}
+
+/// Deserializes an externally tagged enum whose tag was already read from `map`
+struct BareMsgDeserializer<'a, A> {
+    variant: String,
+    map: &'a mut A,
+}
+
+impl<'de, 'a, A: MapAccess<'de>> Deserializer<'de> for BareMsgDeserializer<'a, A> {
+    type Error = A::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, A::Error> {
+        Err(de::Error::custom("expected an enum"))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        visitor.visit_enum(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+impl<'de, 'a, A: MapAccess<'de>> EnumAccess<'de> for BareMsgDeserializer<'a, A> {
+    type Error = A::Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), A::Error> {
+        let variant = seed.deserialize(self.variant.clone().into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a, A: MapAccess<'de>> VariantAccess<'de> for BareMsgDeserializer<'a, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.map.next_value()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, A::Error> {
+        self.map.next_value_seed(seed)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        Err(de::Error::custom("tuple variants are not supported"))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.map.next_value_seed(StructSeed { fields, visitor })
+    }
+}
+
+struct StructSeed<V> {
+    fields: &'static [&'static str],
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for StructSeed<V> {
+    type Value = V::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_struct("", self.fields, self.visitor)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub enum QueryMsg {
-    Balance { address: String },
-    Allowance { owner: String, spender: String },
+    Balance {
+        address: String,
+    },
+    Allowance {
+        owner: String,
+        spender: String,
+    },
+    /// Voting power of `address` at block `at_block`, based on its current voting lock.
+    /// Earlier locks are not recorded, so `at_block` cannot be before the current block.
+    VotingPower {
+        address: String,
+        at_block: u64,
+    },
+    /// Sum of the balances delegated to `address`
+    Votes {
+        address: String,
+    },
+    /// The address `address` delegated its votes to
+    Delegation {
+        address: String,
+    },
+    /// Name and version of the contract code that last instantiated or migrated this instance
+    ContractVersion {},
+    /// Total supply after every block it changed in, from `from_block` to `to_block` (inclusive).
+    /// Returns up to `limit` entries, oldest first.
+    SupplyHistory {
+        from_block: u64,
+        to_block: u64,
+        limit: Option<u32>,
+    },
+    /// Highest total supply the token ever had
+    MaxSupply {},
+    /// Formats `amount` base units as decimal number using the token's decimals
+    Humanize {
+        amount: Uint128,
+    },
+    /// The upgrade waiting to be executed, if any
+    PendingUpgrade {},
+    /// The mint proposal `mint_id`, if it was not executed yet
+    MintProposal {
+        mint_id: u64,
+    },
+    /// Name, symbol and decimals of the token together with its current total supply
+    TokenInfo {},
+    /// Whether the contract is paused
+    Paused {},
+    /// The minter and the address it offered the role to, if any
+    Minter {},
+    /// The nonce the next permit of `owner` must use
+    PermitNonce {
+        owner: String,
+    },
+    /// Whether `address` is frozen
+    IsFrozen {
+        address: String,
+    },
+    /// Whether `address` is blacklisted
+    Blacklisted {
+        address: String,
+    },
+    /// Project, description, logo URL and marketing admin of the token
+    MarketingInfo {},
+    /// Balance of `address` at the end of block `height`. Needs the `iterator` feature.
+    #[cfg(feature = "iterator")]
+    BalanceAt {
+        address: String,
+        height: u64,
+    },
+    /// Addresses holding tokens, ordered by address. Returns up to `limit` addresses after
+    /// `start_after`. Needs the `iterator` feature.
+    #[cfg(feature = "iterator")]
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Whitelisted addresses, ordered by address. Returns up to `limit` addresses after
+    /// `start_after`. Needs the `iterator` feature.
+    #[cfg(feature = "iterator")]
+    Whitelist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The `limit` largest balances (at most 30), largest first and ties ordered by address.
+    /// Needs the `iterator` feature.
+    #[cfg(feature = "iterator")]
+    TopHolders {
+        limit: u32,
+    },
+}
+
+impl QueryMsg {
+    pub fn balance<T: Into<String>>(address: T) -> Self {
+        QueryMsg::Balance {
+            address: address.into(),
+        }
+    }
+
+    pub fn allowance<T: Into<String>, U: Into<String>>(owner: T, spender: U) -> Self {
+        QueryMsg::Allowance {
+            owner: owner.into(),
+            spender: spender.into(),
+        }
+    }
+
+    pub fn voting_power<T: Into<String>>(address: T, at_block: u64) -> Self {
+        QueryMsg::VotingPower {
+            address: address.into(),
+            at_block,
+        }
+    }
+
+    pub fn votes<T: Into<String>>(address: T) -> Self {
+        QueryMsg::Votes {
+            address: address.into(),
+        }
+    }
+
+    pub fn delegation<T: Into<String>>(address: T) -> Self {
+        QueryMsg::Delegation {
+            address: address.into(),
+        }
+    }
+
+    pub fn contract_version() -> Self {
+        QueryMsg::ContractVersion {}
+    }
+
+    pub fn supply_history(from_block: u64, to_block: u64, limit: Option<u32>) -> Self {
+        QueryMsg::SupplyHistory {
+            from_block,
+            to_block,
+            limit,
+        }
+    }
+
+    pub fn max_supply() -> Self {
+        QueryMsg::MaxSupply {}
+    }
+
+    pub fn humanize<A: Into<Uint128>>(amount: A) -> Self {
+        QueryMsg::Humanize {
+            amount: amount.into(),
+        }
+    }
+
+    pub fn mint_proposal(mint_id: u64) -> Self {
+        QueryMsg::MintProposal { mint_id }
+    }
+
+    pub fn token_info() -> Self {
+        QueryMsg::TokenInfo {}
+    }
+
+    pub fn paused() -> Self {
+        QueryMsg::Paused {}
+    }
+
+    pub fn minter() -> Self {
+        QueryMsg::Minter {}
+    }
+
+    pub fn permit_nonce<T: Into<String>>(owner: T) -> Self {
+        QueryMsg::PermitNonce {
+            owner: owner.into(),
+        }
+    }
+
+    pub fn blacklisted<T: Into<String>>(address: T) -> Self {
+        QueryMsg::Blacklisted {
+            address: address.into(),
+        }
+    }
+
+    pub fn is_frozen<T: Into<String>>(address: T) -> Self {
+        QueryMsg::IsFrozen {
+            address: address.into(),
+        }
+    }
+
+    pub fn marketing_info() -> Self {
+        QueryMsg::MarketingInfo {}
+    }
+
+    #[cfg(feature = "iterator")]
+    pub fn balance_at<T: Into<String>>(address: T, height: u64) -> Self {
+        QueryMsg::BalanceAt {
+            address: address.into(),
+            height,
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    pub fn all_accounts<T: Into<String>>(start_after: Option<T>, limit: Option<u32>) -> Self {
+        QueryMsg::AllAccounts {
+            start_after: start_after.map(Into::into),
+            limit,
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    pub fn whitelist<T: Into<String>>(start_after: Option<T>, limit: Option<u32>) -> Self {
+        QueryMsg::Whitelist {
+            start_after: start_after.map(Into::into),
+            limit,
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    pub fn top_holders(limit: u32) -> Self {
+        QueryMsg::TopHolders { limit }
+    }
+
+    /// Serializes the message into the JSON payload the `query` entry point expects
+    pub fn to_binary(&self) -> StdResult<Binary> {
+        to_binary(self)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -50,7 +1182,337 @@ pub struct BalanceResponse {
     pub balance: Uint128,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AllowanceResponse {
     pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct VotingPowerResponse {
+    pub voting_power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotesResponse {
+    pub votes: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DelegationResponse {
+    pub delegatee: Option<Addr>,
+}
+
+/// Total supply at the end of block `height`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupplyHistoryEntry {
+    pub height: u64,
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllAccountsResponse {
+    pub accounts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistResponse {
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HolderBalance {
+    pub address: String,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TopHoldersResponse {
+    pub holders: Vec<HolderBalance>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupplyHistoryResponse {
+    pub entries: Vec<SupplyHistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenInfoResponse {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PausedResponse {
+    pub paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermitNonceResponse {
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FrozenResponse {
+    pub frozen: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlacklistedResponse {
+    pub blacklisted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterResponse {
+    pub minter: Option<Addr>,
+    pub pending_minter: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MaxSupplyResponse {
+    pub max_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HumanizeResponse {
+    /// e.g. `"1.5"`
+    pub amount: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingUpgradeResponse {
+    pub upgrade: Option<PendingUpgrade>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintProposalResponse {
+    pub proposal: Option<MintProposal>,
+}
+
+/// The response oracles return to the condition query of a conditional transfer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConditionResponse {
+    pub satisfied: bool,
+}
+
+/// The message a contract receives when tokens are sent to it. Recipient contracts
+/// include it in their own `ExecuteMsg` as a `Receive(ReceiveMsg)` variant.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ReceiveMsg {
+    /// The account that sent the tokens
+    pub sender: String,
+    pub amount: Uint128,
+    /// Optional payload forwarded from the sender to the recipient contract
+    pub msg: Option<Binary>,
+}
+
+impl ReceiveMsg {
+    /// Serializes the message as `{"receive":{...}}`, the way recipient contracts expect it
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&ReceiverExecuteMsg::Receive(self))
+    }
+
+    /// Creates a message executing `Receive` on the given recipient contract
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            send: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ReceiverExecuteMsg {
+    Receive(ReceiveMsg),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{from_binary, from_slice, to_vec};
+
+    #[test]
+    fn execute_msg_serializes_snake_case() {
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "addr0000".to_string(),
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(3u128),
+        };
+        assert_eq!(
+            to_vec(&msg).unwrap(),
+            br#"{"transfer_from":{"owner":"addr0000","recipient":"addr1111","amount":"3"}}"#
+        );
+
+        let msg = ExecuteMsg::ExecuteMintSchedule { schedule_id: 1 };
+        assert_eq!(
+            to_vec(&msg).unwrap(),
+            br#"{"execute_mint_schedule":{"schedule_id":1}}"#
+        );
+    }
+
+    #[test]
+    fn execute_msg_accepts_legacy_lowercase() {
+        let snake: ExecuteMsg = from_slice(
+            br#"{"transfer_from":{"owner":"addr0000","recipient":"addr1111","amount":"3"}}"#,
+        )
+        .unwrap();
+        let lowercase: ExecuteMsg = from_slice(
+            br#"{"transferfrom":{"owner":"addr0000","recipient":"addr1111","amount":"3"}}"#,
+        )
+        .unwrap();
+        assert_eq!(snake, lowercase);
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict"))]
+    fn lenient_mode_ignores_unknown_fields() {
+        let msg: ExecuteMsg =
+            from_slice(br#"{"transfer":{"recipient":"addr1111","amount":"3","memo":"hi"}}"#)
+                .unwrap();
+        match msg {
+            ExecuteMsg::Transfer { recipient, amount } => {
+                assert_eq!(recipient, "addr1111");
+                assert_eq!(amount, Uint128::from(3u128));
+            }
+            _ => panic!("expected Transfer"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "strict")]
+    fn strict_mode_rejects_unknown_fields() {
+        let err = from_slice::<ExecuteMsg>(
+            br#"{"transfer":{"recipient":"addr1111","amount":"3","memo":"hi"}}"#,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `memo`"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn execute_msg_into_cosmos_msg() {
+        let msg = ExecuteMsg::transfer("addr1111", 5u128)
+            .into_cosmos_msg("token")
+            .unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: Binary::from(
+                    br#"{"transfer":{"recipient":"addr1111","amount":"5"}}"#.to_vec()
+                ),
+                send: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn receive_msg_into_cosmos_msg() {
+        let receive = ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::from(12u128),
+            msg: Some(Binary::from(b"{}")),
+        };
+        let msg = receive.clone().into_cosmos_msg("contract").unwrap();
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                send,
+            }) => {
+                assert_eq!(contract_addr, "contract");
+                assert_eq!(
+                    msg.as_slice(),
+                    br#"{"receive":{"sender":"addr0000","amount":"12","msg":"e30="}}"#
+                );
+                assert_eq!(send, vec![]);
+                // recipients can parse the payload using the exported type
+                let parsed: ReceiverExecuteMsg = from_slice(&msg).unwrap();
+                assert_eq!(parsed, ReceiverExecuteMsg::Receive(receive));
+            }
+            _ => panic!("expected wasm execute message"),
+        }
+    }
+
+    #[test]
+    fn query_msg_serializes_snake_case() {
+        let msg = QueryMsg::Allowance {
+            owner: "addr0000".to_string(),
+            spender: "addr1111".to_string(),
+        };
+        assert_eq!(
+            to_vec(&msg).unwrap(),
+            br#"{"allowance":{"owner":"addr0000","spender":"addr1111"}}"#
+        );
+        let parsed: QueryMsg =
+            from_slice(br#"{"allowance":{"owner":"addr0000","spender":"addr1111"}}"#).unwrap();
+        assert_eq!(parsed, msg);
+    }
+
+    #[test]
+    fn execute_msg_constructors_round_trip() {
+        let msgs = vec![
+            ExecuteMsg::approve("addr0000", 1u128),
+            ExecuteMsg::transfer("addr1111", 2u128),
+            ExecuteMsg::transfer_from("addr0000", "addr1111", 3u128),
+            ExecuteMsg::burn(4u128),
+            ExecuteMsg::set_mint_schedule("addr1111", 5u128, 10, 20),
+            ExecuteMsg::execute_mint_schedule(1),
+            ExecuteMsg::lock_for_voting(6u128, 100),
+            ExecuteMsg::withdraw(),
+            ExecuteMsg::schedule_conditional("addr1111", 7u128, "oracle", Binary::from(b"{}"), 30),
+            ExecuteMsg::execute_conditional(2),
+            ExecuteMsg::refund_conditional(3),
+            ExecuteMsg::genesis_airdrop(
+                vec![AirdropEntry {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(8u128),
+                }],
+                Binary::from(b"sig"),
+            ),
+        ];
+        for msg in msgs {
+            let parsed: ExecuteMsg = from_binary(&msg.to_binary().unwrap()).unwrap();
+            assert_eq!(parsed, msg);
+        }
+
+        assert_eq!(
+            ExecuteMsg::transfer("addr1111", 2u128)
+                .to_binary()
+                .unwrap()
+                .as_slice(),
+            br#"{"transfer":{"recipient":"addr1111","amount":"2"}}"#
+        );
+    }
+
+    #[test]
+    fn query_msg_constructors_round_trip() {
+        let msgs = vec![
+            QueryMsg::balance("addr0000"),
+            QueryMsg::allowance("addr0000", "addr1111"),
+            QueryMsg::voting_power("addr0000", 12),
+            QueryMsg::contract_version(),
+        ];
+        for msg in msgs {
+            let parsed: QueryMsg = from_binary(&msg.to_binary().unwrap()).unwrap();
+            assert_eq!(parsed, msg);
+        }
+
+        assert_eq!(
+            QueryMsg::balance("addr0000")
+                .to_binary()
+                .unwrap()
+                .as_slice(),
+            br#"{"balance":{"address":"addr0000"}}"#
+        );
+    }
 }
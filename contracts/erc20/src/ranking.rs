@@ -0,0 +1,110 @@
+//! Holders ordered by balance, so the largest ones can be listed without reading every
+//! balance.
+//!
+//! Every balance change moves the account's entry, which is keyed by the balance subtracted
+//! from `u128::MAX` followed by the address. Iterating the keys in order yields the largest
+//! balances first and ties ordered by address. Empty accounts are not ranked.
+//!
+//! Ranking needs storage iteration, so this module is only part of builds with the `iterator`
+//! feature.
+
+use std::convert::TryInto;
+
+use cosmwasm_std::{Addr, Order, StdError, StdResult, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+pub const PREFIX_RANKING: &[u8] = b"ranking";
+
+fn ranking_key(address: &Addr, balance: u128) -> Vec<u8> {
+    let mut key = (u128::MAX - balance).to_be_bytes().to_vec();
+    key.extend_from_slice(address.as_str().as_bytes());
+    key
+}
+
+/// Moves `address` from its place for `previous` to the one for `balance`
+pub fn rank_balance(store: &mut dyn Storage, address: &Addr, previous: u128, balance: u128) {
+    if previous == balance {
+        return;
+    }
+    let mut ranking_store = PrefixedStorage::new(store, PREFIX_RANKING);
+    if previous > 0 {
+        ranking_store.remove(&ranking_key(address, previous));
+    }
+    if balance > 0 {
+        ranking_store.set(&ranking_key(address, balance), &[1]);
+    }
+}
+
+/// Removes every entry, so all balances can be ranked again
+pub fn clear_ranking(store: &mut dyn Storage) {
+    let keys: Vec<_> = ReadonlyPrefixedStorage::new(store, PREFIX_RANKING)
+        .range(None, None, Order::Ascending)
+        .map(|(key, _)| key)
+        .collect();
+    let mut ranking_store = PrefixedStorage::new(store, PREFIX_RANKING);
+    for key in keys {
+        ranking_store.remove(&key);
+    }
+}
+
+/// Up to `limit` holders with their balances, largest first
+pub fn top_balances(store: &dyn Storage, limit: usize) -> StdResult<Vec<(String, u128)>> {
+    let ranking_store = ReadonlyPrefixedStorage::new(store, PREFIX_RANKING);
+    ranking_store
+        .range(None, None, Order::Ascending)
+        .take(limit)
+        .map(|(key, _)| {
+            if key.len() < 16 {
+                return Err(StdError::invalid_data_size(16, key.len()));
+            }
+            let (inverted, address) = key.split_at(16);
+            let inverted = u128::from_be_bytes(inverted.try_into().unwrap());
+            Ok((String::from_utf8(address.to_vec())?, u128::MAX - inverted))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    fn addr(address: &str) -> Addr {
+        Addr::unchecked(address)
+    }
+
+    #[test]
+    fn orders_by_balance_then_address() {
+        let mut store = MockStorage::new();
+        rank_balance(&mut store, &addr("carol"), 0, 50);
+        rank_balance(&mut store, &addr("alice"), 0, 100);
+        rank_balance(&mut store, &addr("bob"), 0, 50);
+        rank_balance(&mut store, &addr("dave"), 0, u128::MAX);
+
+        let top = top_balances(&store, 10).unwrap();
+        assert_eq!(
+            top,
+            vec![
+                ("dave".to_string(), u128::MAX),
+                ("alice".to_string(), 100),
+                ("bob".to_string(), 50),
+                ("carol".to_string(), 50),
+            ]
+        );
+        assert_eq!(top_balances(&store, 2).unwrap(), top[..2].to_vec());
+    }
+
+    #[test]
+    fn moves_changed_balances() {
+        let mut store = MockStorage::new();
+        rank_balance(&mut store, &addr("alice"), 0, 100);
+        rank_balance(&mut store, &addr("bob"), 0, 50);
+        rank_balance(&mut store, &addr("alice"), 100, 10);
+        rank_balance(&mut store, &addr("bob"), 50, 0);
+        rank_balance(&mut store, &addr("carol"), 0, 20);
+        assert_eq!(
+            top_balances(&store, 10).unwrap(),
+            vec![("carol".to_string(), 20), ("alice".to_string(), 10)]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! An in-memory simulator of the token for application developers.
+//!
+//! `TokenSim` runs the real contract functions against mock storage, so it behaves exactly
+//! like the contract on chain without the need for a wasm VM. Each executed message is
+//! included in its own block.
+//!
+//! ```
+//! use cw_erc20::sim::TokenSim;
+//! use cw_erc20::{InitialBalance, InstantiateMsg};
+//!
+//! let mut sim = TokenSim::new(InstantiateMsg {
+//!     name: "Cash Token".to_string(),
+//!     symbol: "CASH".to_string(),
+//!     decimals: 9,
+//!     initial_balances: vec![InitialBalance {
+//!         address: "alice".to_string(),
+//!         amount: 100u128.into(),
+//!     }],
+//!     minter: None,
+//!     admins: vec![],
+//!     genesis: None,
+//!     mint_limit_per_window: None,
+//!     mint_window_blocks: 0,
+//!     mint_approval: None,
+//!     admin_delay: 0,
+//!     marketing: None,
+//!     restricted: false,
+//! })
+//! .unwrap();
+//! sim.transfer("alice", "bob", 10).unwrap();
+//! assert_eq!(sim.balance("bob"), 10);
+//! ```
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{from_binary, Env, OwnedDeps, Response};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::msg::{AllowanceResponse, BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::query_total_supply;
+
+/// Seconds between two simulated blocks
+pub const BLOCK_TIME: u64 = 5;
+
+pub struct TokenSim {
+    deps: OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: Env,
+}
+
+impl TokenSim {
+    /// Instantiates the token in the first block, sent by `creator`
+    pub fn new(msg: InstantiateMsg) -> Result<Self, ContractError> {
+        let mut sim = TokenSim {
+            deps: mock_dependencies(&[]),
+            env: mock_env(),
+        };
+        instantiate(
+            sim.deps.as_mut(),
+            sim.env.clone(),
+            mock_info("creator", &[]),
+            msg,
+        )?;
+        sim.advance_blocks(1);
+        Ok(sim)
+    }
+
+    pub fn height(&self) -> u64 {
+        self.env.block.height
+    }
+
+    pub fn advance_blocks(&mut self, blocks: u64) {
+        self.env.block.height += blocks;
+        self.env.block.time = self.env.block.time.plus_seconds(blocks * BLOCK_TIME);
+    }
+
+    /// Executes `msg` sent by `sender` in the current block, then moves on to the next one
+    pub fn execute(&mut self, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        let res = execute(
+            self.deps.as_mut(),
+            self.env.clone(),
+            mock_info(sender, &[]),
+            msg,
+        );
+        self.advance_blocks(1);
+        res
+    }
+
+    pub fn transfer(
+        &mut self,
+        sender: &str,
+        recipient: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        self.execute(sender, ExecuteMsg::transfer(recipient, amount))
+    }
+
+    pub fn approve(
+        &mut self,
+        owner: &str,
+        spender: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        self.execute(owner, ExecuteMsg::approve(spender, amount))
+    }
+
+    pub fn increase_allowance(
+        &mut self,
+        owner: &str,
+        spender: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        self.execute(owner, ExecuteMsg::increase_allowance(spender, amount))
+    }
+
+    pub fn decrease_allowance(
+        &mut self,
+        owner: &str,
+        spender: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        self.execute(owner, ExecuteMsg::decrease_allowance(spender, amount))
+    }
+
+    pub fn transfer_from(
+        &mut self,
+        spender: &str,
+        owner: &str,
+        recipient: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        self.execute(spender, ExecuteMsg::transfer_from(owner, recipient, amount))
+    }
+
+    pub fn burn(&mut self, owner: &str, amount: u128) -> Result<Response, ContractError> {
+        self.execute(owner, ExecuteMsg::burn(amount))
+    }
+
+    pub fn burn_from(
+        &mut self,
+        spender: &str,
+        owner: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        self.execute(spender, ExecuteMsg::burn_from(owner, amount))
+    }
+
+    /// Panics if `address` is invalid
+    pub fn balance(&self, address: &str) -> u128 {
+        let res = query(
+            self.deps.as_ref(),
+            self.env.clone(),
+            QueryMsg::balance(address),
+        )
+        .expect("balance query failed");
+        let balance: BalanceResponse = from_binary(&res).expect("invalid balance response");
+        balance.balance.u128()
+    }
+
+    /// Panics if `owner` or `spender` is invalid
+    pub fn allowance(&self, owner: &str, spender: &str) -> u128 {
+        let msg = QueryMsg::allowance(owner, spender);
+        let res = query(self.deps.as_ref(), self.env.clone(), msg).expect("allowance query failed");
+        let allowance: AllowanceResponse = from_binary(&res).expect("invalid allowance response");
+        allowance.allowance.u128()
+    }
+
+    pub fn total_supply(&self) -> u128 {
+        query_total_supply(&self.deps.storage).expect("total supply not found")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::InitialBalance;
+
+    fn make_sim() -> TokenSim {
+        TokenSim::new(InstantiateMsg {
+            name: "Cash Token".to_string(),
+            symbol: "CASH".to_string(),
+            decimals: 9,
+            initial_balances: vec![InitialBalance {
+                address: "alice".to_string(),
+                amount: 100u128.into(),
+            }],
+            minter: None,
+            admins: vec![],
+            genesis: None,
+            mint_limit_per_window: None,
+            mint_window_blocks: 0,
+            mint_approval: None,
+            admin_delay: 0,
+            marketing: None,
+            restricted: false,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn runs_contract_functions() {
+        let mut sim = make_sim();
+        sim.transfer("alice", "bob", 10).unwrap();
+        sim.approve("alice", "carol", 20).unwrap();
+        sim.transfer_from("carol", "alice", "dave", 15).unwrap();
+        sim.burn("bob", 3).unwrap();
+
+        assert_eq!(sim.balance("alice"), 75);
+        assert_eq!(sim.balance("bob"), 7);
+        assert_eq!(sim.balance("dave"), 15);
+        assert_eq!(sim.allowance("alice", "carol"), 5);
+        assert_eq!(sim.total_supply(), 97);
+
+        match sim.transfer("bob", "alice", 8).unwrap_err() {
+            ContractError::InsufficientFunds {
+                balance: 7,
+                required: 8,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn advances_blocks() {
+        let mut sim = make_sim();
+        let start = sim.height();
+        let time = sim.env.block.time;
+
+        sim.transfer("alice", "bob", 10).unwrap();
+        sim.transfer("alice", "bob", 1000).unwrap_err();
+        assert_eq!(sim.height(), start + 2);
+
+        sim.advance_blocks(10);
+        assert_eq!(sim.height(), start + 12);
+        assert_eq!(sim.env.block.time, time.plus_seconds(12 * BLOCK_TIME));
+    }
+}
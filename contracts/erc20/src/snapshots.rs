@@ -0,0 +1,94 @@
+//! Balance history for contracts that need to know what an account held at a past block,
+//! e.g. to weigh governance votes or pay out staking rewards.
+//!
+//! Every balance change stores the new balance keyed by `(address, height)`. Several changes
+//! in one block leave a single snapshot with the balance at the end of the block.
+
+#[cfg(feature = "iterator")]
+use std::convert::TryInto;
+
+use cosmwasm_std::{Addr, Storage};
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, StdError, StdResult};
+use cosmwasm_storage::PrefixedStorage;
+#[cfg(feature = "iterator")]
+use cosmwasm_storage::ReadonlyPrefixedStorage;
+
+pub const PREFIX_SNAPSHOTS: &[u8] = b"snapshots";
+
+/// Stores `balance` as the balance of `address` at the end of block `height`
+pub fn record_balance(store: &mut dyn Storage, address: &Addr, height: u64, balance: u128) {
+    let mut snapshots_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_SNAPSHOTS, address.as_str().as_bytes()]);
+    snapshots_store.set(&height.to_be_bytes(), &balance.to_be_bytes());
+}
+
+/// The balance of `address` at the end of block `height`, taken from the latest snapshot at
+/// or before it. Accounts without such a snapshot held nothing then.
+#[cfg(feature = "iterator")]
+pub fn balance_at(store: &dyn Storage, address: &Addr, height: u64) -> StdResult<u128> {
+    let snapshots_store = ReadonlyPrefixedStorage::multilevel(
+        store,
+        &[PREFIX_SNAPSHOTS, address.as_str().as_bytes()],
+    );
+    // the range end is exclusive
+    let end = height.checked_add(1).map(u64::to_be_bytes);
+    let latest = snapshots_store
+        .range(None, end.as_ref().map(|key| &key[..]), Order::Descending)
+        .next();
+    match latest {
+        Some((_, value)) => decode_balance(&value),
+        None => Ok(0),
+    }
+}
+
+#[cfg(feature = "iterator")]
+fn decode_balance(data: &[u8]) -> StdResult<u128> {
+    match data.try_into() {
+        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
+        Err(_) => Err(StdError::invalid_data_size(16, data.len())),
+    }
+}
+
+#[cfg(all(test, feature = "iterator"))]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    fn addr(address: &str) -> Addr {
+        Addr::unchecked(address)
+    }
+
+    #[test]
+    fn returns_latest_snapshot_at_or_before_height() {
+        let mut store = MockStorage::new();
+        record_balance(&mut store, &addr("alice"), 10, 100);
+        record_balance(&mut store, &addr("alice"), 20, 40);
+
+        assert_eq!(balance_at(&store, &addr("alice"), 9).unwrap(), 0);
+        assert_eq!(balance_at(&store, &addr("alice"), 10).unwrap(), 100);
+        assert_eq!(balance_at(&store, &addr("alice"), 19).unwrap(), 100);
+        assert_eq!(balance_at(&store, &addr("alice"), 20).unwrap(), 40);
+        assert_eq!(balance_at(&store, &addr("alice"), u64::MAX).unwrap(), 40);
+    }
+
+    #[test]
+    fn keeps_last_balance_of_block() {
+        let mut store = MockStorage::new();
+        record_balance(&mut store, &addr("alice"), 10, 100);
+        record_balance(&mut store, &addr("alice"), 10, 70);
+        assert_eq!(balance_at(&store, &addr("alice"), 10).unwrap(), 70);
+    }
+
+    #[test]
+    fn separates_addresses() {
+        let mut store = MockStorage::new();
+        // "alice" is a prefix of "alicea", which must not leak into its history
+        record_balance(&mut store, &addr("alicea"), 10, 100);
+        record_balance(&mut store, &addr("alice"), 20, 5);
+
+        assert_eq!(balance_at(&store, &addr("alice"), 15).unwrap(), 0);
+        assert_eq!(balance_at(&store, &addr("alicea"), 30).unwrap(), 100);
+        assert_eq!(balance_at(&store, &addr("bob"), 30).unwrap(), 0);
+    }
+}
@@ -1,9 +1,297 @@
+use cosmwasm_std::{from_slice, Addr, Binary, StdError, Storage, Uint128};
+use cosmwasm_storage::{to_length_prefixed, to_length_prefixed_nested, ReadonlyPrefixedStorage};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::contract::{
+    bytes_to_u128, KEY_CONSTANTS, KEY_TOTAL_SUPPLY, PREFIX_ALLOWANCES, PREFIX_BALANCES,
+    PREFIX_CONFIG,
+};
+use crate::error::ContractError;
+use crate::msg::AdminAction;
+
 #[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct Constants {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
 }
+
+/// Project information shown by wallets and explorers, changeable by `marketing_admin`
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct Marketing {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub logo_url: Option<String>,
+    pub marketing_admin: Option<Addr>,
+}
+
+/// Releases `rate` tokens per block to `recipient` between the `start` and `end` block heights
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct MintSchedule {
+    pub recipient: Addr,
+    pub rate: Uint128,
+    pub start: u64,
+    pub end: u64,
+    /// Height up to which tokens have been minted already
+    pub last_executed_block: u64,
+}
+
+/// Caps the tokens minted in windows of `window_blocks` blocks. A window starts with the
+/// first mint after the previous one ended.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct MintRateLimit {
+    pub limit: Uint128,
+    pub window_blocks: u64,
+    pub window_start_block: u64,
+    pub minted_in_window: Uint128,
+}
+
+/// Mints need `threshold` of the `approvers` to approve them
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct MintApproval {
+    pub approvers: Vec<Addr>,
+    pub threshold: u64,
+    pub proposal_expiry: u64,
+}
+
+/// A mint proposed by the minter, which can be executed once approved by enough approvers.
+/// It can no longer be approved or executed at block `expiry` or later.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct MintProposal {
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub approvals: Vec<Addr>,
+    pub expiry: u64,
+}
+
+/// An admin action waiting for its `eta` block height
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct QueuedAdminAction {
+    pub action: AdminAction,
+    pub eta: u64,
+    pub proposer: Addr,
+}
+
+/// A migration of the contract waiting for its `eta` block height
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct PendingUpgrade {
+    pub new_code_id: u64,
+    pub migrate_msg: Binary,
+    pub eta: u64,
+    pub proposer: Addr,
+}
+
+/// Tokens locked for voting. Voting power decays linearly until `end`, when the tokens
+/// can be withdrawn again.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct VotingLock {
+    pub amount: Uint128,
+    /// Height of the first lock. There is no voting power before.
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Tokens taken from `sender` that go to `recipient` once the oracle reports the condition
+/// as satisfied, or back to `sender` if that did not happen before `expiry`
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct ConditionalTransfer {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub oracle: Addr,
+    /// Smart query sent to the oracle, which must answer with a `ConditionResponse`
+    pub condition_query: Binary,
+    pub expiry: u64,
+}
+
+/// Reads the token constants stored at instantiation
+pub fn query_constants(storage: &dyn Storage) -> Result<Constants, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
+    match config_store.get(KEY_CONSTANTS) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(StdError::not_found("Constants").into()),
+    }
+}
+
+/// Constants as written before the minter got its own storage entry
+#[derive(Deserialize)]
+struct LegacyConstants {
+    #[serde(default)]
+    minter: Option<Addr>,
+}
+
+/// Reads the minter from constants written by older versions, which stored it there
+pub(crate) fn query_legacy_minter(storage: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
+    match config_store.get(KEY_CONSTANTS) {
+        Some(data) => Ok(from_slice::<LegacyConstants>(&data)?.minter),
+        None => Ok(None),
+    }
+}
+
+/// Reads the current total supply
+pub fn query_total_supply(storage: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
+    match config_store.get(KEY_TOTAL_SUPPLY) {
+        Some(data) => bytes_to_u128(&data),
+        None => Err(StdError::not_found("total supply").into()),
+    }
+}
+
+/// Raw storage key of the total supply, e.g. for contracts reading it with a raw query
+pub fn total_supply_key() -> Vec<u8> {
+    let mut key = to_length_prefixed(PREFIX_CONFIG);
+    key.extend_from_slice(KEY_TOTAL_SUPPLY);
+    key
+}
+
+/// Raw storage key of the balance of `address`, for clients reading storage directly
+pub fn balance_key(address: &Addr) -> Vec<u8> {
+    let mut key = to_length_prefixed(PREFIX_BALANCES);
+    key.extend_from_slice(address.as_str().as_bytes());
+    key
+}
+
+/// Raw storage key of the amount `spender` may transfer from `owner`. The amount stays in
+/// storage after the allowance expired, the expiry is only checked by the contract.
+pub fn allowance_key(owner: &Addr, spender: &Addr) -> Vec<u8> {
+    let mut key = to_length_prefixed_nested(&[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
+    key.extend_from_slice(spender.as_str().as_bytes());
+    key
+}
+
+/// Decodes a balance, allowance or the total supply read from raw storage
+pub fn decode_amount(data: &[u8]) -> Result<u128, ContractError> {
+    bytes_to_u128(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockStorage};
+    use cosmwasm_storage::PrefixedStorage;
+
+    fn set_config(storage: &mut dyn Storage, key: &[u8], value: &[u8]) {
+        let mut config_store = PrefixedStorage::new(storage, PREFIX_CONFIG);
+        config_store.set(key, value);
+    }
+
+    #[test]
+    fn query_constants_works() {
+        let mut storage = MockStorage::new();
+        set_config(
+            &mut storage,
+            KEY_CONSTANTS,
+            br#"{"name":"Cash Token","symbol":"CASH","decimals":9,"minter":null}"#,
+        );
+        assert_eq!(
+            query_constants(&storage).unwrap(),
+            Constants {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+            }
+        );
+    }
+
+    #[test]
+    fn query_constants_fails_for_missing_or_corrupted_data() {
+        let mut storage = MockStorage::new();
+        match query_constants(&storage).unwrap_err() {
+            ContractError::Std(StdError::NotFound { .. }) => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        set_config(&mut storage, KEY_CONSTANTS, b"{\"name\":");
+        match query_constants(&storage).unwrap_err() {
+            ContractError::Std(StdError::ParseErr { .. }) => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn query_total_supply_works() {
+        let mut storage = MockStorage::new();
+        set_config(&mut storage, KEY_TOTAL_SUPPLY, &1234u128.to_be_bytes());
+        assert_eq!(query_total_supply(&storage).unwrap(), 1234);
+    }
+
+    #[test]
+    fn query_total_supply_fails_for_missing_or_corrupted_data() {
+        let mut storage = MockStorage::new();
+        match query_total_supply(&storage).unwrap_err() {
+            ContractError::Std(StdError::NotFound { .. }) => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        for corrupted in &[&b"\x01\x02\x03"[..], &[0u8; 17][..]] {
+            set_config(&mut storage, KEY_TOTAL_SUPPLY, corrupted);
+            match query_total_supply(&storage).unwrap_err() {
+                ContractError::CorruptedDataFound {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn raw_keys_match_contract_storage() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            name: "Cash Token".to_string(),
+            symbol: "CASH".to_string(),
+            decimals: 9,
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(11u128),
+            }],
+            minter: None,
+            admins: vec![],
+            genesis: None,
+            mint_limit_per_window: None,
+            mint_window_blocks: 0,
+            mint_approval: None,
+            admin_delay: 0,
+            marketing: None,
+            restricted: false,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let info = mock_info("addr0000", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::transfer("addr1111", 4u128),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::approve("addr2222", 3u128),
+        )
+        .unwrap();
+
+        let owner = Addr::unchecked("addr0000");
+        let read = |key: Vec<u8>| decode_amount(&deps.storage.get(&key).unwrap()).unwrap();
+        assert_eq!(read(balance_key(&owner)), 7);
+        assert_eq!(read(balance_key(&Addr::unchecked("addr1111"))), 4);
+        assert_eq!(read(allowance_key(&owner, &Addr::unchecked("addr2222"))), 3);
+        assert_eq!(read(total_supply_key()), 11);
+        assert_eq!(
+            deps.storage.get(&balance_key(&Addr::unchecked("addr2222"))),
+            None
+        );
+    }
+
+    #[test]
+    fn decode_amount_rejects_corrupted_data() {
+        assert_eq!(decode_amount(&7u128.to_be_bytes()).unwrap(), 7);
+        match decode_amount(b"\x07").unwrap_err() {
+            ContractError::CorruptedDataFound {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
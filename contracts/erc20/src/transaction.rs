@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "iterator")]
+use std::iter::Peekable;
+#[cfg(feature = "iterator")]
+use std::ops::Bound;
+
+use cosmwasm_std::Storage;
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, Pair};
+
+/// Buffers writes to `base` until `commit` is called. Dropping the transaction
+/// discards them, so a failed batch leaves no state behind.
+pub struct StorageTransaction<'a> {
+    base: &'a mut dyn Storage,
+    /// `None` marks removed keys
+    writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> StorageTransaction<'a> {
+    pub fn new(base: &'a mut dyn Storage) -> Self {
+        StorageTransaction {
+            base,
+            writes: BTreeMap::new(),
+        }
+    }
+
+    /// Applies all buffered writes to the underlying storage
+    pub fn commit(self) {
+        for (key, value) in self.writes {
+            match value {
+                Some(value) => self.base.set(&key, &value),
+                None => self.base.remove(&key),
+            }
+        }
+    }
+}
+
+impl<'a> Storage for StorageTransaction<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.writes.get(key) {
+            Some(value) => value.clone(),
+            None => self.base.get(key),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Pair> + 'b> {
+        // BTreeMap::range panics when start is after end, Storage::range returns nothing
+        if let (Some(start), Some(end)) = (start, end) {
+            if start >= end {
+                return Box::new(std::iter::empty());
+            }
+        }
+        let bounds = (
+            start.map_or(Bound::Unbounded, |start| Bound::Included(start.to_vec())),
+            end.map_or(Bound::Unbounded, |end| Bound::Excluded(end.to_vec())),
+        );
+        let writes: Box<dyn Iterator<Item = _> + 'b> = match order {
+            Order::Ascending => Box::new(self.writes.range(bounds)),
+            Order::Descending => Box::new(self.writes.range(bounds).rev()),
+        };
+        Box::new(MergedRange {
+            base: self.base.range(start, end, order).peekable(),
+            writes: writes.peekable(),
+            order,
+        })
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.writes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.writes.insert(key.to_vec(), None);
+    }
+}
+
+/// Merges the pending writes into a range of the underlying storage as it is iterated,
+/// so only the entries actually read are loaded
+#[cfg(feature = "iterator")]
+struct MergedRange<'a, B, W>
+where
+    B: Iterator<Item = Pair>,
+    W: Iterator<Item = (&'a Vec<u8>, &'a Option<Vec<u8>>)>,
+{
+    base: Peekable<B>,
+    writes: Peekable<W>,
+    order: Order,
+}
+
+#[cfg(feature = "iterator")]
+impl<'a, B, W> Iterator for MergedRange<'a, B, W>
+where
+    B: Iterator<Item = Pair>,
+    W: Iterator<Item = (&'a Vec<u8>, &'a Option<Vec<u8>>)>,
+{
+    type Item = Pair;
+
+    fn next(&mut self) -> Option<Pair> {
+        loop {
+            // whether the next entry comes from the pending writes rather than the base
+            let take_write = match (self.base.peek(), self.writes.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => false,
+                (None, Some(_)) => true,
+                (Some((base_key, _)), Some((write_key, _))) => {
+                    if base_key == *write_key {
+                        // the pending write shadows the stored value
+                        self.base.next();
+                        true
+                    } else {
+                        match self.order {
+                            Order::Ascending => *write_key < base_key,
+                            Order::Descending => *write_key > base_key,
+                        }
+                    }
+                }
+            };
+            if !take_write {
+                return self.base.next();
+            }
+            if let Some((key, Some(value))) = self.writes.next() {
+                return Some((key.clone(), value.clone()));
+            }
+            // removed keys are skipped
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    #[test]
+    fn reads_own_writes() {
+        let mut storage = MockStorage::new();
+        storage.set(b"foo", b"bar");
+        storage.set(b"gone", b"soon");
+
+        let mut transaction = StorageTransaction::new(&mut storage);
+        assert_eq!(transaction.get(b"foo"), Some(b"bar".to_vec()));
+        transaction.set(b"foo", b"baz");
+        transaction.set(b"new", b"value");
+        transaction.remove(b"gone");
+        assert_eq!(transaction.get(b"foo"), Some(b"baz".to_vec()));
+        assert_eq!(transaction.get(b"new"), Some(b"value".to_vec()));
+        assert_eq!(transaction.get(b"gone"), None);
+    }
+
+    #[test]
+    fn commit_applies_writes() {
+        let mut storage = MockStorage::new();
+        storage.set(b"foo", b"bar");
+        storage.set(b"gone", b"soon");
+
+        let mut transaction = StorageTransaction::new(&mut storage);
+        transaction.set(b"foo", b"baz");
+        transaction.remove(b"gone");
+        transaction.commit();
+        assert_eq!(storage.get(b"foo"), Some(b"baz".to_vec()));
+        assert_eq!(storage.get(b"gone"), None);
+    }
+
+    #[test]
+    fn drop_discards_writes() {
+        let mut storage = MockStorage::new();
+        storage.set(b"foo", b"bar");
+
+        let mut transaction = StorageTransaction::new(&mut storage);
+        transaction.set(b"foo", b"baz");
+        transaction.remove(b"foo");
+        drop(transaction);
+        assert_eq!(storage.get(b"foo"), Some(b"bar".to_vec()));
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn range_merges_writes() {
+        let mut storage = MockStorage::new();
+        for key in &[b"a", b"c", b"e", b"g"] {
+            storage.set(*key, b"old");
+        }
+
+        let mut transaction = StorageTransaction::new(&mut storage);
+        transaction.set(b"b", b"new");
+        transaction.set(b"c", b"new");
+        transaction.remove(b"e");
+        transaction.set(b"h", b"new");
+        let pair = |key: &[u8], value: &[u8]| (key.to_vec(), value.to_vec());
+
+        let all: Vec<_> = transaction.range(None, None, Order::Ascending).collect();
+        assert_eq!(
+            all,
+            vec![
+                pair(b"a", b"old"),
+                pair(b"b", b"new"),
+                pair(b"c", b"new"),
+                pair(b"g", b"old"),
+                pair(b"h", b"new"),
+            ]
+        );
+        let bounded: Vec<_> = transaction
+            .range(Some(b"b"), Some(b"h"), Order::Descending)
+            .collect();
+        assert_eq!(
+            bounded,
+            vec![pair(b"g", b"old"), pair(b"c", b"new"), pair(b"b", b"new")]
+        );
+        assert_eq!(
+            transaction
+                .range(Some(b"h"), Some(b"a"), Order::Ascending)
+                .count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Conversion between raw token amounts and their decimal representation,
+//! e.g. `1500000` with 6 decimals is `"1.5"`.
+
+use crate::error::ContractError;
+
+/// Formats `amount` base units as decimal number without trailing zeros
+pub fn format_units(amount: u128, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", amount, width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+/// Parses a decimal number like `"1.5"` into base units. Trailing zeros are ignored, but
+/// any other digits beyond `decimals` are rejected instead of being rounded away.
+pub fn parse_units(value: &str, decimals: u8) -> Result<u128, ContractError> {
+    let invalid = || ContractError::InvalidAmount {
+        value: value.to_string(),
+    };
+    let (integer, fraction) = match value.find('.') {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => (value, ""),
+    };
+    let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    if integer.is_empty()
+        || !is_digits(integer)
+        || !is_digits(fraction)
+        || (value.contains('.') && fraction.is_empty())
+    {
+        return Err(invalid());
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        return Err(ContractError::TooManyDecimals {
+            value: value.to_string(),
+            decimals,
+        });
+    }
+    let digits = format!(
+        "{}{:0<width$}",
+        integer,
+        fraction,
+        width = decimals as usize
+    );
+    digits.parse().map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_works() {
+        assert_eq!(format_units(1_500_000, 6), "1.5");
+        assert_eq!(format_units(1_000_000, 6), "1");
+        assert_eq!(format_units(1_000_001, 6), "1.000001");
+        assert_eq!(format_units(123, 6), "0.000123");
+        assert_eq!(format_units(0, 6), "0");
+        assert_eq!(format_units(120, 1), "12");
+        assert_eq!(format_units(1, 1), "0.1");
+    }
+
+    #[test]
+    fn format_boundaries() {
+        assert_eq!(format_units(0, 0), "0");
+        assert_eq!(format_units(1230, 0), "1230");
+        assert_eq!(format_units(1, 18), "0.000000000000000001");
+        assert_eq!(format_units(10u128.pow(18), 18), "1");
+        assert_eq!(
+            format_units(u128::MAX, 0),
+            "340282366920938463463374607431768211455"
+        );
+        assert_eq!(
+            format_units(u128::MAX, 18),
+            "340282366920938463463.374607431768211455"
+        );
+        assert_eq!(
+            format_units(u128::MAX, 39),
+            "0.340282366920938463463374607431768211455"
+        );
+        assert_eq!(
+            format_units(5, 40),
+            "0.0000000000000000000000000000000000000005"
+        );
+    }
+
+    #[test]
+    fn parse_works() {
+        assert_eq!(parse_units("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(parse_units("1", 6).unwrap(), 1_000_000);
+        assert_eq!(parse_units("0.000123", 6).unwrap(), 123);
+        assert_eq!(parse_units("0", 6).unwrap(), 0);
+        assert_eq!(parse_units("007.10", 2).unwrap(), 710);
+        // trailing zeros do not count as decimals
+        assert_eq!(parse_units("1.5000000", 6).unwrap(), 1_500_000);
+        assert_eq!(parse_units("12.000", 0).unwrap(), 12);
+    }
+
+    #[test]
+    fn parse_boundaries() {
+        assert_eq!(parse_units("1230", 0).unwrap(), 1230);
+        assert_eq!(parse_units("0.000000000000000001", 18).unwrap(), 1);
+        assert_eq!(parse_units("1", 18).unwrap(), 10u128.pow(18));
+        assert_eq!(
+            parse_units("340282366920938463463.374607431768211455", 18).unwrap(),
+            u128::MAX
+        );
+        match parse_units("340282366920938463463.374607431768211456", 18).unwrap_err() {
+            ContractError::InvalidAmount { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn parse_rejects_extra_decimals() {
+        match parse_units("1.0000001", 6).unwrap_err() {
+            ContractError::TooManyDecimals { value, decimals } => {
+                assert_eq!(value, "1.0000001");
+                assert_eq!(decimals, 6);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match parse_units("1.5", 0).unwrap_err() {
+            ContractError::TooManyDecimals { decimals: 0, .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        for value in &[
+            "", ".", ".5", "1.", "-1", "+1", "1.2.3", "1,5", " 1", "1e6", "abc",
+        ] {
+            match parse_units(value, 6).unwrap_err() {
+                ContractError::InvalidAmount { value: v } => assert_eq!(&v, value),
+                e => panic!("unexpected error for {:?}: {:?}", value, e),
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for &decimals in &[0u8, 1, 6, 9, 18] {
+            for &amount in &[0u128, 1, 9, 10, 1_000_000, 123_456_789, u128::MAX] {
+                let formatted = format_units(amount, decimals);
+                assert_eq!(parse_units(&formatted, decimals).unwrap(), amount);
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use cosmwasm_std::{from_slice, to_vec, StdResult, Storage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const KEY_CONTRACT_VERSION: &[u8] = b"contract_version";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractVersion {
+    /// The crate name of the contract, e.g. "cw-erc20"
+    pub contract: String,
+    /// The crate version of the contract, e.g. "0.10.0"
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+/// Reads the version stored by the contract.
+/// Returns `None` for instances created before versions were stored.
+pub fn read_contract_version(store: &dyn Storage) -> StdResult<Option<ContractVersion>> {
+    match store.get(KEY_CONTRACT_VERSION) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Stores the version of the code currently running
+pub fn write_contract_version(store: &mut dyn Storage) -> StdResult<()> {
+    let version = ContractVersion {
+        contract: CONTRACT_NAME.to_string(),
+        version: CONTRACT_VERSION.to_string(),
+    };
+    store.set(KEY_CONTRACT_VERSION, &to_vec(&version)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    #[test]
+    fn constants_match_cargo_metadata() {
+        let manifest = include_str!("../Cargo.toml");
+        assert!(manifest.contains(&format!("name = \"{}\"", CONTRACT_NAME)));
+        assert!(manifest.contains(&format!("version = \"{}\"", CONTRACT_VERSION)));
+    }
+
+    #[test]
+    fn read_contract_version_works() {
+        let mut store = MockStorage::new();
+        write_contract_version(&mut store).unwrap();
+        assert_eq!(
+            read_contract_version(&store).unwrap(),
+            Some(ContractVersion {
+                contract: "cw-erc20".to_string(),
+                version: CONTRACT_VERSION.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn read_contract_version_handles_legacy_layout() {
+        let store = MockStorage::new();
+        assert_eq!(read_contract_version(&store).unwrap(), None);
+    }
+}
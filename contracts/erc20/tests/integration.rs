@@ -0,0 +1,497 @@
+//! This integration test tries to run and call the generated wasm.
+//! It depends on a Wasm build being available, which you can create with `cargo wasm`.
+//! Then running `cargo integration-test` will validate we can properly call into that generated Wasm.
+
+use cosmwasm_std::{attr, from_binary, Addr, ContractResult, Response, Uint128};
+use cosmwasm_vm::testing::{
+    execute, instantiate, migrate, mock_env, mock_info, mock_instance, query, MockApi, MockQuerier,
+    MockStorage,
+};
+use cosmwasm_vm::{Instance, Storage};
+
+use cw_erc20::sim::TokenSim;
+use cw_erc20::{
+    allowance_key, AllowanceResponse, BalanceResponse, ContractVersion, ExecuteMsg, Expiration,
+    InitialBalance, InstantiateMsg, MigrateMsg, PausedResponse, QueryMsg, TokenInfoResponse,
+    VersionedExecuteMsg, CONTRACT_NAME, CONTRACT_VERSION, EXECUTE_MSG_VERSION,
+};
+
+// This line will test the output of cargo wasm
+static WASM: &[u8] = include_bytes!("../target/wasm32-unknown-unknown/release/cw_erc20.wasm");
+// You can uncomment this line instead to test productionified build from rust-optimizer
+// static WASM: &[u8] = include_bytes!("../contract.wasm");
+
+fn init_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        name: "Cash Token".to_string(),
+        symbol: "CASH".to_string(),
+        decimals: 9,
+        initial_balances: vec![InitialBalance {
+            address: "addr0000".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+        minter: None,
+        genesis: None,
+        mint_limit_per_window: None,
+        mint_window_blocks: 0,
+        mint_approval: None,
+        admins: vec![],
+        admin_delay: 0,
+        marketing: None,
+        restricted: false,
+    }
+}
+
+#[test]
+fn instantiate_transfer_and_query() {
+    let mut deps = mock_instance(WASM, &[]);
+
+    let res: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+    assert_eq!(0, res.messages.len());
+
+    let msg = ExecuteMsg::transfer("addr1111", 40u128);
+    let _: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let res = query(&mut deps, mock_env(), QueryMsg::balance("addr1111")).unwrap();
+    let balance: BalanceResponse = from_binary(&res).unwrap();
+    assert_eq!(balance.balance, Uint128::from(40u128));
+
+    // errors surface as strings through the VM
+    let msg = ExecuteMsg::transfer("addr1111", 61u128);
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg);
+    let err = res.unwrap_err();
+    assert!(
+        err.starts_with("Insufficient funds"),
+        "unexpected error: {}",
+        err
+    );
+
+    // context added in the contract is part of the error string
+    let msg = ExecuteMsg::transfer("x", 1u128);
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg);
+    let err = res.unwrap_err();
+    assert!(
+        err.starts_with("invalid recipient: ") && err.contains("human address too short"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn token_info_after_instantiate() {
+    let mut deps = mock_instance(WASM, &[]);
+    let msg = InstantiateMsg {
+        initial_balances: vec![
+            InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(100u128),
+            },
+            InitialBalance {
+                address: "addr1111".to_string(),
+                amount: Uint128::from(23u128),
+            },
+        ],
+        ..init_msg()
+    };
+    let _: Response = instantiate(&mut deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+    let res = query(&mut deps, mock_env(), QueryMsg::token_info()).unwrap();
+    let token_info: TokenInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        token_info,
+        TokenInfoResponse {
+            name: "Cash Token".to_string(),
+            symbol: "CASH".to_string(),
+            decimals: 9,
+            total_supply: Uint128::from(123u128),
+        }
+    );
+}
+
+#[test]
+fn execute_accepts_versioned_envelopes() {
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+
+    let msg = VersionedExecuteMsg::Envelope {
+        version: EXECUTE_MSG_VERSION,
+        msg: ExecuteMsg::transfer("addr1111", 40u128),
+    };
+    let _: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let res = query(&mut deps, mock_env(), QueryMsg::balance("addr1111")).unwrap();
+    let balance: BalanceResponse = from_binary(&res).unwrap();
+    assert_eq!(balance.balance, Uint128::from(40u128));
+
+    let msg = VersionedExecuteMsg::Envelope {
+        version: EXECUTE_MSG_VERSION + 1,
+        msg: ExecuteMsg::transfer("addr1111", 40u128),
+    };
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg);
+    let err = res.unwrap_err();
+    assert!(
+        err.contains("message version 2 not supported, max 1"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn burn_reduces_total_supply() {
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+
+    let msg = ExecuteMsg::burn(30u128);
+    let _: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let res = query(&mut deps, mock_env(), QueryMsg::token_info()).unwrap();
+    let token_info: TokenInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(token_info.total_supply, Uint128::from(70u128));
+    let res = query(&mut deps, mock_env(), QueryMsg::balance("addr0000")).unwrap();
+    let balance: BalanceResponse = from_binary(&res).unwrap();
+    assert_eq!(balance.balance, Uint128::from(70u128));
+
+    // burning more than the balance fails and leaves the supply untouched
+    let msg = ExecuteMsg::burn(71u128);
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg);
+    let err = res.unwrap_err();
+    assert!(
+        err.starts_with("Insufficient funds"),
+        "unexpected error: {}",
+        err
+    );
+    let res = query(&mut deps, mock_env(), QueryMsg::token_info()).unwrap();
+    let token_info: TokenInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(token_info.total_supply, Uint128::from(70u128));
+}
+
+#[test]
+fn approve_increase_and_decrease_allowance() {
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+
+    let script = vec![
+        (ExecuteMsg::approve("spender", 10u128), 10u128),
+        (ExecuteMsg::increase_allowance("spender", 5u128), 15),
+        (ExecuteMsg::decrease_allowance("spender", 7u128), 8),
+        // an exact decrease has to fit into the allowance, a plain one stops at zero
+        (
+            ExecuteMsg::DecreaseAllowance {
+                spender: "spender".to_string(),
+                amount: Uint128::from(8u128),
+                exact: true,
+                expires: None,
+            },
+            0,
+        ),
+        (ExecuteMsg::increase_allowance("spender", 2u128), 2),
+        (ExecuteMsg::decrease_allowance("spender", 9u128), 0),
+    ];
+    for (msg, expected) in script {
+        let _: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        let res = query(
+            &mut deps,
+            mock_env(),
+            QueryMsg::allowance("addr0000", "spender"),
+        )
+        .unwrap();
+        let allowance: AllowanceResponse = from_binary(&res).unwrap();
+        assert_eq!(allowance.allowance.u128(), expected);
+    }
+
+    let msg = ExecuteMsg::DecreaseAllowance {
+        spender: "spender".to_string(),
+        amount: Uint128::from(1u128),
+        exact: true,
+        expires: None,
+    };
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(
+        res.unwrap_err(),
+        "Insufficient allowance (allowance 0, required=1)"
+    );
+}
+
+#[test]
+fn allowance_expires() {
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+    let expires = mock_env().block.height + 10;
+    let msg = ExecuteMsg::Approve {
+        spender: "spender".to_string(),
+        amount: Uint128::from(30u128),
+        expires: Some(Expiration::AtHeight(expires)),
+    };
+    let _: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.height = expires - 1;
+    let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 10u128);
+    let _: Response = execute(&mut deps, env.clone(), mock_info("spender", &[]), msg).unwrap();
+    let msg = QueryMsg::allowance("addr0000", "spender");
+    let res = query(&mut deps, env, msg).unwrap();
+    let allowance: AllowanceResponse = from_binary(&res).unwrap();
+    assert_eq!(allowance.allowance, Uint128::from(20u128));
+    assert_eq!(allowance.expires, Expiration::AtHeight(expires));
+
+    let mut env = mock_env();
+    env.block.height = expires;
+    let msg = ExecuteMsg::transfer_from("addr0000", "addr1111", 10u128);
+    let res: ContractResult<Response> =
+        execute(&mut deps, env.clone(), mock_info("spender", &[]), msg);
+    assert_eq!(
+        res.unwrap_err(),
+        "Insufficient allowance (allowance 0, required=10)"
+    );
+    let msg = QueryMsg::allowance("addr0000", "spender");
+    let res = query(&mut deps, env, msg).unwrap();
+    let allowance: AllowanceResponse = from_binary(&res).unwrap();
+    assert_eq!(allowance.allowance, Uint128::zero());
+    assert_eq!(allowance.expires, Expiration::Never {});
+}
+
+/// The raw allowance entry, which expired allowances keep until they are swept
+fn stored_allowance(
+    deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
+    owner: &str,
+    spender: &str,
+) -> Option<Vec<u8>> {
+    let key = allowance_key(&Addr::unchecked(owner), &Addr::unchecked(spender));
+    deps.with_storage(|storage| Ok(storage.get(&key).0.unwrap()))
+        .unwrap()
+}
+
+#[test]
+fn sweep_clears_expired_allowances() {
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+    let height = mock_env().block.height;
+    let spenders: Vec<String> = (0..5).map(|i| format!("spender{}", i)).collect();
+    for (i, spender) in spenders.iter().enumerate() {
+        let msg = ExecuteMsg::Approve {
+            spender: spender.clone(),
+            amount: Uint128::from(10u128),
+            expires: Some(Expiration::AtHeight(height + 1 + i as u64)),
+        };
+        let _: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    }
+    let msg = ExecuteMsg::approve("keeper", 10u128);
+    let _: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.height = height + 5;
+    let mut swept = spenders.clone();
+    swept.push("keeper".to_string());
+    let msg = ExecuteMsg::sweep_expired_allowances("addr0000".to_string(), swept);
+    let res: Response = execute(&mut deps, env, mock_info("anyone", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep_expired_allowances"),
+            attr("owner", "addr0000"),
+            attr("swept", "5"),
+        ]
+    );
+
+    for spender in &spenders {
+        assert_eq!(stored_allowance(&mut deps, "addr0000", spender), None);
+    }
+    assert!(stored_allowance(&mut deps, "addr0000", "keeper").is_some());
+}
+
+#[test]
+fn batch_transfer_credits_all_or_nothing() {
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+
+    // addr2222 holds nothing yet and addr3333 is credited nothing at all
+    let msg =
+        ExecuteMsg::batch_transfer(&[("addr1111", 30u128), ("addr2222", 20), ("addr3333", 0)]);
+    let res: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    let recipients = res
+        .attributes
+        .iter()
+        .find(|a| a.key == "recipients")
+        .unwrap();
+    assert_eq!(recipients.value, "3");
+    for (address, expected) in &[
+        ("addr0000", 50u128),
+        ("addr1111", 30),
+        ("addr2222", 20),
+        ("addr3333", 0),
+    ] {
+        let res = query(&mut deps, mock_env(), QueryMsg::balance(*address)).unwrap();
+        let balance: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(balance.balance, Uint128::from(*expected));
+    }
+
+    // one failing transfer fails the whole batch
+    let msg = ExecuteMsg::batch_transfer(&[("addr1111", 30u128), ("addr2222", 30)]);
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(
+        res.unwrap_err(),
+        "Insufficient funds (balance 50, required=60)"
+    );
+
+    let msg = ExecuteMsg::batch_transfer(&vec![("addr1111", 1u128); 51]);
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(
+        res.unwrap_err(),
+        "Batch transfer must not contain more than 50 recipients"
+    );
+    let res = query(&mut deps, mock_env(), QueryMsg::balance("addr0000")).unwrap();
+    let balance: BalanceResponse = from_binary(&res).unwrap();
+    assert_eq!(balance.balance, Uint128::from(50u128));
+}
+
+#[test]
+fn pause_stops_transfers_until_unpaused() {
+    let mut deps = mock_instance(WASM, &[]);
+    let msg = InstantiateMsg {
+        admins: vec!["admin".to_string()],
+        ..init_msg()
+    };
+    let _: Response = instantiate(&mut deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+    let _: Response = execute(
+        &mut deps,
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::pause(),
+    )
+    .unwrap();
+    let res = query(&mut deps, mock_env(), QueryMsg::paused()).unwrap();
+    let paused: PausedResponse = from_binary(&res).unwrap();
+    assert!(paused.paused);
+
+    let msg = ExecuteMsg::transfer("addr1111", 10u128);
+    let res: ContractResult<Response> = execute(
+        &mut deps,
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    assert_eq!(res.unwrap_err(), "Contract is paused");
+    // queries keep working
+    let res = query(&mut deps, mock_env(), QueryMsg::balance("addr0000")).unwrap();
+    let balance: BalanceResponse = from_binary(&res).unwrap();
+    assert_eq!(balance.balance, Uint128::from(100u128));
+
+    let _: Response = execute(
+        &mut deps,
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::unpause(),
+    )
+    .unwrap();
+    let _: Response = execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    let res = query(&mut deps, mock_env(), QueryMsg::balance("addr1111")).unwrap();
+    let balance: BalanceResponse = from_binary(&res).unwrap();
+    assert_eq!(balance.balance, Uint128::from(10u128));
+}
+
+#[test]
+fn only_minter_mints() {
+    let mut deps = mock_instance(WASM, &[]);
+    let msg = InstantiateMsg {
+        minter: Some("minter".to_string()),
+        ..init_msg()
+    };
+    let _: Response = instantiate(&mut deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::mint("addr1111", 50u128);
+    let _: Response = execute(&mut deps, mock_env(), mock_info("minter", &[]), msg).unwrap();
+    let res = query(&mut deps, mock_env(), QueryMsg::token_info()).unwrap();
+    let token_info: TokenInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(token_info.total_supply, Uint128::from(150u128));
+    let res = query(&mut deps, mock_env(), QueryMsg::balance("addr1111")).unwrap();
+    let balance: BalanceResponse = from_binary(&res).unwrap();
+    assert_eq!(balance.balance, Uint128::from(50u128));
+
+    let msg = ExecuteMsg::mint("addr1111", 50u128);
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(res.unwrap_err(), "Unauthorized");
+
+    // without a minter nobody can mint
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+    for sender in &["creator", "minter"] {
+        let msg = ExecuteMsg::mint("addr1111", 50u128);
+        let res: ContractResult<Response> =
+            execute(&mut deps, mock_env(), mock_info(sender, &[]), msg);
+        assert_eq!(res.unwrap_err(), "No admin set");
+    }
+}
+
+#[test]
+fn simulator_matches_vm() {
+    let script = vec![
+        ("addr0000", ExecuteMsg::transfer("addr1111", 30u128)),
+        ("addr0000", ExecuteMsg::approve("addr2222", 50u128)),
+        (
+            "addr2222",
+            ExecuteMsg::transfer_from("addr0000", "addr3333", 20u128),
+        ),
+        ("addr1111", ExecuteMsg::burn(5u128)),
+        ("addr1111", ExecuteMsg::transfer("addr0000", 26u128)),
+        (
+            "addr2222",
+            ExecuteMsg::transfer_from("addr0000", "addr2222", 31u128),
+        ),
+    ];
+    let addresses = ["addr0000", "addr1111", "addr2222", "addr3333"];
+
+    let mut sim = TokenSim::new(init_msg()).unwrap();
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+
+    for (sender, msg) in script {
+        let sim_ok = sim.execute(sender, msg.clone()).is_ok();
+        let res: ContractResult<Response> =
+            execute(&mut deps, mock_env(), mock_info(sender, &[]), msg);
+        assert_eq!(sim_ok, res.is_ok(), "results differ for {}", sender);
+    }
+
+    for address in &addresses {
+        let res = query(&mut deps, mock_env(), QueryMsg::balance(*address)).unwrap();
+        let balance: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(sim.balance(address), balance.balance.u128(), "{}", address);
+    }
+    assert_eq!(sim.allowance("addr0000", "addr2222"), 30);
+}
+
+#[test]
+fn migrate_is_exported() {
+    let mut deps = mock_instance(WASM, &[]);
+    let _: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), init_msg()).unwrap();
+
+    let _: Response = migrate(&mut deps, mock_env(), MigrateMsg {}).unwrap();
+
+    let res = query(&mut deps, mock_env(), QueryMsg::contract_version()).unwrap();
+    let version: ContractVersion = from_binary(&res).unwrap();
+    assert_eq!(
+        version,
+        ContractVersion {
+            contract: CONTRACT_NAME.to_string(),
+            version: CONTRACT_VERSION.to_string(),
+        }
+    );
+}
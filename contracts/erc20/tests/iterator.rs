@@ -0,0 +1,51 @@
+//! Covers the queries behind the `iterator` feature. The VM used by `integration.rs` is
+//! built without iteration support, so these call the entry point functions directly.
+//! Run with `cargo test --features iterator --test iterator`.
+#![cfg(feature = "iterator")]
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, Uint128};
+
+use cw_erc20::contract::{instantiate, query};
+use cw_erc20::{AllAccountsResponse, InitialBalance, InstantiateMsg, QueryMsg};
+
+#[test]
+fn all_accounts_pages_through_holders() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        name: "Cash Token".to_string(),
+        symbol: "CASH".to_string(),
+        decimals: 9,
+        initial_balances: ["addr0000", "addr1111", "addr2222"]
+            .iter()
+            .map(|address| InitialBalance {
+                address: address.to_string(),
+                amount: Uint128::from(10u128),
+            })
+            .collect(),
+        minter: None,
+        genesis: None,
+        mint_limit_per_window: None,
+        mint_window_blocks: 0,
+        mint_approval: None,
+        admins: vec![],
+        admin_delay: 0,
+        marketing: None,
+        restricted: false,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+    let mut accounts = vec![];
+    let mut start_after = None;
+    loop {
+        let msg = QueryMsg::all_accounts(start_after.clone(), Some(2));
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let page: AllAccountsResponse = from_binary(&res).unwrap();
+        match page.accounts.last() {
+            Some(last) => start_after = Some(last.clone()),
+            None => break,
+        }
+        accounts.extend(page.accounts);
+    }
+    assert_eq!(accounts, vec!["addr0000", "addr1111", "addr2222"]);
+}
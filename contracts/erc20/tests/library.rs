@@ -0,0 +1,54 @@
+//! Uses the contract the way an embedding contract would, calling the entry point
+//! functions directly. Run with `cargo test --features library` to check the crate
+//! works as a dependency without its wasm exports.
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, Uint128};
+
+use cw_erc20::contract::{execute, instantiate, query};
+use cw_erc20::{
+    BalanceResponse, ContractError, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg,
+};
+
+fn balance(deps: cosmwasm_std::Deps, address: &str) -> Uint128 {
+    let res = query(deps, mock_env(), QueryMsg::balance(address)).unwrap();
+    let res: BalanceResponse = from_binary(&res).unwrap();
+    res.balance
+}
+
+#[test]
+fn entry_points_callable_as_functions() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        name: "Cash Token".to_string(),
+        symbol: "CASH".to_string(),
+        decimals: 9,
+        initial_balances: vec![InitialBalance {
+            address: "addr0000".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+        minter: None,
+        genesis: None,
+        mint_limit_per_window: None,
+        mint_window_blocks: 0,
+        mint_approval: None,
+        admins: vec![],
+        admin_delay: 0,
+        marketing: None,
+        restricted: false,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+    let info = mock_info("addr0000", &[]);
+    let transfer = ExecuteMsg::transfer("addr1111", 40u128);
+    execute(deps.as_mut(), mock_env(), info.clone(), transfer).unwrap();
+    assert_eq!(balance(deps.as_ref(), "addr0000"), Uint128::from(60u128));
+    assert_eq!(balance(deps.as_ref(), "addr1111"), Uint128::from(40u128));
+
+    let transfer = ExecuteMsg::transfer("addr1111", 61u128);
+    match execute(deps.as_mut(), mock_env(), info, transfer) {
+        Ok(_) => panic!("expected error"),
+        Err(ContractError::InsufficientFunds { .. }) => {}
+        Err(e) => panic!("unexpected error: {:?}", e),
+    }
+}
@@ -16,7 +16,8 @@ pub fn instantiate(
     let state = State {
         arbiter: deps.api.addr_validate(&msg.arbiter)?,
         recipient: deps.api.addr_validate(&msg.recipient)?,
-        source: info.sender,
+        source: deps.api.addr_validate(&msg.source)?,
+        init_balance: info.funds,
         end_height: msg.end_height,
         end_time: msg.end_time,
     };
@@ -41,7 +42,13 @@ pub fn execute(
     let state = config_read(deps.storage).load()?;
     match msg {
         ExecuteMsg::Approve { quantity } => try_approve(deps, env, state, info, quantity),
-        ExecuteMsg::Refund {} => try_refund(deps, env, info, state),
+        ExecuteMsg::Refund {} => {
+            if info.sender != state.source && info.sender != state.arbiter {
+                return Err(ContractError::Unauthorized {});
+            }
+            try_refund(deps, env, state, "refund")
+        }
+        ExecuteMsg::Timeout {} => try_refund(deps, env, state, "timeout"),
     }
 }
 
@@ -80,10 +87,10 @@ fn try_approve(
 fn try_refund(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
     state: State,
+    action: &str,
 ) -> Result<Response, ContractError> {
-    // anyone can try to refund, as long as the contract is expired
+    // the funds can only go back once the contract is expired
     if !state.is_expired(&env) {
         return Err(ContractError::NotExpired {});
     }
@@ -91,7 +98,7 @@ fn try_refund(
     // Querier guarantees to returns up-to-date data, including funds sent in this handle message
     // https://github.com/CosmWasm/wasmd/blob/master/x/wasm/internal/keeper/keeper.go#L185-L192
     let balance = deps.querier.query_all_balances(&env.contract.address)?;
-    Ok(send_tokens(state.source, balance, "refund"))
+    Ok(send_tokens(state.source, balance, action))
 }
 
 // this is a helper to move the tokens, so the business logic is easy to read
@@ -131,6 +138,7 @@ mod tests {
         InstantiateMsg {
             arbiter: String::from("verifies"),
             recipient: String::from("benefits"),
+            source: String::from("creator"),
             end_height: Some(height),
             end_time: None,
         }
@@ -144,7 +152,8 @@ mod tests {
         let mut env = mock_env();
         env.block.height = 876;
         env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("creator", &coins(1000, "earth"));
+        // the funder is taken from the message, not from the sender
+        let info = mock_info("deployer", &coins(1000, "earth"));
 
         let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
         assert_eq!(0, res.messages.len());
@@ -157,6 +166,7 @@ mod tests {
                 arbiter: Addr::unchecked("verifies"),
                 recipient: Addr::unchecked("benefits"),
                 source: Addr::unchecked("creator"),
+                init_balance: coins(1000, "earth"),
                 end_height: Some(1000),
                 end_time: None,
             }
@@ -190,6 +200,7 @@ mod tests {
         let msg = InstantiateMsg {
             arbiter: arbiter.clone().into(),
             recipient: recipient.into(),
+            source: creator.to_string(),
             end_height: None,
             end_time: None,
         };
@@ -305,7 +316,7 @@ mod tests {
         let mut env = mock_env();
         env.block.height = 800;
         env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("anybody", &[]);
+        let info = mock_info("creator", &[]);
         let execute_res = execute(deps.as_mut(), env, info, msg.clone());
         match execute_res.unwrap_err() {
             ContractError::NotExpired { .. } => {}
@@ -317,27 +328,120 @@ mod tests {
         let mut env = mock_env();
         env.block.height = 1000;
         env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("anybody", &[]);
+        let info = mock_info("creator", &[]);
         let execute_res = execute(deps.as_mut(), env, info, msg.clone());
         match execute_res.unwrap_err() {
             ContractError::NotExpired { .. } => {}
             e => panic!("unexpected error: {:?}", e),
         }
 
-        // anyone can release after expiration
+        // only the funder or the arbiter can refund after expiration
         let mut env = mock_env();
         env.block.height = 1001;
         env.block.time = Timestamp::from_seconds(0);
         let info = mock_info("anybody", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, msg.clone()).unwrap();
-        assert_eq!(1, execute_res.messages.len());
-        let msg = execute_res.messages.get(0).expect("no message");
+        let execute_res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        match execute_res.unwrap_err() {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        for sender in &["creator", "verifies"] {
+            let info = mock_info(sender, &[]);
+            let execute_res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+            assert_eq!(1, execute_res.messages.len());
+            let msg = execute_res.messages.get(0).expect("no message");
+            assert_eq!(
+                msg,
+                &CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(1000, "earth"),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn handle_timeout() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = init_msg_expire_by_height(1000);
+        let mut env = mock_env();
+        env.block.height = 876;
+        env.block.time = Timestamp::from_seconds(0);
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let contract_addr = env.clone().contract.address;
+        instantiate(deps.as_mut(), env, info, msg).unwrap();
+        deps.querier
+            .update_balance(&contract_addr, coins(1000, "earth"));
+
+        // cannot time out before the deadline
+        let mut env = mock_env();
+        env.block.height = 1000;
+        env.block.time = Timestamp::from_seconds(0);
+        let info = mock_info("anybody", &[]);
+        let execute_res = execute(deps.as_mut(), env, info, ExecuteMsg::Timeout {});
+        match execute_res.unwrap_err() {
+            ContractError::NotExpired { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // anyone can trigger the refund to the funder after it
+        let mut env = mock_env();
+        env.block.height = 1001;
+        env.block.time = Timestamp::from_seconds(0);
+        let info = mock_info("anybody", &[]);
+        let execute_res = execute(deps.as_mut(), env, info, ExecuteMsg::Timeout {}).unwrap();
         assert_eq!(
-            msg,
-            &CosmosMsg::Bank(BankMsg::Send {
+            execute_res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
                 to_address: "creator".into(),
                 amount: coins(1000, "earth"),
-            })
+            })]
+        );
+        assert_eq!(execute_res.attributes[0], attr("action", "timeout"));
+    }
+
+    #[test]
+    fn never_expires_without_end() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            arbiter: String::from("verifies"),
+            recipient: String::from("benefits"),
+            source: String::from("creator"),
+            end_height: None,
+            end_time: None,
+        };
+        let mut env = mock_env();
+        env.block.height = 876;
+        env.block.time = Timestamp::from_seconds(0);
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let contract_addr = env.clone().contract.address;
+        instantiate(deps.as_mut(), env, info, msg).unwrap();
+        deps.querier
+            .update_balance(&contract_addr, coins(1000, "earth"));
+
+        // no refund, no matter how late
+        let mut env = mock_env();
+        env.block.height = u64::MAX;
+        env.block.time = Timestamp::from_seconds(u64::MAX / 1_000_000_000);
+        let info = mock_info("creator", &[]);
+        let execute_res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Refund {});
+        match execute_res.unwrap_err() {
+            ContractError::NotExpired { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // while the arbiter can still release
+        let info = mock_info("verifies", &[]);
+        let msg = ExecuteMsg::Approve { quantity: None };
+        let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            execute_res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "benefits".into(),
+                amount: coins(1000, "earth"),
+            })]
         );
     }
 }
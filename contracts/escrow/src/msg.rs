@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub struct InstantiateMsg {
     pub arbiter: String,
     pub recipient: String,
+    /// The original funder, who gets the tokens back once the escrow is expired
+    pub source: String,
     /// When end height set and block height exceeds this value, the escrow is expired.
     /// Once an escrow is expired, it can be returned to the original funder (via "refund").
     pub end_height: Option<u64>,
@@ -22,7 +24,12 @@ pub enum ExecuteMsg {
         // release some coins - if quantity is None, release all coins in balance
         quantity: Option<Vec<Coin>>,
     },
+    /// Returns the funds to the original funder once the escrow is expired. Only the funder or
+    /// the arbiter can do this.
     Refund {},
+    /// Like `Refund`, but anyone can trigger it, e.g. a bot watching the deadline.
+    /// The response reports the action "timeout".
+    Timeout {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
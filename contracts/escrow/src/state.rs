@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Env, Storage};
+use cosmwasm_std::{Addr, Coin, Env, Storage};
 use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,8 @@ pub struct State {
     pub arbiter: Addr,
     pub recipient: Addr,
     pub source: Addr,
+    /// The tokens sent along with the instantiation
+    pub init_balance: Vec<Coin>,
     pub end_height: Option<u64>,
     pub end_time: Option<u64>,
 }
@@ -1,31 +1,20 @@
 //! This integration test tries to run and call the generated wasm.
 //! It depends on a Wasm build being available, which you can create with `cargo wasm`.
 //! Then running `cargo integration-test` will validate we can properly call into that generated Wasm.
-//!
-//! You can easily convert unit tests to integration tests as follows:
-//! 1. Copy them over verbatim
-//! 2. Then change
-//!      let mut deps = mock_dependencies(20, &[]);
-//!    to
-//!      let mut deps = mock_instance(WASM, &[]);
-//! 3. If you access raw storage, where ever you see something like:
-//!      deps.storage.get(CONFIG_KEY).expect("no data stored");
-//!    replace it with:
-//!      deps.with_storage(|store| {
-//!          let data = store.get(CONFIG_KEY).expect("no data stored");
-//!          //...
-//!      });
-//! 4. Anywhere you see query(&deps, ...) you must replace it with query(&mut deps, ...)
 
 use cosmwasm_std::{
-    coins, Addr, BlockInfo, Coin, ContractInfo, Env, MessageInfo, Response, Timestamp,
+    coins, Addr, BankMsg, BlockInfo, Coin, ContractInfo, ContractResult, CosmosMsg, Env,
+    MessageInfo, Response, Timestamp,
 };
 use cosmwasm_storage::to_length_prefixed;
-use cosmwasm_vm::testing::{instantiate, mock_info, mock_instance};
-use cosmwasm_vm::{from_slice, Storage};
+use cosmwasm_vm::testing::{
+    execute, instantiate, mock_info, mock_instance, mock_instance_with_balances, MockApi,
+    MockQuerier, MockStorage,
+};
+use cosmwasm_vm::{from_slice, Instance, Storage};
 
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
-use cw_escrow::msg::InstantiateMsg;
+use cw_escrow::msg::{ExecuteMsg, InstantiateMsg};
 use cw_escrow::state::State;
 
 // This line will test the output of cargo wasm
@@ -37,6 +26,7 @@ fn init_msg_expire_by_height(height: u64) -> InstantiateMsg {
     InstantiateMsg {
         arbiter: String::from("verifies"),
         recipient: String::from("benefits"),
+        source: String::from("creator"),
         end_height: Some(height),
         end_time: None,
     }
@@ -54,7 +44,7 @@ fn mock_env_info_height(signer: &str, sent: &[Coin], height: u64, time: u64) ->
         },
     };
     let info = mock_info(signer, sent);
-    return (env, info);
+    (env, info)
 }
 
 #[test]
@@ -76,6 +66,7 @@ fn proper_initialization() {
                 arbiter: Addr::unchecked("verifies"),
                 recipient: Addr::unchecked("benefits"),
                 source: Addr::unchecked("creator"),
+                init_balance: coins(1000, "earth"),
                 end_height: Some(1000),
                 end_time: None,
             }
@@ -84,3 +75,72 @@ fn proper_initialization() {
     })
     .unwrap();
 }
+
+/// Instantiates an escrow expiring at height 1000 which holds 1000 earth
+fn funded_instance() -> Instance<MockApi, MockStorage, MockQuerier> {
+    let mut deps =
+        mock_instance_with_balances(WASM, &[(MOCK_CONTRACT_ADDR, &coins(1000, "earth"))]);
+    let msg = init_msg_expire_by_height(1000);
+    let (env, info) = mock_env_info_height("creator", &coins(1000, "earth"), 876, 0);
+    let _res: Response = instantiate(&mut deps, env, info, msg).unwrap();
+    deps
+}
+
+#[test]
+fn approve_releases_funds_to_recipient() {
+    let mut deps = funded_instance();
+
+    // only the arbiter can approve
+    let (env, info) = mock_env_info_height("anybody", &[], 900, 0);
+    let res: ContractResult<Response> =
+        execute(&mut deps, env, info, ExecuteMsg::Approve { quantity: None });
+    assert!(res.unwrap_err().starts_with("Unauthorized"));
+
+    let (env, info) = mock_env_info_height("verifies", &[], 900, 0);
+    let res: Response =
+        execute(&mut deps, env, info, ExecuteMsg::Approve { quantity: None }).unwrap();
+    assert_eq!(1, res.messages.len());
+    assert_eq!(
+        res.messages[0],
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("benefits"),
+            amount: coins(1000, "earth"),
+        })
+    );
+}
+
+#[test]
+fn cannot_approve_expired() {
+    let mut deps = funded_instance();
+
+    let (env, info) = mock_env_info_height("verifies", &[], 1001, 0);
+    let res: ContractResult<Response> =
+        execute(&mut deps, env, info, ExecuteMsg::Approve { quantity: None });
+    assert!(res.unwrap_err().starts_with("Escrow expired"));
+}
+
+#[test]
+fn refund_returns_funds_to_source() {
+    let mut deps = funded_instance();
+
+    // not yet expired
+    let (env, info) = mock_env_info_height("creator", &[], 900, 0);
+    let res: ContractResult<Response> = execute(&mut deps, env, info, ExecuteMsg::Refund {});
+    assert!(res.unwrap_err().starts_with("Escrow not expired"));
+
+    // only the funder or the arbiter can refund
+    let (env, info) = mock_env_info_height("anybody", &[], 1001, 0);
+    let res: ContractResult<Response> = execute(&mut deps, env, info, ExecuteMsg::Refund {});
+    assert!(res.unwrap_err().starts_with("Unauthorized"));
+
+    let (env, info) = mock_env_info_height("creator", &[], 1001, 0);
+    let res: Response = execute(&mut deps, env, info, ExecuteMsg::Refund {}).unwrap();
+    assert_eq!(1, res.messages.len());
+    assert_eq!(
+        res.messages[0],
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("creator"),
+            amount: coins(1000, "earth"),
+        })
+    );
+}
@@ -0,0 +1,372 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128,
+};
+use cw_erc20::{BalanceResponse, ReceiveMsg};
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, NextDripResponse, QueryMsg};
+use crate::state::{config, config_read, last_drips, last_drips_read, Config, ADMIN};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.drip_amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        drip_amount: msg.drip_amount,
+        cooldown_blocks: msg.cooldown_blocks,
+        paused: false,
+    };
+    config(deps.storage).save(&state)?;
+    let admin = deps.api.addr_validate(&msg.admin)?;
+    ADMIN.save(deps.storage, Some(&admin))?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(receive) => try_receive(deps, info, receive),
+        ExecuteMsg::Drip {} => try_drip(deps, env, info),
+        ExecuteMsg::SetDripAmount { amount } => try_set_drip_amount(deps, info, amount),
+        ExecuteMsg::Pause {} => try_set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => try_set_paused(deps, info, false),
+    }
+}
+
+/// Receive
+///
+/// Accepts tokens sent to the faucet with the erc20 `Send` message.
+///
+/// @param receive the `ReceiveMsg` forwarded by the token
+fn try_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    receive: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if info.sender != state.token {
+        return Err(ContractError::WrongToken {
+            token: state.token.to_string(),
+        });
+    }
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "fund"),
+            attr("funder", receive.sender),
+            attr("amount", receive.amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Drip
+///
+/// Sends `drip_amount` tokens to the signer if the faucet is running, holds enough tokens and
+/// the signer's cooldown is over.
+fn try_drip(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    let key = info.sender.as_str().as_bytes();
+    if let Some(last_drip) = last_drips_read(deps.storage).may_load(key)? {
+        let next_height = last_drip + state.cooldown_blocks;
+        if env.block.height < next_height {
+            return Err(ContractError::TooSoon { next_height });
+        }
+    }
+
+    let balance: BalanceResponse = deps.querier.query_wasm_smart(
+        &state.token,
+        &cw_erc20::QueryMsg::balance(env.contract.address.as_str()),
+    )?;
+    if balance.balance < state.drip_amount {
+        return Err(ContractError::Empty {
+            balance: balance.balance,
+            drip_amount: state.drip_amount,
+        });
+    }
+    last_drips(deps.storage).save(key, &env.block.height)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), state.drip_amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.token)?],
+        attributes: vec![
+            attr("action", "drip"),
+            attr("recipient", info.sender),
+            attr("amount", state.drip_amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Set drip amount
+///
+/// @param amount the tokens sent by every following drip
+fn try_set_drip_amount(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.storage, &info.sender)?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    config(deps.storage).update(|mut state| -> StdResult<_> {
+        state.drip_amount = amount;
+        Ok(state)
+    })?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "set_drip_amount"), attr("amount", amount)],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Pause and unpause
+///
+/// @param paused whether drips are stopped
+fn try_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.storage, &info.sender)?;
+    config(deps.storage).update(|mut state| -> StdResult<_> {
+        state.paused = paused;
+        Ok(state)
+    })?;
+
+    let action = if paused { "pause" } else { "unpause" };
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", action)],
+        data: None,
+    };
+    Ok(res)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => {
+            let state = config_read(deps.storage).load()?;
+            to_binary(&ConfigResponse {
+                token: state.token.to_string(),
+                admin: ADMIN.load(deps.storage)?.map(|admin| admin.to_string()),
+                drip_amount: state.drip_amount,
+                cooldown_blocks: state.cooldown_blocks,
+                paused: state.paused,
+            })
+        }
+        QueryMsg::NextDrip { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let state = config_read(deps.storage).load()?;
+            let height =
+                match last_drips_read(deps.storage).may_load(address.as_str().as_bytes())? {
+                    Some(last_drip) => last_drip + state.cooldown_blocks,
+                    None => env.block.height,
+                };
+            to_binary(&NextDripResponse { height })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{
+        from_binary, from_slice, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+        QueryRequest, SystemError, SystemResult, WasmQuery,
+    };
+    use cw_erc20::admin::AdminError;
+
+    /// Answers balance queries for the faucet's address at "token"
+    #[derive(Default)]
+    struct TokenQuerier {
+        balance: u128,
+    }
+
+    impl Querier for TokenQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            match request {
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                    if contract_addr == "token" =>
+                {
+                    assert_eq!(
+                        from_binary::<cw_erc20::QueryMsg>(&msg).unwrap(),
+                        cw_erc20::QueryMsg::balance(MOCK_CONTRACT_ADDR)
+                    );
+                    let res = to_binary(&BalanceResponse {
+                        balance: Uint128::from(self.balance),
+                    });
+                    SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "non-token".to_string(),
+                }),
+            }
+        }
+    }
+
+    fn setup(balance: u128) -> OwnedDeps<MockStorage, MockApi, TokenQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: TokenQuerier { balance },
+        };
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            admin: "admin".to_string(),
+            drip_amount: Uint128::from(10u128),
+            cooldown_blocks: 100,
+        };
+        instantiate(deps.as_mut(), env_at(1000), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn next_drip(deps: Deps, address: &str) -> u64 {
+        let msg = QueryMsg::NextDrip {
+            address: address.to_string(),
+        };
+        let res: NextDripResponse = from_binary(&query(deps, env_at(1000), msg).unwrap()).unwrap();
+        res.height
+    }
+
+    #[test]
+    fn accepts_funds_from_token_only() {
+        let mut deps = setup(0);
+        let receive = ReceiveMsg {
+            sender: "funder".to_string(),
+            amount: Uint128::from(500u128),
+            msg: None,
+        };
+        let msg = ExecuteMsg::Receive(receive);
+        match run(deps.as_mut(), 1000, "other", msg.clone()).unwrap_err() {
+            ContractError::WrongToken { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = run(deps.as_mut(), 1000, "token", msg).unwrap();
+        assert_eq!(res.attributes[1], attr("funder", "funder"));
+    }
+
+    #[test]
+    fn drips_once_per_cooldown() {
+        let mut deps = setup(1000);
+        assert_eq!(next_drip(deps.as_ref(), "alice"), 1000);
+
+        let res = run(deps.as_mut(), 1000, "alice", ExecuteMsg::Drip {}).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("alice", 10u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("token").unwrap()]
+        );
+        assert_eq!(next_drip(deps.as_ref(), "alice"), 1100);
+
+        match run(deps.as_mut(), 1099, "alice", ExecuteMsg::Drip {}).unwrap_err() {
+            ContractError::TooSoon { next_height: 1100 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // the cooldown is per address
+        run(deps.as_mut(), 1099, "bob", ExecuteMsg::Drip {}).unwrap();
+        run(deps.as_mut(), 1100, "alice", ExecuteMsg::Drip {}).unwrap();
+    }
+
+    #[test]
+    fn fails_when_empty() {
+        let mut deps = setup(9);
+        match run(deps.as_mut(), 1000, "alice", ExecuteMsg::Drip {}).unwrap_err() {
+            ContractError::Empty {
+                balance,
+                drip_amount,
+            } => {
+                assert_eq!(balance.u128(), 9);
+                assert_eq!(drip_amount.u128(), 10);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // failed drips do not start the cooldown
+        deps.querier.balance = 10;
+        run(deps.as_mut(), 1001, "alice", ExecuteMsg::Drip {}).unwrap();
+    }
+
+    #[test]
+    fn admin_controls_faucet() {
+        let mut deps = setup(1000);
+        let set_amount = ExecuteMsg::SetDripAmount {
+            amount: Uint128::from(25u128),
+        };
+        for msg in &[
+            set_amount.clone(),
+            ExecuteMsg::Pause {},
+            ExecuteMsg::Unpause {},
+        ] {
+            match run(deps.as_mut(), 1000, "alice", msg.clone()).unwrap_err() {
+                ContractError::Admin(AdminError::Unauthorized {}) => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        let zero = ExecuteMsg::SetDripAmount {
+            amount: Uint128::zero(),
+        };
+        match run(deps.as_mut(), 1000, "admin", zero).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        run(deps.as_mut(), 1000, "admin", set_amount).unwrap();
+        run(deps.as_mut(), 1000, "admin", ExecuteMsg::Pause {}).unwrap();
+        let res: ConfigResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(res.drip_amount.u128(), 25);
+        assert!(res.paused);
+        match run(deps.as_mut(), 1000, "alice", ExecuteMsg::Drip {}).unwrap_err() {
+            ContractError::Paused {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        run(deps.as_mut(), 1001, "admin", ExecuteMsg::Unpause {}).unwrap();
+        let res = run(deps.as_mut(), 1001, "alice", ExecuteMsg::Drip {}).unwrap();
+        assert_eq!(res.attributes[2], attr("amount", "25"));
+    }
+}
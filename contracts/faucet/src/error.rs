@@ -0,0 +1,30 @@
+use cosmwasm_std::{StdError, Uint128};
+use cw_erc20::admin::AdminError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Only {token} tokens are accepted")]
+    WrongToken { token: String },
+
+    #[error("Faucet is paused")]
+    Paused {},
+
+    #[error("Next drip possible at height {next_height}")]
+    TooSoon { next_height: u64 },
+
+    #[error("Faucet is empty (balance {balance}, drip amount {drip_amount})")]
+    Empty {
+        balance: Uint128,
+        drip_amount: Uint128,
+    },
+}
@@ -0,0 +1,52 @@
+use cosmwasm_std::Uint128;
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub token: String,
+    pub admin: String,
+    pub drip_amount: Uint128,
+    pub cooldown_blocks: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Funds the faucet with tokens sent by the erc20 `Send` message
+    Receive(ReceiveMsg),
+    /// Sends `drip_amount` tokens to the signer, at most once per cooldown
+    Drip {},
+    /// Changes the tokens sent by every drip. Only the admin can do this.
+    SetDripAmount {
+        amount: Uint128,
+    },
+    /// Stops all drips until `Unpause`. Only the admin can do this.
+    Pause {},
+    Unpause {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Height from which `address` can drip again
+    NextDrip {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub token: String,
+    pub admin: Option<String>,
+    pub drip_amount: Uint128,
+    pub cooldown_blocks: u64,
+    pub paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NextDripResponse {
+    pub height: u64,
+}
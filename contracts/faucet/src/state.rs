@@ -0,0 +1,41 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use cw_erc20::admin::AdminStore;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static LAST_DRIPS_KEY: &[u8] = b"last_drips";
+
+/// Can change the drip amount and pause the faucet
+pub const ADMIN: AdminStore = AdminStore::new(b"admin", b"admin", b"pending_admin");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub token: Addr,
+    /// Tokens sent by every drip
+    pub drip_amount: Uint128,
+    /// Blocks an address has to wait between two drips
+    pub cooldown_blocks: u64,
+    pub paused: bool,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Height of the last drip by address
+pub fn last_drips(storage: &mut dyn Storage) -> Bucket<'_, u64> {
+    bucket(storage, LAST_DRIPS_KEY)
+}
+
+pub fn last_drips_read(storage: &dyn Storage) -> ReadonlyBucket<'_, u64> {
+    bucket_read(storage, LAST_DRIPS_KEY)
+}
@@ -0,0 +1,61 @@
+//! Funds and taps the faucet with a real erc20 contract on a test chain, where the faucet's
+//! balance queries are answered by the erc20 contract.
+
+use cosmwasm_std::Uint128;
+use cw_erc20::BalanceResponse;
+use cw_faucet::contract::{execute, instantiate, query};
+use cw_faucet::msg::{ExecuteMsg, InstantiateMsg};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const FAUCET: &str = "faucet";
+
+/// A faucet dripping 10 tokens every 100 blocks, with the funder holding 25 tokens
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(1000);
+    let msg = erc20_msg("CASH", &[("funder", 25)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let faucet = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        admin: "admin".to_string(),
+        drip_amount: Uint128::from(10u128),
+        cooldown_blocks: 100,
+    };
+    chain
+        .instantiate(faucet, FAUCET, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+#[test]
+fn funds_and_drips() {
+    let mut chain = setup();
+    let msg = cw_erc20::ExecuteMsg::send(FAUCET, 25u128, None);
+    chain.execute(TOKEN, "funder", &msg, &[]).unwrap();
+    assert_eq!(balance(&chain, FAUCET), 25);
+
+    chain
+        .execute(FAUCET, "alice", &ExecuteMsg::Drip {}, &[])
+        .unwrap();
+    chain
+        .execute(FAUCET, "bob", &ExecuteMsg::Drip {}, &[])
+        .unwrap();
+    assert_eq!(balance(&chain, "alice"), 10);
+    assert_eq!(balance(&chain, "bob"), 10);
+    assert_eq!(balance(&chain, FAUCET), 5);
+
+    let err = chain
+        .execute(FAUCET, "carol", &ExecuteMsg::Drip {}, &[])
+        .unwrap_err();
+    assert_eq!(err, "Faucet is empty (balance 5, drip amount 10)");
+    assert_eq!(balance(&chain, FAUCET), 5);
+}
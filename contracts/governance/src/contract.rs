@@ -0,0 +1,677 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128,
+};
+use cw_erc20::BalanceResponse;
+
+use crate::error::ContractError;
+use crate::msg::{BallotResponse, ExecuteMsg, InstantiateMsg, ProposalResponse, QueryMsg};
+use crate::state::{
+    ballots, ballots_read, config, config_read, proposal_count, proposal_count_read, proposals,
+    proposals_read, Ballot, Config, Proposal, ProposalStatus, VoteOption,
+};
+
+const MAX_BPS: u64 = 10_000;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.quorum_bps == 0 || msg.quorum_bps > MAX_BPS {
+        return Err(ContractError::InvalidQuorum {
+            bps: msg.quorum_bps,
+        });
+    }
+    if msg.threshold_bps >= MAX_BPS {
+        return Err(ContractError::InvalidThreshold {
+            bps: msg.threshold_bps,
+        });
+    }
+    if msg.voting_period == 0 {
+        return Err(ContractError::InvalidVotingPeriod {});
+    }
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        quorum_bps: msg.quorum_bps,
+        threshold_bps: msg.threshold_bps,
+        voting_period: msg.voting_period,
+    };
+    // fail here rather than on the first vote if the token keeps no snapshots
+    query_balance_at(
+        deps.as_ref(),
+        &state.token,
+        &env.contract.address,
+        env.block.height.saturating_sub(1),
+    )?;
+    config(deps.storage).save(&state)?;
+    proposal_count(deps.storage).save(&0)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Submit {
+            title,
+            description,
+            msgs,
+        } => try_submit(deps, env, info, title, description, msgs),
+        ExecuteMsg::Vote { proposal_id, vote } => try_vote(deps, env, info, proposal_id, vote),
+        ExecuteMsg::Tally { proposal_id } => try_tally(deps, env, proposal_id),
+    }
+}
+
+/// Submit proposal
+///
+/// Opens a proposal to send `msgs` from this contract. Votes are weighed with the token
+/// balances at the end of the previous block, so tokens bought or moved after submission
+/// don't count.
+///
+/// @param title short name of the proposal
+/// @param description what the proposal does and why
+/// @param msgs messages sent by this contract if the proposal passes
+fn try_submit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    msgs: Vec<CosmosMsg>,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let total_supply = query_total_supply(deps.as_ref(), &state.token)?;
+
+    let proposal_id = proposal_count_read(deps.storage).load()?;
+    proposal_count(deps.storage).save(&(proposal_id + 1))?;
+    let end_height = env.block.height + state.voting_period;
+    let proposal = Proposal {
+        proposer: info.sender.clone(),
+        title,
+        description,
+        msgs,
+        snapshot_height: env.block.height.saturating_sub(1),
+        total_supply,
+        end_height,
+        yes_votes: Uint128::zero(),
+        no_votes: Uint128::zero(),
+        abstain_votes: Uint128::zero(),
+        status: ProposalStatus::Open,
+    };
+    proposals(deps.storage).save(&proposal_id.to_be_bytes(), &proposal)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "submit"),
+            attr("proposal_id", proposal_id),
+            attr("proposer", info.sender),
+            attr("end_height", end_height),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    vote: VoteOption,
+) -> Result<Response, ContractError> {
+    let mut proposal = load_proposal(deps.as_ref(), proposal_id)?;
+    if env.block.height >= proposal.end_height {
+        return Err(ContractError::VotingEnded {
+            end_height: proposal.end_height,
+        });
+    }
+    let voter_key = info.sender.as_str().as_bytes();
+    if ballots_read(deps.storage, proposal_id)
+        .may_load(voter_key)?
+        .is_some()
+    {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    let state = config_read(deps.storage).load()?;
+    let weight = query_voting_power(
+        deps.as_ref(),
+        &state,
+        &info.sender,
+        proposal.snapshot_height,
+    )?;
+
+    let votes = match vote {
+        VoteOption::Yes => &mut proposal.yes_votes,
+        VoteOption::No => &mut proposal.no_votes,
+        VoteOption::Abstain => &mut proposal.abstain_votes,
+    };
+    *votes = votes.checked_add(weight)?;
+    proposals(deps.storage).save(&proposal_id.to_be_bytes(), &proposal)?;
+    ballots(deps.storage, proposal_id).save(voter_key, &Ballot { vote, weight })?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "vote"),
+            attr("proposal_id", proposal_id),
+            attr("voter", info.sender),
+            attr("vote", vote_name(vote)),
+            attr("weight", weight),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Tally proposal
+///
+/// Decides a proposal once its voting period is over. It passes if the cast votes, abstentions
+/// included, reach `quorum_bps` of the total supply at submission and the yes votes exceed
+/// `threshold_bps` of the yes and no votes. The messages of a passed proposal are sent along,
+/// so if one of them fails the whole tally is reverted and the proposal stays open.
+///
+/// @param proposal_id the proposal to decide
+fn try_tally(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Response, ContractError> {
+    let mut proposal = load_proposal(deps.as_ref(), proposal_id)?;
+    if proposal.status != ProposalStatus::Open {
+        return Err(ContractError::ProposalTallied {});
+    }
+    if env.block.height < proposal.end_height {
+        return Err(ContractError::VotingNotEnded {
+            end_height: proposal.end_height,
+        });
+    }
+
+    let state = config_read(deps.storage).load()?;
+    let decided = proposal.yes_votes.checked_add(proposal.no_votes)?;
+    let cast = decided.checked_add(proposal.abstain_votes)?;
+    let quorum_reached = cast.u128() * u128::from(MAX_BPS)
+        >= proposal.total_supply.u128() * u128::from(state.quorum_bps);
+    let threshold_reached = proposal.yes_votes.u128() * u128::from(MAX_BPS)
+        > decided.u128() * u128::from(state.threshold_bps);
+
+    let messages = if quorum_reached && threshold_reached {
+        proposal.status = ProposalStatus::Executed;
+        proposal.msgs.clone()
+    } else {
+        proposal.status = ProposalStatus::Rejected;
+        vec![]
+    };
+    proposals(deps.storage).save(&proposal_id.to_be_bytes(), &proposal)?;
+
+    let passed = proposal.status == ProposalStatus::Executed;
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", "tally"),
+            attr("proposal_id", proposal_id),
+            attr("passed", passed.to_string()),
+            attr("quorum_reached", quorum_reached.to_string()),
+            attr("yes_votes", proposal.yes_votes),
+            attr("no_votes", proposal.no_votes),
+            attr("abstain_votes", proposal.abstain_votes),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn vote_name(vote: VoteOption) -> &'static str {
+    match vote {
+        VoteOption::Yes => "yes",
+        VoteOption::No => "no",
+        VoteOption::Abstain => "abstain",
+    }
+}
+
+fn query_voting_power(
+    deps: Deps,
+    state: &Config,
+    voter: &Addr,
+    height: u64,
+) -> Result<Uint128, ContractError> {
+    let balance = query_balance_at(deps, &state.token, voter, height)?;
+    if balance.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+    Ok(balance)
+}
+
+/// Tokens built without the `iterator` feature don't know `BalanceAt` and fail the query
+fn query_balance_at(
+    deps: Deps,
+    token: &Addr,
+    address: &Addr,
+    height: u64,
+) -> Result<Uint128, ContractError> {
+    let balance: BalanceResponse = deps
+        .querier
+        .query_wasm_smart(
+            token,
+            &cw_erc20::QueryMsg::balance_at(address.as_str(), height),
+        )
+        .map_err(|_| ContractError::SnapshotsUnsupported {
+            token: token.to_string(),
+        })?;
+    Ok(balance.balance)
+}
+
+fn query_total_supply(deps: Deps, token: &Addr) -> Result<Uint128, ContractError> {
+    let supply = match deps
+        .querier
+        .query_wasm_raw(token, cw_erc20::total_supply_key())?
+    {
+        Some(data) => cw_erc20::decode_amount(&data).map_err(|_| ContractError::NoSupply {})?,
+        None => 0,
+    };
+    if supply == 0 {
+        return Err(ContractError::NoSupply {});
+    }
+    Ok(Uint128::from(supply))
+}
+
+fn load_proposal(deps: Deps, proposal_id: u64) -> Result<Proposal, ContractError> {
+    proposals_read(deps.storage)
+        .may_load(&proposal_id.to_be_bytes())?
+        .ok_or(ContractError::ProposalNotFound { proposal_id })
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Proposal { proposal_id } => {
+            let proposal = proposals_read(deps.storage)
+                .may_load(&proposal_id.to_be_bytes())?
+                .ok_or_else(|| StdError::not_found("Proposal"))?;
+            to_binary(&ProposalResponse {
+                proposer: proposal.proposer.to_string(),
+                title: proposal.title,
+                description: proposal.description,
+                msgs: proposal.msgs,
+                snapshot_height: proposal.snapshot_height,
+                total_supply: proposal.total_supply,
+                end_height: proposal.end_height,
+                yes_votes: proposal.yes_votes,
+                no_votes: proposal.no_votes,
+                abstain_votes: proposal.abstain_votes,
+                status: proposal.status,
+            })
+        }
+        QueryMsg::Ballot {
+            proposal_id,
+            address,
+        } => {
+            let address = deps.api.addr_validate(&address)?;
+            let ballot =
+                ballots_read(deps.storage, proposal_id).may_load(address.as_str().as_bytes())?;
+            to_binary(&BallotResponse { ballot })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+    use cosmwasm_std::{
+        coins, from_binary, from_slice, BankMsg, ContractResult, Empty, OwnedDeps, Querier,
+        QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+    };
+    use std::collections::HashMap;
+
+    /// Answers snapshot balance and total supply queries to the token at "token". Proposals
+    /// are submitted at height 100, so balances are only ever asked for at height 99.
+    #[derive(Default)]
+    struct TokenQuerier {
+        balances: HashMap<String, u128>,
+        /// Fails `BalanceAt` like a token built without the `iterator` feature
+        no_snapshots: bool,
+    }
+
+    impl TokenQuerier {
+        fn total_supply(&self) -> u128 {
+            self.balances.values().sum()
+        }
+    }
+
+    impl Querier for TokenQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            match request {
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                    if contract_addr == "token" =>
+                {
+                    if self.no_snapshots {
+                        let err = "unknown variant `balance_at`".to_string();
+                        return SystemResult::Ok(ContractResult::Err(err));
+                    }
+                    let address = match from_binary(&msg).unwrap() {
+                        cw_erc20::QueryMsg::BalanceAt { address, height } => {
+                            assert_eq!(height, 99);
+                            address
+                        }
+                        query => panic!("unexpected query: {:?}", query),
+                    };
+                    let balance = self.balances.get(&address).copied().unwrap_or_default();
+                    let res = to_binary(&BalanceResponse {
+                        balance: Uint128::from(balance),
+                    });
+                    SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+                }
+                QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key })
+                    if contract_addr == "token" =>
+                {
+                    assert_eq!(key.as_slice(), cw_erc20::total_supply_key().as_slice());
+                    let supply = Binary::from(self.total_supply().to_be_bytes());
+                    SystemResult::Ok(ContractResult::Ok(supply))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "non-token".to_string(),
+                }),
+            }
+        }
+    }
+
+    /// 120 tokens, a quorum of 48 and a simple majority
+    fn setup() -> OwnedDeps<MockStorage, MockApi, TokenQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: TokenQuerier::default(),
+        };
+        deps.querier.balances.insert("alice".to_string(), 60);
+        deps.querier.balances.insert("bob".to_string(), 30);
+        deps.querier.balances.insert("carol".to_string(), 20);
+        deps.querier.balances.insert("dave".to_string(), 10);
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            quorum_bps: 4000,
+            threshold_bps: 5000,
+            voting_period: 10,
+        };
+        instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn payout() -> CosmosMsg {
+        BankMsg::Send {
+            to_address: "grantee".to_string(),
+            amount: coins(1000, "ucosm"),
+        }
+        .into()
+    }
+
+    /// Submits a proposal paying the grantee at height 100, voting ends at 110
+    fn submit(deps: DepsMut) -> u64 {
+        let msg = ExecuteMsg::Submit {
+            title: "Grant".to_string(),
+            description: "Pay the grantee".to_string(),
+            msgs: vec![payout()],
+        };
+        let res = run(deps, 100, "proposer", msg).unwrap();
+        res.attributes[1].value.parse().unwrap()
+    }
+
+    fn vote(deps: DepsMut, voter: &str, proposal_id: u64, vote: VoteOption) {
+        let msg = ExecuteMsg::Vote { proposal_id, vote };
+        run(deps, 105, voter, msg).unwrap();
+    }
+
+    fn tally(deps: DepsMut, proposal_id: u64) -> Response {
+        run(deps, 110, "anyone", ExecuteMsg::Tally { proposal_id }).unwrap()
+    }
+
+    fn proposal(deps: Deps, proposal_id: u64) -> ProposalResponse {
+        let msg = QueryMsg::Proposal { proposal_id };
+        from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn validates_config() {
+        let mut deps = setup();
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            quorum_bps: 0,
+            threshold_bps: 5000,
+            voting_period: 10,
+        };
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::InvalidQuorum { bps: 0 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            quorum_bps: 4000,
+            threshold_bps: 10_000,
+            voting_period: 10,
+        };
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::InvalidThreshold { bps: 10_000 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            quorum_bps: 4000,
+            threshold_bps: 5000,
+            voting_period: 0,
+        };
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::InvalidVotingPeriod {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn requires_token_snapshots() {
+        let mut deps = setup();
+        deps.querier.no_snapshots = true;
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            quorum_bps: 4000,
+            threshold_bps: 5000,
+            voting_period: 10,
+        };
+        let err = instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg);
+        match err.unwrap_err() {
+            ContractError::SnapshotsUnsupported { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn submits_proposals() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Submit {
+            title: "Grant".to_string(),
+            description: "Pay the grantee".to_string(),
+            msgs: vec![payout()],
+        };
+        let res = run(deps.as_mut(), 100, "proposer", msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "submit"),
+                attr("proposal_id", "0"),
+                attr("proposer", "proposer"),
+                attr("end_height", "110"),
+            ]
+        );
+        assert_eq!(
+            proposal(deps.as_ref(), 0),
+            ProposalResponse {
+                proposer: "proposer".to_string(),
+                title: "Grant".to_string(),
+                description: "Pay the grantee".to_string(),
+                msgs: vec![payout()],
+                snapshot_height: 99,
+                total_supply: Uint128::from(120u128),
+                end_height: 110,
+                yes_votes: Uint128::zero(),
+                no_votes: Uint128::zero(),
+                abstain_votes: Uint128::zero(),
+                status: ProposalStatus::Open,
+            }
+        );
+        assert_eq!(submit(deps.as_mut()), 1);
+    }
+
+    #[test]
+    fn weighs_votes_by_snapshot_balance() {
+        let mut deps = setup();
+        let proposal_id = submit(deps.as_mut());
+        let msg = ExecuteMsg::Vote {
+            proposal_id,
+            vote: VoteOption::Abstain,
+        };
+        let res = run(deps.as_mut(), 101, "bob", msg.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "vote"),
+                attr("proposal_id", "0"),
+                attr("voter", "bob"),
+                attr("vote", "abstain"),
+                attr("weight", "30"),
+            ]
+        );
+        match run(deps.as_mut(), 101, "bob", msg.clone()).unwrap_err() {
+            ContractError::AlreadyVoted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 101, "mallory", msg.clone()).unwrap_err() {
+            ContractError::NoVotingPower {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 110, "alice", msg).unwrap_err() {
+            ContractError::VotingEnded { end_height: 110 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = QueryMsg::Ballot {
+            proposal_id,
+            address: "bob".to_string(),
+        };
+        let res: BallotResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res.ballot,
+            Some(Ballot {
+                vote: VoteOption::Abstain,
+                weight: Uint128::from(30u128),
+            })
+        );
+        assert_eq!(
+            proposal(deps.as_ref(), proposal_id).abstain_votes.u128(),
+            30
+        );
+    }
+
+    #[test]
+    fn rejects_without_quorum() {
+        let mut deps = setup();
+        let proposal_id = submit(deps.as_mut());
+        // 40 of the 48 votes needed, all in favour
+        vote(deps.as_mut(), "bob", proposal_id, VoteOption::Yes);
+        vote(deps.as_mut(), "dave", proposal_id, VoteOption::Abstain);
+
+        match run(
+            deps.as_mut(),
+            109,
+            "anyone",
+            ExecuteMsg::Tally { proposal_id },
+        )
+        .unwrap_err()
+        {
+            ContractError::VotingNotEnded { end_height: 110 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = tally(deps.as_mut(), proposal_id);
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "tally"),
+                attr("proposal_id", "0"),
+                attr("passed", "false"),
+                attr("quorum_reached", "false"),
+                attr("yes_votes", "30"),
+                attr("no_votes", "0"),
+                attr("abstain_votes", "10"),
+            ]
+        );
+        let status = proposal(deps.as_ref(), proposal_id).status;
+        assert_eq!(status, ProposalStatus::Rejected);
+    }
+
+    #[test]
+    fn rejects_below_threshold() {
+        let mut deps = setup();
+        let proposal_id = submit(deps.as_mut());
+        // abstentions count towards the quorum, but a tie is no majority
+        vote(deps.as_mut(), "bob", proposal_id, VoteOption::Yes);
+        vote(deps.as_mut(), "carol", proposal_id, VoteOption::No);
+        vote(deps.as_mut(), "dave", proposal_id, VoteOption::No);
+        vote(deps.as_mut(), "alice", proposal_id, VoteOption::Abstain);
+
+        let res = tally(deps.as_mut(), proposal_id);
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(res.attributes[2], attr("passed", "false"));
+        assert_eq!(res.attributes[3], attr("quorum_reached", "true"));
+        let status = proposal(deps.as_ref(), proposal_id).status;
+        assert_eq!(status, ProposalStatus::Rejected);
+    }
+
+    #[test]
+    fn executes_passed_proposals() {
+        let mut deps = setup();
+        let proposal_id = submit(deps.as_mut());
+        vote(deps.as_mut(), "bob", proposal_id, VoteOption::Yes);
+        vote(deps.as_mut(), "carol", proposal_id, VoteOption::No);
+
+        let res = tally(deps.as_mut(), proposal_id);
+        assert_eq!(res.messages, vec![payout()]);
+        assert_eq!(res.attributes[2], attr("passed", "true"));
+        let status = proposal(deps.as_ref(), proposal_id).status;
+        assert_eq!(status, ProposalStatus::Executed);
+
+        match run(
+            deps.as_mut(),
+            111,
+            "anyone",
+            ExecuteMsg::Tally { proposal_id },
+        )
+        .unwrap_err()
+        {
+            ContractError::ProposalTallied {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
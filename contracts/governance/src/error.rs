@@ -0,0 +1,44 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Quorum must be between 1 and 10000 basis points (got {bps})")]
+    InvalidQuorum { bps: u64 },
+
+    #[error("Threshold must be below 10000 basis points (got {bps})")]
+    InvalidThreshold { bps: u64 },
+
+    #[error("Voting period must be at least one block")]
+    InvalidVotingPeriod {},
+
+    #[error("Proposal {proposal_id} not found")]
+    ProposalNotFound { proposal_id: u64 },
+
+    #[error("Voting ended (end_height {end_height})")]
+    VotingEnded { end_height: u64 },
+
+    #[error("Voting not ended yet (end_height {end_height})")]
+    VotingNotEnded { end_height: u64 },
+
+    #[error("Proposal already tallied")]
+    ProposalTallied {},
+
+    #[error("Already voted")]
+    AlreadyVoted {},
+
+    #[error("Token {token} does not answer BalanceAt, build it with the iterator feature")]
+    SnapshotsUnsupported { token: String },
+
+    #[error("No tokens to vote with at the snapshot height")]
+    NoVotingPower {},
+
+    #[error("Token has no supply")]
+    NoSupply {},
+}
@@ -0,0 +1,70 @@
+use cosmwasm_std::{CosmosMsg, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Ballot, ProposalStatus, VoteOption};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token contract providing the voting power
+    pub token: String,
+    /// Share of the total supply, in basis points, that has to vote for a proposal to pass
+    pub quorum_bps: u64,
+    /// Share of the yes and no votes, in basis points, that yes votes have to exceed,
+    /// e.g. 5000 for a simple majority
+    pub threshold_bps: u64,
+    /// Number of blocks a proposal accepts votes
+    pub voting_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Opens a proposal to send `msgs` from this contract, accepting votes for the voting
+    /// period. Anyone can do this.
+    Submit {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg>,
+    },
+    /// Votes with the signer's token balance at the proposal's snapshot height. Every address
+    /// can vote once per proposal.
+    Vote { proposal_id: u64, vote: VoteOption },
+    /// Decides the proposal once voting ended and executes its messages if it passed.
+    /// Anyone can call this.
+    Tally { proposal_id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Proposal {
+        proposal_id: u64,
+    },
+    /// Returns the vote of `address` on the proposal, if it voted
+    Ballot {
+        proposal_id: u64,
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResponse {
+    pub proposer: String,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub snapshot_height: u64,
+    pub total_supply: Uint128,
+    pub end_height: u64,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub abstain_votes: Uint128,
+    pub status: ProposalStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BallotResponse {
+    pub ballot: Option<Ballot>,
+}
@@ -0,0 +1,102 @@
+use cosmwasm_std::{Addr, CosmosMsg, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static PROPOSAL_COUNT_KEY: &[u8] = b"proposal_count";
+static PROPOSALS_KEY: &[u8] = b"proposals";
+static BALLOTS_KEY: &[u8] = b"ballots";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The erc20 token whose balances weigh the votes
+    pub token: Addr,
+    /// Share of the total supply, in basis points, that has to vote for a proposal to pass
+    pub quorum_bps: u64,
+    /// Share of the yes and no votes, in basis points, that yes votes have to exceed
+    pub threshold_bps: u64,
+    /// Number of blocks a proposal accepts votes
+    pub voting_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+    /// Counts towards the quorum, but not the threshold
+    Abstain,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    Open,
+    /// Passed and its messages were executed
+    Executed,
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Proposal {
+    pub proposer: Addr,
+    pub title: String,
+    pub description: String,
+    /// Sent by this contract when the proposal passes
+    pub msgs: Vec<CosmosMsg>,
+    /// Votes are weighed with the balances at the end of this block
+    pub snapshot_height: u64,
+    /// Total supply of the token when the proposal was submitted
+    pub total_supply: Uint128,
+    /// Votes are accepted up to, but not including, this block height
+    pub end_height: u64,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub abstain_votes: Uint128,
+    pub status: ProposalStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ballot {
+    pub vote: VoteOption,
+    /// Token balance of the voter at the proposal's snapshot height
+    pub weight: Uint128,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn proposal_count(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, PROPOSAL_COUNT_KEY)
+}
+
+pub fn proposal_count_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, PROPOSAL_COUNT_KEY)
+}
+
+/// Proposals by big endian proposal ID
+pub fn proposals(storage: &mut dyn Storage) -> Bucket<'_, Proposal> {
+    bucket(storage, PROPOSALS_KEY)
+}
+
+pub fn proposals_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Proposal> {
+    bucket_read(storage, PROPOSALS_KEY)
+}
+
+/// Ballots of a proposal by voter address
+pub fn ballots(storage: &mut dyn Storage, proposal_id: u64) -> Bucket<'_, Ballot> {
+    Bucket::multilevel(storage, &[BALLOTS_KEY, &proposal_id.to_be_bytes()])
+}
+
+pub fn ballots_read(storage: &dyn Storage, proposal_id: u64) -> ReadonlyBucket<'_, Ballot> {
+    ReadonlyBucket::multilevel(storage, &[BALLOTS_KEY, &proposal_id.to_be_bytes()])
+}
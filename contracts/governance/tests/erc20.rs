@@ -0,0 +1,125 @@
+//! Runs governance against a real erc20 contract on a test chain, which answers the governance
+//! contract's queries from the erc20 contract and executes the messages of passed proposals
+//! like a real chain.
+
+use cw_erc20::BalanceResponse;
+use cw_governance::contract::{execute, instantiate, query};
+use cw_governance::msg::{ExecuteMsg, InstantiateMsg, ProposalResponse, QueryMsg};
+use cw_governance::state::{ProposalStatus, VoteOption};
+use cw_governance::ContractError;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const GOVERNANCE: &str = "governance";
+
+/// 210 tokens, 100 of them in the governance treasury. The token is instantiated at the
+/// `mock_env` height of 12345.
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    let msg = erc20_msg("VOTE", &[("alice", 60), ("bob", 50), (GOVERNANCE, 100)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    chain.set_height(12400);
+    let governance = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        quorum_bps: 4000,
+        threshold_bps: 5000,
+        voting_period: 10,
+    };
+    chain
+        .instantiate(governance, GOVERNANCE, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn run(chain: &mut Chain, height: u64, sender: &str, msg: ExecuteMsg) -> Result<(), String> {
+    chain.set_height(height);
+    chain.execute(GOVERNANCE, sender, &msg, &[]).map(|_| ())
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn proposal(chain: &Chain, proposal_id: u64) -> ProposalResponse {
+    let msg = QueryMsg::Proposal { proposal_id };
+    chain.query(GOVERNANCE, &msg).unwrap()
+}
+
+fn vote(vote: VoteOption) -> ExecuteMsg {
+    ExecuteMsg::Vote {
+        proposal_id: 0,
+        vote,
+    }
+}
+
+/// Submits a proposal paying the grantee `amount` from the treasury at height 12400
+fn submit_grant(chain: &mut Chain, amount: u128) {
+    let grant = cw_erc20::ExecuteMsg::transfer("grantee", amount)
+        .into_cosmos_msg(TOKEN)
+        .unwrap();
+    let msg = ExecuteMsg::Submit {
+        title: "Grant".to_string(),
+        description: "Pay the grantee from the treasury".to_string(),
+        msgs: vec![grant],
+    };
+    run(chain, 12400, "bob", msg).unwrap();
+}
+
+#[test]
+fn executes_treasury_grant() {
+    let mut chain = setup();
+    submit_grant(&mut chain, 100);
+    assert_eq!(proposal(&chain, 0).snapshot_height, 12399);
+
+    // tokens moved after submission keep voting with the old holder
+    chain.set_height(12401);
+    let transfer = cw_erc20::ExecuteMsg::transfer("carol", 60u128);
+    chain.execute(TOKEN, "alice", &transfer, &[]).unwrap();
+    let err = run(&mut chain, 12402, "carol", vote(VoteOption::Yes)).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {}.to_string());
+    run(&mut chain, 12402, "alice", vote(VoteOption::Yes)).unwrap();
+    run(&mut chain, 12402, "bob", vote(VoteOption::No)).unwrap();
+    let res = proposal(&chain, 0);
+    assert_eq!(res.yes_votes.u128(), 60);
+    assert_eq!(res.no_votes.u128(), 50);
+
+    // 110 of 210 tokens voted, above the quorum of 84
+    run(
+        &mut chain,
+        12410,
+        "anyone",
+        ExecuteMsg::Tally { proposal_id: 0 },
+    )
+    .unwrap();
+    assert_eq!(balance(&chain, "grantee"), 100);
+    assert_eq!(balance(&chain, GOVERNANCE), 0);
+    assert_eq!(proposal(&chain, 0).status, ProposalStatus::Executed);
+}
+
+#[test]
+fn failing_grant_is_not_executed() {
+    let mut chain = setup();
+    // more than the treasury holds
+    submit_grant(&mut chain, 101);
+    run(&mut chain, 12402, "alice", vote(VoteOption::Yes)).unwrap();
+    run(&mut chain, 12402, "bob", vote(VoteOption::Yes)).unwrap();
+
+    let err = run(
+        &mut chain,
+        12410,
+        "anyone",
+        ExecuteMsg::Tally { proposal_id: 0 },
+    )
+    .unwrap_err();
+    assert!(
+        err.contains("Insufficient funds"),
+        "unexpected error: {}",
+        err
+    );
+    assert_eq!(balance(&chain, GOVERNANCE), 100);
+    assert_eq!(proposal(&chain, 0).status, ProposalStatus::Open);
+}
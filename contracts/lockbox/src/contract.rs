@@ -0,0 +1,575 @@
+use cosmwasm_std::{
+    attr, coins, entry_point, from_binary, to_binary, BankMsg, Binary, CosmosMsg, Deps, DepsMut,
+    Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw_erc20::ReceiveMsg;
+
+use crate::error::ContractError;
+use crate::msg::{DealResponse, DealStatus, ExecuteMsg, HookMsg, InstantiateMsg, QueryMsg};
+use crate::state::{config, config_read, deal, deal_read, Config, Deal};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        denom: msg.denom,
+    };
+    config(deps.storage).save(&state)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(receive) => try_receive(deps, env, info, receive),
+        ExecuteMsg::Deposit {} => try_deposit(deps, env, info),
+        ExecuteMsg::Settle {} => try_settle(deps, env, info),
+        ExecuteMsg::Cancel {} => try_cancel(deps, env),
+    }
+}
+
+/// Receive
+///
+/// Opens the deal with the tokens sent with the erc20 `Send` message. The sender of the tokens
+/// becomes the maker. A lockbox holds a single deal, so this works only once.
+///
+/// @param receive the `ReceiveMsg` forwarded by the token
+fn try_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if info.sender != state.token {
+        return Err(ContractError::WrongToken {
+            token: state.token.to_string(),
+        });
+    }
+    let (price, counterparty, expires) = match receive.msg {
+        Some(msg) => match from_binary(&msg)? {
+            HookMsg::Offer {
+                price,
+                counterparty,
+                expires,
+            } => (price, counterparty, expires),
+        },
+        None => return Err(ContractError::MissingPayload {}),
+    };
+    if deal_read(deps.storage).may_load()?.is_some() {
+        return Err(ContractError::AlreadyOffered {});
+    }
+    if receive.amount.is_zero() || price.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    if expires <= env.block.height {
+        return Err(ContractError::InvalidExpiry {
+            height: env.block.height,
+        });
+    }
+
+    let maker = deps.api.addr_validate(&receive.sender)?;
+    let counterparty = counterparty
+        .map(|counterparty| deps.api.addr_validate(&counterparty))
+        .transpose()?;
+    let offer = Deal {
+        maker: maker.clone(),
+        token_amount: receive.amount,
+        price,
+        counterparty,
+        expires,
+        taker: None,
+        settled: false,
+        cancelled: false,
+    };
+    deal(deps.storage).save(&offer)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "offer"),
+            attr("maker", maker),
+            attr("token_amount", receive.amount),
+            attr("price", price),
+            attr("expires", expires),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Deposit
+///
+/// Deposits the price in native coins, which must be sent along exactly. The signer becomes
+/// the taker, and must be the counterparty if the maker named one.
+fn try_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let mut offer = load_open_deal(deps.as_ref(), &env)?;
+    if offer.taker.is_some() {
+        return Err(ContractError::AlreadyDeposited {});
+    }
+    if let Some(counterparty) = &offer.counterparty {
+        if *counterparty != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+    match info.funds.as_slice() {
+        [coin] if coin.denom == state.denom && coin.amount == offer.price => {}
+        _ => {
+            return Err(ContractError::WrongFunds {
+                price: offer.price,
+                denom: state.denom,
+            })
+        }
+    }
+    offer.taker = Some(info.sender.clone());
+    deal(deps.storage).save(&offer)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "deposit"),
+            attr("taker", info.sender),
+            attr("amount", offer.price),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Settle
+///
+/// Swaps both deposits in one go: the tokens go to the taker and the coins to the maker.
+/// Only the maker or the taker can do this.
+fn try_settle(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let mut offer = load_open_deal(deps.as_ref(), &env)?;
+    let taker = offer.taker.clone().ok_or(ContractError::NotFunded {})?;
+    if info.sender != offer.maker && info.sender != taker {
+        return Err(ContractError::Unauthorized {});
+    }
+    offer.settled = true;
+    deal(deps.storage).save(&offer)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(taker.as_str(), offer.token_amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![
+            transfer.into_cosmos_msg(&state.token)?,
+            BankMsg::Send {
+                to_address: offer.maker.to_string(),
+                amount: coins(offer.price.u128(), &state.denom),
+            }
+            .into(),
+        ],
+        attributes: vec![
+            attr("action", "settle"),
+            attr("maker", offer.maker),
+            attr("taker", taker),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Cancel
+///
+/// Refunds the tokens to the maker and, if the price was deposited, the coins to the taker.
+/// Only possible once the deal expired without being settled.
+fn try_cancel(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let mut offer = load_deal(deps.as_ref())?;
+    if env.block.height < offer.expires {
+        return Err(ContractError::NotExpired {
+            expires: offer.expires,
+        });
+    }
+    offer.cancelled = true;
+    deal(deps.storage).save(&offer)?;
+
+    let refund = cw_erc20::ExecuteMsg::transfer(offer.maker.as_str(), offer.token_amount);
+    let mut messages: Vec<CosmosMsg> = vec![refund.into_cosmos_msg(&state.token)?];
+    if let Some(taker) = &offer.taker {
+        messages.push(
+            BankMsg::Send {
+                to_address: taker.to_string(),
+                amount: coins(offer.price.u128(), &state.denom),
+            }
+            .into(),
+        );
+    }
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", "cancel"),
+            attr("maker", offer.maker),
+            attr("refunded_taker", offer.taker.is_some()),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Loads the deal unless it was settled or cancelled
+fn load_deal(deps: Deps) -> Result<Deal, ContractError> {
+    let offer = deal_read(deps.storage)
+        .may_load()?
+        .ok_or(ContractError::NoDeal {})?;
+    if offer.settled || offer.cancelled {
+        return Err(ContractError::Closed {});
+    }
+    Ok(offer)
+}
+
+/// Like `load_deal`, but also fails once the deal expired
+fn load_open_deal(deps: Deps, env: &Env) -> Result<Deal, ContractError> {
+    let offer = load_deal(deps)?;
+    if env.block.height >= offer.expires {
+        return Err(ContractError::Expired {
+            expires: offer.expires,
+        });
+    }
+    Ok(offer)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Deal {} => to_binary(&query_deal(deps, &env)?),
+    }
+}
+
+fn query_deal(deps: Deps, env: &Env) -> StdResult<DealResponse> {
+    let state = config_read(deps.storage).load()?;
+    let offer = deal_read(deps.storage)
+        .may_load()?
+        .ok_or_else(|| StdError::not_found("Deal"))?;
+    let status = if offer.settled {
+        DealStatus::Settled
+    } else if offer.cancelled {
+        DealStatus::Cancelled
+    } else if env.block.height >= offer.expires {
+        DealStatus::Expired
+    } else if offer.taker.is_some() {
+        DealStatus::Funded
+    } else {
+        DealStatus::Open
+    };
+    Ok(DealResponse {
+        token: state.token.to_string(),
+        denom: state.denom,
+        maker: offer.maker.to_string(),
+        token_amount: offer.token_amount,
+        price: offer.price,
+        counterparty: offer
+            .counterparty
+            .map(|counterparty| counterparty.to_string()),
+        expires: offer.expires,
+        taker: offer.taker.map(|taker| taker.to_string()),
+        status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+    use cosmwasm_std::{coin, from_binary, MemoryStorage, OwnedDeps, Uint128};
+
+    const EXPIRES: u64 = 12_400;
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn setup() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            denom: "ucosm".to_string(),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn offer(
+        deps: DepsMut,
+        amount: u128,
+        price: u128,
+        counterparty: Option<&str>,
+    ) -> Result<Response, ContractError> {
+        let hook = HookMsg::Offer {
+            price: Uint128::from(price),
+            counterparty: counterparty.map(String::from),
+            expires: EXPIRES,
+        };
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "maker".to_string(),
+            amount: Uint128::from(amount),
+            msg: Some(to_binary(&hook).unwrap()),
+        });
+        execute(deps, mock_env(), mock_info("token", &[]), msg)
+    }
+
+    fn status(deps: Deps, height: u64) -> DealStatus {
+        let res = query(deps, env_at(height), QueryMsg::Deal {}).unwrap();
+        from_binary::<DealResponse>(&res).unwrap().status
+    }
+
+    #[test]
+    fn validates_offer() {
+        let mut deps = setup();
+        let receive = ReceiveMsg {
+            sender: "maker".to_string(),
+            amount: Uint128::from(100u128),
+            msg: None,
+        };
+        let msg = ExecuteMsg::Receive(receive.clone());
+        match execute(deps.as_mut(), mock_env(), mock_info("other", &[]), msg).unwrap_err() {
+            ContractError::WrongToken { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::Receive(receive);
+        match execute(deps.as_mut(), mock_env(), mock_info("token", &[]), msg).unwrap_err() {
+            ContractError::MissingPayload {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match offer(deps.as_mut(), 100, 0, None).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let hook = HookMsg::Offer {
+            price: Uint128::from(50u128),
+            counterparty: None,
+            expires: 12_345,
+        };
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "maker".to_string(),
+            amount: Uint128::from(100u128),
+            msg: Some(to_binary(&hook).unwrap()),
+        });
+        match execute(deps.as_mut(), mock_env(), mock_info("token", &[]), msg).unwrap_err() {
+            ContractError::InvalidExpiry { height: 12_345 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        offer(deps.as_mut(), 100, 50, None).unwrap();
+        assert_eq!(status(deps.as_ref(), 12_345), DealStatus::Open);
+        match offer(deps.as_mut(), 100, 50, None).unwrap_err() {
+            ContractError::AlreadyOffered {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn deposit_takes_exact_price() {
+        let mut deps = setup();
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("taker", &coins(50, "ucosm")),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap_err()
+        {
+            ContractError::NoDeal {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        offer(deps.as_mut(), 100, 50, None).unwrap();
+
+        let wrong_funds = vec![
+            vec![],
+            coins(49, "ucosm"),
+            coins(51, "ucosm"),
+            coins(50, "uatom"),
+            vec![coin(50, "ucosm"), coin(1, "uatom")],
+        ];
+        for funds in wrong_funds {
+            let info = mock_info("taker", &funds);
+            match execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err() {
+                ContractError::WrongFunds { price, denom } => {
+                    assert_eq!(price.u128(), 50);
+                    assert_eq!(denom, "ucosm");
+                }
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        assert_eq!(status(deps.as_ref(), 12_345), DealStatus::Open);
+
+        let info = mock_info("taker", &coins(50, "ucosm"));
+        match execute(deps.as_mut(), env_at(EXPIRES), info, ExecuteMsg::Deposit {}).unwrap_err() {
+            ContractError::Expired { expires: EXPIRES } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("taker", &coins(50, "ucosm"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        assert_eq!(status(deps.as_ref(), 12_345), DealStatus::Funded);
+        let info = mock_info("other", &coins(50, "ucosm"));
+        match execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err() {
+            ContractError::AlreadyDeposited {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn only_counterparty_can_deposit() {
+        let mut deps = setup();
+        offer(deps.as_mut(), 100, 50, Some("taker")).unwrap();
+        let info = mock_info("other", &coins(50, "ucosm"));
+        match execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let info = mock_info("taker", &coins(50, "ucosm"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+    }
+
+    #[test]
+    fn settle_swaps_both_sides() {
+        let mut deps = setup();
+        offer(deps.as_mut(), 100, 50, None).unwrap();
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("maker", &[]),
+            ExecuteMsg::Settle {},
+        )
+        .unwrap_err()
+        {
+            ContractError::NotFunded {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let info = mock_info("taker", &coins(50, "ucosm"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            ExecuteMsg::Settle {},
+        )
+        .unwrap_err()
+        {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match execute(
+            deps.as_mut(),
+            env_at(EXPIRES),
+            mock_info("maker", &[]),
+            ExecuteMsg::Settle {},
+        )
+        .unwrap_err()
+        {
+            ContractError::Expired { expires: EXPIRES } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // the taker can settle just as well as the maker
+        let res = execute(
+            deps.as_mut(),
+            env_at(EXPIRES - 1),
+            mock_info("taker", &[]),
+            ExecuteMsg::Settle {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                cw_erc20::ExecuteMsg::transfer("taker", 100u128)
+                    .into_cosmos_msg("token")
+                    .unwrap(),
+                BankMsg::Send {
+                    to_address: "maker".to_string(),
+                    amount: coins(50, "ucosm"),
+                }
+                .into(),
+            ]
+        );
+        assert_eq!(status(deps.as_ref(), EXPIRES), DealStatus::Settled);
+
+        for msg in &[ExecuteMsg::Settle {}, ExecuteMsg::Cancel {}] {
+            let env = env_at(EXPIRES);
+            match execute(deps.as_mut(), env, mock_info("maker", &[]), msg.clone()).unwrap_err() {
+                ContractError::Closed {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn cancel_refunds_deposited_sides() {
+        // only the maker deposited
+        let mut deps = setup();
+        offer(deps.as_mut(), 100, 50, None).unwrap();
+        match execute(
+            deps.as_mut(),
+            env_at(EXPIRES - 1),
+            mock_info("maker", &[]),
+            ExecuteMsg::Cancel {},
+        )
+        .unwrap_err()
+        {
+            ContractError::NotExpired { expires: EXPIRES } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(status(deps.as_ref(), EXPIRES), DealStatus::Expired);
+        let res = execute(
+            deps.as_mut(),
+            env_at(EXPIRES),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Cancel {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![cw_erc20::ExecuteMsg::transfer("maker", 100u128)
+                .into_cosmos_msg("token")
+                .unwrap()]
+        );
+        assert_eq!(status(deps.as_ref(), EXPIRES), DealStatus::Cancelled);
+
+        // both deposited, but nobody settled in time
+        let mut deps = setup();
+        offer(deps.as_mut(), 100, 50, None).unwrap();
+        let info = mock_info("taker", &coins(50, "ucosm"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env_at(EXPIRES + 10),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Cancel {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                cw_erc20::ExecuteMsg::transfer("maker", 100u128)
+                    .into_cosmos_msg("token")
+                    .unwrap(),
+                BankMsg::Send {
+                    to_address: "taker".to_string(),
+                    amount: coins(50, "ucosm"),
+                }
+                .into(),
+            ]
+        );
+    }
+}
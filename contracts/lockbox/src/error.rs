@@ -0,0 +1,47 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Only {token} tokens are accepted")]
+    WrongToken { token: String },
+
+    #[error("Missing hook message")]
+    MissingPayload {},
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Expiry must be after the current height {height}")]
+    InvalidExpiry { height: u64 },
+
+    #[error("Deal was already offered")]
+    AlreadyOffered {},
+
+    #[error("No deal was offered yet")]
+    NoDeal {},
+
+    #[error("Expected exactly {price}{denom}")]
+    WrongFunds { price: Uint128, denom: String },
+
+    #[error("Price was already deposited")]
+    AlreadyDeposited {},
+
+    #[error("Price was not deposited yet")]
+    NotFunded {},
+
+    #[error("Deal expired at height {expires}")]
+    Expired { expires: u64 },
+
+    #[error("Deal does not expire before height {expires}")]
+    NotExpired { expires: u64 },
+
+    #[error("Deal was already settled or cancelled")]
+    Closed {},
+}
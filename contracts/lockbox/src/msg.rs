@@ -0,0 +1,72 @@
+use cosmwasm_std::Uint128;
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token the maker sells
+    pub token: String,
+    /// The native coin the taker pays with
+    pub denom: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Opens the deal with the tokens sent with the erc20 `Send` message. The message must
+    /// carry a `HookMsg`.
+    Receive(ReceiveMsg),
+    /// Deposits exactly the price in native coins, which makes the signer the taker
+    Deposit {},
+    /// Releases the tokens to the taker and the coins to the maker. Only the maker or the
+    /// taker can do this, once both sides are deposited and before the deal expires.
+    Settle {},
+    /// Refunds every deposited side once the deal expired. Anyone can do this.
+    Cancel {},
+}
+
+/// The payload of the erc20 `Send` message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookMsg {
+    /// Offers the sent tokens for `price` native coins until height `expires`
+    Offer {
+        price: Uint128,
+        counterparty: Option<String>,
+        expires: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the deal, which fails if the maker did not deposit yet
+    Deal {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DealStatus {
+    /// Waiting for the taker's deposit
+    Open,
+    /// Both sides are deposited and can be settled
+    Funded,
+    Settled,
+    Cancelled,
+    /// Expired before it was settled, waiting to be cancelled
+    Expired,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DealResponse {
+    pub token: String,
+    pub denom: String,
+    pub maker: String,
+    pub token_amount: Uint128,
+    pub price: Uint128,
+    pub counterparty: Option<String>,
+    pub expires: u64,
+    pub taker: Option<String>,
+    pub status: DealStatus,
+}
@@ -0,0 +1,49 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static DEAL_KEY: &[u8] = b"deal";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The erc20 token the maker sells
+    pub token: Addr,
+    /// The native coin the taker pays with
+    pub denom: String,
+}
+
+/// The single deal of this lockbox, created by the maker's deposit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Deal {
+    pub maker: Addr,
+    /// Tokens deposited by the maker
+    pub token_amount: Uint128,
+    /// Native coins the taker has to deposit
+    pub price: Uint128,
+    /// The only address allowed to take the deal, anyone if not set
+    pub counterparty: Option<Addr>,
+    /// Height from which the deal can no longer be taken or settled, only cancelled
+    pub expires: u64,
+    /// Set once the price was deposited
+    pub taker: Option<Addr>,
+    pub settled: bool,
+    pub cancelled: bool,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn deal(storage: &mut dyn Storage) -> Singleton<'_, Deal> {
+    singleton(storage, DEAL_KEY)
+}
+
+pub fn deal_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Deal> {
+    singleton_read(storage, DEAL_KEY)
+}
@@ -0,0 +1,124 @@
+//! Trades real erc20 tokens for native coins on a test chain, which keeps the coins in its bank
+//! and reverts failing messages like a real chain.
+
+use cosmwasm_std::{coins, to_binary, Uint128};
+use cw_erc20::BalanceResponse;
+use cw_lockbox::contract::{execute, instantiate, query};
+use cw_lockbox::msg::{DealResponse, DealStatus, ExecuteMsg, HookMsg, InstantiateMsg, QueryMsg};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const LOCKBOX: &str = "lockbox";
+const DENOM: &str = "ucosm";
+const EXPIRES: u64 = 12_400;
+
+/// The maker holds 1000 tokens and the taker 1000 coins
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    let msg = erc20_msg("DEAL", &[("maker", 1000)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let lockbox = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        denom: DENOM.to_string(),
+    };
+    chain
+        .instantiate(lockbox, LOCKBOX, "creator", &msg, &[])
+        .unwrap();
+    chain.set_balance("taker", &coins(1000, DENOM));
+    chain
+}
+
+/// The maker offers `amount` tokens to the taker for `price` coins
+fn offer(chain: &mut Chain, amount: u128, price: u128) {
+    let hook = HookMsg::Offer {
+        price: Uint128::from(price),
+        counterparty: Some("taker".to_string()),
+        expires: EXPIRES,
+    };
+    let msg = cw_erc20::ExecuteMsg::send(LOCKBOX, amount, Some(to_binary(&hook).unwrap()));
+    chain.execute(TOKEN, "maker", &msg, &[]).unwrap();
+}
+
+fn tokens(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn deal(chain: &Chain) -> DealResponse {
+    chain.query(LOCKBOX, &QueryMsg::Deal {}).unwrap()
+}
+
+#[test]
+fn settles_both_sides() {
+    let mut chain = setup();
+    offer(&mut chain, 400, 250);
+    let res = deal(&chain);
+    assert_eq!(res.maker, "maker");
+    assert_eq!(res.token_amount.u128(), 400);
+    assert_eq!(res.status, DealStatus::Open);
+    assert_eq!(tokens(&chain, "maker"), 600);
+    assert_eq!(tokens(&chain, LOCKBOX), 400);
+
+    chain.set_height(12_350);
+    let err = chain
+        .execute(
+            LOCKBOX,
+            "taker",
+            &ExecuteMsg::Deposit {},
+            &coins(200, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(err, "Expected exactly 250ucosm");
+    assert_eq!(chain.balance("taker", DENOM), 1000);
+    chain
+        .execute(
+            LOCKBOX,
+            "taker",
+            &ExecuteMsg::Deposit {},
+            &coins(250, DENOM),
+        )
+        .unwrap();
+    assert_eq!(chain.balance("taker", DENOM), 750);
+    assert_eq!(deal(&chain).status, DealStatus::Funded);
+
+    chain.set_height(12_360);
+    chain
+        .execute(LOCKBOX, "maker", &ExecuteMsg::Settle {}, &[])
+        .unwrap();
+    assert_eq!(tokens(&chain, "taker"), 400);
+    assert_eq!(tokens(&chain, LOCKBOX), 0);
+    assert_eq!(chain.balance("maker", DENOM), 250);
+    assert_eq!(chain.balance(LOCKBOX, DENOM), 0);
+    assert_eq!(deal(&chain).status, DealStatus::Settled);
+}
+
+#[test]
+fn expired_deal_is_refunded() {
+    let mut chain = setup();
+    offer(&mut chain, 400, 250);
+    chain.set_height(12_350);
+    chain
+        .execute(
+            LOCKBOX,
+            "taker",
+            &ExecuteMsg::Deposit {},
+            &coins(250, DENOM),
+        )
+        .unwrap();
+
+    chain.set_height(EXPIRES);
+    let err = chain
+        .execute(LOCKBOX, "taker", &ExecuteMsg::Settle {}, &[])
+        .unwrap_err();
+    assert_eq!(err, "Deal expired at height 12400");
+    chain
+        .execute(LOCKBOX, "anyone", &ExecuteMsg::Cancel {}, &[])
+        .unwrap();
+    assert_eq!(tokens(&chain, "maker"), 1000);
+    assert_eq!(chain.balance("taker", DENOM), 1000);
+    assert_eq!(chain.balance(LOCKBOX, DENOM), 0);
+    assert_eq!(deal(&chain).status, DealStatus::Cancelled);
+}
@@ -0,0 +1,533 @@
+use std::convert::TryInto;
+
+use cosmwasm_std::{
+    attr, coins, entry_point, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, Uint128,
+};
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, TicketsResponse};
+use crate::state::{
+    config, config_read, ticket_counts, ticket_counts_read, tickets, tickets_read, Config, Phase,
+};
+
+/// Shortest accepted secret, so it cannot be guessed from the commitment
+pub const MIN_SECRET_LENGTH: usize = 32;
+pub const MAX_SECRET_LENGTH: usize = 64;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.ticket_price.amount.is_zero() {
+        return Err(ContractError::ZeroPrice {});
+    }
+    if msg.sale_end_height <= env.block.height || msg.reveal_deadline_height <= msg.sale_end_height
+    {
+        return Err(ContractError::InvalidHeights {});
+    }
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+    let state = Config {
+        admin,
+        ticket_price: msg.ticket_price,
+        sale_end_height: msg.sale_end_height,
+        reveal_deadline_height: msg.reveal_deadline_height,
+        commitment: None,
+        ticket_count: 0,
+        secret: None,
+        winner: None,
+    };
+    config(deps.storage).save(&state)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Commit { commitment } => try_commit(deps, env, info, commitment),
+        ExecuteMsg::BuyTicket {} => try_buy_ticket(deps, env, info),
+        ExecuteMsg::Reveal { secret } => try_reveal(deps, env, info, secret),
+        ExecuteMsg::Refund {} => try_refund(deps, env, info),
+    }
+}
+
+/// Fails unless the lottery is in `expected` at the current height
+fn assert_phase(state: &Config, env: &Env, expected: Phase) -> Result<(), ContractError> {
+    let phase = state.phase(env.block.height);
+    if phase != expected {
+        return Err(ContractError::WrongPhase { phase });
+    }
+    Ok(())
+}
+
+/// Commit
+///
+/// Commits the admin to a secret before the first ticket is sold, so the secret cannot be
+/// chosen with knowledge of the tickets.
+///
+/// @param commitment the hex encoded sha256 hash of the secret
+fn try_commit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    commitment: String,
+) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_phase(&state, &env, Phase::AwaitingCommitment)?;
+    if !matches!(hex::decode(&commitment), Ok(bytes) if bytes.len() == 32) {
+        return Err(ContractError::InvalidCommitment {});
+    }
+    state.commitment = Some(commitment.to_lowercase());
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "commit"), attr("commitment", commitment)],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Buy ticket
+///
+/// Sells the next ticket to the signer for exactly `ticket_price`.
+fn try_buy_ticket(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    assert_phase(&state, &env, Phase::Sale)?;
+    if info.funds != [state.ticket_price.clone()] {
+        return Err(ContractError::WrongPayment {
+            price: state.ticket_price,
+        });
+    }
+
+    let ticket = state.ticket_count;
+    tickets(deps.storage).save(&ticket.to_be_bytes(), &info.sender)?;
+    let key = info.sender.as_str().as_bytes();
+    let count = ticket_counts_read(deps.storage)
+        .may_load(key)?
+        .unwrap_or_default();
+    ticket_counts(deps.storage).save(key, &(count + 1))?;
+    state.ticket_count += 1;
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "buy_ticket"),
+            attr("player", info.sender),
+            attr("ticket", ticket),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Reveal
+///
+/// Checks the secret against the commitment, picks the winning ticket with `select_winner`
+/// and sends all ticket sales to its holder.
+///
+/// @param secret the hex encoded secret
+fn try_reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    secret: String,
+) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_phase(&state, &env, Phase::AwaitingReveal)?;
+    let secret_bytes = match hex::decode(&secret) {
+        Ok(bytes) if (MIN_SECRET_LENGTH..=MAX_SECRET_LENGTH).contains(&bytes.len()) => bytes,
+        _ => {
+            return Err(ContractError::InvalidSecret {
+                min: MIN_SECRET_LENGTH,
+                max: MAX_SECRET_LENGTH,
+            })
+        }
+    };
+    if state.commitment != Some(hex::encode(Sha256::digest(&secret_bytes))) {
+        return Err(ContractError::WrongSecret {});
+    }
+
+    state.secret = Some(secret.to_lowercase());
+    let mut messages = vec![];
+    let mut attributes = vec![attr("action", "reveal")];
+    if state.ticket_count > 0 {
+        let ticket = select_winner(&secret_bytes, state.ticket_count);
+        let winner = tickets_read(deps.storage).load(&ticket.to_be_bytes())?;
+        let pot = state
+            .ticket_price
+            .amount
+            .checked_mul(Uint128::from(state.ticket_count))?;
+        messages.push(
+            BankMsg::Send {
+                to_address: winner.to_string(),
+                amount: coins(pot.u128(), &state.ticket_price.denom),
+            }
+            .into(),
+        );
+        attributes.push(attr("ticket", ticket));
+        attributes.push(attr("winner", &winner));
+        attributes.push(attr("prize", pot));
+        state.winner = Some(winner);
+    }
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes,
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Refund
+///
+/// Pays back all tickets of the signer once the reveal deadline passed without a reveal.
+fn try_refund(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    assert_phase(&state, &env, Phase::Refunding)?;
+    let key = info.sender.as_str().as_bytes();
+    let count = match ticket_counts_read(deps.storage).may_load(key)? {
+        Some(count) => count,
+        None => return Err(ContractError::NothingToRefund {}),
+    };
+    ticket_counts(deps.storage).remove(key);
+
+    let amount = state
+        .ticket_price
+        .amount
+        .checked_mul(Uint128::from(count))?;
+    let res = Response {
+        submessages: vec![],
+        messages: vec![BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), &state.ticket_price.denom),
+        }
+        .into()],
+        attributes: vec![
+            attr("action", "refund"),
+            attr("player", info.sender),
+            attr("tickets", count),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// The winning ticket number: the first 8 bytes of `sha256(secret || ticket_count)`, read as
+/// big endian number, modulo the ticket count. `ticket_count` must not be zero.
+pub fn select_winner(secret: &[u8], ticket_count: u64) -> u64 {
+    let digest = Sha256::new()
+        .chain(secret)
+        .chain(ticket_count.to_be_bytes())
+        .finalize();
+    let number = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    number % ticket_count
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => {
+            let state = config_read(deps.storage).load()?;
+            to_binary(&ConfigResponse {
+                admin: state.admin.to_string(),
+                phase: state.phase(env.block.height),
+                ticket_price: state.ticket_price,
+                sale_end_height: state.sale_end_height,
+                reveal_deadline_height: state.reveal_deadline_height,
+                commitment: state.commitment,
+                ticket_count: state.ticket_count,
+                secret: state.secret,
+                winner: state.winner.map(|winner| winner.to_string()),
+            })
+        }
+        QueryMsg::Tickets { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let count = ticket_counts_read(deps.storage)
+                .may_load(address.as_str().as_bytes())?
+                .unwrap_or_default();
+            to_binary(&TicketsResponse { count })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coin, from_binary, CosmosMsg, OwnedDeps};
+
+    const SECRET: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+    fn commitment_of(secret: &str) -> String {
+        hex::encode(Sha256::digest(&hex::decode(secret).unwrap()))
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            ticket_price: coin(10, "ucosm"),
+            sale_end_height: 200,
+            reveal_deadline_height: 300,
+        };
+        instantiate(deps.as_mut(), env_at(100), mock_info("admin", &[]), msg).unwrap();
+        deps
+    }
+
+    /// A lottery with a commitment to `SECRET` and the given tickets sold
+    fn setup_sold(players: &[&str]) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Commit {
+            commitment: commitment_of(SECRET),
+        };
+        run(deps.as_mut(), 100, "admin", msg).unwrap();
+        for player in players {
+            buy(deps.as_mut(), 150, player).unwrap();
+        }
+        deps
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn buy(deps: DepsMut, height: u64, player: &str) -> Result<Response, ContractError> {
+        let info = mock_info(player, &[coin(10, "ucosm")]);
+        execute(deps, env_at(height), info, ExecuteMsg::BuyTicket {})
+    }
+
+    fn reveal(secret: &str) -> ExecuteMsg {
+        ExecuteMsg::Reveal {
+            secret: secret.to_string(),
+        }
+    }
+
+    fn status(deps: Deps, height: u64) -> ConfigResponse {
+        from_binary(&query(deps, env_at(height), QueryMsg::Config {}).unwrap()).unwrap()
+    }
+
+    fn assert_wrong_phase(res: Result<Response, ContractError>, expected: Phase) {
+        match res.unwrap_err() {
+            ContractError::WrongPhase { phase } => assert_eq!(phase, expected),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn validates_config() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = |price, sale_end_height, reveal_deadline_height| InstantiateMsg {
+            admin: Some("admin".to_string()),
+            ticket_price: coin(price, "ucosm"),
+            sale_end_height,
+            reveal_deadline_height,
+        };
+        for &(sale_end, deadline) in &[(100, 300), (200, 200), (200, 150)] {
+            let info = mock_info("creator", &[]);
+            match instantiate(
+                deps.as_mut(),
+                env_at(100),
+                info,
+                msg(10, sale_end, deadline),
+            )
+            .unwrap_err()
+            {
+                ContractError::InvalidHeights {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        let info = mock_info("creator", &[]);
+        match instantiate(deps.as_mut(), env_at(100), info, msg(0, 200, 300)).unwrap_err() {
+            ContractError::ZeroPrice {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env_at(100), info, msg(10, 200, 300)).unwrap();
+        let res = status(deps.as_ref(), 100);
+        assert_eq!(res.admin, "admin");
+        assert_eq!(res.phase, Phase::AwaitingCommitment);
+    }
+
+    #[test]
+    fn commitment_opens_sale() {
+        let mut deps = setup();
+        assert_wrong_phase(buy(deps.as_mut(), 100, "alice"), Phase::AwaitingCommitment);
+
+        let commit = |commitment: &str| ExecuteMsg::Commit {
+            commitment: commitment.to_string(),
+        };
+        match run(deps.as_mut(), 100, "alice", commit(&commitment_of(SECRET))).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        for commitment in &["", "zz", "0102"] {
+            match run(deps.as_mut(), 100, "admin", commit(commitment)).unwrap_err() {
+                ContractError::InvalidCommitment {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        run(deps.as_mut(), 100, "admin", commit(&commitment_of(SECRET))).unwrap();
+        assert_eq!(status(deps.as_ref(), 100).phase, Phase::Sale);
+        // the commitment is final
+        let other = commitment_of(&"03".repeat(32));
+        assert_wrong_phase(
+            run(deps.as_mut(), 101, "admin", commit(&other)),
+            Phase::Sale,
+        );
+
+        let res = buy(deps.as_mut(), 150, "alice").unwrap();
+        assert_eq!(res.attributes[2], attr("ticket", "0"));
+        buy(deps.as_mut(), 151, "bob").unwrap();
+        let res = buy(deps.as_mut(), 199, "alice").unwrap();
+        assert_eq!(res.attributes[2], attr("ticket", "2"));
+        for funds in &[vec![], vec![coin(5, "ucosm")], vec![coin(10, "uatom")]] {
+            let info = mock_info("carol", funds);
+            match execute(deps.as_mut(), env_at(150), info, ExecuteMsg::BuyTicket {}).unwrap_err() {
+                ContractError::WrongPayment { price } => assert_eq!(price, coin(10, "ucosm")),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        assert_wrong_phase(buy(deps.as_mut(), 200, "carol"), Phase::AwaitingReveal);
+
+        let msg = QueryMsg::Tickets {
+            address: "alice".to_string(),
+        };
+        let res: TicketsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.count, 2);
+        assert_eq!(status(deps.as_ref(), 200).ticket_count, 3);
+    }
+
+    #[test]
+    fn selects_winner_deterministically() {
+        let secret = hex::decode(SECRET).unwrap();
+        assert_eq!(select_winner(&secret, 1), 0);
+        for count in 1..20 {
+            let ticket = select_winner(&secret, count);
+            assert!(ticket < count);
+            assert_eq!(select_winner(&secret, count), ticket);
+        }
+        // pinned, so a changed derivation does not go unnoticed
+        assert_eq!(select_winner(&secret, 1000), 310);
+        assert_ne!(
+            select_winner(&secret, 1000),
+            select_winner(&hex::decode("03".repeat(32)).unwrap(), 1000)
+        );
+    }
+
+    #[test]
+    fn reveal_pays_winner() {
+        let players = ["alice", "bob", "carol", "bob"];
+        let mut deps = setup_sold(&players);
+        assert_wrong_phase(
+            run(deps.as_mut(), 199, "admin", reveal(SECRET)),
+            Phase::Sale,
+        );
+        match run(deps.as_mut(), 200, "alice", reveal(SECRET)).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 200, "admin", reveal(&"03".repeat(32))).unwrap_err() {
+            ContractError::WrongSecret {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 200, "admin", reveal("0202")).unwrap_err() {
+            ContractError::InvalidSecret { min: 32, max: 64 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_wrong_phase(
+            run(deps.as_mut(), 200, "alice", ExecuteMsg::Refund {}),
+            Phase::AwaitingReveal,
+        );
+
+        let ticket = select_winner(&hex::decode(SECRET).unwrap(), 4);
+        let winner = players[ticket as usize];
+        let res = run(deps.as_mut(), 299, "admin", reveal(SECRET)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: winner.to_string(),
+                amount: coins(40, "ucosm"),
+            })]
+        );
+        assert_eq!(res.attributes[1], attr("ticket", ticket.to_string()));
+
+        let res = status(deps.as_ref(), 400);
+        assert_eq!(res.phase, Phase::Drawn);
+        assert_eq!(res.winner, Some(winner.to_string()));
+        assert_eq!(res.secret, Some(SECRET.to_string()));
+        // no refunds after the draw, even past the deadline
+        assert_wrong_phase(
+            run(deps.as_mut(), 400, "alice", ExecuteMsg::Refund {}),
+            Phase::Drawn,
+        );
+        assert_wrong_phase(
+            run(deps.as_mut(), 250, "admin", reveal(SECRET)),
+            Phase::Drawn,
+        );
+    }
+
+    #[test]
+    fn refunds_without_reveal() {
+        let mut deps = setup_sold(&["alice", "bob", "alice"]);
+        assert_eq!(status(deps.as_ref(), 300).phase, Phase::Refunding);
+        assert_wrong_phase(
+            run(deps.as_mut(), 300, "admin", reveal(SECRET)),
+            Phase::Refunding,
+        );
+
+        let res = run(deps.as_mut(), 300, "alice", ExecuteMsg::Refund {}).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(20, "ucosm"),
+            })]
+        );
+        match run(deps.as_mut(), 301, "alice", ExecuteMsg::Refund {}).unwrap_err() {
+            ContractError::NothingToRefund {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = run(deps.as_mut(), 301, "bob", ExecuteMsg::Refund {}).unwrap();
+        assert_eq!(res.attributes[3], attr("amount", "10"));
+    }
+}
@@ -0,0 +1,40 @@
+use cosmwasm_std::{Coin, OverflowError, StdError};
+use thiserror::Error;
+
+use crate::state::Phase;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Ticket price must not be zero")]
+    ZeroPrice {},
+
+    #[error("Heights must be ordered: now < sale end < reveal deadline")]
+    InvalidHeights {},
+
+    #[error("Not possible while the lottery is in phase {phase:?}")]
+    WrongPhase { phase: Phase },
+
+    #[error("Commitment must be a hex encoded sha256 hash")]
+    InvalidCommitment {},
+
+    #[error("Send exactly {price} to buy a ticket")]
+    WrongPayment { price: Coin },
+
+    #[error("Secret must be {min} to {max} hex encoded bytes")]
+    InvalidSecret { min: usize, max: usize },
+
+    #[error("Secret does not match the commitment")]
+    WrongSecret {},
+
+    #[error("No tickets to refund")]
+    NothingToRefund {},
+}
@@ -0,0 +1,59 @@
+use cosmwasm_std::Coin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::Phase;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Commits to the secret and reveals it. Defaults to the instantiating address.
+    pub admin: Option<String>,
+    pub ticket_price: Coin,
+    /// Tickets are sold up to, but not including, this block height
+    pub sale_end_height: u64,
+    /// The secret can be revealed up to, but not including, this block height.
+    /// Afterwards players can refund their tickets.
+    pub reveal_deadline_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Commits to the hex encoded sha256 `commitment` of a secret, which opens the ticket
+    /// sale. Only the admin can do this, once.
+    Commit { commitment: String },
+    /// Buys one ticket for exactly `ticket_price`
+    BuyTicket {},
+    /// Reveals the hex encoded secret after the sale ended, which picks the winner and pays
+    /// them all ticket sales. Only the admin can do this.
+    Reveal { secret: String },
+    /// Pays back the signer's tickets if the secret was not revealed by the deadline
+    Refund {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the configuration, the current phase and the winner once drawn
+    Config {},
+    /// Returns the number of tickets held by `address`
+    Tickets { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub admin: String,
+    pub ticket_price: Coin,
+    pub sale_end_height: u64,
+    pub reveal_deadline_height: u64,
+    pub commitment: Option<String>,
+    pub ticket_count: u64,
+    pub phase: Phase,
+    pub secret: Option<String>,
+    pub winner: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketsResponse {
+    pub count: u64,
+}
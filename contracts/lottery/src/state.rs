@@ -0,0 +1,86 @@
+use cosmwasm_std::{Addr, Coin, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static TICKETS_KEY: &[u8] = b"tickets";
+static TICKET_COUNTS_KEY: &[u8] = b"ticket_counts";
+
+/// The stages of a lottery. All but `Drawn` follow from the block height.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Waiting for the admin to commit to a secret. No tickets are sold yet.
+    AwaitingCommitment,
+    /// Tickets are sold until `sale_end_height`
+    Sale,
+    /// Waiting for the admin to reveal the secret until `reveal_deadline_height`
+    AwaitingReveal,
+    /// The secret was revealed and the winner paid
+    Drawn,
+    /// The secret was not revealed in time, players can get their coins back
+    Refunding,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Commits to the secret and reveals it
+    pub admin: Addr,
+    pub ticket_price: Coin,
+    /// Tickets are sold up to, but not including, this block height
+    pub sale_end_height: u64,
+    /// The secret can be revealed up to, but not including, this block height
+    pub reveal_deadline_height: u64,
+    /// Hex encoded sha256 hash of the secret
+    pub commitment: Option<String>,
+    pub ticket_count: u64,
+    /// Hex encoded secret, once revealed
+    pub secret: Option<String>,
+    pub winner: Option<Addr>,
+}
+
+impl Config {
+    pub fn phase(&self, height: u64) -> Phase {
+        if self.secret.is_some() {
+            Phase::Drawn
+        } else if height >= self.reveal_deadline_height {
+            Phase::Refunding
+        } else if height >= self.sale_end_height {
+            Phase::AwaitingReveal
+        } else if self.commitment.is_some() {
+            Phase::Sale
+        } else {
+            Phase::AwaitingCommitment
+        }
+    }
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Ticket holders by big endian ticket number
+pub fn tickets(storage: &mut dyn Storage) -> Bucket<'_, Addr> {
+    bucket(storage, TICKETS_KEY)
+}
+
+pub fn tickets_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Addr> {
+    bucket_read(storage, TICKETS_KEY)
+}
+
+/// Number of tickets held by each player, used for refunds
+pub fn ticket_counts(storage: &mut dyn Storage) -> Bucket<'_, u64> {
+    bucket(storage, TICKET_COUNTS_KEY)
+}
+
+pub fn ticket_counts_read(storage: &dyn Storage) -> ReadonlyBucket<'_, u64> {
+    bucket_read(storage, TICKET_COUNTS_KEY)
+}
@@ -0,0 +1,307 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Uint128,
+};
+use cw_erc20::AllowanceResponse;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, Recipient};
+use crate::state::{config, config_read, Config, MAX_RECIPIENTS};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.max_recipients == 0 || msg.max_recipients > MAX_RECIPIENTS {
+        return Err(ContractError::InvalidMaxRecipients {
+            max: MAX_RECIPIENTS,
+        });
+    }
+    let state = Config {
+        max_recipients: msg.max_recipients,
+    };
+    config(deps.storage).save(&state)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Send {
+            token,
+            recipients,
+            batch,
+        } => try_send(deps, env, info, token, recipients, batch),
+    }
+}
+
+/// Send
+///
+/// Transfers tokens from the signer to every recipient, using the signer's allowance for this
+/// contract. The allowance is checked up front, so a short allowance fails before any
+/// transfer is attempted.
+///
+/// @param token the erc20 contract
+/// @param recipients the addresses and amounts to transfer
+/// @param batch send all transfers as a single erc20 `Batch` message
+fn try_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token: String,
+    recipients: Vec<Recipient>,
+    batch: bool,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let token = deps.api.addr_validate(&token)?;
+    let (transfers, total) = validate_recipients(deps.as_ref(), &state, recipients)?;
+
+    let query = cw_erc20::QueryMsg::Allowance {
+        owner: info.sender.to_string(),
+        spender: env.contract.address.to_string(),
+    };
+    let allowance: AllowanceResponse = deps.querier.query_wasm_smart(&token, &query)?;
+    if allowance.allowance < total {
+        return Err(ContractError::InsufficientAllowance {
+            allowance: allowance.allowance,
+            total,
+        });
+    }
+
+    let count = transfers.len();
+    let transfers = transfers.into_iter().map(|(recipient, amount)| {
+        cw_erc20::ExecuteMsg::transfer_from(info.sender.as_str(), recipient.as_str(), amount)
+    });
+    let messages = if batch {
+        let msgs = transfers.collect();
+        vec![cw_erc20::ExecuteMsg::Batch { msgs }.into_cosmos_msg(&token)?]
+    } else {
+        transfers
+            .map(|transfer| transfer.into_cosmos_msg(&token))
+            .collect::<StdResult<_>>()?
+    };
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", "send"),
+            attr("token", token),
+            attr("sender", info.sender),
+            attr("recipients", count),
+            attr("total", total),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Checks the list against the config and returns the validated transfers with their total
+fn validate_recipients(
+    deps: Deps,
+    state: &Config,
+    recipients: Vec<Recipient>,
+) -> Result<(Vec<(Addr, Uint128)>, Uint128), ContractError> {
+    if recipients.is_empty() {
+        return Err(ContractError::NoRecipients {});
+    }
+    if recipients.len() > state.max_recipients as usize {
+        return Err(ContractError::TooManyRecipients {
+            count: recipients.len(),
+            max: state.max_recipients,
+        });
+    }
+
+    let mut transfers: Vec<(Addr, Uint128)> = vec![];
+    let mut total = Uint128::zero();
+    for recipient in recipients {
+        let address = deps.api.addr_validate(&recipient.address)?;
+        if recipient.amount.is_zero() {
+            return Err(ContractError::ZeroAmount {
+                recipient: address.to_string(),
+            });
+        }
+        if transfers.iter().any(|(existing, _)| *existing == address) {
+            return Err(ContractError::DuplicateRecipient {
+                recipient: address.to_string(),
+            });
+        }
+        total = total.checked_add(recipient.amount)?;
+        transfers.push((address, recipient.amount));
+    }
+    Ok((transfers, total))
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{
+        from_binary, from_slice, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+        QueryRequest, SystemError, SystemResult, WasmQuery,
+    };
+
+    /// Answers allowance queries of "alice" for this contract, at any token
+    struct AllowanceQuerier {
+        allowance: u128,
+    }
+
+    impl Querier for AllowanceQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            match request {
+                QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) => {
+                    assert_eq!(
+                        from_binary::<cw_erc20::QueryMsg>(&msg).unwrap(),
+                        cw_erc20::QueryMsg::Allowance {
+                            owner: "alice".to_string(),
+                            spender: MOCK_CONTRACT_ADDR.to_string(),
+                        }
+                    );
+                    let res = to_binary(&AllowanceResponse {
+                        allowance: Uint128::from(self.allowance),
+                        expires: cw_erc20::Expiration::Never {},
+                    });
+                    SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "non-wasm".to_string(),
+                }),
+            }
+        }
+    }
+
+    fn setup(allowance: u128) -> OwnedDeps<MockStorage, MockApi, AllowanceQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: AllowanceQuerier { allowance },
+        };
+        let msg = InstantiateMsg { max_recipients: 3 };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn recipients(list: &[(&str, u128)]) -> Vec<Recipient> {
+        list.iter()
+            .map(|(address, amount)| Recipient {
+                address: address.to_string(),
+                amount: Uint128::from(*amount),
+            })
+            .collect()
+    }
+
+    fn send(deps: DepsMut, list: &[(&str, u128)], batch: bool) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Send {
+            token: "token".to_string(),
+            recipients: recipients(list),
+            batch,
+        };
+        execute(deps, mock_env(), mock_info("alice", &[]), msg)
+    }
+
+    #[test]
+    fn validates_max_recipients() {
+        for max_recipients in &[0, MAX_RECIPIENTS + 1] {
+            let mut deps = setup(0);
+            let msg = InstantiateMsg {
+                max_recipients: *max_recipients,
+            };
+            match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+                .unwrap_err()
+            {
+                ContractError::InvalidMaxRecipients { max: 100 } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn sends_one_transfer_per_recipient() {
+        let mut deps = setup(60);
+        let res = send(deps.as_mut(), &[("bob", 10), ("carol", 50)], false).unwrap();
+        let transfers = vec![
+            cw_erc20::ExecuteMsg::transfer_from("alice", "bob", 10u128),
+            cw_erc20::ExecuteMsg::transfer_from("alice", "carol", 50u128),
+        ];
+        assert_eq!(
+            res.messages,
+            transfers
+                .iter()
+                .map(|transfer| transfer.clone().into_cosmos_msg("token").unwrap())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "send"),
+                attr("token", "token"),
+                attr("sender", "alice"),
+                attr("recipients", "2"),
+                attr("total", "60"),
+            ]
+        );
+
+        let res = send(deps.as_mut(), &[("bob", 10), ("carol", 50)], true).unwrap();
+        let batch = cw_erc20::ExecuteMsg::Batch { msgs: transfers };
+        assert_eq!(res.messages, vec![batch.into_cosmos_msg("token").unwrap()]);
+    }
+
+    #[test]
+    fn checks_allowance_up_front() {
+        let mut deps = setup(59);
+        match send(deps.as_mut(), &[("bob", 10), ("carol", 50)], false).unwrap_err() {
+            ContractError::InsufficientAllowance { allowance, total } => {
+                assert_eq!(allowance.u128(), 59);
+                assert_eq!(total.u128(), 60);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn validates_recipients() {
+        let mut deps = setup(u128::MAX);
+        match send(deps.as_mut(), &[], false).unwrap_err() {
+            ContractError::NoRecipients {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let list = [("bob", 1), ("carol", 1), ("dave", 1), ("erin", 1)];
+        match send(deps.as_mut(), &list, false).unwrap_err() {
+            ContractError::TooManyRecipients { count: 4, max: 3 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match send(deps.as_mut(), &[("bob", 1), ("carol", 0)], false).unwrap_err() {
+            ContractError::ZeroAmount { recipient } => assert_eq!(recipient, "carol"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match send(
+            deps.as_mut(),
+            &[("bob", 1), ("carol", 1), ("bob", 2)],
+            false,
+        )
+        .unwrap_err()
+        {
+            ContractError::DuplicateRecipient { recipient } => assert_eq!(recipient, "bob"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match send(deps.as_mut(), &[("bob", u128::MAX), ("carol", 1)], false).unwrap_err() {
+            ContractError::Overflow(_) => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Max recipients must be between 1 and {max}")]
+    InvalidMaxRecipients { max: u32 },
+
+    #[error("No recipients")]
+    NoRecipients {},
+
+    #[error("Too many recipients ({count}, at most {max})")]
+    TooManyRecipients { count: usize, max: u32 },
+
+    #[error("Amount for {recipient} must not be zero")]
+    ZeroAmount { recipient: String },
+
+    #[error("Recipient {recipient} is listed twice")]
+    DuplicateRecipient { recipient: String },
+
+    #[error("Allowance of {allowance} does not cover the total of {total}")]
+    InsufficientAllowance { allowance: Uint128, total: Uint128 },
+}
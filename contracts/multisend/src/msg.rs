@@ -0,0 +1,34 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Most recipients a single `Send` may list, at most 100
+    pub max_recipients: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Recipient {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Transfers tokens of the erc20 contract `token` from the sender to every recipient. The
+    /// tokens are pulled with `TransferFrom`, so the sender has to approve this contract for
+    /// the total first. With `batch` set, all transfers go out as a single erc20 `Batch`.
+    Send {
+        token: String,
+        recipients: Vec<Recipient>,
+        batch: bool,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+}
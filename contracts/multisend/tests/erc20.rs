@@ -0,0 +1,93 @@
+//! Fans out transfers of two real erc20 contracts on a test chain, which reverts failing
+//! messages like a real chain.
+
+use cosmwasm_std::Uint128;
+use cw_erc20::BalanceResponse;
+use cw_multisend::contract::{execute, instantiate, query};
+use cw_multisend::msg::{ExecuteMsg, InstantiateMsg, Recipient};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const CASH: &str = "cash";
+const GOLD: &str = "gold";
+const MULTISEND: &str = "multisend";
+
+/// alice holds 1000 of both tokens
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    for token in &[CASH, GOLD] {
+        let msg = erc20_msg(&token.to_uppercase(), &[("alice", 1000)]);
+        chain.instantiate_erc20(token, &msg).unwrap();
+    }
+
+    let multisend = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg { max_recipients: 10 };
+    chain
+        .instantiate(multisend, MULTISEND, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn approve(chain: &mut Chain, token: &str, amount: u128) {
+    let msg = cw_erc20::ExecuteMsg::approve(MULTISEND, amount);
+    chain.execute(token, "alice", &msg, &[]).unwrap();
+}
+
+fn balance(chain: &Chain, token: &str, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(token, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn send(token: &str, list: &[(&str, u128)], batch: bool) -> ExecuteMsg {
+    let recipients = list
+        .iter()
+        .map(|(address, amount)| Recipient {
+            address: address.to_string(),
+            amount: Uint128::from(*amount),
+        })
+        .collect();
+    ExecuteMsg::Send {
+        token: token.to_string(),
+        recipients,
+        batch,
+    }
+}
+
+#[test]
+fn fans_out_transfers_of_several_tokens() {
+    let mut chain = setup();
+    approve(&mut chain, CASH, 600);
+    approve(&mut chain, GOLD, 60);
+
+    let list = [("bob", 100), ("carol", 200), ("dave", 300)];
+    chain
+        .execute(MULTISEND, "alice", &send(CASH, &list, false), &[])
+        .unwrap();
+    let msg = send(GOLD, &[("bob", 10), ("erin", 50)], true);
+    chain.execute(MULTISEND, "alice", &msg, &[]).unwrap();
+
+    assert_eq!(balance(&chain, CASH, "alice"), 400);
+    assert_eq!(balance(&chain, CASH, "bob"), 100);
+    assert_eq!(balance(&chain, CASH, "carol"), 200);
+    assert_eq!(balance(&chain, CASH, "dave"), 300);
+    assert_eq!(balance(&chain, GOLD, "alice"), 940);
+    assert_eq!(balance(&chain, GOLD, "bob"), 10);
+    assert_eq!(balance(&chain, GOLD, "erin"), 50);
+    assert_eq!(balance(&chain, CASH, MULTISEND), 0);
+}
+
+#[test]
+fn rejects_send_above_allowance() {
+    let mut chain = setup();
+    approve(&mut chain, CASH, 299);
+    let msg = send(CASH, &[("bob", 100), ("carol", 200)], false);
+    let err = chain.execute(MULTISEND, "alice", &msg, &[]).unwrap_err();
+    assert_eq!(err, "Allowance of 299 does not cover the total of 300");
+    assert_eq!(balance(&chain, CASH, "alice"), 1000);
+    assert_eq!(balance(&chain, CASH, "bob"), 0);
+
+    // the allowance is per token
+    let msg = send(GOLD, &[("bob", 1)], false);
+    let err = chain.execute(MULTISEND, "alice", &msg, &[]).unwrap_err();
+    assert_eq!(err, "Allowance of 0 does not cover the total of 1");
+}
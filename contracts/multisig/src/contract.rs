@@ -0,0 +1,550 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult,
+};
+
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, ProposalListResponse, ProposalResponse, ProposalStatus, QueryMsg,
+    VoteListResponse,
+};
+use crate::state::{
+    config, config_read, proposal_count, proposal_count_read, proposals, proposals_read, Config,
+    Proposal,
+};
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let mut members: Vec<Addr> = vec![];
+    for member in &msg.members {
+        let member = deps.api.addr_validate(member)?;
+        if members.contains(&member) {
+            return Err(ContractError::DuplicateMember {
+                member: member.to_string(),
+            });
+        }
+        members.push(member);
+    }
+    if msg.threshold == 0 || msg.threshold > members.len() as u64 {
+        return Err(ContractError::InvalidThreshold {
+            threshold: msg.threshold,
+            members: members.len(),
+        });
+    }
+    if msg.voting_period == 0 {
+        return Err(ContractError::InvalidVotingPeriod {});
+    }
+
+    let state = Config {
+        members,
+        threshold: msg.threshold,
+        voting_period: msg.voting_period,
+    };
+    config(deps.storage).save(&state)?;
+    proposal_count(deps.storage).save(&0)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Propose { title, msgs } => try_propose(deps, env, info, title, msgs),
+        ExecuteMsg::Vote { proposal_id } => try_vote(deps, env, info, proposal_id),
+        ExecuteMsg::Execute { proposal_id } => try_execute(deps, env, proposal_id),
+    }
+}
+
+/// Propose
+///
+/// Creates a proposal to execute messages from this contract, voted for by the proposer.
+/// Only members can do this.
+///
+/// @param title a description of the proposal
+/// @param msgs the messages to execute once the proposal passed
+fn try_propose(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    msgs: Vec<CosmosMsg>,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if !state.members.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if msgs.is_empty() {
+        return Err(ContractError::EmptyProposal {});
+    }
+
+    let proposal_id = proposal_count_read(deps.storage).load()?;
+    proposal_count(deps.storage).save(&(proposal_id + 1))?;
+    let proposal = Proposal {
+        title,
+        msgs,
+        proposer: info.sender.clone(),
+        expires: env.block.height + state.voting_period,
+        votes: vec![info.sender.clone()],
+        executed: false,
+    };
+    proposals(deps.storage).save(&proposal_id.to_be_bytes(), &proposal)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "propose"),
+            attr("proposal_id", proposal_id),
+            attr("proposer", info.sender),
+            attr("expires", proposal.expires),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Vote
+///
+/// Adds the signer's vote to an open proposal. Only members can do this.
+///
+/// @param proposal_id the proposal
+fn try_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if !state.members.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut proposal = load_active_proposal(deps.as_ref(), &env, proposal_id)?;
+    if proposal.votes.contains(&info.sender) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    proposal.votes.push(info.sender.clone());
+    proposals(deps.storage).save(&proposal_id.to_be_bytes(), &proposal)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "vote"),
+            attr("proposal_id", proposal_id),
+            attr("voter", info.sender),
+            attr("votes", proposal.votes.len()),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Execute
+///
+/// Emits the messages of a proposal that reached the threshold.
+///
+/// @param proposal_id the proposal
+fn try_execute(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let mut proposal = load_active_proposal(deps.as_ref(), &env, proposal_id)?;
+    let votes = proposal.votes.len() as u64;
+    if votes < state.threshold {
+        return Err(ContractError::ThresholdNotReached {
+            votes,
+            threshold: state.threshold,
+        });
+    }
+    proposal.executed = true;
+    proposals(deps.storage).save(&proposal_id.to_be_bytes(), &proposal)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: proposal.msgs,
+        attributes: vec![attr("action", "execute"), attr("proposal_id", proposal_id)],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Loads a proposal which is neither executed nor expired
+fn load_active_proposal(
+    deps: Deps,
+    env: &Env,
+    proposal_id: u64,
+) -> Result<Proposal, ContractError> {
+    let proposal = proposals_read(deps.storage)
+        .may_load(&proposal_id.to_be_bytes())?
+        .ok_or(ContractError::ProposalNotFound { proposal_id })?;
+    if proposal.executed {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    if env.block.height >= proposal.expires {
+        return Err(ContractError::Expired {
+            expires: proposal.expires,
+        });
+    }
+    Ok(proposal)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Proposal { proposal_id } => to_binary(&query_proposal(deps, &env, proposal_id)?),
+        QueryMsg::ListProposals { start_after, limit } => {
+            to_binary(&query_list_proposals(deps, &env, start_after, limit)?)
+        }
+        QueryMsg::ListVotes {
+            proposal_id,
+            start_after,
+            limit,
+        } => to_binary(&query_list_votes(deps, proposal_id, start_after, limit)?),
+    }
+}
+
+fn query_proposal(deps: Deps, env: &Env, proposal_id: u64) -> StdResult<ProposalResponse> {
+    let state = config_read(deps.storage).load()?;
+    let proposal = proposals_read(deps.storage)
+        .may_load(&proposal_id.to_be_bytes())?
+        .ok_or_else(|| StdError::not_found("Proposal"))?;
+    let votes = proposal.votes.len() as u64;
+    let status = if proposal.executed {
+        ProposalStatus::Executed
+    } else if env.block.height >= proposal.expires {
+        ProposalStatus::Expired
+    } else if votes >= state.threshold {
+        ProposalStatus::Passed
+    } else {
+        ProposalStatus::Open
+    };
+    Ok(ProposalResponse {
+        id: proposal_id,
+        title: proposal.title,
+        msgs: proposal.msgs,
+        proposer: proposal.proposer.to_string(),
+        expires: proposal.expires,
+        votes,
+        status,
+    })
+}
+
+fn query_list_proposals(
+    deps: Deps,
+    env: &Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64;
+    let count = proposal_count_read(deps.storage).load()?;
+    let start = start_after.map_or(0, |id| id.saturating_add(1));
+    let end = start.saturating_add(limit).min(count);
+    let proposals = (start..end)
+        .map(|proposal_id| query_proposal(deps, env, proposal_id))
+        .collect::<StdResult<_>>()?;
+    Ok(ProposalListResponse { proposals })
+}
+
+fn query_list_votes(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VoteListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let proposal = proposals_read(deps.storage)
+        .may_load(&proposal_id.to_be_bytes())?
+        .ok_or_else(|| StdError::not_found("Proposal"))?;
+    let start = match start_after {
+        Some(voter) => proposal
+            .votes
+            .iter()
+            .position(|vote| vote.as_str() == voter)
+            .map_or(proposal.votes.len(), |index| index + 1),
+        None => 0,
+    };
+    let voters = proposal
+        .votes
+        .iter()
+        .skip(start)
+        .take(limit)
+        .map(|voter| voter.to_string())
+        .collect();
+    Ok(VoteListResponse { voters })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coins, from_binary, BankMsg, OwnedDeps};
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn instantiate_msg(threshold: u64) -> InstantiateMsg {
+        InstantiateMsg {
+            members: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+            threshold,
+            voting_period: 10,
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env_at(100), info, instantiate_msg(2)).unwrap();
+        deps
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn payout() -> CosmosMsg {
+        BankMsg::Send {
+            to_address: "dave".to_string(),
+            amount: coins(5, "earth"),
+        }
+        .into()
+    }
+
+    fn propose(deps: DepsMut, height: u64) -> u64 {
+        let msg = ExecuteMsg::Propose {
+            title: "Pay dave".to_string(),
+            msgs: vec![payout()],
+        };
+        let res = run(deps, height, "alice", msg).unwrap();
+        res.attributes[1].value.parse().unwrap()
+    }
+
+    fn proposal(deps: Deps, height: u64, proposal_id: u64) -> ProposalResponse {
+        let msg = QueryMsg::Proposal { proposal_id };
+        from_binary(&query(deps, env_at(height), msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn instantiate_validates_config() {
+        for threshold in &[0, 4] {
+            let mut deps = mock_dependencies(&[]);
+            let info = mock_info("creator", &[]);
+            let msg = instantiate_msg(*threshold);
+            match instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err() {
+                ContractError::InvalidThreshold { members: 3, .. } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        let mut deps = mock_dependencies(&[]);
+        let mut msg = instantiate_msg(2);
+        msg.members.push("alice".to_string());
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::DuplicateMember { member } => assert_eq!(member, "alice"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let mut msg = instantiate_msg(2);
+        msg.voting_period = 0;
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::InvalidVotingPeriod {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn members_propose_and_vote() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Propose {
+            title: "Pay dave".to_string(),
+            msgs: vec![payout()],
+        };
+        match run(deps.as_mut(), 100, "dave", msg).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::Propose {
+            title: "Nothing".to_string(),
+            msgs: vec![],
+        };
+        match run(deps.as_mut(), 100, "alice", msg).unwrap_err() {
+            ContractError::EmptyProposal {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let proposal_id = propose(deps.as_mut(), 100);
+        let res = proposal(deps.as_ref(), 100, proposal_id);
+        assert_eq!(res.votes, 1);
+        assert_eq!(res.expires, 110);
+        assert_eq!(res.status, ProposalStatus::Open);
+
+        let vote = ExecuteMsg::Vote { proposal_id };
+        for voter in &["alice", "dave"] {
+            let err = run(deps.as_mut(), 101, voter, vote.clone()).unwrap_err();
+            match (*voter, err) {
+                ("alice", ContractError::AlreadyVoted {}) => {}
+                ("dave", ContractError::Unauthorized {}) => {}
+                (_, e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+        run(deps.as_mut(), 101, "bob", vote.clone()).unwrap();
+        assert_eq!(
+            proposal(deps.as_ref(), 101, proposal_id).status,
+            ProposalStatus::Passed
+        );
+        match run(deps.as_mut(), 102, "bob", vote).unwrap_err() {
+            ContractError::AlreadyVoted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn executes_passed_proposals_once() {
+        let mut deps = setup();
+        let proposal_id = propose(deps.as_mut(), 100);
+        let exec = ExecuteMsg::Execute { proposal_id };
+        match run(deps.as_mut(), 101, "anyone", exec.clone()).unwrap_err() {
+            ContractError::ThresholdNotReached {
+                votes: 1,
+                threshold: 2,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        run(
+            deps.as_mut(),
+            101,
+            "carol",
+            ExecuteMsg::Vote { proposal_id },
+        )
+        .unwrap();
+        let res = run(deps.as_mut(), 102, "anyone", exec.clone()).unwrap();
+        assert_eq!(res.messages, vec![payout()]);
+        assert_eq!(
+            proposal(deps.as_ref(), 102, proposal_id).status,
+            ProposalStatus::Executed
+        );
+
+        match run(deps.as_mut(), 103, "anyone", exec).unwrap_err() {
+            ContractError::AlreadyExecuted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 103, "bob", ExecuteMsg::Vote { proposal_id }).unwrap_err() {
+            ContractError::AlreadyExecuted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_expired_proposals() {
+        let mut deps = setup();
+        let proposal_id = propose(deps.as_mut(), 100);
+        run(deps.as_mut(), 109, "bob", ExecuteMsg::Vote { proposal_id }).unwrap();
+
+        assert_eq!(
+            proposal(deps.as_ref(), 110, proposal_id).status,
+            ProposalStatus::Expired
+        );
+        match run(
+            deps.as_mut(),
+            110,
+            "anyone",
+            ExecuteMsg::Execute { proposal_id },
+        )
+        .unwrap_err()
+        {
+            ContractError::Expired { expires: 110 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(
+            deps.as_mut(),
+            110,
+            "carol",
+            ExecuteMsg::Vote { proposal_id },
+        )
+        .unwrap_err()
+        {
+            ContractError::Expired { expires: 110 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(
+            deps.as_mut(),
+            110,
+            "carol",
+            ExecuteMsg::Vote { proposal_id: 1 },
+        )
+        .unwrap_err()
+        {
+            ContractError::ProposalNotFound { proposal_id: 1 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn lists_proposals_and_votes() {
+        let mut deps = setup();
+        for _ in 0..3 {
+            propose(deps.as_mut(), 100);
+        }
+        run(
+            deps.as_mut(),
+            101,
+            "carol",
+            ExecuteMsg::Vote { proposal_id: 1 },
+        )
+        .unwrap();
+        run(
+            deps.as_mut(),
+            101,
+            "bob",
+            ExecuteMsg::Vote { proposal_id: 1 },
+        )
+        .unwrap();
+
+        let list = |start_after, limit| {
+            let msg = QueryMsg::ListProposals { start_after, limit };
+            let res: ProposalListResponse =
+                from_binary(&query(deps.as_ref(), env_at(101), msg).unwrap()).unwrap();
+            res.proposals.iter().map(|p| p.id).collect::<Vec<_>>()
+        };
+        assert_eq!(list(None, None), vec![0, 1, 2]);
+        assert_eq!(list(None, Some(2)), vec![0, 1]);
+        assert_eq!(list(Some(1), Some(2)), vec![2]);
+        assert!(list(Some(2), None).is_empty());
+
+        let votes = |start_after: Option<&str>, limit| {
+            let msg = QueryMsg::ListVotes {
+                proposal_id: 1,
+                start_after: start_after.map(|voter| voter.to_string()),
+                limit,
+            };
+            let res: VoteListResponse =
+                from_binary(&query(deps.as_ref(), env_at(101), msg).unwrap()).unwrap();
+            res.voters
+        };
+        assert_eq!(votes(None, None), vec!["alice", "carol", "bob"]);
+        assert_eq!(votes(None, Some(1)), vec!["alice"]);
+        assert_eq!(votes(Some("alice"), Some(1)), vec!["carol"]);
+        assert!(votes(Some("bob"), None).is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Threshold {threshold} must be between 1 and the {members} members")]
+    InvalidThreshold { threshold: u64, members: usize },
+
+    #[error("Member {member} is listed twice")]
+    DuplicateMember { member: String },
+
+    #[error("Voting period must not be zero")]
+    InvalidVotingPeriod {},
+
+    #[error("Proposal has no messages")]
+    EmptyProposal {},
+
+    #[error("Proposal {proposal_id} not found")]
+    ProposalNotFound { proposal_id: u64 },
+
+    #[error("Proposal expired at height {expires}")]
+    Expired { expires: u64 },
+
+    #[error("Proposal was already executed")]
+    AlreadyExecuted {},
+
+    #[error("Already voted")]
+    AlreadyVoted {},
+
+    #[error("Proposal has {votes} votes but needs {threshold}")]
+    ThresholdNotReached { votes: u64, threshold: u64 },
+}
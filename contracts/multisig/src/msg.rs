@@ -0,0 +1,81 @@
+use cosmwasm_std::CosmosMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub members: Vec<String>,
+    pub threshold: u64,
+    /// Blocks after which proposals expire
+    pub voting_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Proposes executing `msgs` from this contract. Counts as the proposer's vote.
+    Propose {
+        title: String,
+        msgs: Vec<CosmosMsg>,
+    },
+    Vote {
+        proposal_id: u64,
+    },
+    /// Executes the messages of a proposal with enough votes. Anyone can do this.
+    Execute {
+        proposal_id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Proposal {
+        proposal_id: u64,
+    },
+    /// Proposals with ids greater than `start_after`, oldest first
+    ListProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Members who voted for a proposal, after the voter `start_after` in voting order
+    ListVotes {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// Waiting for votes
+    Open,
+    /// Has enough votes to be executed
+    Passed,
+    Executed,
+    /// Expired before it was executed
+    Expired,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResponse {
+    pub id: u64,
+    pub title: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub proposer: String,
+    pub expires: u64,
+    pub votes: u64,
+    pub status: ProposalStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalListResponse {
+    pub proposals: Vec<ProposalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteListResponse {
+    pub voters: Vec<String>,
+}
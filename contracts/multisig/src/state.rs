@@ -0,0 +1,58 @@
+use cosmwasm_std::{Addr, CosmosMsg, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static PROPOSAL_COUNT_KEY: &[u8] = b"proposal_count";
+static PROPOSALS_KEY: &[u8] = b"proposals";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub members: Vec<Addr>,
+    /// Votes a proposal needs before it can be executed
+    pub threshold: u64,
+    /// Proposals expire this many blocks after they were proposed
+    pub voting_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Proposal {
+    pub title: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub proposer: Addr,
+    /// Height from which the proposal can no longer be voted on or executed
+    pub expires: u64,
+    /// Members who voted for the proposal, in voting order. The proposer votes first.
+    pub votes: Vec<Addr>,
+    pub executed: bool,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Number of proposals created so far, which is also the id of the next one
+pub fn proposal_count(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, PROPOSAL_COUNT_KEY)
+}
+
+pub fn proposal_count_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, PROPOSAL_COUNT_KEY)
+}
+
+/// Proposals by big endian id
+pub fn proposals(storage: &mut dyn Storage) -> Bucket<'_, Proposal> {
+    bucket(storage, PROPOSALS_KEY)
+}
+
+pub fn proposals_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Proposal> {
+    bucket_read(storage, PROPOSALS_KEY)
+}
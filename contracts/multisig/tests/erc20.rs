@@ -0,0 +1,98 @@
+//! Runs a multisig holding the minter role of a real erc20 contract on a test chain, which
+//! reverts failing messages like a real chain.
+
+use cosmwasm_std::{to_binary, CosmosMsg, WasmMsg};
+use cw_erc20::BalanceResponse;
+use cw_multisig::contract::{execute, instantiate, query};
+use cw_multisig::msg::{ExecuteMsg, InstantiateMsg, ProposalResponse, ProposalStatus, QueryMsg};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const MULTISIG: &str = "multisig";
+
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(100);
+    let msg = cw_erc20::InstantiateMsg {
+        minter: Some(MULTISIG.to_string()),
+        ..erc20_msg("CASH", &[])
+    };
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let multisig = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        members: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+        threshold: 2,
+        voting_period: 50,
+    };
+    chain
+        .instantiate(multisig, MULTISIG, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn proposal(chain: &Chain, proposal_id: u64) -> ProposalResponse {
+    let msg = QueryMsg::Proposal { proposal_id };
+    chain.query(MULTISIG, &msg).unwrap()
+}
+
+fn token_msg(msg: cw_erc20::ExecuteMsg) -> CosmosMsg {
+    WasmMsg::Execute {
+        contract_addr: TOKEN.to_string(),
+        msg: to_binary(&msg).unwrap(),
+        send: vec![],
+    }
+    .into()
+}
+
+#[test]
+fn mints_through_approved_proposal() {
+    let mut chain = setup();
+    // mint 50 tokens to dave at once: 5 per block from block 100 to block 110
+    let propose = ExecuteMsg::Propose {
+        title: "Mint 50 tokens to dave".to_string(),
+        msgs: vec![
+            token_msg(cw_erc20::ExecuteMsg::set_mint_schedule(
+                "dave", 5u128, 100, 110,
+            )),
+            token_msg(cw_erc20::ExecuteMsg::execute_mint_schedule(0)),
+        ],
+    };
+    chain.execute(MULTISIG, "alice", &propose, &[]).unwrap();
+
+    chain.set_height(105);
+    let msg = ExecuteMsg::Vote { proposal_id: 0 };
+    chain.execute(MULTISIG, "carol", &msg, &[]).unwrap();
+    assert_eq!(proposal(&chain, 0).status, ProposalStatus::Passed);
+    assert_eq!(balance(&chain, "dave"), 0);
+
+    chain.set_height(110);
+    let msg = ExecuteMsg::Execute { proposal_id: 0 };
+    chain.execute(MULTISIG, "anyone", &msg, &[]).unwrap();
+    assert_eq!(balance(&chain, "dave"), 50);
+    assert_eq!(proposal(&chain, 0).status, ProposalStatus::Executed);
+}
+
+#[test]
+fn failing_proposal_is_not_marked_executed() {
+    let mut chain = setup();
+    // there is no such schedule
+    let propose = ExecuteMsg::Propose {
+        title: "Mint to dave".to_string(),
+        msgs: vec![token_msg(cw_erc20::ExecuteMsg::execute_mint_schedule(0))],
+    };
+    chain.execute(MULTISIG, "alice", &propose, &[]).unwrap();
+    let msg = ExecuteMsg::Vote { proposal_id: 0 };
+    chain.execute(MULTISIG, "carol", &msg, &[]).unwrap();
+
+    let msg = ExecuteMsg::Execute { proposal_id: 0 };
+    chain.execute(MULTISIG, "anyone", &msg, &[]).unwrap_err();
+    assert_eq!(proposal(&chain, 0).status, ProposalStatus::Passed);
+    assert_eq!(balance(&chain, "dave"), 0);
+}
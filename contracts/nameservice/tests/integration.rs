@@ -1,23 +1,8 @@
 //! This integration test tries to run and call the generated wasm.
 //! It depends on a Wasm build being available, which you can create with `cargo wasm`.
 //! Then running `cargo integration-test` will validate we can properly call into that generated Wasm.
-//!
-//! You can easily convert unit tests to integration tests as follows:
-//! 1. Copy them over verbatim
-//! 2. Then change
-//!      let mut deps = mock_dependencies(20, &[]);
-//!    to
-//!      let mut deps = mock_instance(WASM, &[]);
-//! 3. If you access raw storage, where ever you see something like:
-//!      deps.storage.get(CONFIG_KEY).expect("no data stored");
-//!    replace it with:
-//!      deps.with_storage(|store| {
-//!          let data = store.get(CONFIG_KEY).expect("no data stored");
-//!          //...
-//!      });
-//! 4. Anywhere you see query(&deps, ...) you must replace it with query(&mut deps, ...)
-
-use cosmwasm_std::{coin, coins, from_binary, Coin, Response};
+
+use cosmwasm_std::{coin, coins, from_binary, Coin, ContractResult, Response};
 use cosmwasm_storage::to_length_prefixed;
 use cosmwasm_vm::testing::{
     execute, instantiate, mock_env, mock_instance, query, MockApi, MockQuerier, MockStorage,
@@ -34,12 +19,12 @@ static WASM: &[u8] = include_bytes!("../target/wasm32-unknown-unknown/release/cw
 // static WASM: &[u8] = include_bytes!("../contract.wasm");
 
 fn assert_name_owner(
-    mut deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
+    deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
     name: &str,
     owner: &str,
 ) {
     let res = query(
-        &mut deps,
+        deps,
         mock_env(),
         QueryMsg::ResolveRecord {
             name: name.to_string(),
@@ -52,7 +37,7 @@ fn assert_name_owner(
 }
 
 fn mock_instantiate_with_price(
-    mut deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
+    deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
     purchase_price: Coin,
     transfer_price: Coin,
 ) {
@@ -63,10 +48,10 @@ fn mock_instantiate_with_price(
 
     let params = mock_info("creator", &coins(2, "token"));
     // unwrap: contract successfully executes InstantiateMsg
-    let _res: Response = instantiate(&mut deps, mock_env(), params, msg).unwrap();
+    let _res: Response = instantiate(deps, mock_env(), params, msg).unwrap();
 }
 
-fn mock_instantiate_no_price(mut deps: &mut Instance<MockApi, MockStorage, MockQuerier>) {
+fn mock_instantiate_no_price(deps: &mut Instance<MockApi, MockStorage, MockQuerier>) {
     let msg = InstantiateMsg {
         purchase_price: None,
         transfer_price: None,
@@ -74,11 +59,11 @@ fn mock_instantiate_no_price(mut deps: &mut Instance<MockApi, MockStorage, MockQ
 
     let params = mock_info("creator", &coins(2, "token"));
     // unwrap: contract successfully executes InstantiateMsg
-    let _res: Response = instantiate(&mut deps, mock_env(), params, msg).unwrap();
+    let _res: Response = instantiate(deps, mock_env(), params, msg).unwrap();
 }
 
 fn mock_alice_registers_name(
-    mut deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
+    deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
     sent: &[Coin],
 ) {
     // alice can register an available name
@@ -87,7 +72,7 @@ fn mock_alice_registers_name(
         name: "alice".to_string(),
     };
     // unwrap: contract successfully executes Register message
-    let _res: Response = execute(&mut deps, mock_env(), params, msg).unwrap();
+    let _res: Response = execute(deps, mock_env(), params, msg).unwrap();
 }
 
 #[test]
@@ -185,3 +170,95 @@ fn returns_empty_on_query_unregistered_name() {
     let value: ResolveRecordResponse = from_binary(&res).unwrap();
     assert_eq!(None, value.address);
 }
+
+#[test]
+fn fails_on_register_insufficient_fees() {
+    let mut deps = mock_instance(WASM, &[]);
+    mock_instantiate_with_price(&mut deps, coin(2, "token"), coin(2, "token"));
+
+    // too little, or the wrong denom
+    for sent in &[coins(1, "token"), coins(2, "earth"), vec![]] {
+        let params = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Register {
+            name: "alice".to_string(),
+        };
+        let res: ContractResult<Response> = execute(&mut deps, mock_env(), params, msg);
+        assert_eq!(res.unwrap_err(), "Insufficient funds sent");
+    }
+
+    let res = query(
+        &mut deps,
+        mock_env(),
+        QueryMsg::ResolveRecord {
+            name: "alice".to_string(),
+        },
+    )
+    .unwrap();
+    let value: ResolveRecordResponse = from_binary(&res).unwrap();
+    assert_eq!(None, value.address);
+}
+
+#[test]
+fn fails_on_register_already_taken_name() {
+    let mut deps = mock_instance(WASM, &[]);
+    mock_instantiate_no_price(&mut deps);
+    mock_alice_registers_name(&mut deps, &[]);
+
+    let params = mock_info("bob_key", &[]);
+    let msg = ExecuteMsg::Register {
+        name: "alice".to_string(),
+    };
+    let res: ContractResult<Response> = execute(&mut deps, mock_env(), params, msg);
+    assert_eq!(res.unwrap_err(), "Name has been taken (name alice)");
+
+    assert_name_owner(&mut deps, "alice", "alice_key");
+}
+
+#[test]
+fn fails_on_register_invalid_name() {
+    let mut deps = mock_instance(WASM, &[]);
+    mock_instantiate_no_price(&mut deps);
+
+    let params = mock_info("bob_key", &[]);
+    let msg = ExecuteMsg::Register {
+        name: "Bob".to_string(),
+    };
+    let res: ContractResult<Response> = execute(&mut deps, mock_env(), params, msg);
+    assert!(res.unwrap_err().starts_with("Invalid character"));
+}
+
+#[test]
+fn transfer_works_with_fees() {
+    let mut deps = mock_instance(WASM, &[]);
+    mock_instantiate_with_price(&mut deps, coin(2, "token"), coin(5, "token"));
+    mock_alice_registers_name(&mut deps, &coins(2, "token"));
+
+    let msg = ExecuteMsg::Transfer {
+        name: "alice".to_string(),
+        to: "bob_key".to_string(),
+    };
+
+    // the transfer price applies
+    let params = mock_info("alice_key", &coins(4, "token"));
+    let res: ContractResult<Response> = execute(&mut deps, mock_env(), params, msg.clone());
+    assert_eq!(res.unwrap_err(), "Insufficient funds sent");
+
+    // only the owner can transfer
+    let params = mock_info("bob_key", &coins(5, "token"));
+    let res: ContractResult<Response> = execute(&mut deps, mock_env(), params, msg.clone());
+    assert_eq!(res.unwrap_err(), "Unauthorized");
+    assert_name_owner(&mut deps, "alice", "alice_key");
+
+    let params = mock_info("alice_key", &coins(5, "token"));
+    let _res: Response = execute(&mut deps, mock_env(), params, msg).unwrap();
+    assert_name_owner(&mut deps, "alice", "bob_key");
+
+    // the previous owner lost control over the name
+    let params = mock_info("alice_key", &coins(5, "token"));
+    let msg = ExecuteMsg::Transfer {
+        name: "alice".to_string(),
+        to: "alice_key".to_string(),
+    };
+    let res: ContractResult<Response> = execute(&mut deps, mock_env(), params, msg);
+    assert_eq!(res.unwrap_err(), "Unauthorized");
+}
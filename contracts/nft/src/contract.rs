@@ -0,0 +1,605 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult,
+};
+use cosmwasm_storage::ReadonlyBucket;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ApprovedForAllResponse, ContractInfoResponse, ExecuteMsg, InstantiateMsg, NumTokensResponse,
+    OwnerOfResponse, QueryMsg, TokensResponse,
+};
+use crate::state::{
+    config, config_read, num_tokens, num_tokens_read, operators, operators_read, tokens,
+    tokens_read, Approval, Config, TokenInfo,
+};
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = Config {
+        name: msg.name,
+        symbol: msg.symbol,
+        minter: deps.api.addr_validate(&msg.minter)?,
+    };
+    config(deps.storage).save(&state)?;
+    num_tokens(deps.storage).save(&0)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Mint { token_id, owner } => try_mint(deps, env, info, token_id, owner),
+        ExecuteMsg::Transfer {
+            recipient,
+            token_id,
+        } => try_transfer(deps, env, info, recipient, token_id),
+        ExecuteMsg::Approve { spender, token_id } => {
+            try_approve(deps, env, info, spender, token_id, true)
+        }
+        ExecuteMsg::Revoke { spender, token_id } => {
+            try_approve(deps, env, info, spender, token_id, false)
+        }
+        ExecuteMsg::ApproveAll { operator } => try_approve_all(deps, env, info, operator, true),
+        ExecuteMsg::RevokeAll { operator } => try_approve_all(deps, env, info, operator, false),
+        ExecuteMsg::Burn { token_id } => try_burn(deps, env, info, token_id),
+    }
+}
+
+fn try_mint(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    token_id: String,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if info.sender != state.minter {
+        return Err(ContractError::Unauthorized {});
+    }
+    let owner = deps.api.addr_validate(&owner)?;
+    if tokens_read(deps.storage)
+        .may_load(token_id.as_bytes())?
+        .is_some()
+    {
+        return Err(ContractError::TokenExists { token_id });
+    }
+
+    let token = TokenInfo {
+        owner: owner.clone(),
+        approvals: vec![],
+    };
+    tokens(deps.storage).save(token_id.as_bytes(), &token)?;
+    num_tokens(deps.storage).update(|count| -> StdResult<_> { Ok(count + 1) })?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "mint"),
+            attr("token_id", token_id),
+            attr("owner", owner),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_transfer(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let mut token = load_token(deps.as_ref(), &token_id)?;
+    if !is_approved_or_owner(deps.as_ref(), &info.sender, &token)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let owner = token.owner;
+    token.owner = recipient.clone();
+    token.approvals = vec![];
+    tokens(deps.storage).save(token_id.as_bytes(), &token)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "transfer"),
+            attr("token_id", token_id),
+            attr("sender", info.sender),
+            attr("owner", owner),
+            attr("recipient", recipient),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Approve or revoke
+///
+/// Adds `spender` to the approvals of `token_id` or removes it. Approving a spender twice or
+/// revoking a spender that is not approved changes nothing.
+///
+/// @param spender the address to approve or revoke
+/// @param token_id the token to approve it for
+/// @param approved whether to approve or revoke the spender
+fn try_approve(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+    approved: bool,
+) -> Result<Response, ContractError> {
+    let mut token = load_token(deps.as_ref(), &token_id)?;
+    if !is_owner_or_operator(deps.as_ref(), &info.sender, &token.owner)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    let spender = deps.api.addr_validate(&spender)?;
+    token
+        .approvals
+        .retain(|approval| approval.spender != spender);
+    if approved {
+        token.approvals.push(Approval {
+            spender: spender.clone(),
+        });
+    }
+    tokens(deps.storage).save(token_id.as_bytes(), &token)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", if approved { "approve" } else { "revoke" }),
+            attr("token_id", token_id),
+            attr("sender", info.sender),
+            attr("spender", spender),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_approve_all(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operator: String,
+    approved: bool,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    let mut owner_operators = operators(deps.storage, &info.sender);
+    if approved {
+        owner_operators.save(operator.as_str().as_bytes(), &true)?;
+    } else {
+        owner_operators.remove(operator.as_str().as_bytes());
+    }
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr(
+                "action",
+                if approved {
+                    "approve_all"
+                } else {
+                    "revoke_all"
+                },
+            ),
+            attr("owner", info.sender),
+            attr("operator", operator),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_burn(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let token = load_token(deps.as_ref(), &token_id)?;
+    if !is_approved_or_owner(deps.as_ref(), &info.sender, &token)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    tokens(deps.storage).remove(token_id.as_bytes());
+    num_tokens(deps.storage).update(|count| -> StdResult<_> { Ok(count - 1) })?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "burn"),
+            attr("token_id", token_id),
+            attr("sender", info.sender),
+            attr("owner", token.owner),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Whether `sender` can transfer or burn `token`, as its owner, an approved spender or an
+/// operator of the owner
+pub fn is_approved_or_owner(deps: Deps, sender: &Addr, token: &TokenInfo) -> StdResult<bool> {
+    if token
+        .approvals
+        .iter()
+        .any(|approval| approval.spender == *sender)
+    {
+        return Ok(true);
+    }
+    is_owner_or_operator(deps, sender, &token.owner)
+}
+
+/// Whether `sender` is `owner` or one of its operators
+fn is_owner_or_operator(deps: Deps, sender: &Addr, owner: &Addr) -> StdResult<bool> {
+    if sender == owner {
+        return Ok(true);
+    }
+    let operator = operators_read(deps.storage, owner).may_load(sender.as_str().as_bytes())?;
+    Ok(operator.is_some())
+}
+
+fn load_token(deps: Deps, token_id: &str) -> Result<TokenInfo, ContractError> {
+    tokens_read(deps.storage)
+        .may_load(token_id.as_bytes())?
+        .ok_or_else(|| ContractError::TokenNotFound {
+            token_id: token_id.to_string(),
+        })
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::ContractInfo {} => {
+            let state = config_read(deps.storage).load()?;
+            to_binary(&ContractInfoResponse {
+                name: state.name,
+                symbol: state.symbol,
+            })
+        }
+        QueryMsg::OwnerOf { token_id } => {
+            let token = tokens_read(deps.storage).load(token_id.as_bytes())?;
+            to_binary(&OwnerOfResponse {
+                owner: token.owner.to_string(),
+                approvals: token
+                    .approvals
+                    .into_iter()
+                    .map(|approval| approval.spender.to_string())
+                    .collect(),
+            })
+        }
+        QueryMsg::ApprovedForAll {
+            owner,
+            start_after,
+            limit,
+        } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let operators = operators_read(deps.storage, &owner);
+            to_binary(&ApprovedForAllResponse {
+                operators: list_keys(&operators, start_after, limit)?,
+            })
+        }
+        QueryMsg::NumTokens {} => to_binary(&NumTokensResponse {
+            count: num_tokens_read(deps.storage).load()?,
+        }),
+        QueryMsg::AllTokens { start_after, limit } => to_binary(&TokensResponse {
+            tokens: list_keys(&tokens_read(deps.storage), start_after, limit)?,
+        }),
+    }
+}
+
+/// Up to `limit` keys of `bucket` sorting after `start_after`
+fn list_keys<T>(
+    bucket: &ReadonlyBucket<T>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // appending a zero byte gives the first key sorting after `start_after`
+    let start = start_after.map(|key| {
+        let mut start = key.into_bytes();
+        start.push(0);
+        start
+    });
+    bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, _) = item?;
+            Ok(String::from_utf8(key)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{OwnedDeps, StdError};
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            name: "Crypto Kitties".to_string(),
+            symbol: "KITTY".to_string(),
+            minter: "minter".to_string(),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn mint(deps: DepsMut, token_id: &str, owner: &str) {
+        let msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+        };
+        run(deps, "minter", msg).unwrap();
+    }
+
+    fn transfer(token_id: &str, recipient: &str) -> ExecuteMsg {
+        ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        }
+    }
+
+    fn owner_of(deps: Deps, token_id: &str) -> StdResult<OwnerOfResponse> {
+        let msg = QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+        };
+        from_binary(&query(deps, mock_env(), msg)?)
+    }
+
+    fn num(deps: Deps) -> u64 {
+        let res: NumTokensResponse =
+            from_binary(&query(deps, mock_env(), QueryMsg::NumTokens {}).unwrap()).unwrap();
+        res.count
+    }
+
+    #[test]
+    fn only_minter_mints() {
+        let mut deps = setup();
+        let res: ContractInfoResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap())
+                .unwrap();
+        assert_eq!(res.symbol, "KITTY");
+
+        let msg = ExecuteMsg::Mint {
+            token_id: "kitty".to_string(),
+            owner: "alice".to_string(),
+        };
+        match run(deps.as_mut(), "alice", msg.clone()).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = run(deps.as_mut(), "minter", msg.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "mint"),
+                attr("token_id", "kitty"),
+                attr("owner", "alice"),
+            ]
+        );
+        assert_eq!(owner_of(deps.as_ref(), "kitty").unwrap().owner, "alice");
+        assert_eq!(num(deps.as_ref()), 1);
+
+        match run(deps.as_mut(), "minter", msg).unwrap_err() {
+            ContractError::TokenExists { token_id } => assert_eq!(token_id, "kitty"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(num(deps.as_ref()), 1);
+    }
+
+    #[test]
+    fn transfers_drop_approvals() {
+        let mut deps = setup();
+        mint(deps.as_mut(), "kitty", "alice");
+        match run(deps.as_mut(), "bob", transfer("kitty", "bob")).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::Approve {
+            spender: "bob".to_string(),
+            token_id: "kitty".to_string(),
+        };
+        run(deps.as_mut(), "alice", msg.clone()).unwrap();
+        // approving twice keeps a single approval
+        run(deps.as_mut(), "alice", msg).unwrap();
+        assert_eq!(
+            owner_of(deps.as_ref(), "kitty").unwrap(),
+            OwnerOfResponse {
+                owner: "alice".to_string(),
+                approvals: vec!["bob".to_string()],
+            }
+        );
+
+        let res = run(deps.as_mut(), "bob", transfer("kitty", "carol")).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "transfer"),
+                attr("token_id", "kitty"),
+                attr("sender", "bob"),
+                attr("owner", "alice"),
+                attr("recipient", "carol"),
+            ]
+        );
+        assert_eq!(
+            owner_of(deps.as_ref(), "kitty").unwrap(),
+            OwnerOfResponse {
+                owner: "carol".to_string(),
+                approvals: vec![],
+            }
+        );
+        match run(deps.as_mut(), "bob", transfer("kitty", "bob")).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn revokes_approvals() {
+        let mut deps = setup();
+        mint(deps.as_mut(), "kitty", "alice");
+        let msg = ExecuteMsg::Approve {
+            spender: "bob".to_string(),
+            token_id: "kitty".to_string(),
+        };
+        run(deps.as_mut(), "alice", msg).unwrap();
+        // spenders cannot approve others
+        let msg = ExecuteMsg::Approve {
+            spender: "carol".to_string(),
+            token_id: "kitty".to_string(),
+        };
+        match run(deps.as_mut(), "bob", msg).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::Revoke {
+            spender: "bob".to_string(),
+            token_id: "kitty".to_string(),
+        };
+        let res = run(deps.as_mut(), "alice", msg).unwrap();
+        assert_eq!(res.attributes[0], attr("action", "revoke"));
+        assert_eq!(owner_of(deps.as_ref(), "kitty").unwrap().approvals.len(), 0);
+        match run(deps.as_mut(), "bob", transfer("kitty", "bob")).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn operators_act_for_all_tokens() {
+        let mut deps = setup();
+        mint(deps.as_mut(), "kitty1", "alice");
+        let msg = ExecuteMsg::ApproveAll {
+            operator: "market".to_string(),
+        };
+        let res = run(deps.as_mut(), "alice", msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "approve_all"),
+                attr("owner", "alice"),
+                attr("operator", "market"),
+            ]
+        );
+        // also covers tokens received later
+        mint(deps.as_mut(), "kitty2", "alice");
+
+        let msg = QueryMsg::ApprovedForAll {
+            owner: "alice".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: ApprovedForAllResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.operators, vec!["market".to_string()]);
+
+        let msg = ExecuteMsg::Approve {
+            spender: "bob".to_string(),
+            token_id: "kitty1".to_string(),
+        };
+        run(deps.as_mut(), "market", msg).unwrap();
+        run(deps.as_mut(), "market", transfer("kitty2", "carol")).unwrap();
+        assert_eq!(owner_of(deps.as_ref(), "kitty2").unwrap().owner, "carol");
+
+        let msg = ExecuteMsg::RevokeAll {
+            operator: "market".to_string(),
+        };
+        run(deps.as_mut(), "alice", msg).unwrap();
+        match run(deps.as_mut(), "market", transfer("kitty1", "market")).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // approvals given by the operator stay
+        run(deps.as_mut(), "bob", transfer("kitty1", "bob")).unwrap();
+    }
+
+    #[test]
+    fn burned_tokens_are_gone() {
+        let mut deps = setup();
+        mint(deps.as_mut(), "kitty1", "alice");
+        mint(deps.as_mut(), "kitty2", "alice");
+        let burn = ExecuteMsg::Burn {
+            token_id: "kitty1".to_string(),
+        };
+        match run(deps.as_mut(), "bob", burn.clone()).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        run(deps.as_mut(), "alice", burn.clone()).unwrap();
+        assert_eq!(num(deps.as_ref()), 1);
+        match owner_of(deps.as_ref(), "kitty1").unwrap_err() {
+            StdError::NotFound { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), "alice", burn).unwrap_err() {
+            ContractError::TokenNotFound { token_id } => assert_eq!(token_id, "kitty1"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), "alice", transfer("kitty1", "bob")).unwrap_err() {
+            ContractError::TokenNotFound { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // burned IDs can be minted again
+        mint(deps.as_mut(), "kitty1", "bob");
+        assert_eq!(owner_of(deps.as_ref(), "kitty1").unwrap().owner, "bob");
+    }
+
+    #[test]
+    fn lists_tokens_in_pages() {
+        let mut deps = setup();
+        for token_id in &["b", "a", "d", "c"] {
+            mint(deps.as_mut(), token_id, "alice");
+        }
+        let all_tokens = |start_after: Option<&str>, limit| {
+            let msg = QueryMsg::AllTokens {
+                start_after: start_after.map(String::from),
+                limit: Some(limit),
+            };
+            let res: TokensResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res.tokens
+        };
+        assert_eq!(all_tokens(None, 3), vec!["a", "b", "c"]);
+        assert_eq!(all_tokens(Some("c"), 3), vec!["d"]);
+        assert_eq!(all_tokens(Some("d"), 3), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,17 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Token {token_id} already minted")]
+    TokenExists { token_id: String },
+
+    #[error("Token {token_id} not found")]
+    TokenNotFound { token_id: String },
+}
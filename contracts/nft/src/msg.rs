@@ -0,0 +1,83 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    /// The only address that can mint tokens
+    pub minter: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Creates the token `token_id` owned by `owner`. Only the minter can do this.
+    Mint { token_id: String, owner: String },
+    /// Moves `token_id` to `recipient` and drops its approvals. The owner, an approved spender
+    /// or an operator of the owner can do this.
+    Transfer { recipient: String, token_id: String },
+    /// Lets `spender` transfer or burn `token_id` until its next transfer. The owner or an
+    /// operator of the owner can do this.
+    Approve { spender: String, token_id: String },
+    /// Drops the approval of `spender` for `token_id`
+    Revoke { spender: String, token_id: String },
+    /// Lets `operator` transfer, burn and approve all tokens of the signer, including
+    /// tokens it receives later
+    ApproveAll { operator: String },
+    /// Drops the signer's approval of `operator`
+    RevokeAll { operator: String },
+    /// Destroys `token_id`. The owner, an approved spender or an operator of the owner can
+    /// do this.
+    Burn { token_id: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Name and symbol of the collection
+    ContractInfo {},
+    /// Owner and approved spenders of `token_id`. Fails for tokens that were never minted or
+    /// were burned.
+    OwnerOf { token_id: String },
+    /// Operators of `owner` with addresses sorting after `start_after`, in address order
+    ApprovedForAll {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Number of tokens in existence
+    NumTokens {},
+    /// Token IDs sorting after `start_after`, in ID order
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerOfResponse {
+    pub owner: String,
+    pub approvals: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovedForAllResponse {
+    pub operators: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NumTokensResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
@@ -0,0 +1,66 @@
+use cosmwasm_std::{Addr, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub static CONFIG_KEY: &[u8] = b"config";
+pub static NUM_TOKENS_KEY: &[u8] = b"num_tokens";
+pub static PREFIX_TOKENS: &[u8] = b"tokens";
+pub static PREFIX_OPERATORS: &[u8] = b"operators";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub name: String,
+    pub symbol: String,
+    /// The only address that can mint tokens
+    pub minter: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenInfo {
+    pub owner: Addr,
+    /// Addresses that can transfer or burn this token. Cleared on every transfer.
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: Addr,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn num_tokens(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, NUM_TOKENS_KEY)
+}
+
+pub fn num_tokens_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, NUM_TOKENS_KEY)
+}
+
+/// Tokens by token ID
+pub fn tokens(storage: &mut dyn Storage) -> Bucket<'_, TokenInfo> {
+    bucket(storage, PREFIX_TOKENS)
+}
+
+pub fn tokens_read(storage: &dyn Storage) -> ReadonlyBucket<'_, TokenInfo> {
+    bucket_read(storage, PREFIX_TOKENS)
+}
+
+/// The operators `owner` approved for all its tokens, by operator address
+pub fn operators<'a>(storage: &'a mut dyn Storage, owner: &Addr) -> Bucket<'a, bool> {
+    Bucket::multilevel(storage, &[PREFIX_OPERATORS, owner.as_str().as_bytes()])
+}
+
+pub fn operators_read<'a>(storage: &'a dyn Storage, owner: &Addr) -> ReadonlyBucket<'a, bool> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_OPERATORS, owner.as_str().as_bytes()])
+}
@@ -0,0 +1,140 @@
+//! This integration test tries to run and call the generated wasm.
+//! It depends on a Wasm build being available, which you can create with `cargo wasm`.
+//! Then running `cargo integration-test` will validate we can properly call into that generated Wasm.
+
+use cosmwasm_std::{from_binary, ContractResult, Response};
+use cosmwasm_vm::testing::{
+    execute, instantiate, mock_env, mock_info, mock_instance, query, MockApi, MockQuerier,
+    MockStorage,
+};
+use cosmwasm_vm::Instance;
+
+use cw_nft::msg::{ExecuteMsg, InstantiateMsg, NumTokensResponse, OwnerOfResponse, QueryMsg};
+
+// This line will test the output of cargo wasm
+static WASM: &[u8] = include_bytes!("../target/wasm32-unknown-unknown/release/cw_nft.wasm");
+// You can uncomment this line instead to test productionified build from rust-optimizer
+// static WASM: &[u8] = include_bytes!("../contract.wasm");
+
+type Deps = Instance<MockApi, MockStorage, MockQuerier>;
+
+fn setup() -> Deps {
+    let mut deps = mock_instance(WASM, &[]);
+    let msg = InstantiateMsg {
+        name: "Crypto Kitties".to_string(),
+        symbol: "KITTY".to_string(),
+        minter: "minter".to_string(),
+    };
+    let _res: Response =
+        instantiate(&mut deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    deps
+}
+
+fn run(deps: &mut Deps, sender: &str, msg: ExecuteMsg) -> ContractResult<Response> {
+    execute(deps, mock_env(), mock_info(sender, &[]), msg)
+}
+
+fn mint(deps: &mut Deps, token_id: &str, owner: &str) {
+    let msg = ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+    };
+    run(deps, "minter", msg).unwrap();
+}
+
+fn transfer(token_id: &str, recipient: &str) -> ExecuteMsg {
+    ExecuteMsg::Transfer {
+        recipient: recipient.to_string(),
+        token_id: token_id.to_string(),
+    }
+}
+
+fn owner_of(deps: &mut Deps, token_id: &str) -> Result<OwnerOfResponse, String> {
+    let msg = QueryMsg::OwnerOf {
+        token_id: token_id.to_string(),
+    };
+    let res = query(deps, mock_env(), msg).into_result()?;
+    Ok(from_binary(&res).unwrap())
+}
+
+fn num_tokens(deps: &mut Deps) -> u64 {
+    let res = query(deps, mock_env(), QueryMsg::NumTokens {}).unwrap();
+    let value: NumTokensResponse = from_binary(&res).unwrap();
+    value.count
+}
+
+#[test]
+fn mint_transfer_burn_cycle() {
+    let mut deps = setup();
+    mint(&mut deps, "kitty", "alice");
+    assert_eq!(num_tokens(&mut deps), 1);
+    assert_eq!(owner_of(&mut deps, "kitty").unwrap().owner, "alice");
+
+    // only the owner can move it
+    let res = run(&mut deps, "bob", transfer("kitty", "bob"));
+    assert_eq!(res.unwrap_err(), "Unauthorized");
+    run(&mut deps, "alice", transfer("kitty", "bob")).unwrap();
+    assert_eq!(owner_of(&mut deps, "kitty").unwrap().owner, "bob");
+    let res = run(&mut deps, "alice", transfer("kitty", "alice"));
+    assert_eq!(res.unwrap_err(), "Unauthorized");
+
+    let burn = ExecuteMsg::Burn {
+        token_id: "kitty".to_string(),
+    };
+    let res = run(&mut deps, "alice", burn.clone());
+    assert_eq!(res.unwrap_err(), "Unauthorized");
+    run(&mut deps, "bob", burn.clone()).unwrap();
+    assert_eq!(num_tokens(&mut deps), 0);
+    assert!(owner_of(&mut deps, "kitty")
+        .unwrap_err()
+        .contains("not found"));
+    let res = run(&mut deps, "bob", burn);
+    assert_eq!(res.unwrap_err(), "Token kitty not found");
+}
+
+#[test]
+fn approval_delegation() {
+    let mut deps = setup();
+    mint(&mut deps, "kitty1", "alice");
+    mint(&mut deps, "kitty2", "alice");
+
+    // a spender approved for one token can move just that one
+    let msg = ExecuteMsg::Approve {
+        spender: "bob".to_string(),
+        token_id: "kitty1".to_string(),
+    };
+    run(&mut deps, "alice", msg).unwrap();
+    let res = run(&mut deps, "bob", transfer("kitty2", "bob"));
+    assert_eq!(res.unwrap_err(), "Unauthorized");
+    run(&mut deps, "bob", transfer("kitty1", "carol")).unwrap();
+    assert_eq!(
+        owner_of(&mut deps, "kitty1").unwrap(),
+        OwnerOfResponse {
+            owner: "carol".to_string(),
+            approvals: vec![],
+        }
+    );
+
+    // an operator handles all tokens of the owner and can delegate further
+    let msg = ExecuteMsg::ApproveAll {
+        operator: "market".to_string(),
+    };
+    run(&mut deps, "alice", msg).unwrap();
+    let msg = ExecuteMsg::Approve {
+        spender: "bob".to_string(),
+        token_id: "kitty2".to_string(),
+    };
+    run(&mut deps, "market", msg).unwrap();
+    let msg = ExecuteMsg::RevokeAll {
+        operator: "market".to_string(),
+    };
+    run(&mut deps, "alice", msg).unwrap();
+    let res = run(&mut deps, "market", transfer("kitty2", "market"));
+    assert_eq!(res.unwrap_err(), "Unauthorized");
+
+    let burn = ExecuteMsg::Burn {
+        token_id: "kitty2".to_string(),
+    };
+    run(&mut deps, "bob", burn).unwrap();
+    assert_eq!(num_tokens(&mut deps), 1);
+}
@@ -0,0 +1,540 @@
+use std::collections::HashSet;
+
+use cosmwasm_std::{
+    attr, coins, entry_point, to_binary, Api, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Response, StdResult, Storage, Uint128,
+};
+use cw_erc20::ReceiveMsg;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ConfigResponse, DistributedResponse, ExecuteMsg, InstantiateMsg, PayeeInfo, PendingResponse,
+    QueryMsg,
+};
+use crate::state::{config, config_read, distributed, distributed_read, Config, Payee};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let admin = match msg.admin {
+        Some(admin) => Some(deps.api.addr_validate(&admin)?),
+        None => None,
+    };
+    let state = Config {
+        admin,
+        token: deps.api.addr_validate(&msg.token)?,
+        denom: msg.denom,
+        payees: validate_payees(deps.api, msg.payees)?,
+        undistributed_token: Uint128::zero(),
+    };
+    config(deps.storage).save(&state)?;
+    Ok(Response::default())
+}
+
+fn validate_payees(api: &dyn Api, payees: Vec<PayeeInfo>) -> Result<Vec<Payee>, ContractError> {
+    if payees.is_empty() {
+        return Err(ContractError::NoPayees {});
+    }
+    let mut seen = HashSet::new();
+    let mut total_weight = 0u64;
+    let mut validated = Vec::with_capacity(payees.len());
+    for payee in payees {
+        let address = api.addr_validate(&payee.address)?;
+        if payee.weight == 0 {
+            return Err(ContractError::ZeroWeight {
+                address: payee.address,
+            });
+        }
+        if !seen.insert(address.clone()) {
+            return Err(ContractError::DuplicatePayee {
+                address: payee.address,
+            });
+        }
+        total_weight = total_weight
+            .checked_add(payee.weight)
+            .ok_or(ContractError::WeightOverflow {})?;
+        validated.push(Payee {
+            address,
+            weight: payee.weight,
+        });
+    }
+    Ok(validated)
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(receive) => try_receive(deps, info, receive),
+        ExecuteMsg::Distribute {} => try_distribute(deps, env),
+        ExecuteMsg::UpdatePayees { payees } => try_update_payees(deps, env, info, payees),
+    }
+}
+
+/// Receive
+///
+/// Adds the tokens sent with the erc20 `Send` message to the next distribution.
+///
+/// @param receive the `ReceiveMsg` forwarded by the token
+fn try_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    receive: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.token {
+        return Err(ContractError::WrongToken {
+            token: state.token.to_string(),
+        });
+    }
+    if receive.amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    state.undistributed_token = state.undistributed_token.checked_add(receive.amount)?;
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "receive"),
+            attr("sender", receive.sender),
+            attr("amount", receive.amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Distribute
+///
+/// Splits the undistributed tokens and the native balance between the payees.
+fn try_distribute(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    let native_balance = deps
+        .querier
+        .query_balance(&env.contract.address, &state.denom)?
+        .amount;
+    let payout = distribute(deps.storage, &mut state, native_balance)?;
+    if payout.messages.is_empty() {
+        return Err(ContractError::NothingToDistribute {});
+    }
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: payout.messages,
+        attributes: vec![
+            attr("action", "distribute"),
+            attr("token_amount", payout.token),
+            attr("native_amount", payout.native),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Update payees
+///
+/// Settles everything received so far with the current payees, so a change never touches funds
+/// which arrived before it, then replaces the payees.
+///
+/// @param payees the new payees and weights
+fn try_update_payees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payees: Vec<PayeeInfo>,
+) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if state.admin.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let payees = validate_payees(deps.api, payees)?;
+    let native_balance = deps
+        .querier
+        .query_balance(&env.contract.address, &state.denom)?
+        .amount;
+    let payout = distribute(deps.storage, &mut state, native_balance)?;
+    state.payees = payees;
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: payout.messages,
+        attributes: vec![
+            attr("action", "update_payees"),
+            attr("payees", state.payees.len()),
+            attr("token_amount", payout.token),
+            attr("native_amount", payout.native),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+struct Payout {
+    messages: Vec<CosmosMsg>,
+    token: Uint128,
+    native: Uint128,
+}
+
+/// Pays every payee of `state` their share of the undistributed tokens and `native_balance`.
+/// The rounding remainders stay in the contract: the token remainder is kept in
+/// `undistributed_token` and the native one simply remains in the balance.
+fn distribute(
+    storage: &mut dyn Storage,
+    state: &mut Config,
+    native_balance: Uint128,
+) -> Result<Payout, ContractError> {
+    let token_shares = state.split(state.undistributed_token);
+    let native_shares = state.split(native_balance);
+    let mut payout = Payout {
+        messages: vec![],
+        token: Uint128::zero(),
+        native: Uint128::zero(),
+    };
+    for ((payee, token), native) in state.payees.iter().zip(token_shares).zip(native_shares) {
+        if token.is_zero() && native.is_zero() {
+            continue;
+        }
+        let key = payee.address.as_str().as_bytes();
+        let mut totals = distributed_read(storage).may_load(key)?.unwrap_or_default();
+        totals.token = totals.token.checked_add(token)?;
+        totals.native = totals.native.checked_add(native)?;
+        distributed(storage).save(key, &totals)?;
+
+        // empty payouts are skipped, the bank module rejects them
+        if !native.is_zero() {
+            payout.messages.push(
+                BankMsg::Send {
+                    to_address: payee.address.to_string(),
+                    amount: coins(native.u128(), &state.denom),
+                }
+                .into(),
+            );
+        }
+        if !token.is_zero() {
+            let transfer = cw_erc20::ExecuteMsg::transfer(payee.address.as_str(), token);
+            payout
+                .messages
+                .push(transfer.into_cosmos_msg(&state.token)?);
+        }
+        payout.token = payout.token.checked_add(token)?;
+        payout.native = payout.native.checked_add(native)?;
+    }
+    state.undistributed_token = state.undistributed_token.checked_sub(payout.token)?;
+    Ok(payout)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => {
+            let state = config_read(deps.storage).load()?;
+            to_binary(&ConfigResponse {
+                admin: state.admin.map(|admin| admin.to_string()),
+                token: state.token.to_string(),
+                denom: state.denom,
+                payees: state
+                    .payees
+                    .into_iter()
+                    .map(|payee| PayeeInfo {
+                        address: payee.address.to_string(),
+                        weight: payee.weight,
+                    })
+                    .collect(),
+            })
+        }
+        QueryMsg::Pending {} => {
+            let state = config_read(deps.storage).load()?;
+            let native = deps
+                .querier
+                .query_balance(&env.contract.address, &state.denom)?
+                .amount;
+            to_binary(&PendingResponse {
+                token: state.undistributed_token,
+                native,
+            })
+        }
+        QueryMsg::Distributed { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let totals = distributed_read(deps.storage)
+                .may_load(address.as_str().as_bytes())?
+                .unwrap_or_default();
+            to_binary(&DistributedResponse {
+                token: totals.token,
+                native: totals.native,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{from_binary, OwnedDeps};
+
+    const DENOM: &str = "ucosm";
+
+    fn payees(weights: &[(&str, u64)]) -> Vec<PayeeInfo> {
+        weights
+            .iter()
+            .map(|(address, weight)| PayeeInfo {
+                address: address.to_string(),
+                weight: *weight,
+            })
+            .collect()
+    }
+
+    fn setup(weights: &[(&str, u64)]) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            admin: Some("admin".to_string()),
+            token: "token".to_string(),
+            denom: DENOM.to_string(),
+            payees: payees(weights),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn receive(amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Receive(ReceiveMsg {
+            sender: "payer".to_string(),
+            amount: Uint128::from(amount),
+            msg: None,
+        })
+    }
+
+    fn set_native_balance(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, amount: u128) {
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(amount, DENOM));
+    }
+
+    /// The payouts of a response as (payee, token, native)
+    fn payouts(res: &Response) -> Vec<(String, u128, u128)> {
+        let mut payouts: Vec<(String, u128, u128)> = vec![];
+        for msg in &res.messages {
+            let (payee, token, native) = match msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                    (to_address.clone(), 0, amount[0].amount.u128())
+                }
+                CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+                    contract_addr, msg, ..
+                }) => {
+                    assert_eq!(contract_addr, "token");
+                    match from_binary(msg).unwrap() {
+                        cw_erc20::ExecuteMsg::Transfer { recipient, amount } => {
+                            (recipient, amount.u128(), 0)
+                        }
+                        msg => panic!("unexpected token message: {:?}", msg),
+                    }
+                }
+                msg => panic!("unexpected message: {:?}", msg),
+            };
+            match payouts.iter_mut().find(|(address, ..)| *address == payee) {
+                Some(entry) => {
+                    entry.1 += token;
+                    entry.2 += native;
+                }
+                None => payouts.push((payee, token, native)),
+            }
+        }
+        payouts
+    }
+
+    fn distributed_to(deps: Deps, address: &str) -> (u128, u128) {
+        let msg = QueryMsg::Distributed {
+            address: address.to_string(),
+        };
+        let res: DistributedResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        (res.token.u128(), res.native.u128())
+    }
+
+    fn pending(deps: Deps) -> (u128, u128) {
+        let res: PendingResponse =
+            from_binary(&query(deps, mock_env(), QueryMsg::Pending {}).unwrap()).unwrap();
+        (res.token.u128(), res.native.u128())
+    }
+
+    #[test]
+    fn validates_payees() {
+        let mut deps = mock_dependencies(&[]);
+        let cases: Vec<(Vec<PayeeInfo>, &str)> = vec![
+            (vec![], "At least one payee is required"),
+            (
+                payees(&[("alice", 1), ("bob", 0)]),
+                "Payee bob must have a non-zero weight",
+            ),
+            (
+                payees(&[("alice", 1), ("bob", 2), ("alice", 3)]),
+                "Payee alice is listed twice",
+            ),
+            (
+                payees(&[("alice", u64::MAX), ("bob", 1)]),
+                "Total weight overflows",
+            ),
+        ];
+        for (payees, error) in cases {
+            let msg = InstantiateMsg {
+                admin: None,
+                token: "token".to_string(),
+                denom: DENOM.to_string(),
+                payees,
+            };
+            let err =
+                instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(err.to_string(), error);
+        }
+    }
+
+    #[test]
+    fn splits_unevenly() {
+        let mut deps = setup(&[("alice", 1), ("bob", 2), ("carol", 3)]);
+        run(deps.as_mut(), "token", receive(100)).unwrap();
+        set_native_balance(&mut deps, 50);
+        assert_eq!(pending(deps.as_ref()), (100, 50));
+
+        let res = run(deps.as_mut(), "anyone", ExecuteMsg::Distribute {}).unwrap();
+        assert_eq!(
+            payouts(&res),
+            vec![
+                ("alice".to_string(), 16, 8),
+                ("bob".to_string(), 33, 16),
+                ("carol".to_string(), 50, 25),
+            ]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "distribute"),
+                attr("token_amount", "99"),
+                attr("native_amount", "49"),
+            ]
+        );
+        set_native_balance(&mut deps, 1);
+        assert_eq!(pending(deps.as_ref()), (1, 1));
+        assert_eq!(distributed_to(deps.as_ref(), "bob"), (33, 16));
+    }
+
+    #[test]
+    fn carries_dust_forward() {
+        let mut deps = setup(&[("alice", 1), ("bob", 1), ("carol", 1)]);
+        run(deps.as_mut(), "token", receive(10)).unwrap();
+        set_native_balance(&mut deps, 10);
+        let res = run(deps.as_mut(), "anyone", ExecuteMsg::Distribute {}).unwrap();
+        assert_eq!(payouts(&res)[0], ("alice".to_string(), 3, 3));
+
+        // the remainders are too small to split
+        set_native_balance(&mut deps, 1);
+        run(deps.as_mut(), "token", receive(1)).unwrap();
+        match run(deps.as_mut(), "anyone", ExecuteMsg::Distribute {}).unwrap_err() {
+            ContractError::NothingToDistribute {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // but count towards the next payment
+        run(deps.as_mut(), "token", receive(1)).unwrap();
+        set_native_balance(&mut deps, 3);
+        let res = run(deps.as_mut(), "anyone", ExecuteMsg::Distribute {}).unwrap();
+        assert_eq!(
+            payouts(&res),
+            vec![
+                ("alice".to_string(), 1, 1),
+                ("bob".to_string(), 1, 1),
+                ("carol".to_string(), 1, 1),
+            ]
+        );
+        set_native_balance(&mut deps, 0);
+        assert_eq!(pending(deps.as_ref()), (0, 0));
+        for payee in &["alice", "bob", "carol"] {
+            assert_eq!(distributed_to(deps.as_ref(), payee), (4, 4));
+        }
+    }
+
+    #[test]
+    fn updates_payees_mid_stream() {
+        let mut deps = setup(&[("alice", 1), ("bob", 1)]);
+        run(deps.as_mut(), "token", receive(91)).unwrap();
+        set_native_balance(&mut deps, 20);
+
+        let msg = ExecuteMsg::UpdatePayees {
+            payees: payees(&[("bob", 1), ("carol", 3)]),
+        };
+        match run(deps.as_mut(), "alice", msg.clone()).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // what arrived so far is settled with the old payees
+        let res = run(deps.as_mut(), "admin", msg).unwrap();
+        assert_eq!(
+            payouts(&res),
+            vec![("alice".to_string(), 45, 10), ("bob".to_string(), 45, 10),]
+        );
+        set_native_balance(&mut deps, 0);
+
+        run(deps.as_mut(), "token", receive(39)).unwrap();
+        let res = run(deps.as_mut(), "anyone", ExecuteMsg::Distribute {}).unwrap();
+        assert_eq!(
+            payouts(&res),
+            vec![("bob".to_string(), 10, 0), ("carol".to_string(), 30, 0)]
+        );
+        assert_eq!(distributed_to(deps.as_ref(), "alice"), (45, 10));
+        assert_eq!(distributed_to(deps.as_ref(), "bob"), (55, 10));
+        assert_eq!(distributed_to(deps.as_ref(), "carol"), (30, 0));
+
+        let res: ConfigResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(res.payees, payees(&[("bob", 1), ("carol", 3)]));
+    }
+
+    #[test]
+    fn payees_are_fixed_without_admin() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            admin: None,
+            token: "token".to_string(),
+            denom: DENOM.to_string(),
+            payees: payees(&[("alice", 1)]),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdatePayees {
+            payees: payees(&[("bob", 1)]),
+        };
+        match run(deps.as_mut(), "creator", msg).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_other_tokens() {
+        let mut deps = setup(&[("alice", 1)]);
+        match run(deps.as_mut(), "other_token", receive(10)).unwrap_err() {
+            ContractError::WrongToken { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), "token", receive(0)).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(pending(deps.as_ref()), (0, 0));
+    }
+}
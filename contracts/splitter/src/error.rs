@@ -0,0 +1,35 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("At least one payee is required")]
+    NoPayees {},
+
+    #[error("Payee {address} must have a non-zero weight")]
+    ZeroWeight { address: String },
+
+    #[error("Payee {address} is listed twice")]
+    DuplicatePayee { address: String },
+
+    #[error("Total weight overflows")]
+    WeightOverflow {},
+
+    #[error("Only {token} tokens are accepted")]
+    WrongToken { token: String },
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Nothing to distribute")]
+    NothingToDistribute {},
+}
@@ -0,0 +1,68 @@
+use cosmwasm_std::Uint128;
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Can replace the payees later on. Leave empty to fix them forever.
+    pub admin: Option<String>,
+    /// The erc20 token accepted through the `Send` hook
+    pub token: String,
+    /// The native coin split from the contract's balance
+    pub denom: String,
+    pub payees: Vec<PayeeInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayeeInfo {
+    pub address: String,
+    /// Share of every payment relative to the sum of all weights
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Adds the tokens sent with the erc20 `Send` message to the next distribution. Only
+    /// accepted from the configured token.
+    Receive(ReceiveMsg),
+    /// Pays every payee their share of the undistributed tokens and the contract's native
+    /// balance. Anyone can call this.
+    Distribute {},
+    /// Distributes everything received so far to the current payees, then replaces them.
+    /// Only the admin can do this.
+    UpdatePayees { payees: Vec<PayeeInfo> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Returns the amounts the next `Distribute {}` splits
+    Pending {},
+    /// Returns the totals paid out to `address`
+    Distributed {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub admin: Option<String>,
+    pub token: String,
+    pub denom: String,
+    pub payees: Vec<PayeeInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingResponse {
+    pub token: Uint128,
+    pub native: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DistributedResponse {
+    pub token: Uint128,
+    pub native: Uint128,
+}
@@ -0,0 +1,66 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static DISTRIBUTED_KEY: &[u8] = b"distributed";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Can replace the payees. Without an admin the payees are fixed.
+    pub admin: Option<Addr>,
+    /// The erc20 token accepted through the `Send` hook
+    pub token: Addr,
+    /// The native coin split from the contract's balance
+    pub denom: String,
+    pub payees: Vec<Payee>,
+    /// Tokens received through the hook and not paid out yet, including the rounding
+    /// remainders of earlier distributions
+    pub undistributed_token: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payee {
+    pub address: Addr,
+    pub weight: u64,
+}
+
+impl Config {
+    /// The share of `amount` for every payee, rounded down. The remainder is less than one
+    /// unit per payee and stays in the contract for the next distribution.
+    pub fn split(&self, amount: Uint128) -> Vec<Uint128> {
+        let total_weight: u64 = self.payees.iter().map(|payee| payee.weight).sum();
+        self.payees
+            .iter()
+            .map(|payee| amount.multiply_ratio(payee.weight, total_weight))
+            .collect()
+    }
+}
+
+/// Totals paid out to a payee over all distributions
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Distributed {
+    pub token: Uint128,
+    pub native: Uint128,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Distributed totals by payee address. Entries are kept when a payee is removed.
+pub fn distributed(storage: &mut dyn Storage) -> Bucket<'_, Distributed> {
+    bucket(storage, DISTRIBUTED_KEY)
+}
+
+pub fn distributed_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Distributed> {
+    bucket_read(storage, DISTRIBUTED_KEY)
+}
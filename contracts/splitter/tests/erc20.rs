@@ -0,0 +1,66 @@
+//! Runs the splitter against a real erc20 contract on a test chain, which reverts failing
+//! messages like a real chain.
+
+use cw_erc20::BalanceResponse;
+use cw_splitter::contract::{execute, instantiate, query};
+use cw_splitter::msg::{ExecuteMsg, InstantiateMsg, PayeeInfo};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const SPLITTER: &str = "splitter";
+
+/// A splitter paying alice, bob and carol 1:1:2
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    let msg = erc20_msg("REV", &[("payer", 1000)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let splitter = chain.store_code(Contract::new(instantiate, execute, query));
+    let payees = [("alice", 1u64), ("bob", 1), ("carol", 2)]
+        .iter()
+        .map(|(address, weight)| PayeeInfo {
+            address: address.to_string(),
+            weight: *weight,
+        })
+        .collect();
+    let msg = InstantiateMsg {
+        admin: None,
+        token: TOKEN.to_string(),
+        denom: "ucosm".to_string(),
+        payees,
+    };
+    chain
+        .instantiate(splitter, SPLITTER, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+#[test]
+fn splits_sent_tokens() {
+    let mut chain = setup();
+    let msg = cw_erc20::ExecuteMsg::send(SPLITTER, 101u128, None);
+    chain.execute(TOKEN, "payer", &msg, &[]).unwrap();
+    chain
+        .execute(SPLITTER, "anyone", &ExecuteMsg::Distribute {}, &[])
+        .unwrap();
+    assert_eq!(balance(&chain, "alice"), 25);
+    assert_eq!(balance(&chain, "bob"), 25);
+    assert_eq!(balance(&chain, "carol"), 50);
+    assert_eq!(balance(&chain, SPLITTER), 1);
+
+    let msg = cw_erc20::ExecuteMsg::send(SPLITTER, 3u128, None);
+    chain.execute(TOKEN, "payer", &msg, &[]).unwrap();
+    chain
+        .execute(SPLITTER, "anyone", &ExecuteMsg::Distribute {}, &[])
+        .unwrap();
+    assert_eq!(balance(&chain, "alice"), 26);
+    assert_eq!(balance(&chain, "bob"), 26);
+    assert_eq!(balance(&chain, "carol"), 52);
+    assert_eq!(balance(&chain, SPLITTER), 0);
+}
@@ -0,0 +1,396 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Storage, Uint128,
+};
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, StakerInfoResponse};
+use crate::state::{
+    config, config_read, pool, pool_read, stakers, stakers_read, Config, Pool, Staker,
+};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = Config {
+        staking_token: deps.api.addr_validate(&msg.staking_token)?,
+        reward_token: deps.api.addr_validate(&msg.reward_token)?,
+        reward_rate: msg.reward_rate,
+    };
+    // otherwise staked tokens would be paid out as rewards
+    if state.staking_token == state.reward_token {
+        return Err(ContractError::SameToken {
+            token: state.staking_token.to_string(),
+        });
+    }
+    config(deps.storage).save(&state)?;
+    pool(deps.storage).save(&Pool {
+        total_staked: Uint128::zero(),
+        reward_per_token_stored: Uint128::zero(),
+        last_update_height: env.block.height,
+    })?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Stake { amount } => try_stake(deps, env, info, amount),
+        ExecuteMsg::Unstake { amount } => try_unstake(deps, env, info, amount),
+        ExecuteMsg::ClaimRewards {} => try_claim_rewards(deps, env, info),
+    }
+}
+
+/// Stake
+///
+/// Accounts the signer's rewards so far and adds `amount` to their stake, which earns rewards
+/// from the next block on.
+///
+/// @param amount the staking tokens to pull from the signer
+fn try_stake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let (mut totals, mut staker) =
+        update_rewards(deps.storage, &state, env.block.height, &info.sender)?;
+    staker.staked = staker.staked.checked_add(amount)?;
+    totals.total_staked = totals.total_staked.checked_add(amount)?;
+    save(deps.storage, &totals, &info.sender, &staker)?;
+
+    let transfer =
+        cw_erc20::ExecuteMsg::transfer_from(info.sender.as_str(), env.contract.address, amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.staking_token)?],
+        attributes: vec![
+            attr("action", "stake"),
+            attr("staker", info.sender),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Unstake
+///
+/// Accounts the signer's rewards so far and returns `amount` of their stake. The rewards stay
+/// pending until claimed.
+///
+/// @param amount the staked tokens to return
+fn try_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let (mut totals, mut staker) =
+        update_rewards(deps.storage, &state, env.block.height, &info.sender)?;
+    if staker.staked < amount {
+        return Err(ContractError::InsufficientStake {
+            staked: staker.staked,
+            required: amount,
+        });
+    }
+    staker.staked = staker.staked.checked_sub(amount)?;
+    totals.total_staked = totals.total_staked.checked_sub(amount)?;
+    save(deps.storage, &totals, &info.sender, &staker)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.staking_token)?],
+        attributes: vec![
+            attr("action", "unstake"),
+            attr("staker", info.sender),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Claim rewards
+///
+/// Sends the rewards the signer earned so far. The contract has to hold enough reward tokens,
+/// otherwise the transfer fails.
+fn try_claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let (totals, mut staker) =
+        update_rewards(deps.storage, &state, env.block.height, &info.sender)?;
+    let rewards = staker.pending_rewards;
+    if rewards.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    staker.pending_rewards = Uint128::zero();
+    save(deps.storage, &totals, &info.sender, &staker)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), rewards);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.reward_token)?],
+        attributes: vec![
+            attr("action", "claim_rewards"),
+            attr("staker", info.sender),
+            attr("amount", rewards),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Loads the pool and `address`, both updated to `height`. Every change of a stake must go
+/// through this first, so rewards are accounted with the stake they were earned with.
+fn update_rewards(
+    storage: &dyn Storage,
+    state: &Config,
+    height: u64,
+    address: &Addr,
+) -> Result<(Pool, Staker), ContractError> {
+    let mut totals = pool_read(storage).load()?;
+    totals.update(state.reward_rate, height)?;
+    let mut staker = stakers_read(storage)
+        .may_load(address.as_str().as_bytes())?
+        .unwrap_or_default();
+    staker.update(&totals)?;
+    Ok((totals, staker))
+}
+
+fn save(
+    storage: &mut dyn Storage,
+    totals: &Pool,
+    address: &Addr,
+    staker: &Staker,
+) -> StdResult<()> {
+    pool(storage).save(totals)?;
+    let key = address.as_str().as_bytes();
+    if staker.staked.is_zero() && staker.pending_rewards.is_zero() {
+        stakers(storage).remove(key);
+        Ok(())
+    } else {
+        stakers(storage).save(key, staker)
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => {
+            let state = config_read(deps.storage).load()?;
+            let totals = pool_read(deps.storage).load()?;
+            to_binary(&ConfigResponse {
+                staking_token: state.staking_token.to_string(),
+                reward_token: state.reward_token.to_string(),
+                reward_rate: state.reward_rate,
+                total_staked: totals.total_staked,
+            })
+        }
+        QueryMsg::StakerInfo { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let state = config_read(deps.storage).load()?;
+            let (_, staker) = update_rewards(deps.storage, &state, env.block.height, &address)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_binary(&StakerInfoResponse {
+                staked: staker.staked,
+                pending_rewards: staker.pending_rewards,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, OwnedDeps};
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn setup(reward_rate: u128) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            staking_token: "staking".to_string(),
+            reward_token: "reward".to_string(),
+            reward_rate: Uint128::from(reward_rate),
+        };
+        instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(
+        deps: DepsMut,
+        sender: &str,
+        height: u64,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn stake(amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Stake {
+            amount: Uint128::from(amount),
+        }
+    }
+
+    fn unstake(amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Unstake {
+            amount: Uint128::from(amount),
+        }
+    }
+
+    fn info(deps: Deps, address: &str, height: u64) -> StakerInfoResponse {
+        let msg = QueryMsg::StakerInfo {
+            address: address.to_string(),
+        };
+        from_binary(&query(deps, env_at(height), msg).unwrap()).unwrap()
+    }
+
+    fn pending(deps: Deps, address: &str, height: u64) -> u128 {
+        info(deps, address, height).pending_rewards.u128()
+    }
+
+    #[test]
+    fn rejects_same_token() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            staking_token: "staking".to_string(),
+            reward_token: "staking".to_string(),
+            reward_rate: Uint128::from(100u128),
+        };
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::SameToken { token } => assert_eq!(token, "staking"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn stakes_and_unstakes() {
+        let mut deps = setup(100);
+        let res = run(deps.as_mut(), "alice", 100, stake(40)).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer_from("alice", "cosmos2contract", 40u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("staking").unwrap()]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "stake"),
+                attr("staker", "alice"),
+                attr("amount", "40"),
+            ]
+        );
+
+        match run(deps.as_mut(), "alice", 101, unstake(41)).unwrap_err() {
+            ContractError::InsufficientStake { staked, required } => {
+                assert_eq!(staked.u128(), 40);
+                assert_eq!(required.u128(), 41);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), "alice", 101, stake(0)).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = run(deps.as_mut(), "alice", 101, unstake(40)).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("alice", 40u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("staking").unwrap()]
+        );
+
+        // the rewards of the staked block stay claimable
+        let res = info(deps.as_ref(), "alice", 200);
+        assert_eq!(res.staked.u128(), 0);
+        assert_eq!(res.pending_rewards.u128(), 100);
+    }
+
+    #[test]
+    fn splits_rewards_when_second_staker_joins() {
+        let mut deps = setup(100);
+        run(deps.as_mut(), "alice", 100, stake(100)).unwrap();
+        assert_eq!(pending(deps.as_ref(), "alice", 100), 0);
+        assert_eq!(pending(deps.as_ref(), "alice", 110), 1000);
+
+        // from block 110 on, bob holds 3/4 of the stake
+        run(deps.as_mut(), "bob", 110, stake(300)).unwrap();
+        assert_eq!(pending(deps.as_ref(), "bob", 110), 0);
+        assert_eq!(pending(deps.as_ref(), "alice", 120), 1000 + 250);
+        assert_eq!(pending(deps.as_ref(), "bob", 120), 750);
+
+        let res = run(deps.as_mut(), "alice", 120, ExecuteMsg::ClaimRewards {}).unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("alice", 1250u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("reward").unwrap()]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "claim_rewards"),
+                attr("staker", "alice"),
+                attr("amount", "1250"),
+            ]
+        );
+        match run(deps.as_mut(), "alice", 120, ExecuteMsg::ClaimRewards {}).unwrap_err() {
+            ContractError::NothingToClaim {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // bob leaves, so alice earns everything again
+        run(deps.as_mut(), "bob", 130, unstake(300)).unwrap();
+        assert_eq!(pending(deps.as_ref(), "alice", 140), 250 + 1000);
+        assert_eq!(pending(deps.as_ref(), "bob", 140), 1500);
+
+        // blocks without stake distribute nothing
+        run(deps.as_mut(), "alice", 140, unstake(100)).unwrap();
+        run(deps.as_mut(), "carol", 150, stake(1)).unwrap();
+        assert_eq!(pending(deps.as_ref(), "carol", 151), 100);
+        assert_eq!(pending(deps.as_ref(), "alice", 151), 1250);
+    }
+
+    #[test]
+    fn never_pays_more_than_distributed() {
+        let mut deps = setup(10);
+        run(deps.as_mut(), "alice", 100, stake(1)).unwrap();
+        run(deps.as_mut(), "bob", 100, stake(2)).unwrap();
+        // 10 per block split 1:2, rounded down
+        assert_eq!(pending(deps.as_ref(), "alice", 101), 3);
+        let res = run(deps.as_mut(), "bob", 101, ExecuteMsg::ClaimRewards {}).unwrap();
+        assert_eq!(res.attributes[2], attr("amount", "6"));
+
+        let alice = pending(deps.as_ref(), "alice", 103);
+        let bob = pending(deps.as_ref(), "bob", 103);
+        assert_eq!((alice, bob), (9, 13));
+        assert!(alice + 6 + bob <= 30);
+    }
+}
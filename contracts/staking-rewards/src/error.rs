@@ -0,0 +1,23 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Staking and reward token must differ (both are {token})")]
+    SameToken { token: String },
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Insufficient stake (staked {staked}, required {required})")]
+    InsufficientStake { staked: Uint128, required: Uint128 },
+
+    #[error("No rewards to claim")]
+    NothingToClaim {},
+}
@@ -0,0 +1,47 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub staking_token: String,
+    pub reward_token: String,
+    /// Reward tokens distributed per block among all stakers
+    pub reward_rate: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Stakes `amount` staking tokens, which are pulled with `TransferFrom`, so the contract
+    /// needs an allowance first
+    Stake { amount: Uint128 },
+    /// Returns `amount` staked tokens to the signer. Pending rewards are kept.
+    Unstake { amount: Uint128 },
+    /// Sends the signer's pending rewards to them
+    ClaimRewards {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Returns the stake of `address` and its rewards at the current block
+    StakerInfo {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub staking_token: String,
+    pub reward_token: String,
+    pub reward_rate: Uint128,
+    pub total_staked: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakerInfoResponse {
+    pub staked: Uint128,
+    pub pending_rewards: Uint128,
+}
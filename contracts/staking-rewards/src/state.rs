@@ -0,0 +1,97 @@
+use cosmwasm_std::{Addr, OverflowError, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static POOL_KEY: &[u8] = b"pool";
+static STAKERS_KEY: &[u8] = b"stakers";
+
+/// `reward_per_token_stored` is scaled by this factor, so small rewards spread over a large
+/// stake don't round to zero
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub staking_token: Addr,
+    pub reward_token: Addr,
+    /// Reward tokens distributed per block among all stakers
+    pub reward_rate: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Pool {
+    pub total_staked: Uint128,
+    /// Rewards earned by one staked token since instantiation, times `REWARD_PRECISION`
+    pub reward_per_token_stored: Uint128,
+    /// The block up to which `reward_per_token_stored` is accounted
+    pub last_update_height: u64,
+}
+
+impl Pool {
+    /// Accounts the rewards of the blocks since the last update. Blocks without any stake
+    /// distribute nothing.
+    pub fn update(&mut self, rate: Uint128, height: u64) -> Result<(), OverflowError> {
+        let blocks = height.saturating_sub(self.last_update_height);
+        if blocks > 0 && !self.total_staked.is_zero() {
+            let rewards = rate
+                .checked_mul(Uint128::from(blocks))?
+                .checked_mul(Uint128::from(REWARD_PRECISION))?;
+            self.reward_per_token_stored = self
+                .reward_per_token_stored
+                .checked_add(Uint128::from(rewards.u128() / self.total_staked.u128()))?;
+        }
+        self.last_update_height = std::cmp::max(self.last_update_height, height);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Staker {
+    pub staked: Uint128,
+    /// `reward_per_token_stored` at the last update of this staker
+    pub reward_per_token_paid: Uint128,
+    /// Rewards earned up to the last update and not claimed yet
+    pub pending_rewards: Uint128,
+}
+
+impl Staker {
+    /// Adds the rewards earned since the last update of this staker
+    pub fn update(&mut self, pool: &Pool) -> Result<(), OverflowError> {
+        let per_token = pool
+            .reward_per_token_stored
+            .checked_sub(self.reward_per_token_paid)?;
+        let earned = self.staked.checked_mul(per_token)?.u128() / REWARD_PRECISION;
+        self.pending_rewards = self.pending_rewards.checked_add(Uint128::from(earned))?;
+        self.reward_per_token_paid = pool.reward_per_token_stored;
+        Ok(())
+    }
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn pool(storage: &mut dyn Storage) -> Singleton<'_, Pool> {
+    singleton(storage, POOL_KEY)
+}
+
+pub fn pool_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Pool> {
+    singleton_read(storage, POOL_KEY)
+}
+
+/// Stakes and rewards by staker address
+pub fn stakers(storage: &mut dyn Storage) -> Bucket<'_, Staker> {
+    bucket(storage, STAKERS_KEY)
+}
+
+pub fn stakers_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Staker> {
+    bucket_read(storage, STAKERS_KEY)
+}
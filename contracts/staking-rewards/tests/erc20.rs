@@ -0,0 +1,115 @@
+//! Runs the staking contract against real erc20 contracts for the staking and the reward token,
+//! on a test chain which reverts failing messages like a real chain.
+
+use cosmwasm_std::Uint128;
+use cw_erc20::BalanceResponse;
+use cw_staking_rewards::contract::{execute, instantiate, query};
+use cw_staking_rewards::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, StakerInfoResponse};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const STAKING_TOKEN: &str = "staking_token";
+const REWARD_TOKEN: &str = "reward_token";
+const STAKING: &str = "staking";
+
+/// Distributes 100 reward tokens per block, funded with 10000 for the contract.
+/// alice and bob hold 1000 staking tokens each.
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(100);
+    let msg = erc20_msg("STK", &[("alice", 1_000), ("bob", 1_000)]);
+    chain.instantiate_erc20(STAKING_TOKEN, &msg).unwrap();
+    let msg = erc20_msg("RWD", &[(STAKING, 10_000)]);
+    chain.instantiate_erc20(REWARD_TOKEN, &msg).unwrap();
+
+    let staking = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        staking_token: STAKING_TOKEN.to_string(),
+        reward_token: REWARD_TOKEN.to_string(),
+        reward_rate: Uint128::from(100u128),
+    };
+    chain
+        .instantiate(staking, STAKING, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn stake(chain: &mut Chain, staker: &str, amount: u128) {
+    let approve = cw_erc20::ExecuteMsg::approve(STAKING, amount);
+    chain.execute(STAKING_TOKEN, staker, &approve, &[]).unwrap();
+    let msg = ExecuteMsg::Stake {
+        amount: Uint128::from(amount),
+    };
+    chain.execute(STAKING, staker, &msg, &[]).unwrap();
+}
+
+fn balance(chain: &Chain, token: &str, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(token, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn staker_info(chain: &Chain, address: &str) -> StakerInfoResponse {
+    let msg = QueryMsg::StakerInfo {
+        address: address.to_string(),
+    };
+    chain.query(STAKING, &msg).unwrap()
+}
+
+#[test]
+fn stake_claim_unstake_cycle() {
+    let mut chain = setup();
+    stake(&mut chain, "alice", 100);
+    assert_eq!(balance(&chain, STAKING_TOKEN, "alice"), 900);
+    assert_eq!(balance(&chain, STAKING_TOKEN, STAKING), 100);
+
+    // bob joins with 3 times alice's stake halfway through
+    chain.set_height(110);
+    stake(&mut chain, "bob", 300);
+    chain.set_height(120);
+    assert_eq!(staker_info(&chain, "alice").pending_rewards.u128(), 1_250);
+    assert_eq!(staker_info(&chain, "bob").pending_rewards.u128(), 750);
+
+    let msg = ExecuteMsg::ClaimRewards {};
+    chain.execute(STAKING, "alice", &msg, &[]).unwrap();
+    chain.execute(STAKING, "bob", &msg, &[]).unwrap();
+    assert_eq!(balance(&chain, REWARD_TOKEN, "alice"), 1_250);
+    assert_eq!(balance(&chain, REWARD_TOKEN, "bob"), 750);
+    assert_eq!(balance(&chain, REWARD_TOKEN, STAKING), 8_000);
+
+    let msg = ExecuteMsg::Unstake {
+        amount: Uint128::from(300u128),
+    };
+    chain.execute(STAKING, "bob", &msg, &[]).unwrap();
+    assert_eq!(balance(&chain, STAKING_TOKEN, "bob"), 1_000);
+    assert_eq!(staker_info(&chain, "bob").staked.u128(), 0);
+    assert_eq!(balance(&chain, STAKING_TOKEN, STAKING), 100);
+}
+
+#[test]
+fn stake_needs_allowance() {
+    let mut chain = setup();
+    let msg = ExecuteMsg::Stake {
+        amount: Uint128::from(100u128),
+    };
+    let err = chain.execute(STAKING, "alice", &msg, &[]).unwrap_err();
+    assert!(err.contains("allowance"), "unexpected error: {}", err);
+    assert_eq!(staker_info(&chain, "alice").staked.u128(), 0);
+}
+
+#[test]
+fn claim_fails_when_rewards_run_out() {
+    let mut chain = setup();
+    stake(&mut chain, "alice", 100);
+    // 200 blocks of 100 rewards are more than the 10000 funded
+    chain.set_height(300);
+    let err = chain
+        .execute(STAKING, "alice", &ExecuteMsg::ClaimRewards {}, &[])
+        .unwrap_err();
+    assert!(
+        err.contains("Insufficient funds"),
+        "unexpected error: {}",
+        err
+    );
+    // the rewards stay pending
+    assert_eq!(staker_info(&chain, "alice").pending_rewards.u128(), 20_000);
+}
@@ -0,0 +1,724 @@
+use cosmwasm_std::{
+    attr, coin, coins, entry_point, to_binary, BankMsg, Binary, Decimal, Deps, DepsMut,
+    DistributionMsg, Env, MessageInfo, Response, StakingMsg, StdResult, Uint128, WasmMsg,
+};
+
+use crate::error::ContractError;
+use crate::msg::{
+    BalanceResponse, ClaimsResponse, ExecuteMsg, InstantiateMsg, InvestmentResponse, QueryMsg,
+    TokenInfoResponse,
+};
+use crate::state::{
+    balances, claims, claims_read, config, config_read, load_balance, supply, supply_read, Claim,
+    Config, Supply,
+};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if deps.querier.query_validator(&msg.validator)?.is_none() {
+        return Err(ContractError::UnknownValidator {
+            validator: msg.validator,
+        });
+    }
+    let state = Config {
+        name: msg.name,
+        symbol: msg.symbol,
+        decimals: msg.decimals,
+        validator: msg.validator,
+        bond_denom: deps.querier.query_bonded_denom()?,
+        unbonding_period: msg.unbonding_period,
+    };
+    config(deps.storage).save(&state)?;
+    supply(deps.storage).save(&Supply::default())?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Transfer { recipient, amount } => try_transfer(deps, info, recipient, amount),
+        ExecuteMsg::Bond {} => try_bond(deps, info),
+        ExecuteMsg::Unbond { amount } => try_unbond(deps, env, info, amount),
+        ExecuteMsg::Claim {} => try_claim(deps, env, info),
+        ExecuteMsg::Reinvest {} => try_reinvest(deps, env),
+        ExecuteMsg::BondRewards {} => try_bond_rewards(deps, env, info),
+    }
+}
+
+/// Transfer
+///
+/// Moves derivative tokens between accounts. The stake backing them stays delegated.
+///
+/// @param recipient the account receiving the tokens
+/// @param amount the number of derivative tokens
+fn try_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let sender_balance = load_balance(deps.storage, &info.sender)?;
+    if sender_balance < amount {
+        return Err(ContractError::InsufficientBalance {
+            balance: sender_balance,
+            required: amount,
+        });
+    }
+    balances(deps.storage).save(
+        info.sender.as_str().as_bytes(),
+        &sender_balance.checked_sub(amount)?,
+    )?;
+    let recipient_balance = load_balance(deps.storage, &recipient)?;
+    balances(deps.storage).save(
+        recipient.as_str().as_bytes(),
+        &recipient_balance.checked_add(amount)?,
+    )?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "transfer"),
+            attr("sender", info.sender),
+            attr("recipient", recipient),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Bond
+///
+/// Delegates the sent stake and issues derivative tokens worth the same at the current
+/// exchange rate. The first bond is issued one to one. Rounding favours the existing holders.
+fn try_bond(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == state.bond_denom && !coin.amount.is_zero() => coin.amount,
+        _ => {
+            return Err(ContractError::InvalidBond {
+                denom: state.bond_denom,
+            })
+        }
+    };
+
+    let mut totals = supply_read(deps.storage).load()?;
+    let minted = if totals.issued.is_zero() || totals.bonded.is_zero() {
+        amount
+    } else {
+        amount.multiply_ratio(totals.issued, totals.bonded)
+    };
+    if minted.is_zero() {
+        return Err(ContractError::BondTooSmall { amount });
+    }
+    totals.bonded = totals.bonded.checked_add(amount)?;
+    totals.issued = totals.issued.checked_add(minted)?;
+    supply(deps.storage).save(&totals)?;
+    let balance = load_balance(deps.storage, &info.sender)?;
+    balances(deps.storage).save(
+        info.sender.as_str().as_bytes(),
+        &balance.checked_add(minted)?,
+    )?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![StakingMsg::Delegate {
+            validator: state.validator,
+            amount: info.funds[0].clone(),
+        }
+        .into()],
+        attributes: vec![
+            attr("action", "bond"),
+            attr("staker", info.sender),
+            attr("bonded", amount),
+            attr("minted", minted),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Unbond
+///
+/// Burns derivative tokens and undelegates the stake they are worth at the current exchange
+/// rate. The stake becomes claimable after the unbonding period. Rounding favours the
+/// remaining holders.
+///
+/// @param amount the number of derivative tokens to burn
+fn try_unbond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let balance = load_balance(deps.storage, &info.sender)?;
+    if balance < amount {
+        return Err(ContractError::InsufficientBalance {
+            balance,
+            required: amount,
+        });
+    }
+
+    let mut totals = supply_read(deps.storage).load()?;
+    let unbonded = amount.multiply_ratio(totals.bonded, totals.issued);
+    if unbonded.is_zero() {
+        return Err(ContractError::UnbondTooSmall { amount });
+    }
+    let claim = Claim {
+        amount: unbonded,
+        release_at: env.block.time.plus_seconds(state.unbonding_period),
+    };
+    totals.issued = totals.issued.checked_sub(amount)?;
+    totals.bonded = totals.bonded.checked_sub(unbonded)?;
+    totals.unbonding.push(claim.clone());
+    supply(deps.storage).save(&totals)?;
+    balances(deps.storage).save(
+        info.sender.as_str().as_bytes(),
+        &balance.checked_sub(amount)?,
+    )?;
+    let sender_key = info.sender.as_str().as_bytes();
+    let mut pending = claims_read(deps.storage)
+        .may_load(sender_key)?
+        .unwrap_or_default();
+    pending.push(claim);
+    claims(deps.storage).save(sender_key, &pending)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![StakingMsg::Undelegate {
+            validator: state.validator,
+            amount: coin(unbonded.u128(), &state.bond_denom),
+        }
+        .into()],
+        attributes: vec![
+            attr("action", "unbond"),
+            attr("staker", info.sender),
+            attr("burned", amount),
+            attr("unbonded", unbonded),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Claim
+///
+/// Pays out the signer's claims whose unbonding period ended. Younger claims stay pending.
+fn try_claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let sender_key = info.sender.as_str().as_bytes();
+    let pending = claims_read(deps.storage)
+        .may_load(sender_key)?
+        .unwrap_or_default();
+    let (matured, pending): (Vec<Claim>, Vec<Claim>) = pending
+        .into_iter()
+        .partition(|claim| claim.release_at <= env.block.time);
+    if matured.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    let mut amount = Uint128::zero();
+    for claim in matured {
+        amount = amount.checked_add(claim.amount)?;
+    }
+
+    let mut totals = supply_read(deps.storage).load()?;
+    totals.release_matured(env.block.time)?;
+    totals.claimable = totals.claimable.checked_sub(amount)?;
+    supply(deps.storage).save(&totals)?;
+    if pending.is_empty() {
+        claims(deps.storage).remove(sender_key);
+    } else {
+        claims(deps.storage).save(sender_key, &pending)?;
+    }
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), state.bond_denom),
+        }
+        .into()],
+        attributes: vec![
+            attr("action", "claim"),
+            attr("staker", info.sender),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Reinvest
+///
+/// Withdraws the staking rewards to the contract, then lets the contract delegate them with
+/// `BondRewards`. Messages run in order, so the rewards arrived by then.
+fn try_reinvest(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let res = Response {
+        submessages: vec![],
+        messages: vec![
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: state.validator,
+            }
+            .into(),
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::BondRewards {})?,
+                send: vec![],
+            }
+            .into(),
+        ],
+        attributes: vec![attr("action", "reinvest")],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Bond rewards
+///
+/// Delegates the contract's balance except what is held for matured claims. This adds to the
+/// bonded stake without issuing tokens, so every derivative token is worth more stake.
+/// Only the contract itself can call this.
+fn try_bond_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let mut totals = supply_read(deps.storage).load()?;
+    totals.release_matured(env.block.time)?;
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &state.bond_denom)?;
+    let rewards = balance.amount.checked_sub(totals.claimable)?;
+    totals.bonded = totals.bonded.checked_add(rewards)?;
+    supply(deps.storage).save(&totals)?;
+
+    let mut messages = vec![];
+    if !rewards.is_zero() {
+        messages.push(
+            StakingMsg::Delegate {
+                validator: state.validator,
+                amount: coin(rewards.u128(), &state.bond_denom),
+            }
+            .into(),
+        );
+    }
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![attr("action", "bond_rewards"), attr("amount", rewards)],
+        data: None,
+    };
+    Ok(res)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&BalanceResponse {
+                balance: load_balance(deps.storage, &address)?,
+            })
+        }
+        QueryMsg::Claims { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let claims = claims_read(deps.storage)
+                .may_load(address.as_str().as_bytes())?
+                .unwrap_or_default();
+            to_binary(&ClaimsResponse { claims })
+        }
+        QueryMsg::TokenInfo {} => {
+            let state = config_read(deps.storage).load()?;
+            let totals = supply_read(deps.storage).load()?;
+            to_binary(&TokenInfoResponse {
+                name: state.name,
+                symbol: state.symbol,
+                decimals: state.decimals,
+                total_supply: totals.issued,
+            })
+        }
+        QueryMsg::Investment {} => {
+            let state = config_read(deps.storage).load()?;
+            let totals = supply_read(deps.storage).load()?;
+            let exchange_rate = if totals.issued.is_zero() {
+                Decimal::one()
+            } else {
+                Decimal::from_ratio(totals.bonded, totals.issued)
+            };
+            to_binary(&InvestmentResponse {
+                validator: state.validator,
+                bond_denom: state.bond_denom,
+                unbonding_period: state.unbonding_period,
+                bonded: totals.bonded,
+                issued: totals.issued,
+                exchange_rate,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{from_binary, CosmosMsg, OwnedDeps, Timestamp, Validator};
+
+    const VALIDATOR: &str = "validator";
+    const DENOM: &str = "ustake";
+    const UNBONDING_PERIOD: u64 = 100;
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let validator = Validator {
+            address: VALIDATOR.to_string(),
+            commission: Decimal::percent(3),
+            max_commission: Decimal::percent(10),
+            max_change_rate: Decimal::percent(1),
+        };
+        deps.querier.update_staking(DENOM, &[validator], &[]);
+        let msg = InstantiateMsg {
+            name: "Staked Token".to_string(),
+            symbol: "DSTK".to_string(),
+            decimals: 6,
+            validator: VALIDATOR.to_string(),
+            unbonding_period: UNBONDING_PERIOD,
+        };
+        instantiate(deps.as_mut(), env_at(0), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    fn bond(deps: DepsMut, sender: &str, amount: u128) -> Response {
+        let info = mock_info(sender, &coins(amount, DENOM));
+        execute(deps, env_at(0), info, ExecuteMsg::Bond {}).unwrap()
+    }
+
+    fn unbond(deps: DepsMut, time: u64, sender: &str, amount: u128) -> Response {
+        let msg = ExecuteMsg::Unbond {
+            amount: Uint128::from(amount),
+        };
+        execute(deps, env_at(time), mock_info(sender, &[]), msg).unwrap()
+    }
+
+    /// Lets the validator pay `rewards` to the contract and reinvests them
+    fn reinvest(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, time: u64, rewards: u128) {
+        let held = deps
+            .as_ref()
+            .querier
+            .query_balance(MOCK_CONTRACT_ADDR, DENOM)
+            .unwrap()
+            .amount
+            .u128();
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(held + rewards, DENOM));
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let res = execute(
+            deps.as_mut(),
+            env_at(time),
+            info,
+            ExecuteMsg::BondRewards {},
+        )
+        .unwrap();
+        // the delegated rewards leave the contract's balance
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(held, DENOM));
+        assert_eq!(res.attributes[1], attr("amount", rewards.to_string()));
+    }
+
+    fn balance(deps: Deps, address: &str) -> u128 {
+        let msg = QueryMsg::Balance {
+            address: address.to_string(),
+        };
+        let res: BalanceResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.balance.u128()
+    }
+
+    fn investment(deps: Deps) -> InvestmentResponse {
+        from_binary(&query(deps, mock_env(), QueryMsg::Investment {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn proper_instantiation() {
+        let deps = setup();
+        assert_eq!(
+            investment(deps.as_ref()),
+            InvestmentResponse {
+                validator: VALIDATOR.to_string(),
+                bond_denom: DENOM.to_string(),
+                unbonding_period: UNBONDING_PERIOD,
+                bonded: Uint128::zero(),
+                issued: Uint128::zero(),
+                exchange_rate: Decimal::one(),
+            }
+        );
+        let res: TokenInfoResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap())
+                .unwrap();
+        assert_eq!(res.symbol, "DSTK");
+        assert!(res.total_supply.is_zero());
+
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            name: "Staked Token".to_string(),
+            symbol: "DSTK".to_string(),
+            decimals: 6,
+            validator: "unknown".to_string(),
+            unbonding_period: UNBONDING_PERIOD,
+        };
+        match instantiate(deps.as_mut(), env_at(0), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::UnknownValidator { validator } => assert_eq!(validator, "unknown"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn bonds_at_exchange_rate() {
+        let mut deps = setup();
+        let res = bond(deps.as_mut(), "alice", 1000);
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Staking(StakingMsg::Delegate {
+                validator: VALIDATOR.to_string(),
+                amount: coin(1000, DENOM),
+            })]
+        );
+        assert_eq!(res.attributes[3], attr("minted", "1000"));
+
+        // rewards raise the value of the issued tokens
+        reinvest(&mut deps, 10, 100);
+        let res = investment(deps.as_ref());
+        assert_eq!(res.bonded.u128(), 1100);
+        assert_eq!(res.exchange_rate, Decimal::from_ratio(11u128, 10u128));
+
+        // so later stakers get fewer tokens for their stake
+        let res = bond(deps.as_mut(), "bob", 550);
+        assert_eq!(res.attributes[3], attr("minted", "500"));
+        assert_eq!(balance(deps.as_ref(), "alice"), 1000);
+        assert_eq!(balance(deps.as_ref(), "bob"), 500);
+        let res = investment(deps.as_ref());
+        assert_eq!(res.bonded.u128(), 1650);
+        assert_eq!(res.issued.u128(), 1500);
+
+        for funds in &[vec![], coins(10, "uatom"), coins(0, DENOM)] {
+            let info = mock_info("carol", funds);
+            match execute(deps.as_mut(), env_at(0), info, ExecuteMsg::Bond {}).unwrap_err() {
+                ContractError::InvalidBond { denom } => assert_eq!(denom, DENOM),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn unbonds_and_claims_after_period() {
+        let mut deps = setup();
+        bond(deps.as_mut(), "alice", 1000);
+        reinvest(&mut deps, 10, 100);
+
+        let res = unbond(deps.as_mut(), 20, "alice", 500);
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Staking(StakingMsg::Undelegate {
+                validator: VALIDATOR.to_string(),
+                amount: coin(550, DENOM),
+            })]
+        );
+        assert_eq!(balance(deps.as_ref(), "alice"), 500);
+        let res = investment(deps.as_ref());
+        assert_eq!(res.bonded.u128(), 550);
+        assert_eq!(res.issued.u128(), 500);
+        // unbonding does not change the rate for the remaining holders
+        assert_eq!(res.exchange_rate, Decimal::from_ratio(11u128, 10u128));
+
+        let msg = QueryMsg::Claims {
+            address: "alice".to_string(),
+        };
+        let res: ClaimsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res.claims,
+            vec![Claim {
+                amount: Uint128::from(550u128),
+                release_at: Timestamp::from_seconds(120),
+            }]
+        );
+
+        let info = mock_info("alice", &[]);
+        match execute(
+            deps.as_mut(),
+            env_at(119),
+            info.clone(),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap_err()
+        {
+            ContractError::NothingToClaim {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // the chain pays the stake back, and the contract holds it for alice
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(550, DENOM));
+        reinvest(&mut deps, 120, 0);
+        let res = execute(
+            deps.as_mut(),
+            env_at(120),
+            info.clone(),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(550, DENOM),
+            })]
+        );
+        match execute(deps.as_mut(), env_at(121), info, ExecuteMsg::Claim {}).unwrap_err() {
+            ContractError::NothingToClaim {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn keeps_value_across_cycles() {
+        let mut deps = setup();
+        bond(deps.as_mut(), "alice", 1000);
+        reinvest(&mut deps, 10, 250);
+        bond(deps.as_mut(), "bob", 500);
+        assert_eq!(balance(deps.as_ref(), "bob"), 400);
+
+        // alice leaves with her share of the rewards, bob's share is untouched
+        let res = unbond(deps.as_mut(), 20, "alice", 1000);
+        assert_eq!(res.attributes[3], attr("unbonded", "1250"));
+        assert_eq!(investment(deps.as_ref()).bonded.u128(), 500);
+
+        // rewards earned after alice left all go to bob, even while her stake unbonds
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(1250, DENOM));
+        reinvest(&mut deps, 120, 100);
+        let res = investment(deps.as_ref());
+        assert_eq!(res.bonded.u128(), 600);
+        assert_eq!(res.issued.u128(), 400);
+
+        // a newcomer buys in at the new rate of 1.5
+        bond(deps.as_mut(), "carol", 300);
+        assert_eq!(balance(deps.as_ref(), "carol"), 200);
+        let res = unbond(deps.as_mut(), 130, "bob", 400);
+        assert_eq!(res.attributes[3], attr("unbonded", "600"));
+        let res = unbond(deps.as_mut(), 130, "carol", 200);
+        assert_eq!(res.attributes[3], attr("unbonded", "300"));
+        let res = investment(deps.as_ref());
+        assert!(res.bonded.is_zero());
+        assert!(res.issued.is_zero());
+    }
+
+    #[test]
+    fn rounds_in_favour_of_holders() {
+        let mut deps = setup();
+        bond(deps.as_mut(), "alice", 3);
+        reinvest(&mut deps, 10, 1);
+
+        // one token is worth 4/3 stake now
+        let info = mock_info("bob", &coins(1, DENOM));
+        match execute(deps.as_mut(), env_at(0), info, ExecuteMsg::Bond {}).unwrap_err() {
+            ContractError::BondTooSmall { amount } => assert_eq!(amount.u128(), 1),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = bond(deps.as_mut(), "bob", 2);
+        assert_eq!(res.attributes[3], attr("minted", "1"));
+
+        // bob's token is worth 6/4 stake now, which rounds down to 1
+        let res = unbond(deps.as_mut(), 20, "bob", 1);
+        assert_eq!(res.attributes[3], attr("unbonded", "1"));
+        let res = investment(deps.as_ref());
+        assert_eq!(res.bonded.u128(), 5);
+        assert_eq!(res.issued.u128(), 3);
+    }
+
+    #[test]
+    fn reinvests_through_self_call() {
+        let mut deps = setup();
+        let res = execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Reinvest {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                    validator: VALIDATOR.to_string(),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                    msg: to_binary(&ExecuteMsg::BondRewards {}).unwrap(),
+                    send: vec![],
+                }),
+            ]
+        );
+
+        let info = mock_info("anyone", &[]);
+        match execute(deps.as_mut(), env_at(0), info, ExecuteMsg::BondRewards {}).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn transfers_derivatives() {
+        let mut deps = setup();
+        bond(deps.as_mut(), "alice", 1000);
+        let msg = ExecuteMsg::Transfer {
+            recipient: "bob".to_string(),
+            amount: Uint128::from(400u128),
+        };
+        execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(balance(deps.as_ref(), "alice"), 600);
+        assert_eq!(balance(deps.as_ref(), "bob"), 400);
+
+        match execute(deps.as_mut(), env_at(0), mock_info("carol", &[]), msg).unwrap_err() {
+            ContractError::InsufficientBalance { balance, required } => {
+                assert!(balance.is_zero());
+                assert_eq!(required.u128(), 400);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // the receiver can unbond what it received
+        let res = unbond(deps.as_mut(), 0, "bob", 400);
+        assert_eq!(res.attributes[3], attr("unbonded", "400"));
+    }
+}
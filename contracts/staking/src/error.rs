@@ -0,0 +1,35 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Validator {validator} not found")]
+    UnknownValidator { validator: String },
+
+    #[error("Send exactly one coin of {denom} to bond")]
+    InvalidBond { denom: String },
+
+    #[error("Amount must be greater than zero")]
+    ZeroAmount {},
+
+    #[error("Insufficient balance: {balance} available, {required} required")]
+    InsufficientBalance { balance: Uint128, required: Uint128 },
+
+    #[error("Bonding {amount} stake is worth no derivative tokens")]
+    BondTooSmall { amount: Uint128 },
+
+    #[error("Unbonding {amount} derivative tokens is worth no stake")]
+    UnbondTooSmall { amount: Uint128 },
+
+    #[error("Nothing to claim yet")]
+    NothingToClaim {},
+}
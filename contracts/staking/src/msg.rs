@@ -0,0 +1,87 @@
+use cosmwasm_std::{Decimal, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::Claim;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Name of the derivative token
+    pub name: String,
+    /// Ticker of the derivative token
+    pub symbol: String,
+    pub decimals: u8,
+    /// The validator all stake is delegated to
+    pub validator: String,
+    /// Seconds between `Unbond` and the earliest `Claim` of the unbonded stake.
+    /// This must not be shorter than the chain's unbonding time.
+    pub unbonding_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Moves derivative tokens from the signer to `recipient`
+    Transfer { recipient: String, amount: Uint128 },
+    /// Delegates the sent native stake and issues derivative tokens at the current
+    /// exchange rate
+    Bond {},
+    /// Burns `amount` derivative tokens and undelegates the stake they are worth.
+    /// The stake can be claimed after the unbonding period.
+    Unbond { amount: Uint128 },
+    /// Pays out all of the signer's claims whose unbonding period ended
+    Claim {},
+    /// Withdraws the staking rewards and delegates them, which raises the exchange rate.
+    /// Anyone can call this.
+    Reinvest {},
+    /// Delegates the contract's balance that is not reserved for claims.
+    /// Sent by the contract to itself during `Reinvest`.
+    BondRewards {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the derivative balance of `address`
+    Balance {
+        address: String,
+    },
+    /// Returns the pending claims of `address`
+    Claims {
+        address: String,
+    },
+    TokenInfo {},
+    /// Returns the bonded stake, the issued derivatives and the exchange rate between them
+    Investment {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BalanceResponse {
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimsResponse {
+    pub claims: Vec<Claim>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenInfoResponse {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InvestmentResponse {
+    pub validator: String,
+    pub bond_denom: String,
+    pub unbonding_period: u64,
+    /// Native stake delegated by the contract
+    pub bonded: Uint128,
+    /// Derivative tokens in circulation
+    pub issued: Uint128,
+    /// Native stake paid for one derivative token when unbonding
+    pub exchange_rate: Decimal,
+}
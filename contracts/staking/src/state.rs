@@ -0,0 +1,105 @@
+use cosmwasm_std::{Addr, StdResult, Storage, Timestamp, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static SUPPLY_KEY: &[u8] = b"supply";
+static BALANCES_KEY: &[u8] = b"balances";
+static CLAIMS_KEY: &[u8] = b"claims";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Name of the derivative token
+    pub name: String,
+    /// Ticker of the derivative token
+    pub symbol: String,
+    pub decimals: u8,
+    /// The validator all stake is delegated to
+    pub validator: String,
+    /// The native staking denom, as reported by the chain
+    pub bond_denom: String,
+    /// Seconds between `Unbond` and the earliest `Claim` of the unbonded stake.
+    /// This must not be shorter than the chain's unbonding time.
+    pub unbonding_period: u64,
+}
+
+/// The two sides of the exchange rate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Supply {
+    /// Derivative tokens in circulation
+    pub issued: Uint128,
+    /// Native stake delegated by the contract, including reinvested rewards
+    pub bonded: Uint128,
+    /// Native stake being undelegated, oldest first. The chain pays it back to the contract
+    /// around `release_at`.
+    pub unbonding: Vec<Claim>,
+    /// Unbonded stake the contract holds for claims that matured but were not paid out yet
+    pub claimable: Uint128,
+}
+
+impl Supply {
+    /// Moves the unbonding stake released by `now` into `claimable`
+    pub fn release_matured(&mut self, now: Timestamp) -> StdResult<()> {
+        let matured = self
+            .unbonding
+            .iter()
+            .take_while(|claim| claim.release_at <= now)
+            .count();
+        for claim in self.unbonding.drain(..matured) {
+            self.claimable = self.claimable.checked_add(claim.amount)?;
+        }
+        Ok(())
+    }
+}
+
+/// Native stake an address can claim once `release_at` passed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Claim {
+    pub amount: Uint128,
+    pub release_at: Timestamp,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn supply(storage: &mut dyn Storage) -> Singleton<'_, Supply> {
+    singleton(storage, SUPPLY_KEY)
+}
+
+pub fn supply_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Supply> {
+    singleton_read(storage, SUPPLY_KEY)
+}
+
+/// Derivative balances by address
+pub fn balances(storage: &mut dyn Storage) -> Bucket<'_, Uint128> {
+    bucket(storage, BALANCES_KEY)
+}
+
+pub fn balances_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Uint128> {
+    bucket_read(storage, BALANCES_KEY)
+}
+
+/// Pending claims by address, oldest first
+pub fn claims(storage: &mut dyn Storage) -> Bucket<'_, Vec<Claim>> {
+    bucket(storage, CLAIMS_KEY)
+}
+
+pub fn claims_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Vec<Claim>> {
+    bucket_read(storage, CLAIMS_KEY)
+}
+
+/// The derivative balance of `address`, zero if it never held any
+pub fn load_balance(storage: &dyn Storage, address: &Addr) -> StdResult<Uint128> {
+    Ok(balances_read(storage)
+        .may_load(address.as_str().as_bytes())?
+        .unwrap_or_default())
+}
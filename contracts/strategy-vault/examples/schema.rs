@@ -0,0 +1,27 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use cw_strategy_vault::msg::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, SharesResponse, StrategyExecuteMsg, StrategyQueryMsg,
+    TotalAssetsResponse, TotalValueResponse,
+};
+use cw_strategy_vault::state::Config;
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(StrategyExecuteMsg), &out_dir);
+    export_schema(&schema_for!(StrategyQueryMsg), &out_dir);
+    export_schema(&schema_for!(TotalValueResponse), &out_dir);
+    export_schema(&schema_for!(TotalAssetsResponse), &out_dir);
+    export_schema(&schema_for!(SharesResponse), &out_dir);
+    export_schema(&schema_for!(Config), &out_dir);
+}
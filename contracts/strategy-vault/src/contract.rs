@@ -0,0 +1,510 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, Uint128, WasmMsg,
+};
+use cw_erc20::BalanceResponse;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, SharesResponse, StrategyExecuteMsg, StrategyQueryMsg,
+    TotalAssetsResponse, TotalValueResponse,
+};
+use crate::state::{
+    config, config_read, shares, shares_read, total_shares, total_shares_read, Config,
+};
+
+/// Shares of the first deposit that are locked in the vault forever. Without them, the first
+/// depositor could hold a single share and donate tokens to the vault, inflating the share
+/// price until later deposits round down to few or no shares.
+pub const MINIMUM_SHARES: u128 = 1000;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        strategy: deps.api.addr_validate(&msg.strategy)?,
+        rebalance_threshold: msg.rebalance_threshold,
+    };
+    config(deps.storage).save(&state)?;
+    total_shares(deps.storage).save(&Uint128::zero())?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Deposit { amount } => try_deposit(deps, env, info, amount),
+        ExecuteMsg::Withdraw { shares } => try_withdraw(deps, env, info, shares),
+        ExecuteMsg::Rebalance {} => try_rebalance(deps, env),
+    }
+}
+
+fn try_deposit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let state = config_read(deps.storage).load()?;
+    let total_assets = query_total_assets(deps.as_ref(), &env, &state)?;
+    let supply = total_shares_read(deps.storage).load()?;
+
+    // shares are priced by the assets before this deposit arrives
+    let (minted, new_supply) = if supply.is_zero() {
+        if amount.u128() <= MINIMUM_SHARES {
+            return Err(ContractError::FirstDepositTooSmall {
+                min: MINIMUM_SHARES,
+            });
+        }
+        (amount.checked_sub(Uint128::from(MINIMUM_SHARES))?, amount)
+    } else if total_assets.is_zero() {
+        return Err(ContractError::NoAssets {});
+    } else {
+        let minted = amount.multiply_ratio(supply, total_assets);
+        (minted, supply.checked_add(minted)?)
+    };
+    if minted.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    shares(deps.storage).update(
+        info.sender.as_str().as_bytes(),
+        |owned| -> Result<_, ContractError> { Ok(owned.unwrap_or_default().checked_add(minted)?) },
+    )?;
+    total_shares(deps.storage).save(&new_supply)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer_from(
+        info.sender.as_str(),
+        env.contract.address.as_str(),
+        amount,
+    );
+    let res = Response {
+        submessages: vec![],
+        messages: vec![execute_msg(&state.token, transfer.to_binary()?)],
+        attributes: vec![
+            attr("action", "deposit"),
+            attr("depositor", info.sender),
+            attr("amount", amount),
+            attr("shares", minted),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount_shares: Uint128,
+) -> Result<Response, ContractError> {
+    if amount_shares.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let key = info.sender.as_str().as_bytes();
+    let owned = shares_read(deps.storage).may_load(key)?.unwrap_or_default();
+    if owned < amount_shares {
+        return Err(ContractError::InsufficientShares {
+            shares: owned,
+            required: amount_shares,
+        });
+    }
+
+    let state = config_read(deps.storage).load()?;
+    let idle = query_idle(deps.as_ref(), &env, &state)?;
+    let total_assets = idle.checked_add(query_deployed(deps.as_ref(), &state)?)?;
+    let supply = total_shares_read(deps.storage).load()?;
+    let amount = amount_shares.multiply_ratio(total_assets, supply);
+
+    shares(deps.storage).save(key, &owned.checked_sub(amount_shares)?)?;
+    total_shares(deps.storage).save(&supply.checked_sub(amount_shares)?)?;
+
+    let mut messages = vec![];
+    // idle tokens are paid out first, the strategy covers the rest
+    if amount > idle {
+        let withdraw = StrategyExecuteMsg::Withdraw {
+            amount: amount.checked_sub(idle)?,
+        };
+        messages.push(execute_msg(&state.strategy, to_binary(&withdraw)?));
+    }
+    let transfer = cw_erc20::ExecuteMsg::transfer(info.sender.as_str(), amount);
+    messages.push(execute_msg(&state.token, transfer.to_binary()?));
+
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", "withdraw"),
+            attr("owner", info.sender),
+            attr("shares", amount_shares),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_rebalance(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let idle = query_idle(deps.as_ref(), &env, &state)?;
+    if idle <= state.rebalance_threshold {
+        return Err(ContractError::BelowThreshold {
+            idle,
+            threshold: state.rebalance_threshold,
+        });
+    }
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(state.strategy.as_str(), idle);
+    let deposit = StrategyExecuteMsg::Deposit { amount: idle };
+    let res = Response {
+        submessages: vec![],
+        messages: vec![
+            execute_msg(&state.token, transfer.to_binary()?),
+            execute_msg(&state.strategy, to_binary(&deposit)?),
+        ],
+        attributes: vec![attr("action", "rebalance"), attr("deployed", idle)],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn execute_msg(contract: &Addr, msg: Binary) -> CosmosMsg {
+    WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg,
+        send: vec![],
+    }
+    .into()
+}
+
+/// Tokens held by the vault itself
+fn query_idle(deps: Deps, env: &Env, state: &Config) -> StdResult<Uint128> {
+    let msg = cw_erc20::QueryMsg::balance(env.contract.address.as_str());
+    let res: BalanceResponse = deps.querier.query_wasm_smart(&state.token, &msg)?;
+    Ok(res.balance)
+}
+
+/// Value of the tokens deployed in the strategy
+fn query_deployed(deps: Deps, state: &Config) -> StdResult<Uint128> {
+    let res: TotalValueResponse = deps
+        .querier
+        .query_wasm_smart(&state.strategy, &StrategyQueryMsg::TotalValue {})?;
+    Ok(res.value)
+}
+
+fn query_total_assets(deps: Deps, env: &Env, state: &Config) -> Result<Uint128, ContractError> {
+    let idle = query_idle(deps, env, state)?;
+    Ok(idle.checked_add(query_deployed(deps, state)?)?)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::TotalAssets {} => {
+            let state = config_read(deps.storage).load()?;
+            let idle = query_idle(deps, &env, &state)?;
+            to_binary(&TotalAssetsResponse {
+                total_assets: idle.checked_add(query_deployed(deps, &state)?)?,
+                idle,
+                total_shares: total_shares_read(deps.storage).load()?,
+            })
+        }
+        QueryMsg::Shares { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let owned = shares_read(deps.storage).may_load(address.as_str().as_bytes())?;
+            to_binary(&SharesResponse {
+                shares: owned.unwrap_or_default(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{
+        from_binary, from_slice, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+        QueryRequest, SystemError, SystemResult, WasmQuery,
+    };
+
+    /// The token at "token" and a strategy at "strategy" paying a fixed yield
+    struct ChainQuerier {
+        /// Token balance of the vault
+        idle: u128,
+        /// Tokens the vault deployed into the strategy
+        deployed: u128,
+        /// Yield of the strategy in percent
+        yield_percent: u128,
+    }
+
+    impl Querier for ChainQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            let response = match request {
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                    if contract_addr == "token" =>
+                {
+                    let query: cw_erc20::QueryMsg = from_binary(&msg).unwrap();
+                    assert_eq!(query, cw_erc20::QueryMsg::balance(MOCK_CONTRACT_ADDR));
+                    to_binary(&BalanceResponse {
+                        balance: Uint128::from(self.idle),
+                    })
+                }
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                    if contract_addr == "strategy" =>
+                {
+                    let query: StrategyQueryMsg = from_binary(&msg).unwrap();
+                    assert_eq!(query, StrategyQueryMsg::TotalValue {});
+                    to_binary(&TotalValueResponse {
+                        value: Uint128::from(self.deployed * (100 + self.yield_percent) / 100),
+                    })
+                }
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, .. }) => {
+                    return SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr,
+                    })
+                }
+                _ => {
+                    return SystemResult::Err(SystemError::UnsupportedRequest {
+                        kind: "non-wasm".to_string(),
+                    })
+                }
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, ChainQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: ChainQuerier {
+                idle: 0,
+                deployed: 0,
+                yield_percent: 0,
+            },
+        };
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            strategy: "strategy".to_string(),
+            rebalance_threshold: Uint128::from(50u128),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn run(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn deposit(amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Deposit {
+            amount: Uint128::from(amount),
+        }
+    }
+
+    fn withdraw(shares: u128) -> ExecuteMsg {
+        ExecuteMsg::Withdraw {
+            shares: Uint128::from(shares),
+        }
+    }
+
+    fn token_msg(contract: &str, msg: cw_erc20::ExecuteMsg) -> CosmosMsg {
+        execute_msg(&Addr::unchecked(contract), msg.to_binary().unwrap())
+    }
+
+    fn strategy_msg(msg: StrategyExecuteMsg) -> CosmosMsg {
+        execute_msg(&Addr::unchecked("strategy"), to_binary(&msg).unwrap())
+    }
+
+    fn shares_of(deps: Deps, address: &str) -> u128 {
+        let msg = QueryMsg::Shares {
+            address: address.to_string(),
+        };
+        let res: SharesResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.shares.u128()
+    }
+
+    fn total_assets(deps: Deps) -> TotalAssetsResponse {
+        from_binary(&query(deps, mock_env(), QueryMsg::TotalAssets {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn deposit_pulls_tokens_for_shares() {
+        let mut deps = setup();
+        match run(deps.as_mut(), "alice", deposit(1000)).unwrap_err() {
+            ContractError::FirstDepositTooSmall { min: 1000 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // the first 1000 shares are locked
+        let res = run(deps.as_mut(), "alice", deposit(1100)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![token_msg(
+                "token",
+                cw_erc20::ExecuteMsg::transfer_from("alice", MOCK_CONTRACT_ADDR, 1100u128)
+            )]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "deposit"),
+                attr("depositor", "alice"),
+                attr("amount", "1100"),
+                attr("shares", "100"),
+            ]
+        );
+        assert_eq!(shares_of(deps.as_ref(), "alice"), 100);
+        deps.querier.idle = 1100;
+        assert_eq!(total_assets(deps.as_ref()).total_shares.u128(), 1100);
+
+        match run(deps.as_mut(), "alice", deposit(0)).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rebalance_deploys_idle_tokens() {
+        let mut deps = setup();
+        deps.querier.idle = 50;
+        match run(deps.as_mut(), "anyone", ExecuteMsg::Rebalance {}).unwrap_err() {
+            ContractError::BelowThreshold { idle, threshold } => {
+                assert_eq!(idle.u128(), 50);
+                assert_eq!(threshold.u128(), 50);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        deps.querier.idle = 51;
+        let res = run(deps.as_mut(), "anyone", ExecuteMsg::Rebalance {}).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                token_msg("token", cw_erc20::ExecuteMsg::transfer("strategy", 51u128)),
+                strategy_msg(StrategyExecuteMsg::Deposit {
+                    amount: Uint128::from(51u128)
+                }),
+            ]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "rebalance"), attr("deployed", "51")]
+        );
+    }
+
+    #[test]
+    fn share_price_increases_after_rebalancing() {
+        let mut deps = setup();
+        deps.querier.yield_percent = 10;
+        run(deps.as_mut(), "alice", deposit(2000)).unwrap();
+        deps.querier.idle = 2000;
+        assert_eq!(total_assets(deps.as_ref()).total_assets.u128(), 2000);
+
+        // idle tokens earn no yield
+        run(deps.as_mut(), "anyone", ExecuteMsg::Rebalance {}).unwrap();
+        deps.querier.idle = 0;
+        deps.querier.deployed = 2000;
+        assert_eq!(
+            total_assets(deps.as_ref()),
+            TotalAssetsResponse {
+                total_assets: Uint128::from(2200u128),
+                idle: Uint128::zero(),
+                total_shares: Uint128::from(2000u128),
+            }
+        );
+
+        // a share is worth 1.1 tokens now
+        let res = run(deps.as_mut(), "bob", deposit(110)).unwrap();
+        assert_eq!(res.attributes[3], attr("shares", "100"));
+        deps.querier.idle = 110;
+        assert_eq!(shares_of(deps.as_ref(), "bob"), 100);
+
+        let res = run(deps.as_mut(), "alice", withdraw(1000)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                strategy_msg(StrategyExecuteMsg::Withdraw {
+                    amount: Uint128::from(990u128)
+                }),
+                token_msg("token", cw_erc20::ExecuteMsg::transfer("alice", 1100u128)),
+            ]
+        );
+        assert_eq!(res.attributes[3], attr("amount", "1100"));
+        assert_eq!(shares_of(deps.as_ref(), "alice"), 0);
+        assert_eq!(total_assets(deps.as_ref()).total_shares.u128(), 1100);
+    }
+
+    #[test]
+    fn withdraw_pays_idle_tokens_first() {
+        let mut deps = setup();
+        run(deps.as_mut(), "alice", deposit(1100)).unwrap();
+        deps.querier.idle = 1100;
+
+        let res = run(deps.as_mut(), "alice", withdraw(40)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![token_msg(
+                "token",
+                cw_erc20::ExecuteMsg::transfer("alice", 40u128)
+            )]
+        );
+        assert_eq!(shares_of(deps.as_ref(), "alice"), 60);
+    }
+
+    #[test]
+    fn withdraw_requires_shares() {
+        let mut deps = setup();
+        run(deps.as_mut(), "alice", deposit(1100)).unwrap();
+        deps.querier.idle = 1100;
+
+        match run(deps.as_mut(), "alice", withdraw(101)).unwrap_err() {
+            ContractError::InsufficientShares { shares, required } => {
+                assert_eq!(shares.u128(), 100);
+                assert_eq!(required.u128(), 101);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), "bob", withdraw(1)).unwrap_err() {
+            ContractError::InsufficientShares { shares, .. } => assert!(shares.is_zero()),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(shares_of(deps.as_ref(), "alice"), 100);
+    }
+
+    #[test]
+    fn donations_cannot_inflate_away_deposits() {
+        let mut deps = setup();
+        // the attacker keeps a single share and donates a million tokens
+        run(deps.as_mut(), "attacker", deposit(1001)).unwrap();
+        assert_eq!(shares_of(deps.as_ref(), "attacker"), 1);
+        deps.querier.idle = 1001 + 1_000_000;
+
+        // the victim still gets shares worth about their deposit
+        let res = run(deps.as_mut(), "victim", deposit(2_000_000)).unwrap();
+        assert_eq!(res.attributes[3], attr("shares", "1999"));
+        deps.querier.idle += 2_000_000;
+
+        // while the donation mostly went to the locked shares
+        let res = run(deps.as_mut(), "attacker", withdraw(1)).unwrap();
+        assert_eq!(res.attributes[3], attr("amount", "1000"));
+        deps.querier.idle -= 1000;
+        let res = run(deps.as_mut(), "victim", withdraw(1999)).unwrap();
+        assert_eq!(res.attributes[3], attr("amount", "1999667"));
+    }
+}
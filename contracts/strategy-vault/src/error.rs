@@ -0,0 +1,26 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Insufficient shares (shares {shares}, required={required})")]
+    InsufficientShares { shares: Uint128, required: Uint128 },
+
+    #[error("Idle tokens {idle} do not exceed the rebalance threshold {threshold}")]
+    BelowThreshold { idle: Uint128, threshold: Uint128 },
+
+    #[error("First deposit must be more than {min} tokens")]
+    FirstDepositTooSmall { min: u128 },
+
+    #[error("Shares exist but the vault holds no assets")]
+    NoAssets {},
+}
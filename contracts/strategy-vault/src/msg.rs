@@ -0,0 +1,74 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token contract accepted as deposit
+    pub token: String,
+    /// The strategy contract idle tokens are deployed into
+    pub strategy: String,
+    /// `Rebalance` only deploys idle tokens once they exceed this amount
+    pub rebalance_threshold: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Moves `amount` tokens from the signer into the vault in exchange for shares.
+    /// The signer must have approved the vault to spend them. The first deposit must be more
+    /// than 1000 tokens, of which 1000 shares are locked in the vault.
+    Deposit { amount: Uint128 },
+    /// Burns `shares` of the signer and sends it the tokens they are worth
+    Withdraw { shares: Uint128 },
+    /// Deploys all idle tokens into the strategy if they exceed the rebalance threshold.
+    /// Anyone can call this.
+    Rebalance {},
+}
+
+/// The messages a strategy contract must accept from the vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyExecuteMsg {
+    /// Notifies the strategy that the vault just transferred `amount` tokens to it
+    Deposit { amount: Uint128 },
+    /// Asks the strategy to transfer `amount` tokens back to the vault
+    Withdraw { amount: Uint128 },
+}
+
+/// The query a strategy contract must answer for the vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyQueryMsg {
+    /// Current value of the vault's position in tokens, including accrued yield
+    TotalValue {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalValueResponse {
+    pub value: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Returns the tokens held by the vault and deployed in the strategy, and the total shares
+    TotalAssets {},
+    Shares {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalAssetsResponse {
+    /// Idle tokens plus the value of the strategy position
+    pub total_assets: Uint128,
+    pub idle: Uint128,
+    pub total_shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharesResponse {
+    pub shares: Uint128,
+}
@@ -0,0 +1,43 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static TOTAL_SHARES_KEY: &[u8] = b"total_shares";
+static SHARES_KEY: &[u8] = b"shares";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub token: Addr,
+    pub strategy: Addr,
+    pub rebalance_threshold: Uint128,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn total_shares(storage: &mut dyn Storage) -> Singleton<'_, Uint128> {
+    singleton(storage, TOTAL_SHARES_KEY)
+}
+
+pub fn total_shares_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Uint128> {
+    singleton_read(storage, TOTAL_SHARES_KEY)
+}
+
+/// Shares by owner address
+pub fn shares(storage: &mut dyn Storage) -> Bucket<'_, Uint128> {
+    bucket(storage, SHARES_KEY)
+}
+
+pub fn shares_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Uint128> {
+    bucket_read(storage, SHARES_KEY)
+}
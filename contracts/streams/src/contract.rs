@@ -0,0 +1,413 @@
+use std::cmp::min;
+
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, OverflowError, Response,
+    StdResult, Storage, Uint128,
+};
+use cw_erc20::{AllowanceResponse, BalanceResponse};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, StreamResponse};
+use crate::state::{stream_count, stream_count_read, streams, streams_read, Stream};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    stream_count(deps.storage).save(&0)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateStream {
+            token,
+            recipient,
+            amount_per_block,
+            end_height,
+        } => try_create_stream(
+            deps,
+            env,
+            info,
+            token,
+            recipient,
+            amount_per_block,
+            end_height,
+        ),
+        ExecuteMsg::Ping { id } => try_ping(deps, env, id),
+        ExecuteMsg::Cancel { id } => try_cancel(deps, env, info, id),
+    }
+}
+
+/// Create stream
+///
+/// Starts a stream from the signer at the current height. The total over the whole stream must
+/// fit into a `Uint128`, so no later accrual can overflow.
+///
+/// @param token the erc20 token the stream pays in
+/// @param recipient receives the accrued tokens
+/// @param amount_per_block accrued for every block
+/// @param end_height no more accrual from this height on
+fn try_create_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token: String,
+    recipient: String,
+    amount_per_block: Uint128,
+    end_height: u64,
+) -> Result<Response, ContractError> {
+    if amount_per_block.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    if end_height <= env.block.height {
+        return Err(ContractError::InvalidEndHeight {
+            height: env.block.height,
+        });
+    }
+    let total = amount_per_block.checked_mul(Uint128::from(end_height - env.block.height))?;
+    let stream = Stream {
+        payer: info.sender,
+        token: deps.api.addr_validate(&token)?,
+        recipient: deps.api.addr_validate(&recipient)?,
+        amount_per_block,
+        start_height: env.block.height,
+        end_height,
+        paid: Uint128::zero(),
+        cancelled: false,
+    };
+    let id = stream_count_read(deps.storage).load()?;
+    stream_count(deps.storage).save(&(id + 1))?;
+    streams(deps.storage).save(&id.to_be_bytes(), &stream)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "create_stream"),
+            attr("id", id),
+            attr("payer", stream.payer),
+            attr("recipient", stream.recipient),
+            attr("total", total),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Ping
+///
+/// Transfers what is owed from the payer to the recipient. If the payer's allowance or balance
+/// runs short, only that much is paid and the rest stays owed for a later ping.
+///
+/// @param id the stream to pay out
+fn try_ping(deps: DepsMut, env: Env, id: u64) -> Result<Response, ContractError> {
+    let mut stream = load_stream(deps.storage, id)?;
+    let owed = owed(&stream, env.block.height)?;
+    if owed.is_zero() {
+        return Err(ContractError::NothingOwed {});
+    }
+
+    let query = cw_erc20::QueryMsg::Allowance {
+        owner: stream.payer.to_string(),
+        spender: env.contract.address.to_string(),
+    };
+    let allowance: AllowanceResponse = deps.querier.query_wasm_smart(&stream.token, &query)?;
+    let query = cw_erc20::QueryMsg::balance(stream.payer.as_str());
+    let balance: BalanceResponse = deps.querier.query_wasm_smart(&stream.token, &query)?;
+    let amount = min(owed, min(allowance.allowance, balance.balance));
+    if amount.is_zero() {
+        return Err(ContractError::PayerExhausted { owed });
+    }
+    stream.paid = stream.paid.checked_add(amount)?;
+    streams(deps.storage).save(&id.to_be_bytes(), &stream)?;
+
+    let payment = cw_erc20::ExecuteMsg::transfer_from(
+        stream.payer.as_str(),
+        stream.recipient.as_str(),
+        amount,
+    );
+    let res = Response {
+        submessages: vec![],
+        messages: vec![payment.into_cosmos_msg(&stream.token)?],
+        attributes: vec![
+            attr("action", "ping"),
+            attr("id", id),
+            attr("amount", amount),
+            attr("owed", owed.checked_sub(amount)?),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Cancel
+///
+/// Ends the stream at the current height. Nothing is paid out here, the recipient can still
+/// ping for what accrued before.
+///
+/// @param id the stream to cancel
+fn try_cancel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut stream = load_stream(deps.storage, id)?;
+    if stream.payer != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.height >= stream.end_height {
+        return Err(ContractError::Ended {
+            end_height: stream.end_height,
+        });
+    }
+    stream.end_height = env.block.height;
+    stream.cancelled = true;
+    streams(deps.storage).save(&id.to_be_bytes(), &stream)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "cancel"),
+            attr("id", id),
+            attr("owed", owed(&stream, env.block.height)?),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Accrued at `height` minus what was paid already
+fn owed(stream: &Stream, height: u64) -> Result<Uint128, OverflowError> {
+    let blocks = min(height, stream.end_height).saturating_sub(stream.start_height);
+    stream
+        .amount_per_block
+        .checked_mul(Uint128::from(blocks))?
+        .checked_sub(stream.paid)
+}
+
+fn load_stream(storage: &dyn Storage, id: u64) -> Result<Stream, ContractError> {
+    streams_read(storage)
+        .may_load(&id.to_be_bytes())?
+        .ok_or(ContractError::UnknownStream { id })
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Stream { id } => {
+            let stream = streams_read(deps.storage).load(&id.to_be_bytes())?;
+            to_binary(&StreamResponse {
+                id,
+                payer: stream.payer.to_string(),
+                token: stream.token.to_string(),
+                recipient: stream.recipient.to_string(),
+                amount_per_block: stream.amount_per_block,
+                start_height: stream.start_height,
+                end_height: stream.end_height,
+                paid: stream.paid,
+                owed: owed(&stream, env.block.height)?,
+                cancelled: stream.cancelled,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+    use cosmwasm_std::{
+        from_binary, from_slice, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+        QueryRequest, SystemError, SystemResult, WasmQuery,
+    };
+
+    /// Answers allowance and balance queries at any token
+    struct PayerQuerier {
+        allowance: u128,
+        balance: u128,
+    }
+
+    impl Querier for PayerQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            let res = match request {
+                QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) => match from_binary(&msg) {
+                    Ok(cw_erc20::QueryMsg::Allowance { .. }) => to_binary(&AllowanceResponse {
+                        allowance: Uint128::from(self.allowance),
+                        expires: cw_erc20::Expiration::Never {},
+                    }),
+                    Ok(cw_erc20::QueryMsg::Balance { .. }) => to_binary(&BalanceResponse {
+                        balance: Uint128::from(self.balance),
+                    }),
+                    _ => panic!("unexpected query"),
+                },
+                _ => {
+                    return SystemResult::Err(SystemError::UnsupportedRequest {
+                        kind: "non-wasm".to_string(),
+                    })
+                }
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        }
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    /// Instantiates with stream 0 paying 10 per block from height 100 to 200
+    fn setup(allowance: u128, balance: u128) -> OwnedDeps<MockStorage, MockApi, PayerQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: PayerQuerier { allowance, balance },
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env_at(100), info, InstantiateMsg {}).unwrap();
+        let msg = create_msg(10, 200);
+        execute(deps.as_mut(), env_at(100), mock_info("payer", &[]), msg).unwrap();
+        deps
+    }
+
+    fn create_msg(amount_per_block: u128, end_height: u64) -> ExecuteMsg {
+        ExecuteMsg::CreateStream {
+            token: "token".to_string(),
+            recipient: "recipient".to_string(),
+            amount_per_block: Uint128::from(amount_per_block),
+            end_height,
+        }
+    }
+
+    fn ping(deps: DepsMut, height: u64) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            env_at(height),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Ping { id: 0 },
+        )
+    }
+
+    fn payment(amount: u128) -> Vec<cosmwasm_std::CosmosMsg> {
+        let payment = cw_erc20::ExecuteMsg::transfer_from("payer", "recipient", amount);
+        vec![payment.into_cosmos_msg("token").unwrap()]
+    }
+
+    fn stream(deps: Deps, height: u64) -> StreamResponse {
+        let msg = QueryMsg::Stream { id: 0 };
+        from_binary(&query(deps, env_at(height), msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn validates_stream() {
+        let mut deps = setup(0, 0);
+        let info = mock_info("payer", &[]);
+        match execute(deps.as_mut(), env_at(100), info.clone(), create_msg(0, 200)).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match execute(
+            deps.as_mut(),
+            env_at(100),
+            info.clone(),
+            create_msg(10, 100),
+        )
+        .unwrap_err()
+        {
+            ContractError::InvalidEndHeight { height: 100 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match execute(deps.as_mut(), env_at(100), info, create_msg(u128::MAX, 102)).unwrap_err() {
+            ContractError::Overflow(_) => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Ping { id: 1 },
+        )
+        .unwrap_err()
+        {
+            ContractError::UnknownStream { id: 1 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn accrues_across_irregular_pings() {
+        let mut deps = setup(u128::MAX, u128::MAX);
+        match ping(deps.as_mut(), 100).unwrap_err() {
+            ContractError::NothingOwed {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(ping(deps.as_mut(), 103).unwrap().messages, payment(30));
+        assert_eq!(ping(deps.as_mut(), 104).unwrap().messages, payment(10));
+        match ping(deps.as_mut(), 104).unwrap_err() {
+            ContractError::NothingOwed {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(ping(deps.as_mut(), 150).unwrap().messages, payment(460));
+        assert_eq!(stream(deps.as_ref(), 180).owed.u128(), 300);
+
+        // nothing accrues after the end height
+        assert_eq!(ping(deps.as_mut(), 250).unwrap().messages, payment(500));
+        let stream = stream(deps.as_ref(), 300);
+        assert_eq!(stream.paid.u128(), 1000);
+        assert_eq!(stream.owed.u128(), 0);
+    }
+
+    #[test]
+    fn pays_what_the_payer_covers() {
+        let mut deps = setup(25, 1000);
+        let res = ping(deps.as_mut(), 110).unwrap();
+        assert_eq!(res.messages, payment(25));
+        assert_eq!(res.attributes[3], attr("owed", "75"));
+
+        let mut deps = setup(1000, 40);
+        assert_eq!(ping(deps.as_mut(), 110).unwrap().messages, payment(40));
+
+        let mut deps = setup(0, 1000);
+        match ping(deps.as_mut(), 110).unwrap_err() {
+            ContractError::PayerExhausted { owed } => assert_eq!(owed.u128(), 100),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn cancel_stops_accrual() {
+        let mut deps = setup(u128::MAX, u128::MAX);
+        let cancel = ExecuteMsg::Cancel { id: 0 };
+        let info = mock_info("recipient", &[]);
+        match execute(deps.as_mut(), env_at(120), info, cancel.clone()).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let info = mock_info("payer", &[]);
+        execute(deps.as_mut(), env_at(120), info.clone(), cancel.clone()).unwrap();
+        let stream = stream(deps.as_ref(), 180);
+        assert!(stream.cancelled);
+        assert_eq!(stream.end_height, 120);
+        assert_eq!(stream.owed.u128(), 200);
+
+        // what accrued before the cancellation can still be paid
+        assert_eq!(ping(deps.as_mut(), 180).unwrap().messages, payment(200));
+        match execute(deps.as_mut(), env_at(180), info, cancel).unwrap_err() {
+            ContractError::Ended { end_height: 120 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
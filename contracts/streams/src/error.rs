@@ -0,0 +1,32 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("End height must be after the current height {height}")]
+    InvalidEndHeight { height: u64 },
+
+    #[error("Unknown stream {id}")]
+    UnknownStream { id: u64 },
+
+    #[error("Nothing accrued since the last ping")]
+    NothingOwed {},
+
+    #[error("Payer's allowance or balance does not cover any of the {owed} owed")]
+    PayerExhausted { owed: Uint128 },
+
+    #[error("Stream already ended at height {end_height}")]
+    Ended { end_height: u64 },
+}
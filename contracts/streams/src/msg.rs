@@ -0,0 +1,46 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Streams `amount_per_block` of `token` from the signer to `recipient` for every block until
+    /// `end_height`. The signer has to approve this contract on the token, nothing is pulled up
+    /// front.
+    CreateStream {
+        token: String,
+        recipient: String,
+        amount_per_block: Uint128,
+        end_height: u64,
+    },
+    /// Pays the recipient what accrued since the last ping, as far as the payer's allowance and
+    /// balance cover it. Anyone can call this.
+    Ping { id: u64 },
+    /// Stops the accrual. What accrued before can still be pinged. Only the payer can do this.
+    Cancel { id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Stream { id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StreamResponse {
+    pub id: u64,
+    pub payer: String,
+    pub token: String,
+    pub recipient: String,
+    pub amount_per_block: Uint128,
+    pub start_height: u64,
+    pub end_height: u64,
+    pub paid: Uint128,
+    /// Accrued but not paid yet at the current height
+    pub owed: Uint128,
+    pub cancelled: bool,
+}
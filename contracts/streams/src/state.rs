@@ -0,0 +1,44 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static STREAM_COUNT_KEY: &[u8] = b"stream_count";
+static STREAMS_KEY: &[u8] = b"streams";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Stream {
+    pub payer: Addr,
+    /// The erc20 token the stream pays in
+    pub token: Addr,
+    pub recipient: Addr,
+    /// Accrued for every block between `start_height` and `end_height`
+    pub amount_per_block: Uint128,
+    pub start_height: u64,
+    /// No more accrual from this height on. Moved to the cancel height on cancellation.
+    pub end_height: u64,
+    /// Transferred to the recipient so far
+    pub paid: Uint128,
+    pub cancelled: bool,
+}
+
+/// Number of streams created so far, which is also the id of the next one
+pub fn stream_count(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, STREAM_COUNT_KEY)
+}
+
+pub fn stream_count_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, STREAM_COUNT_KEY)
+}
+
+/// Streams by big endian id
+pub fn streams(storage: &mut dyn Storage) -> Bucket<'_, Stream> {
+    bucket(storage, STREAMS_KEY)
+}
+
+pub fn streams_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Stream> {
+    bucket_read(storage, STREAMS_KEY)
+}
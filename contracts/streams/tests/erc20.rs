@@ -0,0 +1,106 @@
+//! Streams a real erc20 token on a test chain, which answers the allowance and balance queries
+//! from the erc20 contract and reverts failing messages like a real chain.
+
+use cosmwasm_std::Uint128;
+use cw_erc20::BalanceResponse;
+use cw_streams::contract::{execute, instantiate, query};
+use cw_streams::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, StreamResponse};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const STREAMS: &str = "streams";
+
+/// alice holds 1000 tokens and streams 10 per block to bob from height 100 to 200
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(100);
+    let msg = erc20_msg("STREAM", &[("alice", 1000)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let streams = chain.store_code(Contract::new(instantiate, execute, query));
+    chain
+        .instantiate(streams, STREAMS, "creator", &InstantiateMsg {}, &[])
+        .unwrap();
+    let msg = ExecuteMsg::CreateStream {
+        token: TOKEN.to_string(),
+        recipient: "bob".to_string(),
+        amount_per_block: Uint128::from(10u128),
+        end_height: 200,
+    };
+    chain.execute(STREAMS, "alice", &msg, &[]).unwrap();
+    chain
+}
+
+fn approve(chain: &mut Chain, amount: u128) {
+    let msg = cw_erc20::ExecuteMsg::approve(STREAMS, amount);
+    chain.execute(TOKEN, "alice", &msg, &[]).unwrap();
+}
+
+fn ping_at(chain: &mut Chain, height: u64) -> Result<(), String> {
+    chain.set_height(height);
+    chain
+        .execute(STREAMS, "anyone", &ExecuteMsg::Ping { id: 0 }, &[])
+        .map(|_| ())
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn stream(chain: &Chain) -> StreamResponse {
+    chain.query(STREAMS, &QueryMsg::Stream { id: 0 }).unwrap()
+}
+
+#[test]
+fn pays_accrual_across_irregular_pings() {
+    let mut chain = setup();
+    approve(&mut chain, 1000);
+    ping_at(&mut chain, 101).unwrap();
+    assert_eq!(balance(&chain, "bob"), 10);
+    ping_at(&mut chain, 137).unwrap();
+    assert_eq!(balance(&chain, "bob"), 370);
+    let err = ping_at(&mut chain, 137).unwrap_err();
+    assert_eq!(err, "Nothing accrued since the last ping");
+    ping_at(&mut chain, 138).unwrap();
+    assert_eq!(balance(&chain, "bob"), 380);
+
+    // a late ping only pays up to the end height
+    ping_at(&mut chain, 450).unwrap();
+    assert_eq!(balance(&chain, "bob"), 1000);
+    assert_eq!(balance(&chain, "alice"), 0);
+    assert_eq!(stream(&chain).owed.u128(), 0);
+}
+
+#[test]
+fn allowance_runs_out_mid_stream() {
+    let mut chain = setup();
+    approve(&mut chain, 250);
+    ping_at(&mut chain, 120).unwrap();
+    assert_eq!(balance(&chain, "bob"), 200);
+
+    // only the rest of the allowance is paid, the remainder stays owed
+    ping_at(&mut chain, 130).unwrap();
+    assert_eq!(balance(&chain, "bob"), 250);
+    assert_eq!(stream(&chain).owed.u128(), 50);
+    let err = ping_at(&mut chain, 140).unwrap_err();
+    assert_eq!(
+        err,
+        "Payer's allowance or balance does not cover any of the 150 owed"
+    );
+
+    // a new allowance catches up on everything owed
+    approve(&mut chain, 1000);
+    ping_at(&mut chain, 150).unwrap();
+    assert_eq!(balance(&chain, "bob"), 500);
+
+    // cancelling keeps what accrued until then
+    chain.set_height(160);
+    chain
+        .execute(STREAMS, "alice", &ExecuteMsg::Cancel { id: 0 }, &[])
+        .unwrap();
+    ping_at(&mut chain, 190).unwrap();
+    assert_eq!(balance(&chain, "bob"), 600);
+    assert_eq!(balance(&chain, "alice"), 400);
+}
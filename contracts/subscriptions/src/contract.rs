@@ -0,0 +1,504 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, ContractResult, Deps, DepsMut, Env, MessageInfo, Reply,
+    ReplyOn, Response, StdResult, Storage, SubMsg, Uint128,
+};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, PlanResponse, QueryMsg, SubscriptionResponse};
+use crate::state::{
+    plan_count, plan_count_read, plans, plans_read, subscription_count, subscription_count_read,
+    subscriptions, subscriptions_read, Plan, Subscription,
+};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    plan_count(deps.storage).save(&0)?;
+    subscription_count(deps.storage).save(&0)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreatePlan {
+            token,
+            amount,
+            interval,
+        } => try_create_plan(deps, info, token, amount, interval),
+        ExecuteMsg::Subscribe { plan_id } => try_subscribe(deps, env, info, plan_id),
+        ExecuteMsg::Cancel { subscription_id } => try_cancel(deps, info, subscription_id),
+        ExecuteMsg::Collect { subscription_id } => try_collect(deps, env, subscription_id),
+    }
+}
+
+/// Create plan
+///
+/// Registers a plan with the signer as merchant.
+///
+/// @param token the erc20 token payments are made in
+/// @param amount paid once per interval
+/// @param interval blocks between two payments
+fn try_create_plan(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: String,
+    amount: Uint128,
+    interval: u64,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    if interval == 0 {
+        return Err(ContractError::ZeroInterval {});
+    }
+    let plan = Plan {
+        merchant: info.sender,
+        token: deps.api.addr_validate(&token)?,
+        amount,
+        interval,
+    };
+    let plan_id = plan_count_read(deps.storage).load()?;
+    plan_count(deps.storage).save(&(plan_id + 1))?;
+    plans(deps.storage).save(&plan_id.to_be_bytes(), &plan)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "create_plan"),
+            attr("plan_id", plan_id),
+            attr("merchant", plan.merchant),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Subscribe
+///
+/// Subscribes the signer to a plan. The first payment is pulled right away and fails the
+/// whole message if the allowance is missing, the next one is due an interval later.
+///
+/// @param plan_id the plan to subscribe to
+fn try_subscribe(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+) -> Result<Response, ContractError> {
+    let plan = load_plan(deps.storage, plan_id)?;
+    let subscription = Subscription {
+        plan_id,
+        subscriber: info.sender,
+        next_due_height: env.block.height + plan.interval,
+        delinquent: false,
+    };
+    let subscription_id = subscription_count_read(deps.storage).load()?;
+    subscription_count(deps.storage).save(&(subscription_id + 1))?;
+    subscriptions(deps.storage).save(&subscription_id.to_be_bytes(), &subscription)?;
+
+    let payment = cw_erc20::ExecuteMsg::transfer_from(
+        subscription.subscriber.as_str(),
+        plan.merchant.as_str(),
+        plan.amount,
+    );
+    let res = Response {
+        submessages: vec![],
+        messages: vec![payment.into_cosmos_msg(&plan.token)?],
+        attributes: vec![
+            attr("action", "subscribe"),
+            attr("subscription_id", subscription_id),
+            attr("plan_id", plan_id),
+            attr("subscriber", subscription.subscriber),
+            attr("next_due_height", subscription.next_due_height),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Cancel
+///
+/// Ends a subscription of the signer. Payments which are due but were not collected yet are
+/// dropped as well.
+///
+/// @param subscription_id the subscription to end
+fn try_cancel(
+    deps: DepsMut,
+    info: MessageInfo,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    let subscription = load_subscription(deps.storage, subscription_id)?;
+    if subscription.subscriber != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    subscriptions(deps.storage).remove(&subscription_id.to_be_bytes());
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "cancel"),
+            attr("subscription_id", subscription_id),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Collect
+///
+/// Pulls the next payment of a due subscription and moves the due height one interval ahead.
+/// The transfer runs as a submessage, so a failure only ends up in `reply`, which marks the
+/// subscription delinquent and leaves the payment due.
+///
+/// @param subscription_id the subscription to collect
+fn try_collect(deps: DepsMut, env: Env, subscription_id: u64) -> Result<Response, ContractError> {
+    let mut subscription = load_subscription(deps.storage, subscription_id)?;
+    if env.block.height < subscription.next_due_height {
+        return Err(ContractError::NotDue {
+            next_due_height: subscription.next_due_height,
+        });
+    }
+    let plan = load_plan(deps.storage, subscription.plan_id)?;
+    subscription.next_due_height += plan.interval;
+    subscription.delinquent = false;
+    subscriptions(deps.storage).save(&subscription_id.to_be_bytes(), &subscription)?;
+
+    let payment = cw_erc20::ExecuteMsg::transfer_from(
+        subscription.subscriber.as_str(),
+        plan.merchant.as_str(),
+        plan.amount,
+    );
+    let res = Response {
+        submessages: vec![SubMsg {
+            id: subscription_id,
+            msg: payment.into_cosmos_msg(&plan.token)?,
+            gas_limit: None,
+            reply_on: ReplyOn::Error,
+        }],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "collect"),
+            attr("subscription_id", subscription_id),
+            attr("amount", plan.amount),
+            attr("next_due_height", subscription.next_due_height),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Handles failed collections. The reply id is the subscription id.
+#[entry_point]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let error = match msg.result {
+        ContractResult::Err(error) => error,
+        ContractResult::Ok(_) => return Ok(Response::default()),
+    };
+    let subscription_id = msg.id;
+    let mut subscription = load_subscription(deps.storage, subscription_id)?;
+    let plan = load_plan(deps.storage, subscription.plan_id)?;
+    subscription.next_due_height -= plan.interval;
+    subscription.delinquent = true;
+    subscriptions(deps.storage).save(&subscription_id.to_be_bytes(), &subscription)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "collect_failed"),
+            attr("subscription_id", subscription_id),
+            attr("error", error),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn load_plan(storage: &dyn Storage, plan_id: u64) -> Result<Plan, ContractError> {
+    plans_read(storage)
+        .may_load(&plan_id.to_be_bytes())?
+        .ok_or(ContractError::UnknownPlan { plan_id })
+}
+
+fn load_subscription(
+    storage: &dyn Storage,
+    subscription_id: u64,
+) -> Result<Subscription, ContractError> {
+    subscriptions_read(storage)
+        .may_load(&subscription_id.to_be_bytes())?
+        .ok_or(ContractError::UnknownSubscription { subscription_id })
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Plan { plan_id } => {
+            let plan = plans_read(deps.storage).load(&plan_id.to_be_bytes())?;
+            to_binary(&PlanResponse {
+                merchant: plan.merchant.to_string(),
+                token: plan.token.to_string(),
+                amount: plan.amount,
+                interval: plan.interval,
+            })
+        }
+        QueryMsg::Subscription { subscription_id } => {
+            let subscription =
+                subscriptions_read(deps.storage).load(&subscription_id.to_be_bytes())?;
+            to_binary(&SubscriptionResponse {
+                plan_id: subscription.plan_id,
+                subscriber: subscription.subscriber.to_string(),
+                next_due_height: subscription.next_due_height,
+                delinquent: subscription.delinquent,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, OwnedDeps, SubcallResponse};
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("creator", &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CreatePlan {
+            token: "token".to_string(),
+            amount: Uint128::from(10u128),
+            interval: 50,
+        };
+        run(deps.as_mut(), 100, "merchant", msg).unwrap();
+        run(
+            deps.as_mut(),
+            100,
+            "alice",
+            ExecuteMsg::Subscribe { plan_id: 0 },
+        )
+        .unwrap();
+        deps
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn subscription(deps: Deps, subscription_id: u64) -> SubscriptionResponse {
+        let msg = QueryMsg::Subscription { subscription_id };
+        from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+    }
+
+    fn payment() -> cosmwasm_std::CosmosMsg {
+        cw_erc20::ExecuteMsg::transfer_from("alice", "merchant", 10u128)
+            .into_cosmos_msg("token")
+            .unwrap()
+    }
+
+    #[test]
+    fn validates_plans() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::CreatePlan {
+            token: "token".to_string(),
+            amount: Uint128::zero(),
+            interval: 50,
+        };
+        match run(deps.as_mut(), 100, "merchant", msg).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::CreatePlan {
+            token: "token".to_string(),
+            amount: Uint128::from(10u128),
+            interval: 0,
+        };
+        match run(deps.as_mut(), 100, "merchant", msg).unwrap_err() {
+            ContractError::ZeroInterval {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(
+            deps.as_mut(),
+            100,
+            "bob",
+            ExecuteMsg::Subscribe { plan_id: 1 },
+        )
+        .unwrap_err()
+        {
+            ContractError::UnknownPlan { plan_id: 1 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn subscribing_pays_first_interval() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("creator", &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CreatePlan {
+            token: "token".to_string(),
+            amount: Uint128::from(10u128),
+            interval: 50,
+        };
+        run(deps.as_mut(), 100, "merchant", msg).unwrap();
+        let res = run(
+            deps.as_mut(),
+            120,
+            "alice",
+            ExecuteMsg::Subscribe { plan_id: 0 },
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![payment()]);
+        assert_eq!(
+            subscription(deps.as_ref(), 0),
+            SubscriptionResponse {
+                plan_id: 0,
+                subscriber: "alice".to_string(),
+                next_due_height: 170,
+                delinquent: false,
+            }
+        );
+    }
+
+    #[test]
+    fn collects_once_per_interval() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Collect { subscription_id: 0 };
+        match run(deps.as_mut(), 149, "keeper", msg.clone()).unwrap_err() {
+            ContractError::NotDue {
+                next_due_height: 150,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = run(deps.as_mut(), 150, "keeper", msg.clone()).unwrap();
+        assert_eq!(
+            res.submessages,
+            vec![SubMsg {
+                id: 0,
+                msg: payment(),
+                gas_limit: None,
+                reply_on: ReplyOn::Error,
+            }]
+        );
+        assert_eq!(subscription(deps.as_ref(), 0).next_due_height, 200);
+        match run(deps.as_mut(), 199, "keeper", msg.clone()).unwrap_err() {
+            ContractError::NotDue {
+                next_due_height: 200,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // a late keeper can catch up on missed intervals one by one
+        run(deps.as_mut(), 260, "keeper", msg.clone()).unwrap();
+        run(deps.as_mut(), 260, "keeper", msg.clone()).unwrap();
+        assert_eq!(subscription(deps.as_ref(), 0).next_due_height, 300);
+        match run(deps.as_mut(), 260, "keeper", msg).unwrap_err() {
+            ContractError::NotDue { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn failed_collection_marks_delinquent() {
+        let mut deps = setup();
+        run(
+            deps.as_mut(),
+            150,
+            "keeper",
+            ExecuteMsg::Collect { subscription_id: 0 },
+        )
+        .unwrap();
+        let failure = Reply {
+            id: 0,
+            result: ContractResult::Err("Insufficient allowance".to_string()),
+        };
+        let res = reply(deps.as_mut(), env_at(150), failure).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "collect_failed"),
+                attr("subscription_id", "0"),
+                attr("error", "Insufficient allowance"),
+            ]
+        );
+        let state = subscription(deps.as_ref(), 0);
+        assert!(state.delinquent);
+        assert_eq!(state.next_due_height, 150);
+
+        // the payment stays due and a successful retry clears the flag
+        run(
+            deps.as_mut(),
+            151,
+            "keeper",
+            ExecuteMsg::Collect { subscription_id: 0 },
+        )
+        .unwrap();
+        let success = Reply {
+            id: 0,
+            result: ContractResult::Ok(SubcallResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        reply(deps.as_mut(), env_at(151), success).unwrap();
+        let state = subscription(deps.as_ref(), 0);
+        assert!(!state.delinquent);
+        assert_eq!(state.next_due_height, 200);
+    }
+
+    #[test]
+    fn only_subscriber_cancels() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Cancel { subscription_id: 0 };
+        match run(deps.as_mut(), 120, "merchant", msg.clone()).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        run(deps.as_mut(), 120, "alice", msg).unwrap();
+        match run(
+            deps.as_mut(),
+            150,
+            "keeper",
+            ExecuteMsg::Collect { subscription_id: 0 },
+        )
+        .unwrap_err()
+        {
+            ContractError::UnknownSubscription { subscription_id: 0 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Interval must not be zero")]
+    ZeroInterval {},
+
+    #[error("Unknown plan {plan_id}")]
+    UnknownPlan { plan_id: u64 },
+
+    #[error("Unknown subscription {subscription_id}")]
+    UnknownSubscription { subscription_id: u64 },
+
+    #[error("Payment not due before height {next_due_height}")]
+    NotDue { next_due_height: u64 },
+}
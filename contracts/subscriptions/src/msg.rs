@@ -0,0 +1,49 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Registers a plan paying the signer `amount` of `token` every `interval` blocks
+    CreatePlan {
+        token: String,
+        amount: Uint128,
+        interval: u64,
+    },
+    /// Subscribes the signer to a plan and pulls the first payment right away. The signer has
+    /// to approve this contract on the plan's token first, with enough allowance for the
+    /// payments to come.
+    Subscribe { plan_id: u64 },
+    /// Ends a subscription. Only the subscriber can do this.
+    Cancel { subscription_id: u64 },
+    /// Pulls the next payment of a subscription once it is due. Anyone can call this. A failed
+    /// transfer marks the subscription delinquent instead of failing the call.
+    Collect { subscription_id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Plan { plan_id: u64 },
+    Subscription { subscription_id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlanResponse {
+    pub merchant: String,
+    pub token: String,
+    pub amount: Uint128,
+    pub interval: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubscriptionResponse {
+    pub plan_id: u64,
+    pub subscriber: String,
+    pub next_due_height: u64,
+    pub delinquent: bool,
+}
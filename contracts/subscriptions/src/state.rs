@@ -0,0 +1,71 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static PLAN_COUNT_KEY: &[u8] = b"plan_count";
+static PLANS_KEY: &[u8] = b"plans";
+static SUBSCRIPTION_COUNT_KEY: &[u8] = b"subscription_count";
+static SUBSCRIPTIONS_KEY: &[u8] = b"subscriptions";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Plan {
+    /// Receives the payments
+    pub merchant: Addr,
+    /// The erc20 token payments are made in
+    pub token: Addr,
+    /// Paid once per interval
+    pub amount: Uint128,
+    /// Blocks between two payments
+    pub interval: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Subscription {
+    pub plan_id: u64,
+    pub subscriber: Addr,
+    /// The next payment can be collected from this height on
+    pub next_due_height: u64,
+    /// Set when the last collection failed, e.g. for a missing allowance. Cleared by the next
+    /// successful collection.
+    pub delinquent: bool,
+}
+
+/// Number of plans created so far, which is also the id of the next one
+pub fn plan_count(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, PLAN_COUNT_KEY)
+}
+
+pub fn plan_count_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, PLAN_COUNT_KEY)
+}
+
+/// Plans by big endian id
+pub fn plans(storage: &mut dyn Storage) -> Bucket<'_, Plan> {
+    bucket(storage, PLANS_KEY)
+}
+
+pub fn plans_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Plan> {
+    bucket_read(storage, PLANS_KEY)
+}
+
+/// Number of subscriptions created so far, which is also the id of the next one
+pub fn subscription_count(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, SUBSCRIPTION_COUNT_KEY)
+}
+
+pub fn subscription_count_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, SUBSCRIPTION_COUNT_KEY)
+}
+
+/// Subscriptions by big endian id. Cancelled subscriptions are removed.
+pub fn subscriptions(storage: &mut dyn Storage) -> Bucket<'_, Subscription> {
+    bucket(storage, SUBSCRIPTIONS_KEY)
+}
+
+pub fn subscriptions_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Subscription> {
+    bucket_read(storage, SUBSCRIPTIONS_KEY)
+}
@@ -0,0 +1,116 @@
+//! Runs subscriptions against a real erc20 contract on a test chain, which reports failed
+//! submessages to `reply` and reverts failing messages like a real chain.
+
+use cosmwasm_std::Uint128;
+use cw_erc20::BalanceResponse;
+use cw_subscriptions::contract::{execute, instantiate, query, reply};
+use cw_subscriptions::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SubscriptionResponse};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+
+const TOKEN: &str = "token";
+const SUBSCRIPTIONS: &str = "subscriptions";
+
+/// alice holds 100 tokens and there is a plan over 10 tokens every 50 blocks
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(100);
+    let msg = erc20_msg("PAY", &[("alice", 100)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let subscriptions =
+        chain.store_code(Contract::new(instantiate, execute, query).with_reply(reply));
+    chain
+        .instantiate(
+            subscriptions,
+            SUBSCRIPTIONS,
+            "creator",
+            &InstantiateMsg {},
+            &[],
+        )
+        .unwrap();
+    let msg = ExecuteMsg::CreatePlan {
+        token: TOKEN.to_string(),
+        amount: Uint128::from(10u128),
+        interval: 50,
+    };
+    chain.execute(SUBSCRIPTIONS, "merchant", &msg, &[]).unwrap();
+    chain
+}
+
+fn approve(chain: &mut Chain, amount: u128) {
+    let msg = cw_erc20::ExecuteMsg::approve(SUBSCRIPTIONS, amount);
+    chain.execute(TOKEN, "alice", &msg, &[]).unwrap();
+}
+
+fn collect(chain: &mut Chain) {
+    let msg = ExecuteMsg::Collect { subscription_id: 0 };
+    chain.execute(SUBSCRIPTIONS, "keeper", &msg, &[]).unwrap();
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn subscription(chain: &Chain, subscription_id: u64) -> SubscriptionResponse {
+    let msg = QueryMsg::Subscription { subscription_id };
+    chain.query(SUBSCRIPTIONS, &msg).unwrap()
+}
+
+#[test]
+fn collects_two_cycles() {
+    let mut chain = setup();
+    approve(&mut chain, 30);
+    let msg = ExecuteMsg::Subscribe { plan_id: 0 };
+    chain.execute(SUBSCRIPTIONS, "alice", &msg, &[]).unwrap();
+    assert_eq!(balance(&chain, "merchant"), 10);
+
+    for (height, paid) in &[(150, 20), (200, 30)] {
+        chain.set_height(*height);
+        collect(&mut chain);
+        assert_eq!(balance(&chain, "merchant"), *paid);
+        assert!(!subscription(&chain, 0).delinquent);
+    }
+    assert_eq!(balance(&chain, "alice"), 70);
+    assert_eq!(subscription(&chain, 0).next_due_height, 250);
+}
+
+#[test]
+fn missing_allowance_marks_delinquent() {
+    let mut chain = setup();
+    approve(&mut chain, 10);
+    let msg = ExecuteMsg::Subscribe { plan_id: 0 };
+    chain.execute(SUBSCRIPTIONS, "alice", &msg, &[]).unwrap();
+
+    // the keeper's call succeeds even though nothing was paid
+    chain.set_height(160);
+    collect(&mut chain);
+    assert_eq!(balance(&chain, "merchant"), 10);
+    let res = subscription(&chain, 0);
+    assert!(res.delinquent);
+    assert_eq!(res.next_due_height, 150);
+
+    approve(&mut chain, 10);
+    collect(&mut chain);
+    assert_eq!(balance(&chain, "merchant"), 20);
+    let res = subscription(&chain, 0);
+    assert!(!res.delinquent);
+    assert_eq!(res.next_due_height, 200);
+}
+
+#[test]
+fn subscribing_without_allowance_is_reverted() {
+    let mut chain = setup();
+    let msg = ExecuteMsg::Subscribe { plan_id: 0 };
+    let err = chain
+        .execute(SUBSCRIPTIONS, "alice", &msg, &[])
+        .unwrap_err();
+    assert!(err.contains("allowance"), "unexpected error: {}", err);
+    chain
+        .query::<SubscriptionResponse, _>(
+            SUBSCRIPTIONS,
+            &QueryMsg::Subscription { subscription_id: 0 },
+        )
+        .unwrap_err();
+}
@@ -0,0 +1,472 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult,
+};
+
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, OperationListResponse, OperationResponse, OperationStatus, QueryMsg,
+};
+use crate::state::{
+    config, config_read, operation_count, operation_count_read, operations, operations_read,
+    Config, Operation,
+};
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.proposers.is_empty() {
+        return Err(ContractError::NoProposers {});
+    }
+    if msg.grace_period == 0 {
+        return Err(ContractError::InvalidGracePeriod {});
+    }
+    let state = Config {
+        proposers: validate_addresses(deps.as_ref(), &msg.proposers)?,
+        guardians: validate_addresses(deps.as_ref(), &msg.guardians)?,
+        min_delay: msg.min_delay,
+        grace_period: msg.grace_period,
+    };
+    config(deps.storage).save(&state)?;
+    operation_count(deps.storage).save(&0)?;
+    Ok(Response::default())
+}
+
+fn validate_addresses(deps: Deps, addresses: &[String]) -> StdResult<Vec<Addr>> {
+    addresses
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect()
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Schedule { msgs, eta_height } => {
+            try_schedule(deps, env, info, msgs, eta_height)
+        }
+        ExecuteMsg::Execute { id } => try_execute(deps, env, id),
+        ExecuteMsg::Cancel { id } => try_cancel(deps, info, id),
+    }
+}
+
+/// Schedule
+///
+/// Queues messages to be executed from this contract once `eta_height` is reached. Only
+/// proposers can do this.
+///
+/// @param msgs the messages to execute
+/// @param eta_height the earliest height to execute them at, at least `min_delay` blocks away
+fn try_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<CosmosMsg>,
+    eta_height: u64,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if !state.proposers.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if msgs.is_empty() {
+        return Err(ContractError::EmptyOperation {});
+    }
+    let earliest = env.block.height.saturating_add(state.min_delay);
+    if eta_height < earliest {
+        return Err(ContractError::EtaTooEarly { earliest });
+    }
+
+    let id = operation_count_read(deps.storage).load()?;
+    operation_count(deps.storage).save(&(id + 1))?;
+    let operation = Operation {
+        msgs,
+        proposer: info.sender.clone(),
+        eta_height,
+        expires: eta_height.saturating_add(state.grace_period),
+        executed: false,
+        cancelled: false,
+    };
+    operations(deps.storage).save(&id.to_be_bytes(), &operation)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "schedule"),
+            attr("id", id),
+            attr("proposer", info.sender),
+            attr("eta_height", eta_height),
+            attr("expires", operation.expires),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Execute
+///
+/// Emits the messages of an operation whose eta was reached and which did not expire yet.
+///
+/// @param id the operation
+fn try_execute(deps: DepsMut, env: Env, id: u64) -> Result<Response, ContractError> {
+    let mut operation = load_pending_operation(deps.as_ref(), id)?;
+    if env.block.height < operation.eta_height {
+        return Err(ContractError::NotReady {
+            eta_height: operation.eta_height,
+        });
+    }
+    if env.block.height >= operation.expires {
+        return Err(ContractError::Expired {
+            expires: operation.expires,
+        });
+    }
+    operation.executed = true;
+    operations(deps.storage).save(&id.to_be_bytes(), &operation)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: operation.msgs,
+        attributes: vec![attr("action", "execute"), attr("id", id)],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Cancel
+///
+/// Cancels an operation so it can never be executed. Only guardians can do this.
+///
+/// @param id the operation
+fn try_cancel(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if !state.guardians.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut operation = load_pending_operation(deps.as_ref(), id)?;
+    operation.cancelled = true;
+    operations(deps.storage).save(&id.to_be_bytes(), &operation)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "cancel"),
+            attr("id", id),
+            attr("guardian", info.sender),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Loads an operation which is neither executed nor cancelled
+fn load_pending_operation(deps: Deps, id: u64) -> Result<Operation, ContractError> {
+    let operation = operations_read(deps.storage)
+        .may_load(&id.to_be_bytes())?
+        .ok_or(ContractError::OperationNotFound { id })?;
+    if operation.executed {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    if operation.cancelled {
+        return Err(ContractError::Cancelled {});
+    }
+    Ok(operation)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Operation { id } => to_binary(&query_operation(deps, &env, id)?),
+        QueryMsg::ListOperations { start_after, limit } => {
+            to_binary(&query_list_operations(deps, &env, start_after, limit)?)
+        }
+    }
+}
+
+fn query_operation(deps: Deps, env: &Env, id: u64) -> StdResult<OperationResponse> {
+    let operation = operations_read(deps.storage)
+        .may_load(&id.to_be_bytes())?
+        .ok_or_else(|| StdError::not_found("Operation"))?;
+    let status = if operation.executed {
+        OperationStatus::Executed
+    } else if operation.cancelled {
+        OperationStatus::Cancelled
+    } else if env.block.height >= operation.expires {
+        OperationStatus::Expired
+    } else if env.block.height >= operation.eta_height {
+        OperationStatus::Ready
+    } else {
+        OperationStatus::Queued
+    };
+    Ok(OperationResponse {
+        id,
+        msgs: operation.msgs,
+        proposer: operation.proposer.to_string(),
+        eta_height: operation.eta_height,
+        expires: operation.expires,
+        status,
+    })
+}
+
+fn query_list_operations(
+    deps: Deps,
+    env: &Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OperationListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64;
+    let count = operation_count_read(deps.storage).load()?;
+    let start = start_after.map_or(0, |id| id.saturating_add(1));
+    let end = start.saturating_add(limit).min(count);
+    let operations = (start..end)
+        .map(|id| query_operation(deps, env, id))
+        .collect::<StdResult<_>>()?;
+    Ok(OperationListResponse { operations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coins, from_binary, BankMsg, OwnedDeps};
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            proposers: vec!["dao".to_string()],
+            guardians: vec!["guardian".to_string()],
+            min_delay: 20,
+            grace_period: 10,
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env_at(100), info, instantiate_msg()).unwrap();
+        deps
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn payout() -> CosmosMsg {
+        BankMsg::Send {
+            to_address: "dave".to_string(),
+            amount: coins(5, "earth"),
+        }
+        .into()
+    }
+
+    fn schedule(deps: DepsMut, height: u64, eta_height: u64) -> u64 {
+        let msg = ExecuteMsg::Schedule {
+            msgs: vec![payout()],
+            eta_height,
+        };
+        let res = run(deps, height, "dao", msg).unwrap();
+        res.attributes[1].value.parse().unwrap()
+    }
+
+    fn status(deps: Deps, height: u64, id: u64) -> OperationStatus {
+        let msg = QueryMsg::Operation { id };
+        let res: OperationResponse =
+            from_binary(&query(deps, env_at(height), msg).unwrap()).unwrap();
+        res.status
+    }
+
+    #[test]
+    fn instantiate_validates_config() {
+        let mut deps = mock_dependencies(&[]);
+        let mut msg = instantiate_msg();
+        msg.proposers = vec![];
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::NoProposers {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let mut msg = instantiate_msg();
+        msg.grace_period = 0;
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::InvalidGracePeriod {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn proposers_schedule_after_min_delay() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Schedule {
+            msgs: vec![payout()],
+            eta_height: 120,
+        };
+        for sender in &["guardian", "dave"] {
+            match run(deps.as_mut(), 100, sender, msg.clone()).unwrap_err() {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        let early = ExecuteMsg::Schedule {
+            msgs: vec![payout()],
+            eta_height: 119,
+        };
+        match run(deps.as_mut(), 100, "dao", early).unwrap_err() {
+            ContractError::EtaTooEarly { earliest: 120 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let empty = ExecuteMsg::Schedule {
+            msgs: vec![],
+            eta_height: 120,
+        };
+        match run(deps.as_mut(), 100, "dao", empty).unwrap_err() {
+            ContractError::EmptyOperation {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = run(deps.as_mut(), 100, "dao", msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "schedule"),
+                attr("id", "0"),
+                attr("proposer", "dao"),
+                attr("eta_height", "120"),
+                attr("expires", "130"),
+            ]
+        );
+        let msg = QueryMsg::Operation { id: 0 };
+        let res: OperationResponse =
+            from_binary(&query(deps.as_ref(), env_at(100), msg).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            OperationResponse {
+                id: 0,
+                msgs: vec![payout()],
+                proposer: "dao".to_string(),
+                eta_height: 120,
+                expires: 130,
+                status: OperationStatus::Queued,
+            }
+        );
+    }
+
+    #[test]
+    fn executes_between_eta_and_expiry() {
+        let mut deps = setup();
+        let id = schedule(deps.as_mut(), 100, 120);
+        let exec = ExecuteMsg::Execute { id };
+        match run(deps.as_mut(), 119, "anyone", exec.clone()).unwrap_err() {
+            ContractError::NotReady { eta_height: 120 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(status(deps.as_ref(), 120, id), OperationStatus::Ready);
+
+        let res = run(deps.as_mut(), 120, "anyone", exec.clone()).unwrap();
+        assert_eq!(res.messages, vec![payout()]);
+        assert_eq!(status(deps.as_ref(), 120, id), OperationStatus::Executed);
+        match run(deps.as_mut(), 121, "anyone", exec).unwrap_err() {
+            ContractError::AlreadyExecuted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let id = schedule(deps.as_mut(), 100, 125);
+        assert_eq!(status(deps.as_ref(), 135, id), OperationStatus::Expired);
+        match run(deps.as_mut(), 135, "anyone", ExecuteMsg::Execute { id }).unwrap_err() {
+            ContractError::Expired { expires: 135 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 135, "anyone", ExecuteMsg::Execute { id: 2 }).unwrap_err() {
+            ContractError::OperationNotFound { id: 2 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn guardians_cancel_pending_operations() {
+        let mut deps = setup();
+        let id = schedule(deps.as_mut(), 100, 120);
+        for sender in &["dao", "dave"] {
+            match run(deps.as_mut(), 101, sender, ExecuteMsg::Cancel { id }).unwrap_err() {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        let res = run(deps.as_mut(), 101, "guardian", ExecuteMsg::Cancel { id }).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "cancel"),
+                attr("id", "0"),
+                attr("guardian", "guardian"),
+            ]
+        );
+        assert_eq!(status(deps.as_ref(), 120, id), OperationStatus::Cancelled);
+        match run(deps.as_mut(), 120, "anyone", ExecuteMsg::Execute { id }).unwrap_err() {
+            ContractError::Cancelled {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // executed operations cannot be cancelled anymore
+        let id = schedule(deps.as_mut(), 100, 120);
+        run(deps.as_mut(), 120, "anyone", ExecuteMsg::Execute { id }).unwrap();
+        match run(deps.as_mut(), 121, "guardian", ExecuteMsg::Cancel { id }).unwrap_err() {
+            ContractError::AlreadyExecuted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn lists_operations() {
+        let mut deps = setup();
+        for eta_height in &[120, 130, 140] {
+            schedule(deps.as_mut(), 100, *eta_height);
+        }
+        run(deps.as_mut(), 101, "guardian", ExecuteMsg::Cancel { id: 1 }).unwrap();
+
+        let list = |start_after, limit| {
+            let msg = QueryMsg::ListOperations { start_after, limit };
+            let res: OperationListResponse =
+                from_binary(&query(deps.as_ref(), env_at(130), msg).unwrap()).unwrap();
+            res.operations
+                .iter()
+                .map(|operation| (operation.id, operation.status))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            list(None, None),
+            vec![
+                (0, OperationStatus::Expired),
+                (1, OperationStatus::Cancelled),
+                (2, OperationStatus::Queued),
+            ]
+        );
+        assert_eq!(list(None, Some(1)), vec![(0, OperationStatus::Expired)]);
+        assert_eq!(list(Some(1), Some(2)), vec![(2, OperationStatus::Queued)]);
+        assert!(list(Some(2), None).is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("At least one proposer is required")]
+    NoProposers {},
+
+    #[error("Grace period must not be zero")]
+    InvalidGracePeriod {},
+
+    #[error("Operation has no messages")]
+    EmptyOperation {},
+
+    #[error("Eta must be at least height {earliest}")]
+    EtaTooEarly { earliest: u64 },
+
+    #[error("Operation {id} not found")]
+    OperationNotFound { id: u64 },
+
+    #[error("Operation cannot be executed before height {eta_height}")]
+    NotReady { eta_height: u64 },
+
+    #[error("Operation expired at height {expires}")]
+    Expired { expires: u64 },
+
+    #[error("Operation was already executed")]
+    AlreadyExecuted {},
+
+    #[error("Operation was cancelled")]
+    Cancelled {},
+}
@@ -0,0 +1,70 @@
+use cosmwasm_std::CosmosMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub proposers: Vec<String>,
+    pub guardians: Vec<String>,
+    /// Blocks between scheduling an operation and its earliest eta
+    pub min_delay: u64,
+    /// Blocks after the eta during which an operation can be executed
+    pub grace_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Queues `msgs` to be executed from this contract at `eta_height`, which must be at least
+    /// `min_delay` blocks away. Only proposers can do this.
+    Schedule {
+        msgs: Vec<CosmosMsg>,
+        eta_height: u64,
+    },
+    /// Executes the messages of an operation once its eta is reached. Anyone can do this.
+    Execute { id: u64 },
+    /// Cancels an operation which was not executed yet. Only guardians can do this.
+    Cancel { id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Operation {
+        id: u64,
+    },
+    /// Operations with ids greater than `start_after`, oldest first
+    ListOperations {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    /// Waiting for its eta
+    Queued,
+    /// Can be executed
+    Ready,
+    Executed,
+    Cancelled,
+    /// Reached the end of its grace period before it was executed
+    Expired,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperationResponse {
+    pub id: u64,
+    pub msgs: Vec<CosmosMsg>,
+    pub proposer: String,
+    pub eta_height: u64,
+    pub expires: u64,
+    pub status: OperationStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperationListResponse {
+    pub operations: Vec<OperationResponse>,
+}
@@ -0,0 +1,61 @@
+use cosmwasm_std::{Addr, CosmosMsg, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static OPERATION_COUNT_KEY: &[u8] = b"operation_count";
+static OPERATIONS_KEY: &[u8] = b"operations";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Addresses allowed to schedule operations
+    pub proposers: Vec<Addr>,
+    /// Addresses allowed to cancel scheduled operations
+    pub guardians: Vec<Addr>,
+    /// Operations cannot be executed earlier than this many blocks after they were scheduled
+    pub min_delay: u64,
+    /// Operations can be executed for this many blocks from their eta
+    pub grace_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Operation {
+    pub msgs: Vec<CosmosMsg>,
+    pub proposer: Addr,
+    /// Height from which the operation can be executed
+    pub eta_height: u64,
+    /// Height from which the operation can no longer be executed
+    pub expires: u64,
+    pub executed: bool,
+    pub cancelled: bool,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Number of operations scheduled so far, which is also the id of the next one
+pub fn operation_count(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, OPERATION_COUNT_KEY)
+}
+
+pub fn operation_count_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, OPERATION_COUNT_KEY)
+}
+
+/// Operations by big endian id
+pub fn operations(storage: &mut dyn Storage) -> Bucket<'_, Operation> {
+    bucket(storage, OPERATIONS_KEY)
+}
+
+pub fn operations_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Operation> {
+    bucket_read(storage, OPERATIONS_KEY)
+}
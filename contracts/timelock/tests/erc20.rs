@@ -0,0 +1,126 @@
+//! Runs a timelock holding the minter role of a real erc20 contract on a test chain, which
+//! reverts failing messages like a real chain.
+
+use cosmwasm_std::{to_binary, CosmosMsg, WasmMsg};
+use cw_erc20::BalanceResponse;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+use cw_timelock::contract::{execute, instantiate, query};
+use cw_timelock::msg::{ExecuteMsg, InstantiateMsg, OperationResponse, OperationStatus, QueryMsg};
+
+const TOKEN: &str = "token";
+const TIMELOCK: &str = "timelock";
+
+/// The dao schedules with a delay of 50 blocks, the guardian can cancel
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(100);
+    let msg = cw_erc20::InstantiateMsg {
+        minter: Some(TIMELOCK.to_string()),
+        ..erc20_msg("CASH", &[])
+    };
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let timelock = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        proposers: vec!["dao".to_string()],
+        guardians: vec!["guardian".to_string()],
+        min_delay: 50,
+        grace_period: 20,
+    };
+    chain
+        .instantiate(timelock, TIMELOCK, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn operation(chain: &Chain, id: u64) -> OperationResponse {
+    chain.query(TIMELOCK, &QueryMsg::Operation { id }).unwrap()
+}
+
+fn token_msg(msg: cw_erc20::ExecuteMsg) -> CosmosMsg {
+    WasmMsg::Execute {
+        contract_addr: TOKEN.to_string(),
+        msg: to_binary(&msg).unwrap(),
+        send: vec![],
+    }
+    .into()
+}
+
+/// Schedules handing the minter role to `new_minter`
+fn schedule_minter_transfer(chain: &mut Chain, eta_height: u64) {
+    let msg = ExecuteMsg::Schedule {
+        msgs: vec![token_msg(cw_erc20::ExecuteMsg::transfer_minter(
+            "new_minter",
+        ))],
+        eta_height,
+    };
+    chain.execute(TIMELOCK, "dao", &msg, &[]).unwrap();
+}
+
+#[test]
+fn transfers_minter_after_delay() {
+    let mut chain = setup();
+    schedule_minter_transfer(&mut chain, 150);
+    assert_eq!(operation(&chain, 0).status, OperationStatus::Queued);
+
+    chain.set_height(149);
+    let msg = ExecuteMsg::Execute { id: 0 };
+    let err = chain.execute(TIMELOCK, "anyone", &msg, &[]).unwrap_err();
+    assert_eq!(err, "Operation cannot be executed before height 150");
+    let accept = cw_erc20::ExecuteMsg::accept_minter();
+    let err = chain
+        .execute(TOKEN, "new_minter", &accept, &[])
+        .unwrap_err();
+    assert_eq!(err, "Unauthorized");
+
+    chain.set_height(150);
+    chain.execute(TIMELOCK, "anyone", &msg, &[]).unwrap();
+    assert_eq!(operation(&chain, 0).status, OperationStatus::Executed);
+    chain.execute(TOKEN, "new_minter", &accept, &[]).unwrap();
+    let mint = cw_erc20::ExecuteMsg::mint("dave", 10u128);
+    chain.execute(TOKEN, "new_minter", &mint, &[]).unwrap();
+    assert_eq!(balance(&chain, "dave"), 10);
+}
+
+#[test]
+fn cancelled_transfer_never_executes() {
+    let mut chain = setup();
+    schedule_minter_transfer(&mut chain, 150);
+    chain.set_height(120);
+    let msg = ExecuteMsg::Cancel { id: 0 };
+    chain.execute(TIMELOCK, "guardian", &msg, &[]).unwrap();
+
+    chain.set_height(150);
+    let msg = ExecuteMsg::Execute { id: 0 };
+    let err = chain.execute(TIMELOCK, "anyone", &msg, &[]).unwrap_err();
+    assert_eq!(err, "Operation was cancelled");
+    assert_eq!(operation(&chain, 0).status, OperationStatus::Cancelled);
+    let accept = cw_erc20::ExecuteMsg::accept_minter();
+    let err = chain
+        .execute(TOKEN, "new_minter", &accept, &[])
+        .unwrap_err();
+    assert_eq!(err, "Unauthorized");
+}
+
+#[test]
+fn failing_operation_is_not_marked_executed() {
+    let mut chain = setup();
+    // no minter transfer is pending, so there is nothing to accept
+    let msg = ExecuteMsg::Schedule {
+        msgs: vec![token_msg(cw_erc20::ExecuteMsg::accept_minter())],
+        eta_height: 150,
+    };
+    chain.execute(TIMELOCK, "dao", &msg, &[]).unwrap();
+
+    chain.set_height(150);
+    let msg = ExecuteMsg::Execute { id: 0 };
+    let err = chain.execute(TIMELOCK, "anyone", &msg, &[]).unwrap_err();
+    assert_eq!(err, "Unauthorized");
+    assert_eq!(operation(&chain, 0).status, OperationStatus::Ready);
+}
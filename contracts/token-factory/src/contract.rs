@@ -0,0 +1,391 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, ContractResult, Deps, DepsMut, Env, Event, MessageInfo,
+    Reply, ReplyOn, Response, StdError, StdResult, SubMsg, WasmMsg,
+};
+use cw_erc20::InitialBalance;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, TokenListResponse, TokenResponse,
+};
+use crate::state::{
+    config, config_read, token_count, token_count_read, tokens, tokens_read, Config, TokenRecord,
+};
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = Config {
+        erc20_code_id: msg.erc20_code_id,
+    };
+    config(deps.storage).save(&state)?;
+    token_count(deps.storage).save(&0)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateToken {
+            name,
+            symbol,
+            decimals,
+            initial_balances,
+        } => try_create_token(deps, info, name, symbol, decimals, initial_balances),
+    }
+}
+
+/// Create token
+///
+/// Instantiates a new erc20 contract as submessage, whose reply records the new address.
+/// The erc20 contract validates the parameters itself, and if it fails the whole message fails.
+///
+/// @param name the token name
+/// @param symbol the token symbol
+/// @param decimals the number of decimals
+/// @param initial_balances the balances minted on instantiation
+fn try_create_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    initial_balances: Vec<InitialBalance>,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let token_id = token_count_read(deps.storage).load()?;
+    token_count(deps.storage).save(&(token_id + 1))?;
+    let record = TokenRecord {
+        creator: info.sender,
+        name: name.clone(),
+        symbol: symbol.clone(),
+        decimals,
+        address: None,
+    };
+    tokens(deps.storage).save(&token_id.to_be_bytes(), &record)?;
+
+    let init_msg = cw_erc20::InstantiateMsg {
+        name,
+        symbol,
+        decimals,
+        initial_balances,
+        minter: None,
+        admins: vec![],
+        admin_delay: 0,
+        marketing: None,
+        restricted: false,
+        genesis: None,
+        mint_limit_per_window: None,
+        mint_window_blocks: 0,
+        mint_approval: None,
+    };
+    let instantiate = WasmMsg::Instantiate {
+        admin: None,
+        code_id: state.erc20_code_id,
+        msg: to_binary(&init_msg)?,
+        send: vec![],
+        label: token_label(token_id, &record.symbol),
+    };
+    let res = Response {
+        submessages: vec![SubMsg {
+            id: token_id,
+            msg: instantiate.into(),
+            gas_limit: None,
+            reply_on: ReplyOn::Success,
+        }],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "create_token"),
+            attr("token_id", token_id),
+            attr("creator", record.creator),
+            attr("symbol", record.symbol),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// The label of the erc20 instance, unique per factory
+pub fn token_label(token_id: u64, symbol: &str) -> String {
+    format!("token-factory/{}/{}", token_id, symbol)
+}
+
+/// Records the address of a created token. The reply id is the token id.
+#[entry_point]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let token_id = msg.id;
+    let events = match msg.result {
+        ContractResult::Ok(response) => response.events,
+        ContractResult::Err(error) => return Err(StdError::generic_err(error).into()),
+    };
+    let address =
+        instantiated_address(&events).ok_or(ContractError::MissingContractAddress { token_id })?;
+    let address = deps.api.addr_validate(&address)?;
+
+    let key = token_id.to_be_bytes();
+    let mut record = tokens_read(deps.storage)
+        .may_load(&key)?
+        .ok_or(ContractError::UnknownToken { token_id })?;
+    record.address = Some(address.clone());
+    tokens(deps.storage).save(&key, &record)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "register_token"),
+            attr("token_id", token_id),
+            attr("address", address),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Finds the address in the `instantiate` event emitted by the chain. wasmd names the
+/// attribute `_contract_address`, older versions used `contract_address`.
+fn instantiated_address(events: &[Event]) -> Option<String> {
+    events
+        .iter()
+        .filter(|event| event.kind == "instantiate")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| {
+            attribute.key == "_contract_address" || attribute.key == "contract_address"
+        })
+        .map(|attribute| attribute.value.clone())
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => {
+            let state = config_read(deps.storage).load()?;
+            to_binary(&ConfigResponse {
+                erc20_code_id: state.erc20_code_id,
+                token_count: token_count_read(deps.storage).load()?,
+            })
+        }
+        QueryMsg::Token { token_id } => to_binary(&query_token(deps, token_id)?),
+        QueryMsg::ListTokens { start_after, limit } => {
+            to_binary(&query_list_tokens(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_token(deps: Deps, token_id: u64) -> StdResult<TokenResponse> {
+    let record = tokens_read(deps.storage)
+        .may_load(&token_id.to_be_bytes())?
+        .ok_or_else(|| StdError::not_found("Token"))?;
+    Ok(TokenResponse {
+        token_id,
+        creator: record.creator.to_string(),
+        name: record.name,
+        symbol: record.symbol,
+        decimals: record.decimals,
+        address: record.address.map(|address| address.to_string()),
+    })
+}
+
+fn query_list_tokens(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TokenListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64;
+    let count = token_count_read(deps.storage).load()?;
+    let start = start_after.map_or(0, |id| id.saturating_add(1));
+    let end = start.saturating_add(limit).min(count);
+    let tokens = (start..end)
+        .map(|token_id| query_token(deps, token_id))
+        .collect::<StdResult<_>>()?;
+    Ok(TokenListResponse { tokens })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, CosmosMsg, OwnedDeps, SubcallResponse, Uint128};
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg { erc20_code_id: 7 };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn create_token(symbol: &str) -> ExecuteMsg {
+        ExecuteMsg::CreateToken {
+            name: format!("{} Token", symbol),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            initial_balances: vec![InitialBalance {
+                address: "alice".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+        }
+    }
+
+    fn instantiated(token_id: u64, address: &str) -> Reply {
+        Reply {
+            id: token_id,
+            result: ContractResult::Ok(SubcallResponse {
+                events: vec![Event {
+                    kind: "instantiate".to_string(),
+                    attributes: vec![attr("_contract_address", address), attr("code_id", "7")],
+                }],
+                data: None,
+            }),
+        }
+    }
+
+    fn token(deps: Deps, token_id: u64) -> TokenResponse {
+        let msg = QueryMsg::Token { token_id };
+        from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn emits_erc20_instantiation() {
+        let mut deps = setup();
+        let info = mock_info("alice", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, create_token("FOO")).unwrap();
+        assert_eq!(res.submessages.len(), 1);
+        let submsg = &res.submessages[0];
+        assert_eq!(submsg.id, 0);
+        assert_eq!(submsg.reply_on, ReplyOn::Success);
+        let init_msg: cw_erc20::InstantiateMsg = match &submsg.msg {
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                admin,
+                code_id,
+                msg,
+                send,
+                label,
+            }) => {
+                assert_eq!(*admin, None);
+                assert_eq!(*code_id, 7);
+                assert!(send.is_empty());
+                assert_eq!(label, "token-factory/0/FOO");
+                from_binary(msg).unwrap()
+            }
+            msg => panic!("unexpected message: {:?}", msg),
+        };
+        assert_eq!(
+            init_msg,
+            cw_erc20::InstantiateMsg {
+                name: "FOO Token".to_string(),
+                symbol: "FOO".to_string(),
+                decimals: 6,
+                initial_balances: vec![InitialBalance {
+                    address: "alice".to_string(),
+                    amount: Uint128::from(1000u128),
+                }],
+                minter: None,
+                admins: vec![],
+                admin_delay: 0,
+                marketing: None,
+                restricted: false,
+                genesis: None,
+                mint_limit_per_window: None,
+                mint_window_blocks: 0,
+                mint_approval: None,
+            }
+        );
+
+        // the payload is accepted by the erc20 contract
+        let mut token_deps = mock_dependencies(&[]);
+        let info = mock_info("factory", &[]);
+        cw_erc20::contract::instantiate(token_deps.as_mut(), mock_env(), info, init_msg).unwrap();
+    }
+
+    #[test]
+    fn records_address_from_reply() {
+        let mut deps = setup();
+        let info = mock_info("alice", &[]);
+        execute(deps.as_mut(), mock_env(), info, create_token("FOO")).unwrap();
+        assert_eq!(token(deps.as_ref(), 0).address, None);
+
+        let res = reply(deps.as_mut(), mock_env(), instantiated(0, "contract1")).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "register_token"),
+                attr("token_id", "0"),
+                attr("address", "contract1"),
+            ]
+        );
+        assert_eq!(
+            token(deps.as_ref(), 0),
+            TokenResponse {
+                token_id: 0,
+                creator: "alice".to_string(),
+                name: "FOO Token".to_string(),
+                symbol: "FOO".to_string(),
+                decimals: 6,
+                address: Some("contract1".to_string()),
+            }
+        );
+
+        match reply(deps.as_mut(), mock_env(), instantiated(1, "contract2")).unwrap_err() {
+            ContractError::UnknownToken { token_id: 1 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let no_address = Reply {
+            id: 0,
+            result: ContractResult::Ok(SubcallResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        match reply(deps.as_mut(), mock_env(), no_address).unwrap_err() {
+            ContractError::MissingContractAddress { token_id: 0 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn lists_tokens() {
+        let mut deps = setup();
+        for (token_id, symbol) in ["AAA", "BBB", "CCC"].iter().enumerate() {
+            let info = mock_info("alice", &[]);
+            execute(deps.as_mut(), mock_env(), info, create_token(symbol)).unwrap();
+            let address = format!("contract{}", token_id);
+            reply(
+                deps.as_mut(),
+                mock_env(),
+                instantiated(token_id as u64, &address),
+            )
+            .unwrap();
+        }
+        let list = |start_after, limit| {
+            let msg = QueryMsg::ListTokens { start_after, limit };
+            let res: TokenListResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res.tokens
+                .into_iter()
+                .map(|token| token.symbol)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(list(None, None), vec!["AAA", "BBB", "CCC"]);
+        assert_eq!(list(None, Some(2)), vec!["AAA", "BBB"]);
+        assert_eq!(list(Some(1), None), vec!["CCC"]);
+        assert!(list(Some(2), None).is_empty());
+
+        let res: ConfigResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(res.token_count, 3);
+    }
+}
@@ -0,0 +1,14 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unknown token {token_id}")]
+    UnknownToken { token_id: u64 },
+
+    #[error("Instantiation of token {token_id} reported no contract address")]
+    MissingContractAddress { token_id: u64 },
+}
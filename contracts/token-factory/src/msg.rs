@@ -0,0 +1,57 @@
+use cw_erc20::InitialBalance;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Code id of the uploaded erc20 contract
+    pub erc20_code_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Instantiates a new erc20 contract with these parameters, without a minter or admins.
+    /// Anyone can do this.
+    CreateToken {
+        name: String,
+        symbol: String,
+        decimals: u8,
+        initial_balances: Vec<InitialBalance>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Token {
+        token_id: u64,
+    },
+    /// Tokens with ids greater than `start_after`, oldest first
+    ListTokens {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub erc20_code_id: u64,
+    pub token_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenResponse {
+    pub token_id: u64,
+    pub creator: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenListResponse {
+    pub tokens: Vec<TokenResponse>,
+}
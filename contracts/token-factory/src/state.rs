@@ -0,0 +1,53 @@
+use cosmwasm_std::{Addr, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static TOKEN_COUNT_KEY: &[u8] = b"token_count";
+static TOKENS_KEY: &[u8] = b"tokens";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Code id of the uploaded erc20 contract
+    pub erc20_code_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenRecord {
+    pub creator: Addr,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Set in `reply` once the instantiation succeeded, in the same transaction
+    pub address: Option<Addr>,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Number of tokens created so far, which is also the id of the next one
+pub fn token_count(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, TOKEN_COUNT_KEY)
+}
+
+pub fn token_count_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, TOKEN_COUNT_KEY)
+}
+
+/// Created tokens by big endian id
+pub fn tokens(storage: &mut dyn Storage) -> Bucket<'_, TokenRecord> {
+    bucket(storage, TOKENS_KEY)
+}
+
+pub fn tokens_read(storage: &dyn Storage) -> ReadonlyBucket<'_, TokenRecord> {
+    bucket_read(storage, TOKENS_KEY)
+}
@@ -0,0 +1,71 @@
+//! Lets the factory create real erc20 contracts on a test chain, which executes the instantiate
+//! submessages and replies its `instantiate` event to the factory like a real chain.
+
+use cw_erc20::{BalanceResponse, InitialBalance};
+use cw_test_chain::{Chain, Contract};
+use cw_token_factory::contract::{execute, instantiate, query, reply};
+use cw_token_factory::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, TokenResponse};
+
+const FACTORY: &str = "factory";
+
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    let erc20 = chain.store_erc20();
+    let factory = chain.store_code(Contract::new(instantiate, execute, query).with_reply(reply));
+    let msg = InstantiateMsg {
+        erc20_code_id: erc20,
+    };
+    chain
+        .instantiate(factory, FACTORY, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn balance(chain: &Chain, token: &str, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(token, &msg).unwrap();
+    res.balance.u128()
+}
+
+fn token(chain: &Chain, token_id: u64) -> TokenResponse {
+    chain.query(FACTORY, &QueryMsg::Token { token_id }).unwrap()
+}
+
+#[test]
+fn created_tokens_are_registered() {
+    let mut chain = setup();
+    for (symbol, holder) in &[("AAA", "alice"), ("BBB", "bob")] {
+        let msg = ExecuteMsg::CreateToken {
+            name: format!("{} Token", symbol),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            initial_balances: vec![InitialBalance::new(*holder, 500u128)],
+        };
+        chain.execute(FACTORY, "creator", &msg, &[]).unwrap();
+    }
+
+    let first = token(&chain, 0);
+    assert_eq!(first.symbol, "AAA");
+    assert_eq!(first.address.as_deref(), Some("contract1"));
+    assert_eq!(balance(&chain, "contract1", "alice"), 500);
+    let second = token(&chain, 1);
+    assert_eq!(second.address.as_deref(), Some("contract2"));
+    assert_eq!(balance(&chain, "contract2", "bob"), 500);
+    assert_eq!(balance(&chain, "contract2", "alice"), 0);
+}
+
+#[test]
+fn failed_creation_is_not_registered() {
+    let mut chain = setup();
+    // erc20 rejects the symbol
+    let msg = ExecuteMsg::CreateToken {
+        name: "Bad Token".to_string(),
+        symbol: "b".to_string(),
+        decimals: 6,
+        initial_balances: vec![],
+    };
+    chain.execute(FACTORY, "creator", &msg, &[]).unwrap_err();
+    chain
+        .query::<TokenResponse, _>(FACTORY, &QueryMsg::Token { token_id: 0 })
+        .unwrap_err();
+}
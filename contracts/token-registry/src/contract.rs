@@ -0,0 +1,500 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult,
+};
+use cw_erc20::TokenInfoResponse;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, TokenListResponse, TokenResponse,
+};
+use crate::state::{config, config_read, tokens, tokens_read, Config, TokenEntry};
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+    config(deps.storage).save(&Config { admin })?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Register {
+            address,
+            name,
+            symbol,
+            decimals,
+            logo_url,
+        } => try_register(deps, env, info, address, name, symbol, decimals, logo_url),
+        ExecuteMsg::SetVerified { address, verified } => {
+            try_set_verified(deps, info, address, verified)
+        }
+        ExecuteMsg::Remove { address } => try_remove(deps, info, address),
+    }
+}
+
+/// Register
+///
+/// Checks the claimed metadata against the token's `TokenInfo` and stores the entry. An
+/// existing entry is replaced, which clears its verified flag.
+///
+/// @param address the erc20 contract
+/// @param logo_url an https url of the token's logo
+#[allow(clippy::too_many_arguments)]
+fn try_register(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: String,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    logo_url: Option<String>,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let address = deps.api.addr_validate(&address)?;
+    if let Some(existing) = tokens_read(deps.storage).may_load(address.as_str().as_bytes())? {
+        if info.sender != existing.registrant && info.sender != state.admin {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+    if let Some(logo_url) = &logo_url {
+        if !logo_url.starts_with("https://") {
+            return Err(ContractError::InvalidLogoUrl {});
+        }
+    }
+
+    let token_info: TokenInfoResponse = deps
+        .querier
+        .query_wasm_smart(&address, &cw_erc20::QueryMsg::TokenInfo {})?;
+    check_claim("name", &token_info.name, &name)?;
+    check_claim("symbol", &token_info.symbol, &symbol)?;
+    check_claim("decimals", &token_info.decimals, &decimals)?;
+
+    let entry = TokenEntry {
+        name,
+        symbol,
+        decimals,
+        logo_url,
+        registrant: info.sender,
+        verified: false,
+    };
+    tokens(deps.storage).save(address.as_str().as_bytes(), &entry)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "register"),
+            attr("address", address),
+            attr("symbol", entry.symbol),
+            attr("registrant", entry.registrant),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Fails if the token reports something else than was claimed
+fn check_claim<T: PartialEq + ToString>(
+    field: &str,
+    actual: &T,
+    claimed: &T,
+) -> Result<(), ContractError> {
+    if actual != claimed {
+        return Err(ContractError::MetadataMismatch {
+            field: field.to_string(),
+            actual: actual.to_string(),
+            claimed: claimed.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Set verified
+///
+/// Marks a registered token as verified or clears the mark. Only the admin can do this.
+fn try_set_verified(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    verified: bool,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    if info.sender != state.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let address = deps.api.addr_validate(&address)?;
+    let mut entry = load_token(deps.as_ref(), &address)?;
+    entry.verified = verified;
+    tokens(deps.storage).save(address.as_str().as_bytes(), &entry)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "set_verified"),
+            attr("address", address),
+            attr("verified", verified),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Remove
+///
+/// Deletes the entry of a token. Only the registrant or the admin can do this.
+fn try_remove(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let address = deps.api.addr_validate(&address)?;
+    let entry = load_token(deps.as_ref(), &address)?;
+    if info.sender != entry.registrant && info.sender != state.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    tokens(deps.storage).remove(address.as_str().as_bytes());
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![attr("action", "remove"), attr("address", address)],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn load_token(deps: Deps, address: &Addr) -> Result<TokenEntry, ContractError> {
+    tokens_read(deps.storage)
+        .may_load(address.as_str().as_bytes())?
+        .ok_or_else(|| ContractError::UnknownToken {
+            address: address.to_string(),
+        })
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => {
+            let state = config_read(deps.storage).load()?;
+            to_binary(&ConfigResponse {
+                admin: state.admin.to_string(),
+            })
+        }
+        QueryMsg::TokenDetail { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let entry = tokens_read(deps.storage).load(address.as_str().as_bytes())?;
+            to_binary(&token_response(address.to_string(), entry))
+        }
+        QueryMsg::ListTokens { start_after, limit } => {
+            to_binary(&query_list_tokens(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_list_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokenListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // appending a zero byte gives the first key sorting after `start_after`
+    let start = start_after.map(|address| {
+        let mut start = address.into_bytes();
+        start.push(0);
+        start
+    });
+    let tokens = tokens_read(deps.storage)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, entry) = item?;
+            let address = String::from_utf8(key)?;
+            Ok(token_response(address, entry))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(TokenListResponse { tokens })
+}
+
+fn token_response(address: String, entry: TokenEntry) -> TokenResponse {
+    TokenResponse {
+        address,
+        name: entry.name,
+        symbol: entry.symbol,
+        decimals: entry.decimals,
+        logo_url: entry.logo_url,
+        registrant: entry.registrant.to_string(),
+        verified: entry.verified,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+    use cosmwasm_std::{
+        from_binary, from_slice, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+        QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    };
+
+    /// Answers token info queries of the tokens "token0" to "token9", which are called
+    /// "Token N" with the symbol "TKN" and N decimals
+    struct InfoQuerier {}
+
+    impl Querier for InfoQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            match request {
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                    assert_eq!(
+                        from_binary::<cw_erc20::QueryMsg>(&msg).unwrap(),
+                        cw_erc20::QueryMsg::TokenInfo {}
+                    );
+                    let decimals = match contract_addr.strip_prefix("token") {
+                        Some(n) if n.len() == 1 => n.parse::<u8>().unwrap(),
+                        _ => {
+                            return SystemResult::Err(SystemError::NoSuchContract {
+                                addr: contract_addr,
+                            })
+                        }
+                    };
+                    let res = to_binary(&TokenInfoResponse {
+                        name: format!("Token {}", decimals),
+                        symbol: "TKN".to_string(),
+                        decimals,
+                        total_supply: Uint128::from(1000u128),
+                    });
+                    SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "non-wasm".to_string(),
+                }),
+            }
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, InfoQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: InfoQuerier {},
+        };
+        let msg = InstantiateMsg { admin: None };
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+        deps
+    }
+
+    /// Registers token `n` with correct metadata
+    fn register_msg(n: u8) -> ExecuteMsg {
+        ExecuteMsg::Register {
+            address: format!("token{}", n),
+            name: format!("Token {}", n),
+            symbol: "TKN".to_string(),
+            decimals: n,
+            logo_url: None,
+        }
+    }
+
+    fn run(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn detail(deps: Deps, address: &str) -> StdResult<TokenResponse> {
+        let msg = QueryMsg::TokenDetail {
+            address: address.to_string(),
+        };
+        query(deps, mock_env(), msg).map(|res| from_binary(&res).unwrap())
+    }
+
+    #[test]
+    fn verifies_claims_against_token() {
+        let mut deps = setup();
+        let claims = vec![
+            ("name", "Token 6", "Token 7", "TKN", 6),
+            ("symbol", "TKN", "Token 6", "OTHER", 6),
+            ("decimals", "6", "Token 6", "TKN", 8),
+        ];
+        for (field, actual, name, symbol, decimals) in claims {
+            let msg = ExecuteMsg::Register {
+                address: "token6".to_string(),
+                name: name.to_string(),
+                symbol: symbol.to_string(),
+                decimals,
+                logo_url: None,
+            };
+            match run(deps.as_mut(), "alice", msg).unwrap_err() {
+                ContractError::MetadataMismatch {
+                    field: f,
+                    actual: a,
+                    ..
+                } => {
+                    assert_eq!(f, field);
+                    assert_eq!(a, actual);
+                }
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        let msg = ExecuteMsg::Register {
+            address: "token6".to_string(),
+            name: "Token 6".to_string(),
+            symbol: "TKN".to_string(),
+            decimals: 6,
+            logo_url: Some("http://logo.example".to_string()),
+        };
+        match run(deps.as_mut(), "alice", msg).unwrap_err() {
+            ContractError::InvalidLogoUrl {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::Register {
+            address: "wallet".to_string(),
+            name: "Token 6".to_string(),
+            symbol: "TKN".to_string(),
+            decimals: 6,
+            logo_url: None,
+        };
+        match run(deps.as_mut(), "alice", msg).unwrap_err() {
+            ContractError::Std(_) => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert!(detail(deps.as_ref(), "token6").is_err());
+
+        run(deps.as_mut(), "alice", register_msg(6)).unwrap();
+        let token = detail(deps.as_ref(), "token6").unwrap();
+        assert_eq!(token.name, "Token 6");
+        assert_eq!(token.decimals, 6);
+        assert_eq!(token.registrant, "alice");
+        assert!(!token.verified);
+    }
+
+    #[test]
+    fn registering_again_updates_entry() {
+        let mut deps = setup();
+        run(deps.as_mut(), "alice", register_msg(6)).unwrap();
+        let verify = ExecuteMsg::SetVerified {
+            address: "token6".to_string(),
+            verified: true,
+        };
+        run(deps.as_mut(), "admin", verify).unwrap();
+        assert!(detail(deps.as_ref(), "token6").unwrap().verified);
+
+        match run(deps.as_mut(), "bob", register_msg(6)).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::Register {
+            address: "token6".to_string(),
+            name: "Token 6".to_string(),
+            symbol: "TKN".to_string(),
+            decimals: 6,
+            logo_url: Some("https://logo.example/tkn.png".to_string()),
+        };
+        run(deps.as_mut(), "alice", msg).unwrap();
+        let token = detail(deps.as_ref(), "token6").unwrap();
+        assert_eq!(
+            token.logo_url,
+            Some("https://logo.example/tkn.png".to_string())
+        );
+        assert!(!token.verified);
+        let list = query_list_tokens(deps.as_ref(), None, None).unwrap();
+        assert_eq!(list.tokens, vec![token]);
+
+        // the admin may update any entry, and becomes its registrant
+        run(deps.as_mut(), "admin", register_msg(6)).unwrap();
+        assert_eq!(detail(deps.as_ref(), "token6").unwrap().registrant, "admin");
+    }
+
+    #[test]
+    fn only_admin_verifies() {
+        let mut deps = setup();
+        let verify = ExecuteMsg::SetVerified {
+            address: "token6".to_string(),
+            verified: true,
+        };
+        match run(deps.as_mut(), "admin", verify.clone()).unwrap_err() {
+            ContractError::UnknownToken { address } => assert_eq!(address, "token6"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        run(deps.as_mut(), "alice", register_msg(6)).unwrap();
+        match run(deps.as_mut(), "alice", verify.clone()).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        run(deps.as_mut(), "admin", verify).unwrap();
+        assert!(detail(deps.as_ref(), "token6").unwrap().verified);
+        let unverify = ExecuteMsg::SetVerified {
+            address: "token6".to_string(),
+            verified: false,
+        };
+        run(deps.as_mut(), "admin", unverify).unwrap();
+        assert!(!detail(deps.as_ref(), "token6").unwrap().verified);
+    }
+
+    #[test]
+    fn registrant_or_admin_removes() {
+        let mut deps = setup();
+        run(deps.as_mut(), "alice", register_msg(6)).unwrap();
+        run(deps.as_mut(), "alice", register_msg(8)).unwrap();
+        let remove = |n: u8| ExecuteMsg::Remove {
+            address: format!("token{}", n),
+        };
+        match run(deps.as_mut(), "bob", remove(6)).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        run(deps.as_mut(), "alice", remove(6)).unwrap();
+        run(deps.as_mut(), "admin", remove(8)).unwrap();
+        assert!(detail(deps.as_ref(), "token6").is_err());
+        let list = query_list_tokens(deps.as_ref(), None, None).unwrap();
+        assert_eq!(list.tokens, vec![]);
+        match run(deps.as_mut(), "alice", remove(6)).unwrap_err() {
+            ContractError::UnknownToken { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // a removed token can be registered again by anyone
+        run(deps.as_mut(), "bob", register_msg(6)).unwrap();
+        assert_eq!(detail(deps.as_ref(), "token6").unwrap().registrant, "bob");
+    }
+
+    #[test]
+    fn lists_tokens_in_pages() {
+        let mut deps = setup();
+        for n in &[4, 1, 3, 0, 2] {
+            run(deps.as_mut(), "alice", register_msg(*n)).unwrap();
+        }
+        let addresses = |list: TokenListResponse| {
+            list.tokens
+                .into_iter()
+                .map(|token| token.address)
+                .collect::<Vec<_>>()
+        };
+        let page = query_list_tokens(deps.as_ref(), None, Some(2)).unwrap();
+        assert_eq!(addresses(page), vec!["token0", "token1"]);
+        let page = query_list_tokens(deps.as_ref(), Some("token1".to_string()), Some(2)).unwrap();
+        assert_eq!(addresses(page), vec!["token2", "token3"]);
+        let page = query_list_tokens(deps.as_ref(), Some("token3".to_string()), Some(2)).unwrap();
+        assert_eq!(addresses(page), vec!["token4"]);
+        let page = query_list_tokens(deps.as_ref(), Some("token4".to_string()), None).unwrap();
+        assert_eq!(addresses(page), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,24 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Token reports {field} {actual}, not {claimed}")]
+    MetadataMismatch {
+        field: String,
+        actual: String,
+        claimed: String,
+    },
+
+    #[error("Logo url must start with https://")]
+    InvalidLogoUrl {},
+
+    #[error("Unknown token {address}")]
+    UnknownToken { address: String },
+}
@@ -0,0 +1,62 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Marks tokens as verified. Defaults to the instantiating address.
+    pub admin: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Registers the erc20 token at `address`, or updates its entry. The claimed name, symbol
+    /// and decimals must match what the token reports. Only the original registrant or the
+    /// admin can update an entry.
+    Register {
+        address: String,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        logo_url: Option<String>,
+    },
+    /// Sets or clears the verified flag of a token. Only the admin can do this.
+    SetVerified { address: String, verified: bool },
+    /// Removes a token from the registry. Only the registrant or the admin can do this.
+    Remove { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    TokenDetail {
+        address: String,
+    },
+    /// Tokens with addresses sorting after `start_after`, in address order
+    ListTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub admin: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenResponse {
+    pub address: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub logo_url: Option<String>,
+    pub registrant: String,
+    pub verified: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenListResponse {
+    pub tokens: Vec<TokenResponse>,
+}
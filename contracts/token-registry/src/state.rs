@@ -0,0 +1,45 @@
+use cosmwasm_std::{Addr, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static TOKENS_KEY: &[u8] = b"tokens";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Marks tokens as verified and can update or remove any entry
+    pub admin: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenEntry {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub logo_url: Option<String>,
+    /// Registered the token and can update or remove the entry
+    pub registrant: Addr,
+    /// Set by the admin, cleared whenever the entry is updated
+    pub verified: bool,
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+/// Registered tokens by address
+pub fn tokens(storage: &mut dyn Storage) -> Bucket<'_, TokenEntry> {
+    bucket(storage, TOKENS_KEY)
+}
+
+pub fn tokens_read(storage: &dyn Storage) -> ReadonlyBucket<'_, TokenEntry> {
+    bucket_read(storage, TOKENS_KEY)
+}
@@ -0,0 +1,99 @@
+//! Registers real erc20 contracts on a test chain, which answers the registry's token info
+//! queries from the erc20 contracts.
+
+use cw_test_chain::{erc20_msg, Chain, Contract};
+use cw_token_registry::contract::{execute, instantiate, query};
+use cw_token_registry::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, TokenListResponse};
+
+const REGISTRY: &str = "registry";
+
+/// Instantiates a registry next to erc20 contracts with the given symbols at their lowercase
+/// symbols, each named after its symbol and with 6 decimals
+fn setup(symbols: &[&str]) -> Chain {
+    let mut chain = Chain::new();
+    for symbol in symbols {
+        let msg = erc20_msg(symbol, &[("deployer", 1000)]);
+        chain
+            .instantiate_erc20(&symbol.to_lowercase(), &msg)
+            .unwrap();
+    }
+
+    let registry = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        admin: Some("curator".to_string()),
+    };
+    chain
+        .instantiate(registry, REGISTRY, "creator", &msg, &[])
+        .unwrap();
+    chain
+}
+
+/// Registers the token at the lowercase symbol with the given claims
+fn register(chain: &mut Chain, symbol: &str, name: &str, decimals: u8) -> Result<(), String> {
+    let msg = ExecuteMsg::Register {
+        address: symbol.to_lowercase(),
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        decimals,
+        logo_url: None,
+    };
+    chain.execute(REGISTRY, "deployer", &msg, &[]).map(|_| ())
+}
+
+fn list(chain: &Chain, start_after: Option<&str>) -> Vec<(String, bool)> {
+    let msg = QueryMsg::ListTokens {
+        start_after: start_after.map(String::from),
+        limit: Some(2),
+    };
+    let res: TokenListResponse = chain.query(REGISTRY, &msg).unwrap();
+    res.tokens
+        .into_iter()
+        .map(|token| (token.symbol, token.verified))
+        .collect()
+}
+
+#[test]
+fn verifies_metadata_with_token() {
+    let mut chain = setup(&["CASH"]);
+    let err = register(&mut chain, "CASH", "Cash", 6).unwrap_err();
+    assert_eq!(err, "Token reports name CASH Token, not Cash");
+    let err = register(&mut chain, "CASH", "CASH Token", 18).unwrap_err();
+    assert_eq!(err, "Token reports decimals 6, not 18");
+    // not an erc20 contract at all
+    let err = register(&mut chain, "GOLD", "GOLD Token", 6).unwrap_err();
+    assert!(err.contains("gold"), "unexpected error: {}", err);
+
+    register(&mut chain, "CASH", "CASH Token", 6).unwrap();
+    // registering again updates the entry instead of adding another one
+    register(&mut chain, "CASH", "CASH Token", 6).unwrap();
+    assert_eq!(list(&chain, None), vec![("CASH".to_string(), false)]);
+}
+
+#[test]
+fn pages_through_registry() {
+    let symbols = ["ATOM", "BTC", "CASH", "DAI", "ETH"];
+    let mut chain = setup(&symbols);
+    for symbol in symbols.iter().rev() {
+        register(&mut chain, symbol, &format!("{} Token", symbol), 6).unwrap();
+    }
+    let msg = ExecuteMsg::SetVerified {
+        address: "dai".to_string(),
+        verified: true,
+    };
+    chain.execute(REGISTRY, "curator", &msg, &[]).unwrap();
+    let msg = ExecuteMsg::Remove {
+        address: "btc".to_string(),
+    };
+    chain.execute(REGISTRY, "deployer", &msg, &[]).unwrap();
+
+    let entry = |symbol: &str, verified: bool| (symbol.to_string(), verified);
+    assert_eq!(
+        list(&chain, None),
+        vec![entry("ATOM", false), entry("CASH", false)]
+    );
+    assert_eq!(
+        list(&chain, Some("cash")),
+        vec![entry("DAI", true), entry("ETH", false)]
+    );
+    assert_eq!(list(&chain, Some("eth")), vec![]);
+}
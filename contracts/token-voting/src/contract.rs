@@ -0,0 +1,803 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, WasmMsg,
+};
+use cw_erc20::BalanceResponse;
+
+use crate::error::ContractError;
+use crate::msg::{
+    BallotResponse, CampaignResponse, ExecuteMsg, InstantiateMsg, PauseVotesResponse, PollResponse,
+    QueryMsg, TargetExecuteMsg,
+};
+use crate::state::{
+    ballots, ballots_read, campaigns, campaigns_read, config, config_read, pause_votes,
+    pause_votes_read, poll_count, poll_count_read, polls, polls_read, Ballot, Campaign, Config,
+    PauseAction, Poll, PollStatus,
+};
+
+const MAX_BPS: u64 = 10_000;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.pause_threshold_bps == 0 || msg.pause_threshold_bps > MAX_BPS {
+        return Err(ContractError::InvalidPauseThreshold {
+            bps: msg.pause_threshold_bps,
+        });
+    }
+    if msg.vote_window_blocks == 0 {
+        return Err(ContractError::InvalidVoteWindow {});
+    }
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        pause_threshold_bps: msg.pause_threshold_bps,
+        vote_window_blocks: msg.vote_window_blocks,
+    };
+    // fail here rather than on the first vote if the token keeps no snapshots
+    query_balance_at(
+        deps.as_ref(),
+        &state.token,
+        &env.contract.address,
+        env.block.height.saturating_sub(1),
+    )?;
+    config(deps.storage).save(&state)?;
+    poll_count(deps.storage).save(&0)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreatePoll {
+            question,
+            end_height,
+        } => try_create_poll(deps, env, info, question, end_height),
+        ExecuteMsg::Vote { poll_id, yes } => try_vote(deps, env, info, poll_id, yes),
+        ExecuteMsg::Tally { poll_id } => try_tally(deps, env, poll_id),
+        ExecuteMsg::VotePause { target_contract } => {
+            try_vote_pause(deps, env, info, target_contract, PauseAction::Pause)
+        }
+        ExecuteMsg::VoteUnpause { target_contract } => {
+            try_vote_pause(deps, env, info, target_contract, PauseAction::Unpause)
+        }
+    }
+}
+
+/// Create poll
+///
+/// Opens a poll accepting votes until `end_height`. Votes are weighed with the token balances
+/// at the end of the previous block, so tokens bought or moved after creation don't count.
+///
+/// @param question what the poll asks
+/// @param end_height first block height at which votes are no longer accepted
+fn try_create_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    question: String,
+    end_height: u64,
+) -> Result<Response, ContractError> {
+    if end_height <= env.block.height {
+        return Err(ContractError::InvalidEndHeight { end_height });
+    }
+
+    let poll_id = poll_count_read(deps.storage).load()?;
+    poll_count(deps.storage).save(&(poll_id + 1))?;
+    let poll = Poll {
+        creator: info.sender.clone(),
+        question,
+        snapshot_height: env.block.height.saturating_sub(1),
+        end_height,
+        yes_votes: Uint128::zero(),
+        no_votes: Uint128::zero(),
+        status: PollStatus::Open,
+    };
+    polls(deps.storage).save(&poll_id.to_be_bytes(), &poll)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "create_poll"),
+            attr("poll_id", poll_id),
+            attr("creator", info.sender),
+            attr("end_height", end_height),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    yes: bool,
+) -> Result<Response, ContractError> {
+    let mut poll = load_poll(deps.as_ref(), poll_id)?;
+    if env.block.height >= poll.end_height {
+        return Err(ContractError::PollEnded {
+            end_height: poll.end_height,
+        });
+    }
+    let voter_key = info.sender.as_str().as_bytes();
+    if ballots_read(deps.storage, poll_id)
+        .may_load(voter_key)?
+        .is_some()
+    {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    let state = config_read(deps.storage).load()?;
+    let weight = query_voting_power(deps.as_ref(), &state, &info.sender, poll.snapshot_height)?;
+
+    if yes {
+        poll.yes_votes = poll.yes_votes.checked_add(weight)?;
+    } else {
+        poll.no_votes = poll.no_votes.checked_add(weight)?;
+    }
+    polls(deps.storage).save(&poll_id.to_be_bytes(), &poll)?;
+    ballots(deps.storage, poll_id).save(voter_key, &Ballot { yes, weight })?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "vote"),
+            attr("poll_id", poll_id),
+            attr("voter", info.sender),
+            attr("yes", yes.to_string()),
+            attr("weight", weight),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn try_tally(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
+    let mut poll = load_poll(deps.as_ref(), poll_id)?;
+    if poll.status != PollStatus::Open {
+        return Err(ContractError::PollTallied {});
+    }
+    if env.block.height < poll.end_height {
+        return Err(ContractError::PollNotEnded {
+            end_height: poll.end_height,
+        });
+    }
+
+    poll.status = if poll.yes_votes > poll.no_votes {
+        PollStatus::Passed
+    } else {
+        PollStatus::Rejected
+    };
+    polls(deps.storage).save(&poll_id.to_be_bytes(), &poll)?;
+
+    let passed = poll.status == PollStatus::Passed;
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "tally"),
+            attr("poll_id", poll_id),
+            attr("passed", passed.to_string()),
+            attr("yes_votes", poll.yes_votes),
+            attr("no_votes", poll.no_votes),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Pause or unpause campaign
+///
+/// Adds the signer's token balance to the running campaign for `action` on `target_contract`,
+/// or starts a new round if the last one expired. Votes are weighed with the balances at the
+/// end of the block before the round started, so tokens moved after voting cannot vote again
+/// in the same round. Once the round's weight reaches
+/// `pause_threshold_bps` of the total supply, the matching `TargetExecuteMsg` is sent to the
+/// target and the next vote starts a new round.
+///
+/// @param target_contract the contract to pause or unpause
+/// @param action whether to pause or unpause it
+fn try_vote_pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target_contract: String,
+    action: PauseAction,
+) -> Result<Response, ContractError> {
+    let state = config_read(deps.storage).load()?;
+    let target = deps.api.addr_validate(&target_contract)?;
+
+    let mut campaign = campaigns_read(deps.storage, action)
+        .may_load(target.as_str().as_bytes())?
+        .unwrap_or_default();
+    if campaign.weight.is_zero()
+        || env.block.height >= campaign.expires_at_height(state.vote_window_blocks)
+    {
+        campaign = Campaign {
+            round: campaign.round + 1,
+            start_height: env.block.height,
+            snapshot_height: env.block.height.saturating_sub(1),
+            weight: Uint128::zero(),
+        };
+    }
+    let weight = query_voting_power(
+        deps.as_ref(),
+        &state,
+        &info.sender,
+        campaign.snapshot_height,
+    )?;
+    let voter_key = info.sender.as_str().as_bytes();
+    if pause_votes_read(deps.storage, action, &target).may_load(voter_key)? == Some(campaign.round)
+    {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    pause_votes(deps.storage, action, &target).save(voter_key, &campaign.round)?;
+    campaign.weight = campaign.weight.checked_add(weight)?;
+
+    let supply = query_total_supply(deps.as_ref(), &state.token)?;
+    let triggered =
+        campaign.weight.multiply_ratio(MAX_BPS, supply) >= Uint128::from(state.pause_threshold_bps);
+    let mut messages = vec![];
+    if triggered {
+        let msg = match action {
+            PauseAction::Pause => TargetExecuteMsg::Pause {},
+            PauseAction::Unpause => TargetExecuteMsg::Unpause {},
+        };
+        messages.push(
+            WasmMsg::Execute {
+                contract_addr: target.to_string(),
+                msg: to_binary(&msg)?,
+                send: vec![],
+            }
+            .into(),
+        );
+    }
+    let total_weight = campaign.weight;
+    if triggered {
+        // the next vote starts a new round
+        campaign.weight = Uint128::zero();
+    }
+    campaigns(deps.storage, action).save(target.as_str().as_bytes(), &campaign)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", format!("vote_{}", action.as_str())),
+            attr("target_contract", target),
+            attr("voter", info.sender),
+            attr("weight", weight),
+            attr("total_weight", total_weight),
+            attr("triggered", triggered.to_string()),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+fn query_voting_power(
+    deps: Deps,
+    state: &Config,
+    voter: &Addr,
+    height: u64,
+) -> Result<Uint128, ContractError> {
+    let balance = query_balance_at(deps, &state.token, voter, height)?;
+    if balance.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+    Ok(balance)
+}
+
+/// Tokens built without the `iterator` feature don't know `BalanceAt` and fail the query
+fn query_balance_at(
+    deps: Deps,
+    token: &Addr,
+    address: &Addr,
+    height: u64,
+) -> Result<Uint128, ContractError> {
+    let balance: BalanceResponse = deps
+        .querier
+        .query_wasm_smart(
+            token,
+            &cw_erc20::QueryMsg::balance_at(address.as_str(), height),
+        )
+        .map_err(|_| ContractError::SnapshotsUnsupported {
+            token: token.to_string(),
+        })?;
+    Ok(balance.balance)
+}
+
+fn query_total_supply(deps: Deps, token: &Addr) -> Result<Uint128, ContractError> {
+    let supply = match deps
+        .querier
+        .query_wasm_raw(token, cw_erc20::total_supply_key())?
+    {
+        Some(data) => cw_erc20::decode_amount(&data).map_err(|_| ContractError::NoSupply {})?,
+        None => 0,
+    };
+    if supply == 0 {
+        return Err(ContractError::NoSupply {});
+    }
+    Ok(Uint128::from(supply))
+}
+
+fn load_poll(deps: Deps, poll_id: u64) -> Result<Poll, ContractError> {
+    polls_read(deps.storage)
+        .may_load(&poll_id.to_be_bytes())?
+        .ok_or(ContractError::PollNotFound { poll_id })
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Poll { poll_id } => {
+            let poll = polls_read(deps.storage)
+                .may_load(&poll_id.to_be_bytes())?
+                .ok_or_else(|| StdError::not_found("Poll"))?;
+            to_binary(&PollResponse {
+                creator: poll.creator.to_string(),
+                question: poll.question,
+                snapshot_height: poll.snapshot_height,
+                end_height: poll.end_height,
+                yes_votes: poll.yes_votes,
+                no_votes: poll.no_votes,
+                status: poll.status,
+            })
+        }
+        QueryMsg::Ballot { poll_id, address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let ballot =
+                ballots_read(deps.storage, poll_id).may_load(address.as_str().as_bytes())?;
+            to_binary(&BallotResponse { ballot })
+        }
+        QueryMsg::PauseVotes { target_contract } => {
+            let target = deps.api.addr_validate(&target_contract)?;
+            to_binary(&PauseVotesResponse {
+                pause: query_campaign(deps, &env, &target, PauseAction::Pause)?,
+                unpause: query_campaign(deps, &env, &target, PauseAction::Unpause)?,
+            })
+        }
+    }
+}
+
+fn query_campaign(
+    deps: Deps,
+    env: &Env,
+    target: &Addr,
+    action: PauseAction,
+) -> StdResult<CampaignResponse> {
+    let state = config_read(deps.storage).load()?;
+    let running = campaigns_read(deps.storage, action)
+        .may_load(target.as_str().as_bytes())?
+        .filter(|campaign| {
+            !campaign.weight.is_zero()
+                && env.block.height < campaign.expires_at_height(state.vote_window_blocks)
+        });
+    let res = match running {
+        Some(campaign) => CampaignResponse {
+            weight: campaign.weight,
+            snapshot_height: Some(campaign.snapshot_height),
+            expires_at_height: Some(campaign.expires_at_height(state.vote_window_blocks)),
+        },
+        None => CampaignResponse {
+            weight: Uint128::zero(),
+            snapshot_height: None,
+            expires_at_height: None,
+        },
+    };
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+    use cosmwasm_std::{
+        from_binary, from_slice, ContractResult, CosmosMsg, Empty, OwnedDeps, Querier,
+        QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+    };
+    use std::collections::HashMap;
+
+    /// Answers snapshot balance and total supply queries to the token at "token". Balances
+    /// don't change between heights.
+    #[derive(Default)]
+    struct TokenQuerier {
+        balances: HashMap<String, u128>,
+        /// Fails `BalanceAt` like a token built without the `iterator` feature
+        no_snapshots: bool,
+    }
+
+    impl TokenQuerier {
+        fn total_supply(&self) -> u128 {
+            self.balances.values().sum()
+        }
+    }
+
+    impl Querier for TokenQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            match request {
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                    if contract_addr == "token" =>
+                {
+                    if self.no_snapshots {
+                        let err = "unknown variant `balance_at`".to_string();
+                        return SystemResult::Ok(ContractResult::Err(err));
+                    }
+                    let address = match from_binary(&msg).unwrap() {
+                        cw_erc20::QueryMsg::BalanceAt { address, .. } => address,
+                        query => panic!("unexpected query: {:?}", query),
+                    };
+                    let balance = self.balances.get(&address).copied().unwrap_or_default();
+                    let res = to_binary(&BalanceResponse {
+                        balance: Uint128::from(balance),
+                    });
+                    SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+                }
+                QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key })
+                    if contract_addr == "token" =>
+                {
+                    assert_eq!(key.as_slice(), cw_erc20::total_supply_key().as_slice());
+                    let supply = Binary::from(self.total_supply().to_be_bytes());
+                    SystemResult::Ok(ContractResult::Ok(supply))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "non-token".to_string(),
+                }),
+            }
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, TokenQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: TokenQuerier::default(),
+        };
+        deps.querier.balances.insert("alice".to_string(), 60);
+        deps.querier.balances.insert("bob".to_string(), 30);
+        deps.querier.balances.insert("carol".to_string(), 20);
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            pause_threshold_bps: 5000,
+            vote_window_blocks: 10,
+        };
+        instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn run(
+        deps: DepsMut,
+        height: u64,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env_at(height), mock_info(sender, &[]), msg)
+    }
+
+    fn create_poll(deps: DepsMut, end_height: u64) -> u64 {
+        let msg = ExecuteMsg::CreatePoll {
+            question: "Should we?".to_string(),
+            end_height,
+        };
+        let res = run(deps, 100, "creator", msg).unwrap();
+        res.attributes[1].value.parse().unwrap()
+    }
+
+    fn vote(poll_id: u64, yes: bool) -> ExecuteMsg {
+        ExecuteMsg::Vote { poll_id, yes }
+    }
+
+    fn poll(deps: Deps, poll_id: u64) -> PollResponse {
+        from_binary(&query(deps, mock_env(), QueryMsg::Poll { poll_id }).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn creates_polls() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::CreatePoll {
+            question: "Should we?".to_string(),
+            end_height: 100,
+        };
+        match run(deps.as_mut(), 100, "creator", msg).unwrap_err() {
+            ContractError::InvalidEndHeight { end_height: 100 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        assert_eq!(create_poll(deps.as_mut(), 110), 0);
+        assert_eq!(create_poll(deps.as_mut(), 120), 1);
+        assert_eq!(
+            poll(deps.as_ref(), 1),
+            PollResponse {
+                creator: "creator".to_string(),
+                question: "Should we?".to_string(),
+                snapshot_height: 99,
+                end_height: 120,
+                yes_votes: Uint128::zero(),
+                no_votes: Uint128::zero(),
+                status: PollStatus::Open,
+            }
+        );
+    }
+
+    #[test]
+    fn weighs_votes_by_balance() {
+        let mut deps = setup();
+        let poll_id = create_poll(deps.as_mut(), 110);
+
+        let res = run(deps.as_mut(), 101, "alice", vote(poll_id, false)).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "vote"),
+                attr("poll_id", "0"),
+                attr("voter", "alice"),
+                attr("yes", "false"),
+                attr("weight", "60"),
+            ]
+        );
+        run(deps.as_mut(), 102, "bob", vote(poll_id, true)).unwrap();
+        run(deps.as_mut(), 103, "carol", vote(poll_id, true)).unwrap();
+
+        let res = poll(deps.as_ref(), poll_id);
+        assert_eq!(res.yes_votes.u128(), 50);
+        assert_eq!(res.no_votes.u128(), 60);
+
+        let msg = QueryMsg::Ballot {
+            poll_id,
+            address: "bob".to_string(),
+        };
+        let res: BallotResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res.ballot,
+            Some(Ballot {
+                yes: true,
+                weight: Uint128::from(30u128),
+            })
+        );
+
+        match run(deps.as_mut(), 104, "dave", vote(poll_id, true)).unwrap_err() {
+            ContractError::NoVotingPower {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 104, "alice", vote(7, true)).unwrap_err() {
+            ContractError::PollNotFound { poll_id: 7 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_double_votes() {
+        let mut deps = setup();
+        let poll_id = create_poll(deps.as_mut(), 110);
+        run(deps.as_mut(), 101, "alice", vote(poll_id, true)).unwrap();
+        match run(deps.as_mut(), 102, "alice", vote(poll_id, false)).unwrap_err() {
+            ContractError::AlreadyVoted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(poll(deps.as_ref(), poll_id).yes_votes.u128(), 60);
+        assert!(poll(deps.as_ref(), poll_id).no_votes.is_zero());
+
+        // other polls are not affected
+        let other = create_poll(deps.as_mut(), 110);
+        run(deps.as_mut(), 102, "alice", vote(other, false)).unwrap();
+    }
+
+    #[test]
+    fn tallies_after_end_height() {
+        let mut deps = setup();
+        let poll_id = create_poll(deps.as_mut(), 110);
+        run(deps.as_mut(), 101, "bob", vote(poll_id, true)).unwrap();
+        run(deps.as_mut(), 101, "carol", vote(poll_id, false)).unwrap();
+
+        let tally = ExecuteMsg::Tally { poll_id };
+        match run(deps.as_mut(), 109, "anyone", tally.clone()).unwrap_err() {
+            ContractError::PollNotEnded { end_height: 110 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 110, "alice", vote(poll_id, false)).unwrap_err() {
+            ContractError::PollEnded { end_height: 110 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = run(deps.as_mut(), 110, "anyone", tally.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "tally"),
+                attr("poll_id", "0"),
+                attr("passed", "true"),
+                attr("yes_votes", "30"),
+                attr("no_votes", "20"),
+            ]
+        );
+        assert_eq!(poll(deps.as_ref(), poll_id).status, PollStatus::Passed);
+        match run(deps.as_mut(), 111, "anyone", tally).unwrap_err() {
+            ContractError::PollTallied {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // ties are rejected
+        let poll_id = create_poll(deps.as_mut(), 110);
+        run(deps.as_mut(), 110, "anyone", ExecuteMsg::Tally { poll_id }).unwrap();
+        assert_eq!(poll(deps.as_ref(), poll_id).status, PollStatus::Rejected);
+    }
+
+    fn vote_pause(target_contract: &str) -> ExecuteMsg {
+        ExecuteMsg::VotePause {
+            target_contract: target_contract.to_string(),
+        }
+    }
+
+    fn pause_votes(deps: Deps, height: u64, target_contract: &str) -> PauseVotesResponse {
+        let msg = QueryMsg::PauseVotes {
+            target_contract: target_contract.to_string(),
+        };
+        from_binary(&query(deps, env_at(height), msg).unwrap()).unwrap()
+    }
+
+    fn target_call(msg: TargetExecuteMsg) -> CosmosMsg {
+        WasmMsg::Execute {
+            contract_addr: "target".to_string(),
+            msg: to_binary(&msg).unwrap(),
+            send: vec![],
+        }
+        .into()
+    }
+
+    #[test]
+    fn validates_pause_config() {
+        let mut deps = setup();
+        let msg = |pause_threshold_bps, vote_window_blocks| InstantiateMsg {
+            token: "token".to_string(),
+            pause_threshold_bps,
+            vote_window_blocks,
+        };
+        for &bps in &[0, 10_001] {
+            match instantiate(
+                deps.as_mut(),
+                env_at(100),
+                mock_info("creator", &[]),
+                msg(bps, 10),
+            )
+            .unwrap_err()
+            {
+                ContractError::InvalidPauseThreshold { bps: got } => assert_eq!(got, bps),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        match instantiate(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("creator", &[]),
+            msg(5000, 0),
+        )
+        .unwrap_err()
+        {
+            ContractError::InvalidVoteWindow {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn requires_token_snapshots() {
+        let mut deps = setup();
+        deps.querier.no_snapshots = true;
+        let msg = InstantiateMsg {
+            token: "token".to_string(),
+            pause_threshold_bps: 5000,
+            vote_window_blocks: 10,
+        };
+        let err = instantiate(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg);
+        match err.unwrap_err() {
+            ContractError::SnapshotsUnsupported { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn pauses_at_threshold() {
+        let mut deps = setup();
+        // 50% of the 110 tokens are needed
+        let res = run(deps.as_mut(), 100, "bob", vote_pause("target")).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "vote_pause"),
+                attr("target_contract", "target"),
+                attr("voter", "bob"),
+                attr("weight", "30"),
+                attr("total_weight", "30"),
+                attr("triggered", "false"),
+            ]
+        );
+        let res = run(deps.as_mut(), 101, "carol", vote_pause("target")).unwrap();
+        assert!(res.messages.is_empty());
+        match run(deps.as_mut(), 102, "bob", vote_pause("target")).unwrap_err() {
+            ContractError::AlreadyVoted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match run(deps.as_mut(), 102, "dave", vote_pause("target")).unwrap_err() {
+            ContractError::NoVotingPower {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(
+            pause_votes(deps.as_ref(), 102, "target").pause,
+            CampaignResponse {
+                weight: Uint128::from(50u128),
+                snapshot_height: Some(99),
+                expires_at_height: Some(110),
+            }
+        );
+
+        let res = run(deps.as_mut(), 102, "alice", vote_pause("target")).unwrap();
+        assert_eq!(res.messages, vec![target_call(TargetExecuteMsg::Pause {})]);
+        assert_eq!(res.attributes[4], attr("total_weight", "110"));
+        assert_eq!(res.attributes[5], attr("triggered", "true"));
+        assert!(pause_votes(deps.as_ref(), 102, "target")
+            .pause
+            .weight
+            .is_zero());
+
+        // unpausing is a separate campaign
+        let msg = ExecuteMsg::VoteUnpause {
+            target_contract: "target".to_string(),
+        };
+        let res = run(deps.as_mut(), 103, "alice", msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![target_call(TargetExecuteMsg::Unpause {})]
+        );
+        assert_eq!(res.attributes[0], attr("action", "vote_unpause"));
+
+        // a triggered campaign starts over, so everyone can vote again
+        let res = run(deps.as_mut(), 104, "bob", vote_pause("target")).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn pause_votes_expire() {
+        let mut deps = setup();
+        run(deps.as_mut(), 100, "bob", vote_pause("target")).unwrap();
+        run(deps.as_mut(), 100, "bob", vote_pause("other")).unwrap();
+        assert!(pause_votes(deps.as_ref(), 110, "target")
+            .pause
+            .expires_at_height
+            .is_none());
+
+        // the window ended, so carol starts a new round and bob may vote again
+        run(deps.as_mut(), 110, "carol", vote_pause("target")).unwrap();
+        assert_eq!(
+            pause_votes(deps.as_ref(), 110, "target").pause,
+            CampaignResponse {
+                weight: Uint128::from(20u128),
+                snapshot_height: Some(109),
+                expires_at_height: Some(120),
+            }
+        );
+        let res = run(deps.as_mut(), 111, "bob", vote_pause("target")).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(res.attributes[4], attr("total_weight", "50"));
+    }
+}
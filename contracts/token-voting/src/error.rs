@@ -0,0 +1,44 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Poll must end in the future (end_height {end_height})")]
+    InvalidEndHeight { end_height: u64 },
+
+    #[error("Poll {poll_id} not found")]
+    PollNotFound { poll_id: u64 },
+
+    #[error("Poll ended (end_height {end_height})")]
+    PollEnded { end_height: u64 },
+
+    #[error("Poll not ended yet (end_height {end_height})")]
+    PollNotEnded { end_height: u64 },
+
+    #[error("Poll already tallied")]
+    PollTallied {},
+
+    #[error("Already voted")]
+    AlreadyVoted {},
+
+    #[error("Token {token} does not answer BalanceAt, build it with the iterator feature")]
+    SnapshotsUnsupported { token: String },
+
+    #[error("No tokens to vote with")]
+    NoVotingPower {},
+
+    #[error("Pause threshold must be between 1 and 10000 basis points (got {bps})")]
+    InvalidPauseThreshold { bps: u64 },
+
+    #[error("Vote window must be at least one block")]
+    InvalidVoteWindow {},
+
+    #[error("Token has no supply")]
+    NoSupply {},
+}
@@ -0,0 +1,94 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Ballot, PollStatus};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token contract providing the voting power
+    pub token: String,
+    /// Share of the total supply, in basis points, needed to pause or unpause a contract
+    pub pause_threshold_bps: u64,
+    /// Number of blocks a pause or unpause campaign collects votes before it starts over
+    pub vote_window_blocks: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Opens a poll accepting votes until `end_height`. Anyone can do this.
+    CreatePoll { question: String, end_height: u64 },
+    /// Votes with the signer's token balance at the poll's snapshot height. Every address can
+    /// vote once per poll.
+    Vote { poll_id: u64, yes: bool },
+    /// Decides the poll once it ended. It passes with more yes than no votes.
+    /// Anyone can call this.
+    Tally { poll_id: u64 },
+    /// Votes to pause `target_contract` with the signer's token balance at the end of the block
+    /// before the running round started. Once the votes within the vote window reach the pause
+    /// threshold, the target's `Pause {}` is called and the campaign starts over.
+    VotePause { target_contract: String },
+    /// Like `VotePause`, but calls the target's `Unpause {}`
+    VoteUnpause { target_contract: String },
+}
+
+/// The messages a target contract must accept from this contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetExecuteMsg {
+    Pause {},
+    Unpause {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Poll {
+        poll_id: u64,
+    },
+    /// Returns the vote of `address` in the poll, if it voted
+    Ballot {
+        poll_id: u64,
+        address: String,
+    },
+    /// Returns the running pause and unpause campaigns for `target_contract`
+    PauseVotes {
+        target_contract: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollResponse {
+    pub creator: String,
+    pub question: String,
+    pub snapshot_height: u64,
+    pub end_height: u64,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub status: PollStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BallotResponse {
+    pub ballot: Option<Ballot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CampaignResponse {
+    /// Weight voted in the running round, zero if no round is running
+    pub weight: Uint128,
+    /// Votes are weighed with the balances at the end of this block.
+    /// `None` if no round is running.
+    pub snapshot_height: Option<u64>,
+    /// Votes are accepted up to, but not including, this block height.
+    /// `None` if no round is running.
+    pub expires_at_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseVotesResponse {
+    pub pause: CampaignResponse,
+    pub unpause: CampaignResponse,
+}
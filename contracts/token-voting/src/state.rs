@@ -0,0 +1,163 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+static POLL_COUNT_KEY: &[u8] = b"poll_count";
+static POLLS_KEY: &[u8] = b"polls";
+static BALLOTS_KEY: &[u8] = b"ballots";
+static CAMPAIGNS_KEY: &[u8] = b"campaigns";
+static PAUSE_VOTES_KEY: &[u8] = b"pause_votes";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The erc20 token whose balances weigh the votes
+    pub token: Addr,
+    /// Share of the total supply, in basis points, needed to pause or unpause a contract
+    pub pause_threshold_bps: u64,
+    /// Number of blocks a pause or unpause campaign collects votes before it starts over
+    pub vote_window_blocks: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollStatus {
+    Open,
+    Passed,
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Poll {
+    pub creator: Addr,
+    pub question: String,
+    /// Votes are weighed with the balances at the end of this block
+    pub snapshot_height: u64,
+    /// Votes are accepted up to, but not including, this block height
+    pub end_height: u64,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub status: PollStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ballot {
+    pub yes: bool,
+    /// Token balance of the voter at the poll's snapshot height
+    pub weight: Uint128,
+}
+
+/// What a campaign asks the target contract to do
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseAction {
+    Pause,
+    Unpause,
+}
+
+impl PauseAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PauseAction::Pause => "pause",
+            PauseAction::Unpause => "unpause",
+        }
+    }
+}
+
+/// Votes collected to pause or unpause one target contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Campaign {
+    /// Increased whenever the campaign starts over, which invalidates all earlier votes
+    pub round: u64,
+    /// Height of the first vote of this round
+    pub start_height: u64,
+    /// Votes are weighed with the balances at the end of this block, the one before
+    /// `start_height`
+    pub snapshot_height: u64,
+    pub weight: Uint128,
+}
+
+impl Campaign {
+    /// Votes are accepted up to, but not including, this block height
+    pub fn expires_at_height(&self, vote_window_blocks: u64) -> u64 {
+        self.start_height + vote_window_blocks
+    }
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+pub fn poll_count(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, POLL_COUNT_KEY)
+}
+
+pub fn poll_count_read(storage: &dyn Storage) -> ReadonlySingleton<'_, u64> {
+    singleton_read(storage, POLL_COUNT_KEY)
+}
+
+/// Polls by big endian poll ID
+pub fn polls(storage: &mut dyn Storage) -> Bucket<'_, Poll> {
+    bucket(storage, POLLS_KEY)
+}
+
+pub fn polls_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Poll> {
+    bucket_read(storage, POLLS_KEY)
+}
+
+/// Ballots of a poll by voter address
+pub fn ballots(storage: &mut dyn Storage, poll_id: u64) -> Bucket<'_, Ballot> {
+    Bucket::multilevel(storage, &[BALLOTS_KEY, &poll_id.to_be_bytes()])
+}
+
+pub fn ballots_read(storage: &dyn Storage, poll_id: u64) -> ReadonlyBucket<'_, Ballot> {
+    ReadonlyBucket::multilevel(storage, &[BALLOTS_KEY, &poll_id.to_be_bytes()])
+}
+
+/// Campaigns of an action by target contract address
+pub fn campaigns(storage: &mut dyn Storage, action: PauseAction) -> Bucket<'_, Campaign> {
+    Bucket::multilevel(storage, &[CAMPAIGNS_KEY, action.as_str().as_bytes()])
+}
+
+pub fn campaigns_read(storage: &dyn Storage, action: PauseAction) -> ReadonlyBucket<'_, Campaign> {
+    ReadonlyBucket::multilevel(storage, &[CAMPAIGNS_KEY, action.as_str().as_bytes()])
+}
+
+/// The campaign round each voter last voted in, by voter address
+pub fn pause_votes<'a>(
+    storage: &'a mut dyn Storage,
+    action: PauseAction,
+    target: &Addr,
+) -> Bucket<'a, u64> {
+    Bucket::multilevel(
+        storage,
+        &[
+            PAUSE_VOTES_KEY,
+            action.as_str().as_bytes(),
+            target.as_str().as_bytes(),
+        ],
+    )
+}
+
+pub fn pause_votes_read<'a>(
+    storage: &'a dyn Storage,
+    action: PauseAction,
+    target: &Addr,
+) -> ReadonlyBucket<'a, u64> {
+    ReadonlyBucket::multilevel(
+        storage,
+        &[
+            PAUSE_VOTES_KEY,
+            action.as_str().as_bytes(),
+            target.as_str().as_bytes(),
+        ],
+    )
+}
@@ -0,0 +1,217 @@
+//! Runs the voting contract against a real erc20 contract on a test chain, which answers the
+//! voting contract's balance queries from the erc20 contract. Pause votes are delivered to a
+//! small target contract.
+
+use cosmwasm_std::{
+    from_slice, to_binary, to_vec, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult, Uint128,
+};
+use cw_test_chain::{erc20_msg, Chain, Contract};
+use cw_token_voting::contract::{execute, instantiate, query};
+use cw_token_voting::msg::{
+    ExecuteMsg, InstantiateMsg, PauseVotesResponse, PollResponse, QueryMsg, TargetExecuteMsg,
+};
+use cw_token_voting::state::PollStatus;
+use cw_token_voting::ContractError;
+
+const TOKEN: &str = "token";
+const VOTING: &str = "voting";
+const TARGET: &str = "target";
+
+/// 110 tokens, minted at height 90
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(90);
+    let msg = erc20_msg("VOTE", &[("alice", 60), ("bob", 50)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    chain.set_height(100);
+    let voting = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        pause_threshold_bps: 5000,
+        vote_window_blocks: 10,
+    };
+    chain
+        .instantiate(voting, VOTING, "creator", &msg, &[])
+        .unwrap();
+
+    let target = chain.store_code(Contract::new(
+        target_instantiate,
+        target_execute,
+        target_query,
+    ));
+    chain
+        .instantiate(target, TARGET, "creator", &Empty {}, &[])
+        .unwrap();
+    chain
+}
+
+fn run(chain: &mut Chain, height: u64, sender: &str, msg: ExecuteMsg) -> Result<Response, String> {
+    chain.set_height(height);
+    chain.execute(VOTING, sender, &msg, &[])
+}
+
+fn vote(poll_id: u64, yes: bool) -> ExecuteMsg {
+    ExecuteMsg::Vote { poll_id, yes }
+}
+
+fn poll(chain: &Chain, poll_id: u64) -> PollResponse {
+    chain.query(VOTING, &QueryMsg::Poll { poll_id }).unwrap()
+}
+
+#[test]
+fn votes_with_token_balances() {
+    let mut chain = setup();
+    let msg = ExecuteMsg::CreatePoll {
+        question: "Upgrade?".to_string(),
+        end_height: 110,
+    };
+    run(&mut chain, 100, "alice", msg).unwrap();
+
+    run(&mut chain, 101, "alice", vote(0, false)).unwrap();
+    run(&mut chain, 101, "bob", vote(0, true)).unwrap();
+    let res = poll(&chain, 0);
+    assert_eq!(res.yes_votes.u128(), 50);
+    assert_eq!(res.no_votes.u128(), 60);
+
+    // holders without tokens cannot vote
+    let err = run(&mut chain, 102, "carol", vote(0, true)).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {}.to_string());
+
+    let res = run(&mut chain, 110, "anyone", ExecuteMsg::Tally { poll_id: 0 }).unwrap();
+    assert_eq!(res.attributes[2].value, "false");
+    assert_eq!(poll(&chain, 0).status, PollStatus::Rejected);
+}
+
+#[test]
+fn weighs_votes_at_snapshot() {
+    let mut chain = setup();
+    let msg = ExecuteMsg::CreatePoll {
+        question: "Upgrade?".to_string(),
+        end_height: 110,
+    };
+    run(&mut chain, 100, "alice", msg).unwrap();
+    assert_eq!(poll(&chain, 0).snapshot_height, 99);
+    run(&mut chain, 101, "bob", vote(0, true)).unwrap();
+
+    // alice moves part of her tokens to carol before either of them votes
+    chain.set_height(102);
+    let transfer = cw_erc20::ExecuteMsg::transfer("carol", 20u128);
+    chain.execute(TOKEN, "alice", &transfer, &[]).unwrap();
+    run(&mut chain, 102, "alice", vote(0, false)).unwrap();
+    let err = run(&mut chain, 102, "carol", vote(0, true)).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {}.to_string());
+
+    let res = poll(&chain, 0);
+    assert_eq!(res.yes_votes, Uint128::from(50u128));
+    assert_eq!(res.no_votes, Uint128::from(60u128));
+}
+
+#[test]
+fn moved_tokens_cannot_vote_twice() {
+    let mut chain = setup();
+    let msg = ExecuteMsg::CreatePoll {
+        question: "Upgrade?".to_string(),
+        end_height: 110,
+    };
+    run(&mut chain, 100, "alice", msg).unwrap();
+    run(&mut chain, 101, "bob", vote(0, true)).unwrap();
+
+    // bob moves their tokens to a fresh address and votes again from there
+    chain.set_height(102);
+    let transfer = cw_erc20::ExecuteMsg::transfer("bob2", 50u128);
+    chain.execute(TOKEN, "bob", &transfer, &[]).unwrap();
+    let err = run(&mut chain, 103, "bob2", vote(0, true)).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {}.to_string());
+    assert_eq!(poll(&chain, 0).yes_votes, Uint128::from(50u128));
+
+    run(&mut chain, 104, "alice", vote(0, false)).unwrap();
+    run(&mut chain, 110, "anyone", ExecuteMsg::Tally { poll_id: 0 }).unwrap();
+    assert_eq!(poll(&chain, 0).status, PollStatus::Rejected);
+}
+
+// A contract that can be paused by anyone, standing in for a real target
+
+const PAUSED_KEY: &[u8] = b"paused";
+
+fn target_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    deps.storage.set(PAUSED_KEY, &to_vec(&false)?);
+    Ok(Response::default())
+}
+
+fn target_execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: TargetExecuteMsg,
+) -> StdResult<Response> {
+    let paused = matches!(msg, TargetExecuteMsg::Pause {});
+    deps.storage.set(PAUSED_KEY, &to_vec(&paused)?);
+    Ok(Response::default())
+}
+
+fn target_query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    let paused: bool = from_slice(&deps.storage.get(PAUSED_KEY).unwrap_or_default())?;
+    to_binary(&paused)
+}
+
+fn paused(chain: &Chain) -> bool {
+    chain.query(TARGET, &Empty {}).unwrap()
+}
+
+#[test]
+fn pauses_target_at_threshold() {
+    let mut chain = setup();
+    let vote_pause = || ExecuteMsg::VotePause {
+        target_contract: TARGET.to_string(),
+    };
+    let vote_unpause = || ExecuteMsg::VoteUnpause {
+        target_contract: TARGET.to_string(),
+    };
+
+    // bob's 50 of the 110 tokens are below the 50% threshold
+    run(&mut chain, 100, "bob", vote_pause()).unwrap();
+    assert!(!paused(&chain));
+    run(&mut chain, 101, "alice", vote_pause()).unwrap();
+    assert!(paused(&chain));
+
+    run(&mut chain, 102, "bob", vote_unpause()).unwrap();
+    assert!(paused(&chain));
+    // the unpause votes expired before alice joined
+    run(&mut chain, 112, "alice", vote_unpause()).unwrap();
+    assert!(!paused(&chain));
+}
+
+#[test]
+fn moved_tokens_add_no_pause_weight() {
+    let mut chain = setup();
+    let vote_pause = |target_contract: &str| ExecuteMsg::VotePause {
+        target_contract: target_contract.to_string(),
+    };
+    run(&mut chain, 100, "bob", vote_pause(TARGET)).unwrap();
+
+    // bob's tokens would reach the threshold together with the 50 already voted
+    chain.set_height(101);
+    let transfer = cw_erc20::ExecuteMsg::transfer("bob2", 50u128);
+    chain.execute(TOKEN, "bob", &transfer, &[]).unwrap();
+    let err = run(&mut chain, 102, "bob2", vote_pause(TARGET)).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {}.to_string());
+    assert!(!paused(&chain));
+
+    let msg = QueryMsg::PauseVotes {
+        target_contract: TARGET.to_string(),
+    };
+    let res: PauseVotesResponse = chain.query(VOTING, &msg).unwrap();
+    assert_eq!(res.pause.weight, Uint128::from(50u128));
+    assert_eq!(res.pause.snapshot_height, Some(99));
+
+    // a new round uses the balances at its own start
+    run(&mut chain, 110, "bob2", vote_pause(TARGET)).unwrap();
+    assert!(!paused(&chain));
+}
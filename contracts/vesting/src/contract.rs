@@ -0,0 +1,498 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128,
+};
+use cw_erc20::ReceiveMsg;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, VestingResponse};
+use crate::state::{config, config_read, Config};
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.start_height > msg.cliff_height
+        || msg.cliff_height > msg.end_height
+        || msg.start_height >= msg.end_height
+    {
+        return Err(ContractError::InvalidSchedule {});
+    }
+    let state = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        grantor: info.sender,
+        beneficiary: deps.api.addr_validate(&msg.beneficiary)?,
+        start_height: msg.start_height,
+        cliff_height: msg.cliff_height,
+        end_height: msg.end_height,
+        revocable: msg.revocable,
+        funded: Uint128::zero(),
+        claimed: Uint128::zero(),
+        revoked: false,
+    };
+    config(deps.storage).save(&state)?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(receive) => try_receive(deps, info, receive),
+        ExecuteMsg::Claim {} => try_claim(deps, env, info),
+        ExecuteMsg::Revoke {} => try_revoke(deps, env, info),
+    }
+}
+
+/// Receive
+///
+/// Adds a tranche sent by the grantor with the erc20 `Send` message to the grant.
+///
+/// @param receive the `ReceiveMsg` forwarded by the token
+fn try_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    receive: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.token {
+        return Err(ContractError::WrongToken {
+            token: state.token.to_string(),
+        });
+    }
+    if receive.sender != state.grantor.as_str() {
+        return Err(ContractError::Unauthorized {});
+    }
+    if state.revoked {
+        return Err(ContractError::Revoked {});
+    }
+    if receive.amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    state.funded = state.funded.checked_add(receive.amount)?;
+    config(deps.storage).save(&state)?;
+
+    let res = Response {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr("action", "fund"),
+            attr("amount", receive.amount),
+            attr("funded", state.funded),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Claim
+///
+/// Sends the vested tokens not claimed yet to the beneficiary.
+fn try_claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.beneficiary {
+        return Err(ContractError::Unauthorized {});
+    }
+    let amount = state.claimable(env.block.height)?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    state.claimed = state.claimed.checked_add(amount)?;
+    config(deps.storage).save(&state)?;
+
+    let transfer = cw_erc20::ExecuteMsg::transfer(state.beneficiary.as_str(), amount);
+    let res = Response {
+        submessages: vec![],
+        messages: vec![transfer.into_cosmos_msg(&state.token)?],
+        attributes: vec![
+            attr("action", "claim"),
+            attr("beneficiary", state.beneficiary),
+            attr("amount", amount),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+/// Revoke
+///
+/// Returns the tokens not vested at the current height to the grantor. What vested so far
+/// stays claimable by the beneficiary, and nothing vests anymore.
+fn try_revoke(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = config_read(deps.storage).load()?;
+    if info.sender != state.grantor {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !state.revocable {
+        return Err(ContractError::NotRevocable {});
+    }
+    if state.revoked {
+        return Err(ContractError::Revoked {});
+    }
+    let vested = state.vested(env.block.height);
+    let unvested = state.funded.checked_sub(vested)?;
+    state.funded = vested;
+    state.revoked = true;
+    config(deps.storage).save(&state)?;
+
+    let mut messages = vec![];
+    if !unvested.is_zero() {
+        let transfer = cw_erc20::ExecuteMsg::transfer(state.grantor.as_str(), unvested);
+        messages.push(transfer.into_cosmos_msg(&state.token)?);
+    }
+    let res = Response {
+        submessages: vec![],
+        messages,
+        attributes: vec![
+            attr("action", "revoke"),
+            attr("grantor", state.grantor),
+            attr("returned", unvested),
+        ],
+        data: None,
+    };
+    Ok(res)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&config_read(deps.storage).load()?),
+        QueryMsg::Vesting {} => {
+            let state = config_read(deps.storage).load()?;
+            to_binary(&VestingResponse {
+                funded: state.funded,
+                vested: state.vested(env.block.height),
+                claimed: state.claimed,
+                claimable: state.claimable(env.block.height)?,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, Addr, OwnedDeps};
+
+    fn init_msg(revocable: bool) -> InstantiateMsg {
+        InstantiateMsg {
+            token: "token".to_string(),
+            beneficiary: "beneficiary".to_string(),
+            start_height: 100,
+            cliff_height: 150,
+            end_height: 200,
+            revocable,
+        }
+    }
+
+    fn setup(revocable: bool) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("grantor", &[]);
+        instantiate(deps.as_mut(), env_at(50), info, init_msg(revocable)).unwrap();
+        deps
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn fund(deps: DepsMut, height: u64, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "grantor".to_string(),
+            amount: Uint128::from(amount),
+            msg: None,
+        });
+        execute(deps, env_at(height), mock_info("token", &[]), msg)
+    }
+
+    /// Claims at `height` and returns the claimed amount, zero if there was nothing to claim
+    fn claim(deps: DepsMut, height: u64) -> u128 {
+        let info = mock_info("beneficiary", &[]);
+        match execute(deps, env_at(height), info, ExecuteMsg::Claim {}) {
+            Ok(res) => res.attributes[2].value.parse().unwrap(),
+            Err(ContractError::NothingToClaim {}) => 0,
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    fn vesting(deps: Deps, height: u64) -> VestingResponse {
+        from_binary(&query(deps, env_at(height), QueryMsg::Vesting {}).unwrap()).unwrap()
+    }
+
+    fn vested_at(start: u64, cliff: u64, end: u64, funded: u128, height: u64) -> u128 {
+        let state = Config {
+            token: Addr::unchecked("token"),
+            grantor: Addr::unchecked("grantor"),
+            beneficiary: Addr::unchecked("beneficiary"),
+            start_height: start,
+            cliff_height: cliff,
+            end_height: end,
+            revocable: false,
+            funded: Uint128::from(funded),
+            claimed: Uint128::zero(),
+            revoked: false,
+        };
+        state.vested(height).u128()
+    }
+
+    #[test]
+    fn validates_schedule() {
+        let mut deps = mock_dependencies(&[]);
+        for &(start, cliff, end) in &[
+            (100, 99, 200),
+            (100, 201, 200),
+            (100, 100, 100),
+            (200, 150, 100),
+        ] {
+            let msg = InstantiateMsg {
+                start_height: start,
+                cliff_height: cliff,
+                end_height: end,
+                ..init_msg(false)
+            };
+            match instantiate(deps.as_mut(), env_at(50), mock_info("grantor", &[]), msg)
+                .unwrap_err()
+            {
+                ContractError::InvalidSchedule {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        // the cliff may be at either end
+        for &cliff in &[100, 200] {
+            let msg = InstantiateMsg {
+                cliff_height: cliff,
+                ..init_msg(false)
+            };
+            instantiate(deps.as_mut(), env_at(50), mock_info("grantor", &[]), msg).unwrap();
+        }
+        let state: Config =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(state.grantor, "grantor");
+    }
+
+    #[test]
+    fn vests_linearly_after_cliff() {
+        let expected = [
+            (0, 0),
+            (99, 0),
+            (100, 0),
+            (149, 0),
+            // the accrued half is released at the cliff
+            (150, 500),
+            (151, 510),
+            (175, 750),
+            (199, 990),
+            (200, 1000),
+            (201, 1000),
+            (u64::MAX, 1000),
+        ];
+        for &(height, vested) in &expected {
+            assert_eq!(
+                vested_at(100, 150, 200, 1000, height),
+                vested,
+                "height {}",
+                height
+            );
+        }
+        // without a cliff vesting starts right away
+        assert_eq!(vested_at(100, 100, 200, 1000, 100), 0);
+        assert_eq!(vested_at(100, 100, 200, 1000, 101), 10);
+        // with the cliff at the end it is all or nothing
+        assert_eq!(vested_at(100, 200, 200, 1000, 199), 0);
+        assert_eq!(vested_at(100, 200, 200, 1000, 200), 1000);
+    }
+
+    #[test]
+    fn rounds_vesting_down() {
+        // 7 tokens over 100 blocks
+        assert_eq!(vested_at(100, 100, 200, 7, 114), 0);
+        assert_eq!(vested_at(100, 100, 200, 7, 115), 1);
+        assert_eq!(vested_at(100, 100, 200, 7, 199), 6);
+        assert_eq!(vested_at(100, 100, 200, 7, 200), 7);
+        // large grants do not overflow
+        let max = u128::MAX;
+        assert_eq!(vested_at(0, 0, 2, max, 1), max / 2);
+        assert_eq!(vested_at(0, 0, 2, max, 2), max);
+    }
+
+    #[test]
+    fn claims_never_exceed_vested() {
+        for &funded in &[1u128, 7, 99, 1000, 123_456_789] {
+            let mut deps = setup(false);
+            fund(deps.as_mut(), 90, funded).unwrap();
+            let mut total = 0;
+            for height in 90..=210 {
+                total += claim(deps.as_mut(), height);
+                let res = vesting(deps.as_ref(), height);
+                assert_eq!(res.claimed.u128(), total);
+                assert!(res.claimed <= res.vested);
+                assert!(res.claimable.is_zero());
+            }
+            // everything is paid out exactly once
+            assert_eq!(total, funded);
+        }
+    }
+
+    #[test]
+    fn claims_incrementally() {
+        let mut deps = setup(false);
+        fund(deps.as_mut(), 90, 1000).unwrap();
+        assert_eq!(claim(deps.as_mut(), 149), 0);
+
+        let res = execute(
+            deps.as_mut(),
+            env_at(150),
+            mock_info("beneficiary", &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("beneficiary", 500u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("token").unwrap()]
+        );
+        // claiming twice in a block pays nothing more
+        assert_eq!(claim(deps.as_mut(), 150), 0);
+        assert_eq!(claim(deps.as_mut(), 175), 250);
+        assert_eq!(
+            vesting(deps.as_ref(), 180),
+            VestingResponse {
+                funded: Uint128::from(1000u128),
+                vested: Uint128::from(800u128),
+                claimed: Uint128::from(750u128),
+                claimable: Uint128::from(50u128),
+            }
+        );
+        assert_eq!(claim(deps.as_mut(), 300), 250);
+        assert_eq!(claim(deps.as_mut(), 400), 0);
+
+        let info = mock_info("grantor", &[]);
+        match execute(deps.as_mut(), env_at(400), info, ExecuteMsg::Claim {}).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn funds_in_tranches() {
+        let mut deps = setup(false);
+        fund(deps.as_mut(), 90, 600).unwrap();
+        assert_eq!(claim(deps.as_mut(), 160), 360);
+
+        // a later tranche vests on the same schedule, so 70% of it is vested right away
+        let res = fund(deps.as_mut(), 170, 400).unwrap();
+        assert_eq!(res.attributes[2], attr("funded", "1000"));
+        assert_eq!(vesting(deps.as_ref(), 170).claimable.u128(), 340);
+        assert_eq!(claim(deps.as_mut(), 170), 340);
+
+        // a tranche after the end is vested completely
+        fund(deps.as_mut(), 250, 50).unwrap();
+        assert_eq!(claim(deps.as_mut(), 250), 350);
+        assert_eq!(vesting(deps.as_ref(), 250).claimed.u128(), 1050);
+    }
+
+    #[test]
+    fn only_grantor_funds_with_token() {
+        let mut deps = setup(false);
+        let receive = |sender: &str, amount: u128| {
+            ExecuteMsg::Receive(ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::from(amount),
+                msg: None,
+            })
+        };
+        let info = mock_info("other_token", &[]);
+        match execute(deps.as_mut(), env_at(90), info, receive("grantor", 10)).unwrap_err() {
+            ContractError::WrongToken { token } => assert_eq!(token, "token"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let info = mock_info("token", &[]);
+        match execute(deps.as_mut(), env_at(90), info, receive("stranger", 10)).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match fund(deps.as_mut(), 90, 0).unwrap_err() {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert!(vesting(deps.as_ref(), 300).funded.is_zero());
+    }
+
+    #[test]
+    fn revokes_unvested_remainder() {
+        let mut deps = setup(true);
+        fund(deps.as_mut(), 90, 1000).unwrap();
+        assert_eq!(claim(deps.as_mut(), 160), 600);
+
+        let revoke = |deps: DepsMut, sender: &str| {
+            execute(
+                deps,
+                env_at(175),
+                mock_info(sender, &[]),
+                ExecuteMsg::Revoke {},
+            )
+        };
+        match revoke(deps.as_mut(), "beneficiary").unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = revoke(deps.as_mut(), "grantor").unwrap();
+        let transfer = cw_erc20::ExecuteMsg::transfer("grantor", 250u128);
+        assert_eq!(
+            res.messages,
+            vec![transfer.into_cosmos_msg("token").unwrap()]
+        );
+
+        // what vested before stays claimable, nothing vests after
+        assert_eq!(
+            vesting(deps.as_ref(), 300),
+            VestingResponse {
+                funded: Uint128::from(750u128),
+                vested: Uint128::from(750u128),
+                claimed: Uint128::from(600u128),
+                claimable: Uint128::from(150u128),
+            }
+        );
+        assert_eq!(claim(deps.as_mut(), 176), 150);
+        assert_eq!(claim(deps.as_mut(), 300), 0);
+
+        match revoke(deps.as_mut(), "grantor").unwrap_err() {
+            ContractError::Revoked {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match fund(deps.as_mut(), 300, 10).unwrap_err() {
+            ContractError::Revoked {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn revokes_everything_before_cliff() {
+        let mut deps = setup(true);
+        fund(deps.as_mut(), 90, 1000).unwrap();
+        let info = mock_info("grantor", &[]);
+        let res = execute(deps.as_mut(), env_at(149), info, ExecuteMsg::Revoke {}).unwrap();
+        assert_eq!(res.attributes[2], attr("returned", "1000"));
+        assert_eq!(claim(deps.as_mut(), 300), 0);
+
+        let mut deps = setup(false);
+        fund(deps.as_mut(), 90, 1000).unwrap();
+        let info = mock_info("grantor", &[]);
+        match execute(deps.as_mut(), env_at(149), info, ExecuteMsg::Revoke {}).unwrap_err() {
+            ContractError::NotRevocable {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Schedule must satisfy start <= cliff <= end and start < end")]
+    InvalidSchedule {},
+
+    #[error("Only {token} tokens are accepted")]
+    WrongToken { token: String },
+
+    #[error("Amount must not be zero")]
+    ZeroAmount {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Grant is not revocable")]
+    NotRevocable {},
+
+    #[error("Grant was revoked")]
+    Revoked {},
+}
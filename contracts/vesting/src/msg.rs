@@ -0,0 +1,50 @@
+use cosmwasm_std::Uint128;
+use cw_erc20::ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The erc20 token being vested
+    pub token: String,
+    /// Can claim the vested tokens. The instantiating address becomes the grantor.
+    pub beneficiary: String,
+    /// Vesting starts accruing at this height
+    pub start_height: u64,
+    /// Nothing can be claimed before this height. Tokens accrued until then are released at
+    /// once.
+    pub cliff_height: u64,
+    /// Everything is vested from this height on
+    pub end_height: u64,
+    /// Whether the grantor can take back the unvested tokens
+    pub revocable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Adds the tokens the grantor sent with the erc20 `Send` message to the grant
+    Receive(ReceiveMsg),
+    /// Sends the vested tokens not claimed yet to the beneficiary.
+    /// Only the beneficiary can do this.
+    Claim {},
+    /// Returns the unvested tokens to the grantor and ends vesting, so the beneficiary can
+    /// still claim what vested so far. Only the grantor can do this, if the grant is revocable.
+    Revoke {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Returns the funded, vested, claimed and claimable amounts at the current height
+    Vesting {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingResponse {
+    pub funded: Uint128,
+    pub vested: Uint128,
+    pub claimed: Uint128,
+    pub claimable: Uint128,
+}
@@ -0,0 +1,58 @@
+use cosmwasm_std::{Addr, OverflowError, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static CONFIG_KEY: &[u8] = b"config";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The erc20 token being vested
+    pub token: Addr,
+    /// Funds the grant and can revoke it
+    pub grantor: Addr,
+    /// Can claim the vested tokens
+    pub beneficiary: Addr,
+    /// Vesting starts accruing at this height
+    pub start_height: u64,
+    /// Nothing can be claimed before this height
+    pub cliff_height: u64,
+    /// Everything is vested from this height on
+    pub end_height: u64,
+    pub revocable: bool,
+    /// Tokens received from the grantor, minus what was returned on revocation
+    pub funded: Uint128,
+    /// Tokens paid out to the beneficiary
+    pub claimed: Uint128,
+    /// Set once revoked. All remaining tokens are vested from then on.
+    pub revoked: bool,
+}
+
+impl Config {
+    /// Amount of the funded tokens vested at `height`. Every tranche vests on the same
+    /// schedule, so tokens funded after the start are partly vested right away.
+    pub fn vested(&self, height: u64) -> Uint128 {
+        if self.revoked || height >= self.end_height {
+            self.funded
+        } else if height < self.cliff_height {
+            Uint128::zero()
+        } else {
+            let elapsed = height - self.start_height;
+            self.funded
+                .multiply_ratio(elapsed, self.end_height - self.start_height)
+        }
+    }
+
+    /// Vested tokens not claimed yet
+    pub fn claimable(&self, height: u64) -> Result<Uint128, OverflowError> {
+        self.vested(height).checked_sub(self.claimed)
+    }
+}
+
+pub fn config(storage: &mut dyn Storage) -> Singleton<'_, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
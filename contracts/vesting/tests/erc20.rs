@@ -0,0 +1,85 @@
+//! Funds a grant in tranches and vests it with a real erc20 contract on a test chain, which
+//! reverts failing messages like a real chain.
+
+use cw_erc20::BalanceResponse;
+use cw_test_chain::{erc20_msg, Chain, Contract};
+use cw_vesting::contract::{execute, instantiate, query};
+use cw_vesting::msg::{ExecuteMsg, InstantiateMsg};
+
+const TOKEN: &str = "token";
+const VESTING: &str = "vesting";
+
+/// Vests from height 100 to 200 with a cliff at 125, set up at height 50
+fn setup(revocable: bool) -> Chain {
+    let mut chain = Chain::new();
+    chain.set_height(50);
+    let msg = erc20_msg("GRANT", &[("grantor", 1000)]);
+    chain.instantiate_erc20(TOKEN, &msg).unwrap();
+
+    let vesting = chain.store_code(Contract::new(instantiate, execute, query));
+    let msg = InstantiateMsg {
+        token: TOKEN.to_string(),
+        beneficiary: "beneficiary".to_string(),
+        start_height: 100,
+        cliff_height: 125,
+        end_height: 200,
+        revocable,
+    };
+    chain
+        .instantiate(vesting, VESTING, "grantor", &msg, &[])
+        .unwrap();
+    chain
+}
+
+fn fund(chain: &mut Chain, amount: u128) {
+    let msg = cw_erc20::ExecuteMsg::send(VESTING, amount, None);
+    chain.execute(TOKEN, "grantor", &msg, &[]).unwrap();
+}
+
+fn claim_at(chain: &mut Chain, height: u64) -> Result<(), String> {
+    chain.set_height(height);
+    chain
+        .execute(VESTING, "beneficiary", &ExecuteMsg::Claim {}, &[])
+        .map(|_| ())
+}
+
+fn balance(chain: &Chain, address: &str) -> u128 {
+    let msg = cw_erc20::QueryMsg::balance(address);
+    let res: BalanceResponse = chain.query(TOKEN, &msg).unwrap();
+    res.balance.u128()
+}
+
+#[test]
+fn vests_tranches_to_beneficiary() {
+    let mut chain = setup(false);
+    fund(&mut chain, 400);
+    claim_at(&mut chain, 150).unwrap();
+    assert_eq!(balance(&chain, "beneficiary"), 200);
+
+    fund(&mut chain, 400);
+    claim_at(&mut chain, 175).unwrap();
+    assert_eq!(balance(&chain, "beneficiary"), 600);
+
+    claim_at(&mut chain, 200).unwrap();
+    assert_eq!(balance(&chain, "beneficiary"), 800);
+    assert_eq!(balance(&chain, VESTING), 0);
+    assert_eq!(balance(&chain, "grantor"), 200);
+}
+
+#[test]
+fn revokes_unvested_tokens() {
+    let mut chain = setup(true);
+    fund(&mut chain, 1000);
+    claim_at(&mut chain, 130).unwrap();
+    assert_eq!(balance(&chain, "beneficiary"), 300);
+
+    chain.set_height(160);
+    chain
+        .execute(VESTING, "grantor", &ExecuteMsg::Revoke {}, &[])
+        .unwrap();
+    assert_eq!(balance(&chain, "grantor"), 400);
+
+    claim_at(&mut chain, 300).unwrap();
+    assert_eq!(balance(&chain, "beneficiary"), 600);
+    assert_eq!(balance(&chain, VESTING), 0);
+}
@@ -0,0 +1,612 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use cosmwasm_std::testing::{mock_env, MockApi};
+use cosmwasm_std::{
+    attr, from_binary, from_slice, to_binary, to_vec, Addr, AllBalanceResponse, BalanceResponse,
+    BankMsg, BankQuery, Binary, BlockInfo, Coin, ContractInfo, ContractResult, CosmosMsg, Deps,
+    DepsMut, Empty, Env, Event, MessageInfo, Querier, QuerierResult, QuerierWrapper, QueryRequest,
+    Reply, ReplyOn, Response, Storage, SubcallResponse, SystemError, SystemResult, WasmMsg,
+    WasmQuery,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::contract::Contract;
+use crate::store::Store;
+
+#[derive(Clone)]
+struct Instance {
+    code_id: u64,
+    storage: RefCell<Store>,
+}
+
+/// Everything a failing message reverts
+#[derive(Clone, Default)]
+struct State {
+    contracts: BTreeMap<String, Instance>,
+    /// Native coins by address and denom
+    bank: BTreeMap<String, BTreeMap<String, u128>>,
+    /// Contracts instantiated by messages so far, which are named after their position
+    instantiated: u64,
+}
+
+/// Runs contracts natively the way a chain would. The submessages and messages they return
+/// are executed in order, submessage results are replied to the sender and contracts can
+/// query each other and the bank. A message that fails reverts everything it changed,
+/// including the changes of the messages it triggered, so tests can check the state after an
+/// error.
+pub struct Chain {
+    block: BlockInfo,
+    api: MockApi,
+    codes: Vec<Contract>,
+    /// Code id of the erc20 contract, once stored
+    pub(crate) erc20_code: Option<u64>,
+    state: State,
+}
+
+impl Default for Chain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chain {
+    /// An empty chain at the block of `mock_env`
+    pub fn new() -> Self {
+        Chain {
+            block: mock_env().block,
+            api: MockApi::default(),
+            codes: vec![],
+            erc20_code: None,
+            state: State::default(),
+        }
+    }
+
+    pub fn block(&self) -> &BlockInfo {
+        &self.block
+    }
+
+    pub fn set_height(&mut self, height: u64) {
+        self.block.height = height;
+    }
+
+    /// Stores a contract's code and returns the code id to instantiate it with
+    pub fn store_code(&mut self, contract: Contract) -> u64 {
+        self.codes.push(contract);
+        self.codes.len() as u64
+    }
+
+    /// Instantiates `code_id` at `address`, with `funds` sent by `sender`. Contracts
+    /// instantiated by messages instead get the addresses "contract1", "contract2" and so on.
+    pub fn instantiate<T: Serialize>(
+        &mut self,
+        code_id: u64,
+        address: &str,
+        sender: &str,
+        msg: &T,
+        funds: &[Coin],
+    ) -> Result<Response, String> {
+        let msg = to_vec(msg).map_err(|err| err.to_string())?;
+        self.transaction(|chain| chain.run_instantiate(code_id, address, sender, &msg, funds))
+    }
+
+    /// Executes `msg` on `contract`, with `funds` sent by `sender`
+    pub fn execute<T: Serialize>(
+        &mut self,
+        contract: &str,
+        sender: &str,
+        msg: &T,
+        funds: &[Coin],
+    ) -> Result<Response, String> {
+        let msg = to_vec(msg).map_err(|err| err.to_string())?;
+        self.transaction(|chain| chain.run_execute(contract, sender, &msg, funds))
+    }
+
+    pub fn query<T: DeserializeOwned, M: Serialize>(
+        &self,
+        contract: &str,
+        msg: &M,
+    ) -> Result<T, String> {
+        let msg = to_vec(msg).map_err(|err| err.to_string())?;
+        let res = self.run_query(contract, &msg)?;
+        from_binary(&res).map_err(|err| err.to_string())
+    }
+
+    /// Replaces the native coins held by `address`
+    pub fn set_balance(&mut self, address: &str, amount: &[Coin]) {
+        let coins = amount
+            .iter()
+            .map(|coin| (coin.denom.clone(), coin.amount.u128()))
+            .collect();
+        self.state.bank.insert(address.to_string(), coins);
+    }
+
+    /// The amount of `denom` held by `address`
+    pub fn balance(&self, address: &str, denom: &str) -> u128 {
+        self.state
+            .bank
+            .get(address)
+            .and_then(|coins| coins.get(denom))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Runs `action` and restores the state from before it when it fails
+    fn transaction<T>(
+        &mut self,
+        action: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let snapshot = self.state.clone();
+        let res = action(self);
+        if res.is_err() {
+            self.state = snapshot;
+        }
+        res
+    }
+
+    fn env(&self, contract: &str) -> Env {
+        Env {
+            block: self.block.clone(),
+            contract: ContractInfo {
+                address: Addr::unchecked(contract),
+            },
+        }
+    }
+
+    fn instance(&self, contract: &str) -> Result<(&Contract, &RefCell<Store>), String> {
+        let instance = self
+            .state
+            .contracts
+            .get(contract)
+            .ok_or_else(|| format!("No contract at {}", contract))?;
+        Ok((
+            &self.codes[instance.code_id as usize - 1],
+            &instance.storage,
+        ))
+    }
+
+    /// Calls an entry point of `contract` with mutable access to its storage
+    fn call(
+        &self,
+        contract: &str,
+        entry_point: impl FnOnce(&Contract, DepsMut, Env) -> Result<Response, String>,
+    ) -> Result<Response, String> {
+        let (code, storage) = self.instance(contract)?;
+        let mut storage = storage.borrow_mut();
+        let querier = ChainQuerier { chain: self };
+        let deps = DepsMut {
+            storage: &mut *storage,
+            api: &self.api,
+            querier: QuerierWrapper::new(&querier),
+        };
+        entry_point(code, deps, self.env(contract))
+    }
+
+    fn run_instantiate(
+        &mut self,
+        code_id: u64,
+        address: &str,
+        sender: &str,
+        msg: &[u8],
+        funds: &[Coin],
+    ) -> Result<Response, String> {
+        if code_id == 0 || code_id > self.codes.len() as u64 {
+            return Err(format!("No code with id {}", code_id));
+        }
+        if self.state.contracts.contains_key(address) {
+            return Err(format!("Contract {} already exists", address));
+        }
+        let instance = Instance {
+            code_id,
+            storage: RefCell::default(),
+        };
+        self.state.contracts.insert(address.to_string(), instance);
+        self.send(sender, address, funds)?;
+        let info = message_info(sender, funds);
+        let res = self.call(address, |code, deps, env| {
+            code.instantiate(deps, env, info, msg)
+        })?;
+        self.dispatch(address, &res)?;
+        Ok(res)
+    }
+
+    fn run_execute(
+        &mut self,
+        contract: &str,
+        sender: &str,
+        msg: &[u8],
+        funds: &[Coin],
+    ) -> Result<Response, String> {
+        self.instance(contract)?;
+        self.send(sender, contract, funds)?;
+        let info = message_info(sender, funds);
+        let res = self.call(contract, |code, deps, env| {
+            code.execute(deps, env, info, msg)
+        })?;
+        self.dispatch(contract, &res)?;
+        Ok(res)
+    }
+
+    fn run_reply(&mut self, contract: &str, reply: Reply) -> Result<Response, String> {
+        let res = self.call(contract, |code, deps, env| code.reply(deps, env, reply))?;
+        self.dispatch(contract, &res)?;
+        Ok(res)
+    }
+
+    fn run_query(&self, contract: &str, msg: &[u8]) -> Result<Binary, String> {
+        let (code, storage) = self.instance(contract)?;
+        let storage = storage
+            .try_borrow()
+            .map_err(|_| format!("{} cannot be queried while it executes", contract))?;
+        let querier = ChainQuerier { chain: self };
+        let deps = Deps {
+            storage: &*storage,
+            api: &self.api,
+            querier: QuerierWrapper::new(&querier),
+        };
+        code.query(deps, self.env(contract), msg)
+    }
+
+    /// Executes the submessages and then the messages returned by `contract`
+    fn dispatch(&mut self, contract: &str, res: &Response) -> Result<(), String> {
+        for submsg in &res.submessages {
+            let result = self.transaction(|chain| chain.run_msg(contract, &submsg.msg));
+            let result = match result {
+                Ok(res) if matches!(submsg.reply_on, ReplyOn::Always | ReplyOn::Success) => {
+                    ContractResult::Ok(res)
+                }
+                Err(err) if matches!(submsg.reply_on, ReplyOn::Always | ReplyOn::Error) => {
+                    ContractResult::Err(err)
+                }
+                Ok(_) => continue,
+                Err(err) => return Err(err),
+            };
+            let reply = Reply {
+                id: submsg.id,
+                result,
+            };
+            self.run_reply(contract, reply)?;
+        }
+        for msg in &res.messages {
+            self.run_msg(contract, msg)?;
+        }
+        Ok(())
+    }
+
+    /// Executes `msg` sent by `sender` and returns what a reply to it receives
+    fn run_msg(&mut self, sender: &str, msg: &CosmosMsg) -> Result<SubcallResponse, String> {
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                send,
+            }) => {
+                let res = self.run_execute(contract_addr, sender, msg, send)?;
+                let events = vec![wasm_event(contract_addr, &res)];
+                Ok(SubcallResponse {
+                    events,
+                    data: res.data,
+                })
+            }
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                code_id, msg, send, ..
+            }) => {
+                self.state.instantiated += 1;
+                let address = format!("contract{}", self.state.instantiated);
+                let res = self.run_instantiate(*code_id, &address, sender, msg, send)?;
+                let instantiated = Event::new(
+                    "instantiate",
+                    vec![
+                        attr("_contract_address", &address),
+                        attr("code_id", code_id),
+                    ],
+                );
+                let events = vec![instantiated, wasm_event(&address, &res)];
+                Ok(SubcallResponse {
+                    events,
+                    data: res.data,
+                })
+            }
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                self.send(sender, to_address, amount)?;
+                Ok(SubcallResponse {
+                    events: vec![],
+                    data: None,
+                })
+            }
+            CosmosMsg::Bank(BankMsg::Burn { amount }) => {
+                self.burn(sender, amount)?;
+                Ok(SubcallResponse {
+                    events: vec![],
+                    data: None,
+                })
+            }
+            msg => Err(format!("Unsupported message: {:?}", msg)),
+        }
+    }
+
+    fn send(&mut self, from: &str, to: &str, amount: &[Coin]) -> Result<(), String> {
+        self.burn(from, amount)?;
+        let coins = self.state.bank.entry(to.to_string()).or_default();
+        for coin in amount {
+            *coins.entry(coin.denom.clone()).or_default() += coin.amount.u128();
+        }
+        Ok(())
+    }
+
+    fn burn(&mut self, address: &str, amount: &[Coin]) -> Result<(), String> {
+        let coins = self.state.bank.entry(address.to_string()).or_default();
+        for coin in amount {
+            let balance = coins.entry(coin.denom.clone()).or_default();
+            *balance = balance
+                .checked_sub(coin.amount.u128())
+                .ok_or_else(|| format!("{} holds less than {}", address, coin))?;
+        }
+        Ok(())
+    }
+}
+
+fn message_info(sender: &str, funds: &[Coin]) -> MessageInfo {
+    MessageInfo {
+        sender: Addr::unchecked(sender),
+        funds: funds.to_vec(),
+    }
+}
+
+fn wasm_event(contract: &str, res: &Response) -> Event {
+    let mut attributes = vec![attr("contract_address", contract)];
+    attributes.extend(res.attributes.iter().cloned());
+    Event::new("wasm", attributes)
+}
+
+/// Answers the queries of contracts from the chain's state
+struct ChainQuerier<'a> {
+    chain: &'a Chain,
+}
+
+impl Querier for ChainQuerier<'_> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(request) => request,
+            Err(err) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: err.to_string(),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        let res = match request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if !self.chain.state.contracts.contains_key(&contract_addr) {
+                    return SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr,
+                    });
+                }
+                self.chain.run_query(&contract_addr, &msg)
+            }
+            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
+                match self.chain.state.contracts.get(&contract_addr) {
+                    Some(instance) => match instance.storage.try_borrow() {
+                        Ok(storage) => Ok(Binary::from(storage.get(&key).unwrap_or_default())),
+                        Err(_) => Err(format!(
+                            "{} cannot be queried while it executes",
+                            contract_addr
+                        )),
+                    },
+                    None => {
+                        return SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr,
+                        })
+                    }
+                }
+            }
+            QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
+                let amount = Coin::new(self.chain.balance(&address, &denom), denom);
+                to_binary(&BalanceResponse { amount }).map_err(|err| err.to_string())
+            }
+            QueryRequest::Bank(BankQuery::AllBalances { address }) => {
+                let amount = match self.chain.state.bank.get(&address) {
+                    Some(coins) => coins
+                        .iter()
+                        .filter(|(_, amount)| **amount > 0)
+                        .map(|(denom, amount)| Coin::new(*amount, denom))
+                        .collect(),
+                    None => vec![],
+                };
+                to_binary(&AllBalanceResponse { amount }).map_err(|err| err.to_string())
+            }
+            request => {
+                return SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: format!("{:?}", request),
+                })
+            }
+        };
+        SystemResult::Ok(res.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coins, StdError, StdResult, SubMsg};
+    use serde::Deserialize;
+
+    const DENOM: &str = "ucosm";
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum CounterMsg {
+        Increment {},
+        /// Increments and sends `msg` to `contract`, as a submessage when `reply_on` is set
+        Forward {
+            contract: String,
+            msg: Binary,
+            reply_on: Option<ReplyOn>,
+        },
+        Fail {},
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum CounterQuery {
+        Count {},
+        LastReply {},
+    }
+
+    fn load_count(storage: &dyn Storage) -> u64 {
+        storage
+            .get(b"count")
+            .map(|count| from_slice(&count).unwrap())
+            .unwrap_or_default()
+    }
+
+    fn counter_instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        deps.storage.set(b"count", &to_vec(&0u64)?);
+        Ok(Response::default())
+    }
+
+    fn counter_execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: CounterMsg,
+    ) -> StdResult<Response> {
+        let count = load_count(deps.storage) + 1;
+        deps.storage.set(b"count", &to_vec(&count)?);
+        let mut res = Response::default();
+        match msg {
+            CounterMsg::Increment {} => {}
+            CounterMsg::Forward {
+                contract,
+                msg,
+                reply_on,
+            } => {
+                let msg: CosmosMsg = WasmMsg::Execute {
+                    contract_addr: contract,
+                    msg,
+                    send: info.funds,
+                }
+                .into();
+                match reply_on {
+                    Some(reply_on) => res.submessages.push(SubMsg {
+                        id: count,
+                        msg,
+                        gas_limit: None,
+                        reply_on,
+                    }),
+                    None => res.messages.push(msg),
+                }
+            }
+            CounterMsg::Fail {} => return Err(StdError::generic_err("failing on purpose")),
+        }
+        Ok(res)
+    }
+
+    fn counter_query(deps: Deps, _env: Env, msg: CounterQuery) -> StdResult<Binary> {
+        match msg {
+            CounterQuery::Count {} => to_binary(&load_count(deps.storage)),
+            CounterQuery::LastReply {} => Ok(deps.storage.get(b"reply").unwrap_or_default().into()),
+        }
+    }
+
+    fn counter_reply(deps: DepsMut, _env: Env, reply: Reply) -> StdResult<Response> {
+        let reply = match reply.result {
+            ContractResult::Ok(_) => format!("{}: ok", reply.id),
+            ContractResult::Err(err) => format!("{}: {}", reply.id, err),
+        };
+        deps.storage.set(b"reply", &to_vec(&reply)?);
+        Ok(Response::default())
+    }
+
+    /// Counters "a" and "b", with alice holding 100 coins
+    fn setup() -> Chain {
+        let mut chain = Chain::new();
+        let code_id = chain.store_code(
+            Contract::new(counter_instantiate, counter_execute, counter_query)
+                .with_reply(counter_reply),
+        );
+        for address in &["a", "b"] {
+            chain
+                .instantiate(code_id, address, "creator", &Empty {}, &[])
+                .unwrap();
+        }
+        chain.set_balance("alice", &coins(100, DENOM));
+        chain
+    }
+
+    fn count(chain: &Chain, contract: &str) -> u64 {
+        chain.query(contract, &CounterQuery::Count {}).unwrap()
+    }
+
+    fn forward(contract: &str, msg: &CounterMsg, reply_on: Option<ReplyOn>) -> CounterMsg {
+        CounterMsg::Forward {
+            contract: contract.to_string(),
+            msg: to_binary(msg).unwrap(),
+            reply_on,
+        }
+    }
+
+    #[test]
+    fn executes_returned_messages() {
+        let mut chain = setup();
+        let msg = forward("b", &CounterMsg::Increment {}, None);
+        chain
+            .execute("a", "alice", &msg, &coins(30, DENOM))
+            .unwrap();
+        assert_eq!(count(&chain, "a"), 1);
+        assert_eq!(count(&chain, "b"), 1);
+        assert_eq!(chain.balance("alice", DENOM), 70);
+        assert_eq!(chain.balance("b", DENOM), 30);
+    }
+
+    #[test]
+    fn failing_message_reverts_everything() {
+        let mut chain = setup();
+        let msg = forward("b", &forward("a", &CounterMsg::Fail {}, None), None);
+        let err = chain
+            .execute("a", "alice", &msg, &coins(30, DENOM))
+            .unwrap_err();
+        assert_eq!(err, "Generic error: failing on purpose");
+        assert_eq!(count(&chain, "a"), 0);
+        assert_eq!(count(&chain, "b"), 0);
+        assert_eq!(chain.balance("alice", DENOM), 100);
+        assert_eq!(chain.balance("a", DENOM), 0);
+
+        let err = chain
+            .execute("a", "alice", &CounterMsg::Increment {}, &coins(101, DENOM))
+            .unwrap_err();
+        assert_eq!(err, "alice holds less than 101ucosm");
+        assert_eq!(count(&chain, "a"), 0);
+    }
+
+    #[test]
+    fn failing_submessage_is_reverted_and_replied() {
+        let mut chain = setup();
+        let failing = forward("a", &CounterMsg::Fail {}, None);
+        let msg = forward("b", &failing, Some(ReplyOn::Error));
+        chain.execute("a", "alice", &msg, &[]).unwrap();
+        assert_eq!(count(&chain, "a"), 1);
+        assert_eq!(count(&chain, "b"), 0);
+        let reply: String = chain.query("a", &CounterQuery::LastReply {}).unwrap();
+        assert_eq!(reply, "1: Generic error: failing on purpose");
+
+        // not replied on success, which the reply leaves alone
+        let msg = forward("b", &CounterMsg::Increment {}, Some(ReplyOn::Error));
+        chain.execute("a", "alice", &msg, &[]).unwrap();
+        assert_eq!(count(&chain, "b"), 1);
+        let reply: String = chain.query("a", &CounterQuery::LastReply {}).unwrap();
+        assert_eq!(reply, "1: Generic error: failing on purpose");
+
+        // and the failure is not caught without a reply
+        let msg = forward("b", &failing, Some(ReplyOn::Success));
+        let err = chain.execute("a", "alice", &msg, &[]).unwrap_err();
+        assert_eq!(err, "Generic error: failing on purpose");
+        assert_eq!(count(&chain, "a"), 2);
+    }
+}
@@ -0,0 +1,90 @@
+use cosmwasm_std::{from_slice, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response};
+use serde::de::DeserializeOwned;
+
+type InstantiateFn = Box<dyn Fn(DepsMut, Env, MessageInfo, &[u8]) -> Result<Response, String>>;
+type QueryFn = Box<dyn Fn(Deps, Env, &[u8]) -> Result<Binary, String>>;
+type ReplyFn = Box<dyn Fn(DepsMut, Env, Reply) -> Result<Response, String>>;
+
+/// The entry points of a contract crate, taking JSON messages and reporting errors as
+/// strings so contracts with different message and error types can run side by side.
+pub struct Contract {
+    instantiate: InstantiateFn,
+    execute: InstantiateFn,
+    query: QueryFn,
+    reply: Option<ReplyFn>,
+}
+
+impl Contract {
+    pub fn new<I, X, Q, E1, E2, E3>(
+        instantiate: fn(DepsMut, Env, MessageInfo, I) -> Result<Response, E1>,
+        execute: fn(DepsMut, Env, MessageInfo, X) -> Result<Response, E2>,
+        query: fn(Deps, Env, Q) -> Result<Binary, E3>,
+    ) -> Self
+    where
+        I: DeserializeOwned + 'static,
+        X: DeserializeOwned + 'static,
+        Q: DeserializeOwned + 'static,
+        E1: ToString + 'static,
+        E2: ToString + 'static,
+        E3: ToString + 'static,
+    {
+        Contract {
+            instantiate: Box::new(move |deps, env, info, msg| {
+                instantiate(deps, env, info, parse(msg)?).map_err(|err| err.to_string())
+            }),
+            execute: Box::new(move |deps, env, info, msg| {
+                execute(deps, env, info, parse(msg)?).map_err(|err| err.to_string())
+            }),
+            query: Box::new(move |deps, env, msg| {
+                query(deps, env, parse(msg)?).map_err(|err| err.to_string())
+            }),
+            reply: None,
+        }
+    }
+
+    /// Adds the entry point receiving the results of submessages
+    pub fn with_reply<E: ToString + 'static>(
+        mut self,
+        reply: fn(DepsMut, Env, Reply) -> Result<Response, E>,
+    ) -> Self {
+        self.reply = Some(Box::new(move |deps, env, msg| {
+            reply(deps, env, msg).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub(crate) fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> Result<Response, String> {
+        (self.instantiate)(deps, env, info, msg)
+    }
+
+    pub(crate) fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> Result<Response, String> {
+        (self.execute)(deps, env, info, msg)
+    }
+
+    pub(crate) fn query(&self, deps: Deps, env: Env, msg: &[u8]) -> Result<Binary, String> {
+        (self.query)(deps, env, msg)
+    }
+
+    pub(crate) fn reply(&self, deps: DepsMut, env: Env, msg: Reply) -> Result<Response, String> {
+        match &self.reply {
+            Some(reply) => reply(deps, env, msg),
+            None => Err("Contract has no reply entry point".to_string()),
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(msg: &[u8]) -> Result<T, String> {
+    from_slice(msg).map_err(|err| err.to_string())
+}
@@ -0,0 +1,46 @@
+use cosmwasm_std::Response;
+use cw_erc20::{InitialBalance, InstantiateMsg};
+
+use crate::chain::Chain;
+use crate::contract::Contract;
+
+/// The message for a token named "{symbol} Token" with 6 decimals and the given balances.
+/// Other settings can be added with the struct update syntax.
+pub fn erc20_msg(symbol: &str, balances: &[(&str, u128)]) -> InstantiateMsg {
+    InstantiateMsg {
+        name: format!("{} Token", symbol),
+        symbol: symbol.to_string(),
+        decimals: 6,
+        initial_balances: balances
+            .iter()
+            .map(|(address, amount)| InitialBalance::new(*address, *amount))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+impl Chain {
+    /// Stores the erc20 contract on first use and returns its code id
+    pub fn store_erc20(&mut self) -> u64 {
+        if let Some(code_id) = self.erc20_code {
+            return code_id;
+        }
+        let code_id = self.store_code(Contract::new(
+            cw_erc20::contract::instantiate,
+            cw_erc20::contract::execute,
+            cw_erc20::contract::query,
+        ));
+        self.erc20_code = Some(code_id);
+        code_id
+    }
+
+    /// Instantiates an erc20 token at `address`, created by "creator"
+    pub fn instantiate_erc20(
+        &mut self,
+        address: &str,
+        msg: &InstantiateMsg,
+    ) -> Result<Response, String> {
+        let code_id = self.store_erc20();
+        self.instantiate(code_id, address, "creator", msg, &[])
+    }
+}
@@ -0,0 +1,12 @@
+//! A native chain for testing the example contracts together, e.g. a contract with the erc20
+//! tokens it moves. Only meant as a dev-dependency.
+
+mod chain;
+mod contract;
+mod erc20;
+mod store;
+
+pub use crate::chain::Chain;
+pub use crate::contract::Contract;
+pub use crate::erc20::erc20_msg;
+pub use crate::store::Store;
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "iterator")]
+use std::ops::Bound;
+
+use cosmwasm_std::Storage;
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, Pair};
+
+/// Storage of a single contract. Unlike `MockStorage` it can be cloned, which is how the chain
+/// takes the snapshots it restores when a message fails.
+#[derive(Clone, Debug, Default)]
+pub struct Store {
+    data: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Storage for Store {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.data.get(key).cloned()
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Pair> + 'a> {
+        if let (Some(start), Some(end)) = (start, end) {
+            if start >= end {
+                return Box::new(std::iter::empty());
+            }
+        }
+        let start = start.map_or(Bound::Unbounded, |key| Bound::Included(key.to_vec()));
+        let end = end.map_or(Bound::Unbounded, |key| Bound::Excluded(key.to_vec()));
+        let iter = self
+            .data
+            .range((start, end))
+            .map(|(key, value)| (key.clone(), value.clone()));
+        match order {
+            Order::Ascending => Box::new(iter),
+            Order::Descending => Box::new(iter.rev()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        // like MockStorage, so contracts behave the same in both
+        assert!(!value.is_empty(), "Value must not be empty in Storage::set");
+        self.data.insert(key.to_vec(), value.to_vec());
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.data.remove(key);
+    }
+}
+
+#[cfg(all(test, feature = "iterator"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_respects_bounds_and_order() {
+        let mut store = Store::default();
+        for key in &[b"a", b"b", b"c", b"d"] {
+            store.set(*key, b"1");
+        }
+        let keys = |iter: Box<dyn Iterator<Item = Pair>>| -> Vec<Vec<u8>> {
+            iter.map(|(key, _)| key).collect()
+        };
+        assert_eq!(
+            keys(store.range(Some(b"b"), Some(b"d"), Order::Ascending)),
+            vec![b"b".to_vec(), b"c".to_vec()]
+        );
+        assert_eq!(
+            keys(store.range(Some(b"b"), None, Order::Descending)),
+            vec![b"d".to_vec(), b"c".to_vec(), b"b".to_vec()]
+        );
+        assert!(keys(store.range(Some(b"c"), Some(b"b"), Order::Ascending)).is_empty());
+    }
+}